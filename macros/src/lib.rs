@@ -0,0 +1,82 @@
+// ============================================================================
+// scylladb_cdc_macros - Derive Macros of the scylladb_cdc Crate
+// ============================================================================
+//
+// `#[derive(DomainEvent)]` implements `scylladb_cdc::event_sourcing::DomainEvent`
+// for an event struct:
+//
+//   #[derive(Serialize, Deserialize, Clone, Debug, DomainEvent)]
+//   #[event(type = "OrderShipped", version = 2)]
+//   pub struct OrderShipped { ... }
+//
+// `type` defaults to the struct's name and `version` to 1. Both are also
+// emitted as the inherent consts `EVENT_TYPE` / `EVENT_VERSION`, which
+// `domain_event_enum!` reads for its variants - a renamed event is stored
+// under the same name whether written alone or through its aggregate's enum.
+//
+// Use the re-export `scylladb_cdc::event_sourcing::DomainEvent` (trait and
+// derive under one name, like serde's) rather than this crate directly.
+//
+// ============================================================================
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, LitInt, LitStr};
+
+#[proc_macro_derive(DomainEvent, attributes(event))]
+pub fn derive_domain_event(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    if !matches!(input.data, Data::Struct(_)) {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "DomainEvent is derived for event structs; declare event enums with domain_event_enum!",
+        ));
+    }
+
+    let name = &input.ident;
+    let mut event_type = name.to_string();
+    let mut event_version: i32 = 1;
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("event")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("type") {
+                event_type = meta.value()?.parse::<LitStr>()?.value();
+                if event_type.is_empty() {
+                    return Err(meta.error("event type must not be empty"));
+                }
+            } else if meta.path.is_ident("version") {
+                event_version = meta.value()?.parse::<LitInt>()?.base10_parse()?;
+                if event_version < 1 {
+                    return Err(meta.error("event version starts at 1"));
+                }
+            } else {
+                return Err(meta.error("expected `type = \"...\"` or `version = N`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        #[allow(dead_code)]
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Stored event type (event_store.event_type, outbox, topic)
+            pub const EVENT_TYPE: &'static str = #event_type;
+            /// Schema version of the payload
+            pub const EVENT_VERSION: i32 = #event_version;
+        }
+
+        impl #impl_generics ::scylladb_cdc::event_sourcing::DomainEvent for #name #ty_generics #where_clause {
+            fn event_type() -> &'static str {
+                Self::EVENT_TYPE
+            }
+
+            fn event_version() -> i32 {
+                Self::EVENT_VERSION
+            }
+        }
+    })
+}
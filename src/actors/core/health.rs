@@ -29,6 +29,31 @@ impl HealthStatus {
     pub fn is_unhealthy(&self) -> bool {
         matches!(self, HealthStatus::Unhealthy(_))
     }
+
+    /// "healthy", "degraded" or "unhealthy", without the message
+    pub fn label(&self) -> &'static str {
+        match self {
+            HealthStatus::Healthy => "healthy",
+            HealthStatus::Degraded(_) => "degraded",
+            HealthStatus::Unhealthy(_) => "unhealthy",
+        }
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        match self {
+            HealthStatus::Healthy => None,
+            HealthStatus::Degraded(msg) | HealthStatus::Unhealthy(msg) => Some(msg),
+        }
+    }
+
+    /// Inverse of `label` + `message`; unknown labels read as unhealthy
+    pub fn from_label(label: &str, message: Option<String>) -> Self {
+        match label {
+            "healthy" => HealthStatus::Healthy,
+            "degraded" => HealthStatus::Degraded(message.unwrap_or_default()),
+            _ => HealthStatus::Unhealthy(message.unwrap_or_else(|| format!("unknown status '{}'", label))),
+        }
+    }
 }
 
 /// Health information for a component
@@ -57,10 +82,18 @@ impl ComponentHealth {
 }
 
 /// Trait for actors that can report their health status
+///
+/// Register with a HealthRegistry to have the health monitor poll it.
 pub trait HealthCheckable {
     /// Get the current health status
     fn check_health(&self) -> ComponentHealth;
 
     /// Get the component name
     fn component_name(&self) -> &str;
+
+    /// Whether the service is only ready (GET /health/ready) while this
+    /// component is healthy; otherwise it only shows up in /health
+    fn gates_readiness(&self) -> bool {
+        true
+    }
 }
@@ -8,8 +8,10 @@
 // ============================================================================
 
 mod health;
+mod priority;
 mod supervised;
 
 // Re-export core types
 pub use health::*;
+pub use priority::*;
 pub use supervised::*;
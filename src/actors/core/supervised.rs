@@ -1,28 +1,122 @@
 // ============================================================================
-// Supervised Actor Metadata
+// Supervised Actors - Restart Policy
 // ============================================================================
 //
-// Metadata structures for supervised actors.
 // Kameo provides built-in supervision via Actor trait hooks:
 // - on_start, on_stop, on_panic, on_link_died
 //
+// A supervisor links its children and, when one dies abnormally, asks the
+// child's RestartTracker what to do under its RestartPolicy: restart it
+// after an exponential backoff, or give up after `max_restarts` in a row.
+// A child that stays up for `reset_after` earns a fresh set of restarts.
+//
 // ============================================================================
 
-/// Supervision strategy for an actor
+use std::time::{Duration, Instant};
+
+/// When and how often a failed child actor is restarted
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestartPolicy {
+    /// Restarts in a row before giving up; 0 never restarts
+    pub max_restarts: u32,
+    /// Wait before the first restart
+    pub initial_backoff: Duration,
+    /// Longest wait between restarts
+    pub max_backoff: Duration,
+    /// Backoff growth per restart in a row
+    pub multiplier: f64,
+    /// A child surviving this long after a restart starts over with the
+    /// initial backoff and a full set of restarts
+    pub reset_after: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            max_restarts: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            multiplier: 2.0,
+            reset_after: Duration::from_secs(300),
+        }
+    }
+}
+
+/// What to do about a child actor that died
 #[derive(Debug, Clone, Copy, PartialEq)]
-pub enum SupervisionStrategy {
-    /// Restart actor on failure
-    Restart,
-    /// Stop actor permanently on failure
-    Stop,
-    /// Escalate failure to parent
-    Escalate,
+pub enum RestartDecision {
+    /// Restart it after `delay`; `attempt` counts the restarts in a row
+    Restart { attempt: u32, delay: Duration },
+    /// It failed `restarts` times in a row already - leave it stopped
+    GiveUp { restarts: u32 },
+}
+
+/// Restarts of one supervised child
+#[derive(Debug, Clone, Default)]
+pub struct RestartTracker {
+    restarts: u32,
+    last_restart: Option<Instant>,
+}
+
+impl RestartTracker {
+    /// Decide about the child's latest failure
+    pub fn on_failure(&mut self, policy: &RestartPolicy) -> RestartDecision {
+        self.on_failure_at(policy, Instant::now())
+    }
+
+    fn on_failure_at(&mut self, policy: &RestartPolicy, now: Instant) -> RestartDecision {
+        if self.last_restart.is_some_and(|at| now.duration_since(at) >= policy.reset_after) {
+            self.restarts = 0;
+        }
+        if self.restarts >= policy.max_restarts {
+            return RestartDecision::GiveUp { restarts: self.restarts };
+        }
+
+        let backoff = policy.initial_backoff.as_secs_f64() * policy.multiplier.max(1.0).powi(self.restarts as i32);
+        let delay = Duration::from_secs_f64(backoff.min(policy.max_backoff.as_secs_f64()));
+        self.restarts += 1;
+        self.last_restart = Some(now);
+        RestartDecision::Restart { attempt: self.restarts, delay }
+    }
 }
 
-/// Metadata about a supervised actor
-#[derive(Debug, Clone)]
-pub struct ActorMetadata {
-    pub name: String,
-    pub description: String,
-    pub strategy: SupervisionStrategy,
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_backoff_gives_up_and_resets() {
+        let policy = RestartPolicy {
+            max_restarts: 3,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(3),
+            reset_after: Duration::from_secs(60),
+            ..Default::default()
+        };
+        let mut tracker = RestartTracker::default();
+        let start = Instant::now();
+
+        let delays: Vec<RestartDecision> = (0..4).map(|i| tracker.on_failure_at(&policy, start + Duration::from_secs(i))).collect();
+        assert_eq!(
+            delays,
+            vec![
+                RestartDecision::Restart { attempt: 1, delay: Duration::from_secs(1) },
+                RestartDecision::Restart { attempt: 2, delay: Duration::from_secs(2) },
+                // Capped at max_backoff
+                RestartDecision::Restart { attempt: 3, delay: Duration::from_secs(3) },
+                RestartDecision::GiveUp { restarts: 3 },
+            ]
+        );
+
+        // Stable for reset_after since the last restart: a fresh start
+        let later = start + Duration::from_secs(2 + 60);
+        assert_eq!(
+            tracker.on_failure_at(&policy, later),
+            RestartDecision::Restart { attempt: 1, delay: Duration::from_secs(1) }
+        );
+    }
 }
@@ -0,0 +1,349 @@
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use scylla::client::session::Session;
+use scylla::value::Row;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::Arc;
+use uuid::Uuid;
+
+// ============================================================================
+// Approval Gate - Manual Release of Sensitive Events
+// ============================================================================
+//
+// Some event types (e.g. RefundIssued) must not leave the system before a
+// human or policy approved them. The CDC relay asks the gate about every
+// event of a configured type (CDC_APPROVAL_REQUIRED):
+//
+//   unknown   -> parked in pending_publications (status "pending"), not published
+//   pending   -> still waiting (CDC redelivery), not published
+//   approved  -> published through the normal pipeline, published_at recorded
+//   rejected  -> dropped
+//
+// Approving (admin API) flips the status with an LWT and re-inserts the
+// parked row into outbox_messages under its original id. CDC picks it up
+// like any other write, so the release gets the usual region filter,
+// routing, retries and DLQ. Rows are never deleted: who decided, when, why
+// and when the event finally went out stays queryable as the audit trail.
+//
+// Parked sequences are reported to the gap detector as handled, so gap
+// backfill (which republishes straight from event_store) cannot leak them.
+//
+// ============================================================================
+
+/// Where a gated event stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PublicationStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl PublicationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PublicationStatus::Pending => "pending",
+            PublicationStatus::Approved => "approved",
+            PublicationStatus::Rejected => "rejected",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(PublicationStatus::Pending),
+            "approved" => Some(PublicationStatus::Approved),
+            "rejected" => Some(PublicationStatus::Rejected),
+            _ => None,
+        }
+    }
+}
+
+/// A gated outbox event with its approval state
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingPublication {
+    /// Outbox row id
+    pub id: Uuid,
+    pub aggregate_id: Uuid,
+    pub event_id: Option<Uuid>,
+    pub correlation_id: Option<Uuid>,
+    pub causation_id: Option<Uuid>,
+    pub sequence_number: Option<i64>,
+    pub event_type: String,
+    pub payload: String,
+    pub partition_key: Option<String>,
+    pub origin_region: Option<String>,
+    pub status: PublicationStatus,
+    pub parked_at: DateTime<Utc>,
+    pub decided_by: Option<String>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub reason: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+/// Result of an approve/reject request
+#[derive(Debug)]
+pub enum DecisionOutcome {
+    Decided(PendingPublication),
+    NotFound,
+    /// Someone decided first; carries their decision
+    AlreadyDecided(PendingPublication),
+}
+
+const PUBLICATION_COLUMNS: &str = "id, aggregate_id, event_id, correlation_id, causation_id, sequence_number, \
+    event_type, payload, partition_key, origin_region, status, parked_at, decided_by, decided_at, reason, published_at";
+
+type PublicationRow = (
+    Uuid,
+    Uuid,
+    Option<Uuid>,
+    Option<Uuid>,
+    Option<Uuid>,
+    Option<i64>,
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    String,
+    DateTime<Utc>,
+    Option<String>,
+    Option<DateTime<Utc>>,
+    Option<String>,
+    Option<DateTime<Utc>>,
+);
+
+fn publication(row: PublicationRow) -> Result<PendingPublication> {
+    let (id, aggregate_id, event_id, correlation_id, causation_id, sequence_number, event_type, payload,
+         partition_key, origin_region, status, parked_at, decided_by, decided_at, reason, published_at) = row;
+
+    let Some(status) = PublicationStatus::parse(&status) else {
+        bail!("Unknown publication status '{}' for {}", status, id);
+    };
+    Ok(PendingPublication {
+        id,
+        aggregate_id,
+        event_id,
+        correlation_id,
+        causation_id,
+        sequence_number,
+        event_type,
+        payload,
+        partition_key,
+        origin_region,
+        status,
+        parked_at,
+        decided_by,
+        decided_at,
+        reason,
+        published_at,
+    })
+}
+
+/// Read `[applied]` of an LWT
+pub(super) fn lwt_applied(result: scylla::response::query_result::QueryResult) -> Result<bool> {
+    let Some(row) = result.into_rows_result()?.maybe_first_row::<Row>()? else {
+        bail!("LWT returned no [applied] row");
+    };
+    Ok(row.columns.first().cloned().flatten().and_then(|v| v.as_boolean()).unwrap_or(false))
+}
+
+/// Parks configured event types until they are approved
+pub struct ApprovalGate {
+    session: Arc<Session>,
+    event_types: HashSet<String>,
+}
+
+impl ApprovalGate {
+    pub fn new(session: Arc<Session>, event_types: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            session,
+            event_types: event_types.into_iter().collect(),
+        }
+    }
+
+    pub fn requires_approval(&self, event_type: &str) -> bool {
+        self.event_types.contains(event_type)
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<Option<PendingPublication>> {
+        let row = self
+            .session
+            .query_unpaged(
+                format!("SELECT {} FROM pending_publications WHERE id = ?", PUBLICATION_COLUMNS),
+                (id,),
+            )
+            .await?
+            .into_rows_result()?
+            .maybe_first_row::<PublicationRow>()?;
+
+        row.map(publication).transpose()
+    }
+
+    /// Publications in `status`, at most `limit`
+    pub async fn list(&self, status: PublicationStatus, limit: i32) -> Result<Vec<PendingPublication>> {
+        let result = self
+            .session
+            .query_unpaged(
+                format!("SELECT {} FROM pending_publications WHERE status = ? LIMIT ?", PUBLICATION_COLUMNS),
+                (status.as_str(), limit),
+            )
+            .await?
+            .into_rows_result()?;
+
+        let mut publications = Vec::new();
+        for row in result.rows::<PublicationRow>()? {
+            publications.push(publication(row?)?);
+        }
+        Ok(publications)
+    }
+
+    /// Park a gated event; parking it again (CDC redelivery) keeps the first row
+    pub async fn park(&self, publication: &PendingPublication) -> Result<()> {
+        self.session
+            .query_unpaged(
+                "INSERT INTO pending_publications (id, aggregate_id, event_id, correlation_id, causation_id, \
+                 sequence_number, event_type, payload, partition_key, origin_region, status, parked_at) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) IF NOT EXISTS",
+                (
+                    publication.id,
+                    publication.aggregate_id,
+                    publication.event_id,
+                    publication.correlation_id,
+                    publication.causation_id,
+                    publication.sequence_number,
+                    &publication.event_type,
+                    &publication.payload,
+                    &publication.partition_key,
+                    &publication.origin_region,
+                    PublicationStatus::Pending.as_str(),
+                    publication.parked_at,
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Approve or reject a pending publication; approval releases it
+    ///
+    /// Approving an approved but unpublished event again repeats the release,
+    /// e.g. after the outbox write failed.
+    pub async fn decide(&self, id: Uuid, approve: bool, decided_by: &str, reason: Option<&str>) -> Result<DecisionOutcome> {
+        let status = if approve { PublicationStatus::Approved } else { PublicationStatus::Rejected };
+        let result = self
+            .session
+            .query_unpaged(
+                "UPDATE pending_publications SET status = ?, decided_by = ?, decided_at = ?, reason = ? \
+                 WHERE id = ? IF status = ?",
+                (status.as_str(), decided_by, Utc::now(), reason, id, PublicationStatus::Pending.as_str()),
+            )
+            .await?;
+        let applied = lwt_applied(result)?;
+
+        let Some(publication) = self.get(id).await? else {
+            return Ok(DecisionOutcome::NotFound);
+        };
+        if !applied {
+            let retry_release = approve
+                && publication.status == PublicationStatus::Approved
+                && publication.published_at.is_none();
+            if !retry_release {
+                return Ok(DecisionOutcome::AlreadyDecided(publication));
+            }
+            self.release(&publication).await?;
+            return Ok(DecisionOutcome::Decided(publication));
+        }
+
+        tracing::info!(
+            publication_id = %id,
+            event_type = %publication.event_type,
+            status = status.as_str(),
+            decided_by = %decided_by,
+            "🛂 Publication decided"
+        );
+        if approve {
+            self.release(&publication).await?;
+        }
+        Ok(DecisionOutcome::Decided(publication))
+    }
+
+    /// Hand an approved event back to the CDC relay via the outbox
+    async fn release(&self, publication: &PendingPublication) -> Result<()> {
+        self.session
+            .query_unpaged(
+                "INSERT INTO outbox_messages (id, aggregate_id, event_id, event_type, sequence_number, payload, \
+                 partition_key, causation_id, correlation_id, origin_region, created_at, attempts) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 0)",
+                (
+                    publication.id,
+                    publication.aggregate_id,
+                    publication.event_id,
+                    &publication.event_type,
+                    publication.sequence_number,
+                    &publication.payload,
+                    &publication.partition_key,
+                    publication.causation_id,
+                    publication.correlation_id,
+                    &publication.origin_region,
+                    Utc::now(),
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Record that an approved event reached the broker
+    pub async fn mark_published(&self, id: Uuid) -> Result<()> {
+        self.session
+            .query_unpaged(
+                "UPDATE pending_publications SET published_at = ? WHERE id = ?",
+                (Utc::now(), id),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_round_trip() {
+        for status in [PublicationStatus::Pending, PublicationStatus::Approved, PublicationStatus::Rejected] {
+            assert_eq!(PublicationStatus::parse(status.as_str()), Some(status));
+            assert_eq!(serde_json::to_value(status).unwrap(), status.as_str());
+        }
+        assert_eq!(PublicationStatus::parse("Approved"), None);
+    }
+
+    #[test]
+    fn test_row_with_unknown_status_is_rejected() {
+        let row: PublicationRow = (
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            None,
+            None,
+            None,
+            Some(3),
+            "RefundIssued".to_string(),
+            "{}".to_string(),
+            None,
+            None,
+            "approved".to_string(),
+            Utc::now(),
+            Some("ops@example.com".to_string()),
+            Some(Utc::now()),
+            None,
+            None,
+        );
+        assert_eq!(publication(row.clone()).unwrap().status, PublicationStatus::Approved);
+
+        let mut unknown = row;
+        unknown.10 = "on_hold".to_string();
+        assert!(publication(unknown).is_err());
+    }
+}
@@ -0,0 +1,312 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use scylla_cdc::cdc_types::{GenerationTimestamp, StreamID};
+use scylla_cdc::checkpoints::{CDCCheckpointSaver, Checkpoint};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid::Uuid;
+
+use super::relay_drain::RelayDrain;
+use crate::db::{Operation, StatementCache};
+
+// ============================================================================
+// CDC Relay Checkpoint - Resume the Outbox Relay Where It Stopped
+// ============================================================================
+//
+// Without a checkpoint the relay's log reader starts at "now", and outbox
+// rows written while the service was down never reach Redpanda.
+//
+// Per stream (streaming mode): the log reader reads its CDC streams
+// concurrently, window by window, and streams lag behind each other by
+// arbitrary amounts. The checkpoint is the reader's CDCCheckpointSaver:
+// every FLUSH_INTERVAL the reader saves, per stream, the start of the
+// first window not yet consumed, with its CDC generation, in
+// cdc_stream_offsets ((consumer_id, table_name), stream_id); a marker row
+// (stream id 0x00) holds the newest generation. A window only counts as
+// consumed once its batches are out (the consumers wait for in-flight rows
+// at every end_of_batch); once the drain has begun, rows are refused and
+// saves are ignored. On start each stream resumes from its own position,
+// and the reader starts no earlier than the saved generation.
+//
+// Relay-wide: the consumers also report every completed CDC batch (a row
+// with end_of_batch) with its cdc$time; the checkpoint keeps the newest one
+// and persists it in cdc_offsets (consumer_id, table_name) every
+// FLUSH_INTERVAL. The outbox poller resumes from it; a reader without
+// stream positions yet (first start after an upgrade) starts REPLAY_SLACK
+// before it.
+//
+// `restore` returns where reading starts: the oldest stream position of the
+// current generation (streams without a position start there), else the
+// relay-wide position - REPLAY_SLACK. Rows read again are relayed again,
+// which the relay's at-least-once contract already allows (outbox events
+// already published are skipped, see published_events.rs).
+//
+// One relay per consumer_id: region-aware relays use their own.
+//
+// ============================================================================
+
+/// How far before the relay-wide checkpoint a restart without stream
+/// positions resumes reading
+pub(crate) const REPLAY_SLACK: chrono::Duration = chrono::Duration::minutes(2);
+
+/// How often an advanced checkpoint is persisted
+pub(crate) const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// (stream_id, generation, time) of a cdc_stream_offsets row
+type StreamPosition = (Vec<u8>, Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+/// cdc_stream_offsets row holding the newest generation
+fn generation_marker() -> StreamID {
+    StreamID::new(vec![0])
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Position {
+    time: DateTime<Utc>,
+    event_id: Option<Uuid>,
+}
+
+/// Newest relayed CDC position of one relay, persisted in cdc_offsets
+pub(crate) struct CdcCheckpoint {
+    statements: Arc<StatementCache>,
+    consumer_id: String,
+    table: String,
+    position: Mutex<Option<Position>>,
+    /// Advanced since the last flush
+    dirty: AtomicBool,
+    /// Stream positions are no longer saved once the drain has begun
+    drain: Option<Arc<RelayDrain>>,
+}
+
+impl CdcCheckpoint {
+    pub fn new(statements: Arc<StatementCache>, consumer_id: &str, table: &str) -> Self {
+        Self {
+            statements,
+            consumer_id: consumer_id.to_string(),
+            table: table.to_string(),
+            position: Mutex::new(None),
+            dirty: AtomicBool::new(false),
+            drain: None,
+        }
+    }
+
+    /// Stop saving stream positions once `drain` has begun
+    pub fn with_drain(mut self, drain: Arc<RelayDrain>) -> Self {
+        self.drain = Some(drain);
+        self
+    }
+
+    /// Restore the persisted positions; returns where the reader should start
+    pub async fn restore(&self) -> anyhow::Result<Option<DateTime<Utc>>> {
+        let relay_wide = self.restore_relay_wide().await?;
+        let oldest_stream = self.oldest_stream_position().await?;
+        Ok(oldest_stream.or(relay_wide.map(|time| time - REPLAY_SLACK)))
+    }
+
+    /// Oldest stream position of the newest saved generation
+    async fn oldest_stream_position(&self) -> anyhow::Result<Option<DateTime<Utc>>> {
+        let rows = self
+            .statements
+            .execute(
+                Operation::Offsets,
+                "SELECT stream_id, generation, time FROM cdc_stream_offsets WHERE consumer_id = ? AND table_name = ?",
+                (&self.consumer_id, &self.table),
+            )
+            .await?
+            .into_rows_result()?;
+        let positions = rows
+            .rows::<StreamPosition>()?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(oldest_of_generation(&positions))
+    }
+
+    /// Restore the relay-wide position
+    async fn restore_relay_wide(&self) -> anyhow::Result<Option<DateTime<Utc>>> {
+        let row = self
+            .statements
+            .execute(
+                Operation::Offsets,
+                "SELECT last_processed_time, last_event_id FROM cdc_offsets WHERE consumer_id = ? AND table_name = ?",
+                (&self.consumer_id, &self.table),
+            )
+            .await?
+            .into_rows_result()?
+            .maybe_first_row::<(Option<DateTime<Utc>>, Option<Uuid>)>()?;
+
+        let Some((Some(time), event_id)) = row else {
+            return Ok(None);
+        };
+        *self.position.lock().unwrap() = Some(Position { time, event_id });
+        Ok(Some(time))
+    }
+
+    fn is_draining(&self) -> bool {
+        self.drain.as_ref().is_some_and(|drain| drain.is_draining())
+    }
+
+    /// Record a completed CDC batch; never moves the checkpoint backwards
+    pub fn advance(&self, time: DateTime<Utc>, event_id: Option<Uuid>) {
+        let mut position = self.position.lock().unwrap();
+        if position.is_some_and(|p| p.time >= time) {
+            return;
+        }
+        *position = Some(Position { time, event_id });
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    /// Persist the position if it advanced since the last flush
+    pub async fn flush(&self) -> anyhow::Result<()> {
+        if !self.dirty.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+        let Some(position) = *self.position.lock().unwrap() else { return Ok(()) };
+
+        let result = self
+            .statements
+            .execute(
+                Operation::Offsets,
+                "UPDATE cdc_offsets SET last_processed_time = ?, last_event_id = ?, updated_at = ?
+                 WHERE consumer_id = ? AND table_name = ?",
+                (position.time, position.event_id, Utc::now(), &self.consumer_id, &self.table),
+            )
+            .await;
+        if result.is_err() {
+            // Try again with the next flush
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+        result?;
+        Ok(())
+    }
+
+    /// Flush every FLUSH_INTERVAL in the background
+    pub fn spawn_flusher(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.flush().await {
+                    tracing::warn!(consumer_id = %self.consumer_id, error = %e, "Failed to persist CDC checkpoint");
+                }
+            }
+        });
+    }
+}
+
+/// Oldest position among the rows of the newest generation; the generation
+/// marker row only names that generation
+fn oldest_of_generation(rows: &[StreamPosition]) -> Option<DateTime<Utc>> {
+    let newest = rows.iter().filter_map(|(_, generation, _)| *generation).max()?;
+    rows.iter()
+        .filter(|(stream_id, generation, _)| *stream_id != [0] && *generation == Some(newest))
+        .filter_map(|(_, _, time)| *time)
+        .min()
+}
+
+/// Saves and loads the log reader's per-stream positions
+#[async_trait]
+impl CDCCheckpointSaver for CdcCheckpoint {
+    async fn save_checkpoint(&self, checkpoint: &Checkpoint) -> anyhow::Result<()> {
+        // Rows refused by the drain look consumed to the reader
+        if self.is_draining() {
+            return Ok(());
+        }
+        let time = DateTime::from_timestamp_millis(checkpoint.timestamp.as_millis() as i64)
+            .ok_or_else(|| anyhow::anyhow!("CDC stream position out of range"))?;
+        self.statements
+            .execute(
+                Operation::Offsets,
+                "UPDATE cdc_stream_offsets SET generation = ?, time = ?, updated_at = ?
+                 WHERE consumer_id = ? AND table_name = ? AND stream_id = ?",
+                (&checkpoint.generation, time, Utc::now(), &self.consumer_id, &self.table, &checkpoint.stream_id),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn save_new_generation(&self, generation: &GenerationTimestamp) -> anyhow::Result<()> {
+        self.statements
+            .execute(
+                Operation::Offsets,
+                "UPDATE cdc_stream_offsets SET generation = ?, updated_at = ?
+                 WHERE consumer_id = ? AND table_name = ? AND stream_id = ?",
+                (generation, Utc::now(), &self.consumer_id, &self.table, generation_marker()),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn load_last_generation(&self) -> anyhow::Result<Option<GenerationTimestamp>> {
+        let row = self
+            .statements
+            .execute(
+                Operation::Offsets,
+                "SELECT generation FROM cdc_stream_offsets WHERE consumer_id = ? AND table_name = ? AND stream_id = ?",
+                (&self.consumer_id, &self.table, generation_marker()),
+            )
+            .await?
+            .into_rows_result()?
+            .maybe_first_row::<(Option<GenerationTimestamp>,)>()?;
+        Ok(row.and_then(|(generation,)| generation))
+    }
+
+    async fn load_last_checkpoint(&self, stream_id: &StreamID) -> anyhow::Result<Option<chrono::Duration>> {
+        let row = self
+            .statements
+            .execute(
+                Operation::Offsets,
+                "SELECT time FROM cdc_stream_offsets WHERE consumer_id = ? AND table_name = ? AND stream_id = ?",
+                (&self.consumer_id, &self.table, stream_id),
+            )
+            .await?
+            .into_rows_result()?
+            .maybe_first_row::<(Option<DateTime<Utc>>,)>()?;
+        Ok(row
+            .and_then(|(time,)| time)
+            .map(|time| chrono::Duration::milliseconds(time.timestamp_millis())))
+    }
+}
+
+/// Wall-clock time of a cdc$time timeuuid
+pub(crate) fn cdc_time(time: Uuid) -> Option<DateTime<Utc>> {
+    let (seconds, nanos) = time.get_timestamp()?.to_unix();
+    DateTime::from_timestamp(seconds as i64, nanos)
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::{NoContext, Timestamp};
+
+    #[test]
+    fn test_cdc_time_of_timeuuid() {
+        let at = DateTime::from_timestamp(1_767_268_800, 250_000_000).unwrap();
+        let time = Uuid::new_v7(Timestamp::from_unix(NoContext, 1_767_268_800, 250_000_000));
+        assert_eq!(cdc_time(time), Some(at));
+
+        // Random UUIDs carry no time
+        assert_eq!(cdc_time(Uuid::new_v4()), None);
+    }
+
+    #[test]
+    fn test_oldest_stream_position_of_newest_generation() {
+        let at = |secs| DateTime::from_timestamp(secs, 0).unwrap();
+        let (old_generation, generation) = (Some(at(1_000)), Some(at(2_000)));
+        let rows = vec![
+            (vec![0], generation, None),
+            // A stream lagging far behind the others still resumes from its own position
+            (vec![1, 1], generation, Some(at(2_100))),
+            (vec![1, 2], generation, Some(at(9_000))),
+            // Streams of the previous generation are finished
+            (vec![2, 1], old_generation, Some(at(1_500))),
+        ];
+
+        assert_eq!(oldest_of_generation(&rows), Some(at(2_100)));
+        assert_eq!(oldest_of_generation(&rows[..1]), None);
+        assert_eq!(oldest_of_generation(&[]), None);
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::actors::core::{ComponentHealth, HealthCheckable, HealthStatus};
+
+// ============================================================================
+// CDC Lag - How Far Behind the Writes Is the Relay?
+// ============================================================================
+//
+// A running reader (cdc_reader) can still fall behind: Redpanda is slow,
+// the publish pool is saturated, the throttle backs off. Every relayed
+// outbox row records its lag - the time between the row's `created_at` and
+// the moment the relay handles it - per `keyspace.table`:
+//
+//   cdc_lag_seconds{source}   lag of the last row relayed from the table
+//   outbox_pending_rows       rows not (yet) marked published, counted by
+//                             each OutboxJanitor pass
+//
+// Registered with the HealthRegistry as the `cdc_lag` component: degraded
+// while the last lag of any table exceeds `max_lag` ([cdc] max_lag_secs).
+// A sample older than `max_lag` counts as idle - no rows were written since,
+// or the reader stopped, which `cdc_reader` reports. Lag does not gate
+// readiness: taking the service out would not help the relay catch up.
+//
+// ============================================================================
+
+/// Lag of the last row relayed from one table
+#[derive(Debug, Clone, Copy)]
+struct LagSample {
+    lag: Duration,
+    observed_at: Instant,
+}
+
+/// Relay lag of the CDC processors, by `keyspace.table`
+#[derive(Debug)]
+pub struct CdcLag {
+    max_lag: Duration,
+    sources: Mutex<BTreeMap<String, LagSample>>,
+}
+
+impl CdcLag {
+    pub fn new(max_lag: Duration) -> Self {
+        Self { max_lag, sources: Mutex::new(BTreeMap::new()) }
+    }
+
+    pub fn max_lag(&self) -> Duration {
+        self.max_lag
+    }
+
+    /// Record the lag of a row of `source` relayed now
+    pub fn observe(&self, source: &str, lag: Duration) {
+        self.observe_at(source, lag, Instant::now());
+    }
+
+    fn observe_at(&self, source: &str, lag: Duration, observed_at: Instant) {
+        self.sources.lock().unwrap().insert(source.to_string(), LagSample { lag, observed_at });
+    }
+
+    /// Health of all tables together, and one `source: lag` per table
+    pub fn health(&self) -> (HealthStatus, String) {
+        self.health_at(Instant::now())
+    }
+
+    fn health_at(&self, now: Instant) -> (HealthStatus, String) {
+        let sources = self.sources.lock().unwrap();
+        let fresh = |sample: &LagSample| now.saturating_duration_since(sample.observed_at) <= self.max_lag;
+        let details = sources
+            .iter()
+            .map(|(source, sample)| {
+                if fresh(sample) {
+                    format!("{}: {:.1}s", source, sample.lag.as_secs_f64())
+                } else {
+                    format!("{}: idle", source)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let behind: Vec<String> = sources
+            .iter()
+            .filter(|(_, sample)| fresh(sample) && sample.lag > self.max_lag)
+            .map(|(source, sample)| format!("{} ({:.0}s)", source, sample.lag.as_secs_f64()))
+            .collect();
+        let status = if behind.is_empty() {
+            HealthStatus::Healthy
+        } else {
+            HealthStatus::Degraded(format!(
+                "CDC lag above {}s: {}",
+                self.max_lag.as_secs(),
+                behind.join(", ")
+            ))
+        };
+        (status, details)
+    }
+}
+
+impl HealthCheckable for CdcLag {
+    fn check_health(&self) -> ComponentHealth {
+        let (status, details) = self.health();
+        ComponentHealth::new(self.component_name(), status).with_details(details)
+    }
+
+    fn component_name(&self) -> &str {
+        "cdc_lag"
+    }
+
+    fn gates_readiness(&self) -> bool {
+        false
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_degraded_while_lag_exceeds_threshold() {
+        let lag = CdcLag::new(Duration::from_secs(60));
+        assert_eq!(lag.health().0, HealthStatus::Healthy);
+
+        let start = Instant::now();
+        lag.observe_at("orders_ks.outbox_messages", Duration::from_secs(2), start);
+        lag.observe_at("orders_ks.order_summaries", Duration::from_secs(90), start);
+        let (status, details) = lag.health_at(start);
+        assert_eq!(
+            status,
+            HealthStatus::Degraded("CDC lag above 60s: orders_ks.order_summaries (90s)".to_string())
+        );
+        assert_eq!(details, "orders_ks.order_summaries: 90.0s, orders_ks.outbox_messages: 2.0s");
+
+        // Caught up
+        lag.observe_at("orders_ks.order_summaries", Duration::from_millis(500), start);
+        assert!(lag.health_at(start).0.is_healthy());
+
+        // A stale sample no longer counts
+        lag.observe_at("orders_ks.order_summaries", Duration::from_secs(90), start);
+        let (status, details) = lag.health_at(start + Duration::from_secs(61));
+        assert_eq!(status, HealthStatus::Healthy);
+        assert!(details.contains("orders_ks.order_summaries: idle"));
+        assert!(!lag.gates_readiness());
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use crate::actors::core::{ComponentHealth, HealthCheckable, HealthStatus};
+
+// ============================================================================
+// CDC Reader Liveness - Is Every CDC Log Reader Still Running?
+// ============================================================================
+//
+// A CDC log reader runs in a background task; when it fails (generation
+// fetch errors, lost session) the relay silently stops while everything
+// else looks fine. Each CdcProcessor records its reader's state here:
+//
+//   pending  waiting for its startup phase or still building the reader
+//   running  reading the CDC log
+//   stopped  the reader task ended (shutdown, or the reader gave up)
+//   failed   the reader could not be started or ended with an error
+//
+// Registered with the HealthRegistry as the `cdc_reader` component:
+// unhealthy when any reader stopped or failed, degraded while one is
+// pending - and not ready until every reader runs.
+//
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CdcReaderState {
+    Pending,
+    Running,
+    Stopped,
+    Failed(String),
+}
+
+impl CdcReaderState {
+    fn label(&self) -> &'static str {
+        match self {
+            CdcReaderState::Pending => "pending",
+            CdcReaderState::Running => "running",
+            CdcReaderState::Stopped => "stopped",
+            CdcReaderState::Failed(_) => "failed",
+        }
+    }
+}
+
+/// States of the CDC readers, by `keyspace.table`
+#[derive(Debug, Default)]
+pub struct CdcLiveness {
+    readers: Mutex<BTreeMap<String, CdcReaderState>>,
+}
+
+impl CdcLiveness {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&self, source: &str, state: CdcReaderState) {
+        self.readers.lock().unwrap().insert(source.to_string(), state);
+    }
+
+    /// Health of all readers together, and one `source: state` per reader
+    pub fn health(&self) -> (HealthStatus, String) {
+        let readers = self.readers.lock().unwrap();
+        let details = readers
+            .iter()
+            .map(|(source, state)| match state {
+                CdcReaderState::Failed(error) => format!("{}: failed ({})", source, error),
+                state => format!("{}: {}", source, state.label()),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let down: Vec<&str> = readers
+            .iter()
+            .filter(|(_, state)| matches!(state, CdcReaderState::Stopped | CdcReaderState::Failed(_)))
+            .map(|(source, _)| source.as_str())
+            .collect();
+        let status = if readers.is_empty() {
+            HealthStatus::Degraded("No CDC reader registered".to_string())
+        } else if !down.is_empty() {
+            HealthStatus::Unhealthy(format!("CDC reader down: {}", down.join(", ")))
+        } else if readers.values().any(|state| *state == CdcReaderState::Pending) {
+            HealthStatus::Degraded("CDC reader starting".to_string())
+        } else {
+            HealthStatus::Healthy
+        };
+        (status, details)
+    }
+}
+
+impl HealthCheckable for CdcLiveness {
+    fn check_health(&self) -> ComponentHealth {
+        let (status, details) = self.health();
+        ComponentHealth::new(self.component_name(), status).with_details(details)
+    }
+
+    fn component_name(&self) -> &str {
+        "cdc_reader"
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_any_stopped_reader_is_unhealthy() {
+        let liveness = CdcLiveness::new();
+        assert!(liveness.health().0.is_degraded());
+
+        liveness.set("orders_ks.outbox_messages", CdcReaderState::Pending);
+        liveness.set("orders_ks.order_summaries", CdcReaderState::Running);
+        assert!(liveness.health().0.is_degraded());
+
+        liveness.set("orders_ks.outbox_messages", CdcReaderState::Running);
+        assert_eq!(liveness.health().0, HealthStatus::Healthy);
+        assert!(liveness.check_health().status.is_healthy());
+        assert!(liveness.gates_readiness());
+
+        liveness.set("orders_ks.order_summaries", CdcReaderState::Failed("no generation".to_string()));
+        let (status, details) = liveness.health();
+        assert_eq!(status, HealthStatus::Unhealthy("CDC reader down: orders_ks.order_summaries".to_string()));
+        assert_eq!(
+            details,
+            "orders_ks.order_summaries: failed (no generation), orders_ks.outbox_messages: running"
+        );
+    }
+}
@@ -1,16 +1,39 @@
 use kameo::Actor;
-use kameo::actor::ActorRef;
+use kameo::actor::{ActorRef, WeakActorRef};
 use kameo::error::Infallible;
+use kameo::message::{Context, Message};
 use scylla::client::session::Session;
-use std::sync::Arc;
-use crate::messaging::RedpandaClient;
-use crate::utils::{retry_with_backoff, RetryConfig, RetryResult};
-use super::{DlqActor, AddToDlq};
+use std::sync::{Arc, Mutex};
+use crate::messaging::{EnvelopeHeaders, EventPublisher, EventSubscriptions, KeyFields, KeyStrategy, PayloadFormat, PublishOrigin, RedpandaClient, RegionConfig, RoutedEvent, RoutingRules};
+use crate::actors::core::PriorityMailbox;
+use crate::config::{CdcMode, CdcSource, CdcTopicMapping};
+use crate::db::StatementCache;
+use crate::event_sourcing::{open_payload, EventCrypto, EventEnvelope, ShardLayout, TenantContext, UpcasterRegistry};
+use crate::metrics::{EventLabels, MetricsHandle};
+use crate::utils::{record_retry, retry_with_backoff_recorded, RetryConfig, RetryResult};
+use super::{CdcThrottle, DeadLetterSink, DlqActor, AddToDlq, FailureContext, StartupPhase, StartupSequencer};
+use super::approval_gate::{ApprovalGate, PendingPublication, PublicationStatus};
+use super::cdc_checkpoint::{cdc_time, CdcCheckpoint, FLUSH_INTERVAL};
+use super::cdc_lag::CdcLag;
+use super::cdc_liveness::{CdcLiveness, CdcReaderState};
+use super::outbox_janitor::PublishMarker;
+use super::outbox_poller::OutboxPoller;
+use super::outbox_scheduler::{due_bucket, OutboxScheduler, ScheduleSink, ScheduledPublication};
+use super::published_events::PublishedEvents;
+use super::publish_pool::{PublishPool, PublishPoolConfig};
+use super::relay_drain::{InFlightGuard, RelayDrain};
+use super::table_relay::{TableConsumerFactory, TableRelay};
+use super::sequence_gaps::{GapBackfill, SequenceGapDetector};
+use super::stream_auditor::StreamAuditor;
+use super::outbox_row::OutboxRow;
 use uuid::Uuid;
 use chrono::Utc;
-use scylla_cdc::consumer::{Consumer, ConsumerFactory, CDCRow, OperationType};
-use scylla_cdc::log_reader::CDCLogReaderBuilder;
+use scylla_cdc::consumer::{Consumer, ConsumerFactory, CDCRow};
+use scylla_cdc::log_reader::{CDCLogReader, CDCLogReaderBuilder};
 use async_trait::async_trait;
+use futures_util::FutureExt;
+use std::panic::AssertUnwindSafe;
+use tracing::Instrument;
 
 // ============================================================================
 // CDC Stream Processor Actor - Uses real ScyllaDB CDC streams
@@ -30,82 +53,411 @@ use async_trait::async_trait;
 // - The scylla-cdc library reads from these log tables continuously
 // - We implement the Consumer trait to process each CDC row
 // - Each row represents a change (insert/update/delete) to outbox_messages
-// - We extract the event data and publish to Redpanda
+// - We extract the event data and publish to Redpanda, with the envelope
+//   metadata (id, aggregate, sequence, version, correlation) as message
+//   headers; PayloadFormat::Envelope publishes the whole EventEnvelope JSON
+//   as the value instead of just the event (see messaging/headers.rs)
+// - Event types that need approval are parked by the ApprovalGate instead
+//   and published once approved
+// - Rows with a future `publish_after` are held by the OutboxScheduler and
+//   published once due (outbox_scheduler.rs)
+// - With an UpcasterRegistry, payloads stored at an older schema version are
+//   upcast to the latest before publishing; one that cannot be upcast goes
+//   to the DLQ
+// - The log reader saves each CDC stream's position (cdc_stream_offsets)
+//   and completed CDC batches advance the relay's checkpoint (cdc_offsets);
+//   a restart resumes every stream from its own position instead of "now"
+// - DrainCdc (graceful shutdown) stops the reader, waits for the rows in
+//   flight to be published or dead-lettered and flushes the checkpoint
+// - A reader that fails to start, fails or panics outside a drain stops
+//   the actor with an error (checkpoint flushed first), so its supervisor
+//   (the coordinator) restarts it with a fresh reader
+// - Published events are recorded in published_events; rows re-delivered
+//   after a restart or reader error are skipped as duplicates
+// - The StreamAuditor checks the per-aggregate order of what was published
+// - Published outbox rows get `published_at` set, so the OutboxJanitor can
+//   reclaim them after the retention
+//
+// One processor streams one table (CdcSource). Outbox tables go through
+// OutboxCDCConsumer; other tables with a Rows mapping through the
+// TableRelay (table_relay.rs). Consumed rows are counted per table in
+// cdc_rows_total{source="keyspace.table"}. Every publish records its
+// outcome and latency (cdc_events_processed/failed_total,
+// cdc_processing_duration_seconds) and its retries (retry_*{operation="cdc_publish"}).
+// The lag between writing an outbox row and relaying it is kept per table
+// in cdc_lag_seconds and reported to CdcLag (cdc_lag.rs) for health.
+//
+// Outbox rows are relayed inline, or with a PublishPoolConfig of several
+// workers by a per-stream PublishPool (publish_pool.rs): concurrently across
+// aggregates, in order per aggregate, with backpressure on the CDC reader.
+//
+// In polling mode (CdcMode::Polling) an OutboxPoller reads the outbox table
+// directly instead of the CDC log, relaying through the same consumer with
+// the same checkpoint (outbox_poller.rs).
+//
+// Every relayed event runs in a `cdc_relay` span with the correlation,
+// causation and aggregate id of its outbox row - the ids its command and
+// append were traced with - so the publish logs (and the `redpanda_publish`
+// span below it) can be found by correlation id.
 //
 // ============================================================================
 
-const KEYSPACE: &str = "orders_ks";
-const TABLE: &str = "outbox_messages";
-
 /// Our custom consumer that processes CDC rows from outbox_messages table
+#[derive(Clone)]
 pub(crate) struct OutboxCDCConsumer {
-    redpanda: Arc<RedpandaClient>,
-    dlq_actor: Option<ActorRef<DlqActor>>,
+    publisher: Arc<dyn EventPublisher>,
+    dlq: Option<Arc<dyn DeadLetterSink>>,
     retry_config: RetryConfig,
+    gap_detector: Option<Arc<SequenceGapDetector>>,
+    stream_auditor: Option<Arc<StreamAuditor>>,
+    region: Option<Arc<RegionConfig>>,
+    routing: Option<Arc<RoutingRules>>,
+    key_strategy: KeyStrategy,
+    throttle: Option<Arc<CdcThrottle>>,
+    approval_gate: Option<Arc<ApprovalGate>>,
+    checkpoint: Option<Arc<CdcCheckpoint>>,
+    drain: Option<Arc<RelayDrain>>,
+    published_events: Option<Arc<PublishedEvents>>,
+    publish_marker: Option<Arc<PublishMarker>>,
+    payload_format: PayloadFormat,
+    subscriptions: Option<Arc<EventSubscriptions>>,
+    /// Opens payloads the event store sealed
+    crypto: Option<Arc<dyn EventCrypto>>,
+    metrics: MetricsHandle,
+    /// `keyspace.table`, the metrics label of consumed rows
+    source: String,
+    /// Where the lag of relayed rows is reported for health
+    lag: Option<Arc<CdcLag>>,
+    /// Holds rows whose publish_after is still ahead
+    scheduler: Option<Arc<dyn ScheduleSink>>,
+    /// Brings payloads of older schema versions up to date
+    upcasters: Option<Arc<UpcasterRegistry>>,
+    /// Relays rows concurrently; None relays them inline
+    pool: Option<Arc<PublishPool<PublishJob>>>,
+}
+
+/// A row handed to the publish pool
+struct PublishJob {
+    event: OutboxEvent,
+    _in_flight: Option<InFlightGuard>,
 }
 
 impl OutboxCDCConsumer {
-    pub fn new(redpanda: Arc<RedpandaClient>, dlq_actor: Option<ActorRef<DlqActor>>) -> Self {
+    pub fn new(publisher: Arc<dyn EventPublisher>, dlq: Option<Arc<dyn DeadLetterSink>>) -> Self {
         Self {
-            redpanda,
-            dlq_actor,
+            publisher,
+            dlq,
             retry_config: RetryConfig::aggressive(), // More retries for CDC events
+            gap_detector: None,
+            stream_auditor: None,
+            region: None,
+            routing: None,
+            key_strategy: KeyStrategy::default(), // Per-aggregate ordering
+            throttle: None,
+            approval_gate: None,
+            checkpoint: None,
+            drain: None,
+            published_events: None,
+            publish_marker: None,
+            payload_format: PayloadFormat::default(),
+            subscriptions: None,
+            crypto: None,
+            metrics: MetricsHandle::noop(),
+            source: CdcSource::default().label(),
+            lag: None,
+            scheduler: None,
+            upcasters: None,
+            pool: None,
+        }
+    }
+
+    pub fn with_retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.retry_config = retry_config;
+        self
+    }
+
+    /// Report completed CDC batches to the relay's resume checkpoint
+    pub fn with_checkpoint(mut self, checkpoint: Arc<CdcCheckpoint>) -> Self {
+        self.checkpoint = Some(checkpoint);
+        self
+    }
+
+    /// Track rows in flight so shutdown can wait for them
+    pub fn with_drain(mut self, drain: Arc<RelayDrain>) -> Self {
+        self.drain = Some(drain);
+        self
+    }
+
+    /// Skip events that were already published (re-delivered rows)
+    pub fn with_published_events(mut self, published_events: Arc<PublishedEvents>) -> Self {
+        self.published_events = Some(published_events);
+        self
+    }
+
+    pub fn with_subscriptions(mut self, subscriptions: Arc<EventSubscriptions>) -> Self {
+        self.subscriptions = Some(subscriptions);
+        self
+    }
+
+    /// Set `published_at` on outbox rows once published (janitor retention)
+    pub fn with_publish_marker(mut self, publish_marker: Arc<PublishMarker>) -> Self {
+        self.publish_marker = Some(publish_marker);
+        self
+    }
+
+    /// Publish the event alone (default) or its whole envelope as the value
+    pub fn with_payload_format(mut self, payload_format: PayloadFormat) -> Self {
+        self.payload_format = payload_format;
+        self
+    }
+
+    /// Count consumed rows of `source` by outcome
+    pub fn with_metrics(mut self, metrics: MetricsHandle, source: &CdcSource) -> Self {
+        self.metrics = metrics;
+        self.source = source.label();
+        self
+    }
+
+    /// Relay rows on a pool of workers; call last, the workers relay with
+    /// the configuration at this point
+    pub fn with_publish_pool(mut self, config: PublishPoolConfig) -> Self {
+        if !config.is_concurrent() {
+            return self;
         }
+        let relay = Arc::new(Self { pool: None, ..self.clone() });
+        self.pool = Some(Arc::new(PublishPool::spawn(config, move |job: PublishJob| {
+            let relay = relay.clone();
+            async move {
+                let outcome = relay.relay(Some(job.event)).await;
+                relay.metrics.record_cdc_row(&relay.source, outcome.map_or("skipped", PublishOutcome::as_str));
+            }
+        })));
+        self
+    }
+
+    /// Check per-aggregate sequence continuity of published events
+    pub fn with_gap_detector(mut self, gap_detector: Arc<SequenceGapDetector>) -> Self {
+        self.gap_detector = Some(gap_detector);
+        self
+    }
+
+    pub fn with_stream_auditor(mut self, stream_auditor: Arc<StreamAuditor>) -> Self {
+        self.stream_auditor = Some(stream_auditor);
+        self
+    }
+
+    /// Filter by origin region and route to per-region topics
+    pub fn with_region(mut self, region: Arc<RegionConfig>) -> Self {
+        self.region = Some(region);
+        self
+    }
+
+    /// Copy events matching routing rules to additional topics
+    pub fn with_routing(mut self, routing: Arc<RoutingRules>) -> Self {
+        self.routing = Some(routing);
+        self
+    }
+
+    /// Choose which outbox field becomes the Kafka message key
+    pub fn with_key_strategy(mut self, key_strategy: KeyStrategy) -> Self {
+        self.key_strategy = key_strategy;
+        self
+    }
+
+    /// Slow down consumption while Scylla is under stress
+    pub fn with_throttle(mut self, throttle: Arc<CdcThrottle>) -> Self {
+        self.throttle = Some(throttle);
+        self
+    }
+
+    /// Hold back configured event types until they are approved
+    pub fn with_approval_gate(mut self, approval_gate: Arc<ApprovalGate>) -> Self {
+        self.approval_gate = Some(approval_gate);
+        self
+    }
+
+    pub fn with_crypto(mut self, crypto: Arc<dyn EventCrypto>) -> Self {
+        self.crypto = Some(crypto);
+        self
+    }
+
+    /// Report the lag of relayed rows (cdc_lag health)
+    pub fn with_lag(mut self, lag: Arc<CdcLag>) -> Self {
+        self.lag = Some(lag);
+        self
+    }
+
+    /// Hold rows with a future publish_after until they are due
+    pub fn with_scheduler(mut self, scheduler: Arc<dyn ScheduleSink>) -> Self {
+        self.scheduler = Some(scheduler);
+        self
+    }
+
+    /// Publish payloads at the latest schema version of their type
+    pub fn with_upcasters(mut self, upcasters: Arc<UpcasterRegistry>) -> Self {
+        self.upcasters = Some(upcasters);
+        self
     }
 
     /// Extract event data from a CDC row
     /// CDC rows contain the actual data that was inserted into outbox_messages
-    fn extract_event_from_cdc_row(&self, data: &CDCRow<'_>) -> anyhow::Result<Option<OutboxEvent>> {
+    fn extract_event(&self, row: &impl OutboxRow) -> anyhow::Result<Option<OutboxEvent>> {
         // Only process inserts - we don't care about updates/deletes on outbox table
-        match data.operation {
-            OperationType::RowInsert | OperationType::PostImage => {
-                // Extract the columns from the CDC row
-                let id = data.get_value("id")
-                    .as_ref()
-                    .and_then(|v| v.as_uuid())
-                    .ok_or_else(|| anyhow::anyhow!("Missing or invalid id"))?;
-
-                let aggregate_id = data.get_value("aggregate_id")
-                    .as_ref()
-                    .and_then(|v| v.as_uuid())
-                    .ok_or_else(|| anyhow::anyhow!("Missing or invalid aggregate_id"))?;
-
-                let event_type = data.get_value("event_type")
-                    .as_ref()
-                    .and_then(|v| v.as_text())
-                    .map(|s| s.to_string())
-                    .ok_or_else(|| anyhow::anyhow!("Missing or invalid event_type"))?;
-
-                let payload = data.get_value("payload")
-                    .as_ref()
-                    .and_then(|v| v.as_text())
-                    .map(|s| s.to_string())
-                    .ok_or_else(|| anyhow::anyhow!("Missing or invalid payload"))?;
-
-                tracing::debug!(
+        if !row.is_insert() {
+            tracing::debug!(
+                cdc_operation = %row.operation_name(),
+                "Skipping non-insert CDC operation"
+            );
+            return Ok(None);
+        }
+
+        // Extract the columns from the CDC row
+        let id = row.uuid("id")
+            .ok_or_else(|| anyhow::anyhow!("Missing or invalid id"))?;
+
+        let aggregate_id = row.uuid("aggregate_id")
+            .ok_or_else(|| anyhow::anyhow!("Missing or invalid aggregate_id"))?;
+
+        let event_type = row.text("event_type")
+            .ok_or_else(|| anyhow::anyhow!("Missing or invalid event_type"))?;
+
+        let stored_payload = row.text("payload")
+            .ok_or_else(|| anyhow::anyhow!("Missing or invalid payload"))?;
+
+        // Sealed payloads are published decrypted; one that cannot be opened
+        // (key not configured) stops the stream rather than leak or drop it
+        let opened = match open_payload(self.crypto.as_deref(), &stored_payload)? {
+            std::borrow::Cow::Owned(payload) => Some(payload),
+            std::borrow::Cow::Borrowed(_) => None,
+        };
+        let (payload, sealed_payload) = match opened {
+            Some(payload) => (payload, Some(stored_payload)),
+            None => (stored_payload, None),
+        };
+
+        // NULL for rows written before sequence tracking existed
+        let sequence_number = row.bigint("sequence_number");
+        let event_version = row.int("event_version");
+        let created_at = row.timestamp("created_at");
+        // NULL unless the event was scheduled
+        let publish_after = row.timestamp("publish_after");
+
+        // NULL for legacy OrderActor rows
+        let partition_key = row.text("partition_key");
+
+        let origin_region = row.text("origin_region");
+
+        // NULL for legacy OrderActor rows
+        let aggregate_type = row.text("aggregate_type");
+
+        // NULL for rows written before the outbox stored both type names
+        let aggregate_event_type = row.text("aggregate_event_type");
+        if let Some(variant_type) = row.text("variant_type") {
+            if variant_type != event_type {
+                tracing::warn!(
                     event_id = %id,
                     event_type = %event_type,
-                    aggregate_id = %aggregate_id,
-                    cdc_operation = %data.operation,
-                    "Extracted event from CDC row"
+                    variant_type = %variant_type,
+                    "Outbox event_type differs from the event's own type name"
                 );
+            }
+        }
 
-                Ok(Some(OutboxEvent {
-                    id,
-                    aggregate_id,
-                    event_type,
-                    payload,
-                }))
+        // Envelope metadata, NULL for legacy OrderActor rows
+        let event_id = row.uuid("event_id");
+        let correlation_id = row.uuid("correlation_id");
+        let causation_id = row.uuid("causation_id");
+
+        tracing::debug!(
+            event_id = %id,
+            event_type = %event_type,
+            aggregate_id = %aggregate_id,
+            cdc_operation = %row.operation_name(),
+            "Extracted event from CDC row"
+        );
+
+        Ok(Some(OutboxEvent {
+            id,
+            aggregate_id,
+            aggregate_type,
+            event_id,
+            correlation_id,
+            causation_id,
+            sequence_number,
+            event_version,
+            event_type,
+            aggregate_event_type,
+            payload,
+            sealed_payload,
+            partition_key,
+            origin_region,
+            created_at,
+            publish_after,
+        }))
+    }
+
+    /// Filter an extracted event by region and approval, and publish it
+    ///
+    /// Returns None when nothing was handled (non-insert or foreign origin).
+    async fn relay(&self, event: Option<OutboxEvent>) -> Option<PublishOutcome> {
+        // Non-insert operation, nothing to publish
+        let event = event?;
+        self.record_lag(&event);
+        let span = event.span();
+        self.relay_event(event).instrument(span).await
+    }
+
+    /// Time between writing the row and relaying it now
+    fn record_lag(&self, event: &OutboxEvent) {
+        let Some(created_at) = event.created_at else {
+            return;
+        };
+        let lag = (Utc::now() - created_at).to_std().unwrap_or_default();
+        self.metrics.set_cdc_lag(&self.source, lag.as_secs_f64());
+        if let Some(ref tracker) = self.lag {
+            tracker.observe(&self.source, lag);
+        }
+    }
+
+    async fn relay_event(&self, mut event: OutboxEvent) -> Option<PublishOutcome> {
+        if !self.should_relay(&event) {
+            tracing::debug!(
+                event_id = %event.id,
+                origin_region = ?event.origin_region,
+                "Skipping event originated in another region"
+            );
+            return None;
+        }
+
+        if let Some(ref scheduler) = self.scheduler {
+            if let Some(held) = self.hold_until_due(scheduler.as_ref(), &event).await {
+                return Some(held);
             }
-            _ => {
-                // Ignore updates, deletes, etc.
-                tracing::debug!(
-                    cdc_operation = %data.operation,
-                    "Skipping non-insert CDC operation"
-                );
-                Ok(None)
+        }
+
+        if let Err(e) = self.upcast(&mut event) {
+            return Some(self.dead_letter_held(&event, "Upcasting", e).await);
+        }
+
+        let gate = self
+            .approval_gate
+            .as_ref()
+            .filter(|gate| gate.requires_approval(&event.event_type));
+        let Some(gate) = gate else {
+            return Some(self.publish_once(event).await);
+        };
+        if let Some(held) = self.hold_for_approval(gate, &event).await {
+            return Some(held);
+        }
+
+        let id = event.id;
+        let outcome = self.publish_once(event).await;
+        if outcome == PublishOutcome::Published {
+            if let Err(e) = gate.mark_published(id).await {
+                tracing::warn!(event_id = %id, error = %e, "Failed to record publication of approved event");
             }
         }
+        Some(outcome)
     }
 }
 
@@ -113,196 +465,1865 @@ impl OutboxCDCConsumer {
 struct OutboxEvent {
     id: Uuid,
     aggregate_id: Uuid,
+    aggregate_type: Option<String>,
+    event_id: Option<Uuid>,
+    correlation_id: Option<Uuid>,
+    causation_id: Option<Uuid>,
+    sequence_number: Option<i64>,
+    event_version: Option<i32>,
     event_type: String,
+    /// Type the payload decodes into, e.g. "OrderEvent"
+    aggregate_event_type: Option<String>,
     payload: String,
+    /// The payload as stored, when the event store sealed it
+    sealed_payload: Option<String>,
+    partition_key: Option<String>,
+    origin_region: Option<String>,
+    created_at: Option<chrono::DateTime<Utc>>,
+    /// Publish no earlier than this
+    publish_after: Option<chrono::DateTime<Utc>>,
 }
 
-#[async_trait]
-impl Consumer for OutboxCDCConsumer {
-    async fn consume_cdc(&mut self, data: CDCRow<'_>) -> anyhow::Result<()> {
-        tracing::debug!(
-            stream_id = ?data.stream_id,
-            operation = %data.operation,
-            "Received CDC row"
-        );
+impl OutboxEvent {
+    /// The payload as the outbox stored it, kept by the DLQ and approval gate
+    fn stored_payload(&self) -> &str {
+        self.sealed_payload.as_deref().unwrap_or(&self.payload)
+    }
 
-        // Extract event from CDC row
-        match self.extract_event_from_cdc_row(&data)? {
-            Some(event) => {
-                tracing::info!(
-                    event_id = %event.id,
-                    event_type = %event.event_type,
-                    aggregate_id = %event.aggregate_id,
-                    "📤 Publishing event from CDC stream to Redpanda"
-                );
+    /// Identity of the event across re-deliveries (row id for legacy rows)
+    fn event_key(&self) -> Uuid {
+        self.event_id.unwrap_or(self.id)
+    }
 
-                // Publish with retry
-                let redpanda = self.redpanda.clone();
-                let event_type = event.event_type.clone();
-                let event_id = event.id;
-                let aggregate_id = event.aggregate_id;
-                let payload = event.payload.clone();
-                let first_attempt_time = Utc::now();
-
-                let result = retry_with_backoff(
-                    self.retry_config.clone(),
-                    |attempt| {
-                        let redpanda = redpanda.clone();
-                        let event_type = event_type.clone();
-                        let event_id_str = event_id.to_string();
-                        let payload = payload.clone();
-
-                        async move {
-                            tracing::debug!(
-                                attempt = attempt,
-                                event_id = %event_id,
-                                "Attempting to publish event"
-                            );
-
-                            redpanda.publish(&event_type, &event_id_str, &payload).await
-                        }
-                    }
-                ).await;
+    /// Span of the event's relay; legacy rows have no correlation/causation
+    fn span(&self) -> tracing::Span {
+        tracing::info_span!(
+            "cdc_relay",
+            correlation_id = self.correlation_id.map(tracing::field::display),
+            causation_id = self.causation_id.map(tracing::field::display),
+            aggregate_id = %self.aggregate_id,
+            event_id = %self.event_key(),
+            event_type = %self.event_type,
+        )
+    }
 
-                match result {
-                    RetryResult::Success(_) => {
-                        tracing::info!(
-                            event_id = %event_id,
-                            event_type = %event_type,
-                            "✅ Successfully published event via CDC stream"
-                        );
-                        Ok(())
-                    }
-                    RetryResult::Failed(e) | RetryResult::PermanentFailure(e) => {
-                        tracing::error!(
-                            error = %e,
-                            event_id = %event_id,
-                            event_type = %event_type,
-                            "❌ Failed to publish event after retries, sending to DLQ"
-                        );
+    /// The event as EventEnvelope JSON, the event itself under `event_data`
+    ///
+    /// None for legacy rows without envelope metadata (no event_id) and for
+    /// payloads that are not JSON; those are published as they are.
+    fn envelope(&self, origin_region: Option<&str>) -> Option<EventEnvelope<serde_json::Value>> {
+        let envelope = EventEnvelope {
+            event_id: self.event_id?,
+            aggregate_id: self.aggregate_id,
+            sequence_number: self.sequence_number.unwrap_or(0),
+            event_type: self.event_type.clone(),
+            event_version: self.event_version.unwrap_or(1),
+            event_data: serde_json::from_str::<serde_json::Value>(&self.payload).ok()?,
+            causation_id: self.causation_id,
+            correlation_id: self.correlation_id.unwrap_or_else(Uuid::nil),
+            user_id: None,
+            timestamp: self.created_at.unwrap_or_else(Utc::now),
+            metadata: Default::default(),
+        };
+        Some(match origin_region {
+            Some(region) => envelope.with_origin_region(region),
+            None => envelope,
+        })
+    }
 
-                        // Send to Dead Letter Queue
-                        if let Some(ref dlq) = self.dlq_actor {
-                            // Fire and forget - use tell
-                            let _ = dlq.tell(AddToDlq {
-                                id: event_id,
-                                aggregate_id,
-                                event_type: event_type.clone(),
-                                payload,
-                                error_message: e.to_string(),
-                                failure_count: self.retry_config.max_attempts as i32,
-                                first_failed_at: first_attempt_time,
-                            }).send().await;
-                        }
+    fn envelope_json(&self, origin_region: Option<&str>) -> Option<String> {
+        serde_json::to_string(&self.envelope(origin_region)?).ok()
+    }
+}
+
+/// What happened to an event handed to the publish pipeline
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum PublishOutcome {
+    Published,
+    DeadLettered,
+    /// Waiting for approval
+    Parked,
+    /// Approval was refused, never published
+    Rejected,
+    /// Held until its publish_after
+    Scheduled,
+    /// Already published before, skipped
+    Duplicate,
+}
 
-                        // Don't propagate error - message is in DLQ for manual handling
-                        Ok(())
+impl PublishOutcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PublishOutcome::Published => "published",
+            PublishOutcome::DeadLettered => "dead_lettered",
+            PublishOutcome::Parked => "parked",
+            PublishOutcome::Rejected => "rejected",
+            PublishOutcome::Scheduled => "scheduled",
+            PublishOutcome::Duplicate => "duplicate",
+        }
+    }
+}
+
+impl OutboxCDCConsumer {
+    /// Whether this region relays the event (other regions relay their own)
+    fn should_relay(&self, event: &OutboxEvent) -> bool {
+        self.region
+            .as_ref()
+            .map(|region| region.should_relay(event.origin_region.as_deref()))
+            .unwrap_or(true)
+    }
+
+    /// Publish an event unless it was already published, and record it
+    ///
+    /// A failed lookup publishes anyway - a duplicate beats a lost event.
+    async fn publish_once(&self, event: OutboxEvent) -> PublishOutcome {
+        let key = event.event_key();
+        if let Some(ref published_events) = self.published_events {
+            match published_events.contains(key).await {
+                Ok(true) => {
+                    tracing::info!(event_id = %key, event_type = %event.event_type, "Skipping already published event");
+                    if let (Some(detector), Some(sequence)) = (&self.gap_detector, event.sequence_number) {
+                        detector.record(event.aggregate_id, sequence).await;
+                    }
+                    // In the stream already
+                    if let (Some(auditor), Some(sequence)) = (&self.stream_auditor, event.sequence_number) {
+                        auditor.record(event.aggregate_id, sequence).await;
                     }
+                    return PublishOutcome::Duplicate;
                 }
+                Ok(false) => {}
+                Err(e) => tracing::warn!(event_id = %key, error = %e, "Failed to look up published event, publishing anyway"),
             }
-            None => {
-                // Non-insert operation, nothing to publish
-                Ok(())
+        }
+
+        let (id, event_type) = (event.id, event.event_type.clone());
+        let outcome = self.publish_event(event).await;
+        if outcome == PublishOutcome::Published {
+            self.confirm_published(id, key, &event_type).await;
+        }
+        outcome
+    }
+
+    /// Record a successful publish in the ledger and on the outbox row
+    ///
+    /// Best effort: the event is out either way.
+    async fn confirm_published(&self, id: Uuid, key: Uuid, event_type: &str) {
+        if let Some(ref published_events) = self.published_events {
+            if let Err(e) = published_events.record(key, event_type).await {
+                tracing::warn!(event_id = %key, error = %e, "Failed to record published event");
+            }
+        }
+        if let Some(ref publish_marker) = self.publish_marker {
+            if let Err(e) = publish_marker.mark(id).await {
+                tracing::warn!(event_id = %key, error = %e, "Failed to mark outbox row published");
             }
         }
     }
-}
 
-/// Factory for creating consumer instances
-/// The scylla-cdc library will create one consumer per VNode group
-pub(crate) struct OutboxConsumerFactory {
-    redpanda: Arc<RedpandaClient>,
-    dlq_actor: Option<ActorRef<DlqActor>>,
-}
+    /// Publish an extracted outbox event with retry, falling back to the DLQ
+    async fn publish_event(&self, event: OutboxEvent) -> PublishOutcome {
+        tracing::info!(
+            event_id = %event.id,
+            event_type = %event.event_type,
+            aggregate_id = %event.aggregate_id,
+            "📤 Publishing event from CDC stream to Redpanda"
+        );
 
-impl OutboxConsumerFactory {
-    pub fn new(redpanda: Arc<RedpandaClient>, dlq_actor: Option<ActorRef<DlqActor>>) -> Self {
-        Self { redpanda, dlq_actor }
-    }
-}
+        // Publish with retry
+        let publisher = self.publisher.clone();
+        let event_type = event.event_type.clone();
+        let event_id = event.id;
+        let aggregate_id = event.aggregate_id;
+        let first_attempt_time = Utc::now();
+        let started = std::time::Instant::now();
+        let key = self.key_strategy.key_for(&KeyFields {
+            event_id: event.event_key(),
+            aggregate_id,
+            correlation_id: event.correlation_id,
+            event_type: &event_type,
+            partition_key: event.partition_key.as_deref(),
+        });
 
-#[async_trait]
-impl ConsumerFactory for OutboxConsumerFactory {
-    async fn new_consumer(&self) -> Box<dyn Consumer> {
-        tracing::debug!("Creating new OutboxCDCConsumer instance");
-        Box::new(OutboxCDCConsumer::new(self.redpanda.clone(), self.dlq_actor.clone()))
+        let origin_region = event
+            .origin_region
+            .clone()
+            .or_else(|| self.region.as_ref().map(|r| r.region().to_string()));
+        let topic = match self.region {
+            Some(ref region) => region.topic_for(&event_type, origin_region.as_deref()),
+            None => event_type.clone(),
+        };
+        // The DLQ keeps the stored event either way
+        let envelope = match self.payload_format {
+            PayloadFormat::Envelope => event.envelope_json(origin_region.as_deref()),
+            PayloadFormat::Event => None,
+        };
+        let payload_format = envelope.is_some().then_some(PayloadFormat::Envelope);
+        let message = envelope.unwrap_or_else(|| event.payload.clone());
+        let headers = EnvelopeHeaders {
+            event_id: event.event_id,
+            aggregate_id: Some(aggregate_id),
+            sequence_number: event.sequence_number,
+            event_type: Some(event_type.clone()),
+            aggregate_event_type: event.aggregate_event_type.clone(),
+            correlation_id: event.correlation_id,
+            causation_id: event.causation_id,
+            event_version: event.event_version,
+            origin_region,
+            payload_format,
+        }
+        .to_pairs();
+
+        let (result, attempts) = retry_with_backoff_recorded(
+            self.retry_config.clone(),
+            |attempt| {
+                let publisher = publisher.clone();
+                let topic = topic.clone();
+                let key = key.clone();
+                let message = message.clone();
+                let headers = headers.clone();
+
+                async move {
+                    tracing::debug!(
+                        attempt = attempt,
+                        event_id = %event_id,
+                        "Attempting to publish event"
+                    );
+
+                    let headers: Vec<(&str, &str)> = headers.iter().map(|(k, v)| (*k, v.as_str())).collect();
+                    publisher.publish_with_origin(&topic, &key, &message, &headers, PublishOrigin::OutboxCdc).await
+                }
+            }
+        ).await;
+
+        let published = matches!(result, RetryResult::Success(_));
+        let labels = EventLabels {
+            event_type: &event_type,
+            aggregate_type: event.aggregate_type.as_deref().unwrap_or("unknown"),
+            topic: &topic,
+        };
+        self.metrics.record_cdc_event(&labels, started.elapsed().as_secs_f64(), published, None);
+        record_retry(&self.metrics, "cdc_publish", &attempts, published);
+
+        match result {
+            RetryResult::Success(_) => {
+                tracing::info!(
+                    event_id = %event_id,
+                    event_type = %event_type,
+                    "✅ Successfully published event via CDC stream"
+                );
+
+                if let (Some(detector), Some(sequence)) = (&self.gap_detector, event.sequence_number) {
+                    detector.record(aggregate_id, sequence).await;
+                }
+                if let (Some(auditor), Some(sequence)) = (&self.stream_auditor, event.sequence_number) {
+                    auditor.record(aggregate_id, sequence).await;
+                }
+
+                if let Some(ref routing) = self.routing {
+                    let headers: Vec<(&str, &str)> = headers.iter().map(|(k, v)| (*k, v.as_str())).collect();
+                    self.route_copies(routing, &event_type, &key, &message, &headers).await;
+                }
+
+                PublishOutcome::Published
+            }
+            RetryResult::Failed(e) | RetryResult::PermanentFailure(e) => {
+                tracing::error!(
+                    error = %e,
+                    event_id = %event_id,
+                    event_type = %event_type,
+                    "❌ Failed to publish event after retries, sending to DLQ"
+                );
+
+                // Send to Dead Letter Queue
+                if let Some(ref dlq) = self.dlq {
+                    let publisher = self.publisher.diagnostics(&topic).await;
+                    let failure_context = FailureContext {
+                        topic,
+                        key,
+                        attempts,
+                        publisher,
+                        captured_at: Utc::now(),
+                    };
+
+                    // Fire and forget - the DLQ actor only queues it
+                    let _ = dlq.dead_letter(AddToDlq {
+                        id: event_id,
+                        aggregate_id,
+                        event_type: event_type.clone(),
+                        payload: event.stored_payload().to_string(),
+                        error_message: e.to_string(),
+                        failure_count: self.retry_config.max_attempts as i32,
+                        first_failed_at: first_attempt_time,
+                        failure_context: Some(failure_context),
+                    }).await;
+                }
+
+                // Don't propagate error - message is in DLQ for manual handling
+                PublishOutcome::DeadLettered
+            }
+        }
     }
 }
 
-// ============================================================================
-// CDC Processor Actor
-// ============================================================================
+impl OutboxCDCConsumer {
+    /// Park or drop a gated event unless it was approved
+    ///
+    /// Returns None when the event may be published. Anything held counts as
+    /// handled for gap detection, so gap backfill cannot publish it.
+    async fn hold_for_approval(&self, gate: &ApprovalGate, event: &OutboxEvent) -> Option<PublishOutcome> {
+        let outcome = match gate.get(event.id).await {
+            Ok(Some(publication)) => match publication.status {
+                PublicationStatus::Approved => return None,
+                PublicationStatus::Pending => PublishOutcome::Parked,
+                PublicationStatus::Rejected => {
+                    tracing::info!(event_id = %event.id, event_type = %event.event_type, "Skipping rejected event");
+                    PublishOutcome::Rejected
+                }
+            },
+            Ok(None) => {
+                let publication = PendingPublication {
+                    id: event.id,
+                    aggregate_id: event.aggregate_id,
+                    event_id: event.event_id,
+                    correlation_id: event.correlation_id,
+                    causation_id: event.causation_id,
+                    sequence_number: event.sequence_number,
+                    event_type: event.event_type.clone(),
+                    payload: event.stored_payload().to_string(),
+                    partition_key: event.partition_key.clone(),
+                    origin_region: event.origin_region.clone(),
+                    status: PublicationStatus::Pending,
+                    parked_at: Utc::now(),
+                    decided_by: None,
+                    decided_at: None,
+                    reason: None,
+                    published_at: None,
+                };
+                match gate.park(&publication).await {
+                    Ok(()) => {
+                        tracing::info!(
+                            event_id = %event.id,
+                            event_type = %event.event_type,
+                            aggregate_id = %event.aggregate_id,
+                            "🛂 Event parked for approval"
+                        );
+                        PublishOutcome::Parked
+                    }
+                    Err(e) => self.dead_letter_held(event, "Approval gate", e).await,
+                }
+            }
+            Err(e) => self.dead_letter_held(event, "Approval gate", e).await,
+        };
 
-pub struct CdcProcessor {
-    session: Arc<Session>,
-    redpanda: Arc<RedpandaClient>,
-    dlq_actor: Option<ActorRef<DlqActor>>,
-}
+        if let (Some(detector), Some(sequence)) = (&self.gap_detector, event.sequence_number) {
+            detector.record(event.aggregate_id, sequence).await;
+        }
+        Some(outcome)
+    }
 
-impl CdcProcessor {
-    pub fn new(session: Arc<Session>, redpanda: Arc<RedpandaClient>, dlq_actor: Option<ActorRef<DlqActor>>) -> Self {
-        Self { session, redpanda, dlq_actor }
+    /// Schedule an event whose publish_after is still ahead
+    ///
+    /// Returns None when the event is due (or was never scheduled). A held
+    /// event counts as handled for gap detection, like a parked one.
+    async fn hold_until_due(&self, scheduler: &dyn ScheduleSink, event: &OutboxEvent) -> Option<PublishOutcome> {
+        let publish_after = event.publish_after.filter(|at| *at > Utc::now())?;
+        let publication = ScheduledPublication {
+            due_bucket: due_bucket(publish_after),
+            id: event.id,
+            outbox_table: self.source.clone(),
+            aggregate_id: event.aggregate_id,
+            aggregate_type: event.aggregate_type.clone(),
+            event_id: event.event_id,
+            event_version: event.event_version,
+            correlation_id: event.correlation_id,
+            causation_id: event.causation_id,
+            sequence_number: event.sequence_number,
+            event_type: event.event_type.clone(),
+            aggregate_event_type: event.aggregate_event_type.clone(),
+            payload: event.stored_payload().to_string(),
+            partition_key: event.partition_key.clone(),
+            origin_region: event.origin_region.clone(),
+            publish_after,
+            scheduled_at: Utc::now(),
+        };
+        let outcome = match scheduler.schedule(&publication).await {
+            Ok(()) => {
+                tracing::info!(
+                    event_id = %event.id,
+                    event_type = %event.event_type,
+                    publish_after = %publish_after,
+                    "⏰ Event scheduled for later publication"
+                );
+                PublishOutcome::Scheduled
+            }
+            Err(e) => self.dead_letter_held(event, "Outbox scheduler", e).await,
+        };
+
+        if let (Some(detector), Some(sequence)) = (&self.gap_detector, event.sequence_number) {
+            detector.record(event.aggregate_id, sequence).await;
+        }
+        Some(outcome)
     }
 
-    /// Start the CDC log reader
-    /// This will continuously stream changes from the CDC log
-    pub async fn start_cdc_streaming(&self) -> anyhow::Result<()> {
-        tracing::info!("🔄 Starting CDC streaming for outbox_messages table");
-        tracing::info!("📊 This uses real ScyllaDB CDC streams with retry and DLQ!");
+    /// Bring the payload of an event stored at an older version up to date
+    ///
+    /// Rows without a version (legacy) and current ones are left alone.
+    fn upcast(&self, event: &mut OutboxEvent) -> anyhow::Result<()> {
+        let (Some(upcasters), Some(version)) = (&self.upcasters, event.event_version) else {
+            return Ok(());
+        };
+        if !upcasters.is_outdated(&event.event_type, version) {
+            return Ok(());
+        }
+        let payload = serde_json::from_str(&event.payload)?;
+        let (payload, latest) = upcasters.upcast(&event.event_type, version, payload)?;
+        tracing::debug!(event_id = %event.id, event_type = %event.event_type, from_version = version, to_version = latest, "Upcast event");
+        event.payload = serde_json::to_string(&payload)?;
+        event.event_version = Some(latest);
+        Ok(())
+    }
 
-        let factory = Arc::new(OutboxConsumerFactory::new(self.redpanda.clone(), self.dlq_actor.clone()));
+    /// A held event whose `stage` failed must not be published
+    async fn dead_letter_held(&self, event: &OutboxEvent, stage: &str, error: anyhow::Error) -> PublishOutcome {
+        tracing::error!(
+            error = %error,
+            event_id = %event.id,
+            event_type = %event.event_type,
+            "❌ {} failed, sending event to DLQ",
+            stage
+        );
 
-        // Build the CDC log reader
-        // It will start reading from "now" and continue forever
-        let (_reader, handle) = CDCLogReaderBuilder::new()
-            .session(self.session.clone())
-            .keyspace(KEYSPACE)
-            .table_name(TABLE)
-            .consumer_factory(factory)
-            .build()
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to create CDC log reader: {}", e))?;
+        if let Some(ref dlq) = self.dlq {
+            let _ = dlq.dead_letter(AddToDlq {
+                id: event.id,
+                aggregate_id: event.aggregate_id,
+                event_type: event.event_type.clone(),
+                payload: event.stored_payload().to_string(),
+                error_message: format!("{}: {}", stage, error),
+                failure_count: 1,
+                first_failed_at: Utc::now(),
+                failure_context: None,
+            }).await;
+        }
+        PublishOutcome::DeadLettered
+    }
 
-        tracing::info!("✅ CDC log reader started successfully");
-        tracing::info!("🎯 Listening for changes to {}.{}", KEYSPACE, TABLE);
+    /// Publish copies to the topics of matching routing rules
+    ///
+    /// Best effort: the event already reached its primary topic, so a failed
+    /// copy is logged and counted but neither retried nor dead-lettered.
+    async fn route_copies(
+        &self,
+        routing: &RoutingRules,
+        event_type: &str,
+        key: &str,
+        payload: &str,
+        headers: &[(&str, &str)],
+    ) {
+        let decisions = routing.evaluate(&RoutedEvent { event_type, payload, headers });
 
-        // Spawn the handle to run in the background
-        tokio::spawn(async move {
-            match handle.await {
-                Ok(_) => {
-                    tracing::info!("CDC reader completed successfully");
+        for decision in decisions {
+            for topic in &decision.topics {
+                if decision.dry_run {
+                    tracing::info!(
+                        rule = %decision.rule,
+                        topic = %topic,
+                        event_type = %event_type,
+                        "🧪 Routing rule matched (dry run, not published)"
+                    );
+                    routing.record_routed(&decision.rule, topic, "dry_run");
+                    continue;
                 }
-                Err(e) => {
-                    tracing::error!(error = %e, "CDC reader failed");
+
+                match self.publisher.publish_with_origin(topic, key, payload, headers, PublishOrigin::RoutedCopy).await {
+                    Ok(()) => {
+                        tracing::debug!(rule = %decision.rule, topic = %topic, "Routed event copy");
+                        routing.record_routed(&decision.rule, topic, "published");
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            rule = %decision.rule,
+                            topic = %topic,
+                            error = %e,
+                            "Failed to route event copy"
+                        );
+                        routing.record_routed(&decision.rule, topic, "failed");
+                    }
                 }
             }
-        });
+        }
+    }
+}
+
+#[async_trait]
+impl Consumer for OutboxCDCConsumer {
+    async fn consume_cdc(&mut self, data: CDCRow<'_>) -> anyhow::Result<()> {
+        tracing::debug!(
+            stream_id = ?data.stream_id,
+            operation = %data.operation,
+            "Received CDC row"
+        );
+
+        // Shutting down: leave the row to the next start (the checkpoint
+        // does not advance past it)
+        let in_flight = match self.drain {
+            Some(ref drain) => match drain.enter() {
+                Some(guard) => Some(guard),
+                None => return Ok(()),
+            },
+            None => None,
+        };
 
+        let event_id = self.consume_row(&data, in_flight).await?;
+
+        if data.end_of_batch {
+            // The checkpoint must not pass rows still being published
+            self.flush().await;
+            if let (Some(checkpoint), Some(time)) = (&self.checkpoint, cdc_time(data.time)) {
+                checkpoint.advance(time, event_id);
+            }
+        }
         Ok(())
     }
 }
 
-impl Actor for CdcProcessor {
-    type Args = Self;
-    type Error = Infallible;
+impl OutboxCDCConsumer {
+    /// Relay one outbox row, inline or on the publish pool
+    ///
+    /// Shared by the CDC consumer and the OutboxPoller; returns the event
+    /// key of the row, None for changes without an event.
+    pub(crate) async fn consume_row(
+        &self,
+        row: &(impl OutboxRow + Sync),
+        in_flight: Option<InFlightGuard>,
+    ) -> anyhow::Result<Option<Uuid>> {
+        if let Some(ref throttle) = self.throttle {
+            throttle.pace().await;
+        }
 
-    async fn on_start(
-        state: Self::Args,
-        _actor_ref: ActorRef<Self>
-    ) -> Result<Self, Self::Error> {
-        tracing::info!("CdcProcessor actor started");
+        let event = self.extract_event(row)?;
+        // Watchers see every committed event, whether this region relays it or not
+        if let (Some(subscriptions), Some(envelope)) = (
+            &self.subscriptions,
+            event.as_ref().and_then(|event| event.envelope(event.origin_region.as_deref())),
+        ) {
+            subscriptions.publish(envelope);
+        }
+        let event_id = event.as_ref().map(OutboxEvent::event_key);
+        match (&self.pool, event) {
+            (Some(pool), Some(event)) => {
+                // Same worker for all events of an aggregate keeps their order
+                pool.submit(event.aggregate_id, PublishJob { event, _in_flight: in_flight }).await?;
+            }
+            (_, event) => {
+                let outcome = self.relay(event).await;
+                self.metrics.record_cdc_row(&self.source, outcome.map_or("skipped", PublishOutcome::as_str));
+            }
+        }
+        Ok(event_id)
+    }
 
-        let session = state.session.clone();
-        let redpanda = state.redpanda.clone();
-        let dlq_actor = state.dlq_actor.clone();
+    /// Relay a row read back from event_store for a sequence gap, inline
+    ///
+    /// Goes through the same region filter, upcasting, approval and
+    /// published_events check as a relayed row; the lag is not reported,
+    /// the event is old by design.
+    pub(crate) async fn republish_row(&self, row: &impl OutboxRow) -> anyhow::Result<Option<PublishOutcome>> {
+        let Some(event) = self.extract_event(row)? else {
+            return Ok(None);
+        };
+        let span = event.span();
+        Ok(self.relay_event(event).instrument(span).await)
+    }
 
-        tokio::spawn(async move {
-            let processor = CdcProcessor::new(session, redpanda, dlq_actor);
-            if let Err(e) = processor.start_cdc_streaming().await {
-                tracing::error!("Failed to start CDC streaming: {}", e);
-            }
-        });
+    /// Wait for the rows handed to the publish pool
+    pub(crate) async fn flush(&self) {
+        if let Some(ref pool) = self.pool {
+            pool.flush().await;
+        }
+    }
+}
+
+/// Factory for creating consumer instances
+/// The scylla-cdc library will create one consumer per VNode group
+pub(crate) struct OutboxConsumerFactory {
+    publisher: Arc<dyn EventPublisher>,
+    dlq: Option<Arc<dyn DeadLetterSink>>,
+    gap_detector: Option<Arc<SequenceGapDetector>>,
+    stream_auditor: Option<Arc<StreamAuditor>>,
+    region: Option<Arc<RegionConfig>>,
+    routing: Option<Arc<RoutingRules>>,
+    key_strategy: KeyStrategy,
+    throttle: Option<Arc<CdcThrottle>>,
+    approval_gate: Option<Arc<ApprovalGate>>,
+    checkpoint: Option<Arc<CdcCheckpoint>>,
+    drain: Option<Arc<RelayDrain>>,
+    published_events: Option<Arc<PublishedEvents>>,
+    publish_marker: Option<Arc<PublishMarker>>,
+    payload_format: PayloadFormat,
+    publish_pool: PublishPoolConfig,
+    subscriptions: Option<Arc<EventSubscriptions>>,
+    crypto: Option<Arc<dyn EventCrypto>>,
+    metrics: MetricsHandle,
+    source: CdcSource,
+    lag: Option<Arc<CdcLag>>,
+    scheduler: Option<Arc<dyn ScheduleSink>>,
+    upcasters: Option<Arc<UpcasterRegistry>>,
+    retry_config: RetryConfig,
+}
+
+impl OutboxConsumerFactory {
+    pub fn new(publisher: Arc<dyn EventPublisher>, dlq: Option<Arc<dyn DeadLetterSink>>) -> Self {
+        Self {
+            publisher,
+            dlq,
+            gap_detector: None,
+            stream_auditor: None,
+            region: None,
+            routing: None,
+            key_strategy: KeyStrategy::default(),
+            throttle: None,
+            approval_gate: None,
+            checkpoint: None,
+            drain: None,
+            published_events: None,
+            publish_marker: None,
+            payload_format: PayloadFormat::default(),
+            publish_pool: PublishPoolConfig::default(),
+            subscriptions: None,
+            crypto: None,
+            metrics: MetricsHandle::noop(),
+            source: CdcSource::default(),
+            lag: None,
+            scheduler: None,
+            upcasters: None,
+            retry_config: RetryConfig::aggressive(),
+        }
+    }
+
+    pub fn with_retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.retry_config = retry_config;
+        self
+    }
+
+    pub fn with_key_strategy(mut self, key_strategy: KeyStrategy) -> Self {
+        self.key_strategy = key_strategy;
+        self
+    }
+
+    pub fn with_routing(mut self, routing: Arc<RoutingRules>) -> Self {
+        self.routing = Some(routing);
+        self
+    }
+
+    pub fn with_region(mut self, region: Arc<RegionConfig>) -> Self {
+        self.region = Some(region);
+        self
+    }
+
+    /// Share one gap detector across all consumers - rows of one aggregate
+    /// may arrive through different CDC streams
+    pub fn with_gap_detector(mut self, gap_detector: Arc<SequenceGapDetector>) -> Self {
+        self.gap_detector = Some(gap_detector);
+        self
+    }
+
+    pub fn with_stream_auditor(mut self, stream_auditor: Arc<StreamAuditor>) -> Self {
+        self.stream_auditor = Some(stream_auditor);
+        self
+    }
+
+    pub fn with_throttle(mut self, throttle: Arc<CdcThrottle>) -> Self {
+        self.throttle = Some(throttle);
+        self
+    }
+
+    pub fn with_approval_gate(mut self, approval_gate: Arc<ApprovalGate>) -> Self {
+        self.approval_gate = Some(approval_gate);
+        self
+    }
+
+    pub fn with_checkpoint(mut self, checkpoint: Arc<CdcCheckpoint>) -> Self {
+        self.checkpoint = Some(checkpoint);
+        self
+    }
+
+    pub fn with_drain(mut self, drain: Arc<RelayDrain>) -> Self {
+        self.drain = Some(drain);
+        self
+    }
+
+    pub fn with_published_events(mut self, published_events: Arc<PublishedEvents>) -> Self {
+        self.published_events = Some(published_events);
+        self
+    }
+
+    pub fn with_subscriptions(mut self, subscriptions: Arc<EventSubscriptions>) -> Self {
+        self.subscriptions = Some(subscriptions);
+        self
+    }
+
+    pub fn with_publish_marker(mut self, publish_marker: Arc<PublishMarker>) -> Self {
+        self.publish_marker = Some(publish_marker);
+        self
+    }
+
+    pub fn with_payload_format(mut self, payload_format: PayloadFormat) -> Self {
+        self.payload_format = payload_format;
+        self
+    }
+
+    pub fn with_crypto(mut self, crypto: Arc<dyn EventCrypto>) -> Self {
+        self.crypto = Some(crypto);
+        self
+    }
+
+    /// One pool per consumer, i.e. per CDC stream (VNode group)
+    pub fn with_publish_pool(mut self, publish_pool: PublishPoolConfig) -> Self {
+        self.publish_pool = publish_pool;
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: MetricsHandle, source: CdcSource) -> Self {
+        self.metrics = metrics;
+        self.source = source;
+        self
+    }
+
+    pub fn with_lag(mut self, lag: Arc<CdcLag>) -> Self {
+        self.lag = Some(lag);
+        self
+    }
+
+    pub fn with_scheduler(mut self, scheduler: Arc<dyn ScheduleSink>) -> Self {
+        self.scheduler = Some(scheduler);
+        self
+    }
+
+    pub fn with_upcasters(mut self, upcasters: Arc<UpcasterRegistry>) -> Self {
+        self.upcasters = Some(upcasters);
+        self
+    }
+}
+
+impl OutboxConsumerFactory {
+    /// A consumer with the factory's configuration and its own publish pool
+    pub(crate) fn consumer(&self) -> OutboxCDCConsumer {
+        self.inline_consumer().with_publish_pool(self.publish_pool)
+    }
+
+    /// A consumer for republishing gap backfills: inline, and without the
+    /// publish marker - the outbox row of a missing event is gone
+    pub(crate) fn backfill_consumer(&self) -> OutboxCDCConsumer {
+        OutboxCDCConsumer { publish_marker: None, ..self.inline_consumer() }
+    }
+
+    fn inline_consumer(&self) -> OutboxCDCConsumer {
+        let mut consumer = OutboxCDCConsumer::new(self.publisher.clone(), self.dlq.clone())
+            .with_key_strategy(self.key_strategy)
+            .with_payload_format(self.payload_format)
+            .with_retry_config(self.retry_config.clone())
+            .with_metrics(self.metrics.clone(), &self.source);
+        if let Some(ref gap_detector) = self.gap_detector {
+            consumer = consumer.with_gap_detector(gap_detector.clone());
+        }
+        if let Some(ref stream_auditor) = self.stream_auditor {
+            consumer = consumer.with_stream_auditor(stream_auditor.clone());
+        }
+        if let Some(ref region) = self.region {
+            consumer = consumer.with_region(region.clone());
+        }
+        if let Some(ref routing) = self.routing {
+            consumer = consumer.with_routing(routing.clone());
+        }
+        if let Some(ref throttle) = self.throttle {
+            consumer = consumer.with_throttle(throttle.clone());
+        }
+        if let Some(ref approval_gate) = self.approval_gate {
+            consumer = consumer.with_approval_gate(approval_gate.clone());
+        }
+        if let Some(ref checkpoint) = self.checkpoint {
+            consumer = consumer.with_checkpoint(checkpoint.clone());
+        }
+        if let Some(ref drain) = self.drain {
+            consumer = consumer.with_drain(drain.clone());
+        }
+        if let Some(ref published_events) = self.published_events {
+            consumer = consumer.with_published_events(published_events.clone());
+        }
+        if let Some(ref publish_marker) = self.publish_marker {
+            consumer = consumer.with_publish_marker(publish_marker.clone());
+        }
+        if let Some(ref subscriptions) = self.subscriptions {
+            consumer = consumer.with_subscriptions(subscriptions.clone());
+        }
+        if let Some(ref crypto) = self.crypto {
+            consumer = consumer.with_crypto(crypto.clone());
+        }
+        if let Some(ref lag) = self.lag {
+            consumer = consumer.with_lag(lag.clone());
+        }
+        if let Some(ref scheduler) = self.scheduler {
+            consumer = consumer.with_scheduler(scheduler.clone());
+        }
+        if let Some(ref upcasters) = self.upcasters {
+            consumer = consumer.with_upcasters(upcasters.clone());
+        }
+        consumer
+    }
+}
+
+#[async_trait]
+impl ConsumerFactory for OutboxConsumerFactory {
+    async fn new_consumer(&self) -> Box<dyn Consumer> {
+        tracing::debug!("Creating new OutboxCDCConsumer instance");
+        Box::new(self.consumer())
+    }
+}
+
+// ============================================================================
+// CDC Processor Actor
+// ============================================================================
+
+/// How long a skipped sequence may stay missing before it is a confirmed gap
+const SEQUENCE_GAP_GRACE: std::time::Duration = std::time::Duration::from_secs(60);
+
+pub struct CdcProcessor {
+    session: Arc<Session>,
+    /// Prepared checkpoint statements, at the configured consistency
+    statements: Arc<StatementCache>,
+    redpanda: Arc<RedpandaClient>,
+    dlq: Option<PriorityMailbox<DlqActor>>,
+    gap_backfill: bool,
+    region: Option<Arc<RegionConfig>>,
+    routing: Option<Arc<RoutingRules>>,
+    key_strategy: KeyStrategy,
+    startup: Option<Arc<StartupSequencer>>,
+    throttle: Option<Arc<CdcThrottle>>,
+    approval_gate: Option<Arc<ApprovalGate>>,
+    event_shards: ShardLayout,
+    /// Tenant whose outbox this is; None for the shared outbox
+    tenant: Option<TenantContext>,
+    source: CdcSource,
+    mapping: CdcTopicMapping,
+    retry_config: RetryConfig,
+    metrics: MetricsHandle,
+    /// How long published events are remembered; None disables deduplication
+    dedup_ttl: Option<std::time::Duration>,
+    /// Set `published_at` on published outbox rows
+    mark_published: bool,
+    /// Message value of relayed outbox events
+    payload_format: PayloadFormat,
+    /// Concurrency of publishing outbox rows, per CDC stream
+    publish_pool: PublishPoolConfig,
+    /// In-process watchers of relayed outbox events
+    subscriptions: Option<Arc<EventSubscriptions>>,
+    /// Audit of the published streams, shared by all relays
+    stream_auditor: Option<Arc<StreamAuditor>>,
+    /// Opens payloads the event store sealed
+    crypto: Option<Arc<dyn EventCrypto>>,
+    /// Stream the CDC log or poll the outbox table
+    mode: CdcMode,
+    /// Pause between polls in polling mode
+    poll_interval: std::time::Duration,
+    /// Read the CDC log from here instead of "now"
+    start_from: Option<chrono::DateTime<Utc>>,
+    drain: Arc<RelayDrain>,
+    /// Where the reader's state is reported for health
+    liveness: Option<Arc<CdcLiveness>>,
+    /// Where the lag of relayed rows is reported for health
+    lag: Option<Arc<CdcLag>>,
+    /// Holds outbox rows whose publish_after is still ahead
+    scheduler: Option<Arc<OutboxScheduler>>,
+    /// Upcasts outbox payloads of older schema versions before publishing
+    upcasters: Option<Arc<UpcasterRegistry>>,
+    /// Set once streaming started (from the startup task)
+    stream: Arc<Mutex<Option<CdcStream>>>,
+    /// The actor, told when its reader exits (set by the startup task)
+    actor_ref: Option<WeakActorRef<CdcProcessor>>,
+}
+
+/// A running CDC log reader (or poller) and what shutdown needs to stop it
+pub(crate) struct CdcStream {
+    /// None when polling - the poller stops once the drain begins
+    reader: Option<CDCLogReader>,
+    task: tokio::task::JoinHandle<()>,
+    checkpoint: Arc<CdcCheckpoint>,
+}
+
+impl CdcProcessor {
+    pub fn new(session: Arc<Session>, redpanda: Arc<RedpandaClient>, dlq: Option<PriorityMailbox<DlqActor>>) -> Self {
+        Self {
+            statements: Arc::new(StatementCache::new(session.clone())),
+            session,
+            redpanda,
+            dlq,
+            gap_backfill: false,
+            region: None,
+            routing: None,
+            key_strategy: KeyStrategy::default(),
+            startup: None,
+            throttle: None,
+            approval_gate: None,
+            event_shards: ShardLayout::default(),
+            tenant: None,
+            source: CdcSource::default(),
+            mapping: CdcTopicMapping::Outbox,
+            retry_config: RetryConfig::aggressive(), // More retries for CDC events
+            metrics: MetricsHandle::noop(),
+            dedup_ttl: None,
+            mark_published: false,
+            payload_format: PayloadFormat::default(),
+            publish_pool: PublishPoolConfig::default(),
+            subscriptions: None,
+            stream_auditor: None,
+            crypto: None,
+            mode: CdcMode::default(),
+            poll_interval: std::time::Duration::from_secs(1),
+            start_from: None,
+            drain: Arc::new(RelayDrain::new()),
+            liveness: None,
+            lag: None,
+            scheduler: None,
+            upcasters: None,
+            stream: Arc::new(Mutex::new(None)),
+            actor_ref: None,
+        }
+    }
+
+    /// Keyspace and table whose CDC log is relayed
+    pub fn with_source(mut self, source: CdcSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// How rows become messages (default: an outbox)
+    pub fn with_mapping(mut self, mapping: CdcTopicMapping) -> Self {
+        self.mapping = mapping;
+        self
+    }
+
+    /// Retry of publishing each CDC event before it goes to the DLQ
+    pub fn with_retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.retry_config = retry_config;
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: MetricsHandle) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Skip events published within `ttl` (re-delivered outbox rows)
+    pub fn with_dedup_ttl(mut self, ttl: Option<std::time::Duration>) -> Self {
+        self.dedup_ttl = ttl;
+        self
+    }
+
+    /// Confirm publishes on the outbox rows (needed by the OutboxJanitor)
+    pub fn with_mark_published(mut self, enabled: bool) -> Self {
+        self.mark_published = enabled;
+        self
+    }
+
+    /// Publish outbox events alone (default) or as whole EventEnvelope JSON
+    pub fn with_payload_format(mut self, payload_format: PayloadFormat) -> Self {
+        self.payload_format = payload_format;
+        self
+    }
+
+    /// Publish outbox rows on a pool of workers per CDC stream (default: inline)
+    pub fn with_publish_pool(mut self, publish_pool: PublishPoolConfig) -> Self {
+        self.publish_pool = publish_pool;
+        self
+    }
+
+    /// Hand relayed outbox events to in-process subscribers of their aggregate
+    pub fn with_event_subscriptions(mut self, subscriptions: Option<Arc<EventSubscriptions>>) -> Self {
+        self.subscriptions = subscriptions;
+        self
+    }
+
+    /// Check the per-aggregate order of published events
+    pub(crate) fn with_stream_auditor(mut self, stream_auditor: Option<Arc<StreamAuditor>>) -> Self {
+        self.stream_auditor = stream_auditor;
+        self
+    }
+
+    /// Stream the CDC log (default) or poll the outbox table every `poll_interval`
+    pub fn with_mode(mut self, mode: CdcMode, poll_interval: std::time::Duration) -> Self {
+        self.mode = mode;
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Report whether the CDC log reader is running (cdc_reader health)
+    pub fn with_liveness(mut self, liveness: Option<Arc<CdcLiveness>>) -> Self {
+        self.liveness = liveness;
+        self
+    }
+
+    /// Report how far behind the writes the relay is (cdc_lag health)
+    pub fn with_lag(mut self, lag: Option<Arc<CdcLag>>) -> Self {
+        self.lag = lag;
+        self
+    }
+
+    /// Hold outbox rows until their publish_after (scheduled events)
+    pub fn with_scheduler(mut self, scheduler: Option<Arc<OutboxScheduler>>) -> Self {
+        self.scheduler = scheduler;
+        self
+    }
+
+    /// Publish outbox payloads at the latest schema version of their type
+    pub fn with_upcasters(mut self, upcasters: Option<Arc<UpcasterRegistry>>) -> Self {
+        self.upcasters = upcasters;
+        self
+    }
+
+    fn report_reader(&self, state: CdcReaderState) {
+        if let Some(ref liveness) = self.liveness {
+            liveness.set(&self.source.label(), state);
+        }
+    }
+
+    /// Hold back CDC consumption until its startup phase comes up
+    pub fn with_startup(mut self, startup: Option<Arc<StartupSequencer>>) -> Self {
+        self.startup = startup;
+        self
+    }
+
+    /// Back off CDC consumption while Scylla latencies/errors spike
+    pub fn with_throttle(mut self, throttle: Option<Arc<CdcThrottle>>) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    /// Kafka key of relayed events (default: aggregate_id for per-aggregate ordering)
+    pub fn with_key_strategy(mut self, key_strategy: KeyStrategy) -> Self {
+        self.key_strategy = key_strategy;
+        self
+    }
+
+    /// Fan out events to additional topics by routing rules
+    pub fn with_routing(mut self, routing: Option<Arc<RoutingRules>>) -> Self {
+        self.routing = routing;
+        self
+    }
+
+    /// Run region-aware: relay only local-origin events, optionally per-region topics
+    pub fn with_region(mut self, region: Option<Arc<RegionConfig>>) -> Self {
+        self.region = region;
+        self
+    }
+
+    /// Park configured event types until approved (admin API)
+    pub fn with_approval_gate(mut self, approval_gate: Option<Arc<ApprovalGate>>) -> Self {
+        self.approval_gate = approval_gate;
+        self
+    }
+
+    /// Decrypt sealed payloads before publishing them (see [encryption])
+    pub fn with_crypto(mut self, crypto: Option<Arc<dyn EventCrypto>>) -> Self {
+        self.crypto = crypto;
+        self
+    }
+
+    /// Republish confirmed sequence gaps from event_store automatically
+    pub fn with_gap_backfill(mut self, enabled: bool) -> Self {
+        self.gap_backfill = enabled;
+        self
+    }
+
+    /// Event tables gap backfill reads from (sharded event store)
+    pub fn with_event_shards(mut self, layout: ShardLayout) -> Self {
+        self.event_shards = layout;
+        self
+    }
+
+    /// Relay the outbox of `tenant`: own checkpoint, backfill from its event tables
+    pub fn with_tenant(mut self, tenant: Option<TenantContext>) -> Self {
+        self.tenant = tenant;
+        self
+    }
+
+    /// Statement cache (and its consistency levels) for the checkpoint
+    pub fn with_statement_cache(mut self, statements: Arc<StatementCache>) -> Self {
+        self.statements = statements;
+        self
+    }
+
+    /// Where the relay's consumers dead-letter failed publishes
+    fn dead_letters(&self) -> Option<Arc<dyn DeadLetterSink>> {
+        self.dlq.clone().map(|dlq| Arc::new(dlq) as Arc<dyn DeadLetterSink>)
+    }
+
+    /// Key of this relay's checkpoint; region-aware and tenant relays each have their own
+    fn consumer_id(&self) -> String {
+        let relay = match self.mapping {
+            CdcTopicMapping::Outbox => "outbox-relay",
+            CdcTopicMapping::Rows { .. } => "table-relay",
+        };
+        let mut consumer_id = match self.region {
+            Some(ref region) => format!("{}-{}", relay, region.region()),
+            None => relay.to_string(),
+        };
+        // Keyspace tenants share the outbox table name
+        if let Some(tenant_id) = self.tenant.as_ref().and_then(TenantContext::tenant_id) {
+            consumer_id = format!("{}@{}", consumer_id, tenant_id);
+        }
+        consumer_id
+    }
+
+    /// Consumers of an outbox table: events with gap detection, routing,
+    /// region filtering and approval
+    fn outbox_factory(&self) -> OutboxConsumerFactory {
+        let gap_detector = Arc::new(
+            SequenceGapDetector::new(SEQUENCE_GAP_GRACE)
+                .with_session(self.session.clone())
+                .with_metrics(self.metrics.clone()),
+        );
+
+        let mut factory = OutboxConsumerFactory::new(self.redpanda.clone(), self.dead_letters())
+            .with_gap_detector(gap_detector.clone())
+            .with_key_strategy(self.key_strategy)
+            .with_payload_format(self.payload_format)
+            .with_publish_pool(self.publish_pool)
+            .with_retry_config(self.retry_config.clone())
+            .with_metrics(self.metrics.clone(), self.source.clone());
+        if let Some(ref region) = self.region {
+            tracing::info!(region = %region.region(), "🌍 Relaying events that originated in this region");
+            factory = factory.with_region(region.clone());
+        }
+        if let Some(ref routing) = self.routing {
+            tracing::info!(rules = routing.len(), "🔀 Routing rules enabled");
+            factory = factory.with_routing(routing.clone());
+        }
+        if let Some(ref approval_gate) = self.approval_gate {
+            factory = factory.with_approval_gate(approval_gate.clone());
+        }
+        if let Some(ttl) = self.dedup_ttl {
+            factory = factory.with_published_events(Arc::new(PublishedEvents::new(self.session.clone(), ttl)));
+        }
+        if self.mark_published {
+            factory = factory.with_publish_marker(Arc::new(PublishMarker::new(self.session.clone(), &self.source)));
+        }
+        if let Some(ref subscriptions) = self.subscriptions {
+            factory = factory.with_subscriptions(subscriptions.clone());
+        }
+        if let Some(ref stream_auditor) = self.stream_auditor {
+            factory = factory.with_stream_auditor(stream_auditor.clone());
+        }
+        if let Some(ref crypto) = self.crypto {
+            factory = factory.with_crypto(crypto.clone());
+        }
+        if let Some(ref lag) = self.lag {
+            factory = factory.with_lag(lag.clone());
+        }
+        if let Some(ref scheduler) = self.scheduler {
+            factory = factory.with_scheduler(scheduler.clone());
+        }
+        if let Some(ref upcasters) = self.upcasters {
+            factory = factory.with_upcasters(upcasters.clone());
+        }
+
+        // Backfilled events are relayed like the outbox rows they replace
+        let backfill = self.gap_backfill.then(|| {
+            GapBackfill::new(self.statements.clone(), factory.backfill_consumer())
+                .with_shards(self.event_shards)
+                .with_tenant(self.tenant.clone().unwrap_or_default())
+        });
+        gap_detector.spawn_monitor(backfill);
+        factory
+    }
+
+    /// Start relaying in the configured mode
+    async fn start(&self) -> anyhow::Result<CdcStream> {
+        match self.mode {
+            CdcMode::Streaming => self.start_cdc_streaming().await,
+            CdcMode::Polling => self.start_outbox_polling().await,
+        }
+    }
+
+    /// The relay's checkpoint and where to resume reading from it
+    async fn restore_checkpoint(&self) -> (Arc<CdcCheckpoint>, Option<chrono::DateTime<Utc>>) {
+        // Resume where the previous run stopped; without a checkpoint (first
+        // run) read from start_from or "now"
+        let checkpoint = Arc::new(
+            CdcCheckpoint::new(self.statements.clone(), &self.consumer_id(), &self.source.table).with_drain(self.drain.clone()),
+        );
+        let resume_from = match checkpoint.restore().await {
+            Ok(resume_from) => resume_from,
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to restore CDC checkpoint - starting without it");
+                None
+            }
+        };
+        if let Some(resume_from) = resume_from {
+            tracing::info!(resume_from = %resume_from, "⏪ Resuming CDC relay from checkpoint");
+        }
+        checkpoint.clone().spawn_flusher();
+        (checkpoint, [resume_from, self.start_from].into_iter().flatten().min())
+    }
+
+    /// Start the CDC log reader
+    /// This will continuously stream changes from the CDC log
+    async fn start_cdc_streaming(&self) -> anyhow::Result<CdcStream> {
+        tracing::info!("🔄 Starting CDC streaming for {}", self.source.label());
+        tracing::info!("📊 This uses real ScyllaDB CDC streams with retry and DLQ!");
+
+        let (checkpoint, start_from) = self.restore_checkpoint().await;
+
+        let factory: Arc<dyn ConsumerFactory> = match self.mapping {
+            CdcTopicMapping::Outbox => {
+                let mut factory = self.outbox_factory();
+                if let Some(ref throttle) = self.throttle {
+                    factory = factory.with_throttle(throttle.clone());
+                }
+                Arc::new(factory.with_checkpoint(checkpoint.clone()).with_drain(self.drain.clone()))
+            }
+            CdcTopicMapping::Rows { ref topic, ref key_column, ref columns } => {
+                tracing::info!(topic = %topic, key_column = %key_column, "📋 Relaying row changes");
+                let relay = TableRelay::new(self.redpanda.clone(), self.dead_letters(), self.source.clone(), topic, key_column, columns)
+                    .with_retry_config(self.retry_config.clone())
+                    .with_metrics(self.metrics.clone());
+                let mut factory = TableConsumerFactory::new(relay).with_metrics(self.metrics.clone());
+                if let Some(ref throttle) = self.throttle {
+                    factory = factory.with_throttle(throttle.clone());
+                }
+                Arc::new(factory.with_checkpoint(checkpoint.clone()).with_drain(self.drain.clone()))
+            }
+        };
+
+        // Build the CDC log reader
+        // Each stream resumes from its saved position; streams without one
+        // from the checkpoint (or start_from, or "now"). Continues forever
+        let mut builder = CDCLogReaderBuilder::new()
+            .session(self.session.clone())
+            .keyspace(&self.source.keyspace)
+            .table_name(&self.source.table)
+            .consumer_factory(factory)
+            .checkpoint_saver(checkpoint.clone())
+            .should_load_progress(true)
+            .should_save_progress(true)
+            .pause_between_saves(FLUSH_INTERVAL);
+        if let Some(start_from) = start_from {
+            builder = builder.start_timestamp(chrono::Duration::milliseconds(start_from.timestamp_millis()));
+        }
+        let (reader, handle) = builder
+            .build()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to create CDC log reader: {}", e))?;
+
+        tracing::info!("✅ CDC log reader started successfully");
+        tracing::info!("🎯 Listening for changes to {}.{}", self.source.keyspace, self.source.table);
+
+        let task = self.spawn_reader(handle, checkpoint.clone());
+        Ok(CdcStream { reader: Some(reader), task, checkpoint })
+    }
+
+    /// Start polling the outbox table (where CDC streaming is unavailable)
+    async fn start_outbox_polling(&self) -> anyhow::Result<CdcStream> {
+        if self.mapping != CdcTopicMapping::Outbox {
+            anyhow::bail!("{} publishes row changes, which cannot be polled", self.source.label());
+        }
+        tracing::info!(
+            interval_ms = self.poll_interval.as_millis() as u64,
+            "🔄 Starting outbox polling for {}",
+            self.source.label()
+        );
+
+        let (checkpoint, start_from) = self.restore_checkpoint().await;
+        let mut factory = self.outbox_factory();
+        if let Some(ref throttle) = self.throttle {
+            factory = factory.with_throttle(throttle.clone());
+        }
+        let poller = OutboxPoller::new(
+            self.session.clone(),
+            self.source.clone(),
+            factory.consumer(),
+            checkpoint.clone(),
+            self.drain.clone(),
+            start_from.unwrap_or_else(Utc::now),
+        )
+        .with_interval(self.poll_interval)
+        .with_metrics(self.metrics.clone());
+
+        let task = self.spawn_reader(poller.run(), checkpoint.clone());
+        Ok(CdcStream { reader: None, task, checkpoint })
+    }
+
+    /// Run the reader in the background and hand its exit to the actor
+    fn spawn_reader<F, T, E>(&self, reader: F, checkpoint: Arc<CdcCheckpoint>) -> tokio::task::JoinHandle<()>
+    where
+        F: std::future::Future<Output = Result<T, E>> + Send + 'static,
+        T: Send + 'static,
+        E: std::fmt::Display + Send + 'static,
+    {
+        let liveness = self.liveness.clone();
+        let label = self.source.label();
+        let drain = self.drain.clone();
+        let actor_ref = self.actor_ref.clone();
+        let task = tokio::spawn(async move {
+            let state = match AssertUnwindSafe(reader).catch_unwind().await {
+                Ok(Ok(_)) => {
+                    tracing::info!("CDC reader completed successfully");
+                    CdcReaderState::Stopped
+                }
+                Ok(Err(e)) => {
+                    tracing::error!(error = %e, "CDC reader failed");
+                    CdcReaderState::Failed(e.to_string())
+                }
+                Err(_) => {
+                    tracing::error!("CDC reader panicked");
+                    CdcReaderState::Failed("CDC reader panicked".to_string())
+                }
+            };
+            if let Some(liveness) = liveness {
+                liveness.set(&label, state.clone());
+            }
+            if drain.is_draining() {
+                return;
+            }
+
+            // Not shut down: hand the failure to the actor (and its supervisor)
+            if let Err(e) = checkpoint.flush().await {
+                tracing::warn!(error = %e, "Failed to persist CDC checkpoint of the exited reader");
+            }
+            let error = match state {
+                CdcReaderState::Failed(error) => error,
+                _ => "reader stopped".to_string(),
+            };
+            if let Some(actor_ref) = actor_ref.and_then(|actor_ref| actor_ref.upgrade()) {
+                let _ = actor_ref.tell(CdcReaderExited { error }).send().await;
+            }
+        });
+        self.report_reader(CdcReaderState::Running);
+        task
+    }
+}
+
+impl Actor for CdcProcessor {
+    type Args = Self;
+    type Error = Infallible;
+
+    async fn on_start(
+        state: Self::Args,
+        actor_ref: ActorRef<Self>
+    ) -> Result<Self, Self::Error> {
+        tracing::info!("CdcProcessor actor started");
+        state.report_reader(CdcReaderState::Pending);
+
+        let session = state.session.clone();
+        let statements = state.statements.clone();
+        let redpanda = state.redpanda.clone();
+        let dlq = state.dlq.clone();
+        let gap_backfill = state.gap_backfill;
+        let region = state.region.clone();
+        let routing = state.routing.clone();
+        let key_strategy = state.key_strategy;
+        let startup = state.startup.clone();
+        let throttle = state.throttle.clone();
+        let approval_gate = state.approval_gate.clone();
+        let event_shards = state.event_shards;
+        let tenant = state.tenant.clone();
+        let source = state.source.clone();
+        let mapping = state.mapping.clone();
+        let retry_config = state.retry_config.clone();
+        let metrics = state.metrics.clone();
+        let dedup_ttl = state.dedup_ttl;
+        let mark_published = state.mark_published;
+        let payload_format = state.payload_format;
+        let publish_pool = state.publish_pool;
+        let subscriptions = state.subscriptions.clone();
+        let stream_auditor = state.stream_auditor.clone();
+        let (mode, poll_interval) = (state.mode, state.poll_interval);
+        let liveness = state.liveness.clone();
+        let lag = state.lag.clone();
+        let scheduler = state.scheduler.clone();
+        let upcasters = state.upcasters.clone();
+        let drain = state.drain.clone();
+        let stream = state.stream.clone();
+        let weak_ref = actor_ref.downgrade();
+        // A delayed start still relays everything written since the actor started
+        let started_at = Utc::now();
+
+        tokio::spawn(async move {
+            if let Some(ref startup) = startup {
+                startup.wait_turn(StartupPhase::CdcConsumption).await;
+            }
+            let mut processor = CdcProcessor::new(session, redpanda, dlq)
+                .with_statement_cache(statements)
+                .with_gap_backfill(gap_backfill)
+                .with_region(region)
+                .with_routing(routing)
+                .with_key_strategy(key_strategy)
+                .with_throttle(throttle)
+                .with_approval_gate(approval_gate)
+                .with_event_shards(event_shards)
+                .with_tenant(tenant)
+                .with_source(source)
+                .with_mapping(mapping)
+                .with_retry_config(retry_config)
+                .with_metrics(metrics)
+                .with_dedup_ttl(dedup_ttl)
+                .with_mark_published(mark_published)
+                .with_payload_format(payload_format)
+                .with_publish_pool(publish_pool)
+                .with_event_subscriptions(subscriptions)
+                .with_stream_auditor(stream_auditor)
+                .with_mode(mode, poll_interval)
+                .with_liveness(liveness)
+                .with_lag(lag)
+                .with_scheduler(scheduler)
+                .with_upcasters(upcasters);
+            if startup.is_some() {
+                processor.start_from = Some(started_at);
+            }
+            processor.drain = drain.clone();
+            processor.actor_ref = Some(weak_ref.clone());
+            if drain.is_draining() {
+                tracing::info!("Shutting down - CDC streaming not started");
+            } else {
+                match processor.start().await {
+                    Ok(started) => *stream.lock().unwrap() = Some(started),
+                    Err(e) => {
+                        tracing::error!("Failed to start CDC streaming: {}", e);
+                        processor.report_reader(CdcReaderState::Failed(e.to_string()));
+                        if let Some(actor_ref) = weak_ref.upgrade() {
+                            let _ = actor_ref.tell(CdcReaderExited { error: e.to_string() }).send().await;
+                        }
+                    }
+                }
+            }
+            // Streaming runs in the background from here (or failed and
+            // shows up in health) - release the next phase either way
+            if let Some(ref startup) = startup {
+                startup.complete(StartupPhase::CdcConsumption);
+            }
+        });
 
         Ok(state)
     }
 }
+
+// ============================================================================
+// Messages
+// ============================================================================
+
+/// The CDC reader failed to start, failed or stopped outside a drain
+struct CdcReaderExited {
+    error: String,
+}
+
+impl Message<CdcReaderExited> for CdcProcessor {
+    type Reply = Result<(), String>;
+
+    async fn handle(&mut self, msg: CdcReaderExited, _ctx: &mut Context<Self, Self::Reply>) -> Self::Reply {
+        if self.drain.is_draining() {
+            return Ok(());
+        }
+        // An error from a told message stops the actor as failed - the
+        // supervisor decides about restarting it
+        Err(format!("CDC reader of {} exited: {}", self.source.label(), msg.error))
+    }
+}
+
+/// Stop the CDC reader, wait for in-flight rows and flush the checkpoint
+pub struct DrainCdc;
+
+impl Message<DrainCdc> for CdcProcessor {
+    type Reply = ();
+
+    async fn handle(&mut self, _msg: DrainCdc, _ctx: &mut Context<Self, Self::Reply>) -> Self::Reply {
+        self.drain.begin();
+        let stream = self.stream.lock().unwrap().take();
+        let Some(mut stream) = stream else {
+            tracing::info!("CDC streaming not running - nothing to drain");
+            return;
+        };
+
+        if let Some(ref mut reader) = stream.reader {
+            reader.stop();
+        }
+        tracing::info!(in_flight = self.drain.in_flight(), "Draining in-flight CDC events...");
+        self.drain.drained().await;
+        // Rows after the last completed batch are read again on the next start
+        stream.task.abort();
+
+        if let Err(e) = stream.checkpoint.flush().await {
+            tracing::error!(error = %e, "Failed to persist CDC checkpoint on shutdown");
+        }
+        tracing::info!("✅ CDC relay drained");
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messaging::test_support::{FailingPublisher, RecordingPublisher};
+    use crate::messaging::REGION_HEADER;
+    use crate::actors::infrastructure::test_support::SyntheticOutboxRow;
+    use crate::test_support::RecordingDlq;
+    use scylla_cdc::consumer::OperationType;
+    use std::time::Duration;
+
+    fn fast_retry() -> RetryConfig {
+        RetryConfig {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            multiplier: 2.0,
+            ..RetryConfig::default()
+        }
+    }
+
+    fn outbox_event() -> OutboxEvent {
+        OutboxEvent {
+            id: Uuid::new_v4(),
+            aggregate_id: Uuid::new_v4(),
+            aggregate_type: Some("Order".to_string()),
+            event_id: Some(Uuid::new_v4()),
+            correlation_id: Some(Uuid::new_v4()),
+            causation_id: None,
+            sequence_number: Some(1),
+            event_version: Some(1),
+            event_type: "OrderCreated".to_string(),
+            aggregate_event_type: Some("OrderEvent".to_string()),
+            payload: r#"{"type":"Created"}"#.to_string(),
+            sealed_payload: None,
+            partition_key: None,
+            origin_region: None,
+            created_at: None,
+            publish_after: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_event_success() {
+        let publisher = Arc::new(RecordingPublisher::new());
+        let consumer = OutboxCDCConsumer::new(publisher.clone(), None)
+            .with_retry_config(fast_retry());
+
+        let event = outbox_event();
+        let aggregate_id = event.aggregate_id;
+
+        let outcome = consumer.publish_event(event).await;
+
+        assert_eq!(outcome, PublishOutcome::Published);
+        let published = publisher.published();
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].topic, "OrderCreated");
+        assert_eq!(published[0].origin, PublishOrigin::OutboxCdc);
+        // Keyed by aggregate so its events stay on one partition
+        assert_eq!(published[0].key, aggregate_id.to_string());
+        // Envelope metadata travels as headers
+        let headers = EnvelopeHeaders::from_pairs(published[0].headers.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+        assert_eq!(headers.aggregate_id, Some(aggregate_id));
+        assert_eq!(headers.sequence_number, Some(1));
+        assert_eq!(headers.event_type.as_deref(), Some("OrderCreated"));
+        assert_eq!(headers.event_version, Some(1));
+        assert_eq!(headers.payload_format, None);
+        assert_eq!(published[0].payload, r#"{"type":"Created"}"#);
+    }
+
+    #[tokio::test]
+    async fn test_envelope_payload_round_trips() {
+        use crate::domain::order::OrderEvent;
+        use crate::messaging::decode_envelope;
+
+        let publisher = Arc::new(RecordingPublisher::new());
+        let consumer = OutboxCDCConsumer::new(publisher.clone(), None)
+            .with_retry_config(fast_retry())
+            .with_payload_format(PayloadFormat::Envelope);
+        let event = OutboxEvent {
+            sequence_number: Some(3),
+            event_type: "OrderCancelled".to_string(),
+            payload: r#"{"type":"Cancelled","data":{"reason":"changed mind","cancelled_by":null}}"#.to_string(),
+            origin_region: Some("eu-west".to_string()),
+            created_at: Some(Utc::now()),
+            ..outbox_event()
+        };
+
+        let (event_id, aggregate_id, correlation_id, created_at) =
+            (event.event_id, event.aggregate_id, event.correlation_id, event.created_at);
+
+        consumer.publish_event(event).await;
+
+        let published = publisher.published();
+        let headers = EnvelopeHeaders::from_pairs(published[0].headers.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+        assert_eq!(headers.payload_format, Some(PayloadFormat::Envelope));
+        let envelope = decode_envelope::<OrderEvent>(
+            &published[0].topic,
+            Some(&published[0].key),
+            published[0].payload.as_bytes(),
+            &headers,
+            None,
+        )
+        .unwrap();
+        assert_eq!(envelope.event_id, event_id.unwrap());
+        assert_eq!(envelope.aggregate_id, aggregate_id);
+        assert_eq!(envelope.sequence_number, 3);
+        assert_eq!(envelope.correlation_id, correlation_id.unwrap());
+        assert_eq!(envelope.timestamp, created_at.unwrap());
+        assert_eq!(envelope.origin_region(), Some("eu-west"));
+        assert!(matches!(envelope.event_data, OrderEvent::Cancelled(ref c) if c.reason.as_deref() == Some("changed mind")));
+
+        // Legacy rows have no envelope to publish
+        let legacy = OutboxEvent { event_id: None, ..outbox_event() };
+        assert!(legacy.envelope_json(None).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_events_of_one_aggregate_share_key() {
+        let publisher = Arc::new(RecordingPublisher::new());
+        let consumer = OutboxCDCConsumer::new(publisher.clone(), None)
+            .with_retry_config(fast_retry());
+        let aggregate_id = Uuid::new_v4();
+
+        for _ in 0..3 {
+            consumer.publish_event(OutboxEvent { aggregate_id, ..outbox_event() }).await;
+        }
+        consumer.publish_event(outbox_event()).await;
+
+        let keys: Vec<String> = publisher.published().into_iter().map(|m| m.key).collect();
+        assert!(keys[..3].iter().all(|k| *k == aggregate_id.to_string()));
+        assert_ne!(keys[3], keys[0]);
+    }
+
+    #[tokio::test]
+    async fn test_partition_key_strategy_uses_stored_key() {
+        let publisher = Arc::new(RecordingPublisher::new());
+        let consumer = OutboxCDCConsumer::new(publisher.clone(), None)
+            .with_retry_config(fast_retry())
+            .with_key_strategy(KeyStrategy::PartitionKey);
+
+        let event = OutboxEvent { partition_key: Some("customer-7".to_string()), ..outbox_event() };
+        consumer.publish_event(event).await;
+
+        assert_eq!(publisher.published()[0].key, "customer-7");
+    }
+
+    #[tokio::test]
+    async fn test_publish_event_retries_transient_failures() {
+        let publisher = Arc::new(FailingPublisher::failing_times(2));
+        let consumer = OutboxCDCConsumer::new(publisher.clone(), None)
+            .with_retry_config(fast_retry());
+
+        let outcome = consumer.publish_event(outbox_event()).await;
+
+        assert_eq!(outcome, PublishOutcome::Published);
+        assert_eq!(publisher.attempts(), 3);
+        assert_eq!(publisher.published().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_publish_event_dead_letters_after_retries() {
+        let publisher = Arc::new(FailingPublisher::always());
+        let consumer = OutboxCDCConsumer::new(publisher.clone(), None)
+            .with_retry_config(fast_retry());
+
+        let outcome = consumer.publish_event(outbox_event()).await;
+
+        assert_eq!(outcome, PublishOutcome::DeadLettered);
+        assert_eq!(publisher.attempts(), 3);
+        assert!(publisher.published().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dead_lettered_event_reaches_dlq_with_context() {
+        let dlq = Arc::new(RecordingDlq::new());
+        let consumer = OutboxCDCConsumer::new(Arc::new(FailingPublisher::always()), Some(dlq.clone() as Arc<dyn DeadLetterSink>))
+            .with_retry_config(fast_retry());
+        let event = outbox_event();
+        let (id, aggregate_id) = (event.id, event.aggregate_id);
+
+        assert_eq!(consumer.publish_event(event).await, PublishOutcome::DeadLettered);
+
+        let entries = dlq.dead_letters();
+        assert_eq!(entries.len(), 1);
+        assert_eq!((entries[0].id, entries[0].aggregate_id), (id, aggregate_id));
+        assert_eq!(entries[0].failure_count, 3);
+        let context = entries[0].failure_context.as_ref().expect("failure context");
+        assert_eq!(context.topic, "OrderCreated");
+        assert_eq!(context.attempts.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_region_routing_and_origin_filter() {
+        let publisher = Arc::new(RecordingPublisher::new());
+        let region = Arc::new(RegionConfig::new("eu-west").with_per_region_topics());
+        let consumer = OutboxCDCConsumer::new(publisher.clone(), None)
+            .with_retry_config(fast_retry())
+            .with_region(region);
+
+        let foreign = OutboxEvent { origin_region: Some("us-east".to_string()), ..outbox_event() };
+        assert!(!consumer.should_relay(&foreign));
+
+        let local = OutboxEvent { origin_region: Some("eu-west".to_string()), ..outbox_event() };
+        assert!(consumer.should_relay(&local));
+        consumer.publish_event(local).await;
+
+        let published = publisher.published();
+        assert_eq!(published[0].topic, "OrderCreated.eu-west");
+        assert!(published[0].headers.contains(&(REGION_HEADER.to_string(), "eu-west".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_routing_rules_copy_matching_events() {
+        let publisher = Arc::new(RecordingPublisher::new());
+        let routing = RoutingRules::from_json(r#"{"rules": [
+            {"name": "created", "event_types": ["OrderCreated"], "route_to": ["fraud-review"]},
+            {"name": "preview", "route_to": ["audit"], "dry_run": true}
+        ]}"#).unwrap();
+        let consumer = OutboxCDCConsumer::new(publisher.clone(), None)
+            .with_retry_config(fast_retry())
+            .with_routing(Arc::new(routing));
+
+        consumer.publish_event(outbox_event()).await;
+
+        let published: Vec<(String, PublishOrigin)> = publisher.published().into_iter().map(|m| (m.topic, m.origin)).collect();
+        // Primary topic plus the live rule; the dry-run rule publishes nothing
+        assert_eq!(
+            published,
+            vec![
+                ("OrderCreated".to_string(), PublishOrigin::OutboxCdc),
+                ("fraud-review".to_string(), PublishOrigin::RoutedCopy),
+            ]
+        );
+    }
+
+    // ------------------------------------------------------------------------
+    // Extraction and relay from synthetic CDC rows
+    // ------------------------------------------------------------------------
+
+    fn consumer(publisher: Arc<dyn EventPublisher>) -> OutboxCDCConsumer {
+        OutboxCDCConsumer::new(publisher, None).with_retry_config(fast_retry())
+    }
+
+    #[test]
+    fn test_extract_full_outbox_row() {
+        let consumer = consumer(Arc::new(RecordingPublisher::new()));
+        let row = SyntheticOutboxRow::outbox_event("OrderCreated", r#"{"type":"Created"}"#)
+            .bigint("sequence_number", 7)
+            .text("origin_region", "eu-west");
+
+        let event = consumer.extract_event(&row).unwrap().unwrap();
+
+        assert_eq!(event.id, row.get_uuid("id"));
+        assert_eq!(event.aggregate_id, row.get_uuid("aggregate_id"));
+        assert_eq!(event.aggregate_type.as_deref(), Some("Order"));
+        assert_eq!(event.event_type, "OrderCreated");
+        assert_eq!(event.aggregate_event_type.as_deref(), Some("OrderEvent"));
+        assert_eq!(event.sequence_number, Some(7));
+        assert_eq!(event.origin_region.as_deref(), Some("eu-west"));
+    }
+
+    #[test]
+    fn test_extract_legacy_row_without_optional_columns() {
+        let consumer = consumer(Arc::new(RecordingPublisher::new()));
+        let row = SyntheticOutboxRow::outbox_event("OrderCreated", "{}")
+            .without("sequence_number")
+            .without("aggregate_event_type");
+
+        let event = consumer.extract_event(&row).unwrap().unwrap();
+        assert_eq!(event.sequence_number, None);
+        assert_eq!(event.origin_region, None);
+        assert_eq!(event.aggregate_event_type, None);
+    }
+
+    #[test]
+    fn test_extract_opens_sealed_payload() {
+        use crate::event_sourcing::{seal_payload, AesGcmCrypto};
+
+        let crypto = Arc::new(AesGcmCrypto::new("k1", &[7; 32]).unwrap());
+        let sealed = seal_payload(crypto.as_ref(), r#"{"type":"Created"}"#).unwrap();
+        let row = SyntheticOutboxRow::outbox_event("OrderCreated", &sealed);
+
+        // Published decrypted, kept sealed for the DLQ
+        let event = consumer(Arc::new(RecordingPublisher::new())).with_crypto(crypto).extract_event(&row).unwrap().unwrap();
+        assert_eq!(event.payload, r#"{"type":"Created"}"#);
+        assert_eq!(event.stored_payload(), sealed);
+
+        // Without the key the row is not relayed
+        assert!(consumer(Arc::new(RecordingPublisher::new())).extract_event(&row).is_err());
+    }
+
+    #[test]
+    fn test_event_key_falls_back_to_row_id() {
+        let event = outbox_event();
+        assert_eq!(event.event_key(), event.event_id.unwrap());
+
+        let legacy = OutboxEvent { event_id: None, ..outbox_event() };
+        assert_eq!(legacy.event_key(), legacy.id);
+    }
+
+    #[test]
+    fn test_extract_rejects_missing_required_columns() {
+        let consumer = consumer(Arc::new(RecordingPublisher::new()));
+
+        for column in ["id", "aggregate_id", "event_type", "payload"] {
+            let row = SyntheticOutboxRow::outbox_event("OrderCreated", "{}").without(column);
+            let error = consumer.extract_event(&row).unwrap_err();
+            assert!(error.to_string().contains(column), "column {}", column);
+        }
+    }
+
+    #[test]
+    fn test_extract_ignores_non_insert_operations() {
+        let consumer = consumer(Arc::new(RecordingPublisher::new()));
+
+        for operation in [OperationType::RowUpdate, OperationType::RowDelete, OperationType::PreImage] {
+            let row = SyntheticOutboxRow::with_operation(operation)
+                .uuid("id", Uuid::new_v4())
+                .text("event_type", "OrderCreated");
+            assert!(consumer.extract_event(&row).unwrap().is_none());
+        }
+
+        let post_image = SyntheticOutboxRow::with_operation(OperationType::PostImage)
+            .uuid("id", Uuid::new_v4())
+            .uuid("aggregate_id", Uuid::new_v4())
+            .text("event_type", "OrderCreated")
+            .text("payload", "{}");
+        assert!(consumer.extract_event(&post_image).unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_row_relayed_with_retry() {
+        let publisher = Arc::new(FailingPublisher::failing_times(1));
+        let consumer = consumer(publisher.clone());
+        let row = SyntheticOutboxRow::outbox_event("OrderShipped", r#"{"type":"Shipped"}"#);
+
+        let outcome = consumer.relay(consumer.extract_event(&row).unwrap()).await;
+
+        assert_eq!(outcome, Some(PublishOutcome::Published));
+        assert_eq!(publisher.attempts(), 2);
+        assert_eq!(publisher.published()[0].key, row.get_uuid("aggregate_id").to_string());
+    }
+
+    #[tokio::test]
+    async fn test_row_dead_lettered_when_broker_down() {
+        let publisher = Arc::new(FailingPublisher::always());
+        let consumer = consumer(publisher.clone());
+        let row = SyntheticOutboxRow::outbox_event("OrderShipped", "{}");
+
+        let outcome = consumer.relay(consumer.extract_event(&row).unwrap()).await;
+
+        assert_eq!(outcome, Some(PublishOutcome::DeadLettered));
+        assert_eq!(publisher.attempts(), fast_retry().max_attempts);
+    }
+
+    #[tokio::test]
+    async fn test_foreign_region_row_not_relayed() {
+        let publisher = Arc::new(RecordingPublisher::new());
+        let consumer = consumer(publisher.clone()).with_region(Arc::new(RegionConfig::new("eu-west")));
+        let row = SyntheticOutboxRow::outbox_event("OrderCreated", "{}").text("origin_region", "us-east");
+
+        assert_eq!(consumer.relay(consumer.extract_event(&row).unwrap()).await, None);
+        assert_eq!(publisher.count(), 0);
+    }
+}
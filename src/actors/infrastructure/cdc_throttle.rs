@@ -0,0 +1,266 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::db::{LatencyMonitor, LatencySnapshot};
+use crate::metrics::MetricsHandle;
+
+// ============================================================================
+// CDC Throttle - Adaptive Backoff Under Scylla Stress
+// ============================================================================
+//
+// The CDC relay reads the outbox log tables as fast as scylla-cdc serves
+// them. While the cluster is already struggling (read latencies spiking,
+// queries failing) that extra read load makes things worse, so the relay
+// backs off on its own:
+//
+// - The LatencyMonitor collects latency/outcome of our own Scylla queries
+// - `evaluate` (every `evaluate_interval`, driven by the coordinator) checks
+//   the window against the thresholds:
+//     stressed → delay starts at `initial_delay`, doubles up to `max_delay`
+//     healthy  → delay halves, back to 0 once below `initial_delay`
+// - The outbox consumer `pace`s each CDC row by the current delay; scylla-cdc
+//   doesn't fetch a stream's next window before its rows are consumed, so
+//   the delay also slows down the log table reads
+//
+// Nothing is skipped - throttling only stretches the relay lag, which the
+// staleness SLAs surface.
+//
+// State is reported as the `cdc_throttle` health component (Degraded while
+// throttled) and via the cdc_throttle_delay_seconds, scylla_latency_p95_seconds
+// and scylla_error_rate gauges.
+//
+// Configuration (env):
+//   CDC_THROTTLE_P95_MS        p95 latency threshold (default 250)
+//   CDC_THROTTLE_ERROR_RATE    error rate threshold (default 0.05)
+//   CDC_THROTTLE_MAX_DELAY_MS  (default 1000)
+//
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CdcThrottleConfig {
+    /// p95 query latency above which the relay backs off
+    pub latency_threshold: Duration,
+    /// Share of failed queries above which the relay backs off
+    pub error_rate_threshold: f64,
+    /// Fewer samples than this never throttle (not enough signal)
+    pub min_samples: usize,
+    /// First delay once stressed
+    pub initial_delay: Duration,
+    /// Upper bound of the delay
+    pub max_delay: Duration,
+    /// How often the throttle re-evaluates
+    pub evaluate_interval: Duration,
+}
+
+impl Default for CdcThrottleConfig {
+    fn default() -> Self {
+        Self {
+            latency_threshold: Duration::from_millis(250),
+            error_rate_threshold: 0.05,
+            min_samples: 20,
+            initial_delay: Duration::from_millis(20),
+            max_delay: Duration::from_secs(1),
+            evaluate_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+impl CdcThrottleConfig {
+    pub fn from_env() -> anyhow::Result<Self> {
+        let mut config = Self::default();
+
+        if let Ok(ms) = std::env::var("CDC_THROTTLE_P95_MS") {
+            config.latency_threshold = Duration::from_millis(ms.parse()?);
+        }
+        if let Ok(rate) = std::env::var("CDC_THROTTLE_ERROR_RATE") {
+            config.error_rate_threshold = rate.parse()?;
+        }
+        if let Ok(ms) = std::env::var("CDC_THROTTLE_MAX_DELAY_MS") {
+            config.max_delay = Duration::from_millis(ms.parse()?);
+        }
+
+        Ok(config)
+    }
+
+    /// Why the window counts as stressed, if it does
+    fn stress_reason(&self, snapshot: &LatencySnapshot) -> Option<String> {
+        if snapshot.samples < self.min_samples {
+            return None;
+        }
+        if snapshot.p95 > self.latency_threshold {
+            return Some(format!(
+                "Scylla p95 {}ms above {}ms",
+                snapshot.p95.as_millis(),
+                self.latency_threshold.as_millis()
+            ));
+        }
+        if snapshot.error_rate > self.error_rate_threshold {
+            return Some(format!(
+                "Scylla error rate {:.1}% above {:.1}%",
+                snapshot.error_rate * 100.0,
+                self.error_rate_threshold * 100.0
+            ));
+        }
+        None
+    }
+
+    /// Next delay given the current one
+    fn step(&self, current: Duration, stressed: bool) -> Duration {
+        if stressed {
+            if current.is_zero() {
+                self.initial_delay
+            } else {
+                (current * 2).min(self.max_delay)
+            }
+        } else {
+            let halved = current / 2;
+            if halved < self.initial_delay {
+                Duration::ZERO
+            } else {
+                halved
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ThrottleState {
+    pub throttled: bool,
+    pub delay_ms: u64,
+    pub p95_ms: u64,
+    pub error_rate: f64,
+    pub samples: usize,
+    /// Why the relay is backing off (None when healthy)
+    pub reason: Option<String>,
+    /// When the current throttling episode started
+    pub since: Option<DateTime<Utc>>,
+}
+
+pub struct CdcThrottle {
+    config: CdcThrottleConfig,
+    monitor: Arc<LatencyMonitor>,
+    delay_ms: AtomicU64,
+    state: Mutex<ThrottleState>,
+    metrics: MetricsHandle,
+}
+
+impl CdcThrottle {
+    pub fn new(config: CdcThrottleConfig, monitor: Arc<LatencyMonitor>) -> Self {
+        Self {
+            config,
+            monitor,
+            delay_ms: AtomicU64::new(0),
+            state: Mutex::new(ThrottleState::default()),
+            metrics: MetricsHandle::default(),
+        }
+    }
+
+    pub fn with_metrics(mut self, metrics: MetricsHandle) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    pub fn config(&self) -> &CdcThrottleConfig {
+        &self.config
+    }
+
+    /// Re-evaluate the monitor window and adjust the delay
+    pub fn evaluate(&self) -> ThrottleState {
+        self.apply(self.monitor.snapshot())
+    }
+
+    fn apply(&self, snapshot: LatencySnapshot) -> ThrottleState {
+        let reason = self.config.stress_reason(&snapshot);
+        let delay = self.config.step(self.delay(), reason.is_some());
+        self.delay_ms.store(delay.as_millis() as u64, Ordering::Relaxed);
+
+        let mut state = self.state.lock().unwrap();
+        let throttled = !delay.is_zero();
+        match (state.throttled, throttled) {
+            (false, true) => {
+                tracing::warn!(
+                    reason = reason.as_deref().unwrap_or_default(),
+                    delay_ms = delay.as_millis() as u64,
+                    "Throttling CDC consumption"
+                );
+                state.since = Some(Utc::now());
+            }
+            (true, false) => {
+                tracing::info!("Scylla recovered, CDC consumption back to full speed");
+                state.since = None;
+            }
+            _ => {}
+        }
+
+        state.throttled = throttled;
+        state.delay_ms = delay.as_millis() as u64;
+        state.p95_ms = snapshot.p95.as_millis() as u64;
+        state.error_rate = snapshot.error_rate;
+        state.samples = snapshot.samples;
+        // Keep the last reason while winding down, so health shows why
+        if reason.is_some() || !throttled {
+            state.reason = reason;
+        }
+
+        self.metrics.record_cdc_throttle(delay.as_secs_f64(), snapshot.p95.as_secs_f64(), snapshot.error_rate);
+        state.clone()
+    }
+
+    /// Current delay per CDC row
+    pub fn delay(&self) -> Duration {
+        Duration::from_millis(self.delay_ms.load(Ordering::Relaxed))
+    }
+
+    /// Wait out the current delay before consuming a CDC row
+    pub async fn pace(&self) {
+        let delay = self.delay();
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(samples: usize, p95_ms: u64, error_rate: f64) -> LatencySnapshot {
+        LatencySnapshot { samples, p95: Duration::from_millis(p95_ms), error_rate }
+    }
+
+    #[test]
+    fn test_stress_detection() {
+        let config = CdcThrottleConfig::default();
+
+        assert!(config.stress_reason(&snapshot(100, 10, 0.0)).is_none());
+        assert!(config.stress_reason(&snapshot(100, 800, 0.0)).unwrap().contains("p95"));
+        assert!(config.stress_reason(&snapshot(100, 10, 0.2)).unwrap().contains("error rate"));
+        // Too few samples to judge
+        assert!(config.stress_reason(&snapshot(5, 800, 1.0)).is_none());
+    }
+
+    #[test]
+    fn test_backs_off_and_recovers() {
+        let throttle = CdcThrottle::new(CdcThrottleConfig::default(), Arc::new(LatencyMonitor::new(Duration::from_secs(30))));
+
+        let delays: Vec<u64> = (0..7).map(|_| throttle.apply(snapshot(100, 800, 0.0)).delay_ms).collect();
+        assert_eq!(delays, vec![20, 40, 80, 160, 320, 640, 1000]);
+        assert!(throttle.state.lock().unwrap().since.is_some());
+
+        let delays: Vec<u64> = (0..6).map(|_| throttle.apply(snapshot(100, 10, 0.0)).delay_ms).collect();
+        assert_eq!(delays, vec![500, 250, 125, 62, 31, 0]);
+
+        let state = throttle.state.lock().unwrap().clone();
+        assert!(!state.throttled);
+        assert!(state.since.is_none());
+        assert!(state.reason.is_none());
+        assert_eq!(throttle.delay(), Duration::ZERO);
+    }
+}
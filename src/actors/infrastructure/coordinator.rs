@@ -1,20 +1,28 @@
 use kameo::Actor;
 use kameo::message::{Context, Message};
-use kameo::actor::ActorRef;
-use kameo::error::Infallible;
+use kameo::actor::{ActorId, ActorRef, WeakActorRef};
+use kameo::error::{ActorStopReason, Infallible};
 use scylla::client::session::Session;
+use std::collections::HashMap;
+use std::ops::ControlFlow;
 use std::sync::Arc;
-use futures_util::task::SpawnExt;
-use crate::messaging::RedpandaClient;
-use crate::actors::core::HealthStatus;
-use super::{CdcProcessor, DlqActor, HealthMonitorActor, UpdateHealth, GetSystemHealth};
+use std::time::Duration;
+use crate::config::{CdcMode, CdcSource, CdcTable, CdcTopicMapping};
+use crate::messaging::{EventSubscriptions, KeyStrategy, PayloadFormat, RedpandaClient, RegionConfig, RoutingRules};
+use crate::db::{self, KeyspaceExpectations, StatementCache};
+use crate::event_sourcing::{EventCrypto, ShardLayout, UpcasterRegistry};
+use crate::metrics::MetricsHandle;
+use crate::utils::{RetryBudget, RetryConfig};
+use crate::actors::core::{HealthStatus, MessagePriority, PriorityMailbox, RestartDecision, RestartPolicy, RestartTracker};
+use super::stream_auditor::StreamAuditor;
+use super::{ApprovalGate, CdcLag, CdcLiveness, CdcProcessor, CdcThrottle, DlqActor, DlqQuarantinePolicy, DrainCdc, HealthHistory, HealthMonitorActor, HealthRegistry, HealthSnapshot, OutboxJanitor, OutboxRetention, OutboxScheduler, PublishPoolConfig, UpdateHealth, GetSystemHealth, StartupSequencer};
 
 // ============================================================================
 // Coordinator Actor - Orchestrates all system actors
 // ============================================================================
 //
 // Responsibilities:
-// - Manages lifecycle of child actors (CdcProcessor, DlqActor, HealthCheck)
+// - Manages lifecycle of child actors (CdcProcessors, DlqActor, HealthCheck)
 // - Implements supervision strategy
 // - Coordinates graceful shutdown
 // - Reports system health
@@ -22,28 +30,455 @@ use super::{CdcProcessor, DlqActor, HealthMonitorActor, UpdateHealth, GetSystemH
 //
 // Actor Hierarchy:
 //   CoordinatorActor (Supervisor)
-//   ├── CdcProcessor
+//   ├── CdcProcessor (one per observed table)
 //   ├── DlqActor
+//   ├── OutboxJanitor (with an outbox retention)
 //   └── HealthCheckActor
 //
+// Observed tables (AppConfig::cdc_tables): the outbox, the outbox of each
+// tenant ([[tenants]]), plus any [[cdc.tables]] - further outboxes or
+// read-model tables published as row changes. Each gets its own processor,
+// reader, checkpoint and consumers; tenant readers backfill gaps from their
+// tenant's event tables.
+//
+// Health reports go through a PriorityMailbox: lifecycle and supervision
+// reports as Critical messages, so they are never stuck behind the periodic
+// startup and throttle reports (Normal). The relays dead-letter through the
+// DLQ actor's PriorityMailbox (Normal); shutdown waits for it to drain
+// before stopping the DLQ actor.
+//
+// In CdcMode::Polling the processors poll their outbox tables instead of
+// streaming the CDC logs (outbox_poller.rs).
+//
+// With a StartupSequencer the CDC processor waits for its startup phase, and
+// startup progress is reported as the `startup` health component (Degraded
+// until every phase completed).
+//
+// With a CdcThrottle the coordinator re-evaluates it periodically and reports
+// it as the `cdc_throttle` health component (Degraded while backing off).
+//
+// With an OutboxRetention the outbox relays mark published rows and an
+// OutboxJanitor deletes them once the retention has passed.
+//
+// With an outbox schedule interval, one OutboxScheduler holds the rows all
+// outbox relays read before their `publish_after` and releases them once
+// due (outbox_scheduler.rs). Without one, such rows are published at once.
+//
+// With an UpcasterRegistry the outbox relays publish every payload at the
+// latest schema version of its event type.
+//
+// All outbox relays report their lag to one CdcLag, registered as the
+// `cdc_lag` health component (Degraded above `with_cdc_max_lag`).
+//
+// With a RetryBudget the publish retries of all CDC processors share it, so
+// an outage cannot be amplified by every relay retrying at full rate.
+//
+// With the stream audit enabled, one StreamAuditor checks what all outbox
+// relays publish (stream_auditor.rs); its positions are flushed on shutdown.
+//
+// With EventSubscriptions the outbox readers hand each event they read to
+// the in-process subscribers of its aggregate (messaging/subscriptions.rs).
+//
+// Supervision: every child is linked to the coordinator. A CDC processor
+// (whose reader failed or panicked) or the OutboxJanitor that dies is
+// restarted after an exponential backoff under the RestartPolicy, until it
+// failed `max_restarts` times in a row. Restarts are counted in
+// actor_restarts_total{actor,outcome} and reported as the `supervisor`
+// health component - Degraded while a restart is pending, Unhealthy once
+// the coordinator gave up on a child. The DLQ actor and the health monitor
+// are not restarted (the processors and the mailbox hold their refs); their
+// death is reported as Unhealthy.
+//
+// Shutdown drains before it stops: the CDC processor stops its reader and
+// waits for the events in flight (published or handed to the DLQ) and
+// flushes its checkpoint, then the DLQ stops gracefully, writing what it
+// still buffers. The caller bounds the whole sequence with a timeout
+// (system::ShutdownController).
+//
 // ============================================================================
 
 pub struct CoordinatorActor {
     session: Arc<Session>,
+    statements: Arc<StatementCache>,
     redpanda: Arc<RedpandaClient>,
-    cdc_processor: Option<ActorRef<CdcProcessor>>,
+    cdc_processors: Vec<ActorRef<CdcProcessor>>,
     health_monitor: Option<ActorRef<HealthMonitorActor>>,
+    health_mailbox: Option<PriorityMailbox<HealthMonitorActor>>,
+    health_history: Option<Arc<HealthHistory>>,
+    health_snapshot: Option<Arc<HealthSnapshot>>,
+    health_registry: Arc<HealthRegistry>,
     dlq_actor: Option<ActorRef<DlqActor>>,
+    /// Dead letters from the relays, behind the DLQ's own messages
+    dlq_mailbox: Option<PriorityMailbox<DlqActor>>,
+    keyspace_expectations: Option<KeyspaceExpectations>,
+    region: Option<Arc<RegionConfig>>,
+    routing: Option<Arc<RoutingRules>>,
+    startup: Option<Arc<StartupSequencer>>,
+    cdc_throttle: Option<Arc<CdcThrottle>>,
+    approval_gate: Option<Arc<ApprovalGate>>,
+    event_subscriptions: Option<Arc<EventSubscriptions>>,
+    stream_audit: bool,
+    stream_auditor: Option<Arc<StreamAuditor>>,
+    event_crypto: Option<Arc<dyn EventCrypto>>,
+    upcasters: Option<Arc<UpcasterRegistry>>,
+    event_shards: ShardLayout,
+    cdc_tables: Vec<CdcTable>,
+    cdc_mode: CdcMode,
+    cdc_poll_interval: Duration,
+    cdc_retry: RetryConfig,
+    retry_budget: Option<Arc<RetryBudget>>,
+    cdc_dedup_ttl: Option<Duration>,
+    cdc_payload_format: PayloadFormat,
+    cdc_key_strategy: KeyStrategy,
+    cdc_publish_pool: PublishPoolConfig,
+    outbox_retention: Option<OutboxRetention>,
+    outbox_schedule_interval: Option<Duration>,
+    outbox_scheduler: Option<Arc<OutboxScheduler>>,
+    dlq_quarantine: DlqQuarantinePolicy,
+    outbox_janitor: Option<ActorRef<OutboxJanitor>>,
+    cdc_liveness: Arc<CdcLiveness>,
+    cdc_lag: Arc<CdcLag>,
+    restart_policy: RestartPolicy,
+    restarts: HashMap<SupervisedChild, RestartTracker>,
+    /// Children that died for good, by label
+    abandoned: Vec<String>,
+    shutting_down: bool,
+    metrics: MetricsHandle,
+}
+
+/// A linked child of the coordinator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum SupervisedChild {
+    /// The processor of `cdc_tables[index]`
+    CdcProcessor(usize),
+    OutboxJanitor,
+    DlqActor,
+    HealthMonitor,
+}
+
+impl SupervisedChild {
+    /// `actor` label of actor_restarts_total
+    fn as_str(&self) -> &'static str {
+        match self {
+            SupervisedChild::CdcProcessor(_) => "cdc_processor",
+            SupervisedChild::OutboxJanitor => "outbox_janitor",
+            SupervisedChild::DlqActor => "dlq_actor",
+            SupervisedChild::HealthMonitor => "health_monitor",
+        }
+    }
 }
 
 impl CoordinatorActor {
     pub fn new(session: Arc<Session>, redpanda: Arc<RedpandaClient>) -> Self {
         Self {
+            statements: Arc::new(StatementCache::new(session.clone())),
             session,
             redpanda,
-            cdc_processor: None,
+            cdc_processors: Vec::new(),
             health_monitor: None,
+            health_mailbox: None,
+            health_history: None,
+            health_snapshot: None,
+            health_registry: Arc::new(HealthRegistry::new()),
             dlq_actor: None,
+            dlq_mailbox: None,
+            keyspace_expectations: None,
+            region: None,
+            routing: None,
+            startup: None,
+            cdc_throttle: None,
+            approval_gate: None,
+            event_subscriptions: None,
+            stream_audit: false,
+            stream_auditor: None,
+            event_crypto: None,
+            upcasters: None,
+            event_shards: ShardLayout::default(),
+            cdc_tables: vec![CdcTable::outbox(CdcSource::default())],
+            cdc_mode: CdcMode::default(),
+            cdc_poll_interval: Duration::from_secs(1),
+            cdc_retry: RetryConfig::aggressive(),
+            retry_budget: None,
+            cdc_dedup_ttl: None,
+            cdc_payload_format: PayloadFormat::default(),
+            cdc_key_strategy: KeyStrategy::default(),
+            cdc_publish_pool: PublishPoolConfig::default(),
+            outbox_retention: None,
+            outbox_schedule_interval: None,
+            outbox_scheduler: None,
+            dlq_quarantine: DlqQuarantinePolicy::default(),
+            outbox_janitor: None,
+            cdc_liveness: Arc::new(CdcLiveness::new()),
+            cdc_lag: Arc::new(CdcLag::new(Duration::from_secs(60))),
+            restart_policy: RestartPolicy::default(),
+            restarts: HashMap::new(),
+            abandoned: Vec::new(),
+            shutting_down: false,
+            metrics: MetricsHandle::noop(),
+        }
+    }
+
+    /// When failed child actors are restarted
+    pub fn with_restart_policy(mut self, policy: RestartPolicy) -> Self {
+        self.restart_policy = policy;
+        self
+    }
+
+    /// Verify keyspace replication/consistency on start and report it to health
+    pub fn with_keyspace_expectations(mut self, expectations: KeyspaceExpectations) -> Self {
+        self.keyspace_expectations = Some(expectations);
+        self
+    }
+
+    /// Run the CDC relay region-aware (active-active deployments)
+    pub fn with_region(mut self, region: RegionConfig) -> Self {
+        self.region = Some(Arc::new(region));
+        self
+    }
+
+    /// Route matching events to additional topics from the CDC relay
+    pub fn with_routing_rules(mut self, routing: RoutingRules) -> Self {
+        self.routing = Some(Arc::new(routing));
+        self
+    }
+
+    /// Handed down to the DLQ, CDC processor and health mailbox
+    pub fn with_metrics(mut self, metrics: MetricsHandle) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Stagger CDC consumption with the other startup phases
+    pub fn with_startup(mut self, startup: Arc<StartupSequencer>) -> Self {
+        self.startup = Some(startup);
+        self
+    }
+
+    /// Tables to run a CDC processor for
+    pub fn with_cdc_tables(mut self, tables: Vec<CdcTable>) -> Self {
+        self.cdc_tables = tables;
+        self
+    }
+
+    /// Stream the CDC logs or poll the outbox tables every `poll_interval`
+    pub fn with_cdc_mode(mut self, mode: CdcMode, poll_interval: Duration) -> Self {
+        self.cdc_mode = mode;
+        self.cdc_poll_interval = poll_interval;
+        self
+    }
+
+    /// Publish retry of the CDC processor
+    pub fn with_cdc_retry(mut self, retry_config: RetryConfig) -> Self {
+        self.cdc_retry = retry_config;
+        self
+    }
+
+    /// One budget for the publish retries of every CDC processor
+    pub fn with_retry_budget(mut self, budget: Arc<RetryBudget>) -> Self {
+        self.retry_budget = Some(budget);
+        self
+    }
+
+    /// Report the CDC relay degraded while it lags more than `max_lag`
+    pub fn with_cdc_max_lag(mut self, max_lag: Duration) -> Self {
+        self.cdc_lag = Arc::new(CdcLag::new(max_lag));
+        self
+    }
+
+    /// Skip re-delivered outbox events published within `ttl`
+    pub fn with_cdc_dedup_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.cdc_dedup_ttl = ttl;
+        self
+    }
+
+    /// Publish outbox events alone or as whole EventEnvelope JSON
+    pub fn with_cdc_payload_format(mut self, payload_format: PayloadFormat) -> Self {
+        self.cdc_payload_format = payload_format;
+        self
+    }
+
+    /// Kafka key of relayed events (and of gap backfills)
+    pub fn with_cdc_key_strategy(mut self, key_strategy: KeyStrategy) -> Self {
+        self.cdc_key_strategy = key_strategy;
+        self
+    }
+
+    /// Publish outbox rows concurrently per CDC stream, up to `publish_pool.workers`
+    pub fn with_cdc_publish_pool(mut self, publish_pool: PublishPoolConfig) -> Self {
+        self.cdc_publish_pool = publish_pool;
+        self
+    }
+
+    /// Reclaim published outbox rows after `retention.retention`
+    pub fn with_outbox_retention(mut self, retention: Option<OutboxRetention>) -> Self {
+        self.outbox_retention = retention;
+        self
+    }
+
+    /// Hold outbox rows until their publish_after, releasing due ones every `interval`
+    pub fn with_outbox_schedule_interval(mut self, interval: Option<Duration>) -> Self {
+        self.outbox_schedule_interval = interval;
+        self
+    }
+
+    /// When DLQ replays give up and DLQ entries count as aged
+    pub fn with_dlq_quarantine_policy(mut self, policy: DlqQuarantinePolicy) -> Self {
+        self.dlq_quarantine = policy;
+        self
+    }
+
+    /// Persist health transitions across restarts
+    pub fn with_health_history(mut self, history: Arc<HealthHistory>) -> Self {
+        self.health_history = Some(history);
+        self
+    }
+
+    /// Keep `snapshot` current with the system health (served by GET /health)
+    pub fn with_health_snapshot(mut self, snapshot: Arc<HealthSnapshot>) -> Self {
+        self.health_snapshot = Some(snapshot);
+        self
+    }
+
+    /// Components polled by the health monitor; the coordinator adds the CDC readers
+    pub fn with_health_registry(mut self, registry: Arc<HealthRegistry>) -> Self {
+        self.health_registry = registry;
+        self
+    }
+
+    /// Back off CDC consumption while Scylla is under stress
+    pub fn with_cdc_throttle(mut self, throttle: Arc<CdcThrottle>) -> Self {
+        self.cdc_throttle = Some(throttle);
+        self
+    }
+
+    /// Park sensitive event types in the CDC relay until approved
+    pub fn with_approval_gate(mut self, approval_gate: Arc<ApprovalGate>) -> Self {
+        self.approval_gate = Some(approval_gate);
+        self
+    }
+
+    /// Feed outbox events to in-process aggregate subscribers as they are read
+    pub fn with_event_subscriptions(mut self, subscriptions: Arc<EventSubscriptions>) -> Self {
+        self.event_subscriptions = Some(subscriptions);
+        self
+    }
+
+    /// Audit the per-aggregate order of published events (persisted in stream_audit_progress)
+    pub fn with_stream_audit(mut self, enabled: bool) -> Self {
+        self.stream_audit = enabled;
+        self
+    }
+
+    /// Keys of sealed event payloads, for the CDC relays and DLQ replay
+    pub fn with_event_crypto(mut self, crypto: Arc<dyn EventCrypto>) -> Self {
+        self.event_crypto = Some(crypto);
+        self
+    }
+
+    /// Upcasters of the domain events, for the CDC relays
+    pub fn with_upcasters(mut self, upcasters: Arc<UpcasterRegistry>) -> Self {
+        self.upcasters = Some(upcasters);
+        self
+    }
+
+    /// Event store shard layout, for gap backfill in the CDC processor
+    pub fn with_event_shards(mut self, layout: ShardLayout) -> Self {
+        self.event_shards = layout;
+        self
+    }
+
+    /// Statement cache shared with the DLQ and CDC checkpoints
+    pub fn with_statement_cache(mut self, statements: Arc<StatementCache>) -> Self {
+        self.statements = statements;
+        self
+    }
+
+    /// Spawn the CDC processor of `cdc_tables[index]`; a restarted one
+    /// resumes from its checkpoint without waiting for a startup phase
+    fn spawn_cdc_processor(&self, index: usize, restarted: bool) -> ActorRef<CdcProcessor> {
+        let table = &self.cdc_tables[index];
+        let startup = if restarted { None } else { self.startup.clone() };
+        CdcProcessor::spawn(
+            CdcProcessor::new(self.session.clone(), self.redpanda.clone(), self.dlq_mailbox.clone())
+                .with_region(self.region.clone())
+                .with_routing(self.routing.clone())
+                .with_startup(startup)
+                .with_throttle(self.cdc_throttle.clone())
+                .with_approval_gate(self.approval_gate.clone())
+                .with_event_subscriptions(self.event_subscriptions.clone())
+                .with_stream_auditor(self.stream_auditor.clone())
+                .with_crypto(self.event_crypto.clone())
+                .with_event_shards(self.event_shards)
+                .with_statement_cache(self.statements.clone())
+                .with_tenant(table.tenant.clone())
+                .with_source(table.source.clone())
+                .with_mapping(table.mapping.clone())
+                .with_mode(self.cdc_mode, self.cdc_poll_interval)
+                .with_retry_config(self.cdc_retry.clone())
+                .with_dedup_ttl(self.cdc_dedup_ttl)
+                .with_payload_format(self.cdc_payload_format)
+                .with_key_strategy(self.cdc_key_strategy)
+                .with_publish_pool(self.cdc_publish_pool)
+                .with_mark_published(self.outbox_retention.is_some())
+                .with_liveness(Some(self.cdc_liveness.clone()))
+                .with_lag(Some(self.cdc_lag.clone()))
+                .with_scheduler(self.outbox_scheduler.clone())
+                .with_upcasters(self.upcasters.clone())
+                .with_metrics(self.metrics.clone()),
+        )
+    }
+
+    /// Spawn the OutboxJanitor reclaiming published rows of the outbox tables
+    fn spawn_outbox_janitor(&self, retention: &OutboxRetention) -> ActorRef<OutboxJanitor> {
+        let outboxes: Vec<CdcSource> = self
+            .cdc_tables
+            .iter()
+            .filter(|table| table.mapping == CdcTopicMapping::Outbox)
+            .map(|table| table.source.clone())
+            .collect();
+        let janitor = OutboxJanitor::new(self.session.clone(), outboxes, retention.clone())
+            .with_metrics(self.metrics.clone());
+        OutboxJanitor::spawn(janitor)
+    }
+
+    /// Which child `id` is, if it is one
+    fn child(&self, id: ActorId) -> Option<SupervisedChild> {
+        if let Some(index) = self.cdc_processors.iter().position(|processor| processor.id() == id) {
+            return Some(SupervisedChild::CdcProcessor(index));
+        }
+        if self.outbox_janitor.as_ref().is_some_and(|janitor| janitor.id() == id) {
+            return Some(SupervisedChild::OutboxJanitor);
+        }
+        if self.dlq_actor.as_ref().is_some_and(|dlq| dlq.id() == id) {
+            return Some(SupervisedChild::DlqActor);
+        }
+        if self.health_monitor.as_ref().is_some_and(|monitor| monitor.id() == id) {
+            return Some(SupervisedChild::HealthMonitor);
+        }
+        None
+    }
+
+    /// Human-readable name of `child`, for logs and health details
+    fn child_label(&self, child: SupervisedChild) -> String {
+        match child {
+            SupervisedChild::CdcProcessor(index) => format!("cdc_processor[{}]", self.cdc_tables[index].source.label()),
+            other => other.as_str().to_string(),
+        }
+    }
+
+    /// Report the `supervisor` component: `status`, unless a child died for good
+    fn report_supervision(&self, status: HealthStatus, details: String) {
+        let status = if self.abandoned.is_empty() {
+            status
+        } else {
+            HealthStatus::Unhealthy(format!("Not restarted: {}", self.abandoned.join(", ")))
+        };
+        if let Some(ref health_mailbox) = self.health_mailbox {
+            health_mailbox.tell(UpdateHealth {
+                component: "supervisor".to_string(),
+                status,
+                details: Some(details),
+            }, MessagePriority::Critical);
         }
     }
 }
@@ -54,42 +489,182 @@ impl Actor for CoordinatorActor {
 
     async fn on_start(
         mut state: Self::Args,
-        _actor_ref: ActorRef<Self>
+        actor_ref: ActorRef<Self>
     ) -> Result<Self, Self::Error> {
         tracing::info!("🎯 CoordinatorActor started - Event Sourcing with CDC");
 
         // Start health monitor actor
-        let health_monitor = HealthMonitorActor::spawn(HealthMonitorActor::new(state.redpanda.clone()));
+        let mut health_monitor = HealthMonitorActor::new(state.redpanda.clone())
+            .with_session(state.session.clone())
+            .with_registry(state.health_registry.clone());
+        if let Some(history) = &state.health_history {
+            health_monitor = health_monitor.with_history(history.clone());
+        }
+        if let Some(snapshot) = &state.health_snapshot {
+            health_monitor = health_monitor.with_snapshot(snapshot.clone());
+        }
+        let health_monitor = HealthMonitorActor::spawn(health_monitor);
+        actor_ref.link(&health_monitor).await;
         state.health_monitor = Some(health_monitor.clone());
+        let health_mailbox = PriorityMailbox::spawn(health_monitor.clone(), "health_monitor", state.metrics.clone());
+        state.health_mailbox = Some(health_mailbox.clone());
+
+        // Verify keyspace replication matches what we expect in production
+        if let Some(ref expectations) = state.keyspace_expectations {
+            let (status, details) = match db::check_keyspace(&state.session, expectations).await {
+                Ok(report) => {
+                    let details = format!(
+                        "{} ({}, RF {})",
+                        report.keyspace, report.actual.strategy, report.actual.min_factor()
+                    );
+                    (report.health_status(), details)
+                }
+                Err(e) => (
+                    HealthStatus::Degraded(format!("Keyspace check failed: {}", e)),
+                    expectations.keyspace.clone(),
+                ),
+            };
+
+            health_mailbox.tell(UpdateHealth {
+                component: "keyspace".to_string(),
+                status,
+                details: Some(details),
+            }, MessagePriority::Critical);
+        }
 
         // Start DLQ actor
-        let dlq_actor = DlqActor::spawn(DlqActor::new(state.session.clone()));
+        let dlq_actor = DlqActor::spawn(
+            DlqActor::new(state.session.clone())
+                .with_statement_cache(state.statements.clone())
+                .with_metrics(state.metrics.clone())
+                .with_publisher(state.redpanda.clone())
+                .with_crypto(state.event_crypto.clone())
+                .with_quarantine_policy(state.dlq_quarantine.clone()),
+        );
+        actor_ref.link(&dlq_actor).await;
+        state.dlq_mailbox = Some(PriorityMailbox::spawn(dlq_actor.clone(), "dlq_actor", state.metrics.clone()));
         state.dlq_actor = Some(dlq_actor.clone());
 
         // Report DLQ actor health
-        let _ = health_monitor.tell(UpdateHealth {
+        health_mailbox.tell(UpdateHealth {
             component: "dlq_actor".to_string(),
             status: HealthStatus::Healthy,
             details: Some("DLQ actor started".to_string()),
-        }).send().await;
+        }, MessagePriority::Critical);
+
+        if state.stream_audit {
+            let stream_auditor = Arc::new(
+                StreamAuditor::new()
+                    .with_statement_cache(state.statements.clone())
+                    .with_metrics(state.metrics.clone()),
+            );
+            stream_auditor.clone().spawn_flusher();
+            state.stream_auditor = Some(stream_auditor);
+        }
 
-        // Start CDC stream processor with DLQ support
-        let cdc_processor = CdcProcessor::spawn(CdcProcessor::new(
-            state.session.clone(),
-            state.redpanda.clone(),
-            Some(dlq_actor.clone()),
-        ));
-        state.cdc_processor = Some(cdc_processor.clone());
+        // Release scheduled outbox events once due
+        if let Some(interval) = state.outbox_schedule_interval {
+            let scheduler = Arc::new(
+                OutboxScheduler::new(state.session.clone())
+                    .with_interval(interval)
+                    .with_metrics(state.metrics.clone()),
+            );
+            scheduler.clone().spawn_releaser();
+            state.outbox_scheduler = Some(scheduler);
+        }
+
+        // Retries of all relays draw from the same budget
+        if let Some(ref budget) = state.retry_budget {
+            state.cdc_retry = state.cdc_retry.clone().with_budget(budget.clone());
+        }
+
+        // Start a CDC stream processor with DLQ support per observed table
+        state.health_registry.register(state.cdc_liveness.clone());
+        state.health_registry.register(state.cdc_lag.clone());
+        for index in 0..state.cdc_tables.len() {
+            let cdc_processor = state.spawn_cdc_processor(index, false);
+            actor_ref.link(&cdc_processor).await;
+            state.cdc_processors.push(cdc_processor);
+        }
+
+        // Reclaim published rows of the outbox tables
+        if let Some(retention) = state.outbox_retention.clone() {
+            let janitor = state.spawn_outbox_janitor(&retention);
+            actor_ref.link(&janitor).await;
+            state.outbox_janitor = Some(janitor);
+        }
 
         // Report CDC processor health
-        let _ = health_monitor.tell(UpdateHealth {
+        let tables: Vec<String> = state.cdc_tables.iter().map(|table| table.source.label()).collect();
+        health_mailbox.tell(UpdateHealth {
             component: "cdc_processor".to_string(),
             status: HealthStatus::Healthy,
-            details: Some("CDC processor started".to_string()),
-        }).send().await;
+            details: Some(format!("CDC processors started: {}", tables.join(", "))),
+        }, MessagePriority::Critical);
 
         tracing::info!("✅ All supervised actors started successfully");
 
+        // Report startup progress until every phase completed
+        if let Some(startup) = state.startup.clone() {
+            let health_mailbox = health_mailbox.clone();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(std::time::Duration::from_secs(1));
+                loop {
+                    interval.tick().await;
+                    let status = startup.status();
+                    health_mailbox.tell(UpdateHealth {
+                        component: "startup".to_string(),
+                        status: if status.ready {
+                            HealthStatus::Healthy
+                        } else {
+                            HealthStatus::Degraded(format!("Starting: {}", status.summary()))
+                        },
+                        details: Some(status.summary()),
+                    }, MessagePriority::Normal);
+                    if status.ready {
+                        break;
+                    }
+                }
+            });
+        }
+
+        // Re-evaluate the CDC throttle; report on change and while backing off
+        if let Some(throttle) = state.cdc_throttle.clone() {
+            let health_mailbox = health_mailbox.clone();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(throttle.config().evaluate_interval);
+                let mut reported: Option<bool> = None;
+                loop {
+                    interval.tick().await;
+                    let throttle_state = throttle.evaluate();
+                    if reported == Some(false) && !throttle_state.throttled {
+                        continue;
+                    }
+                    reported = Some(throttle_state.throttled);
+
+                    let status = if throttle_state.throttled {
+                        HealthStatus::Degraded(format!(
+                            "CDC consumption throttled: {}",
+                            throttle_state.reason.as_deref().unwrap_or("recovering")
+                        ))
+                    } else {
+                        HealthStatus::Healthy
+                    };
+                    health_mailbox.tell(UpdateHealth {
+                        component: "cdc_throttle".to_string(),
+                        status,
+                        details: Some(format!(
+                            "delay {}ms, p95 {}ms, error rate {:.1}% ({} samples)",
+                            throttle_state.delay_ms,
+                            throttle_state.p95_ms,
+                            throttle_state.error_rate * 100.0,
+                            throttle_state.samples
+                        )),
+                    }, MessagePriority::Normal);
+                }
+            });
+        }
+
         // Clone what we need for periodic health checks
         let health_monitor_clone = state.health_monitor.clone();
 
@@ -125,6 +700,57 @@ impl Actor for CoordinatorActor {
         Ok(state)
     }
 
+    async fn on_link_died(
+        &mut self,
+        actor_ref: WeakActorRef<Self>,
+        id: ActorId,
+        reason: ActorStopReason,
+    ) -> Result<ControlFlow<ActorStopReason>, Self::Error> {
+        let Some(child) = self.child(id) else {
+            return Ok(ControlFlow::Continue(()));
+        };
+        // Stopped on purpose (shutdown drains and kills its children)
+        if self.shutting_down || matches!(reason, ActorStopReason::Normal | ActorStopReason::Killed) {
+            return Ok(ControlFlow::Continue(()));
+        }
+        let label = self.child_label(child);
+        tracing::error!(child = %label, reason = ?reason, "💥 Supervised actor died");
+
+        if matches!(child, SupervisedChild::DlqActor | SupervisedChild::HealthMonitor) {
+            self.metrics.record_actor_restart(child.as_str(), "not_restartable");
+            self.abandoned.push(label);
+            self.report_supervision(HealthStatus::Healthy, format!("{:?}", reason));
+            return Ok(ControlFlow::Continue(()));
+        }
+
+        let policy = self.restart_policy.clone();
+        match self.restarts.entry(child).or_default().on_failure(&policy) {
+            RestartDecision::Restart { attempt, delay } => {
+                tracing::warn!(child = %label, attempt = attempt, delay_ms = delay.as_millis() as u64, "Restarting supervised actor");
+                self.report_supervision(
+                    HealthStatus::Degraded(format!("Restarting {} in {:?}", label, delay)),
+                    format!("attempt {} of {} after {:?}", attempt, policy.max_restarts, reason),
+                );
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    if let Some(actor_ref) = actor_ref.upgrade() {
+                        let _ = actor_ref.tell(RestartChild { child, attempt }).send().await;
+                    }
+                });
+            }
+            RestartDecision::GiveUp { restarts } => {
+                tracing::error!(child = %label, restarts = restarts, "❌ Supervised actor keeps failing - giving up");
+                self.metrics.record_actor_restart(child.as_str(), "gave_up");
+                self.abandoned.push(label.clone());
+                self.report_supervision(
+                    HealthStatus::Healthy,
+                    format!("{} failed after {} restarts in a row: {:?}", label, restarts, reason),
+                );
+            }
+        }
+        Ok(ControlFlow::Continue(()))
+    }
+
     async fn on_stop(
         &mut self,
         _actor_ref: kameo::actor::WeakActorRef<Self>,
@@ -139,6 +765,56 @@ impl Actor for CoordinatorActor {
 // Messages
 // ============================================================================
 
+/// Restart a failed child after its backoff
+struct RestartChild {
+    child: SupervisedChild,
+    attempt: u32,
+}
+
+impl Message<RestartChild> for CoordinatorActor {
+    type Reply = ();
+
+    async fn handle(&mut self, msg: RestartChild, ctx: &mut Context<Self, Self::Reply>) -> Self::Reply {
+        if self.shutting_down {
+            return;
+        }
+        let actor_ref = ctx.actor_ref().clone();
+        match msg.child {
+            SupervisedChild::CdcProcessor(index) => {
+                let cdc_processor = self.spawn_cdc_processor(index, true);
+                actor_ref.link(&cdc_processor).await;
+                self.cdc_processors[index] = cdc_processor;
+            }
+            SupervisedChild::OutboxJanitor => {
+                let Some(retention) = self.outbox_retention.clone() else { return };
+                let janitor = self.spawn_outbox_janitor(&retention);
+                actor_ref.link(&janitor).await;
+                self.outbox_janitor = Some(janitor);
+            }
+            SupervisedChild::DlqActor | SupervisedChild::HealthMonitor => return,
+        }
+
+        let label = self.child_label(msg.child);
+        tracing::info!(child = %label, attempt = msg.attempt, "🔁 Supervised actor restarted");
+        self.metrics.record_actor_restart(msg.child.as_str(), "restarted");
+        self.report_supervision(
+            HealthStatus::Healthy,
+            format!("{} restarted (attempt {} of {})", label, msg.attempt, self.restart_policy.max_restarts),
+        );
+    }
+}
+
+/// The DLQ actor of the relay, for the admin API; None before it started
+pub struct GetDlqActor;
+
+impl Message<GetDlqActor> for CoordinatorActor {
+    type Reply = Option<ActorRef<DlqActor>>;
+
+    async fn handle(&mut self, _msg: GetDlqActor, _ctx: &mut Context<Self, Self::Reply>) -> Self::Reply {
+        self.dlq_actor.clone()
+    }
+}
+
 pub struct Shutdown;
 
 impl Message<Shutdown> for CoordinatorActor {
@@ -146,16 +822,39 @@ impl Message<Shutdown> for CoordinatorActor {
 
     async fn handle(&mut self, _msg: Shutdown, ctx: &mut Context<Self, Self::Reply>) -> Self::Reply {
         tracing::info!("Received shutdown signal");
+        // Children stopped from here on are not restarted
+        self.shutting_down = true;
 
-        // Stop child actors gracefully using Kameo's kill method
-        if let Some(ref cdc_processor) = self.cdc_processor {
+        // Drain the relays first - failed publishes still go to the DLQ
+        for cdc_processor in &self.cdc_processors {
             tracing::info!("Stopping CdcProcessor...");
+            if let Err(e) = cdc_processor.ask(DrainCdc).await {
+                tracing::error!(error = %e, "Failed to drain CdcProcessor");
+            }
             cdc_processor.kill();
         }
+        if let Some(ref stream_auditor) = self.stream_auditor {
+            if let Err(e) = stream_auditor.flush().await {
+                tracing::warn!(error = %e, "Failed to persist stream audit positions");
+            }
+        }
 
+        // Dead letters still queued in the mailbox reach the actor first
+        if let Some(ref dlq_mailbox) = self.dlq_mailbox {
+            dlq_mailbox.drained().await;
+        }
+
+        // Graceful stop handles queued messages and flushes the write buffer
         if let Some(ref dlq_actor) = self.dlq_actor {
             tracing::info!("Stopping DlqActor...");
-            dlq_actor.kill();
+            if dlq_actor.stop_gracefully().await.is_ok() {
+                dlq_actor.wait_for_shutdown().await;
+            }
+        }
+
+        if let Some(ref outbox_janitor) = self.outbox_janitor {
+            tracing::info!("Stopping OutboxJanitor...");
+            outbox_janitor.kill();
         }
 
         if let Some(ref health_monitor) = self.health_monitor {
@@ -1,12 +1,26 @@
+use async_trait::async_trait;
+use futures_util::TryStreamExt;
 use kameo::Actor;
 use kameo::message::{Context, Message};
 use kameo::actor::ActorRef;
 use kameo::error::Infallible;
 use scylla::client::session::Session;
+use scylla::statement::batch::BatchType;
+use scylla::value::{CqlTimestamp, CqlValue};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
+use crate::actors::core::{MessagePriority, PriorityMailbox};
+use crate::db::{Operation, StatementCache};
+use crate::event_sourcing::{open_payload, EventCrypto};
+use crate::messaging::{EventPublisher, PublishOrigin, PublisherDiagnostics};
+use crate::metrics::MetricsHandle;
+use crate::projections::{query_page, Page, Paging};
+use crate::utils::{redact_payload, RetryAttempt};
+
 // ============================================================================
 // Dead Letter Queue Actor
 // ============================================================================
@@ -18,29 +32,351 @@ use chrono::{DateTime, Utc};
 // - Metrics on failure patterns
 // - Retry mechanism for DLQ messages
 //
+// Write path:
+// During a broker outage every event fails at once. Inserting them one by
+// one would make the DLQ the next bottleneck, so AddToDlq only buffers the
+// message. The buffer is written as an UNLOGGED batch when it reaches
+// `batch_size` or every `flush_interval`, with up to `max_concurrent_writes`
+// batches in flight. Beyond `max_buffered` messages new ones are rejected
+// (logged in full and counted) instead of growing memory without bound.
+// Batches, replay updates and quarantine moves use prepared statements.
+// Relays dead-letter through a PriorityMailbox at Normal priority: the
+// backlog waits there, not in the actor's mailbox ahead of its flush and
+// age-check ticks and the admin API's requests.
+//
+// Failure context:
+// Besides the final error string each entry stores a JSON FailureContext in
+// `failure_context`: every retry attempt with its error and backoff, the
+// circuit breaker state and broker settings at failure time, and the topic
+// and key that were used. The admin API renders it (GET /admin/dlq/{id}).
+//
+// Replay and quarantine:
+// ReplayDlqMessage publishes an entry again to the topic and key recorded
+// in its failure context (the stored payload, without envelope headers).
+// Published entries are removed. A failed replay bumps failure_count; once
+// it reaches `max_failures` (relay attempts plus failed replays) the entry
+// moves to dead_letter_quarantine instead of being retried forever.
+//
+// Aging:
+// Every `age_check_interval` the actor counts entries whose first failure
+// is older than `max_age` (dlq_aged_messages) and warns while there are any
+// - dead letters nobody looks at are lost events in all but name.
+//
+// Browsing:
+// ListDlqMessages (and GET /admin/dlq) pages through the DLQ with the
+// read models' opaque cursors, filtered by event type, aggregate and a
+// last_failed_at range. event_type and aggregate_id are secondary indexes;
+// a time range, or a second filter, is applied with ALLOW FILTERING on top
+// of the most selective index (aggregate_id, then event_type).
+//
+// Management:
+// The admin API drives this actor: GET /admin/dlq lists (ListDlqMessages),
+// POST /admin/dlq/{id}/retry replays (ReplayDlqMessage), DELETE
+// /admin/dlq/{id} drops an entry for good (DeleteDlqMessage) and GET
+// /admin/dlq/stats counts entries per event type (GetDlqStats, a scan of
+// the DLQ - fine for the few entries it should hold).
+//
 // ============================================================================
 
+/// Batching and overflow limits for DLQ writes
+#[derive(Debug, Clone)]
+pub struct DlqWriterConfig {
+    pub batch_size: usize,
+    pub flush_interval: Duration,
+    pub max_concurrent_writes: usize,
+    /// Buffered + in-flight messages above which AddToDlq is rejected
+    pub max_buffered: usize,
+}
+
+impl Default for DlqWriterConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: 100,
+            flush_interval: Duration::from_millis(200),
+            max_concurrent_writes: 4,
+            max_buffered: 10_000,
+        }
+    }
+}
+
+/// When dead letters stop being replayed, and when they count as aged
+#[derive(Debug, Clone)]
+pub struct DlqQuarantinePolicy {
+    /// failure_count (relay attempts plus failed replays) that quarantines an entry
+    pub max_failures: i32,
+    /// Entries first failing longer ago are reported as aged
+    pub max_age: Duration,
+    pub age_check_interval: Duration,
+}
+
+impl Default for DlqQuarantinePolicy {
+    fn default() -> Self {
+        Self {
+            max_failures: 10,
+            max_age: Duration::from_secs(24 * 60 * 60),
+            age_check_interval: Duration::from_secs(300),
+        }
+    }
+}
+
+impl DlqQuarantinePolicy {
+    pub fn should_quarantine(&self, failure_count: i32) -> bool {
+        failure_count >= self.max_failures
+    }
+}
+
 pub struct DlqActor {
     session: Arc<Session>,
+    statements: Arc<StatementCache>,
+    config: DlqWriterConfig,
+    quarantine: DlqQuarantinePolicy,
+    /// Replays publish through this; None rejects replays
+    publisher: Option<Arc<dyn EventPublisher>>,
+    /// Opens sealed payloads for replay
+    crypto: Option<Arc<dyn EventCrypto>>,
+    buffer: Vec<AddToDlq>,
+    write_permits: Arc<Semaphore>,
+    in_flight: Arc<std::sync::atomic::AtomicUsize>,
+    metrics: MetricsHandle,
 }
 
 impl DlqActor {
     pub fn new(session: Arc<Session>) -> Self {
-        Self { session }
+        Self::with_config(session, DlqWriterConfig::default())
+    }
+
+    pub fn with_config(session: Arc<Session>, config: DlqWriterConfig) -> Self {
+        Self {
+            statements: Arc::new(StatementCache::new(session.clone())),
+            session,
+            write_permits: Arc::new(Semaphore::new(config.max_concurrent_writes.max(1))),
+            config,
+            quarantine: DlqQuarantinePolicy::default(),
+            publisher: None,
+            crypto: None,
+            buffer: Vec::new(),
+            in_flight: Arc::new(std::sync::atomic::AtomicUsize::new(0)),
+            metrics: MetricsHandle::noop(),
+        }
+    }
+
+    pub fn with_metrics(mut self, metrics: MetricsHandle) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Publisher used by ReplayDlqMessage
+    pub fn with_publisher(mut self, publisher: Arc<dyn EventPublisher>) -> Self {
+        self.publisher = Some(publisher);
+        self
+    }
+
+    /// Entries keep the payload as the outbox stored it; replays decrypt it
+    pub fn with_crypto(mut self, crypto: Option<Arc<dyn EventCrypto>>) -> Self {
+        self.crypto = crypto;
+        self
+    }
+
+    pub fn with_quarantine_policy(mut self, quarantine: DlqQuarantinePolicy) -> Self {
+        self.quarantine = quarantine;
+        self
+    }
+
+    /// Statement cache (and its consistency levels) for DLQ writes
+    pub fn with_statement_cache(mut self, statements: Arc<StatementCache>) -> Self {
+        self.statements = statements;
+        self
+    }
+
+    fn pending(&self) -> usize {
+        self.buffer.len() + self.in_flight.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    fn update_buffer_gauge(&self) {
+        self.metrics.set_dlq_buffered(self.pending() as i64);
+    }
+
+    /// Write everything buffered, in batches of `batch_size`
+    ///
+    /// Waits for a write permit per batch, so at most
+    /// `max_concurrent_writes` batches hit Scylla at once.
+    async fn flush(&mut self) {
+        while !self.buffer.is_empty() {
+            let take = self.buffer.len().min(self.config.batch_size.max(1));
+            let messages: Vec<AddToDlq> = self.buffer.drain(..take).collect();
+
+            let permit = match self.write_permits.clone().acquire_owned().await {
+                Ok(permit) => permit,
+                Err(_) => return,
+            };
+
+            self.in_flight.fetch_add(messages.len(), std::sync::atomic::Ordering::SeqCst);
+            let statements = self.statements.clone();
+            let in_flight = self.in_flight.clone();
+            let metrics = self.metrics.clone();
+
+            tokio::spawn(async move {
+                let count = messages.len();
+                let result = write_batch(&statements, &messages).await;
+
+                match result {
+                    Ok(()) => {
+                        tracing::info!(count = count, "Stored batch of messages in DLQ");
+                        metrics.record_dlq_batch_write(true);
+                        for msg in &messages {
+                            metrics.record_dlq_message(&msg.event_type);
+                        }
+                    }
+                    Err(e) => {
+                        // Keep the payloads in the logs so nothing is lost silently
+                        // (PII fields scrubbed in production)
+                        for msg in &messages {
+                            tracing::error!(
+                                event_id = %msg.id,
+                                event_type = %msg.event_type,
+                                aggregate_id = %msg.aggregate_id,
+                                payload = %redact_payload(&msg.payload),
+                                error = %e,
+                                "Failed to insert into DLQ"
+                            );
+                        }
+                        metrics.record_dlq_batch_write(false);
+                    }
+                }
+
+                in_flight.fetch_sub(count, std::sync::atomic::Ordering::SeqCst);
+                metrics.record_dlq_drained(count as i64);
+                drop(permit);
+            });
+        }
+
+        self.update_buffer_gauge();
     }
 }
 
+async fn write_batch(statements: &StatementCache, messages: &[AddToDlq]) -> anyhow::Result<()> {
+    let insert = statements
+        .statement(
+            Operation::Dlq,
+            "INSERT INTO dead_letter_queue (
+                id, aggregate_id, event_type, payload,
+                error_message, failure_count, first_failed_at,
+                last_failed_at, created_at, failure_context
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .await?;
+    let now = Utc::now();
+    let mut batch = statements.batch(Operation::Dlq, BatchType::Unlogged);
+    let mut values = Vec::with_capacity(messages.len());
+
+    for msg in messages {
+        batch.append_statement(insert.clone());
+        values.push((
+            msg.id,
+            msg.aggregate_id,
+            msg.event_type.clone(),
+            msg.payload.clone(),
+            msg.error_message.clone(),
+            msg.failure_count,
+            msg.first_failed_at,
+            now,
+            now,
+            msg.failure_context.as_ref().and_then(|c| serde_json::to_string(c).ok()),
+        ));
+    }
+
+    statements.session().batch(&batch, values).await?;
+    Ok(())
+}
+
 impl Actor for DlqActor {
     type Args = Self;
     type Error = Infallible;
 
     async fn on_start(
         state: Self::Args,
-        _actor_ref: ActorRef<Self>
+        actor_ref: ActorRef<Self>
     ) -> Result<Self, Self::Error> {
-        tracing::info!("DlqActor started - Dead Letter Queue ready");
+        tracing::info!(
+            batch_size = state.config.batch_size,
+            max_concurrent_writes = state.config.max_concurrent_writes,
+            "DlqActor started - Dead Letter Queue ready"
+        );
+
+        // Flush partially filled batches periodically
+        let flush_interval = state.config.flush_interval;
+        let weak_ref = actor_ref.downgrade();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(flush_interval);
+            loop {
+                interval.tick().await;
+                match weak_ref.upgrade() {
+                    Some(actor_ref) => {
+                        let _ = actor_ref.tell(FlushDlq).send().await;
+                    }
+                    None => break,
+                }
+            }
+        });
+
+        // Report entries nobody replayed for too long
+        let age_check_interval = state.quarantine.age_check_interval;
+        let weak_ref = actor_ref.downgrade();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(age_check_interval);
+            loop {
+                interval.tick().await;
+                match weak_ref.upgrade() {
+                    Some(actor_ref) => {
+                        let _ = actor_ref.tell(CheckDlqAge).send().await;
+                    }
+                    None => break,
+                }
+            }
+        });
+
         Ok(state)
     }
+
+    async fn on_stop(
+        &mut self,
+        _actor_ref: kameo::actor::WeakActorRef<Self>,
+        _reason: kameo::error::ActorStopReason,
+    ) -> Result<(), Self::Error> {
+        // Don't lose buffered messages on shutdown
+        self.flush().await;
+        tracing::info!("DlqActor stopped");
+        Ok(())
+    }
+}
+
+/// Where relays put the events they could not publish
+///
+/// The DlqActor's PriorityMailbox in production; test doubles
+/// (test_support::RecordingDlq) keep the entries in memory.
+#[async_trait]
+pub trait DeadLetterSink: Send + Sync {
+    async fn dead_letter(&self, message: AddToDlq) -> anyhow::Result<()>;
+}
+
+#[async_trait]
+impl DeadLetterSink for ActorRef<DlqActor> {
+    async fn dead_letter(&self, message: AddToDlq) -> anyhow::Result<()> {
+        self.tell(message)
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("DLQ unavailable: {}", e))
+    }
+}
+
+/// Dead letters are bulk traffic: Normal priority, behind the actor's own
+/// control messages
+#[async_trait]
+impl DeadLetterSink for PriorityMailbox<DlqActor> {
+    async fn dead_letter(&self, message: AddToDlq) -> anyhow::Result<()> {
+        self.tell(message, MessagePriority::Normal);
+        Ok(())
+    }
 }
 
 // ============================================================================
@@ -56,16 +392,119 @@ pub struct AddToDlq {
     pub error_message: String,
     pub failure_count: i32,
     pub first_failed_at: DateTime<Utc>,
+    pub failure_context: Option<FailureContext>,
+}
+
+/// Structured snapshot of why a message ended up in the DLQ
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FailureContext {
+    pub topic: String,
+    pub key: String,
+    pub attempts: Vec<RetryAttempt>,
+    pub publisher: PublisherDiagnostics,
+    pub captured_at: DateTime<Utc>,
 }
 
+/// Write all buffered DLQ messages now
+pub(crate) struct FlushDlq;
+
 pub(crate) struct GetDlqMessages {
     pub limit: i32,
 }
 
-pub(crate) struct GetDlqStats;
+/// Publish a DLQ entry again; quarantines it after too many failures
+pub struct ReplayDlqMessage {
+    pub id: Uuid,
+}
 
-#[derive(Debug, Clone)]
-pub(crate) struct DlqMessage {
+/// What a replay did with the entry
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case", tag = "outcome")]
+pub enum ReplayOutcome {
+    /// Published and removed from the DLQ
+    Published,
+    /// Still in the DLQ, with its new failure_count
+    Failed { failure_count: i32, error: String },
+    /// Moved to dead_letter_quarantine
+    Quarantined { failure_count: i32, error: String },
+}
+
+impl ReplayOutcome {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ReplayOutcome::Published => "published",
+            ReplayOutcome::Failed { .. } => "failed",
+            ReplayOutcome::Quarantined { .. } => "quarantined",
+        }
+    }
+}
+
+/// Count and report aged DLQ entries
+pub(crate) struct CheckDlqAge;
+
+/// One page of DLQ entries matching `filter`
+pub struct ListDlqMessages {
+    pub filter: DlqFilter,
+    pub paging: Paging,
+}
+
+/// Remove a DLQ entry without publishing it; replies whether it existed
+pub struct DeleteDlqMessage {
+    pub id: Uuid,
+}
+
+/// Entries per event type and the oldest failure
+pub struct GetDlqStats;
+
+/// Which DLQ entries to list; None matches any
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DlqFilter {
+    pub event_type: Option<String>,
+    pub aggregate_id: Option<Uuid>,
+    /// Last failed at or after
+    pub failed_from: Option<DateTime<Utc>>,
+    /// Last failed before
+    pub failed_until: Option<DateTime<Utc>>,
+}
+
+impl DlqFilter {
+    /// Listing query and its bound values
+    fn query(&self) -> (String, Vec<CqlValue>) {
+        // The indexed equality goes first; Scylla picks it to drive the scan
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+        if let Some(aggregate_id) = self.aggregate_id {
+            conditions.push("aggregate_id = ?");
+            values.push(CqlValue::Uuid(aggregate_id));
+        }
+        if let Some(ref event_type) = self.event_type {
+            conditions.push("event_type = ?");
+            values.push(CqlValue::Text(event_type.clone()));
+        }
+        if let Some(from) = self.failed_from {
+            conditions.push("last_failed_at >= ?");
+            values.push(CqlValue::Timestamp(CqlTimestamp(from.timestamp_millis())));
+        }
+        if let Some(until) = self.failed_until {
+            conditions.push("last_failed_at < ?");
+            values.push(CqlValue::Timestamp(CqlTimestamp(until.timestamp_millis())));
+        }
+
+        let mut query = format!("SELECT {} FROM dead_letter_queue", DLQ_COLUMNS);
+        if !conditions.is_empty() {
+            query = format!("{} WHERE {}", query, conditions.join(" AND "));
+        }
+        // A single indexed equality is served by its index alone
+        let single_index = conditions.len() == 1 && (self.aggregate_id.is_some() || self.event_type.is_some());
+        if !conditions.is_empty() && !single_index {
+            query.push_str(" ALLOW FILTERING");
+        }
+        (query, values)
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DlqMessage {
     pub id: Uuid,
     pub aggregate_id: Uuid,
     pub event_type: String,
@@ -74,12 +513,26 @@ pub(crate) struct DlqMessage {
     pub failure_count: i32,
     pub first_failed_at: DateTime<Utc>,
     pub last_failed_at: DateTime<Utc>,
+    /// None for entries written before failure contexts were recorded
+    pub failure_context: Option<FailureContext>,
 }
 
-#[derive(Debug, Clone)]
-pub(crate) struct DlqStats {
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct DlqStats {
     pub total_messages: i64,
-    pub by_event_type: std::collections::HashMap<String, i64>,
+    pub by_event_type: std::collections::BTreeMap<String, i64>,
+    /// First failure of the oldest entry; None when the DLQ is empty
+    pub oldest_failed_at: Option<DateTime<Utc>>,
+}
+
+impl DlqStats {
+    fn count(&mut self, event_type: String, first_failed_at: DateTime<Utc>) {
+        self.total_messages += 1;
+        *self.by_event_type.entry(event_type).or_insert(0) += 1;
+        if self.oldest_failed_at.is_none_or(|oldest| first_failed_at < oldest) {
+            self.oldest_failed_at = Some(first_failed_at);
+        }
+    }
 }
 
 // ============================================================================
@@ -90,7 +543,18 @@ impl Message<AddToDlq> for DlqActor {
     type Reply = Result<(), String>;
 
     async fn handle(&mut self, msg: AddToDlq, _ctx: &mut Context<Self, Self::Reply>) -> Self::Reply {
-        let now = Utc::now();
+        if self.pending() >= self.config.max_buffered {
+            tracing::error!(
+                event_id = %msg.id,
+                event_type = %msg.event_type,
+                aggregate_id = %msg.aggregate_id,
+                payload = %redact_payload(&msg.payload),
+                error = %msg.error_message,
+                "DLQ buffer full - message rejected"
+            );
+            self.metrics.record_dlq_overflow();
+            return Err(format!("DLQ buffer full ({} messages pending)", self.pending()));
+        }
 
         tracing::error!(
             event_id = %msg.id,
@@ -101,47 +565,35 @@ impl Message<AddToDlq> for DlqActor {
             "💀 Adding message to Dead Letter Queue"
         );
 
-        self.session
-            .query_unpaged(
-                "INSERT INTO dead_letter_queue (
-                    id, aggregate_id, event_type, payload,
-                    error_message, failure_count, first_failed_at,
-                    last_failed_at, created_at
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
-                (
-                    msg.id,
-                    msg.aggregate_id,
-                    &msg.event_type,
-                    &msg.payload,
-                    &msg.error_message,
-                    msg.failure_count,
-                    msg.first_failed_at,
-                    now,
-                    now,
-                ),
-            )
-            .await
-            .map_err(|e| format!("Failed to insert into DLQ: {}", e))?;
+        self.buffer.push(msg);
 
-        tracing::info!(
-            event_id = %msg.id,
-            "Message successfully stored in DLQ"
-        );
+        if self.buffer.len() >= self.config.batch_size {
+            self.flush().await;
+        } else {
+            self.update_buffer_gauge();
+        }
 
         Ok(())
     }
 }
 
+impl Message<FlushDlq> for DlqActor {
+    type Reply = ();
+
+    async fn handle(&mut self, _msg: FlushDlq, _ctx: &mut Context<Self, Self::Reply>) -> Self::Reply {
+        if !self.buffer.is_empty() {
+            self.flush().await;
+        }
+    }
+}
+
 impl Message<GetDlqMessages> for DlqActor {
     type Reply = Result<Vec<DlqMessage>, String>;
 
     async fn handle(&mut self, msg: GetDlqMessages, _ctx: &mut Context<Self, Self::Reply>) -> Self::Reply {
         let result = self.session
             .query_unpaged(
-                "SELECT id, aggregate_id, event_type, payload, error_message,
-                        failure_count, first_failed_at, last_failed_at
-                 FROM dead_letter_queue
-                 LIMIT ?",
+                format!("SELECT {} FROM dead_letter_queue LIMIT ?", DLQ_COLUMNS),
                 (msg.limit,),
             )
             .await
@@ -151,63 +603,310 @@ impl Message<GetDlqMessages> for DlqActor {
 
         let rows_result = result.into_rows_result()
             .map_err(|e| format!("Failed to parse DLQ results: {}", e))?;
-        let rows = rows_result.rows()
+        let rows = rows_result.rows::<DlqRow>()
             .map_err(|e| format!("Failed to get rows: {}", e))?;
 
         for row in rows {
-            let (id, aggregate_id, event_type, payload, error_message,
-                 failure_count, first_failed_at, last_failed_at):
-                (Uuid, Uuid, String, String, String, i32, DateTime<Utc>, DateTime<Utc>) =
-                row.map_err(|e| format!("Failed to parse row: {}", e))?;
-
-            messages.push(DlqMessage {
-                id,
-                aggregate_id,
-                event_type,
-                payload,
-                error_message,
-                failure_count,
-                first_failed_at,
-                last_failed_at,
-            });
+            messages.push(dlq_message(row.map_err(|e| format!("Failed to parse row: {}", e))?));
         }
 
         Ok(messages)
     }
 }
 
+const DLQ_COLUMNS: &str = "id, aggregate_id, event_type, payload, error_message,
+    failure_count, first_failed_at, last_failed_at, failure_context";
+
+type DlqRow = (Uuid, Uuid, String, String, String, i32, DateTime<Utc>, DateTime<Utc>, Option<String>);
+
+fn dlq_message(row: DlqRow) -> DlqMessage {
+    let (id, aggregate_id, event_type, payload, error_message,
+         failure_count, first_failed_at, last_failed_at, failure_context) = row;
+
+    DlqMessage {
+        id,
+        aggregate_id,
+        event_type,
+        payload,
+        error_message,
+        failure_count,
+        first_failed_at,
+        last_failed_at,
+        // An unreadable context shouldn't hide the entry itself
+        failure_context: failure_context.and_then(|json| serde_json::from_str(&json).ok()),
+    }
+}
+
+/// Load a single DLQ entry, for the admin API
+pub async fn load_dlq_message(session: &Session, id: Uuid) -> anyhow::Result<Option<DlqMessage>> {
+    let row = session
+        .query_unpaged(
+            format!("SELECT {} FROM dead_letter_queue WHERE id = ?", DLQ_COLUMNS),
+            (id,),
+        )
+        .await?
+        .into_rows_result()?
+        .maybe_first_row::<DlqRow>()?;
+
+    Ok(row.map(dlq_message))
+}
+
+/// One page of DLQ entries matching `filter`, in token order
+pub async fn list_dlq_messages(session: &Session, filter: &DlqFilter, paging: &Paging) -> anyhow::Result<Page<DlqMessage>> {
+    let (query, values) = filter.query();
+    let (rows, next_cursor) = query_page(session, &query, values, paging).await?;
+    let items = rows
+        .rows::<DlqRow>()?
+        .map(|row| row.map(dlq_message))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(Page { items, next_cursor })
+}
+
+impl Message<ListDlqMessages> for DlqActor {
+    type Reply = Result<Page<DlqMessage>, String>;
+
+    async fn handle(&mut self, msg: ListDlqMessages, _ctx: &mut Context<Self, Self::Reply>) -> Self::Reply {
+        list_dlq_messages(&self.session, &msg.filter, &msg.paging)
+            .await
+            .map_err(|e| format!("Failed to list DLQ: {:#}", e))
+    }
+}
+
+impl Message<ReplayDlqMessage> for DlqActor {
+    type Reply = Result<ReplayOutcome, String>;
+
+    async fn handle(&mut self, msg: ReplayDlqMessage, _ctx: &mut Context<Self, Self::Reply>) -> Self::Reply {
+        let publisher = self.publisher.clone().ok_or_else(|| "DLQ replay is not configured (no publisher)".to_string())?;
+        let message = load_dlq_message(&self.session, msg.id)
+            .await
+            .map_err(|e| format!("Failed to load DLQ entry: {}", e))?
+            .ok_or_else(|| format!("DLQ entry {} not found", msg.id))?;
+        let context = message
+            .failure_context
+            .as_ref()
+            .ok_or_else(|| format!("DLQ entry {} has no recorded topic to replay to", msg.id))?;
+        let payload = open_payload(self.crypto.as_deref(), &message.payload)
+            .map_err(|e| format!("Failed to decrypt DLQ entry {}: {}", msg.id, e))?;
+
+        // Outbox events go out as the relay would have sent them; row changes
+        // of relayed tables (no aggregate) are direct publishes
+        let origin = if message.aggregate_id.is_nil() { PublishOrigin::Direct } else { PublishOrigin::OutboxCdc };
+        let outcome = match publisher.publish_with_origin(&context.topic, &context.key, &payload, &[], origin).await {
+            Ok(()) => {
+                self.statements
+                    .execute(Operation::Dlq, "DELETE FROM dead_letter_queue WHERE id = ?", (message.id,))
+                    .await
+                    .map_err(|e| format!("Replayed, but failed to remove the DLQ entry: {}", e))?;
+                ReplayOutcome::Published
+            }
+            Err(e) => {
+                let failure_count = message.failure_count + 1;
+                let error = e.to_string();
+                if self.quarantine.should_quarantine(failure_count) {
+                    quarantine(&self.statements, &message, failure_count, &error)
+                        .await
+                        .map_err(|e| format!("Failed to quarantine DLQ entry: {}", e))?;
+                    tracing::error!(
+                        event_id = %message.id,
+                        event_type = %message.event_type,
+                        failure_count = failure_count,
+                        error = %error,
+                        "☣️ DLQ entry quarantined after repeated replay failures"
+                    );
+                    ReplayOutcome::Quarantined { failure_count, error }
+                } else {
+                    self.statements
+                        .execute(
+                            Operation::Dlq,
+                            "UPDATE dead_letter_queue SET failure_count = ?, last_failed_at = ?, error_message = ? WHERE id = ?",
+                            (failure_count, Utc::now(), error.as_str(), message.id),
+                        )
+                        .await
+                        .map_err(|e| format!("Failed to record replay failure: {}", e))?;
+                    ReplayOutcome::Failed { failure_count, error }
+                }
+            }
+        };
+
+        self.metrics.record_dlq_replay(outcome.as_str());
+        Ok(outcome)
+    }
+}
+
+/// Move a DLQ entry to dead_letter_quarantine
+async fn quarantine(statements: &StatementCache, message: &DlqMessage, failure_count: i32, error: &str) -> anyhow::Result<()> {
+    let now = Utc::now();
+    let mut batch = statements.batch(Operation::Dlq, BatchType::Logged);
+    batch.append_statement(
+        statements
+            .statement(
+                Operation::Dlq,
+                "INSERT INTO dead_letter_quarantine (
+                    id, aggregate_id, event_type, payload,
+                    error_message, failure_count, first_failed_at,
+                    last_failed_at, failure_context, quarantined_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .await?,
+    );
+    batch.append_statement(statements.statement(Operation::Dlq, "DELETE FROM dead_letter_queue WHERE id = ?").await?);
+
+    let failure_context = message.failure_context.as_ref().and_then(|c| serde_json::to_string(c).ok());
+    statements
+        .session()
+        .batch(
+            &batch,
+            (
+                (
+                    message.id,
+                    message.aggregate_id,
+                    message.event_type.as_str(),
+                    message.payload.as_str(),
+                    error,
+                    failure_count,
+                    message.first_failed_at,
+                    now,
+                    failure_context,
+                    now,
+                ),
+                (message.id,),
+            ),
+        )
+        .await?;
+    Ok(())
+}
+
+impl Message<CheckDlqAge> for DlqActor {
+    type Reply = ();
+
+    async fn handle(&mut self, _msg: CheckDlqAge, _ctx: &mut Context<Self, Self::Reply>) -> Self::Reply {
+        let cutoff = Utc::now() - chrono::Duration::from_std(self.quarantine.max_age).unwrap_or_default();
+        let result = self
+            .session
+            .query_unpaged("SELECT COUNT(*) FROM dead_letter_queue WHERE first_failed_at < ? ALLOW FILTERING", (cutoff,))
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|result| Ok(result.into_rows_result()?.first_row::<(i64,)>()?.0));
+
+        match result {
+            Ok(aged) => {
+                self.metrics.set_dlq_aged(aged);
+                if aged > 0 {
+                    tracing::warn!(
+                        aged = aged,
+                        max_age_secs = self.quarantine.max_age.as_secs(),
+                        "DLQ holds messages older than the aging threshold - replay or quarantine them"
+                    );
+                }
+            }
+            Err(e) => tracing::warn!(error = %e, "Failed to check DLQ message age"),
+        }
+    }
+}
+
 impl Message<GetDlqStats> for DlqActor {
     type Reply = Result<DlqStats, String>;
 
     async fn handle(&mut self, _msg: GetDlqStats, _ctx: &mut Context<Self, Self::Reply>) -> Self::Reply {
-        // Get total count
-        let count_result = self.session
-            .query_unpaged("SELECT COUNT(*) FROM dead_letter_queue", &[])
-            .await
-            .map_err(|e| format!("Failed to count DLQ messages: {}", e))?;
-
-        let total_messages = match count_result.into_rows_result() {
-            Ok(rows_result) => {
-                match rows_result.rows() {
-                    Ok(mut rows) => {
-                        rows.next()
-                            .and_then(|row| row.ok())
-                            .map(|row: (i64,)| row.0)
-                            .unwrap_or(0)
-                    }
-                    Err(_) => 0,
-                }
+        let stats = async {
+            let mut rows = self
+                .session
+                .query_iter("SELECT event_type, first_failed_at FROM dead_letter_queue", &[])
+                .await?
+                .rows_stream::<(String, DateTime<Utc>)>()?;
+            let mut stats = DlqStats::default();
+            while let Some((event_type, first_failed_at)) = rows.try_next().await? {
+                stats.count(event_type, first_failed_at);
             }
-            Err(_) => 0,
+            Ok::<_, anyhow::Error>(stats)
+        };
+        stats.await.map_err(|e| format!("Failed to collect DLQ stats: {}", e))
+    }
+}
+
+impl Message<DeleteDlqMessage> for DlqActor {
+    type Reply = Result<bool, String>;
+
+    async fn handle(&mut self, msg: DeleteDlqMessage, _ctx: &mut Context<Self, Self::Reply>) -> Self::Reply {
+        let Some(message) = load_dlq_message(&self.session, msg.id)
+            .await
+            .map_err(|e| format!("Failed to load DLQ entry: {}", e))?
+        else {
+            return Ok(false);
+        };
+        self.statements
+            .execute(Operation::Dlq, "DELETE FROM dead_letter_queue WHERE id = ?", (message.id,))
+            .await
+            .map_err(|e| format!("Failed to delete DLQ entry: {}", e))?;
+        tracing::warn!(
+            event_id = %message.id,
+            event_type = %message.event_type,
+            aggregate_id = %message.aggregate_id,
+            failure_count = message.failure_count,
+            "🗑️ DLQ entry deleted without being published"
+        );
+        Ok(true)
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_queries() {
+        let (query, values) = DlqFilter::default().query();
+        assert!(query.ends_with("FROM dead_letter_queue"));
+        assert!(values.is_empty());
+
+        let filter = DlqFilter { event_type: Some("OrderCreated".to_string()), ..Default::default() };
+        let (query, values) = filter.query();
+        assert!(query.ends_with("WHERE event_type = ?"));
+        assert_eq!(values, vec![CqlValue::Text("OrderCreated".to_string())]);
+
+        let aggregate_id = Uuid::new_v4();
+        let from = Utc::now();
+        let filter = DlqFilter {
+            event_type: Some("OrderCreated".to_string()),
+            aggregate_id: Some(aggregate_id),
+            failed_from: Some(from),
+            failed_until: None,
         };
+        let (query, values) = filter.query();
+        assert!(query.ends_with("WHERE aggregate_id = ? AND event_type = ? AND last_failed_at >= ? ALLOW FILTERING"));
+        assert_eq!(values[0], CqlValue::Uuid(aggregate_id));
+        assert_eq!(values[2], CqlValue::Timestamp(CqlTimestamp(from.timestamp_millis())));
+    }
+
+    #[test]
+    fn test_quarantine_after_max_failures() {
+        let policy = DlqQuarantinePolicy { max_failures: 6, ..Default::default() };
+        // Relayed with 5 attempts: the first failed replay quarantines
+        assert!(!policy.should_quarantine(5));
+        assert!(policy.should_quarantine(6));
+
+        let outcome = ReplayOutcome::Quarantined { failure_count: 6, error: "timeout".to_string() };
+        assert_eq!(
+            serde_json::to_value(&outcome).unwrap(),
+            serde_json::json!({ "outcome": "quarantined", "failure_count": 6, "error": "timeout" })
+        );
+    }
 
-        // For now, return basic stats
-        // In production, you'd query by event_type
-        let by_event_type = std::collections::HashMap::new();
+    #[test]
+    fn test_stats_count_per_event_type() {
+        let now = Utc::now();
+        let mut stats = DlqStats::default();
+        stats.count("OrderCreated".to_string(), now);
+        stats.count("OrderShipped".to_string(), now - chrono::Duration::hours(2));
+        stats.count("OrderCreated".to_string(), now - chrono::Duration::hours(1));
 
-        Ok(DlqStats {
-            total_messages,
-            by_event_type,
-        })
+        assert_eq!(stats.total_messages, 3);
+        assert_eq!(stats.by_event_type["OrderCreated"], 2);
+        assert_eq!(stats.by_event_type["OrderShipped"], 1);
+        assert_eq!(stats.oldest_failed_at, Some(now - chrono::Duration::hours(2)));
     }
 }
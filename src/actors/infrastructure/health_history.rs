@@ -0,0 +1,201 @@
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use scylla::client::session::Session;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::actors::core::HealthStatus;
+
+// ============================================================================
+// Health History - Persistent Component Health Transitions
+// ============================================================================
+//
+// The HealthMonitorActor keeps component health in memory only, so a restart
+// wiped out the evidence of components flapping between healthy and
+// degraded. Every change of a component's status kind (healthy / degraded /
+// unhealthy) is now written to `health_events`:
+//
+// - Rows are bucketed by UTC day and expire through a TTL (`retention`,
+//   7 days by default), so the table needs no cleanup job
+// - Message-only changes ("lag 3s" -> "lag 4s") are not transitions
+// - On start the monitor reloads each component's latest state, so the first
+//   report after a restart is not recorded as a spurious transition
+// - GET /health/history?hours=N on the metrics server lists the transitions
+//   of the last N hours with a per-component count (flapping shows up as a
+//   high count)
+//
+// Writes happen off the actor's message loop; a failed write is logged and
+// dropped rather than blocking health reporting.
+//
+// ============================================================================
+
+/// How long transitions are kept
+pub const DEFAULT_HEALTH_RETENTION: Duration = Duration::from_secs(7 * 24 * 3600);
+
+/// One change of a component's health status kind
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthTransition {
+    pub component: String,
+    /// Previous status label, None for the first report of a component
+    pub from: Option<String>,
+    pub to: String,
+    pub message: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl HealthTransition {
+    /// The transition from `previous` to `status`, if the status kind changed
+    pub fn between(component: &str, previous: Option<&HealthStatus>, status: &HealthStatus) -> Option<Self> {
+        if previous.is_some_and(|previous| previous.label() == status.label()) {
+            return None;
+        }
+        Some(Self {
+            component: component.to_string(),
+            from: previous.map(|p| p.label().to_string()),
+            to: status.label().to_string(),
+            message: status.message().map(str::to_string),
+            occurred_at: Utc::now(),
+        })
+    }
+
+    pub fn status(&self) -> HealthStatus {
+        HealthStatus::from_label(&self.to, self.message.clone())
+    }
+}
+
+/// Transitions per component, for spotting flapping
+pub fn transition_counts(transitions: &[HealthTransition]) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for transition in transitions {
+        *counts.entry(transition.component.clone()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Day buckets covering `since..=until`, newest first
+fn day_buckets(since: DateTime<Utc>, until: DateTime<Utc>) -> Vec<String> {
+    let first: NaiveDate = since.date_naive();
+    let mut day = until.date_naive();
+    let mut buckets = Vec::new();
+    while day >= first {
+        buckets.push(day.format("%Y-%m-%d").to_string());
+        let Some(previous) = day.pred_opt() else { break };
+        day = previous;
+    }
+    buckets
+}
+
+type TransitionRow = (String, Option<String>, String, Option<String>, DateTime<Utc>);
+
+/// Reads and writes the `health_events` table
+pub struct HealthHistory {
+    session: Arc<Session>,
+    retention: Duration,
+}
+
+impl HealthHistory {
+    pub fn new(session: Arc<Session>) -> Self {
+        Self {
+            session,
+            retention: DEFAULT_HEALTH_RETENTION,
+        }
+    }
+
+    pub fn retention(&self) -> Duration {
+        self.retention
+    }
+
+    pub async fn record(&self, transition: &HealthTransition) -> Result<()> {
+        let ttl = self.retention.as_secs().min(i32::MAX as u64) as i32;
+        self.session
+            .query_unpaged(
+                "INSERT INTO health_events (day, occurred_at, component, from_status, to_status, message) \
+                 VALUES (?, ?, ?, ?, ?, ?) USING TTL ?",
+                (
+                    transition.occurred_at.date_naive().format("%Y-%m-%d").to_string(),
+                    transition.occurred_at,
+                    &transition.component,
+                    &transition.from,
+                    &transition.to,
+                    &transition.message,
+                    ttl,
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Transitions since `since`, newest first
+    pub async fn since(&self, since: DateTime<Utc>) -> Result<Vec<HealthTransition>> {
+        let mut transitions = Vec::new();
+        for day in day_buckets(since, Utc::now()) {
+            let result = self
+                .session
+                .query_unpaged(
+                    "SELECT component, from_status, to_status, message, occurred_at \
+                     FROM health_events WHERE day = ? AND occurred_at >= ?",
+                    (day, since),
+                )
+                .await?
+                .into_rows_result()?;
+
+            for row in result.rows::<TransitionRow>()? {
+                let (component, from, to, message, occurred_at) = row?;
+                transitions.push(HealthTransition { component, from, to, message, occurred_at });
+            }
+        }
+        Ok(transitions)
+    }
+
+    /// Latest recorded transition of every component within the retention
+    pub async fn latest(&self) -> Result<HashMap<String, HealthTransition>> {
+        let retention = chrono::Duration::from_std(self.retention)?;
+        let mut latest = HashMap::new();
+        // Newest first, so the first row seen per component wins
+        for transition in self.since(Utc::now() - retention).await? {
+            latest.entry(transition.component.clone()).or_insert(transition);
+        }
+        Ok(latest)
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_only_status_kind_changes_are_transitions() {
+        let degraded = HealthStatus::Degraded("lag 3s".to_string());
+
+        let first = HealthTransition::between("cdc", None, &degraded).unwrap();
+        assert_eq!((first.from.as_deref(), first.to.as_str()), (None, "degraded"));
+        assert_eq!(first.status(), degraded);
+
+        assert!(HealthTransition::between("cdc", Some(&degraded), &HealthStatus::Degraded("lag 4s".to_string())).is_none());
+
+        let recovered = HealthTransition::between("cdc", Some(&degraded), &HealthStatus::Healthy).unwrap();
+        assert_eq!((recovered.from.as_deref(), recovered.to.as_str()), (Some("degraded"), "healthy"));
+        assert_eq!(recovered.message, None);
+
+        let counts = transition_counts(&[first, recovered]);
+        assert_eq!(counts.get("cdc"), Some(&2));
+    }
+
+    #[test]
+    fn test_day_buckets_cover_window_newest_first() {
+        let until = Utc.with_ymd_and_hms(2024, 3, 2, 1, 0, 0).unwrap();
+
+        assert_eq!(day_buckets(until - chrono::Duration::hours(1), until), vec!["2024-03-02"]);
+        assert_eq!(
+            day_buckets(until - chrono::Duration::hours(26), until),
+            vec!["2024-03-02", "2024-03-01", "2024-02-29"]
+        );
+    }
+}
@@ -3,12 +3,15 @@ use kameo::message::{Context, Message};
 use kameo::actor::ActorRef;
 use kameo::error::Infallible;
 use kameo::reply::{Reply, ReplyError};
-use std::sync::Arc;
+use scylla::client::session::Session;
+use std::sync::{Arc, RwLock};
 use std::collections::HashMap;
+use std::time::Duration;
 use chrono::Utc;
 use crate::messaging::RedpandaClient;
 use crate::utils::CircuitState;
-use crate::actors::core::{HealthStatus, ComponentHealth};
+use crate::actors::core::{HealthStatus, ComponentHealth, HealthCheckable};
+use super::health_history::{HealthHistory, HealthTransition};
 
 // ============================================================================
 // Health Monitor Actor - Monitors system health
@@ -19,9 +22,54 @@ use crate::actors::core::{HealthStatus, ComponentHealth};
 // - Provide health endpoints for monitoring
 // - Detect and report degraded states
 // - Aggregate system-wide health
+// - Persist status transitions (with a HealthHistory, see health_history.rs)
+// - Publish the current state to a HealthSnapshot read by GET /health
+//
+// Checked here every 10s: `redpanda` (the publisher's per-topic circuit
+// breakers: degraded while some topics are cut off, unhealthy when all are),
+// `scylla` (a trivial query, with a session) and every HealthCheckable in
+// the HealthRegistry (the coordinator registers `cdc_reader`). Other
+// components push reports through UpdateHealth (`dlq_actor`, `keyspace`,
+// `startup`, `cdc_throttle`).
+//
+// Liveness vs readiness: the process is live while the monitor keeps
+// reporting. It is ready once `scylla` and every registered component that
+// gates readiness report healthy - not while a CDC reader is still
+// resolving its generations or the Scylla session is reconnecting.
 //
 // ============================================================================
 
+/// Interval of the built-in redpanda/scylla checks
+const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A Scylla probe slower than this counts as a failed one
+const SCYLLA_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Health of the publisher from its per-topic breaker states
+fn circuit_health(states: &[(String, CircuitState)]) -> (HealthStatus, String) {
+    let topics_in = |wanted: CircuitState| {
+        states.iter().filter(|(_, state)| *state == wanted).map(|(topic, _)| topic.as_str()).collect::<Vec<_>>()
+    };
+    let (open, half_open) = (topics_in(CircuitState::Open), topics_in(CircuitState::HalfOpen));
+
+    let status = if !open.is_empty() && open.len() == states.len() {
+        HealthStatus::Unhealthy("Circuit breaker open for every topic".to_string())
+    } else if !open.is_empty() {
+        HealthStatus::Degraded(format!("Circuit breaker open for {}", open.join(", ")))
+    } else if !half_open.is_empty() {
+        HealthStatus::Degraded(format!("Circuit breaker half-open for {}", half_open.join(", ")))
+    } else {
+        HealthStatus::Healthy
+    };
+    let details = if states.is_empty() {
+        "No publishes yet".to_string()
+    } else {
+        let breakers: Vec<String> = states.iter().map(|(topic, state)| format!("{}={:?}", topic, state)).collect();
+        format!("Circuit breakers: {}", breakers.join(", "))
+    };
+    (status, details)
+}
+
 // ============================================================================
 // Messages
 // ============================================================================
@@ -39,6 +87,14 @@ pub struct SystemHealth {
     pub overall_status: HealthStatus,
     pub components: HashMap<String, ComponentHealth>,
     pub check_time: chrono::DateTime<Utc>,
+    /// Components gating readiness that are not healthy (or not reported yet)
+    pub not_ready: Vec<String>,
+}
+
+impl SystemHealth {
+    pub fn is_ready(&self) -> bool {
+        self.not_ready.is_empty()
+    }
 }
 
 // Implement Reply for SystemHealth to use it as a message reply type
@@ -60,6 +116,62 @@ impl Reply for SystemHealth {
     }
 }
 
+/// Components polled by the health monitor
+#[derive(Default)]
+pub struct HealthRegistry {
+    components: RwLock<Vec<Arc<dyn HealthCheckable + Send + Sync>>>,
+}
+
+impl HealthRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Poll `component` from now on; registering a name again replaces it
+    pub fn register(&self, component: Arc<dyn HealthCheckable + Send + Sync>) {
+        let mut components = self.components.write().unwrap();
+        components.retain(|c| c.component_name() != component.component_name());
+        tracing::debug!(component = component.component_name(), "Registered health check");
+        components.push(component);
+    }
+
+    fn check_all(&self) -> Vec<ComponentHealth> {
+        self.components.read().unwrap().iter().map(|c| c.check_health()).collect()
+    }
+
+    fn readiness_components(&self) -> Vec<String> {
+        self.components
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|c| c.gates_readiness())
+            .map(|c| c.component_name().to_string())
+            .collect()
+    }
+}
+
+/// The latest SystemHealth, shared with readers outside the actor system
+/// (the metrics server runs on its own runtime)
+#[derive(Debug, Default)]
+pub struct HealthSnapshot {
+    latest: RwLock<Option<SystemHealth>>,
+}
+
+impl HealthSnapshot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// None until the health monitor received its first report
+    pub fn latest(&self) -> Option<SystemHealth> {
+        self.latest.read().unwrap().clone()
+    }
+
+    fn publish(&self, health: SystemHealth) {
+        *self.latest.write().unwrap() = Some(health);
+    }
+}
+
 // ============================================================================
 // Health Monitor Actor
 // ============================================================================
@@ -67,6 +179,12 @@ impl Reply for SystemHealth {
 pub struct HealthMonitorActor {
     components: HashMap<String, ComponentHealth>,
     redpanda: Option<Arc<RedpandaClient>>,
+    session: Option<Arc<Session>>,
+    history: Option<Arc<HealthHistory>>,
+    snapshot: Option<Arc<HealthSnapshot>>,
+    registry: Arc<HealthRegistry>,
+    /// States restored from history (checked before this) do not count for readiness
+    started_at: chrono::DateTime<Utc>,
 }
 
 impl HealthMonitorActor {
@@ -74,9 +192,63 @@ impl HealthMonitorActor {
         Self {
             components: HashMap::new(),
             redpanda: Some(redpanda),
+            session: None,
+            history: None,
+            snapshot: None,
+            registry: Arc::new(HealthRegistry::new()),
+            started_at: Utc::now(),
         }
     }
 
+    /// Poll the components registered in `registry`
+    pub fn with_registry(mut self, registry: Arc<HealthRegistry>) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Check Scylla connectivity (`scylla` component)
+    pub fn with_session(mut self, session: Arc<Session>) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    /// Record transitions and restore the last known states on start
+    pub fn with_history(mut self, history: Arc<HealthHistory>) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Keep `snapshot` current after every report
+    pub fn with_snapshot(mut self, snapshot: Arc<HealthSnapshot>) -> Self {
+        self.snapshot = Some(snapshot);
+        self
+    }
+
+    fn system_health(&self) -> SystemHealth {
+        SystemHealth {
+            overall_status: self.compute_overall_status(),
+            components: self.components.clone(),
+            check_time: Utc::now(),
+            not_ready: self.not_ready(),
+        }
+    }
+
+    fn not_ready(&self) -> Vec<String> {
+        let mut gating = self.registry.readiness_components();
+        if self.session.is_some() {
+            gating.push("scylla".to_string());
+        }
+        gating.sort();
+        gating.dedup();
+        gating.retain(|name| {
+            !self
+                .components
+                .get(name)
+                .is_some_and(|health| health.status.is_healthy() && health.last_check >= self.started_at)
+        });
+        gating
+    }
+
     fn compute_overall_status(&self) -> HealthStatus {
         let mut has_degraded = false;
         let mut unhealthy_components = Vec::new();
@@ -108,38 +280,84 @@ impl Actor for HealthMonitorActor {
     type Error = Infallible;
 
     async fn on_start(
-        state: Self::Args,
+        mut state: Self::Args,
         actor_ref: ActorRef<Self>
     ) -> Result<Self, Self::Error> {
         tracing::info!("HealthMonitorActor started");
 
+        // Last known states, so the first report after a restart is compared
+        // against them instead of being recorded as a new transition
+        if let Some(history) = &state.history {
+            match history.latest().await {
+                Ok(latest) => {
+                    tracing::info!(components = latest.len(), "Restored component health from history");
+                    for (name, transition) in latest {
+                        let health = ComponentHealth {
+                            name: name.clone(),
+                            status: transition.status(),
+                            last_check: transition.occurred_at,
+                            details: Some("Restored from health history".to_string()),
+                        };
+                        state.components.insert(name, health);
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to restore health history: {}", e),
+            }
+        }
+
+        if let Some(snapshot) = &state.snapshot {
+            snapshot.publish(state.system_health());
+        }
+
         // Clone what we need for the periodic task
         let redpanda = state.redpanda.clone();
+        let session = state.session.clone();
+        let registry = state.registry.clone();
         let actor_ref_clone = actor_ref.clone();
 
         // Schedule periodic health checks
         tokio::spawn(async move {
-            let mut interval = tokio::time::interval(std::time::Duration::from_secs(10));
+            let mut interval = tokio::time::interval(CHECK_INTERVAL);
             loop {
                 interval.tick().await;
 
                 // Check Redpanda health periodically
                 if let Some(ref rp) = redpanda {
-                    let status = match rp.get_circuit_breaker_state().await {
-                        CircuitState::Closed => HealthStatus::Healthy,
-                        CircuitState::HalfOpen => {
-                            HealthStatus::Degraded("Circuit breaker half-open".to_string())
-                        }
-                        CircuitState::Open => {
-                            HealthStatus::Unhealthy("Circuit breaker open".to_string())
-                        }
-                    };
+                    let (status, details) = circuit_health(&rp.circuit_breaker_states().await);
 
                     // Fire and forget - use tell
                     let _ = actor_ref_clone.tell(UpdateHealth {
                         component: "redpanda".to_string(),
                         status,
-                        details: None,
+                        details: Some(details),
+                    }).send().await;
+                }
+
+                if let Some(ref session) = session {
+                    let started = std::time::Instant::now();
+                    let probe = tokio::time::timeout(
+                        SCYLLA_PROBE_TIMEOUT,
+                        session.query_unpaged("SELECT now() FROM system.local", &[]),
+                    )
+                    .await;
+                    let status = match probe {
+                        Ok(Ok(_)) => HealthStatus::Healthy,
+                        Ok(Err(e)) => HealthStatus::Unhealthy(format!("Scylla query failed: {}", e)),
+                        Err(_) => HealthStatus::Unhealthy(format!("Scylla probe timed out after {:?}", SCYLLA_PROBE_TIMEOUT)),
+                    };
+
+                    let _ = actor_ref_clone.tell(UpdateHealth {
+                        component: "scylla".to_string(),
+                        status,
+                        details: Some(format!("Probe took {}ms", started.elapsed().as_millis())),
+                    }).send().await;
+                }
+
+                for health in registry.check_all() {
+                    let _ = actor_ref_clone.tell(UpdateHealth {
+                        component: health.name,
+                        status: health.status,
+                        details: health.details,
                     }).send().await;
                 }
             }
@@ -157,6 +375,24 @@ impl Message<UpdateHealth> for HealthMonitorActor {
     type Reply = ();
 
     async fn handle(&mut self, msg: UpdateHealth, _ctx: &mut Context<Self, Self::Reply>) -> Self::Reply {
+        if let Some(history) = &self.history {
+            let previous = self.components.get(&msg.component).map(|h| &h.status);
+            if let Some(transition) = HealthTransition::between(&msg.component, previous, &msg.status) {
+                tracing::info!(
+                    component = %transition.component,
+                    from = ?transition.from,
+                    to = %transition.to,
+                    "Component health changed"
+                );
+                let history = history.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = history.record(&transition).await {
+                        tracing::warn!(component = %transition.component, "Failed to record health transition: {}", e);
+                    }
+                });
+            }
+        }
+
         let health = ComponentHealth {
             name: msg.component.clone(),
             status: msg.status.clone(),
@@ -171,6 +407,9 @@ impl Message<UpdateHealth> for HealthMonitorActor {
         );
 
         self.components.insert(msg.component, health);
+        if let Some(snapshot) = &self.snapshot {
+            snapshot.publish(self.system_health());
+        }
     }
 }
 
@@ -178,12 +417,6 @@ impl Message<GetSystemHealth> for HealthMonitorActor {
     type Reply = SystemHealth;
 
     async fn handle(&mut self, _msg: GetSystemHealth, _ctx: &mut Context<Self, Self::Reply>) -> Self::Reply {
-        let overall_status = self.compute_overall_status();
-
-        SystemHealth {
-            overall_status,
-            components: self.components.clone(),
-            check_time: Utc::now(),
-        }
+        self.system_health()
     }
 }
@@ -3,21 +3,63 @@
 // ============================================================================
 //
 // Reusable infrastructure actors for system concerns:
-// - CDC stream processing
+// - CDC stream processing (with sequence gap detection, resume checkpoints
+//   and in-flight draining on shutdown), one reader per observed table,
+//   each reporting its liveness and lag; outbox polling where CDC
+//   streaming is unavailable
+// - Stream audit (per-aggregate order of what was actually published)
+// - Published events ledger (deduplication of re-delivered CDC rows)
+// - Table relay (row changes of non-outbox tables)
+// - Approval gate (sensitive events held until approved)
 // - Dead letter queue
-// - Health monitoring
+// - Outbox janitor (retention of published outbox rows)
+// - Outbox scheduler (events held until their publish_after)
+// - Health monitoring (with persistent transition history and a snapshot
+//   served by GET /health)
 // - Coordination and supervision
+// - Startup sequencing (staggered cold start)
+// - CDC throttling (backoff under Scylla stress)
 //
 // ============================================================================
 
 // Private module declarations
 mod cdc_processor;
+mod cdc_checkpoint;
+mod cdc_liveness;
+mod cdc_lag;
+mod relay_drain;
+mod published_events;
+mod table_relay;
+mod approval_gate;
+mod outbox_row;
+mod publish_pool;
+mod sequence_gaps;
+mod stream_auditor;
 mod dlq;
+mod outbox_janitor;
+mod outbox_poller;
+mod outbox_scheduler;
 mod health_monitor;
+mod health_history;
 mod coordinator;
+mod startup;
+mod cdc_throttle;
+
+#[cfg(test)]
+pub(crate) mod test_support;
 
 // Re-export for public API
-pub use cdc_processor::CdcProcessor;
-pub use dlq::{DlqActor, AddToDlq};
-pub use health_monitor::{HealthMonitorActor, UpdateHealth, GetSystemHealth, SystemHealth};
-pub use coordinator::CoordinatorActor;
+pub use cdc_processor::{CdcProcessor, DrainCdc};
+pub use cdc_liveness::CdcLiveness;
+pub use cdc_lag::CdcLag;
+pub use publish_pool::PublishPoolConfig;
+pub use approval_gate::{ApprovalGate, DecisionOutcome, PublicationStatus};
+pub(crate) use outbox_row::OutboxRow;
+pub use dlq::{DeadLetterSink, DlqActor, DlqWriterConfig, DlqQuarantinePolicy, AddToDlq, DeleteDlqMessage, DlqFilter, DlqMessage, DlqStats, FailureContext, GetDlqStats, ListDlqMessages, ReplayDlqMessage, ReplayOutcome, list_dlq_messages, load_dlq_message};
+pub use outbox_janitor::{OutboxJanitor, OutboxRetention};
+pub use outbox_scheduler::{OutboxScheduler, ScheduleSink, ScheduledPublication};
+pub use health_monitor::{HealthMonitorActor, HealthRegistry, HealthSnapshot, UpdateHealth, GetSystemHealth, SystemHealth};
+pub use health_history::{HealthHistory, transition_counts};
+pub use coordinator::{CoordinatorActor, GetDlqActor, Shutdown};
+pub use cdc_throttle::{CdcThrottle, CdcThrottleConfig};
+pub use startup::{StartupPhase, StartupPolicy, StartupSequencer, StartupStatus};
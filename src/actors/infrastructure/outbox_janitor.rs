@@ -0,0 +1,275 @@
+use kameo::Actor;
+use kameo::message::{Context, Message};
+use kameo::actor::ActorRef;
+use kameo::error::Infallible;
+use chrono::{DateTime, Utc};
+use futures_util::TryStreamExt;
+use scylla::client::session::Session;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::config::CdcSource;
+use crate::metrics::MetricsHandle;
+
+// ============================================================================
+// Outbox Janitor - Retention of Published Outbox Rows
+// ============================================================================
+//
+// An outbox row is only needed until the CDC relay has published it; after
+// that it is dead weight in every scan (integrity check, partition advisor)
+// and in compaction. Table TTLs alone keep it for a fixed time whether it
+// was published or not, and outbox tables without a default TTL keep it
+// forever.
+//
+// Publish confirmations: once an event is published the relay's
+// PublishMarker sets `published_at` on its outbox row (this update shows up
+// in the CDC log as a non-insert, which every consumer skips).
+//
+// Every `interval` the janitor scans each outbox table and:
+//   - deletes rows published more than `retention` ago, at most
+//     `max_rows_per_pass` per table and pass
+//   - counts rows older than `retention` that were never marked published
+//     (outbox_unpublished_rows) - these are stuck, parked for approval or
+//     were written while the marker was off, and are left alone
+//   - counts all rows not marked published yet, of any age
+//     (outbox_pending_rows) - the relay's backlog, next to cdc_lag_seconds
+//
+// The scan is a full-table read: keep the interval generous. The
+// integrity check never looks further back than the retention, so the
+// janitor's deletes are not reported as missing outbox rows.
+//
+// ============================================================================
+
+/// How long published outbox rows are kept, and how often they are reclaimed
+#[derive(Debug, Clone)]
+pub struct OutboxRetention {
+    /// Keep rows for this long after they were published
+    pub retention: Duration,
+    /// How often the janitor runs
+    pub interval: Duration,
+    /// Upper bound of deletes per table and pass
+    pub max_rows_per_pass: usize,
+}
+
+impl Default for OutboxRetention {
+    fn default() -> Self {
+        Self {
+            retention: Duration::from_secs(3600),
+            interval: Duration::from_secs(300),
+            max_rows_per_pass: 10_000,
+        }
+    }
+}
+
+/// What a pass decides for one outbox row
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum RowAction {
+    Keep,
+    Reclaim,
+    /// Past retention but never confirmed published
+    Unpublished,
+}
+
+impl OutboxRetention {
+    /// Decide on one row; `cutoff` is now - retention
+    pub(crate) fn action(
+        cutoff: DateTime<Utc>,
+        created_at: Option<DateTime<Utc>>,
+        published_at: Option<DateTime<Utc>>,
+    ) -> RowAction {
+        match (published_at, created_at) {
+            (Some(published_at), _) if published_at < cutoff => RowAction::Reclaim,
+            (Some(_), _) => RowAction::Keep,
+            (None, Some(created_at)) if created_at < cutoff => RowAction::Unpublished,
+            (None, _) => RowAction::Keep,
+        }
+    }
+}
+
+/// Records publish confirmations on outbox rows (`published_at`)
+pub(crate) struct PublishMarker {
+    session: Arc<Session>,
+    table: String,
+}
+
+impl PublishMarker {
+    pub fn new(session: Arc<Session>, source: &CdcSource) -> Self {
+        Self { session, table: source.label() }
+    }
+
+    /// Mark the outbox row `id` published now
+    pub async fn mark(&self, id: Uuid) -> anyhow::Result<()> {
+        self.session
+            .query_unpaged(
+                format!("UPDATE {} SET published_at = ? WHERE id = ?", self.table),
+                (Utc::now(), id),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+/// Result of one janitor pass over all outbox tables
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct OutboxCleanup {
+    pub rows_scanned: usize,
+    pub rows_reclaimed: usize,
+    /// Rows past retention that were never marked published
+    pub rows_unpublished: usize,
+    /// Rows not marked published yet, whatever their age
+    pub rows_pending: usize,
+    pub errors: Vec<String>,
+}
+
+pub struct OutboxJanitor {
+    session: Arc<Session>,
+    tables: Vec<CdcSource>,
+    retention: OutboxRetention,
+    metrics: MetricsHandle,
+}
+
+impl OutboxJanitor {
+    pub fn new(session: Arc<Session>, tables: Vec<CdcSource>, retention: OutboxRetention) -> Self {
+        Self {
+            session,
+            tables,
+            retention,
+            metrics: MetricsHandle::noop(),
+        }
+    }
+
+    pub fn with_metrics(mut self, metrics: MetricsHandle) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Reclaim published rows of every outbox table
+    async fn clean(&self) -> OutboxCleanup {
+        let mut cleanup = OutboxCleanup::default();
+        let Ok(retention) = chrono::Duration::from_std(self.retention.retention) else {
+            cleanup.errors.push("retention out of range".to_string());
+            return cleanup;
+        };
+        let cutoff = Utc::now() - retention;
+
+        for table in &self.tables {
+            if let Err(e) = self.clean_table(&table.label(), cutoff, &mut cleanup).await {
+                tracing::warn!(table = %table.label(), error = %e, "Outbox cleanup failed");
+                cleanup.errors.push(format!("{}: {}", table.label(), e));
+            }
+        }
+
+        self.metrics.record_outbox_cleanup(cleanup.rows_reclaimed as u64, cleanup.rows_unpublished, cleanup.rows_pending);
+        tracing::info!(
+            scanned = cleanup.rows_scanned,
+            reclaimed = cleanup.rows_reclaimed,
+            unpublished = cleanup.rows_unpublished,
+            pending = cleanup.rows_pending,
+            "🧹 Outbox cleanup pass complete"
+        );
+        cleanup
+    }
+
+    async fn clean_table(&self, table: &str, cutoff: DateTime<Utc>, cleanup: &mut OutboxCleanup) -> anyhow::Result<()> {
+        let mut rows = self.session
+            .query_iter(format!("SELECT id, created_at, published_at FROM {}", table), &[])
+            .await?
+            .rows_stream::<(Uuid, Option<DateTime<Utc>>, Option<DateTime<Utc>>)>()?;
+
+        let mut reclaim = Vec::new();
+        while let Some((id, created_at, published_at)) = rows.try_next().await? {
+            cleanup.rows_scanned += 1;
+            if published_at.is_none() {
+                cleanup.rows_pending += 1;
+            }
+            match OutboxRetention::action(cutoff, created_at, published_at) {
+                RowAction::Reclaim if reclaim.len() < self.retention.max_rows_per_pass => reclaim.push(id),
+                RowAction::Unpublished => cleanup.rows_unpublished += 1,
+                _ => {}
+            }
+        }
+
+        let delete = format!("DELETE FROM {} WHERE id = ?", table);
+        for id in reclaim {
+            self.session.query_unpaged(delete.as_str(), (id,)).await?;
+            cleanup.rows_reclaimed += 1;
+        }
+        Ok(())
+    }
+}
+
+impl Actor for OutboxJanitor {
+    type Args = Self;
+    type Error = Infallible;
+
+    async fn on_start(
+        state: Self::Args,
+        actor_ref: ActorRef<Self>
+    ) -> Result<Self, Self::Error> {
+        tracing::info!(
+            retention_secs = state.retention.retention.as_secs(),
+            interval_secs = state.retention.interval.as_secs(),
+            tables = state.tables.len(),
+            "OutboxJanitor started"
+        );
+
+        // The first tick completes immediately - skip it, startup is busy enough
+        let interval = state.retention.interval;
+        let weak_ref = actor_ref.downgrade();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                match weak_ref.upgrade() {
+                    Some(actor_ref) => {
+                        let _ = actor_ref.tell(CleanOutbox).send().await;
+                    }
+                    None => break,
+                }
+            }
+        });
+
+        Ok(state)
+    }
+}
+
+// ============================================================================
+// Messages
+// ============================================================================
+
+/// Run a cleanup pass now
+pub struct CleanOutbox;
+
+impl Message<CleanOutbox> for OutboxJanitor {
+    type Reply = Result<OutboxCleanup, String>;
+
+    async fn handle(&mut self, _msg: CleanOutbox, _ctx: &mut Context<Self, Self::Reply>) -> Self::Reply {
+        Ok(self.clean().await)
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_rows_published_before_cutoff_are_reclaimed() {
+        let cutoff = Utc::now() - chrono::Duration::hours(1);
+        let before = cutoff - chrono::Duration::minutes(5);
+        let after = cutoff + chrono::Duration::minutes(5);
+
+        assert_eq!(OutboxRetention::action(cutoff, Some(before), Some(before)), RowAction::Reclaim);
+        // Published recently, even though written long ago
+        assert_eq!(OutboxRetention::action(cutoff, Some(before), Some(after)), RowAction::Keep);
+        // Never published: kept, and flagged once past retention
+        assert_eq!(OutboxRetention::action(cutoff, Some(after), None), RowAction::Keep);
+        assert_eq!(OutboxRetention::action(cutoff, Some(before), None), RowAction::Unpublished);
+        assert_eq!(OutboxRetention::action(cutoff, None, None), RowAction::Keep);
+    }
+}
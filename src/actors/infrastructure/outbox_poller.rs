@@ -0,0 +1,397 @@
+use chrono::{DateTime, Utc};
+use futures_util::TryStreamExt;
+use scylla::client::session::Session;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::config::CdcSource;
+use crate::metrics::MetricsHandle;
+use crate::utils::{record_retry, retry_with_backoff_recorded, RetryConfig, RetryResult};
+use super::cdc_checkpoint::CdcCheckpoint;
+use super::cdc_processor::OutboxCDCConsumer;
+use super::outbox_row::{OutboxRow, RowChange};
+use super::relay_drain::RelayDrain;
+
+// ============================================================================
+// Outbox Poller - Relaying Without CDC Streaming (cdc.mode = "polling")
+// ============================================================================
+//
+// Some environments cannot run the scylla-cdc log reader: CDC disabled on
+// the cluster, no access to the log tables or the stream descriptions. In
+// polling mode the CdcProcessor runs an OutboxPoller instead, which reads
+// the outbox table itself every poll interval:
+//
+//   SELECT ... FROM <outbox> WHERE created_at > <cursor - POLL_LOOKBACK>
+//
+// The rows are relayed oldest first (per aggregate in sequence order)
+// through the same OutboxCDCConsumer as CDC rows - publish retries, DLQ,
+// deduplication, approval, gap detection, stream audit and metrics all
+// behave as in streaming mode. Rows already marked published are skipped.
+//
+// The cursor is the newest created_at relayed. It is persisted in the
+// relay's cdc_offsets checkpoint (same consumer_id as the log reader, so
+// switching modes resumes instead of starting over). POLL_LOOKBACK re-reads
+// the last minute on every poll: rows committed a little after their
+// created_at (clock skew between writers, slow batches) are still picked
+// up, and the ids relayed within the window are remembered so they are not
+// published twice.
+//
+// A poll whose read still fails after its retries (retry_*{operation="cdc_poll"})
+// ends the poller like a failed log reader - the coordinator restarts the
+// processor.
+//
+// Each poll is a filtered full-table read: polling is a fallback for
+// outboxes the janitor keeps small, not a replacement for streaming. Only
+// outbox tables can be polled; row changes ([[cdc.tables]] with a topic)
+// need the CDC log.
+//
+// ============================================================================
+
+/// How far before the cursor each poll reads again
+const POLL_LOOKBACK: chrono::Duration = chrono::Duration::minutes(1);
+
+/// Rows relayed per poll; a full poll is followed by the next right away
+const MAX_ROWS_PER_POLL: usize = 1000;
+
+const OUTBOX_COLUMNS: &str = "id, aggregate_id, aggregate_type, event_id, event_version, sequence_number, \
+     event_type, payload, partition_key, causation_id, correlation_id, origin_region, created_at, published_at, \
+     aggregate_event_type, variant_type, publish_after";
+
+/// An outbox row read from the table, as the consumer reads a CDC insert
+///
+/// Read by column name: OUTBOX_COLUMNS has more columns than a tuple holds.
+#[derive(Debug, Clone, Default, scylla::DeserializeRow)]
+pub(crate) struct PolledOutboxRow {
+    id: Uuid,
+    aggregate_id: Option<Uuid>,
+    aggregate_type: Option<String>,
+    event_id: Option<Uuid>,
+    event_version: Option<i32>,
+    sequence_number: Option<i64>,
+    event_type: Option<String>,
+    payload: Option<String>,
+    partition_key: Option<String>,
+    causation_id: Option<Uuid>,
+    correlation_id: Option<Uuid>,
+    origin_region: Option<String>,
+    created_at: Option<DateTime<Utc>>,
+    published_at: Option<DateTime<Utc>>,
+    aggregate_event_type: Option<String>,
+    variant_type: Option<String>,
+    publish_after: Option<DateTime<Utc>>,
+}
+
+impl OutboxRow for PolledOutboxRow {
+    /// The row as it is now - every polled row is an insert
+    fn is_insert(&self) -> bool {
+        true
+    }
+
+    fn operation_name(&self) -> String {
+        "Polled".to_string()
+    }
+
+    fn uuid(&self, column: &str) -> Option<Uuid> {
+        match column {
+            "id" => Some(self.id),
+            "aggregate_id" => self.aggregate_id,
+            "event_id" => self.event_id,
+            "causation_id" => self.causation_id,
+            "correlation_id" => self.correlation_id,
+            _ => None,
+        }
+    }
+
+    fn text(&self, column: &str) -> Option<String> {
+        match column {
+            "aggregate_type" => self.aggregate_type.clone(),
+            "event_type" => self.event_type.clone(),
+            "payload" => self.payload.clone(),
+            "partition_key" => self.partition_key.clone(),
+            "origin_region" => self.origin_region.clone(),
+            "aggregate_event_type" => self.aggregate_event_type.clone(),
+            "variant_type" => self.variant_type.clone(),
+            _ => None,
+        }
+    }
+
+    fn int(&self, column: &str) -> Option<i32> {
+        match column {
+            "event_version" => self.event_version,
+            _ => None,
+        }
+    }
+
+    fn bigint(&self, column: &str) -> Option<i64> {
+        match column {
+            "sequence_number" => self.sequence_number,
+            _ => None,
+        }
+    }
+
+    fn timestamp(&self, column: &str) -> Option<DateTime<Utc>> {
+        match column {
+            "created_at" => self.created_at,
+            "published_at" => self.published_at,
+            "publish_after" => self.publish_after,
+            _ => None,
+        }
+    }
+
+    fn row_change(&self) -> Option<RowChange> {
+        Some(RowChange::Upsert)
+    }
+
+    fn json(&self, column: &str) -> Option<Value> {
+        self.text(column)
+            .map(Value::from)
+            .or_else(|| self.uuid(column).map(|v| Value::from(v.to_string())))
+            .or_else(|| self.bigint(column).map(Value::from))
+            .or_else(|| self.int(column).map(Value::from))
+            .or_else(|| self.timestamp(column).map(|ts| Value::from(ts.to_rfc3339())))
+    }
+}
+
+/// Where the poller is in an outbox table
+#[derive(Debug)]
+pub(crate) struct PollCursor {
+    /// Newest created_at relayed (or where polling started)
+    position: DateTime<Utc>,
+    /// Rows relayed within the lookback window
+    relayed: HashMap<Uuid, DateTime<Utc>>,
+}
+
+impl PollCursor {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self { position: start, relayed: HashMap::new() }
+    }
+
+    pub fn position(&self) -> DateTime<Utc> {
+        self.position
+    }
+
+    /// Lower bound (exclusive) of the next poll's created_at
+    pub fn since(&self) -> DateTime<Utc> {
+        self.position - POLL_LOOKBACK
+    }
+
+    /// The rows of a poll still to relay, oldest first, at most `limit`
+    pub fn select(&self, rows: Vec<PolledOutboxRow>, limit: usize) -> Vec<PolledOutboxRow> {
+        let mut rows: Vec<PolledOutboxRow> = rows
+            .into_iter()
+            .filter(|row| row.created_at.is_some() && row.published_at.is_none())
+            .filter(|row| !self.relayed.contains_key(&row.id))
+            .collect();
+        rows.sort_by_key(|row| (row.created_at, row.sequence_number));
+        rows.truncate(limit);
+        rows
+    }
+
+    /// Record a relayed row and forget those out of the lookback window
+    pub fn relayed(&mut self, id: Uuid, created_at: DateTime<Utc>) {
+        self.relayed.insert(id, created_at);
+        if created_at > self.position {
+            self.position = created_at;
+            let since = self.since();
+            self.relayed.retain(|_, at| *at > since);
+        }
+    }
+}
+
+/// Relays an outbox table by polling it
+pub(crate) struct OutboxPoller {
+    session: Arc<Session>,
+    source: CdcSource,
+    consumer: OutboxCDCConsumer,
+    checkpoint: Arc<CdcCheckpoint>,
+    drain: Arc<RelayDrain>,
+    cursor: PollCursor,
+    interval: Duration,
+    metrics: MetricsHandle,
+}
+
+impl OutboxPoller {
+    pub fn new(
+        session: Arc<Session>,
+        source: CdcSource,
+        consumer: OutboxCDCConsumer,
+        checkpoint: Arc<CdcCheckpoint>,
+        drain: Arc<RelayDrain>,
+        start: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            session,
+            source,
+            consumer,
+            checkpoint,
+            drain,
+            cursor: PollCursor::new(start),
+            interval: Duration::from_secs(1),
+            metrics: MetricsHandle::noop(),
+        }
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: MetricsHandle) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Poll until the drain begins; fails once a poll cannot read the table
+    pub async fn run(mut self) -> anyhow::Result<()> {
+        while !self.drain.is_draining() {
+            if self.poll().await? < MAX_ROWS_PER_POLL {
+                tokio::time::sleep(self.interval).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Relay the rows written since the cursor; returns how many
+    async fn poll(&mut self) -> anyhow::Result<usize> {
+        let since = self.cursor.since();
+        let poller = &*self;
+        let (result, attempts) = retry_with_backoff_recorded(RetryConfig::default(), |_| poller.fetch(since)).await;
+        let fetched = matches!(result, RetryResult::Success(_));
+        record_retry(&self.metrics, "cdc_poll", &attempts, fetched);
+        let rows = match result {
+            RetryResult::Success(rows) => self.cursor.select(rows, MAX_ROWS_PER_POLL),
+            RetryResult::Failed(e) | RetryResult::PermanentFailure(e) => return Err(e),
+        };
+
+        let mut relayed = 0;
+        let mut last_event_id = None;
+        for row in rows {
+            // Shutting down: the rest is polled again on the next start
+            let Some(in_flight) = self.drain.enter() else { break };
+            match self.consumer.consume_row(&row, Some(in_flight)).await {
+                Ok(event_id) => last_event_id = event_id.or(last_event_id),
+                Err(e) => tracing::warn!(id = %row.id, table = %self.source.label(), error = %e, "Skipping unreadable outbox row"),
+            }
+            if let Some(created_at) = row.created_at {
+                self.cursor.relayed(row.id, created_at);
+            }
+            relayed += 1;
+        }
+
+        // The checkpoint must not pass rows still being published
+        self.consumer.flush().await;
+        if relayed > 0 {
+            self.checkpoint.advance(self.cursor.position(), last_event_id);
+            tracing::debug!(rows = relayed, table = %self.source.label(), "Polled outbox rows relayed");
+        }
+        Ok(relayed)
+    }
+
+    async fn fetch(&self, since: DateTime<Utc>) -> anyhow::Result<Vec<PolledOutboxRow>> {
+        let rows: Vec<PolledOutboxRow> = self
+            .session
+            .query_iter(
+                format!(
+                    "SELECT {} FROM {} WHERE created_at > ? ALLOW FILTERING",
+                    OUTBOX_COLUMNS,
+                    self.source.label()
+                ),
+                (since,),
+            )
+            .await?
+            .rows_stream::<PolledOutboxRow>()?
+            .try_collect()
+            .await?;
+        Ok(rows)
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messaging::test_support::RecordingPublisher;
+    use crate::test_support::RecordingSchedule;
+    use crate::actors::infrastructure::outbox_scheduler::due_bucket;
+
+    fn row(created_at: DateTime<Utc>, sequence_number: i64) -> PolledOutboxRow {
+        PolledOutboxRow {
+            id: Uuid::new_v4(),
+            aggregate_id: Some(Uuid::new_v4()),
+            event_id: Some(Uuid::new_v4()),
+            sequence_number: Some(sequence_number),
+            event_type: Some("OrderCreated".to_string()),
+            payload: Some("{}".to_string()),
+            created_at: Some(created_at),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_cursor_relays_each_row_once_in_order() {
+        let start = Utc::now();
+        let mut cursor = PollCursor::new(start);
+        let (first, second) = (row(start + chrono::Duration::seconds(1), 2), row(start + chrono::Duration::seconds(1), 1));
+        let published = PolledOutboxRow { published_at: Some(start), ..row(start, 3) };
+
+        let selected = cursor.select(vec![first.clone(), second.clone(), published], 10);
+        assert_eq!(selected.iter().map(|r| r.id).collect::<Vec<_>>(), vec![second.id, first.id]);
+        assert_eq!(cursor.select(selected.clone(), 1).len(), 1);
+        for row in &selected {
+            cursor.relayed(row.id, row.created_at.unwrap());
+        }
+        assert_eq!(cursor.position(), start + chrono::Duration::seconds(1));
+
+        // Re-read within the lookback, plus a row committed late
+        let late = row(start - chrono::Duration::seconds(5), 1);
+        let selected = cursor.select(vec![first, second, late.clone()], 10);
+        assert_eq!(selected.iter().map(|r| r.id).collect::<Vec<_>>(), vec![late.id]);
+    }
+
+    #[tokio::test]
+    async fn test_polled_row_relayed_like_cdc_insert() {
+        let publisher = Arc::new(RecordingPublisher::new());
+        let consumer = OutboxCDCConsumer::new(publisher.clone(), None);
+        let polled = row(Utc::now(), 4);
+
+        let event_id = consumer.consume_row(&polled, None).await.unwrap();
+
+        assert_eq!(event_id, polled.event_id);
+        let published = publisher.published();
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].topic, "OrderCreated");
+        assert_eq!(published[0].key, polled.aggregate_id.unwrap().to_string());
+    }
+
+    #[tokio::test]
+    async fn test_future_row_held_until_released() {
+        let publisher = Arc::new(RecordingPublisher::new());
+        let schedule = Arc::new(RecordingSchedule::new());
+        let consumer = OutboxCDCConsumer::new(publisher.clone(), None).with_scheduler(schedule.clone());
+        let publish_after = Utc::now() + chrono::Duration::hours(2);
+        let future = PolledOutboxRow { publish_after: Some(publish_after), ..row(Utc::now(), 4) };
+
+        consumer.consume_row(&future, None).await.unwrap();
+
+        assert_eq!(publisher.count(), 0);
+        let scheduled = schedule.scheduled();
+        assert_eq!(scheduled.len(), 1);
+        assert_eq!((scheduled[0].id, scheduled[0].publish_after), (future.id, publish_after));
+        assert_eq!(scheduled[0].due_bucket, due_bucket(publish_after));
+
+        // Released: re-inserted into the outbox without publish_after
+        let released = PolledOutboxRow { publish_after: None, created_at: Some(Utc::now()), ..future };
+        consumer.consume_row(&released, None).await.unwrap();
+
+        let published = publisher.published();
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].topic, "OrderCreated");
+        assert_eq!(schedule.scheduled().len(), 1);
+    }
+}
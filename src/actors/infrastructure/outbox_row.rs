@@ -0,0 +1,134 @@
+use chrono::{DateTime, Utc};
+use scylla::value::CqlValue;
+use scylla_cdc::consumer::{CDCRow, OperationType};
+use serde_json::Value;
+use uuid::Uuid;
+
+// ============================================================================
+// Outbox Row Access - What the CDC Consumer Reads from a Change
+// ============================================================================
+//
+// CDCRow can only be built by scylla-cdc from a live log table, which made
+// the consumer's extraction logic untestable without a cluster. The
+// consumer reads rows through this trait instead; CDCRow implements it for
+// production and test_support::SyntheticOutboxRow for unit tests.
+//
+// Accessors return None for NULL, missing, or differently typed columns.
+//
+// The table relay (non-outbox tables) reads rows through the same trait:
+// `row_change` classifies the change and `json` renders any column.
+//
+// ============================================================================
+
+pub(crate) trait OutboxRow {
+    /// Row insert or post-image - the only changes that carry a new event
+    fn is_insert(&self) -> bool;
+
+    /// Change kind for logging
+    fn operation_name(&self) -> String;
+
+    fn uuid(&self, column: &str) -> Option<Uuid>;
+
+    fn text(&self, column: &str) -> Option<String>;
+
+    fn int(&self, column: &str) -> Option<i32>;
+
+    fn bigint(&self, column: &str) -> Option<i64>;
+
+    fn timestamp(&self, column: &str) -> Option<DateTime<Utc>>;
+
+    /// Upsert or delete; None for changes that carry no row state (pre-images, range deletes)
+    fn row_change(&self) -> Option<RowChange>;
+
+    /// Any column as JSON; None for NULL, missing, or unsupported types
+    fn json(&self, column: &str) -> Option<Value>;
+}
+
+/// What a change did to its row
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum RowChange {
+    Upsert,
+    Delete,
+}
+
+impl RowChange {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RowChange::Upsert => "upsert",
+            RowChange::Delete => "delete",
+        }
+    }
+}
+
+impl OutboxRow for CDCRow<'_> {
+    fn is_insert(&self) -> bool {
+        is_insert_operation(&self.operation)
+    }
+
+    fn operation_name(&self) -> String {
+        self.operation.to_string()
+    }
+
+    fn uuid(&self, column: &str) -> Option<Uuid> {
+        self.get_value(column).as_ref().and_then(|v| v.as_uuid())
+    }
+
+    fn text(&self, column: &str) -> Option<String> {
+        self.get_value(column).as_ref().and_then(|v| v.as_text()).map(|s| s.to_string())
+    }
+
+    fn int(&self, column: &str) -> Option<i32> {
+        self.get_value(column).as_ref().and_then(|v| v.as_int())
+    }
+
+    fn bigint(&self, column: &str) -> Option<i64> {
+        self.get_value(column).as_ref().and_then(|v| v.as_bigint())
+    }
+
+    fn timestamp(&self, column: &str) -> Option<DateTime<Utc>> {
+        self.get_value(column)
+            .as_ref()
+            .and_then(|v| v.as_cql_timestamp())
+            .and_then(|ts| DateTime::from_timestamp_millis(ts.0))
+    }
+
+    fn row_change(&self) -> Option<RowChange> {
+        row_change_of(&self.operation)
+    }
+
+    fn json(&self, column: &str) -> Option<Value> {
+        self.get_value(column).as_ref().and_then(cql_to_json)
+    }
+}
+
+pub(crate) fn is_insert_operation(operation: &OperationType) -> bool {
+    matches!(operation, OperationType::RowInsert | OperationType::PostImage)
+}
+
+pub(crate) fn row_change_of(operation: &OperationType) -> Option<RowChange> {
+    match operation {
+        OperationType::RowInsert | OperationType::RowUpdate | OperationType::PostImage => Some(RowChange::Upsert),
+        OperationType::RowDelete | OperationType::PartitionDelete => Some(RowChange::Delete),
+        _ => None,
+    }
+}
+
+/// JSON for the CQL types read models and outboxes use
+fn cql_to_json(value: &CqlValue) -> Option<Value> {
+    let json = match value {
+        CqlValue::Ascii(v) | CqlValue::Text(v) => Value::from(v.as_str()),
+        CqlValue::Boolean(v) => Value::from(*v),
+        CqlValue::TinyInt(v) => Value::from(*v),
+        CqlValue::SmallInt(v) => Value::from(*v),
+        CqlValue::Int(v) => Value::from(*v),
+        CqlValue::BigInt(v) => Value::from(*v),
+        CqlValue::Float(v) => Value::from(*v),
+        CqlValue::Double(v) => Value::from(*v),
+        CqlValue::Uuid(v) => Value::from(v.to_string()),
+        CqlValue::Timeuuid(v) => Value::from(Uuid::from(*v).to_string()),
+        CqlValue::Timestamp(ts) => Value::from(DateTime::from_timestamp_millis(ts.0)?.to_rfc3339()),
+        CqlValue::List(items) | CqlValue::Set(items) => Value::Array(items.iter().filter_map(cql_to_json).collect()),
+        _ => return None,
+    };
+    Some(json)
+}
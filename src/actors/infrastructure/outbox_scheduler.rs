@@ -0,0 +1,347 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures_util::TryStreamExt;
+use scylla::client::session::Session;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::metrics::MetricsHandle;
+use super::approval_gate::lwt_applied;
+
+// ============================================================================
+// Outbox Scheduler - Events Published at a Later Time
+// ============================================================================
+//
+// Some events are written now but meant for later ("remind the customer in
+// 24h"). EventEnvelope::with_publish_after sets the outbox row's
+// `publish_after`; the CDC relay (and the poller) hand such a row here
+// instead of publishing it while it is not due:
+//
+//   relay      publish_after in the future -> row copied to
+//              scheduled_publications (INSERT IF NOT EXISTS), outbox row
+//              left unpublished
+//   scheduler  every `interval`: rows due -> re-inserted into their outbox
+//              table (created_at now, publish_after NULL), then relayed
+//              like any other write
+//
+// The copy is needed because outbox rows expire after a day. Scheduled rows
+// are partitioned by the hour they are due in (`due_bucket`), clustered by
+// publish_after, so a pass reads only the due rows of the buckets from its
+// cursor up to now - never the events scheduled further ahead. The cursor
+// is the oldest bucket that may still hold due rows: it starts `lookback`
+// before the start, stays at a bucket with a failed release, and each pass
+// also rescans the bucket before it (clock skew between instances).
+//
+// A release first deletes the scheduled row with an LWT, so several
+// instances never release the same event twice; if the outbox insert then
+// fails, the row is written back and retried on the next pass. Events are
+// published no earlier than `publish_after` and at most one interval later.
+//
+// Scheduled sequences are reported to the gap detector as handled, so gap
+// backfill cannot publish them early. The stream auditor sees a released
+// event as out of order, which for a scheduled event is expected.
+//
+//   outbox_scheduled_pending          due events not released (retried)
+//   outbox_scheduled_released_total   events handed back to the relay
+//
+// ============================================================================
+
+/// How often due events are released unless configured otherwise
+pub const DEFAULT_SCHEDULE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How far back the first pass looks for events that fell due while stopped
+pub const DEFAULT_SCHEDULE_LOOKBACK: Duration = Duration::from_secs(7 * 24 * 3600);
+
+/// Width of a due_bucket
+const BUCKET_SECONDS: i64 = 3600;
+
+const SCHEDULED_COLUMNS: &str = "due_bucket, id, outbox_table, aggregate_id, aggregate_type, event_id, event_version, \
+     correlation_id, causation_id, sequence_number, event_type, aggregate_event_type, payload, partition_key, \
+     origin_region, publish_after, scheduled_at";
+
+/// The due_bucket of an event due at `at`: the start of its hour
+pub fn due_bucket(at: DateTime<Utc>) -> DateTime<Utc> {
+    let seconds = at.timestamp();
+    DateTime::from_timestamp(seconds - seconds.rem_euclid(BUCKET_SECONDS), 0).unwrap_or(at)
+}
+
+/// The buckets from the one of `from` to the one of `to`, oldest first
+fn buckets_between(from: DateTime<Utc>, to: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+    let last = due_bucket(to);
+    let mut bucket = due_bucket(from);
+    let mut buckets = Vec::new();
+    while bucket <= last {
+        buckets.push(bucket);
+        bucket += chrono::Duration::seconds(BUCKET_SECONDS);
+    }
+    buckets
+}
+
+/// An outbox event held until `publish_after`
+#[derive(Debug, Clone, PartialEq, scylla::SerializeRow, scylla::DeserializeRow)]
+pub struct ScheduledPublication {
+    /// `due_bucket(publish_after)`, the partition
+    pub due_bucket: DateTime<Utc>,
+    /// Outbox row id
+    pub id: Uuid,
+    /// `keyspace.table` the row was relayed from, and is released into
+    pub outbox_table: String,
+    pub aggregate_id: Uuid,
+    pub aggregate_type: Option<String>,
+    pub event_id: Option<Uuid>,
+    pub event_version: Option<i32>,
+    pub correlation_id: Option<Uuid>,
+    pub causation_id: Option<Uuid>,
+    pub sequence_number: Option<i64>,
+    pub event_type: String,
+    pub aggregate_event_type: Option<String>,
+    /// The payload as the outbox stored it (sealed if it was)
+    pub payload: String,
+    pub partition_key: Option<String>,
+    pub origin_region: Option<String>,
+    pub publish_after: DateTime<Utc>,
+    pub scheduled_at: DateTime<Utc>,
+}
+
+/// Result of one release pass
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ScheduleRelease {
+    /// Events handed back to the relay
+    pub released: u64,
+    /// Due events that could not be released (retried next pass)
+    pub failed: usize,
+}
+
+/// Where relays hold events whose publish_after is still ahead
+///
+/// The OutboxScheduler in production; test doubles
+/// (test_support::RecordingSchedule) keep the publications in memory.
+#[async_trait]
+pub trait ScheduleSink: Send + Sync {
+    async fn schedule(&self, publication: &ScheduledPublication) -> Result<()>;
+}
+
+/// Holds scheduled outbox events and releases them once due
+pub struct OutboxScheduler {
+    session: Arc<Session>,
+    interval: Duration,
+    lookback: Duration,
+    /// Oldest bucket that may still hold due events; None before the first pass
+    cursor: Mutex<Option<DateTime<Utc>>>,
+    metrics: MetricsHandle,
+}
+
+impl OutboxScheduler {
+    pub fn new(session: Arc<Session>) -> Self {
+        Self {
+            session,
+            interval: DEFAULT_SCHEDULE_INTERVAL,
+            lookback: DEFAULT_SCHEDULE_LOOKBACK,
+            cursor: Mutex::new(None),
+            metrics: MetricsHandle::noop(),
+        }
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// How far back the first pass looks for due events
+    pub fn with_lookback(mut self, lookback: Duration) -> Self {
+        self.lookback = lookback;
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: MetricsHandle) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Hold an event until its `publish_after`
+    ///
+    /// A re-delivered row that is already scheduled is left as it is.
+    pub async fn schedule(&self, publication: &ScheduledPublication) -> Result<()> {
+        let inserted = self
+            .session
+            .query_unpaged(
+                format!(
+                    "INSERT INTO scheduled_publications ({}) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
+                     IF NOT EXISTS",
+                    SCHEDULED_COLUMNS
+                ),
+                publication,
+            )
+            .await?;
+        if !lwt_applied(inserted)? {
+            tracing::debug!(event_id = %publication.id, "Event already scheduled");
+        }
+        Ok(())
+    }
+
+    /// Release every scheduled event that is due
+    ///
+    /// Reads the buckets from the cursor up to now; a failed scan leaves the
+    /// cursor where it was.
+    pub async fn release_due(&self) -> Result<ScheduleRelease> {
+        let now = Utc::now();
+        let from = match *self.cursor.lock().unwrap() {
+            Some(cursor) => cursor - chrono::Duration::seconds(BUCKET_SECONDS),
+            None => now - chrono::Duration::from_std(self.lookback).unwrap_or(chrono::Duration::zero()),
+        };
+
+        let mut release = ScheduleRelease::default();
+        let mut cursor = due_bucket(now);
+        for bucket in buckets_between(from, now) {
+            let failed = release.failed;
+            self.release_bucket(bucket, now, &mut release).await?;
+            if release.failed > failed {
+                cursor = cursor.min(bucket);
+            }
+        }
+        *self.cursor.lock().unwrap() = Some(cursor);
+
+        self.metrics.record_outbox_schedule(release.released, release.failed);
+        Ok(release)
+    }
+
+    /// Release the events of one bucket that are due at `now`
+    async fn release_bucket(&self, bucket: DateTime<Utc>, now: DateTime<Utc>, release: &mut ScheduleRelease) -> Result<()> {
+        let mut rows = self
+            .session
+            .query_iter(
+                format!(
+                    "SELECT {} FROM scheduled_publications WHERE due_bucket = ? AND publish_after <= ?",
+                    SCHEDULED_COLUMNS
+                ),
+                (bucket, now),
+            )
+            .await?
+            .rows_stream::<ScheduledPublication>()?;
+
+        while let Some(publication) = rows.try_next().await? {
+            match self.release(&publication).await {
+                Ok(true) => {
+                    tracing::info!(
+                        event_id = %publication.id,
+                        event_type = %publication.event_type,
+                        publish_after = %publication.publish_after,
+                        "⏰ Scheduled event released"
+                    );
+                    release.released += 1;
+                }
+                // Released by another instance
+                Ok(false) => {}
+                Err(e) => {
+                    tracing::warn!(event_id = %publication.id, error = %e, "Failed to release scheduled event");
+                    release.failed += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Claim a due event and hand it back to the relay via its outbox table
+    ///
+    /// Returns false when another instance claimed it first.
+    async fn release(&self, publication: &ScheduledPublication) -> Result<bool> {
+        let claimed = self
+            .session
+            .query_unpaged(
+                "DELETE FROM scheduled_publications WHERE due_bucket = ? AND publish_after = ? AND id = ? IF EXISTS",
+                (publication.due_bucket, publication.publish_after, publication.id),
+            )
+            .await?;
+        if !lwt_applied(claimed)? {
+            return Ok(false);
+        }
+
+        let inserted = self
+            .session
+            .query_unpaged(
+                format!(
+                    "INSERT INTO {} (id, aggregate_id, aggregate_type, event_id, event_version, event_type, \
+                     aggregate_event_type, sequence_number, payload, partition_key, causation_id, correlation_id, \
+                     origin_region, created_at, attempts) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 0)",
+                    publication.outbox_table
+                ),
+                (
+                    publication.id,
+                    publication.aggregate_id,
+                    &publication.aggregate_type,
+                    publication.event_id,
+                    publication.event_version,
+                    &publication.event_type,
+                    &publication.aggregate_event_type,
+                    publication.sequence_number,
+                    &publication.payload,
+                    &publication.partition_key,
+                    publication.causation_id,
+                    publication.correlation_id,
+                    &publication.origin_region,
+                    Utc::now(),
+                ),
+            )
+            .await;
+        if let Err(e) = inserted {
+            // Keep it scheduled for the next pass
+            self.schedule(publication).await?;
+            return Err(e.into());
+        }
+        Ok(true)
+    }
+
+    /// Release due events every `interval` in the background
+    pub fn spawn_releaser(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.release_due().await {
+                    tracing::warn!(error = %e, "Failed to release scheduled outbox events");
+                }
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl ScheduleSink for OutboxScheduler {
+    async fn schedule(&self, publication: &ScheduledPublication) -> Result<()> {
+        OutboxScheduler::schedule(self, publication).await
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_due_bucket_is_start_of_hour() {
+        assert_eq!(due_bucket(at("2026-03-02T09:00:00Z")), at("2026-03-02T09:00:00Z"));
+        assert_eq!(due_bucket(at("2026-03-02T09:59:59Z")), at("2026-03-02T09:00:00Z"));
+        assert_eq!(due_bucket(at("2026-03-02T10:00:01Z")), at("2026-03-02T10:00:00Z"));
+    }
+
+    #[test]
+    fn test_pass_reads_buckets_up_to_now_only() {
+        let buckets = buckets_between(at("2026-03-02T07:30:00Z"), at("2026-03-02T09:10:00Z"));
+        assert_eq!(
+            buckets,
+            vec![at("2026-03-02T07:00:00Z"), at("2026-03-02T08:00:00Z"), at("2026-03-02T09:00:00Z")]
+        );
+
+        // Within the current hour, just its bucket
+        assert_eq!(buckets_between(at("2026-03-02T09:00:00Z"), at("2026-03-02T09:10:00Z")).len(), 1);
+    }
+}
@@ -0,0 +1,192 @@
+use anyhow::{bail, Result};
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::{mpsc, Notify};
+
+// ============================================================================
+// Publish Pool - Bounded Concurrent Publishing per CDC Stream
+// ============================================================================
+//
+// Relaying rows one at a time means one slow publish (Redpanda retries)
+// stalls the whole stream. With a pool, the consumer hands each row to one
+// of `workers` tasks and moves on:
+//
+//   - rows go to the worker of their key (the aggregate), so the events of
+//     an aggregate are still published one after another, in order
+//   - every worker queues at most `queue_depth` rows; a full queue makes the
+//     consumer wait, which holds back the CDC reader (backpressure)
+//   - `flush` waits for every submitted row; the consumer flushes at the end
+//     of each CDC batch before advancing the checkpoint, so a checkpoint
+//     never covers a row that is still being published
+//
+// workers = 1 (the default) relays inline without a pool.
+//
+// ============================================================================
+
+/// Concurrency of the outbox relay, per CDC stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublishPoolConfig {
+    pub workers: usize,
+    /// Rows queued per worker before the CDC reader is held back
+    pub queue_depth: usize,
+}
+
+impl Default for PublishPoolConfig {
+    fn default() -> Self {
+        Self { workers: 1, queue_depth: 100 }
+    }
+}
+
+impl PublishPoolConfig {
+    pub fn is_concurrent(&self) -> bool {
+        self.workers > 1
+    }
+}
+
+pub(crate) struct PublishPool<J> {
+    workers: Vec<mpsc::Sender<J>>,
+    outstanding: Arc<Outstanding>,
+}
+
+/// Submitted jobs not finished yet
+#[derive(Default)]
+struct Outstanding {
+    count: AtomicUsize,
+    idle: Notify,
+}
+
+impl Outstanding {
+    fn finish(&self) {
+        if self.count.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.idle.notify_waiters();
+        }
+    }
+}
+
+impl<J: Send + 'static> PublishPool<J> {
+    /// Start the workers; they stop once the pool is dropped
+    pub fn spawn<F, Fut>(config: PublishPoolConfig, handler: F) -> Self
+    where
+        F: Fn(J) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let handler = Arc::new(handler);
+        let outstanding = Arc::new(Outstanding::default());
+        let workers = (0..config.workers.max(1))
+            .map(|_| {
+                let (sender, mut receiver) = mpsc::channel::<J>(config.queue_depth.max(1));
+                let handler = handler.clone();
+                let outstanding = outstanding.clone();
+                tokio::spawn(async move {
+                    while let Some(job) = receiver.recv().await {
+                        handler(job).await;
+                        outstanding.finish();
+                    }
+                });
+                sender
+            })
+            .collect();
+        Self { workers, outstanding }
+    }
+
+    /// Queue `job` on the worker of `key`; waits while that worker's queue is full
+    pub async fn submit(&self, key: impl Hash, job: J) -> Result<()> {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        key.hash(&mut hasher);
+        let worker = &self.workers[(hasher.finish() % self.workers.len() as u64) as usize];
+
+        self.outstanding.count.fetch_add(1, Ordering::SeqCst);
+        let job = match worker.try_send(job) {
+            Ok(()) => return Ok(()),
+            Err(mpsc::error::TrySendError::Full(job)) => {
+                tracing::debug!("Publish queue full - holding back the CDC reader");
+                job
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => {
+                self.outstanding.finish();
+                bail!("Publish worker stopped");
+            }
+        };
+        if worker.send(job).await.is_err() {
+            self.outstanding.finish();
+            bail!("Publish worker stopped");
+        }
+        Ok(())
+    }
+
+    /// Wait until every submitted job finished
+    pub async fn flush(&self) {
+        loop {
+            let idle = self.outstanding.idle.notified();
+            if self.outstanding.count.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+            idle.await;
+        }
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_jobs_of_one_key_stay_in_order() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorder = seen.clone();
+        let pool = PublishPool::spawn(PublishPoolConfig { workers: 4, queue_depth: 2 }, move |(key, n): (u32, u32)| {
+            let recorder = recorder.clone();
+            async move {
+                // Later jobs finish faster - only the per-key queue keeps them in order
+                tokio::time::sleep(Duration::from_millis(u64::from(10 - n))).await;
+                recorder.lock().unwrap().push((key, n));
+            }
+        });
+
+        for n in 0..10 {
+            for key in 0..3 {
+                pool.submit(key, (key, n)).await.unwrap();
+            }
+        }
+        pool.flush().await;
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 30);
+        for key in 0..3 {
+            let order: Vec<u32> = seen.iter().filter(|(k, _)| *k == key).map(|(_, n)| *n).collect();
+            assert_eq!(order, (0..10).collect::<Vec<_>>());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_holds_back_submit() {
+        let release = Arc::new(tokio::sync::Semaphore::new(0));
+        let gate = release.clone();
+        let pool = Arc::new(PublishPool::spawn(PublishPoolConfig { workers: 1, queue_depth: 1 }, move |_: u32| {
+            let gate = gate.clone();
+            async move { gate.acquire().await.unwrap().forget() }
+        }));
+
+        // One job in the worker, one in the queue - the third has to wait
+        pool.submit(0, 1).await.unwrap();
+        pool.submit(0, 2).await.unwrap();
+        let third = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.submit(0, 3).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!third.is_finished(), "submit must wait while the queue is full");
+
+        release.add_permits(3);
+        third.await.unwrap().unwrap();
+        tokio::time::timeout(Duration::from_secs(1), pool.flush()).await.unwrap();
+    }
+}
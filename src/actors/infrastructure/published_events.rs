@@ -0,0 +1,61 @@
+use anyhow::Result;
+use chrono::Utc;
+use scylla::client::session::Session;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+// ============================================================================
+// Published Events - Deduplication of CDC Re-Deliveries
+// ============================================================================
+//
+// The CDC relay is at-least-once: after a restart it resumes from its
+// checkpoint minus a replay slack, and scylla-cdc may hand a row to a
+// consumer again after a reader error. Without a record of what went out,
+// every such row is published a second time and each downstream consumer
+// has to deduplicate.
+//
+// The relay records every successful publish in `published_events`, keyed
+// by event_id (the outbox row id for legacy rows without one), and skips
+// rows whose event is already recorded. Entries expire after the TTL
+// (CDC_DEDUP_TTL_SECS), which must exceed the longest expected replay.
+//
+// Best effort by design: a failed lookup publishes anyway (a duplicate is
+// better than a lost event), and a crash between publish and record still
+// re-publishes that one event. The producer itself is idempotent
+// (enable.idempotence), so its own retries never duplicate a message.
+//
+// ============================================================================
+
+pub(crate) struct PublishedEvents {
+    session: Arc<Session>,
+    ttl: Duration,
+}
+
+impl PublishedEvents {
+    pub fn new(session: Arc<Session>, ttl: Duration) -> Self {
+        Self { session, ttl }
+    }
+
+    /// Whether the event was already published
+    pub async fn contains(&self, event_id: Uuid) -> Result<bool> {
+        let row = self
+            .session
+            .query_unpaged("SELECT event_id FROM published_events WHERE event_id = ?", (event_id,))
+            .await?
+            .into_rows_result()?
+            .maybe_first_row::<(Uuid,)>()?;
+        Ok(row.is_some())
+    }
+
+    /// Record a successful publish of the event
+    pub async fn record(&self, event_id: Uuid, event_type: &str) -> Result<()> {
+        self.session
+            .query_unpaged(
+                "INSERT INTO published_events (event_id, event_type, published_at) VALUES (?, ?, ?) USING TTL ?",
+                (event_id, event_type, Utc::now(), self.ttl.as_secs() as i32),
+            )
+            .await?;
+        Ok(())
+    }
+}
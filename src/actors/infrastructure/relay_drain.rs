@@ -0,0 +1,119 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::Notify;
+
+// ============================================================================
+// Relay Drain - In-Flight Tracking for Graceful Shutdown
+// ============================================================================
+//
+// Every CDC row the relay consumes holds an InFlightGuard until it has been
+// published, parked or handed to the DLQ. On shutdown the CdcProcessor
+// begins the drain: rows arriving from then on are refused (their batch
+// never advances the checkpoint, so the next start relays them again), and
+// `drained` resolves once the rows already in flight have finished.
+//
+// ============================================================================
+
+#[derive(Default)]
+pub(crate) struct RelayDrain {
+    draining: AtomicBool,
+    in_flight: AtomicUsize,
+    idle: Notify,
+}
+
+/// A row being relayed; dropping it marks the row finished
+pub(crate) struct InFlightGuard(Arc<RelayDrain>);
+
+impl RelayDrain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a row; None once the drain has begun
+    pub fn enter(self: &Arc<Self>) -> Option<InFlightGuard> {
+        if self.is_draining() {
+            return None;
+        }
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        let guard = InFlightGuard(self.clone());
+        // The drain may have begun between the check and the increment
+        if self.is_draining() {
+            return None;
+        }
+        Some(guard)
+    }
+
+    /// Refuse new rows from now on
+    pub fn begin(&self) {
+        self.draining.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Wait until no row is in flight
+    pub async fn drained(&self) {
+        loop {
+            let idle = self.idle.notified();
+            if self.in_flight() == 0 {
+                return;
+            }
+            idle.await;
+        }
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if self.0.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.idle.notify_waiters();
+        }
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_rows_refused_once_draining() {
+        let drain = Arc::new(RelayDrain::new());
+        let guard = drain.enter();
+        assert!(guard.is_some());
+        assert_eq!(drain.in_flight(), 1);
+
+        drain.begin();
+        assert!(drain.enter().is_none());
+        assert_eq!(drain.in_flight(), 1);
+
+        drop(guard);
+        assert_eq!(drain.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_drained_waits_for_in_flight_rows() {
+        let drain = Arc::new(RelayDrain::new());
+        let guard = drain.enter().unwrap();
+        drain.begin();
+
+        let waiter = tokio::spawn({
+            let drain = drain.clone();
+            async move { drain.drained().await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+
+        drop(guard);
+        tokio::time::timeout(Duration::from_secs(1), waiter).await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,605 @@
+use chrono::{DateTime, Utc};
+use scylla::client::session::Session;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+use anyhow::Result;
+
+use crate::db::{Operation, StatementCache};
+use crate::event_sourcing::{ShardLayout, TenantContext};
+use crate::metrics::MetricsHandle;
+use super::cdc_processor::{OutboxCDCConsumer, PublishOutcome};
+use super::outbox_row::{OutboxRow, RowChange};
+
+// ============================================================================
+// Sequence Gap Detection - Per-Aggregate Continuity of Published Events
+// ============================================================================
+//
+// Every outbox row carries the event's sequence_number. If outbox rows are
+// deleted (TTL, manual cleanup) or CDC drops rows, some sequences are never
+// published and downstream consumers see a silent hole.
+//
+// The detector remembers the highest published sequence per aggregate
+// (in memory, persisted to aggregate_publish_progress) and the sequences
+// skipped over. Because outbox rows of one aggregate can arrive via
+// different CDC streams, a skipped sequence is only a *suspected* gap at
+// first; it becomes a confirmed gap once it is still missing after the
+// grace period. Confirmed gaps are logged, counted, and - if enabled -
+// backfilled by republishing the events from event_store.
+//
+// Only recently published aggregates are kept in memory: an aggregate not
+// seen for the idle TTL is forgotten, and past the capacity the least
+// recently seen ones go first. Aggregates with suspected gaps are kept
+// until the gap is filled or confirmed. A forgotten aggregate reloads its
+// highest sequence from aggregate_publish_progress when it is seen again.
+//
+// Backfill reads the missing events from event_store and hands them to the
+// relay's own consumer, so they get the same topic, region routing, key,
+// headers, payload format, upcasting and published_events check as events
+// relayed from the outbox.
+//
+// ============================================================================
+
+/// Result of recording a published sequence
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SequenceCheck {
+    /// Next expected sequence (or first seen for this aggregate)
+    InOrder,
+    /// Sequences between the previous highest and this one are missing
+    Gap { missing: Vec<i64> },
+    /// A previously missing sequence arrived late
+    GapFilled,
+    /// Already published before
+    Duplicate,
+}
+
+/// How long an aggregate without suspected gaps is tracked after it was last seen
+const DEFAULT_IDLE_TTL: Duration = Duration::from_secs(600);
+
+/// Most aggregates tracked in memory at once
+const DEFAULT_MAX_TRACKED: usize = 100_000;
+
+#[derive(Debug)]
+struct AggregateProgress {
+    highest: i64,
+    /// Missing sequence -> when it was first noticed
+    missing: HashMap<i64, Instant>,
+    /// When a sequence of the aggregate was last recorded
+    last_seen: Instant,
+}
+
+impl AggregateProgress {
+    fn new(highest: i64, now: Instant) -> Self {
+        Self { highest, missing: HashMap::new(), last_seen: now }
+    }
+}
+
+/// A gap still open after the grace period
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ConfirmedGap {
+    pub aggregate_id: Uuid,
+    pub sequences: Vec<i64>,
+}
+
+pub(crate) struct SequenceGapDetector {
+    progress: Mutex<HashMap<Uuid, AggregateProgress>>,
+    grace: Duration,
+    idle_ttl: Duration,
+    max_tracked: usize,
+    session: Option<Arc<Session>>,
+    metrics: MetricsHandle,
+}
+
+impl SequenceGapDetector {
+    pub fn new(grace: Duration) -> Self {
+        Self {
+            progress: Mutex::new(HashMap::new()),
+            grace,
+            idle_ttl: DEFAULT_IDLE_TTL,
+            max_tracked: DEFAULT_MAX_TRACKED,
+            session: None,
+            metrics: MetricsHandle::noop(),
+        }
+    }
+
+    /// Persist progress to aggregate_publish_progress
+    pub fn with_session(mut self, session: Arc<Session>) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: MetricsHandle) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Record that `sequence` of `aggregate_id` was published
+    pub async fn record(&self, aggregate_id: Uuid, sequence: i64) -> SequenceCheck {
+        let known = self.progress.lock().unwrap().contains_key(&aggregate_id);
+        if !known {
+            // Without persisted progress the first sequence we see is the
+            // baseline - we may simply have started mid-stream
+            let highest = self
+                .load_progress(aggregate_id)
+                .await
+                .unwrap_or(sequence - 1);
+            let now = Instant::now();
+            let mut progress = self.progress.lock().unwrap();
+            progress
+                .entry(aggregate_id)
+                .or_insert_with(|| AggregateProgress::new(highest, now));
+            if progress.len() > self.max_tracked {
+                self.evict(&mut progress, now);
+            }
+        }
+
+        let (check, highest) = {
+            let now = Instant::now();
+            let mut progress = self.progress.lock().unwrap();
+            let entry = progress
+                .entry(aggregate_id)
+                .or_insert_with(|| AggregateProgress::new(sequence - 1, now));
+            entry.last_seen = now;
+            (Self::apply(entry, sequence, now), entry.highest)
+        };
+
+        match &check {
+            SequenceCheck::Gap { missing } => {
+                tracing::warn!(
+                    aggregate_id = %aggregate_id,
+                    sequence = sequence,
+                    missing = ?missing,
+                    "⚠️  Sequence gap observed at publish time (may still arrive)"
+                );
+                self.count("suspected", missing.len() as u64);
+            }
+            SequenceCheck::GapFilled => {
+                tracing::debug!(aggregate_id = %aggregate_id, sequence = sequence, "Late sequence filled a gap");
+                self.count("filled", 1);
+            }
+            SequenceCheck::Duplicate => {
+                tracing::debug!(aggregate_id = %aggregate_id, sequence = sequence, "Sequence already published");
+            }
+            SequenceCheck::InOrder => {}
+        }
+
+        if highest == sequence {
+            self.save_progress(aggregate_id, highest).await;
+        }
+
+        check
+    }
+
+    fn apply(entry: &mut AggregateProgress, sequence: i64, now: Instant) -> SequenceCheck {
+        if entry.missing.remove(&sequence).is_some() {
+            return SequenceCheck::GapFilled;
+        }
+        if sequence <= entry.highest {
+            return SequenceCheck::Duplicate;
+        }
+
+        let missing: Vec<i64> = (entry.highest + 1..sequence).collect();
+        for seq in &missing {
+            entry.missing.insert(*seq, now);
+        }
+        entry.highest = sequence;
+
+        if missing.is_empty() {
+            SequenceCheck::InOrder
+        } else {
+            SequenceCheck::Gap { missing }
+        }
+    }
+
+    /// Gaps still open after the grace period; they are removed from tracking
+    pub fn take_confirmed(&self) -> Vec<ConfirmedGap> {
+        self.take_confirmed_at(Instant::now())
+    }
+
+    fn take_confirmed_at(&self, now: Instant) -> Vec<ConfirmedGap> {
+        let mut progress = self.progress.lock().unwrap();
+        let mut confirmed = Vec::new();
+
+        for (aggregate_id, entry) in progress.iter_mut() {
+            let overdue: BTreeSet<i64> = entry
+                .missing
+                .iter()
+                .filter(|(_, noticed)| now.duration_since(**noticed) >= self.grace)
+                .map(|(seq, _)| *seq)
+                .collect();
+
+            if overdue.is_empty() {
+                continue;
+            }
+            for seq in &overdue {
+                entry.missing.remove(seq);
+            }
+            confirmed.push(ConfirmedGap {
+                aggregate_id: *aggregate_id,
+                sequences: overdue.into_iter().collect(),
+            });
+        }
+
+        self.evict(&mut progress, now);
+        confirmed
+    }
+
+    /// Forget idle aggregates, then the least recently seen ones past the capacity
+    ///
+    /// Past the capacity a tenth more is evicted, so a stream of new
+    /// aggregates does not sort the map on every one. Aggregates with
+    /// suspected gaps stay until the gap is filled or confirmed.
+    fn evict(&self, progress: &mut HashMap<Uuid, AggregateProgress>, now: Instant) {
+        progress.retain(|_, entry| !entry.missing.is_empty() || now.duration_since(entry.last_seen) < self.idle_ttl);
+        if progress.len() <= self.max_tracked {
+            return;
+        }
+
+        let mut idle: Vec<(Instant, Uuid)> = progress
+            .iter()
+            .filter(|(_, entry)| entry.missing.is_empty())
+            .map(|(aggregate_id, entry)| (entry.last_seen, *aggregate_id))
+            .collect();
+        idle.sort_unstable();
+        let excess = progress.len() - (self.max_tracked - self.max_tracked / 10);
+        for (_, aggregate_id) in idle.into_iter().take(excess) {
+            progress.remove(&aggregate_id);
+        }
+    }
+
+    /// Periodically confirm gaps, alert, and optionally backfill them
+    pub fn spawn_monitor(self: Arc<Self>, backfill: Option<GapBackfill>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.grace.max(Duration::from_secs(1)) / 2);
+            loop {
+                interval.tick().await;
+
+                for gap in self.take_confirmed() {
+                    tracing::error!(
+                        aggregate_id = %gap.aggregate_id,
+                        sequences = ?gap.sequences,
+                        "🕳️  Sequence gap confirmed - events never published"
+                    );
+                    self.count("confirmed", gap.sequences.len() as u64);
+
+                    if let Some(ref backfill) = backfill {
+                        match backfill.republish(gap.aggregate_id, &gap.sequences).await {
+                            Ok(count) => self.count("backfilled", count as u64),
+                            Err(e) => tracing::error!(
+                                aggregate_id = %gap.aggregate_id,
+                                error = %e,
+                                "Gap backfill failed"
+                            ),
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn count(&self, outcome: &str, n: u64) {
+        self.metrics.record_sequence_gaps(outcome, n);
+    }
+
+    async fn load_progress(&self, aggregate_id: Uuid) -> Option<i64> {
+        let session = self.session.as_ref()?;
+
+        let result = session
+            .query_unpaged(
+                "SELECT last_published_sequence FROM aggregate_publish_progress WHERE aggregate_id = ?",
+                (aggregate_id,),
+            )
+            .await;
+
+        let rows = result.ok()?.into_rows_result().ok()?;
+        match rows.maybe_first_row::<(i64,)>() {
+            Ok(Some((sequence,))) => Some(sequence),
+            _ => None,
+        }
+    }
+
+    async fn save_progress(&self, aggregate_id: Uuid, sequence: i64) {
+        let Some(ref session) = self.session else {
+            return;
+        };
+
+        if let Err(e) = session
+            .query_unpaged(
+                "INSERT INTO aggregate_publish_progress (aggregate_id, last_published_sequence, updated_at)
+                 VALUES (?, ?, ?)",
+                (aggregate_id, sequence, chrono::Utc::now()),
+            )
+            .await
+        {
+            tracing::warn!(aggregate_id = %aggregate_id, error = %e, "Failed to persist publish progress");
+        }
+    }
+}
+
+/// Columns of an event_store row read back for backfill
+const STORED_EVENT_COLUMNS: &str = "aggregate_id, sequence_number, event_id, event_type, event_version, \
+    event_data, causation_id, correlation_id, timestamp, origin_region";
+
+/// An event_store row, read by the relay as if it were its outbox row
+#[derive(Debug, Clone, scylla::DeserializeRow)]
+pub(crate) struct StoredEventRow {
+    aggregate_id: Uuid,
+    sequence_number: i64,
+    event_id: Uuid,
+    event_type: String,
+    event_version: Option<i32>,
+    event_data: String,
+    causation_id: Option<Uuid>,
+    correlation_id: Option<Uuid>,
+    timestamp: Option<DateTime<Utc>>,
+    origin_region: Option<String>,
+}
+
+impl OutboxRow for StoredEventRow {
+    fn is_insert(&self) -> bool {
+        true
+    }
+
+    fn operation_name(&self) -> String {
+        "Backfill".to_string()
+    }
+
+    /// The outbox row is gone; the event id stands in for its id
+    fn uuid(&self, column: &str) -> Option<Uuid> {
+        match column {
+            "id" | "event_id" => Some(self.event_id),
+            "aggregate_id" => Some(self.aggregate_id),
+            "causation_id" => self.causation_id,
+            "correlation_id" => self.correlation_id,
+            _ => None,
+        }
+    }
+
+    fn text(&self, column: &str) -> Option<String> {
+        match column {
+            "event_type" => Some(self.event_type.clone()),
+            "payload" => Some(self.event_data.clone()),
+            "origin_region" => self.origin_region.clone(),
+            _ => None,
+        }
+    }
+
+    fn int(&self, column: &str) -> Option<i32> {
+        match column {
+            "event_version" => self.event_version,
+            _ => None,
+        }
+    }
+
+    fn bigint(&self, column: &str) -> Option<i64> {
+        match column {
+            "sequence_number" => Some(self.sequence_number),
+            _ => None,
+        }
+    }
+
+    fn timestamp(&self, column: &str) -> Option<DateTime<Utc>> {
+        match column {
+            "created_at" => self.timestamp,
+            _ => None,
+        }
+    }
+
+    fn row_change(&self) -> Option<RowChange> {
+        Some(RowChange::Upsert)
+    }
+
+    fn json(&self, column: &str) -> Option<Value> {
+        self.text(column)
+            .map(Value::from)
+            .or_else(|| self.uuid(column).map(|v| Value::from(v.to_string())))
+            .or_else(|| self.bigint(column).map(Value::from))
+            .or_else(|| self.int(column).map(Value::from))
+            .or_else(|| self.timestamp(column).map(|ts| Value::from(ts.to_rfc3339())))
+    }
+}
+
+/// Republishes missing events from event_store through the relay
+pub(crate) struct GapBackfill {
+    statements: Arc<StatementCache>,
+    relay: OutboxCDCConsumer,
+    shards: ShardLayout,
+    tenant: TenantContext,
+}
+
+impl GapBackfill {
+    /// `relay` is the outbox consumer the events are republished through
+    pub fn new(statements: Arc<StatementCache>, relay: OutboxCDCConsumer) -> Self {
+        Self {
+            statements,
+            relay,
+            shards: ShardLayout::default(),
+            tenant: TenantContext::default(),
+        }
+    }
+
+    /// Read events from the shard tables of a sharded event store
+    pub fn with_shards(mut self, layout: ShardLayout) -> Self {
+        self.shards = layout;
+        self
+    }
+
+    /// Read events from the event tables of `tenant`
+    pub fn with_tenant(mut self, tenant: TenantContext) -> Self {
+        self.tenant = tenant;
+        self
+    }
+
+    /// Republish the given sequences; returns how many were published
+    ///
+    /// Events already in published_events are skipped, events that cannot
+    /// be published go to the DLQ like relayed ones.
+    pub async fn republish(&self, aggregate_id: Uuid, sequences: &[i64]) -> Result<usize> {
+        let query = format!(
+            "SELECT {} FROM {} WHERE aggregate_id = ? AND sequence_number = ?",
+            STORED_EVENT_COLUMNS,
+            self.tenant.table(&self.shards.table_for(aggregate_id))
+        );
+        let mut republished = 0;
+
+        for sequence in sequences {
+            let row = self
+                .statements
+                .execute(Operation::Read, &query, (aggregate_id, *sequence))
+                .await?
+                .into_rows_result()?
+                .maybe_first_row::<StoredEventRow>()?;
+
+            let Some(row) = row else {
+                tracing::error!(
+                    aggregate_id = %aggregate_id,
+                    sequence = sequence,
+                    "Missing event not found in event_store - cannot backfill"
+                );
+                continue;
+            };
+
+            let outcome = self.relay.republish_row(&row).await?;
+            tracing::info!(
+                aggregate_id = %aggregate_id,
+                sequence = sequence,
+                event_type = %row.event_type,
+                outcome = outcome.map_or("skipped", PublishOutcome::as_str),
+                "♻️  Backfilled missing event from event_store"
+            );
+            if outcome == Some(PublishOutcome::Published) {
+                republished += 1;
+            }
+        }
+
+        Ok(republished)
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messaging::test_support::RecordingPublisher;
+    use crate::messaging::{PublishOrigin, RegionConfig};
+
+    #[tokio::test]
+    async fn test_in_order_sequences() {
+        let detector = SequenceGapDetector::new(Duration::from_secs(30));
+        let id = Uuid::new_v4();
+
+        assert_eq!(detector.record(id, 1).await, SequenceCheck::InOrder);
+        assert_eq!(detector.record(id, 2).await, SequenceCheck::InOrder);
+        assert_eq!(detector.record(id, 2).await, SequenceCheck::Duplicate);
+    }
+
+    #[tokio::test]
+    async fn test_gap_detected_and_filled_late() {
+        let detector = SequenceGapDetector::new(Duration::from_secs(30));
+        let id = Uuid::new_v4();
+
+        detector.record(id, 1).await;
+        assert_eq!(detector.record(id, 4).await, SequenceCheck::Gap { missing: vec![2, 3] });
+        assert_eq!(detector.record(id, 3).await, SequenceCheck::GapFilled);
+
+        // Only 2 is still missing, and not yet past the grace period
+        assert!(detector.take_confirmed().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_first_sequence_seen_is_baseline() {
+        let detector = SequenceGapDetector::new(Duration::from_secs(30));
+        let id = Uuid::new_v4();
+
+        // Started mid-stream: 1..=6 were published before we were running
+        assert_eq!(detector.record(id, 7).await, SequenceCheck::InOrder);
+        assert_eq!(detector.record(id, 9).await, SequenceCheck::Gap { missing: vec![8] });
+    }
+
+    #[tokio::test]
+    async fn test_gap_confirmed_after_grace() {
+        let detector = SequenceGapDetector::new(Duration::from_millis(10));
+        let id = Uuid::new_v4();
+
+        detector.record(id, 1).await;
+        detector.record(id, 3).await;
+
+        let later = Instant::now() + Duration::from_millis(50);
+        let confirmed = detector.take_confirmed_at(later);
+
+        assert_eq!(confirmed, vec![ConfirmedGap { aggregate_id: id, sequences: vec![2] }]);
+        // Confirmed gaps are reported once
+        assert!(detector.take_confirmed_at(later).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_idle_aggregates_forgotten_unless_gap_open() {
+        let mut detector = SequenceGapDetector::new(Duration::from_secs(120));
+        detector.idle_ttl = Duration::from_secs(60);
+        let (idle, gapped) = (Uuid::new_v4(), Uuid::new_v4());
+
+        detector.record(idle, 1).await;
+        detector.record(gapped, 1).await;
+        detector.record(gapped, 3).await;
+
+        // Past the idle TTL, still within the grace period of the gap
+        assert!(detector.take_confirmed_at(Instant::now() + Duration::from_secs(61)).is_empty());
+        let progress = detector.progress.lock().unwrap();
+        assert!(!progress.contains_key(&idle));
+        assert!(progress.contains_key(&gapped));
+    }
+
+    #[tokio::test]
+    async fn test_least_recently_seen_evicted_past_capacity() {
+        let mut detector = SequenceGapDetector::new(Duration::from_secs(30));
+        detector.max_tracked = 2;
+        let (first, second, third) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        detector.record(first, 1).await;
+        detector.record(second, 1).await;
+        detector.record(first, 2).await;
+        detector.record(third, 1).await;
+
+        let progress = detector.progress.lock().unwrap();
+        assert_eq!(progress.len(), 2);
+        assert!(!progress.contains_key(&second));
+        assert_eq!(progress[&first].highest, 2);
+    }
+
+    #[tokio::test]
+    async fn test_backfilled_event_relayed_like_outbox_row() {
+        let publisher = Arc::new(RecordingPublisher::new());
+        let relay = OutboxCDCConsumer::new(publisher.clone(), None)
+            .with_region(Arc::new(RegionConfig::new("eu-west").with_per_region_topics()));
+        let row = StoredEventRow {
+            aggregate_id: Uuid::new_v4(),
+            sequence_number: 4,
+            event_id: Uuid::new_v4(),
+            event_type: "OrderCreated".to_string(),
+            event_version: Some(1),
+            event_data: r#"{"customer_id":"c-1"}"#.to_string(),
+            causation_id: None,
+            correlation_id: Some(Uuid::new_v4()),
+            timestamp: Some(Utc::now()),
+            origin_region: Some("eu-west".to_string()),
+        };
+
+        assert_eq!(relay.republish_row(&row).await.unwrap(), Some(PublishOutcome::Published));
+
+        let published = publisher.published();
+        assert_eq!(published[0].topic, "OrderCreated.eu-west");
+        assert_eq!(published[0].key, row.aggregate_id.to_string());
+        assert_eq!(published[0].origin, PublishOrigin::OutboxCdc);
+        assert!(published[0].headers.contains(&("sequence-number".to_string(), "4".to_string())));
+
+        // Events of other regions are theirs to backfill
+        let foreign = StoredEventRow { origin_region: Some("us-east".to_string()), ..row };
+        assert_eq!(relay.republish_row(&foreign).await.unwrap(), None);
+        assert_eq!(publisher.count(), 1);
+    }
+}
@@ -0,0 +1,413 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::watch;
+
+// ============================================================================
+// Startup Sequencing - Staggered Cold Start
+// ============================================================================
+//
+// On a cold start, projections rebuilding from the event store while the CDC
+// relay catches up on the outbox (and the API accepts commands on top) all
+// hit Scylla at once. The StartupSequencer runs the startup phases in a
+// configured order:
+//
+//   projection_rebuild → cdc_consumption → api_availability   (default)
+//
+// - A phase starts once every earlier phase completed and `stagger` passed
+//   since the last completion (`wait_turn`)
+// - A phase stuck longer than `phase_timeout` no longer holds back the
+//   following ones (logged) - a slow rebuild must not keep the relay down
+// - Phases left out of the order are skipped
+// - Phases report progress while running (`report_progress`)
+//
+// The coordinator reports the status to the health monitor as the `startup`
+// component (Degraded with progress until every phase completed), and the
+// metrics server serves it on /status/startup.
+//
+// Configuration (env):
+//   STARTUP_ORDER         comma-separated phases (default as above)
+//   STARTUP_STAGGER_MS    pause between phases (default 2000)
+//   STARTUP_PHASE_TIMEOUT_SECS  (default 300)
+//
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupPhase {
+    ProjectionRebuild,
+    CdcConsumption,
+    ApiAvailability,
+}
+
+impl StartupPhase {
+    pub const ALL: [StartupPhase; 3] = [
+        StartupPhase::ProjectionRebuild,
+        StartupPhase::CdcConsumption,
+        StartupPhase::ApiAvailability,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StartupPhase::ProjectionRebuild => "projection_rebuild",
+            StartupPhase::CdcConsumption => "cdc_consumption",
+            StartupPhase::ApiAvailability => "api_availability",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim() {
+            "projection_rebuild" | "projections" => Some(StartupPhase::ProjectionRebuild),
+            "cdc_consumption" | "cdc" => Some(StartupPhase::CdcConsumption),
+            "api_availability" | "api" => Some(StartupPhase::ApiAvailability),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StartupPolicy {
+    /// Phases in start order; phases not listed are skipped
+    pub order: Vec<StartupPhase>,
+    /// Pause after a phase completes before the next one starts
+    pub stagger: Duration,
+    /// Longest a phase may hold back the following phases
+    pub phase_timeout: Duration,
+}
+
+impl Default for StartupPolicy {
+    fn default() -> Self {
+        Self {
+            order: StartupPhase::ALL.to_vec(),
+            stagger: Duration::from_secs(2),
+            phase_timeout: Duration::from_secs(300),
+        }
+    }
+}
+
+impl StartupPolicy {
+    /// Everything at once, as before sequencing existed
+    pub fn immediate() -> Self {
+        Self { order: Vec::new(), stagger: Duration::ZERO, ..Self::default() }
+    }
+
+    pub fn from_env() -> anyhow::Result<Self> {
+        let mut policy = Self::default();
+
+        if let Ok(order) = std::env::var("STARTUP_ORDER") {
+            policy.order = order
+                .split(',')
+                .filter(|s| !s.trim().is_empty())
+                .map(|s| StartupPhase::parse(s).ok_or_else(|| anyhow::anyhow!("Unknown startup phase '{}'", s.trim())))
+                .collect::<anyhow::Result<_>>()?;
+        }
+        if let Ok(ms) = std::env::var("STARTUP_STAGGER_MS") {
+            policy.stagger = Duration::from_millis(ms.parse()?);
+        }
+        if let Ok(secs) = std::env::var("STARTUP_PHASE_TIMEOUT_SECS") {
+            policy.phase_timeout = Duration::from_secs(secs.parse()?);
+        }
+
+        Ok(policy)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PhaseState {
+    Waiting,
+    Running,
+    Completed,
+    /// Exceeded the phase timeout; following phases were released
+    TimedOut,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PhaseStatus {
+    pub phase: StartupPhase,
+    pub state: PhaseState,
+    pub completed_units: u64,
+    pub total_units: Option<u64>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl PhaseStatus {
+    fn new(phase: StartupPhase, state: PhaseState) -> Self {
+        Self { phase, state, completed_units: 0, total_units: None, started_at: None, finished_at: None }
+    }
+
+    /// Releases the following phases
+    fn is_settled(&self) -> bool {
+        matches!(self.state, PhaseState::Completed | PhaseState::TimedOut | PhaseState::Skipped)
+    }
+
+    fn describe(&self) -> String {
+        match (self.state, self.total_units) {
+            (PhaseState::Running, Some(total)) if total > 0 => {
+                format!("{} {}%", self.phase.as_str(), self.completed_units * 100 / total)
+            }
+            (PhaseState::Running, _) => format!("{} running ({} done)", self.phase.as_str(), self.completed_units),
+            _ => format!("{} waiting", self.phase.as_str()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupStatus {
+    /// Every phase completed (or timed out / skipped)
+    pub ready: bool,
+    pub phases: Vec<PhaseStatus>,
+}
+
+impl StartupStatus {
+    /// One-line progress for health details, e.g. "cdc_consumption waiting"
+    pub fn summary(&self) -> String {
+        if self.ready {
+            return "all startup phases completed".to_string();
+        }
+        self.phases
+            .iter()
+            .filter(|p| !p.is_settled())
+            .map(PhaseStatus::describe)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+pub struct StartupSequencer {
+    policy: StartupPolicy,
+    /// Phases in start order (skipped ones last)
+    phases: Mutex<Vec<PhaseStatus>>,
+    changed: watch::Sender<()>,
+}
+
+impl StartupSequencer {
+    pub fn new(policy: StartupPolicy) -> Self {
+        let mut phases: Vec<PhaseStatus> = policy
+            .order
+            .iter()
+            .map(|phase| PhaseStatus::new(*phase, PhaseState::Waiting))
+            .collect();
+        for phase in StartupPhase::ALL {
+            if !policy.order.contains(&phase) {
+                phases.push(PhaseStatus::new(phase, PhaseState::Skipped));
+            }
+        }
+
+        Self { policy, phases: Mutex::new(phases), changed: watch::channel(()).0 }
+    }
+
+    pub fn policy(&self) -> &StartupPolicy {
+        &self.policy
+    }
+
+    /// Wait until `phase` may start, then mark it running
+    pub async fn wait_turn(&self, phase: StartupPhase) {
+        let mut changed = self.changed.subscribe();
+
+        loop {
+            let wait = {
+                let mut phases = self.phases.lock().unwrap();
+                let now = Utc::now();
+                self.time_out_stuck(&mut phases, now);
+                match self.turn(&phases, phase, now) {
+                    Turn::Go => {
+                        if let Some(status) = phases.iter_mut().find(|p| p.phase == phase && p.state == PhaseState::Waiting) {
+                            status.state = PhaseState::Running;
+                            status.started_at = Some(now);
+                        }
+                        None
+                    }
+                    Turn::WaitFor(wait) => Some(wait),
+                }
+            };
+
+            let Some(wait) = wait else { break };
+            // Woken by progress/completion or when the stagger/timeout ends
+            let _ = tokio::time::timeout(wait, changed.changed()).await;
+        }
+
+        self.changed.send_replace(());
+        tracing::info!(phase = phase.as_str(), "🚦 Startup phase started");
+    }
+
+    /// `completed` of `total` units (events, aggregates, ...) done
+    pub fn report_progress(&self, phase: StartupPhase, completed: u64, total: Option<u64>) {
+        let mut phases = self.phases.lock().unwrap();
+        if let Some(status) = phases.iter_mut().find(|p| p.phase == phase) {
+            status.completed_units = completed;
+            status.total_units = total;
+        }
+    }
+
+    pub fn complete(&self, phase: StartupPhase) {
+        {
+            let mut phases = self.phases.lock().unwrap();
+            if let Some(status) = phases.iter_mut().find(|p| p.phase == phase) {
+                if status.state != PhaseState::Skipped {
+                    status.state = PhaseState::Completed;
+                    status.finished_at = Some(Utc::now());
+                }
+            }
+        }
+        self.changed.send_replace(());
+        tracing::info!(phase = phase.as_str(), "✅ Startup phase completed");
+    }
+
+    pub fn status(&self) -> StartupStatus {
+        let mut phases = self.phases.lock().unwrap();
+        self.time_out_stuck(&mut phases, Utc::now());
+        StartupStatus {
+            ready: phases.iter().all(PhaseStatus::is_settled),
+            phases: phases.clone(),
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.status().ready
+    }
+
+    /// Wait until every phase settled
+    pub async fn wait_ready(&self) {
+        let mut changed = self.changed.subscribe();
+        while !self.is_ready() {
+            let _ = tokio::time::timeout(Duration::from_secs(1), changed.changed()).await;
+        }
+    }
+
+    fn turn(&self, phases: &[PhaseStatus], phase: StartupPhase, now: DateTime<Utc>) -> Turn {
+        let Some(index) = phases.iter().position(|p| p.phase == phase) else {
+            return Turn::Go;
+        };
+        if phases[index].state == PhaseState::Skipped {
+            return Turn::Go;
+        }
+
+        let earlier = &phases[..index];
+        if let Some(pending) = earlier.iter().find(|p| !p.is_settled()) {
+            // Running phases time out from their start; wake up then
+            let wait = match pending.started_at {
+                Some(started) => remaining(started, self.policy.phase_timeout, now),
+                None => self.policy.phase_timeout,
+            };
+            return Turn::WaitFor(wait.max(Duration::from_millis(10)));
+        }
+
+        let last_finished = earlier.iter().filter_map(|p| p.finished_at).max();
+        match last_finished {
+            Some(finished) if now < finished + self.policy.stagger => {
+                Turn::WaitFor(remaining(finished, self.policy.stagger, now))
+            }
+            _ => Turn::Go,
+        }
+    }
+
+    fn time_out_stuck(&self, phases: &mut [PhaseStatus], now: DateTime<Utc>) {
+        for status in phases.iter_mut().filter(|p| p.state == PhaseState::Running) {
+            let Some(started) = status.started_at else { continue };
+            if !self.policy.phase_timeout.is_zero() && remaining(started, self.policy.phase_timeout, now).is_zero() {
+                tracing::warn!(
+                    phase = status.phase.as_str(),
+                    timeout_secs = self.policy.phase_timeout.as_secs(),
+                    "⚠️ Startup phase exceeded its timeout, releasing the next phases"
+                );
+                status.state = PhaseState::TimedOut;
+                status.finished_at = Some(now);
+            }
+        }
+    }
+}
+
+enum Turn {
+    Go,
+    WaitFor(Duration),
+}
+
+/// Time left of `duration` started at `since`
+fn remaining(since: DateTime<Utc>, duration: Duration, now: DateTime<Utc>) -> Duration {
+    let Some(deadline) = chrono::Duration::from_std(duration).ok().and_then(|d| since.checked_add_signed(d)) else {
+        return duration;
+    };
+    (deadline - now).to_std().unwrap_or(Duration::ZERO)
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn policy(stagger_ms: u64) -> StartupPolicy {
+        StartupPolicy {
+            stagger: Duration::from_millis(stagger_ms),
+            phase_timeout: Duration::from_secs(5),
+            ..StartupPolicy::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_phases_start_in_order_with_stagger() {
+        let sequencer = Arc::new(StartupSequencer::new(policy(50)));
+
+        let cdc = tokio::spawn({
+            let sequencer = sequencer.clone();
+            async move { sequencer.wait_turn(StartupPhase::CdcConsumption).await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!cdc.is_finished(), "cdc must wait for the projection rebuild");
+
+        sequencer.wait_turn(StartupPhase::ProjectionRebuild).await;
+        sequencer.report_progress(StartupPhase::ProjectionRebuild, 1, Some(4));
+        assert!(sequencer.status().summary().contains("projection_rebuild 25%"));
+
+        sequencer.complete(StartupPhase::ProjectionRebuild);
+        let completed_at = std::time::Instant::now();
+        cdc.await.unwrap();
+        assert!(completed_at.elapsed() >= Duration::from_millis(40));
+
+        assert!(!sequencer.is_ready());
+        sequencer.complete(StartupPhase::CdcConsumption);
+        sequencer.wait_turn(StartupPhase::ApiAvailability).await;
+        sequencer.complete(StartupPhase::ApiAvailability);
+        assert!(sequencer.is_ready());
+    }
+
+    #[tokio::test]
+    async fn test_stuck_phase_times_out() {
+        let sequencer = StartupSequencer::new(StartupPolicy {
+            phase_timeout: Duration::from_millis(30),
+            ..policy(0)
+        });
+
+        sequencer.wait_turn(StartupPhase::ProjectionRebuild).await;
+        // Never completed - cdc starts anyway after the timeout
+        tokio::time::timeout(Duration::from_secs(1), sequencer.wait_turn(StartupPhase::CdcConsumption))
+            .await
+            .unwrap();
+
+        let status = sequencer.status();
+        assert_eq!(status.phases[0].state, PhaseState::TimedOut);
+        assert_eq!(status.phases[1].state, PhaseState::Running);
+    }
+
+    #[tokio::test]
+    async fn test_unlisted_phases_are_skipped() {
+        let sequencer = StartupSequencer::new(StartupPolicy {
+            order: vec![StartupPhase::CdcConsumption],
+            ..policy(0)
+        });
+
+        sequencer.wait_turn(StartupPhase::ApiAvailability).await;
+        sequencer.wait_turn(StartupPhase::CdcConsumption).await;
+        sequencer.complete(StartupPhase::CdcConsumption);
+
+        assert!(sequencer.is_ready());
+        assert_eq!(sequencer.status().phases[2].state, PhaseState::Skipped);
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::db::{Operation, StatementCache};
+use crate::metrics::MetricsHandle;
+
+// ============================================================================
+// Stream Auditor - At-Least-Once Audit of the Published Streams
+// ============================================================================
+//
+// The SequenceGapDetector accounts for every outbox row the relay reads,
+// parked and dead-lettered ones included. The StreamAuditor only sees what
+// Redpanda acknowledged, and checks that per aggregate it is what consumers
+// expect: sequence after sequence, each once.
+//
+//   in_order     the next sequence (or the first one seen)
+//   gap          sequences were skipped: lost, dead-lettered or parked rows
+//   regression   a skipped sequence arrived after a later one (reordered,
+//                e.g. published from the DLQ or a retry overtaken)
+//   duplicate    published again (redelivered rows, at-least-once)
+//
+// Outcomes are counted in stream_audit_events_total; gaps and regressions
+// are logged as warnings.
+//
+// The highest published sequence per aggregate lives in memory and is
+// persisted to stream_audit_progress every FLUSH_INTERVAL (and on shutdown),
+// so a restart continues the audit instead of starting over. Rows re-read
+// after a restart and skipped as already published count as duplicates and
+// move the position forward, covering progress a crash did not persist.
+//
+// ============================================================================
+
+/// How often advanced positions are persisted
+const FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Largest gap whose sequences are remembered to recognise late arrivals
+const MAX_REMEMBERED_GAP: i64 = 1000;
+
+/// What a published sequence means for its aggregate's stream
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum StreamCheck {
+    InOrder,
+    /// Sequences `from..=to` were skipped
+    Gap { from: i64, to: i64 },
+    /// Published after `highest`, a later sequence
+    Regression { highest: i64 },
+    Duplicate,
+}
+
+impl StreamCheck {
+    fn outcome(&self) -> &'static str {
+        match self {
+            StreamCheck::InOrder => "in_order",
+            StreamCheck::Gap { .. } => "gap",
+            StreamCheck::Regression { .. } => "regression",
+            StreamCheck::Duplicate => "duplicate",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct StreamPosition {
+    highest: i64,
+    /// Skipped sequences not published yet
+    skipped: BTreeSet<i64>,
+    /// Advanced since the last flush
+    dirty: bool,
+}
+
+impl StreamPosition {
+    fn apply(&mut self, sequence: i64) -> StreamCheck {
+        if self.skipped.remove(&sequence) {
+            return StreamCheck::Regression { highest: self.highest };
+        }
+        if sequence <= self.highest {
+            return StreamCheck::Duplicate;
+        }
+
+        let (from, to) = (self.highest + 1, sequence - 1);
+        self.highest = sequence;
+        self.dirty = true;
+        if from > to {
+            return StreamCheck::InOrder;
+        }
+        if to - from < MAX_REMEMBERED_GAP {
+            self.skipped.extend(from..=to);
+        }
+        StreamCheck::Gap { from, to }
+    }
+}
+
+/// Highest published sequence per aggregate, shared by all CDC relays
+#[derive(Default)]
+pub(crate) struct StreamAuditor {
+    positions: Mutex<HashMap<Uuid, StreamPosition>>,
+    /// Persists positions in stream_audit_progress; None keeps them in memory
+    statements: Option<Arc<StatementCache>>,
+    metrics: MetricsHandle,
+}
+
+impl StreamAuditor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_statement_cache(mut self, statements: Arc<StatementCache>) -> Self {
+        self.statements = Some(statements);
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: MetricsHandle) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Record that `sequence` of `aggregate_id` is in the published stream
+    pub async fn record(&self, aggregate_id: Uuid, sequence: i64) -> StreamCheck {
+        let known = self.positions.lock().unwrap().contains_key(&aggregate_id);
+        if !known {
+            // Without a persisted position the first sequence seen is the
+            // baseline - the audit may have started mid-stream
+            let highest = match self.load(aggregate_id).await {
+                Ok(Some(highest)) => highest,
+                Ok(None) => sequence - 1,
+                Err(e) => {
+                    tracing::warn!(aggregate_id = %aggregate_id, error = %e, "Failed to load stream audit position");
+                    sequence - 1
+                }
+            };
+            self.positions
+                .lock()
+                .unwrap()
+                .entry(aggregate_id)
+                .or_insert_with(|| StreamPosition { highest, ..Default::default() });
+        }
+
+        let check = self.positions.lock().unwrap().entry(aggregate_id).or_default().apply(sequence);
+        match check {
+            StreamCheck::Gap { from, to } => {
+                tracing::warn!(
+                    aggregate_id = %aggregate_id,
+                    sequence = sequence,
+                    missing_from = from,
+                    missing_to = to,
+                    "⚠️  Published stream skipped sequences"
+                );
+                self.metrics.record_stream_audit("missing", (to - from + 1) as u64);
+            }
+            StreamCheck::Regression { highest } => tracing::warn!(
+                aggregate_id = %aggregate_id,
+                sequence = sequence,
+                highest = highest,
+                "⚠️  Event published after a later one of its aggregate"
+            ),
+            StreamCheck::Duplicate => {
+                tracing::debug!(aggregate_id = %aggregate_id, sequence = sequence, "Sequence published again");
+            }
+            StreamCheck::InOrder => {}
+        }
+        self.metrics.record_stream_audit(check.outcome(), 1);
+        check
+    }
+
+    async fn load(&self, aggregate_id: Uuid) -> anyhow::Result<Option<i64>> {
+        let Some(ref statements) = self.statements else {
+            return Ok(None);
+        };
+        let row = statements
+            .execute(
+                Operation::Offsets,
+                "SELECT last_sequence FROM stream_audit_progress WHERE aggregate_id = ?",
+                (aggregate_id,),
+            )
+            .await?
+            .into_rows_result()?
+            .maybe_first_row::<(Option<i64>,)>()?;
+        Ok(row.and_then(|(sequence,)| sequence))
+    }
+
+    /// Persist the positions that advanced since the last flush
+    pub async fn flush(&self) -> anyhow::Result<usize> {
+        let Some(ref statements) = self.statements else {
+            return Ok(0);
+        };
+        let advanced: Vec<(Uuid, i64)> = self
+            .positions
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .filter(|(_, position)| position.dirty)
+            .map(|(aggregate_id, position)| {
+                position.dirty = false;
+                (*aggregate_id, position.highest)
+            })
+            .collect();
+
+        let now = chrono::Utc::now();
+        for (i, (aggregate_id, highest)) in advanced.iter().enumerate() {
+            let result = statements
+                .execute(
+                    Operation::Offsets,
+                    "UPDATE stream_audit_progress SET last_sequence = ?, updated_at = ? WHERE aggregate_id = ?",
+                    (*highest, now, *aggregate_id),
+                )
+                .await;
+            if let Err(e) = result {
+                // Try the rest again with the next flush
+                let mut positions = self.positions.lock().unwrap();
+                for (aggregate_id, _) in &advanced[i..] {
+                    if let Some(position) = positions.get_mut(aggregate_id) {
+                        position.dirty = true;
+                    }
+                }
+                return Err(e);
+            }
+        }
+        Ok(advanced.len())
+    }
+
+    /// Flush every FLUSH_INTERVAL in the background
+    pub fn spawn_flusher(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.flush().await {
+                    tracing::warn!(error = %e, "Failed to persist stream audit positions");
+                }
+            }
+        });
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_gaps_regressions_and_duplicates() {
+        let auditor = StreamAuditor::new();
+        let aggregate_id = Uuid::new_v4();
+
+        // Started mid-stream: the first sequence is the baseline
+        assert_eq!(auditor.record(aggregate_id, 7).await, StreamCheck::InOrder);
+        assert_eq!(auditor.record(aggregate_id, 8).await, StreamCheck::InOrder);
+        assert_eq!(auditor.record(aggregate_id, 11).await, StreamCheck::Gap { from: 9, to: 10 });
+        assert_eq!(auditor.record(aggregate_id, 9).await, StreamCheck::Regression { highest: 11 });
+        assert_eq!(auditor.record(aggregate_id, 9).await, StreamCheck::Duplicate);
+        assert_eq!(auditor.record(aggregate_id, 11).await, StreamCheck::Duplicate);
+        assert_eq!(auditor.record(aggregate_id, 12).await, StreamCheck::InOrder);
+
+        // Nothing to persist to without a statement cache
+        assert_eq!(auditor.flush().await.unwrap(), 0);
+    }
+}
@@ -0,0 +1,317 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use scylla_cdc::consumer::{CDCRow, Consumer, ConsumerFactory};
+use serde_json::{json, Map, Value};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::config::CdcSource;
+use crate::messaging::EventPublisher;
+use crate::metrics::MetricsHandle;
+use crate::utils::{record_retry, retry_with_backoff_recorded, RetryAttempt, RetryConfig, RetryResult};
+use super::{AddToDlq, CdcThrottle, DeadLetterSink, FailureContext};
+use super::cdc_checkpoint::{cdc_time, CdcCheckpoint};
+use super::cdc_processor::PublishOutcome;
+use super::outbox_row::OutboxRow;
+use super::relay_drain::RelayDrain;
+
+// ============================================================================
+// Table Relay - Row Changes of Non-Outbox Tables
+// ============================================================================
+//
+// Read-model tables (and anything else with CDC enabled) carry no event
+// columns, so their rows cannot go through the outbox consumer. A table
+// configured with a topic ([[cdc.tables]] with topic/key_column/columns)
+// gets this relay instead: every upsert or delete becomes one message on
+// that topic, keyed by `key_column`:
+//
+//   { "keyspace": "orders_ks", "table": "order_summaries",
+//     "operation": "upsert", "columns": { "order_id": "...", ... } }
+//
+// Only the configured columns are carried (NULL/absent ones are left out;
+// deletes carry just the key columns). Pre-images and range deletes are
+// skipped. Publishing retries like the outbox relay and falls back to the
+// DLQ, with `keyspace.table` as the event type.
+//
+// ============================================================================
+
+/// A row change ready to publish
+pub(crate) struct RowMessage {
+    /// None when the key column is NULL/missing (dead-lettered)
+    key: Option<String>,
+    payload: String,
+}
+
+/// Publishes the row changes of one table
+pub(crate) struct TableRelay {
+    publisher: Arc<dyn EventPublisher>,
+    dlq: Option<Arc<dyn DeadLetterSink>>,
+    retry_config: RetryConfig,
+    source: CdcSource,
+    topic: String,
+    key_column: String,
+    columns: Vec<String>,
+    metrics: MetricsHandle,
+}
+
+impl TableRelay {
+    pub fn new(
+        publisher: Arc<dyn EventPublisher>,
+        dlq: Option<Arc<dyn DeadLetterSink>>,
+        source: CdcSource,
+        topic: &str,
+        key_column: &str,
+        columns: &[String],
+    ) -> Self {
+        Self {
+            publisher,
+            dlq,
+            retry_config: RetryConfig::aggressive(),
+            source,
+            topic: topic.to_string(),
+            key_column: key_column.to_string(),
+            columns: columns.to_vec(),
+            metrics: MetricsHandle::noop(),
+        }
+    }
+
+    pub fn with_retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.retry_config = retry_config;
+        self
+    }
+
+    /// Record publish retries (retry_*{operation="cdc_row_publish"})
+    pub fn with_metrics(mut self, metrics: MetricsHandle) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Message of a row change; None for changes without row state
+    pub fn row_message(&self, row: &impl OutboxRow) -> Option<RowMessage> {
+        let change = row.row_change()?;
+        let key = match row.json(&self.key_column) {
+            Some(Value::String(key)) => Some(key),
+            Some(key) => Some(key.to_string()),
+            None => None,
+        };
+        let columns: Map<String, Value> = self
+            .columns
+            .iter()
+            .filter_map(|column| row.json(column).map(|value| (column.clone(), value)))
+            .collect();
+        let payload = json!({
+            "keyspace": self.source.keyspace,
+            "table": self.source.table,
+            "operation": change.as_str(),
+            "columns": columns,
+        })
+        .to_string();
+        Some(RowMessage { key, payload })
+    }
+
+    /// Publish a row change with retry, falling back to the DLQ
+    ///
+    /// Returns None when there was nothing to publish.
+    pub async fn relay(&self, message: Option<RowMessage>) -> Option<PublishOutcome> {
+        let RowMessage { key, payload } = message?;
+        let Some(key) = key else {
+            let error = format!("Missing key column {}", self.key_column);
+            self.dead_letter(String::new(), payload, error, Vec::new()).await;
+            return Some(PublishOutcome::DeadLettered);
+        };
+
+        let (result, attempts) = retry_with_backoff_recorded(self.retry_config.clone(), |_attempt| {
+            let publisher = self.publisher.clone();
+            let (topic, key, payload) = (self.topic.clone(), key.clone(), payload.clone());
+            async move { publisher.publish(&topic, &key, &payload).await }
+        })
+        .await;
+        record_retry(&self.metrics, "cdc_row_publish", &attempts, matches!(result, RetryResult::Success(_)));
+
+        match result {
+            RetryResult::Success(_) => {
+                tracing::debug!(source = %self.source.label(), topic = %self.topic, key = %key, "Published row change");
+                Some(PublishOutcome::Published)
+            }
+            RetryResult::Failed(e) | RetryResult::PermanentFailure(e) => {
+                self.dead_letter(key, payload, e.to_string(), attempts).await;
+                Some(PublishOutcome::DeadLettered)
+            }
+        }
+    }
+
+    async fn dead_letter(&self, key: String, payload: String, error: String, attempts: Vec<RetryAttempt>) {
+        tracing::error!(
+            source = %self.source.label(),
+            topic = %self.topic,
+            error = %error,
+            "❌ Failed to publish row change, sending to DLQ"
+        );
+        let Some(ref dlq) = self.dlq else { return };
+
+        let failure_context = FailureContext {
+            topic: self.topic.clone(),
+            key,
+            attempts,
+            publisher: self.publisher.diagnostics(&self.topic).await,
+            captured_at: Utc::now(),
+        };
+        let _ = dlq.dead_letter(AddToDlq {
+            id: Uuid::new_v4(),
+            aggregate_id: Uuid::nil(),
+            event_type: self.source.label(),
+            payload,
+            error_message: error,
+            failure_count: self.retry_config.max_attempts as i32,
+            first_failed_at: Utc::now(),
+            failure_context: Some(failure_context),
+        }).await;
+    }
+}
+
+/// Consumer of one CDC stream of a relayed table
+struct TableCDCConsumer {
+    relay: Arc<TableRelay>,
+    throttle: Option<Arc<CdcThrottle>>,
+    checkpoint: Option<Arc<CdcCheckpoint>>,
+    drain: Option<Arc<RelayDrain>>,
+    metrics: MetricsHandle,
+    source: String,
+}
+
+#[async_trait]
+impl Consumer for TableCDCConsumer {
+    async fn consume_cdc(&mut self, data: CDCRow<'_>) -> anyhow::Result<()> {
+        // Shutting down: leave the row to the next start
+        let _in_flight = match self.drain {
+            Some(ref drain) => match drain.enter() {
+                Some(guard) => Some(guard),
+                None => return Ok(()),
+            },
+            None => None,
+        };
+
+        if let Some(ref throttle) = self.throttle {
+            throttle.pace().await;
+        }
+
+        let message = self.relay.row_message(&data);
+        let outcome = self.relay.relay(message).await;
+        self.metrics.record_cdc_row(&self.source, outcome.map_or("skipped", PublishOutcome::as_str));
+
+        if data.end_of_batch {
+            if let (Some(checkpoint), Some(time)) = (&self.checkpoint, cdc_time(data.time)) {
+                checkpoint.advance(time, None);
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) struct TableConsumerFactory {
+    relay: Arc<TableRelay>,
+    throttle: Option<Arc<CdcThrottle>>,
+    checkpoint: Option<Arc<CdcCheckpoint>>,
+    drain: Option<Arc<RelayDrain>>,
+    metrics: MetricsHandle,
+}
+
+impl TableConsumerFactory {
+    pub fn new(relay: TableRelay) -> Self {
+        Self {
+            relay: Arc::new(relay),
+            throttle: None,
+            checkpoint: None,
+            drain: None,
+            metrics: MetricsHandle::noop(),
+        }
+    }
+
+    pub fn with_throttle(mut self, throttle: Arc<CdcThrottle>) -> Self {
+        self.throttle = Some(throttle);
+        self
+    }
+
+    pub fn with_checkpoint(mut self, checkpoint: Arc<CdcCheckpoint>) -> Self {
+        self.checkpoint = Some(checkpoint);
+        self
+    }
+
+    pub fn with_drain(mut self, drain: Arc<RelayDrain>) -> Self {
+        self.drain = Some(drain);
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: MetricsHandle) -> Self {
+        self.metrics = metrics;
+        self
+    }
+}
+
+#[async_trait]
+impl ConsumerFactory for TableConsumerFactory {
+    async fn new_consumer(&self) -> Box<dyn Consumer> {
+        Box::new(TableCDCConsumer {
+            relay: self.relay.clone(),
+            throttle: self.throttle.clone(),
+            checkpoint: self.checkpoint.clone(),
+            drain: self.drain.clone(),
+            metrics: self.metrics.clone(),
+            source: self.relay.source.label(),
+        })
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::infrastructure::test_support::SyntheticOutboxRow;
+    use crate::messaging::test_support::RecordingPublisher;
+    use crate::messaging::PublishOrigin;
+    use scylla_cdc::consumer::OperationType;
+
+    fn relay(publisher: Arc<RecordingPublisher>) -> TableRelay {
+        let source = CdcSource { keyspace: "orders_ks".to_string(), table: "order_summaries".to_string() };
+        let columns = vec!["order_id".to_string(), "status".to_string(), "total".to_string()];
+        TableRelay::new(publisher, None, source, "order-summaries", "order_id", &columns)
+    }
+
+    #[tokio::test]
+    async fn test_row_changes_published_by_key() {
+        let publisher = Arc::new(RecordingPublisher::new());
+        let relay = relay(publisher.clone());
+        let order_id = Uuid::new_v4();
+
+        let upsert = SyntheticOutboxRow::with_operation(OperationType::RowUpdate)
+            .uuid("order_id", order_id)
+            .text("status", "SHIPPED")
+            .text("ignored", "not configured");
+        let delete = SyntheticOutboxRow::with_operation(OperationType::RowDelete).uuid("order_id", order_id);
+        let pre_image = SyntheticOutboxRow::with_operation(OperationType::PreImage).uuid("order_id", order_id);
+
+        assert_eq!(relay.relay(relay.row_message(&upsert)).await, Some(PublishOutcome::Published));
+        assert_eq!(relay.relay(relay.row_message(&delete)).await, Some(PublishOutcome::Published));
+        assert_eq!(relay.relay(relay.row_message(&pre_image)).await, None);
+
+        let published = publisher.published();
+        assert_eq!(published.len(), 2);
+        assert!(published.iter().all(|m| m.topic == "order-summaries" && m.key == order_id.to_string()));
+        // Row changes are not outbox events for the dual-write guard
+        assert!(published.iter().all(|m| m.origin == PublishOrigin::Direct));
+
+        let upsert: Value = serde_json::from_str(&published[0].payload).unwrap();
+        assert_eq!(upsert["operation"], "upsert");
+        assert_eq!(upsert["table"], "order_summaries");
+        assert_eq!(upsert["columns"]["status"], "SHIPPED");
+        assert!(upsert["columns"].get("ignored").is_none());
+        assert!(upsert["columns"].get("total").is_none());
+
+        let delete: Value = serde_json::from_str(&published[1].payload).unwrap();
+        assert_eq!(delete["operation"], "delete");
+        assert_eq!(delete["columns"].as_object().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,154 @@
+use chrono::{DateTime, Utc};
+use scylla_cdc::consumer::OperationType;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::outbox_row::{is_insert_operation, row_change_of, OutboxRow, RowChange};
+
+// ============================================================================
+// CDC Test Doubles
+// ============================================================================
+//
+// - SyntheticOutboxRow: an outbox (or any table's) change built in memory,
+//   readable through OutboxRow exactly like a CDCRow from the log table
+//
+// ============================================================================
+
+#[derive(Debug, Clone)]
+enum Column {
+    Uuid(Uuid),
+    Text(String),
+    Int(i32),
+    BigInt(i64),
+    Timestamp(DateTime<Utc>),
+}
+
+pub(crate) struct SyntheticOutboxRow {
+    operation: OperationType,
+    columns: HashMap<String, Column>,
+}
+
+impl SyntheticOutboxRow {
+    /// Empty row insert; add columns with the builder methods
+    pub fn insert() -> Self {
+        Self::with_operation(OperationType::RowInsert)
+    }
+
+    pub fn with_operation(operation: OperationType) -> Self {
+        Self { operation, columns: HashMap::new() }
+    }
+
+    /// Row insert as written by EventStore::append_events
+    pub fn outbox_event(event_type: &str, payload: &str) -> Self {
+        Self::insert()
+            .uuid("id", Uuid::new_v4())
+            .uuid("aggregate_id", Uuid::new_v4())
+            .uuid("event_id", Uuid::new_v4())
+            .text("aggregate_type", "Order")
+            .text("event_type", event_type)
+            .text("aggregate_event_type", "OrderEvent")
+            .text("variant_type", event_type)
+            .text("payload", payload)
+            .int("event_version", 1)
+            .bigint("sequence_number", 1)
+    }
+
+    pub fn uuid(mut self, column: &str, value: Uuid) -> Self {
+        self.columns.insert(column.to_string(), Column::Uuid(value));
+        self
+    }
+
+    pub fn text(mut self, column: &str, value: &str) -> Self {
+        self.columns.insert(column.to_string(), Column::Text(value.to_string()));
+        self
+    }
+
+    pub fn int(mut self, column: &str, value: i32) -> Self {
+        self.columns.insert(column.to_string(), Column::Int(value));
+        self
+    }
+
+    pub fn bigint(mut self, column: &str, value: i64) -> Self {
+        self.columns.insert(column.to_string(), Column::BigInt(value));
+        self
+    }
+
+    pub fn timestamp(mut self, column: &str, value: DateTime<Utc>) -> Self {
+        self.columns.insert(column.to_string(), Column::Timestamp(value));
+        self
+    }
+
+    /// Make `column` NULL
+    pub fn without(mut self, column: &str) -> Self {
+        self.columns.remove(column);
+        self
+    }
+
+    pub fn get_uuid(&self, column: &str) -> Uuid {
+        self.uuid_value(column).expect("column is not a uuid")
+    }
+
+    fn uuid_value(&self, column: &str) -> Option<Uuid> {
+        match self.columns.get(column) {
+            Some(Column::Uuid(v)) => Some(*v),
+            _ => None,
+        }
+    }
+}
+
+impl OutboxRow for SyntheticOutboxRow {
+    fn is_insert(&self) -> bool {
+        is_insert_operation(&self.operation)
+    }
+
+    fn operation_name(&self) -> String {
+        format!("{:?}", self.operation)
+    }
+
+    fn uuid(&self, column: &str) -> Option<Uuid> {
+        self.uuid_value(column)
+    }
+
+    fn text(&self, column: &str) -> Option<String> {
+        match self.columns.get(column) {
+            Some(Column::Text(v)) => Some(v.clone()),
+            _ => None,
+        }
+    }
+
+    fn int(&self, column: &str) -> Option<i32> {
+        match self.columns.get(column) {
+            Some(Column::Int(v)) => Some(*v),
+            _ => None,
+        }
+    }
+
+    fn bigint(&self, column: &str) -> Option<i64> {
+        match self.columns.get(column) {
+            Some(Column::BigInt(v)) => Some(*v),
+            _ => None,
+        }
+    }
+
+    fn timestamp(&self, column: &str) -> Option<DateTime<Utc>> {
+        match self.columns.get(column) {
+            Some(Column::Timestamp(v)) => Some(*v),
+            _ => None,
+        }
+    }
+
+    fn row_change(&self) -> Option<RowChange> {
+        row_change_of(&self.operation)
+    }
+
+    fn json(&self, column: &str) -> Option<serde_json::Value> {
+        let json = match self.columns.get(column)? {
+            Column::Uuid(v) => serde_json::Value::from(v.to_string()),
+            Column::Text(v) => serde_json::Value::from(v.as_str()),
+            Column::Int(v) => serde_json::Value::from(*v),
+            Column::BigInt(v) => serde_json::Value::from(*v),
+            Column::Timestamp(v) => serde_json::Value::from(v.to_rfc3339()),
+        };
+        Some(json)
+    }
+}
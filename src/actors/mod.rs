@@ -5,7 +5,7 @@
 // Actor-based infrastructure for asynchronous, concurrent operations.
 //
 // Structure:
-// - core/           - Abstract traits and types (HealthCheckable, SupervisedActor)
+// - core/           - Abstract traits and types (HealthCheckable, SupervisedActor, PriorityMailbox)
 // - infrastructure/ - Concrete infrastructure actors (CDC, DLQ, Health, Coordinator)
 //
 // Note: Domain logic (Order, Customer, etc.) uses CommandHandlers, NOT actors.
@@ -18,16 +18,21 @@ mod core;
 mod infrastructure;
 
 // Re-export only what's needed in the public API
-pub use infrastructure::CoordinatorActor;
+pub use infrastructure::{CdcProcessor, DrainCdc, DeadLetterSink, DlqActor, DlqWriterConfig, DlqQuarantinePolicy, AddToDlq, DeleteDlqMessage, DlqFilter, DlqMessage, DlqStats, FailureContext, GetDlqStats, ListDlqMessages, ReplayDlqMessage, ReplayOutcome, list_dlq_messages};
+pub use infrastructure::{ApprovalGate, CdcThrottle, CdcThrottleConfig, CoordinatorActor, GetDlqActor, HealthHistory, HealthRegistry, HealthSnapshot, OutboxRetention, PublishPoolConfig, ScheduleSink, ScheduledPublication, Shutdown, StartupPhase, StartupPolicy, StartupSequencer, StartupStatus};
+// Components register HealthCheckable implementations with a HealthRegistry
+pub use core::{HealthStatus, ComponentHealth, HealthCheckable};
+// The coordinator's restart policy is configured through AppConfig
+pub use core::RestartPolicy;
 
 // Internal re-exports for use within the crate
-pub(crate) use core::{HealthStatus, ComponentHealth, HealthCheckable};
 pub(crate) use infrastructure::{
-    CdcProcessor,
-    DlqActor,
-    HealthMonitorActor,
-    UpdateHealth,
-    GetSystemHealth,
     SystemHealth,
-    AddToDlq,
+    transition_counts,
+    load_dlq_message,
+    DecisionOutcome,
+    PublicationStatus,
+    OutboxRow,
 };
+#[cfg(test)]
+pub(crate) use infrastructure::test_support;
@@ -0,0 +1,576 @@
+use actix_web::{web, App, HttpResponse, HttpServer, Responder};
+use chrono::{DateTime, Utc};
+use kameo::actor::ActorRef;
+use scylla::client::session::Session;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::actors::{
+    load_dlq_message, ApprovalGate, CoordinatorActor, DecisionOutcome, DeleteDlqMessage, DlqActor, DlqFilter, GetDlqActor, GetDlqStats,
+    ListDlqMessages, PublicationStatus, ReplayDlqMessage,
+};
+use crate::config::ConfigAuditLog;
+use crate::messaging::StateSnapshotPublisher;
+use crate::event_sourcing::{AggregateCatalog, AggregateRoot, AsOf, DomainEvent, EventFeedFilter, EventStore, DEFAULT_STATS_DAYS, DEFAULT_STATS_TOP};
+use crate::metrics::{AccessLog, MetricsHandle};
+use super::admin_auth::AdminAuth;
+use crate::projections::Paging;
+use crate::domain::order::{OrderAggregate, OrderEvent};
+use crate::domain::customer::{CustomerAggregate, CustomerEvent};
+
+// ============================================================================
+// Admin API - Aggregate Inspection for Support Teams
+// ============================================================================
+//
+// Endpoints:
+//   GET /admin/orders/{id}/versions/{version}/diff
+//   GET /admin/customers/{id}/versions/{version}/diff
+//   GET /admin/orders/{id}/state?version=N | ?at=RFC3339
+//   GET /admin/customers/{id}/state?version=N | ?at=RFC3339
+//   GET /admin/orders/events?from=&to=&event_type=&limit=&cursor=
+//   GET /admin/customers/events?from=&to=&event_type=&limit=&cursor=
+//   GET /admin/stats?days=7&top=10
+//   POST /admin/orders/versions      {"ids": [...]}
+//   POST /admin/customers/versions   {"ids": [...]}
+//   GET /admin/dlq?event_type=&aggregate_id=&failed_from=&failed_until=&limit=&cursor=
+//   GET /admin/dlq/stats
+//   GET /admin/dlq/{id}
+//   POST /admin/dlq/{id}/retry
+//   DELETE /admin/dlq/{id}
+//   GET /admin/config/history?key=&limit=
+//   GET /admin/publications?status=pending&limit=
+//   GET /admin/publications/{id}
+//   POST /admin/publications/{id}/approve   {"decided_by": "...", "reason": "..."}
+//   POST /admin/publications/{id}/reject    {"decided_by": "...", "reason": "..."}
+//   POST /admin/orders/{id}/state-snapshot
+//   POST /admin/customers/{id}/state-snapshot
+//
+// The diff endpoints return the state diff introduced by the event at
+// {version}: the aggregate is replayed to version-1 and to version, and the
+// serialized states are compared field by field.
+//
+// The state endpoints return the aggregate as it was after version N, or
+// after the events written up to a time - exactly one of the two; 404 when
+// the aggregate did not exist yet or has not reached N.
+//
+// The events endpoints page through the events of all orders (customers)
+// with a timestamp in [from, to) (RFC 3339, at most 24 hours), oldest
+// first, optionally only of the comma-separated event types.
+//
+// The stats endpoint reports aggregates per type, events appended per type
+// and day over the last `days` days, and the `top` aggregates with the most
+// events - for capacity planning. It scans the catalog and
+// aggregate_sequence, so it is not for frequent polling.
+//
+// The versions endpoints return the current version of up to
+// MAX_VERSION_LOOKUP_IDS aggregates at once (0 = does not exist).
+//
+// The DLQ listing pages through dead-lettered messages (default 50 per
+// page, `next_cursor` for the next one), optionally filtered by event type,
+// aggregate and last failure time (RFC 3339, from inclusive, until
+// exclusive). The DLQ entry endpoint returns a message with its failure
+// context (retry attempts, breaker state, broker settings) for root-cause
+// analysis. Retry publishes an entry again (removed when published,
+// quarantined after too many failures); delete drops it unpublished. The
+// stats endpoint counts entries per event type. All of them go through the
+// relay's DlqActor.
+//
+// With an admin token configured (ADMIN_TOKEN), every endpoint requires
+// `Authorization: Bearer <token>` (see admin_auth.rs).
+//
+// The config history endpoint returns recorded configuration changes,
+// newest first (default limit 50), optionally of a single key.
+//
+// The publications endpoints work the approval gate: events of
+// approval-required types wait there until approved (then published through
+// the CDC relay) or rejected. Only pending publications can be decided;
+// deciding one twice returns 409 with the first decision.
+//
+// The state-snapshot endpoints publish an aggregate's current full state to
+// its compacted state topic right away and return the published version;
+// 404 when state snapshots are disabled or the aggregate does not exist.
+//
+// ============================================================================
+
+/// Ids accepted by one versions request
+const MAX_VERSION_LOOKUP_IDS: usize = 1000;
+
+/// Largest `days` and `top` of a stats request
+const MAX_STATS_DAYS: u32 = 90;
+const MAX_STATS_TOP: usize = 100;
+
+/// Shared state for admin endpoints
+pub struct AdminState {
+    pub orders: Arc<EventStore<OrderEvent>>,
+    pub customers: Arc<EventStore<CustomerEvent>>,
+    pub session: Arc<Session>,
+    pub config_audit: Arc<ConfigAuditLog>,
+    pub approvals: Arc<ApprovalGate>,
+    /// None when state snapshots are disabled
+    pub order_states: Option<Arc<StateSnapshotPublisher<OrderAggregate>>>,
+    pub customer_states: Option<Arc<StateSnapshotPublisher<CustomerAggregate>>>,
+    /// Owner of the DlqActor the DLQ endpoints use
+    pub coordinator: ActorRef<CoordinatorActor>,
+    /// Bearer token required on every request; None leaves the API open
+    pub admin_token: Option<String>,
+}
+
+/// Start the admin HTTP server
+/// This should be called in a separate thread/runtime to avoid conflicts
+pub async fn start_admin_server(state: Arc<AdminState>, metrics: MetricsHandle, port: u16) -> std::io::Result<()> {
+    tracing::info!("🛠️  Starting admin API on http://0.0.0.0:{}/admin", port);
+    if state.admin_token.is_none() {
+        tracing::warn!("No ADMIN_TOKEN configured - the admin API accepts unauthenticated requests");
+    }
+
+    HttpServer::new(move || {
+        App::new()
+            .wrap(AdminAuth::new(state.admin_token.clone()))
+            .wrap(AccessLog::new("admin").with_metrics(metrics.clone()))
+            .app_data(web::Data::new(state.clone()))
+            .route("/admin/orders/{id}/versions/{version}/diff", web::get().to(order_diff_handler))
+            .route("/admin/customers/{id}/versions/{version}/diff", web::get().to(customer_diff_handler))
+            .route("/admin/orders/{id}/state", web::get().to(order_state_handler))
+            .route("/admin/customers/{id}/state", web::get().to(customer_state_handler))
+            .route("/admin/orders/events", web::get().to(order_events_handler))
+            .route("/admin/customers/events", web::get().to(customer_events_handler))
+            .route("/admin/stats", web::get().to(stats_handler))
+            .route("/admin/orders/versions", web::post().to(order_versions_handler))
+            .route("/admin/customers/versions", web::post().to(customer_versions_handler))
+            .route("/admin/dlq", web::get().to(dlq_list_handler))
+            .route("/admin/dlq/stats", web::get().to(dlq_stats_handler))
+            .route("/admin/dlq/{id}", web::get().to(dlq_entry_handler))
+            .route("/admin/dlq/{id}", web::delete().to(dlq_delete_handler))
+            .route("/admin/dlq/{id}/retry", web::post().to(dlq_retry_handler))
+            .route("/admin/config/history", web::get().to(config_history_handler))
+            .route("/admin/publications", web::get().to(publications_handler))
+            .route("/admin/publications/{id}", web::get().to(publication_handler))
+            .route("/admin/publications/{id}/approve", web::post().to(approve_publication_handler))
+            .route("/admin/publications/{id}/reject", web::post().to(reject_publication_handler))
+            .route("/admin/orders/{id}/state-snapshot", web::post().to(order_state_snapshot_handler))
+            .route("/admin/customers/{id}/state-snapshot", web::post().to(customer_state_snapshot_handler))
+    })
+    .bind(("0.0.0.0", port))?
+    .run()
+    .await
+}
+
+fn diff_response(aggregate_id: Uuid, version: i64, result: anyhow::Result<crate::event_sourcing::StateDiff>) -> HttpResponse {
+    match result {
+        Ok(diff) => HttpResponse::Ok().json(diff),
+        Err(e) => {
+            let message = e.to_string();
+            tracing::debug!(aggregate_id = %aggregate_id, version = version, error = %message, "Diff request failed");
+
+            if message.contains("not found") || message.contains("no event at version") {
+                HttpResponse::NotFound().json(serde_json::json!({ "error": message }))
+            } else if message.contains("must be >= 1") {
+                HttpResponse::BadRequest().json(serde_json::json!({ "error": message }))
+            } else {
+                HttpResponse::InternalServerError().json(serde_json::json!({ "error": message }))
+            }
+        }
+    }
+}
+
+async fn order_diff_handler(path: web::Path<(Uuid, i64)>, state: web::Data<Arc<AdminState>>) -> impl Responder {
+    let (aggregate_id, version) = path.into_inner();
+    let result = state.orders.diff_version::<OrderAggregate>(aggregate_id, version).await;
+    diff_response(aggregate_id, version, result)
+}
+
+async fn customer_diff_handler(path: web::Path<(Uuid, i64)>, state: web::Data<Arc<AdminState>>) -> impl Responder {
+    let (aggregate_id, version) = path.into_inner();
+    let result = state.customers.diff_version::<CustomerAggregate>(aggregate_id, version).await;
+    diff_response(aggregate_id, version, result)
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct AsOfQuery {
+    version: Option<i64>,
+    at: Option<DateTime<Utc>>,
+}
+
+impl AsOfQuery {
+    fn as_of(&self) -> Result<AsOf, &'static str> {
+        match (self.version, self.at) {
+            (Some(version), None) => Ok(AsOf::Version(version)),
+            (None, Some(at)) => Ok(AsOf::Timestamp(at)),
+            _ => Err("Give exactly one of version or at"),
+        }
+    }
+}
+
+fn state_response<A: AggregateRoot + serde::Serialize>(aggregate_id: Uuid, as_of: AsOf, result: anyhow::Result<A>) -> HttpResponse {
+    match result {
+        Ok(aggregate) => HttpResponse::Ok().json(serde_json::json!({
+            "aggregate_id": aggregate_id,
+            "version": aggregate.version(),
+            "as_of": as_of,
+            "state": aggregate,
+        })),
+        Err(e) => {
+            let message = e.to_string();
+            tracing::debug!(aggregate_id = %aggregate_id, as_of = %as_of, error = %message, "State request failed");
+
+            if message.contains("not found") || message.contains("no event at version") {
+                HttpResponse::NotFound().json(serde_json::json!({ "error": message }))
+            } else if message.contains("must be >= 1") {
+                HttpResponse::BadRequest().json(serde_json::json!({ "error": message }))
+            } else {
+                HttpResponse::InternalServerError().json(serde_json::json!({ "error": message }))
+            }
+        }
+    }
+}
+
+async fn order_state_handler(path: web::Path<Uuid>, query: web::Query<AsOfQuery>, state: web::Data<Arc<AdminState>>) -> impl Responder {
+    let aggregate_id = path.into_inner();
+    let as_of = match query.as_of() {
+        Ok(as_of) => as_of,
+        Err(error) => return HttpResponse::BadRequest().json(serde_json::json!({ "error": error })),
+    };
+    let result = state.orders.load_aggregate_at::<OrderAggregate>(aggregate_id, as_of).await;
+    state_response(aggregate_id, as_of, result)
+}
+
+async fn customer_state_handler(path: web::Path<Uuid>, query: web::Query<AsOfQuery>, state: web::Data<Arc<AdminState>>) -> impl Responder {
+    let aggregate_id = path.into_inner();
+    let as_of = match query.as_of() {
+        Ok(as_of) => as_of,
+        Err(error) => return HttpResponse::BadRequest().json(serde_json::json!({ "error": error })),
+    };
+    let result = state.customers.load_aggregate_at::<CustomerAggregate>(aggregate_id, as_of).await;
+    state_response(aggregate_id, as_of, result)
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct EventFeedQuery {
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    /// Comma-separated event types
+    event_type: Option<String>,
+    limit: Option<i32>,
+    cursor: Option<String>,
+}
+
+async fn events_response<E: DomainEvent>(store: &EventStore<E>, query: EventFeedQuery) -> HttpResponse {
+    let EventFeedQuery { from, to, event_type, limit, cursor } = query;
+    let event_types = event_type.iter().flat_map(|types| types.split(',')).map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect();
+    let filter = EventFeedFilter { event_types };
+
+    match store.load_events_in_range(from, to, &filter, &Paging { limit, cursor }).await {
+        Ok(page) => HttpResponse::Ok().json(page),
+        Err(e) if e.to_string() == "Invalid cursor" || e.to_string().starts_with("Invalid range") => {
+            HttpResponse::BadRequest().json(serde_json::json!({ "error": e.to_string() }))
+        }
+        Err(e) => {
+            tracing::warn!(from = %from, to = %to, filter = ?filter, error = %e, "Failed to load events by time");
+            HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn order_events_handler(query: web::Query<EventFeedQuery>, state: web::Data<Arc<AdminState>>) -> impl Responder {
+    events_response(&state.orders, query.into_inner()).await
+}
+
+async fn customer_events_handler(query: web::Query<EventFeedQuery>, state: web::Data<Arc<AdminState>>) -> impl Responder {
+    events_response(&state.customers, query.into_inner()).await
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct StatsQuery {
+    days: Option<u32>,
+    top: Option<usize>,
+}
+
+async fn stats_handler(query: web::Query<StatsQuery>, state: web::Data<Arc<AdminState>>) -> impl Responder {
+    let days = query.days.unwrap_or(DEFAULT_STATS_DAYS).clamp(1, MAX_STATS_DAYS);
+    let top = query.top.unwrap_or(DEFAULT_STATS_TOP).min(MAX_STATS_TOP);
+    let catalog = AggregateCatalog::new(state.session.clone(), state.orders.tenant().clone());
+
+    match catalog.stats(days, top).await {
+        Ok(stats) => HttpResponse::Ok().json(stats),
+        Err(e) => {
+            tracing::warn!(days = days, top = top, error = %e, "Failed to collect aggregate statistics");
+            HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct VersionsRequest {
+    ids: Vec<Uuid>,
+}
+
+fn versions_response(ids: &[Uuid], result: anyhow::Result<std::collections::HashMap<Uuid, i64>>) -> HttpResponse {
+    match result {
+        Ok(versions) => HttpResponse::Ok().json(versions),
+        Err(e) => {
+            tracing::warn!(ids = ids.len(), error = %e, "Version lookup failed");
+            HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+fn too_many_ids(ids: &[Uuid]) -> Option<HttpResponse> {
+    (ids.len() > MAX_VERSION_LOOKUP_IDS).then(|| {
+        HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("At most {} ids per request, got {}", MAX_VERSION_LOOKUP_IDS, ids.len())
+        }))
+    })
+}
+
+async fn order_versions_handler(body: web::Json<VersionsRequest>, state: web::Data<Arc<AdminState>>) -> impl Responder {
+    if let Some(response) = too_many_ids(&body.ids) {
+        return response;
+    }
+    versions_response(&body.ids, state.orders.get_versions(&body.ids).await)
+}
+
+async fn customer_versions_handler(body: web::Json<VersionsRequest>, state: web::Data<Arc<AdminState>>) -> impl Responder {
+    if let Some(response) = too_many_ids(&body.ids) {
+        return response;
+    }
+    versions_response(&body.ids, state.customers.get_versions(&body.ids).await)
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct DlqListQuery {
+    event_type: Option<String>,
+    aggregate_id: Option<Uuid>,
+    failed_from: Option<DateTime<Utc>>,
+    failed_until: Option<DateTime<Utc>>,
+    limit: Option<i32>,
+    cursor: Option<String>,
+}
+
+async fn dlq_list_handler(query: web::Query<DlqListQuery>, state: web::Data<Arc<AdminState>>) -> impl Responder {
+    let DlqListQuery { event_type, aggregate_id, failed_from, failed_until, limit, cursor } = query.into_inner();
+    let filter = DlqFilter { event_type, aggregate_id, failed_from, failed_until };
+
+    let dlq = match dlq_actor(&state).await {
+        Ok(dlq) => dlq,
+        Err(response) => return response,
+    };
+
+    match dlq.ask(ListDlqMessages { filter: filter.clone(), paging: Paging { limit, cursor } }).await {
+        Ok(page) => HttpResponse::Ok().json(page),
+        Err(e) if e.to_string().ends_with("Invalid cursor") => {
+            HttpResponse::BadRequest().json(serde_json::json!({ "error": "Invalid cursor" }))
+        }
+        Err(e) => {
+            tracing::warn!(filter = ?filter, error = %e, "Failed to list DLQ entries");
+            HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+/// The relay's DlqActor, or 503 while the coordinator has not started it
+async fn dlq_actor(state: &AdminState) -> Result<ActorRef<DlqActor>, HttpResponse> {
+    match state.coordinator.ask(GetDlqActor).await {
+        Ok(Some(dlq)) => Ok(dlq),
+        Ok(None) => Err(HttpResponse::ServiceUnavailable().json(serde_json::json!({ "error": "DLQ actor not started" }))),
+        Err(e) => Err(HttpResponse::ServiceUnavailable().json(serde_json::json!({ "error": e.to_string() }))),
+    }
+}
+
+async fn dlq_stats_handler(state: web::Data<Arc<AdminState>>) -> impl Responder {
+    let dlq = match dlq_actor(&state).await {
+        Ok(dlq) => dlq,
+        Err(response) => return response,
+    };
+    match dlq.ask(GetDlqStats).await {
+        Ok(stats) => HttpResponse::Ok().json(stats),
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to collect DLQ stats");
+            HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn dlq_retry_handler(path: web::Path<Uuid>, state: web::Data<Arc<AdminState>>) -> impl Responder {
+    let id = path.into_inner();
+    let dlq = match dlq_actor(&state).await {
+        Ok(dlq) => dlq,
+        Err(response) => return response,
+    };
+    match dlq.ask(ReplayDlqMessage { id }).await {
+        Ok(outcome) => HttpResponse::Ok().json(outcome),
+        Err(e) if e.to_string().contains("not found") => {
+            HttpResponse::NotFound().json(serde_json::json!({ "error": format!("DLQ entry {} not found", id) }))
+        }
+        Err(e) => {
+            tracing::warn!(dlq_id = %id, error = %e, "DLQ retry failed");
+            HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn dlq_delete_handler(path: web::Path<Uuid>, state: web::Data<Arc<AdminState>>) -> impl Responder {
+    let id = path.into_inner();
+    let dlq = match dlq_actor(&state).await {
+        Ok(dlq) => dlq,
+        Err(response) => return response,
+    };
+    match dlq.ask(DeleteDlqMessage { id }).await {
+        Ok(true) => HttpResponse::NoContent().finish(),
+        Ok(false) => HttpResponse::NotFound().json(serde_json::json!({ "error": format!("DLQ entry {} not found", id) })),
+        Err(e) => {
+            tracing::warn!(dlq_id = %id, error = %e, "Failed to delete DLQ entry");
+            HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn dlq_entry_handler(path: web::Path<Uuid>, state: web::Data<Arc<AdminState>>) -> impl Responder {
+    let id = path.into_inner();
+    match load_dlq_message(&state.session, id).await {
+        Ok(Some(message)) => HttpResponse::Ok().json(message),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({ "error": format!("DLQ entry {} not found", id) })),
+        Err(e) => {
+            tracing::warn!(dlq_id = %id, error = %e, "Failed to load DLQ entry");
+            HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ConfigHistoryQuery {
+    key: Option<String>,
+    limit: Option<usize>,
+}
+
+async fn config_history_handler(query: web::Query<ConfigHistoryQuery>, state: web::Data<Arc<AdminState>>) -> impl Responder {
+    let limit = query.limit.unwrap_or(50);
+    match state.config_audit.history(query.key.as_deref(), limit).await {
+        Ok(history) => HttpResponse::Ok().json(history),
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to load configuration history");
+            HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct PublicationsQuery {
+    status: Option<String>,
+    limit: Option<i32>,
+}
+
+async fn publications_handler(query: web::Query<PublicationsQuery>, state: web::Data<Arc<AdminState>>) -> impl Responder {
+    let status = match query.status.as_deref() {
+        None => PublicationStatus::Pending,
+        Some(value) => match PublicationStatus::parse(value) {
+            Some(status) => status,
+            None => {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": format!("Unknown status '{}' (pending, approved, rejected)", value)
+                }))
+            }
+        },
+    };
+
+    match state.approvals.list(status, query.limit.unwrap_or(50)).await {
+        Ok(publications) => HttpResponse::Ok().json(publications),
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to list publications");
+            HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn publication_handler(path: web::Path<Uuid>, state: web::Data<Arc<AdminState>>) -> impl Responder {
+    let id = path.into_inner();
+    match state.approvals.get(id).await {
+        Ok(Some(publication)) => HttpResponse::Ok().json(publication),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({ "error": format!("Publication {} not found", id) })),
+        Err(e) => {
+            tracing::warn!(publication_id = %id, error = %e, "Failed to load publication");
+            HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct DecisionRequest {
+    decided_by: String,
+    reason: Option<String>,
+}
+
+async fn decide_publication(id: Uuid, approve: bool, body: DecisionRequest, state: &AdminState) -> HttpResponse {
+    if body.decided_by.trim().is_empty() {
+        return HttpResponse::BadRequest().json(serde_json::json!({ "error": "decided_by is required" }));
+    }
+
+    match state.approvals.decide(id, approve, body.decided_by.trim(), body.reason.as_deref()).await {
+        Ok(DecisionOutcome::Decided(publication)) => HttpResponse::Ok().json(publication),
+        Ok(DecisionOutcome::NotFound) => {
+            HttpResponse::NotFound().json(serde_json::json!({ "error": format!("Publication {} not found", id) }))
+        }
+        Ok(DecisionOutcome::AlreadyDecided(publication)) => HttpResponse::Conflict().json(serde_json::json!({
+            "error": format!("Publication {} is already {}", id, publication.status.as_str()),
+            "publication": publication,
+        })),
+        Err(e) => {
+            tracing::warn!(publication_id = %id, approve = approve, error = %e, "Failed to decide publication");
+            HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+async fn approve_publication_handler(
+    path: web::Path<Uuid>,
+    body: web::Json<DecisionRequest>,
+    state: web::Data<Arc<AdminState>>,
+) -> impl Responder {
+    decide_publication(path.into_inner(), true, body.into_inner(), &state).await
+}
+
+async fn reject_publication_handler(
+    path: web::Path<Uuid>,
+    body: web::Json<DecisionRequest>,
+    state: web::Data<Arc<AdminState>>,
+) -> impl Responder {
+    decide_publication(path.into_inner(), false, body.into_inner(), &state).await
+}
+
+fn state_snapshot_response(aggregate_id: Uuid, topic: &str, result: anyhow::Result<i64>) -> HttpResponse {
+    match result {
+        Ok(version) => HttpResponse::Ok().json(serde_json::json!({
+            "aggregate_id": aggregate_id,
+            "version": version,
+            "topic": topic,
+        })),
+        Err(e) => {
+            let message = e.to_string();
+            tracing::warn!(aggregate_id = %aggregate_id, error = %message, "State snapshot request failed");
+            if message.contains("not found") {
+                HttpResponse::NotFound().json(serde_json::json!({ "error": message }))
+            } else {
+                HttpResponse::InternalServerError().json(serde_json::json!({ "error": message }))
+            }
+        }
+    }
+}
+
+fn state_snapshots_disabled() -> HttpResponse {
+    HttpResponse::NotFound().json(serde_json::json!({ "error": "State snapshots are disabled" }))
+}
+
+async fn order_state_snapshot_handler(path: web::Path<Uuid>, state: web::Data<Arc<AdminState>>) -> impl Responder {
+    let Some(publisher) = &state.order_states else {
+        return state_snapshots_disabled();
+    };
+    let aggregate_id = path.into_inner();
+    state_snapshot_response(aggregate_id, publisher.topic(), publisher.publish_now(aggregate_id).await)
+}
+
+async fn customer_state_snapshot_handler(path: web::Path<Uuid>, state: web::Data<Arc<AdminState>>) -> impl Responder {
+    let Some(publisher) = &state.customer_states else {
+        return state_snapshots_disabled();
+    };
+    let aggregate_id = path.into_inner();
+    state_snapshot_response(aggregate_id, publisher.topic(), publisher.publish_now(aggregate_id).await)
+}
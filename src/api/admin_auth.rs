@@ -0,0 +1,131 @@
+use actix_web::body::EitherBody;
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::AUTHORIZATION;
+use actix_web::HttpResponse;
+use futures_util::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use std::sync::Arc;
+
+// ============================================================================
+// Admin Auth Middleware - Bearer Token for the Admin API
+// ============================================================================
+//
+// The admin API replays and deletes dead letters, approves publications and
+// exposes aggregate state. With ADMIN_TOKEN (or `[api] admin_token`) set,
+// every request must carry it:
+//
+//   Authorization: Bearer <token>
+//
+// and is answered 401 otherwise. Without a token the API stays open, as
+// before - only acceptable when the admin port is not reachable from
+// outside. One shared token, no users or roles: enough to keep operators
+// off direct CQL access, not a replacement for a real identity provider.
+//
+// ============================================================================
+
+/// Bearer token middleware factory; without a token every request passes
+#[derive(Clone)]
+pub struct AdminAuth {
+    token: Option<Arc<str>>,
+}
+
+impl AdminAuth {
+    pub fn new(token: Option<String>) -> Self {
+        Self { token: token.map(Arc::from) }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for AdminAuth
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Transform = AdminAuthMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(AdminAuthMiddleware { service, token: self.token.clone() }))
+    }
+}
+
+pub struct AdminAuthMiddleware<S> {
+    service: S,
+    token: Option<Arc<str>>,
+}
+
+impl<S, B> Service<ServiceRequest> for AdminAuthMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        if let Some(ref token) = self.token {
+            let presented = req
+                .headers()
+                .get(AUTHORIZATION)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.strip_prefix("Bearer "));
+            if !presented.is_some_and(|presented| token_matches(token, presented.trim())) {
+                tracing::warn!(method = %req.method(), path = %req.path(), "Admin request without a valid token");
+                let response = HttpResponse::Unauthorized()
+                    .json(serde_json::json!({ "error": "Missing or invalid admin token" }));
+                return Box::pin(ready(Ok(req.into_response(response).map_into_right_body())));
+            }
+        }
+
+        let fut = self.service.call(req);
+        Box::pin(async move { Ok(fut.await?.map_into_left_body()) })
+    }
+}
+
+/// Compare without returning early on the first differing byte
+fn token_matches(expected: &str, presented: &str) -> bool {
+    expected.len() == presented.len()
+        && expected.bytes().zip(presented.bytes()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, web, App};
+
+    #[actix_web::test]
+    async fn test_token_required_when_configured() {
+        let app = test::init_service(
+            App::new()
+                .wrap(AdminAuth::new(Some("s3cret".to_string())))
+                .route("/admin/dlq", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let request = |authorization: Option<&str>| {
+            let req = test::TestRequest::get().uri("/admin/dlq");
+            match authorization {
+                Some(value) => req.insert_header((AUTHORIZATION, value)).to_request(),
+                None => req.to_request(),
+            }
+        };
+        assert_eq!(test::call_service(&app, request(Some("Bearer s3cret"))).await.status(), 200);
+        assert_eq!(test::call_service(&app, request(Some("Bearer s3cre7"))).await.status(), 401);
+        assert_eq!(test::call_service(&app, request(Some("s3cret"))).await.status(), 401);
+        assert_eq!(test::call_service(&app, request(None)).await.status(), 401);
+
+        let open = test::init_service(App::new().wrap(AdminAuth::new(None)).route("/admin/dlq", web::get().to(HttpResponse::Ok))).await;
+        assert_eq!(test::call_service(&open, request(None)).await.status(), 200);
+    }
+}
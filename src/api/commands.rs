@@ -0,0 +1,798 @@
+use actix_web::http::StatusCode;
+use actix_web::{web, App, HttpMessage, HttpRequest, HttpResponse, HttpServer};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::domain::customer::{Address, CustomerCommand, CustomerCommandHandler, CustomerError, CustomerStatus, CustomerTier, Email, PhoneNumber};
+use crate::domain::order::{OrderAggregate, OrderCommand, OrderCommandHandler, OrderError, OrderEvent, OrderItem};
+use crate::event_sourcing::{CommandContext, ConcurrencyConflict, DeadlineExceeded, EventStore, FencedOut};
+use crate::metrics::{AccessLog, CorrelationId, MetricsHandle};
+use crate::projections::{CustomerFilter, CustomerQueryService, OrderQueryService, Page, Paging, Visibility};
+use super::idempotency::{valid_key, IdempotencyStore, StoredCommand, IDEMPOTENCY_HEADER, REPLAYED_HEADER};
+
+// ============================================================================
+// Command API - HTTP Entry Point for Order and Customer Commands
+// ============================================================================
+//
+// Endpoints (JSON bodies, all optional fields may be omitted):
+//   POST /orders                          {order_id?, customer_id, items}
+//   POST /orders/{id}/items               {items, reason?}
+//   POST /orders/{id}/confirm
+//   POST /orders/{id}/ship                {tracking_number, carrier}
+//   POST /orders/{id}/deliver             {signature?}
+//   POST /orders/{id}/cancel              {reason?, cancelled_by?}
+//   POST /customers                       {customer_id?, email, first_name, last_name, phone?}
+//   POST /customers/{id}/profile          {first_name?, last_name?, phone?}
+//   POST /customers/{id}/email            {new_email}
+//   POST /customers/{id}/phone            {new_phone}
+//   POST /customers/{id}/addresses        {address_id?, address, set_as_default?}
+//   POST /customers/{id}/tier             {new_tier}
+//   POST /customers/{id}/suspend          {reason}
+//   POST /customers/{id}/reactivate       {notes?}
+//   POST /customers/{id}/deactivate       {reason}
+//   POST /customers/{id}/forget           erase personal data (see personal_data.rs)
+//
+// Reads for API clients (see src/client):
+//   GET  /orders/{id}                     current order state (replayed)
+//   GET  /orders/{id}/events?after=N      events after sequence N (max 500)
+//
+// Read model queries (eventually consistent, see order_read_model.rs):
+//   GET  /orders/{id}/view                order from order_read_model
+//   GET  /customers/{id}/orders?limit=N&cursor=C
+//                                         a customer's orders, newest first
+//   (cancelled orders are soft-deleted: add include_deleted=true to see them)
+//   GET  /customers/{id}                  customer from customer_read_model
+//   GET  /customers?email=E               the customer with that email (0 or 1 items)
+//   GET  /customers?tier=T&status=S&limit=N&cursor=C
+//                                         customers by tier and/or status
+//
+// Successful commands return the aggregate id and its new version (201 for
+// creates, 200 otherwise). Ids of created aggregates are generated unless
+// given. The X-Correlation-ID header (a UUID) is propagated into the events;
+// a new one is generated otherwise and echoed in the response. X-User-ID
+// and X-Causation-ID (UUIDs), the client ip and the User-Agent are stored
+// with the events as their audit trail.
+//
+// Commands carrying an Idempotency-Key header are applied at most once per
+// key; retries get the first result replayed (see idempotency.rs).
+// An X-Command-ID header (a UUID) is checked per aggregate by the command
+// handler instead: a repeated id returns the version of its first run, also
+// when two retries race each other.
+//
+// Every command runs under COMMAND_TIMEOUT as its deadline. Errors map to:
+//   422 domain rule violated (OrderError / CustomerError)
+//   404 aggregate does not exist
+//   409 concurrency conflict - reload and retry
+//   503 instance fenced out by a newer deployment
+//   504 deadline exceeded
+//   500 anything else
+//
+// ============================================================================
+
+/// Deadline of a single command
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub const CORRELATION_HEADER: &str = "X-Correlation-ID";
+
+/// User issuing the command (a UUID), stored with its events
+pub const USER_HEADER: &str = "X-User-ID";
+
+/// Message that caused the command (a UUID), stored with its events
+pub const CAUSATION_HEADER: &str = "X-Causation-ID";
+
+/// Caller-chosen id of the command (a UUID); repeats are not applied again
+pub const COMMAND_ID_HEADER: &str = "X-Command-ID";
+
+/// Events returned per GET /orders/{id}/events page
+const MAX_EVENTS_PAGE: usize = 500;
+
+/// Shared state for command endpoints
+pub struct CommandApiState {
+    pub orders: Arc<OrderCommandHandler>,
+    pub customers: Arc<CustomerCommandHandler>,
+    /// Order reads (GET /orders/...)
+    pub order_events: Arc<EventStore<OrderEvent>>,
+    /// Read model queries (GET /orders/{id}/view, /customers/{id}/orders)
+    pub order_queries: Arc<OrderQueryService>,
+    /// Read model queries (GET /customers, /customers/{id})
+    pub customer_queries: Arc<CustomerQueryService>,
+    pub idempotency: Arc<IdempotencyStore>,
+}
+
+/// Start the command HTTP server
+/// This should be called in a separate thread/runtime to avoid conflicts
+pub async fn start_command_server(state: Arc<CommandApiState>, metrics: MetricsHandle, port: u16) -> std::io::Result<()> {
+    tracing::info!("📮 Starting command API on http://0.0.0.0:{}", port);
+
+    HttpServer::new(move || {
+        App::new()
+            .wrap(AccessLog::new("commands").with_metrics(metrics.clone()))
+            .app_data(web::Data::new(state.clone()))
+            .route("/orders", web::post().to(create_order))
+            .route("/orders/{id}", web::get().to(get_order))
+            .route("/orders/{id}/events", web::get().to(get_order_events))
+            .route("/orders/{id}/view", web::get().to(get_order_view))
+            .route("/orders/{id}/items", web::post().to(update_order_items))
+            .route("/orders/{id}/confirm", web::post().to(confirm_order))
+            .route("/orders/{id}/ship", web::post().to(ship_order))
+            .route("/orders/{id}/deliver", web::post().to(deliver_order))
+            .route("/orders/{id}/cancel", web::post().to(cancel_order))
+            .route("/customers", web::post().to(register_customer))
+            .route("/customers", web::get().to(list_customers))
+            .route("/customers/{id}", web::get().to(get_customer_view))
+            .route("/customers/{id}/orders", web::get().to(list_customer_orders))
+            .route("/customers/{id}/profile", web::post().to(update_customer_profile))
+            .route("/customers/{id}/email", web::post().to(change_customer_email))
+            .route("/customers/{id}/phone", web::post().to(change_customer_phone))
+            .route("/customers/{id}/addresses", web::post().to(add_customer_address))
+            .route("/customers/{id}/tier", web::post().to(upgrade_customer_tier))
+            .route("/customers/{id}/suspend", web::post().to(suspend_customer))
+            .route("/customers/{id}/reactivate", web::post().to(reactivate_customer))
+            .route("/customers/{id}/deactivate", web::post().to(deactivate_customer))
+            .route("/customers/{id}/forget", web::post().to(forget_customer))
+    })
+    .bind(("0.0.0.0", port))?
+    .run()
+    .await
+}
+
+#[derive(Debug, Serialize)]
+struct CommandAccepted {
+    aggregate_id: Uuid,
+    version: i64,
+    correlation_id: Uuid,
+}
+
+/// Request context from the correlation and audit headers
+///
+/// Prefers the id the access log picked, so the request's `http_request`
+/// span and the command's spans carry the same correlation id.
+fn command_context(req: &HttpRequest) -> CommandContext {
+    let header = |name: &str| req.headers().get(name).and_then(|v| v.to_str().ok()).map(|v| v.to_string());
+    let access_log_id = req.extensions().get::<CorrelationId>().map(|c| c.0.clone());
+    let correlation_id = access_log_id
+        .or_else(|| header(CORRELATION_HEADER))
+        .and_then(|v| Uuid::parse_str(&v).ok())
+        .unwrap_or_else(Uuid::new_v4);
+
+    let mut ctx = CommandContext::new(correlation_id).with_timeout(COMMAND_TIMEOUT);
+    if let Some(user_id) = header(USER_HEADER).and_then(|v| Uuid::parse_str(&v).ok()) {
+        ctx = ctx.with_user(user_id);
+    }
+    if let Some(causation_id) = header(CAUSATION_HEADER).and_then(|v| Uuid::parse_str(&v).ok()) {
+        ctx = ctx.with_causation(causation_id);
+    }
+    if let Some(command_id) = header(COMMAND_ID_HEADER).and_then(|v| Uuid::parse_str(&v).ok()) {
+        ctx = ctx.with_command_id(command_id);
+    }
+    if let Some(client_ip) = req.connection_info().realip_remote_addr() {
+        ctx = ctx.with_metadata("client_ip", client_ip);
+    }
+    if let Some(user_agent) = header("User-Agent") {
+        ctx = ctx.with_metadata("user_agent", &user_agent);
+    }
+    ctx
+}
+
+/// HTTP status for a failed command
+fn error_status(error: &anyhow::Error) -> StatusCode {
+    if error.downcast_ref::<OrderError>().is_some() || error.downcast_ref::<CustomerError>().is_some() {
+        return StatusCode::UNPROCESSABLE_ENTITY;
+    }
+    if error.downcast_ref::<DeadlineExceeded>().is_some() {
+        return StatusCode::GATEWAY_TIMEOUT;
+    }
+    if error.downcast_ref::<FencedOut>().is_some() {
+        return StatusCode::SERVICE_UNAVAILABLE;
+    }
+    if error.downcast_ref::<ConcurrencyConflict>().is_some() {
+        return StatusCode::CONFLICT;
+    }
+
+    let message = error.to_string();
+    if message.contains("does not exist") || message.contains("not found") {
+        StatusCode::NOT_FOUND
+    } else {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
+fn command_response(aggregate_id: Uuid, ctx: &CommandContext, created: bool, result: anyhow::Result<i64>) -> HttpResponse {
+    match result {
+        Ok(version) => {
+            let body = CommandAccepted { aggregate_id, version, correlation_id: ctx.correlation_id };
+            let mut response = if created { HttpResponse::Created() } else { HttpResponse::Ok() };
+            response.insert_header((CORRELATION_HEADER, ctx.correlation_id.to_string())).json(body)
+        }
+        Err(e) => {
+            let status = error_status(&e);
+            if status.is_server_error() {
+                tracing::warn!(aggregate_id = %aggregate_id, correlation_id = %ctx.correlation_id, error = %e, "Command failed");
+            } else {
+                tracing::debug!(aggregate_id = %aggregate_id, correlation_id = %ctx.correlation_id, error = %e, "Command rejected");
+            }
+            HttpResponse::build(status)
+                .insert_header((CORRELATION_HEADER, ctx.correlation_id.to_string()))
+                .json(serde_json::json!({ "error": e.to_string() }))
+        }
+    }
+}
+
+/// "METHOD /path" an idempotency key is bound to
+fn request_signature(req: &HttpRequest) -> String {
+    format!("{} {}", req.method(), req.path())
+}
+
+/// The idempotency key of `req`, or the error of a malformed one (422)
+fn idempotency_key(req: &HttpRequest) -> Result<Option<String>, String> {
+    let Some(value) = req.headers().get(IDEMPOTENCY_HEADER) else {
+        return Ok(None);
+    };
+    match value.to_str() {
+        Ok(key) if valid_key(key) => Ok(Some(key.to_string())),
+        _ => Err(format!("Invalid {} header", IDEMPOTENCY_HEADER)),
+    }
+}
+
+/// Response for a request whose key already has a result
+fn replayed_response(req: &HttpRequest, stored: StoredCommand) -> HttpResponse {
+    if stored.request != request_signature(req) {
+        return HttpResponse::UnprocessableEntity().json(serde_json::json!({
+            "error": format!("{} was already used for {}", IDEMPOTENCY_HEADER, stored.request)
+        }));
+    }
+
+    let body = CommandAccepted {
+        aggregate_id: stored.aggregate_id,
+        version: stored.version,
+        correlation_id: stored.correlation_id,
+    };
+    let mut response = if stored.created { HttpResponse::Created() } else { HttpResponse::Ok() };
+    response
+        .insert_header((CORRELATION_HEADER, stored.correlation_id.to_string()))
+        .insert_header((REPLAYED_HEADER, "true"))
+        .json(body)
+}
+
+/// Run a command once per idempotency key
+async fn run_command<F>(
+    state: &CommandApiState,
+    req: &HttpRequest,
+    aggregate_id: Uuid,
+    created: bool,
+    handle: impl FnOnce(CommandContext) -> F,
+) -> HttpResponse
+where
+    F: std::future::Future<Output = anyhow::Result<i64>>,
+{
+    let key = match idempotency_key(req) {
+        Ok(key) => key,
+        Err(error) => return HttpResponse::UnprocessableEntity().json(serde_json::json!({ "error": error })),
+    };
+
+    if let Some(ref key) = key {
+        match state.idempotency.get(key).await {
+            Ok(Some(stored)) => return replayed_response(req, stored),
+            Ok(None) => {}
+            Err(e) => {
+                // Running the command without the check could apply it twice
+                tracing::warn!(error = %e, "Idempotency lookup failed");
+                return HttpResponse::ServiceUnavailable()
+                    .json(serde_json::json!({ "error": "Idempotency store unavailable, retry later" }));
+            }
+        }
+    }
+
+    let ctx = command_context(req);
+    let result = handle(ctx.clone()).await;
+
+    if let (Some(key), Ok(version)) = (&key, &result) {
+        let stored = StoredCommand {
+            request: request_signature(req),
+            aggregate_id,
+            version: *version,
+            correlation_id: ctx.correlation_id,
+            created,
+        };
+        if let Err(e) = state.idempotency.put(key, &stored).await {
+            tracing::warn!(aggregate_id = %aggregate_id, error = %e, "Failed to store idempotency key");
+        }
+    }
+
+    command_response(aggregate_id, &ctx, created, result)
+}
+
+async fn run_order_command(
+    state: &CommandApiState,
+    req: &HttpRequest,
+    order_id: Uuid,
+    created: bool,
+    command: OrderCommand,
+) -> HttpResponse {
+    run_command(state, req, order_id, created, |ctx| async move {
+        state.orders.handle(order_id, command, &ctx).await
+    })
+    .await
+}
+
+async fn run_customer_command(
+    state: &CommandApiState,
+    req: &HttpRequest,
+    customer_id: Uuid,
+    created: bool,
+    command: CustomerCommand,
+) -> HttpResponse {
+    run_command(state, req, customer_id, created, |ctx| async move {
+        state.customers.handle(customer_id, command, &ctx).await
+    })
+    .await
+}
+
+// ----------------------------------------------------------------------------
+// Orders
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize)]
+struct CreateOrderRequest {
+    order_id: Option<Uuid>,
+    customer_id: Uuid,
+    items: Vec<OrderItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateItemsRequest {
+    items: Vec<OrderItem>,
+    reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ShipOrderRequest {
+    tracking_number: String,
+    carrier: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct DeliverOrderRequest {
+    signature: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CancelOrderRequest {
+    reason: Option<String>,
+    cancelled_by: Option<Uuid>,
+}
+
+async fn create_order(req: HttpRequest, body: web::Json<CreateOrderRequest>, state: web::Data<Arc<CommandApiState>>) -> HttpResponse {
+    let body = body.into_inner();
+    let order_id = body.order_id.unwrap_or_else(Uuid::new_v4);
+    let command = OrderCommand::CreateOrder { order_id, customer_id: body.customer_id, items: body.items };
+    run_order_command(&state, &req, order_id, true, command).await
+}
+
+async fn update_order_items(
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<UpdateItemsRequest>,
+    state: web::Data<Arc<CommandApiState>>,
+) -> HttpResponse {
+    let body = body.into_inner();
+    let command = OrderCommand::UpdateItems { items: body.items, reason: body.reason };
+    run_order_command(&state, &req, path.into_inner(), false, command).await
+}
+
+async fn confirm_order(req: HttpRequest, path: web::Path<Uuid>, state: web::Data<Arc<CommandApiState>>) -> HttpResponse {
+    run_order_command(&state, &req, path.into_inner(), false, OrderCommand::ConfirmOrder).await
+}
+
+async fn ship_order(
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<ShipOrderRequest>,
+    state: web::Data<Arc<CommandApiState>>,
+) -> HttpResponse {
+    let body = body.into_inner();
+    let command = OrderCommand::ShipOrder { tracking_number: body.tracking_number, carrier: body.carrier };
+    run_order_command(&state, &req, path.into_inner(), false, command).await
+}
+
+async fn deliver_order(
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: Option<web::Json<DeliverOrderRequest>>,
+    state: web::Data<Arc<CommandApiState>>,
+) -> HttpResponse {
+    let body = body.map(|b| b.into_inner()).unwrap_or_default();
+    let command = OrderCommand::DeliverOrder { signature: body.signature };
+    run_order_command(&state, &req, path.into_inner(), false, command).await
+}
+
+async fn cancel_order(
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: Option<web::Json<CancelOrderRequest>>,
+    state: web::Data<Arc<CommandApiState>>,
+) -> HttpResponse {
+    let body = body.map(|b| b.into_inner()).unwrap_or_default();
+    let command = OrderCommand::CancelOrder { reason: body.reason, cancelled_by: body.cancelled_by };
+    run_order_command(&state, &req, path.into_inner(), false, command).await
+}
+
+async fn get_order(req: HttpRequest, path: web::Path<Uuid>, state: web::Data<Arc<CommandApiState>>) -> HttpResponse {
+    let order_id = path.into_inner();
+    let ctx = command_context(&req);
+
+    match state.order_events.load_from_snapshot_within::<OrderAggregate>(ctx.deadline(), order_id).await {
+        Ok(mut order) => {
+            // Replay does not restore the id (it is not part of OrderCreated)
+            order.id = order_id;
+            HttpResponse::Ok().json(order)
+        }
+        Err(e) => HttpResponse::build(error_status(&e)).json(serde_json::json!({ "error": e.to_string() })),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct EventsQuery {
+    #[serde(default)]
+    after: i64,
+}
+
+async fn get_order_events(
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    query: web::Query<EventsQuery>,
+    state: web::Data<Arc<CommandApiState>>,
+) -> HttpResponse {
+    let order_id = path.into_inner();
+    let ctx = command_context(&req);
+
+    match state.order_events.load_events_after_within(ctx.deadline(), order_id, query.after).await {
+        Ok(mut events) => {
+            events.truncate(MAX_EVENTS_PAGE);
+            HttpResponse::Ok().json(serde_json::json!({ "order_id": order_id, "events": events }))
+        }
+        Err(e) => HttpResponse::build(error_status(&e)).json(serde_json::json!({ "error": e.to_string() })),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OrderViewQuery {
+    #[serde(default)]
+    include_deleted: bool,
+}
+
+fn visibility(include_deleted: bool) -> Visibility {
+    if include_deleted {
+        Visibility::IncludeDeleted
+    } else {
+        Visibility::ActiveOnly
+    }
+}
+
+async fn get_order_view(
+    path: web::Path<Uuid>,
+    query: web::Query<OrderViewQuery>,
+    state: web::Data<Arc<CommandApiState>>,
+) -> HttpResponse {
+    let order_id = path.into_inner();
+
+    match state.order_queries.get_order_with_visibility(order_id, visibility(query.include_deleted)).await {
+        Ok(Some(order)) => HttpResponse::Ok().json(order),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({ "error": format!("Order not found: {}", order_id) })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() })),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ListOrdersQuery {
+    limit: Option<i32>,
+    cursor: Option<String>,
+    #[serde(default)]
+    include_deleted: bool,
+}
+
+async fn list_customer_orders(
+    path: web::Path<Uuid>,
+    query: web::Query<ListOrdersQuery>,
+    state: web::Data<Arc<CommandApiState>>,
+) -> HttpResponse {
+    let ListOrdersQuery { limit, cursor, include_deleted } = query.into_inner();
+    let paging = Paging { limit, cursor };
+
+    match state
+        .order_queries
+        .list_orders_by_customer_with_visibility(path.into_inner(), &paging, visibility(include_deleted))
+        .await
+    {
+        Ok(page) => HttpResponse::Ok().json(page),
+        Err(e) if e.to_string() == "Invalid cursor" => {
+            HttpResponse::BadRequest().json(serde_json::json!({ "error": e.to_string() }))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() })),
+    }
+}
+
+async fn get_customer_view(path: web::Path<Uuid>, state: web::Data<Arc<CommandApiState>>) -> HttpResponse {
+    let customer_id = path.into_inner();
+
+    match state.customer_queries.get_customer(customer_id).await {
+        Ok(Some(customer)) => HttpResponse::Ok().json(customer),
+        Ok(None) => {
+            HttpResponse::NotFound().json(serde_json::json!({ "error": format!("Customer not found: {}", customer_id) }))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() })),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ListCustomersQuery {
+    email: Option<String>,
+    tier: Option<CustomerTier>,
+    status: Option<CustomerStatus>,
+    limit: Option<i32>,
+    cursor: Option<String>,
+}
+
+async fn list_customers(query: web::Query<ListCustomersQuery>, state: web::Data<Arc<CommandApiState>>) -> HttpResponse {
+    let ListCustomersQuery { email, tier, status, limit, cursor } = query.into_inner();
+
+    // An email identifies at most one customer; tier/status/paging do not apply
+    let page = match email {
+        Some(email) => state
+            .customer_queries
+            .find_by_email(&email)
+            .await
+            .map(|customer| Page { items: customer.into_iter().collect(), next_cursor: None }),
+        None => {
+            let filter = CustomerFilter { tier, status };
+            state.customer_queries.list_customers(&filter, &Paging { limit, cursor }).await
+        }
+    };
+
+    match page {
+        Ok(page) => HttpResponse::Ok().json(page),
+        Err(e) if e.to_string() == "Invalid cursor" => {
+            HttpResponse::BadRequest().json(serde_json::json!({ "error": e.to_string() }))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({ "error": e.to_string() })),
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Customers
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize)]
+struct RegisterCustomerRequest {
+    customer_id: Option<Uuid>,
+    email: String,
+    first_name: String,
+    last_name: String,
+    phone: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateProfileRequest {
+    first_name: Option<String>,
+    last_name: Option<String>,
+    phone: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChangeEmailRequest {
+    new_email: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChangePhoneRequest {
+    new_phone: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AddAddressRequest {
+    address_id: Option<Uuid>,
+    address: Address,
+    #[serde(default)]
+    set_as_default: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpgradeTierRequest {
+    new_tier: CustomerTier,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReasonRequest {
+    reason: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ReactivateRequest {
+    notes: Option<String>,
+}
+
+async fn register_customer(
+    req: HttpRequest,
+    body: web::Json<RegisterCustomerRequest>,
+    state: web::Data<Arc<CommandApiState>>,
+) -> HttpResponse {
+    let body = body.into_inner();
+    let customer_id = body.customer_id.unwrap_or_else(Uuid::new_v4);
+    let command = CustomerCommand::RegisterCustomer {
+        customer_id,
+        email: Email::new(body.email),
+        first_name: body.first_name,
+        last_name: body.last_name,
+        phone: body.phone.map(PhoneNumber::new),
+    };
+    run_customer_command(&state, &req, customer_id, true, command).await
+}
+
+async fn update_customer_profile(
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<UpdateProfileRequest>,
+    state: web::Data<Arc<CommandApiState>>,
+) -> HttpResponse {
+    let body = body.into_inner();
+    let command = CustomerCommand::UpdateProfile {
+        first_name: body.first_name,
+        last_name: body.last_name,
+        phone: body.phone.map(PhoneNumber::new),
+    };
+    run_customer_command(&state, &req, path.into_inner(), false, command).await
+}
+
+async fn change_customer_email(
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<ChangeEmailRequest>,
+    state: web::Data<Arc<CommandApiState>>,
+) -> HttpResponse {
+    let command = CustomerCommand::ChangeEmail { new_email: Email::new(body.into_inner().new_email) };
+    run_customer_command(&state, &req, path.into_inner(), false, command).await
+}
+
+async fn change_customer_phone(
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<ChangePhoneRequest>,
+    state: web::Data<Arc<CommandApiState>>,
+) -> HttpResponse {
+    let command = CustomerCommand::ChangePhone { new_phone: PhoneNumber::new(body.into_inner().new_phone) };
+    run_customer_command(&state, &req, path.into_inner(), false, command).await
+}
+
+async fn add_customer_address(
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<AddAddressRequest>,
+    state: web::Data<Arc<CommandApiState>>,
+) -> HttpResponse {
+    let body = body.into_inner();
+    let command = CustomerCommand::AddAddress {
+        address_id: body.address_id.unwrap_or_else(Uuid::new_v4),
+        address: body.address,
+        set_as_default: body.set_as_default,
+    };
+    run_customer_command(&state, &req, path.into_inner(), false, command).await
+}
+
+async fn upgrade_customer_tier(
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<UpgradeTierRequest>,
+    state: web::Data<Arc<CommandApiState>>,
+) -> HttpResponse {
+    let command = CustomerCommand::UpgradeTier { new_tier: body.into_inner().new_tier };
+    run_customer_command(&state, &req, path.into_inner(), false, command).await
+}
+
+async fn suspend_customer(
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<ReasonRequest>,
+    state: web::Data<Arc<CommandApiState>>,
+) -> HttpResponse {
+    let command = CustomerCommand::SuspendCustomer { reason: body.into_inner().reason };
+    run_customer_command(&state, &req, path.into_inner(), false, command).await
+}
+
+async fn reactivate_customer(
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: Option<web::Json<ReactivateRequest>>,
+    state: web::Data<Arc<CommandApiState>>,
+) -> HttpResponse {
+    let body = body.map(|b| b.into_inner()).unwrap_or_default();
+    let command = CustomerCommand::ReactivateCustomer { notes: body.notes };
+    run_customer_command(&state, &req, path.into_inner(), false, command).await
+}
+
+async fn deactivate_customer(
+    req: HttpRequest,
+    path: web::Path<Uuid>,
+    body: web::Json<ReasonRequest>,
+    state: web::Data<Arc<CommandApiState>>,
+) -> HttpResponse {
+    let command = CustomerCommand::DeactivateCustomer { reason: body.into_inner().reason };
+    run_customer_command(&state, &req, path.into_inner(), false, command).await
+}
+
+async fn forget_customer(req: HttpRequest, path: web::Path<Uuid>, state: web::Data<Arc<CommandApiState>>) -> HttpResponse {
+    let customer_id = path.into_inner();
+    let customers = state.customers.clone();
+    run_command(&state, &req, customer_id, false, |ctx| async move {
+        customers.forget(customer_id, &ctx).await
+    })
+    .await
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_domain_errors_are_unprocessable() {
+        let error = anyhow::Error::new(OrderError::NotConfirmed);
+        assert_eq!(error_status(&error), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let error = anyhow::Error::new(CustomerError::AlreadySuspended);
+        assert_eq!(error_status(&error), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[test]
+    fn test_infrastructure_errors_map_to_statuses() {
+        let missing = anyhow::anyhow!("Aggregate does not exist: {}", Uuid::new_v4());
+        assert_eq!(error_status(&missing), StatusCode::NOT_FOUND);
+
+        let conflict = anyhow::Error::new(ConcurrencyConflict { aggregate_id: Uuid::new_v4(), expected_version: 3, current_version: 4 });
+        assert_eq!(error_status(&conflict), StatusCode::CONFLICT);
+
+        let late = anyhow::Error::new(DeadlineExceeded { operation: "event_store.append".to_string(), budget: COMMAND_TIMEOUT });
+        assert_eq!(error_status(&late), StatusCode::GATEWAY_TIMEOUT);
+
+        assert_eq!(error_status(&anyhow::anyhow!("connection reset")), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn test_command_context_uses_access_log_correlation_id() {
+        let logged = Uuid::new_v4();
+        let req = actix_web::test::TestRequest::default()
+            .insert_header((CORRELATION_HEADER, Uuid::new_v4().to_string()))
+            .to_http_request();
+        req.extensions_mut().insert(CorrelationId(logged.to_string()));
+        assert_eq!(command_context(&req).correlation_id, logged);
+
+        let sent = Uuid::new_v4();
+        let req = actix_web::test::TestRequest::default()
+            .insert_header((CORRELATION_HEADER, sent.to_string()))
+            .to_http_request();
+        assert_eq!(command_context(&req).correlation_id, sent);
+    }
+
+    #[test]
+    fn test_command_context_carries_audit_headers() {
+        let (user_id, command_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let req = actix_web::test::TestRequest::default()
+            .insert_header((USER_HEADER, user_id.to_string()))
+            .insert_header((CAUSATION_HEADER, "not-a-uuid"))
+            .insert_header((COMMAND_ID_HEADER, command_id.to_string()))
+            .insert_header(("User-Agent", "orders-cli/1.2"))
+            .peer_addr("10.0.0.7:52000".parse().unwrap())
+            .to_http_request();
+
+        let ctx = command_context(&req);
+        assert_eq!(ctx.user_id, Some(user_id));
+        assert_eq!(ctx.causation_id, None);
+        assert_eq!(ctx.command_id, Some(command_id));
+        assert_eq!(ctx.metadata.get("client_ip").map(String::as_str), Some("10.0.0.7"));
+        assert_eq!(ctx.metadata.get("user_agent").map(String::as_str), Some("orders-cli/1.2"));
+    }
+}
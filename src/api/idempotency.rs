@@ -0,0 +1,121 @@
+use anyhow::Result;
+use scylla::client::session::Session;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+// ============================================================================
+// Idempotency Keys - Safe Retries of Commands
+// ============================================================================
+//
+// A client that times out cannot tell whether its command was applied.
+// Retrying blindly may apply it twice (two ItemsUpdated), not retrying may
+// lose it. Clients therefore send an `Idempotency-Key` header, reused for
+// every retry of the same logical call.
+//
+// The first successful result per key is stored in `command_idempotency`
+// (TTL 24h, INSERT IF NOT EXISTS). A later request with the same key gets
+// the stored result back, marked with `Idempotent-Replayed: true`, without
+// running the command again. A key reused for a different request (method
+// and path) is rejected with 422.
+//
+// Only successes are stored: a rejected command changed nothing, and
+// retrying it may legitimately succeed later (e.g. after a 409).
+//
+// Two requests with the same key racing each other both run; the second
+// usually fails on optimistic concurrency (409), which is still safe.
+//
+// ============================================================================
+
+pub const IDEMPOTENCY_HEADER: &str = "Idempotency-Key";
+pub const REPLAYED_HEADER: &str = "Idempotent-Replayed";
+
+/// How long results are kept for replay
+const IDEMPOTENCY_TTL: Duration = Duration::from_secs(24 * 3600);
+
+const MAX_KEY_LENGTH: usize = 255;
+
+/// Result of a command stored under its idempotency key
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredCommand {
+    /// "METHOD /path" of the original request
+    pub request: String,
+    pub aggregate_id: Uuid,
+    pub version: i64,
+    pub correlation_id: Uuid,
+    pub created: bool,
+}
+
+/// Whether `key` is acceptable as an idempotency key
+pub fn valid_key(key: &str) -> bool {
+    !key.is_empty() && key.len() <= MAX_KEY_LENGTH && key.chars().all(|c| c.is_ascii_graphic())
+}
+
+pub struct IdempotencyStore {
+    session: Arc<Session>,
+}
+
+impl IdempotencyStore {
+    pub fn new(session: Arc<Session>) -> Self {
+        Self { session }
+    }
+
+    pub async fn get(&self, key: &str) -> Result<Option<StoredCommand>> {
+        let row = self
+            .session
+            .query_unpaged(
+                "SELECT request, aggregate_id, version, correlation_id, created \
+                 FROM command_idempotency WHERE idempotency_key = ?",
+                (key,),
+            )
+            .await?
+            .into_rows_result()?
+            .maybe_first_row::<(String, Uuid, i64, Uuid, bool)>()?;
+
+        Ok(row.map(|(request, aggregate_id, version, correlation_id, created)| StoredCommand {
+            request,
+            aggregate_id,
+            version,
+            correlation_id,
+            created,
+        }))
+    }
+
+    /// Store the first result for `key`; later ones are ignored
+    pub async fn put(&self, key: &str, command: &StoredCommand) -> Result<()> {
+        self.session
+            .query_unpaged(
+                "INSERT INTO command_idempotency (idempotency_key, request, aggregate_id, version, correlation_id, created) \
+                 VALUES (?, ?, ?, ?, ?, ?) IF NOT EXISTS USING TTL ?",
+                (
+                    key,
+                    &command.request,
+                    command.aggregate_id,
+                    command.version,
+                    command.correlation_id,
+                    command.created,
+                    IDEMPOTENCY_TTL.as_secs() as i32,
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_keys() {
+        assert!(valid_key("create-order-6f1c2a5e"));
+        assert!(valid_key(&Uuid::new_v4().to_string()));
+        assert!(!valid_key(""));
+        assert!(!valid_key("has space"));
+        assert!(!valid_key(&"k".repeat(MAX_KEY_LENGTH + 1)));
+    }
+}
@@ -0,0 +1,21 @@
+// ============================================================================
+// API Module - HTTP Endpoints
+// ============================================================================
+//
+// Structure:
+// - admin       - Support/operator endpoints (aggregate version diffs, DLQ management)
+// - admin_auth  - Bearer token middleware of the admin API
+// - commands    - REST command API for Order and Customer aggregates
+// - idempotency - Idempotency-Key storage for safely retried commands
+//
+// ============================================================================
+
+mod admin;
+mod admin_auth;
+mod commands;
+mod idempotency;
+
+pub use admin::{start_admin_server, AdminState};
+pub use admin_auth::AdminAuth;
+pub use commands::{start_command_server, CommandApiState, CAUSATION_HEADER, COMMAND_ID_HEADER, CORRELATION_HEADER, USER_HEADER};
+pub use idempotency::{IdempotencyStore, IDEMPOTENCY_HEADER, REPLAYED_HEADER};
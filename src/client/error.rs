@@ -0,0 +1,81 @@
+use std::time::Duration;
+
+use crate::utils::IsTransient;
+
+/// Failure of a client call
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    /// Connection failure, timeout of a single attempt, broken response
+    #[error("Transport error: {0}")]
+    Transport(#[from] reqwest::Error),
+
+    /// The service answered with a non-success status
+    #[error("HTTP {status}: {message}")]
+    Api { status: u16, message: String },
+
+    /// The call's overall deadline passed, retries included
+    #[error("Deadline of {0:?} exceeded")]
+    DeadlineExceeded(Duration),
+}
+
+impl ClientError {
+    /// HTTP status of an API error
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            ClientError::Api { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(404)
+    }
+
+    /// Optimistic concurrency conflict: reload and decide again
+    pub fn is_conflict(&self) -> bool {
+        self.status() == Some(409)
+    }
+}
+
+/// Statuses worth retrying with the same idempotency key
+///
+/// 409 is not among them: whether the command still makes sense against the
+/// newer state is the caller's decision.
+pub(crate) fn is_retryable_status(status: u16) -> bool {
+    matches!(status, 429 | 500 | 502 | 503 | 504)
+}
+
+impl IsTransient for ClientError {
+    fn is_transient(&self) -> bool {
+        match self {
+            ClientError::Transport(e) => !e.is_decode() && !e.is_builder(),
+            ClientError::Api { status, .. } => is_retryable_status(*status),
+            ClientError::DeadlineExceeded(_) => false,
+        }
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api(status: u16) -> ClientError {
+        ClientError::Api { status, message: String::new() }
+    }
+
+    #[test]
+    fn test_retryable_statuses() {
+        for status in [429, 500, 502, 503, 504] {
+            assert!(api(status).is_transient(), "{} should be retried", status);
+        }
+        for status in [400, 404, 409, 422] {
+            assert!(!api(status).is_transient(), "{} should not be retried", status);
+        }
+        assert!(!ClientError::DeadlineExceeded(Duration::from_secs(1)).is_transient());
+        assert!(api(409).is_conflict() && api(404).is_not_found());
+    }
+}
@@ -0,0 +1,21 @@
+// ============================================================================
+// Client SDK - Typed Access to the Command API for Other Rust Services
+// ============================================================================
+//
+// Built with `--features client`. Wraps the REST command API (src/api) so
+// integrating services do not hand-write HTTP calls, status mapping and
+// retry loops. The service exposes HTTP only; there is no gRPC surface.
+//
+// - error  - ClientError, classified into retryable and permanent failures
+// - orders - OrdersClient: commands, reads and a polled event stream
+//
+// Every logical call carries one Idempotency-Key, reused across its retries
+// and also sent as X-Command-ID, so a retried command is applied at most once.
+//
+// ============================================================================
+
+mod error;
+mod orders;
+
+pub use error::ClientError;
+pub use orders::{CommandAccepted, OrdersClient};
@@ -0,0 +1,262 @@
+use futures_util::stream::{self, Stream};
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::time::Duration;
+use uuid::Uuid;
+
+use super::error::ClientError;
+use crate::api::{COMMAND_ID_HEADER, CORRELATION_HEADER, IDEMPOTENCY_HEADER, REPLAYED_HEADER};
+use crate::domain::order::{OrderAggregate, OrderEvent, OrderItem};
+use crate::event_sourcing::EventEnvelope;
+use crate::utils::{retry_on_transient, RetryConfig, RetryResult};
+
+/// Timeout of a single HTTP attempt
+const DEFAULT_ATTEMPT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Budget of a whole call, retries included
+const DEFAULT_DEADLINE: Duration = Duration::from_secs(15);
+
+/// Pause between polls once the event stream has caught up
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A command the service applied
+#[derive(Debug, Clone, Deserialize)]
+pub struct CommandAccepted {
+    pub aggregate_id: Uuid,
+    /// Aggregate version after the command
+    pub version: i64,
+    pub correlation_id: Uuid,
+    /// True when an earlier attempt had already applied the command
+    #[serde(skip)]
+    pub replayed: bool,
+}
+
+#[derive(Deserialize)]
+struct EventsPage {
+    events: Vec<EventEnvelope<OrderEvent>>,
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    error: String,
+}
+
+/// Typed client for the order endpoints of the command API
+pub struct OrdersClient {
+    http: reqwest::Client,
+    base_url: String,
+    attempt_timeout: Duration,
+    deadline: Duration,
+    poll_interval: Duration,
+    retry: RetryConfig,
+}
+
+impl OrdersClient {
+    /// Client for the command API at `base_url` (e.g. "http://orders:8081")
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            attempt_timeout: DEFAULT_ATTEMPT_TIMEOUT,
+            deadline: DEFAULT_DEADLINE,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            retry: RetryConfig::default(),
+        }
+    }
+
+    /// Timeout of each HTTP attempt
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.attempt_timeout = timeout;
+        self
+    }
+
+    /// Overall budget of each call, retries included
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    pub fn with_retry_config(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    // ------------------------------------------------------------------------
+    // Commands
+    // ------------------------------------------------------------------------
+
+    /// Create an order; its id is chosen here so a lost response does not
+    /// lose the order
+    pub async fn create_order(&self, customer_id: Uuid, items: Vec<OrderItem>) -> Result<CommandAccepted, ClientError> {
+        let body = serde_json::json!({ "order_id": Uuid::new_v4(), "customer_id": customer_id, "items": items });
+        self.command("/orders".to_string(), body).await
+    }
+
+    pub async fn update_items(&self, order_id: Uuid, items: Vec<OrderItem>, reason: Option<String>) -> Result<CommandAccepted, ClientError> {
+        let body = serde_json::json!({ "items": items, "reason": reason });
+        self.command(format!("/orders/{}/items", order_id), body).await
+    }
+
+    pub async fn confirm_order(&self, order_id: Uuid) -> Result<CommandAccepted, ClientError> {
+        self.command(format!("/orders/{}/confirm", order_id), serde_json::json!({})).await
+    }
+
+    pub async fn ship_order(&self, order_id: Uuid, tracking_number: &str, carrier: &str) -> Result<CommandAccepted, ClientError> {
+        let body = serde_json::json!({ "tracking_number": tracking_number, "carrier": carrier });
+        self.command(format!("/orders/{}/ship", order_id), body).await
+    }
+
+    pub async fn cancel_order(&self, order_id: Uuid, reason: Option<String>) -> Result<CommandAccepted, ClientError> {
+        let body = serde_json::json!({ "reason": reason });
+        self.command(format!("/orders/{}/cancel", order_id), body).await
+    }
+
+    // ------------------------------------------------------------------------
+    // Reads
+    // ------------------------------------------------------------------------
+
+    pub async fn get_order(&self, order_id: Uuid) -> Result<OrderAggregate, ClientError> {
+        let url = &self.url(&format!("/orders/{}", order_id));
+        self.with_retries(move |_| async move {
+            let response = self.http.get(url).timeout(self.attempt_timeout).send().await?;
+            Ok(checked(response).await?.json().await?)
+        })
+        .await
+    }
+
+    /// Events of an order after sequence number `after`, one page
+    pub async fn events_after(&self, order_id: Uuid, after: i64) -> Result<Vec<EventEnvelope<OrderEvent>>, ClientError> {
+        let url = &self.url(&format!("/orders/{}/events?after={}", order_id, after));
+        self.with_retries(move |_| async move {
+            let response = self.http.get(url).timeout(self.attempt_timeout).send().await?;
+            let page: EventsPage = checked(response).await?.json().await?;
+            Ok(page.events)
+        })
+        .await
+    }
+
+    /// Follow an order's events from sequence number `after` (0 = all)
+    ///
+    /// Polls the service; pages are fetched back to back while behind, then
+    /// every poll interval. The stream never ends on its own: failed polls
+    /// (after retries) are yielded as errors and polling continues, so drop
+    /// the stream to stop.
+    pub fn stream_events(
+        &self,
+        order_id: Uuid,
+        after: i64,
+    ) -> impl Stream<Item = Result<EventEnvelope<OrderEvent>, ClientError>> + '_ {
+        let state = (after, VecDeque::<EventEnvelope<OrderEvent>>::new(), false);
+        stream::unfold(state, move |(mut after, mut pending, mut idle)| async move {
+            loop {
+                if let Some(event) = pending.pop_front() {
+                    after = event.sequence_number;
+                    return Some((Ok(event), (after, pending, idle)));
+                }
+                if idle {
+                    tokio::time::sleep(self.poll_interval).await;
+                }
+                match self.events_after(order_id, after).await {
+                    Ok(events) => {
+                        idle = events.is_empty();
+                        pending.extend(events);
+                    }
+                    Err(e) => return Some((Err(e), (after, pending, true))),
+                }
+            }
+        })
+    }
+
+    // ------------------------------------------------------------------------
+    // Internals
+    // ------------------------------------------------------------------------
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// POST a command; every attempt carries the same idempotency key, also
+    /// sent as the command id
+    async fn command(&self, path: String, body: serde_json::Value) -> Result<CommandAccepted, ClientError> {
+        let url = self.url(&path);
+        let key = Uuid::new_v4().to_string();
+        let correlation_id = Uuid::new_v4().to_string();
+        let (url, key, correlation_id, body) = (&url, &key, &correlation_id, &body);
+
+        self.with_retries(move |_| async move {
+            let response = self
+                .http
+                .post(url)
+                .timeout(self.attempt_timeout)
+                .header(IDEMPOTENCY_HEADER, key)
+                .header(COMMAND_ID_HEADER, key)
+                .header(CORRELATION_HEADER, correlation_id)
+                .json(body)
+                .send()
+                .await?;
+            let replayed = response.headers().contains_key(REPLAYED_HEADER);
+            let mut accepted: CommandAccepted = checked(response).await?.json().await?;
+            accepted.replayed = replayed;
+            Ok(accepted)
+        })
+        .await
+    }
+
+    /// Retry transient failures within the call's deadline
+    async fn with_retries<T, F, Fut>(&self, operation: F) -> Result<T, ClientError>
+    where
+        F: FnMut(u32) -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        match tokio::time::timeout(self.deadline, retry_on_transient(self.retry.clone(), operation)).await {
+            Ok(RetryResult::Success(value)) => Ok(value),
+            Ok(RetryResult::Failed(e)) | Ok(RetryResult::PermanentFailure(e)) => Err(e),
+            Err(_) => Err(ClientError::DeadlineExceeded(self.deadline)),
+        }
+    }
+}
+
+/// The response if successful, its error body as `ClientError::Api` otherwise
+async fn checked(response: reqwest::Response) -> Result<reqwest::Response, ClientError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let text = response.text().await.unwrap_or_default();
+    let message = serde_json::from_str::<ErrorBody>(&text).map(|body| body.error).unwrap_or(text);
+    Err(ClientError::Api { status: status.as_u16(), message })
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_url_is_normalized() {
+        let client = OrdersClient::new("http://orders:8081/");
+        let order_id = Uuid::new_v4();
+        assert_eq!(client.url(&format!("/orders/{}", order_id)), format!("http://orders:8081/orders/{}", order_id));
+    }
+
+    #[test]
+    fn test_command_accepted_parses_api_body() {
+        let body = serde_json::json!({
+            "aggregate_id": Uuid::new_v4(),
+            "version": 3,
+            "correlation_id": Uuid::new_v4(),
+        });
+        let accepted: CommandAccepted = serde_json::from_value(body).unwrap();
+        assert_eq!(accepted.version, 3);
+        assert!(!accepted.replayed);
+    }
+}
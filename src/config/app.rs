@@ -0,0 +1,1894 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::actors::RestartPolicy;
+use crate::db::ConsistencyConfig;
+use crate::domain::order::RegionalTaxCalculator;
+use crate::domain::DEFAULT_CONFLICT_RETRIES;
+use crate::event_sourcing::{AesGcmCrypto, ShardLayout, SnapshotRetentionPolicy, TenantContext, DEFAULT_LOAD_PAGE_SIZE};
+use crate::loadgen::{parse_burst, LoadProfile};
+use crate::messaging::{BatchConfig, DualWriteGuard, DualWritePolicy, KeyStrategy, PayloadFormat, PublishRateLimit};
+use crate::utils::{CircuitBreakerConfig, Jitter, RetryBudget, RetryConfig};
+
+// ============================================================================
+// Application Configuration - Connections and Tuning
+// ============================================================================
+//
+// Connection settings and tuning knobs that used to be hard-coded in main.
+// Loaded in three layers, later ones winning:
+//
+//   1. defaults (the local docker-compose setup)
+//   2. a TOML file named by APP_CONFIG_FILE, if set
+//   3. environment variables
+//
+// Every section and field is optional in the file:
+//
+//   environment = "production"
+//   region = "eu-west"         # active-active: tag events with their origin and
+//                              # skip rows written by other regions in the relay
+//
+//   [scylla]
+//   nodes = ["scylla-1:9042", "scylla-2:9042"]
+//   keyspace = "orders_ks"
+//   username = "cassandra"
+//   replication_factor = 3     # of a keyspace created by `--migrate`
+//
+//   [consistency]              # per operation; default local_quorum / local_serial
+//   append = "quorum"          # event + outbox batches, snapshots
+//   read = "local_one"         # event, version and snapshot loads
+//   lwt = "quorum"             # commit phase of the version claims
+//   serial = "serial"          # Paxos phase of the version claims
+//   dlq = "local_quorum"
+//   offsets = "local_quorum"   # CDC checkpoints
+//
+//   [redpanda]
+//   brokers = "redpanda-1:9092,redpanda-2:9092"
+//   batch_max_records = 500    # publishes flushed together; 1 disables batching
+//   batch_linger_ms = 50       # flush a batch this long after its first record
+//   transactional_id = "scylladb-cdc-1"  # exactly-once: one transaction per
+//                                         # batch; unique per instance, needs
+//                                         # batch_max_records > 1
+//   rate_limit_per_sec = 2000  # publishes per second, all topics; 0 = unlimited
+//   rate_limit_burst = 500     # publishes a rate limit allows at once
+//   dual_write_policy = "deny" # direct publishes to event topics: allow, warn
+//                              # (default) or deny
+//   protected_topics = ["OrderCreated"]  # default: every domain event type
+//   [redpanda.topic_rate_limits]
+//   OrderCreated = 200         # publishes per second of one topic
+//
+//   [retry]
+//   max_attempts = 8
+//   jitter = "full"            # none, full, equal or decorrelated
+//   budget_per_sec = 20        # retries per second across all relays; 0 = unlimited
+//   budget_burst = 100
+//
+//   [circuit_breaker]
+//   failure_threshold = 5      # consecutive failures that open the circuit
+//   failure_rate = 0.5         # or this share of the last window_size calls; 0 = off
+//   window_size = 100
+//   minimum_calls = 20         # calls the window needs before the rate counts
+//   half_open_max_calls = 3    # trial calls at once while half-open
+//
+//   [event_store]
+//   shards = 8                 # change only together with `reshard`
+//   conflict_retries = 3       # commands retried after a version conflict
+//   load_page_size = 1000      # events fetched per page when loading an aggregate
+//   snapshot_keep_last = 3     # snapshots kept per aggregate, in every tenant
+//   snapshot_prune_interval_secs = 3600  # 0 disables snapshot pruning
+//
+//   [encryption]               # AES-256-GCM payloads in event store, outbox, snapshots
+//   keys = ["k2:<base64 32-byte key>", "k1:<base64 32-byte key>"]
+//                              # first seals new events, all open old ones
+//
+//   [cdc]
+//   mode = "polling"           # read the outbox tables directly where CDC
+//                              # streaming is unavailable (default "streaming")
+//   poll_interval_ms = 1000    # pause between polls in polling mode
+//   approval_required = ["RefundIssued"]   # parked until approved (admin API)
+//   dedup_ttl_secs = 604800    # remember published events; 0 disables dedup
+//   payload_format = "envelope"  # publish whole EventEnvelope JSON (default "event")
+//   key_strategy = "aggregate_id"  # Kafka key: aggregate_id, correlation_id,
+//                                  # event_type, partition_key (custom), event_id
+//   publish_workers = 4        # concurrent publishes per CDC stream (default 1)
+//   publish_queue_depth = 100  # rows queued per worker before the reader waits
+//   stream_audit = true        # flag gaps/reordering in published streams
+//   max_lag_secs = 60          # cdc_lag health degraded above this relay lag
+//   routing_rules_file = "routing.toml"  # fan-out of events to more topics
+//
+//   [[cdc.tables]]             # more CDC-enabled tables, one reader each
+//   table = "order_summaries"  # keyspace defaults to [scylla] keyspace
+//   topic = "order-summaries"  # row changes as JSON; omit for another outbox
+//   key_column = "order_id"
+//   columns = ["order_id", "status", "total"]
+//
+//   [[tenants]]                # isolated event stores, one outbox reader each
+//   id = "acme"
+//   keyspace = "acme_ks"       # own keyspace with the standard tables, or
+//   # table_prefix = "acme"    # acme_event_store, ... in the [scylla] keyspace
+//
+//   [state_snapshots]          # full states on order-state/customer-state
+//   enabled = true
+//   every = 50
+//   partitions = 6
+//
+//   [shutdown]
+//   timeout_secs = 30          # drain budget after SIGINT/SIGTERM
+//
+//   [supervision]              # restarts of failed CDC processors / janitor
+//   max_restarts = 5           # in a row, then the child stays down (unhealthy)
+//   initial_backoff_ms = 1000  # doubled per restart in a row...
+//   max_backoff_ms = 60000     # ...up to this
+//   reset_after_secs = 300     # up this long again: restarts count from zero
+//
+//   [outbox]
+//   retention_secs = 3600      # keep published rows this long; 0 disables the janitor
+//   cleanup_interval_secs = 300
+//   cleanup_batch = 10000      # deletes per table and pass
+//   schedule_interval_secs = 10  # release due publish_after rows; 0 publishes them at once
+//
+//   [read_models]
+//   tombstone_retention_secs = 2592000  # hard-delete cancelled orders this long
+//                                       # after cancelling; 0 (default) keeps them
+//   purge_interval_secs = 3600
+//
+//   [integrity]
+//   check_interval_secs = 3600 # outbox/event_store integrity scan; 0 (default) = off
+//
+//   [load]                     # traffic of `load-test`
+//   rate_per_sec = 50.0
+//   duration_secs = 60
+//   concurrency = 32           # commands in flight
+//   customer_ratio = 0.3       # share of customer commands, the rest are orders
+//   hot_aggregates = 5         # per type; 0 disables hot-spotting
+//   hot_fraction = 0.2         # share of commands sent to a hot aggregate
+//   burst = "30:5:4"           # <every secs>:<length secs>:<rate multiplier>
+//   seed = 42
+//
+//   [dlq]
+//   max_replays = 3            # failed replays before an entry is quarantined
+//   max_age_secs = 86400       # older entries are reported as aged
+//   age_check_interval_secs = 300
+//
+//   [fencing]                  # blue/green write fencing; off without a generation
+//   generation = 12            # increase per rollout: a newer generation
+//                              # fences out the appends of older ones
+//   name = "orders-service"    # fence shared by the deployments of the service
+//   refresh_interval_ms = 2000 # how often an instance re-reads the fence
+//
+//   [telemetry]                # span export; needs the `otel` cargo feature
+//   otlp_endpoint = "http://tempo:4317"  # OTLP/gRPC collector; unset disables export
+//   service_name = "scylladb_cdc"
+//   sample_ratio = 0.1         # share of traces exported (default 1.0)
+//
+//   [pricing]                  # orders are not priced without this section
+//   currency = "USD"
+//   tax_rate_bps = 725
+//   [pricing.unit_prices]
+//   "6f1c2a5e-0000-4000-8000-000000000001" = 1999
+//
+// Environment overrides:
+//   APP_ENV, SERVICE_REGION, SCYLLA_NODES (comma-separated), SCYLLA_KEYSPACE, SCYLLA_USERNAME,
+//   SCYLLA_PASSWORD, SCYLLA_REPLICATION_FACTOR, SCYLLA_CONSISTENCY_APPEND,
+//   SCYLLA_CONSISTENCY_READ, SCYLLA_CONSISTENCY_LWT, SCYLLA_SERIAL_CONSISTENCY,
+//   SCYLLA_CONSISTENCY_DLQ, SCYLLA_CONSISTENCY_OFFSETS, REDPANDA_BROKERS,
+//   REDPANDA_BATCH_MAX_RECORDS, REDPANDA_BATCH_LINGER_MS, REDPANDA_TRANSACTIONAL_ID,
+//   REDPANDA_RATE_LIMIT_PER_SEC, REDPANDA_RATE_LIMIT_BURST,
+//   REDPANDA_TOPIC_RATE_LIMITS (comma-separated topic=rate), REDPANDA_DUAL_WRITE_POLICY,
+//   REDPANDA_PROTECTED_TOPICS (comma-separated),
+//   CDC_OUTBOX_TABLE,
+//   CDC_APPROVAL_REQUIRED (comma-separated event types), SNAPSHOT_EVERY,
+//   EVENT_STORE_SHARDS, EVENT_STORE_LOAD_PAGE_SIZE, COMMAND_CONFLICT_RETRIES,
+//   SNAPSHOT_KEEP_LAST, SNAPSHOT_PRUNE_INTERVAL_SECS,
+//   STATE_SNAPSHOTS_ENABLED, STATE_SNAPSHOT_EVERY, RETRY_MAX_ATTEMPTS,
+//   RETRY_INITIAL_DELAY_MS, RETRY_MAX_DELAY_MS, RETRY_JITTER, RETRY_BUDGET_PER_SEC,
+//   RETRY_BUDGET_BURST, CIRCUIT_BREAKER_FAILURE_THRESHOLD,
+//   CIRCUIT_BREAKER_TIMEOUT_SECS, CIRCUIT_BREAKER_SUCCESS_THRESHOLD, CIRCUIT_BREAKER_FAILURE_RATE,
+//   CIRCUIT_BREAKER_WINDOW_SIZE, CIRCUIT_BREAKER_HALF_OPEN_MAX_CALLS, METRICS_PORT, API_PORT, ADMIN_PORT,
+//   ADMIN_TOKEN,
+//   SHUTDOWN_TIMEOUT_SECS, SUPERVISION_MAX_RESTARTS, SUPERVISION_INITIAL_BACKOFF_MS,
+//   SUPERVISION_MAX_BACKOFF_MS, CDC_DEDUP_TTL_SECS, CDC_PAYLOAD_FORMAT, CDC_KEY_STRATEGY,
+//   CDC_PUBLISH_WORKERS, CDC_PUBLISH_QUEUE_DEPTH, CDC_STREAM_AUDIT, CDC_MODE,
+//   CDC_POLL_INTERVAL_MS, CDC_MAX_LAG_SECS, ROUTING_RULES_FILE, OUTBOX_RETENTION_SECS,
+//   OUTBOX_CLEANUP_INTERVAL_SECS, OUTBOX_SCHEDULE_INTERVAL_SECS, READ_MODEL_TOMBSTONE_RETENTION_SECS,
+//   READ_MODEL_PURGE_INTERVAL_SECS, INTEGRITY_CHECK_INTERVAL_SECS, DLQ_MAX_REPLAYS, DLQ_MAX_AGE_SECS,
+//   FENCE_GENERATION, FENCE_NAME, FENCE_REFRESH_INTERVAL_MS, FENCE_HOLDER (default
+//   HOSTNAME), OTEL_EXPORTER_OTLP_ENDPOINT, OTEL_SERVICE_NAME, OTEL_TRACES_SAMPLER_ARG,
+//   EVENT_ENCRYPTION_KEYS (comma-separated <key_id>:<base64>, active first),
+//   LOAD_RATE, LOAD_DURATION_SECS, LOAD_CONCURRENCY, LOAD_CUSTOMER_RATIO,
+//   LOAD_HOT_AGGREGATES, LOAD_HOT_FRACTION, LOAD_BURST, LOAD_SEED
+//
+// Keep the password, admin token and encryption keys out of the file - set
+// SCYLLA_PASSWORD, ADMIN_TOKEN and EVENT_ENCRYPTION_KEYS instead.
+//
+// ============================================================================
+
+/// Upper bound for COMMAND_CONFLICT_RETRIES (backoff doubles per retry)
+const MAX_CONFLICT_RETRIES: u32 = 10;
+
+/// Largest TTL Scylla accepts (20 years)
+const MAX_TTL_SECS: u64 = 630_720_000;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppConfig {
+    /// "development" (default) or "production" - production redacts PII in logs
+    pub environment: String,
+    /// Region of this deployment (active-active); None for a single region
+    pub region: Option<String>,
+    pub scylla: ScyllaConfig,
+    pub consistency: ConsistencyConfig,
+    pub redpanda: RedpandaConfig,
+    pub cdc: CdcConfig,
+    pub event_store: EventStoreConfig,
+    pub tenants: Vec<TenantSettings>,
+    pub retry: RetrySettings,
+    pub circuit_breaker: CircuitBreakerSettings,
+    pub metrics: MetricsConfig,
+    pub api: ApiConfig,
+    pub pricing: Option<PricingConfig>,
+    pub state_snapshots: StateSnapshotConfig,
+    pub shutdown: ShutdownConfig,
+    pub supervision: SupervisionConfig,
+    pub outbox: OutboxConfig,
+    pub read_models: ReadModelConfig,
+    pub integrity: IntegrityConfig,
+    pub load: LoadSettings,
+    pub dlq: DlqConfig,
+    pub telemetry: TelemetryConfig,
+    pub encryption: EncryptionConfig,
+    pub fencing: FencingConfig,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            environment: "development".to_string(),
+            region: None,
+            scylla: ScyllaConfig::default(),
+            consistency: ConsistencyConfig::default(),
+            redpanda: RedpandaConfig::default(),
+            cdc: CdcConfig::default(),
+            event_store: EventStoreConfig::default(),
+            tenants: Vec::new(),
+            retry: RetrySettings::default(),
+            circuit_breaker: CircuitBreakerSettings::default(),
+            metrics: MetricsConfig::default(),
+            api: ApiConfig::default(),
+            pricing: None,
+            state_snapshots: StateSnapshotConfig::default(),
+            shutdown: ShutdownConfig::default(),
+            supervision: SupervisionConfig::default(),
+            outbox: OutboxConfig::default(),
+            read_models: ReadModelConfig::default(),
+            integrity: IntegrityConfig::default(),
+            load: LoadSettings::default(),
+            dlq: DlqConfig::default(),
+            telemetry: TelemetryConfig::default(),
+            encryption: EncryptionConfig::default(),
+            fencing: FencingConfig::default(),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScyllaConfig {
+    pub nodes: Vec<String>,
+    pub keyspace: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Replication factor of the keyspace when migrations create it
+    pub replication_factor: u32,
+}
+
+impl Default for ScyllaConfig {
+    fn default() -> Self {
+        Self {
+            nodes: vec!["127.0.0.1:9042".to_string()],
+            keyspace: "orders_ks".to_string(),
+            username: None,
+            password: None,
+            replication_factor: 1,
+        }
+    }
+}
+
+// Manual Debug so the password never ends up in logs
+impl std::fmt::Debug for ScyllaConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScyllaConfig")
+            .field("nodes", &self.nodes)
+            .field("keyspace", &self.keyspace)
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "***"))
+            .field("replication_factor", &self.replication_factor)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RedpandaConfig {
+    /// Comma-separated bootstrap servers
+    pub brokers: String,
+    /// Publishes flushed together; 1 sends every record on its own
+    pub batch_max_records: usize,
+    /// Longest wait for a batch to fill up
+    pub batch_linger_ms: u64,
+    /// Enables transactional (exactly-once) publishing; unique per instance
+    pub transactional_id: Option<String>,
+    /// Publishes per second across all topics, 0 leaves them unlimited
+    pub rate_limit_per_sec: f64,
+    /// Publishes a rate limit allows at once
+    pub rate_limit_burst: u32,
+    /// Publishes per second of single topics
+    pub topic_rate_limits: BTreeMap<String, f64>,
+    /// What a direct publish to an event topic gets
+    pub dual_write_policy: DualWritePolicy,
+    /// Event topics before the relay published to them; None protects the
+    /// topics of the domain events
+    pub protected_topics: Option<Vec<String>>,
+}
+
+impl Default for RedpandaConfig {
+    fn default() -> Self {
+        let batch = BatchConfig::default();
+        Self {
+            brokers: "127.0.0.1:9092".to_string(),
+            batch_max_records: batch.max_records,
+            batch_linger_ms: batch.linger_ms,
+            transactional_id: None,
+            rate_limit_per_sec: 0.0,
+            rate_limit_burst: 500,
+            topic_rate_limits: BTreeMap::new(),
+            dual_write_policy: DualWritePolicy::Warn,
+            protected_topics: None,
+        }
+    }
+}
+
+impl RedpandaConfig {
+    pub fn batch_config(&self) -> BatchConfig {
+        BatchConfig { max_records: self.batch_max_records, linger_ms: self.batch_linger_ms }
+    }
+
+    pub fn rate_limit(&self) -> PublishRateLimit {
+        PublishRateLimit {
+            per_sec: self.rate_limit_per_sec,
+            burst: self.rate_limit_burst,
+            topics: self.topic_rate_limits.clone(),
+        }
+    }
+
+    /// Guard of the direct publishes, protecting `protected_topics` or the
+    /// event types the relay publishes to
+    pub fn dual_write_guard(&self) -> DualWriteGuard {
+        let guard = DualWriteGuard::new(self.dual_write_policy);
+        match self.protected_topics {
+            Some(ref topics) => guard.protect_topics(topics.iter().cloned()),
+            None => guard.protect_topics(crate::domain::event_types()),
+        }
+    }
+}
+
+/// Where the CDC relay and projections read from
+#[derive(Debug, Clone, PartialEq)]
+pub struct CdcSource {
+    pub keyspace: String,
+    pub table: String,
+}
+
+impl CdcSource {
+    /// `keyspace.table` - the metrics label of its reader
+    pub fn label(&self) -> String {
+        format!("{}.{}", self.keyspace, self.table)
+    }
+}
+
+/// How the relay reads changes of the observed tables
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CdcMode {
+    /// scylla-cdc log readers (the default)
+    #[default]
+    Streaming,
+    /// Periodic reads of the outbox tables, where CDC streaming is unavailable
+    Polling,
+}
+
+impl CdcMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CdcMode::Streaming => "streaming",
+            CdcMode::Polling => "polling",
+        }
+    }
+}
+
+impl std::str::FromStr for CdcMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "streaming" => Ok(CdcMode::Streaming),
+            "polling" => Ok(CdcMode::Polling),
+            other => anyhow::bail!("Unknown CDC mode '{}' (streaming or polling)", other),
+        }
+    }
+}
+
+/// How the rows of an observed CDC table become messages
+#[derive(Debug, Clone, PartialEq)]
+pub enum CdcTopicMapping {
+    /// Outbox rows: one event per row, its event_type names the topic
+    Outbox,
+    /// Any table: each row change as JSON on one topic, keyed by a column
+    Rows {
+        topic: String,
+        key_column: String,
+        columns: Vec<String>,
+    },
+}
+
+/// A CDC-enabled table the coordinator runs a reader for
+#[derive(Debug, Clone, PartialEq)]
+pub struct CdcTable {
+    pub source: CdcSource,
+    pub mapping: CdcTopicMapping,
+    /// Tenant whose outbox this is; None for shared tables
+    pub tenant: Option<TenantContext>,
+}
+
+impl CdcTable {
+    pub fn outbox(source: CdcSource) -> Self {
+        Self { source, mapping: CdcTopicMapping::Outbox, tenant: None }
+    }
+}
+
+impl Default for CdcSource {
+    fn default() -> Self {
+        Self {
+            keyspace: ScyllaConfig::default().keyspace,
+            table: CdcConfig::default().outbox_table,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CdcConfig {
+    /// Stream the CDC logs or poll the outbox tables
+    pub mode: CdcMode,
+    /// Pause between polls of an outbox table (polling mode)
+    pub poll_interval_ms: u64,
+    /// CDC-enabled outbox table (in the Scylla keyspace)
+    pub outbox_table: String,
+    /// Event types the relay holds back until approved
+    pub approval_required: Vec<String>,
+    /// Further tables streamed next to the outbox
+    pub tables: Vec<CdcTableConfig>,
+    /// How long published events are remembered for deduplication, 0 disables it
+    pub dedup_ttl_secs: u64,
+    /// Message value of relayed outbox events (event or envelope)
+    pub payload_format: PayloadFormat,
+    /// Kafka key of relayed events; aggregate_id keeps per-aggregate order
+    pub key_strategy: KeyStrategy,
+    /// Rows published concurrently per CDC stream; 1 relays them one by one
+    pub publish_workers: usize,
+    /// Rows queued per publish worker before the CDC reader is held back
+    pub publish_queue_depth: usize,
+    /// Check the per-aggregate order of published events (stream audit)
+    pub stream_audit: bool,
+    /// Relay lag above which the cdc_lag health component is degraded
+    pub max_lag_secs: u64,
+    /// TOML routing rules copying events to more topics
+    pub routing_rules_file: Option<String>,
+}
+
+impl Default for CdcConfig {
+    fn default() -> Self {
+        Self {
+            mode: CdcMode::default(),
+            poll_interval_ms: 1000,
+            outbox_table: "outbox_messages".to_string(),
+            approval_required: Vec::new(),
+            tables: Vec::new(),
+            dedup_ttl_secs: 7 * 24 * 60 * 60,
+            payload_format: PayloadFormat::default(),
+            key_strategy: KeyStrategy::default(),
+            publish_workers: 1,
+            publish_queue_depth: 100,
+            stream_audit: true,
+            max_lag_secs: 60,
+            routing_rules_file: None,
+        }
+    }
+}
+
+impl CdcConfig {
+    /// Retention of the published_events ledger; None disables deduplication
+    pub fn dedup_ttl(&self) -> Option<Duration> {
+        (self.dedup_ttl_secs > 0).then(|| Duration::from_secs(self.dedup_ttl_secs))
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval_ms)
+    }
+
+    pub fn max_lag(&self) -> Duration {
+        Duration::from_secs(self.max_lag_secs)
+    }
+}
+
+/// An additional CDC-enabled table ([[cdc.tables]])
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CdcTableConfig {
+    /// Defaults to the Scylla keyspace
+    pub keyspace: Option<String>,
+    pub table: String,
+    /// Publish row changes to this topic; without it the table is another outbox
+    pub topic: Option<String>,
+    /// Column keying row messages (required with `topic`)
+    pub key_column: Option<String>,
+    /// Columns carried by row messages (required with `topic`)
+    #[serde(default)]
+    pub columns: Vec<String>,
+}
+
+/// A tenant with its own event store tables ([[tenants]])
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TenantSettings {
+    pub id: String,
+    /// Keyspace holding the tenant's tables
+    pub keyspace: Option<String>,
+    /// Prefix of the tenant's tables in the Scylla keyspace
+    pub table_prefix: Option<String>,
+}
+
+impl TenantSettings {
+    pub fn context(&self) -> Result<TenantContext> {
+        match (&self.keyspace, &self.table_prefix) {
+            (Some(keyspace), None) => TenantContext::keyspace(&self.id, keyspace),
+            (None, Some(prefix)) => TenantContext::table_prefix(&self.id, prefix),
+            _ => anyhow::bail!("Tenant {} needs exactly one of keyspace and table_prefix", self.id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EventStoreConfig {
+    /// Snapshot aggregates every N events, 0 disables snapshots
+    pub snapshot_every: i64,
+    /// Event tables aggregates are spread over, 1 keeps the single event_store table
+    pub shards: u32,
+    /// Command retries after a concurrency conflict, 0 fails on the first conflict
+    pub conflict_retries: u32,
+    /// Events fetched per page when loading an aggregate's history
+    pub load_page_size: i32,
+    /// Snapshots kept per aggregate by the pruner
+    pub snapshot_keep_last: usize,
+    /// How often snapshots are pruned, 0 disables pruning
+    pub snapshot_prune_interval_secs: u64,
+}
+
+impl Default for EventStoreConfig {
+    fn default() -> Self {
+        Self {
+            snapshot_every: 100,
+            shards: 1,
+            conflict_retries: DEFAULT_CONFLICT_RETRIES,
+            load_page_size: DEFAULT_LOAD_PAGE_SIZE,
+            snapshot_keep_last: 3,
+            snapshot_prune_interval_secs: 3600,
+        }
+    }
+}
+
+impl EventStoreConfig {
+    pub fn shard_layout(&self) -> Result<ShardLayout> {
+        ShardLayout::new(self.shards).context("Invalid EVENT_STORE_SHARDS")
+    }
+
+    /// Snapshot pruning policy; None when pruning is disabled
+    pub fn snapshot_retention(&self) -> Option<SnapshotRetentionPolicy> {
+        (self.snapshot_prune_interval_secs > 0).then(|| SnapshotRetentionPolicy {
+            keep_last: self.snapshot_keep_last,
+            interval: Duration::from_secs(self.snapshot_prune_interval_secs),
+        })
+    }
+}
+
+/// Retry of CDC event publishing (see utils::RetryConfig)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetrySettings {
+    pub max_attempts: u32,
+    pub initial_delay_ms: u64,
+    pub max_delay_ms: u64,
+    pub multiplier: f64,
+    pub jitter: Jitter,
+    /// Retries per second shared by all CDC relays, 0 leaves them unlimited
+    pub budget_per_sec: f64,
+    /// Retries the budget allows at once
+    pub budget_burst: u32,
+}
+
+impl Default for RetrySettings {
+    fn default() -> Self {
+        let aggressive = RetryConfig::aggressive();
+        Self {
+            max_attempts: aggressive.max_attempts,
+            initial_delay_ms: aggressive.initial_delay.as_millis() as u64,
+            max_delay_ms: aggressive.max_delay.as_millis() as u64,
+            multiplier: aggressive.multiplier,
+            jitter: aggressive.jitter,
+            budget_per_sec: 0.0,
+            budget_burst: 100,
+        }
+    }
+}
+
+impl RetrySettings {
+    pub fn retry_config(&self) -> RetryConfig {
+        RetryConfig {
+            max_attempts: self.max_attempts,
+            initial_delay: Duration::from_millis(self.initial_delay_ms),
+            max_delay: Duration::from_millis(self.max_delay_ms),
+            multiplier: self.multiplier,
+            jitter: self.jitter,
+            budget: None,
+        }
+    }
+
+    /// The retry budget to share between relays; None when unlimited
+    pub fn retry_budget(&self) -> Option<RetryBudget> {
+        (self.budget_per_sec > 0.0).then(|| RetryBudget::new(self.budget_per_sec, self.budget_burst))
+    }
+}
+
+/// Circuit breaker of the Redpanda producer (see utils::CircuitBreakerConfig)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CircuitBreakerSettings {
+    pub failure_threshold: u32,
+    pub timeout_secs: u64,
+    pub success_threshold: u32,
+    /// Failed share of the last `window_size` calls that opens the circuit; 0 = off
+    pub failure_rate: f64,
+    pub window_size: usize,
+    pub minimum_calls: usize,
+    pub half_open_max_calls: u32,
+}
+
+impl Default for CircuitBreakerSettings {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            timeout_secs: 30,
+            success_threshold: 3,
+            failure_rate: 0.0,
+            window_size: 100,
+            minimum_calls: 20,
+            half_open_max_calls: 3,
+        }
+    }
+}
+
+impl CircuitBreakerSettings {
+    pub fn circuit_breaker_config(&self) -> CircuitBreakerConfig {
+        CircuitBreakerConfig {
+            failure_threshold: self.failure_threshold,
+            timeout: Duration::from_secs(self.timeout_secs),
+            success_threshold: self.success_threshold,
+            failure_rate_threshold: (self.failure_rate > 0.0).then_some(self.failure_rate),
+            window_size: self.window_size,
+            minimum_calls: self.minimum_calls,
+            half_open_max_calls: self.half_open_max_calls,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsConfig {
+    pub port: u16,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self { port: 9090 }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiConfig {
+    /// Command API (POST /orders, /customers, ...)
+    pub port: u16,
+    /// Admin/support API (/admin/...)
+    pub admin_port: u16,
+    /// Bearer token the admin API requires; None leaves it open
+    pub admin_token: Option<String>,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self { port: 8080, admin_port: 8081, admin_token: None }
+    }
+}
+
+// Manual Debug so the admin token never ends up in logs
+impl std::fmt::Debug for ApiConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApiConfig")
+            .field("port", &self.port)
+            .field("admin_port", &self.admin_port)
+            .field("admin_token", &self.admin_token.as_ref().map(|_| "***"))
+            .finish()
+    }
+}
+
+/// Full aggregate states on compacted topics (order-state, customer-state)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StateSnapshotConfig {
+    pub enabled: bool,
+    /// Publish every N events, 0 publishes on demand only (admin API)
+    pub every: i64,
+    /// Partitions of the state topics when they are created
+    pub partitions: i32,
+}
+
+impl Default for StateSnapshotConfig {
+    fn default() -> Self {
+        Self { enabled: false, every: 100, partitions: 6 }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShutdownConfig {
+    /// Time to drain in-flight events after a signal before exiting anyway
+    pub timeout_secs: u64,
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        Self { timeout_secs: 30 }
+    }
+}
+
+impl ShutdownConfig {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs)
+    }
+}
+
+/// Restarts of failed child actors by the coordinator (actors::core::RestartPolicy)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SupervisionConfig {
+    /// Restarts in a row before a child is left stopped; 0 never restarts
+    pub max_restarts: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    pub reset_after_secs: u64,
+}
+
+impl Default for SupervisionConfig {
+    fn default() -> Self {
+        Self { max_restarts: 5, initial_backoff_ms: 1000, max_backoff_ms: 60_000, reset_after_secs: 300 }
+    }
+}
+
+impl SupervisionConfig {
+    pub fn restart_policy(&self) -> RestartPolicy {
+        RestartPolicy {
+            max_restarts: self.max_restarts,
+            initial_backoff: Duration::from_millis(self.initial_backoff_ms),
+            max_backoff: Duration::from_millis(self.max_backoff_ms),
+            reset_after: Duration::from_secs(self.reset_after_secs),
+            ..RestartPolicy::default()
+        }
+    }
+}
+
+/// Retention of published outbox rows (OutboxJanitor)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutboxConfig {
+    /// Keep rows this long after they were published, 0 disables the janitor
+    pub retention_secs: u64,
+    pub cleanup_interval_secs: u64,
+    /// Upper bound of deletes per table and pass
+    pub cleanup_batch: usize,
+    /// How often scheduled rows are checked for being due, 0 disables scheduling
+    pub schedule_interval_secs: u64,
+}
+
+impl Default for OutboxConfig {
+    fn default() -> Self {
+        Self { retention_secs: 3600, cleanup_interval_secs: 300, cleanup_batch: 10_000, schedule_interval_secs: 10 }
+    }
+}
+
+impl OutboxConfig {
+    /// None when the janitor is disabled
+    pub fn retention(&self) -> Option<Duration> {
+        (self.retention_secs > 0).then(|| Duration::from_secs(self.retention_secs))
+    }
+
+    pub fn cleanup_interval(&self) -> Duration {
+        Duration::from_secs(self.cleanup_interval_secs)
+    }
+
+    /// None when rows are published regardless of their publish_after
+    pub fn schedule_interval(&self) -> Option<Duration> {
+        (self.schedule_interval_secs > 0).then(|| Duration::from_secs(self.schedule_interval_secs))
+    }
+}
+
+/// Soft-deleted read model rows (projections::soft_delete)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReadModelConfig {
+    /// Hard-delete rows this long after they were soft-deleted, 0 keeps them
+    pub tombstone_retention_secs: u64,
+    pub purge_interval_secs: u64,
+}
+
+impl Default for ReadModelConfig {
+    fn default() -> Self {
+        Self { tombstone_retention_secs: 0, purge_interval_secs: 3600 }
+    }
+}
+
+impl ReadModelConfig {
+    /// None when soft-deleted rows are kept
+    pub fn tombstone_retention(&self) -> Option<Duration> {
+        (self.tombstone_retention_secs > 0).then(|| Duration::from_secs(self.tombstone_retention_secs))
+    }
+
+    pub fn purge_interval(&self) -> Duration {
+        Duration::from_secs(self.purge_interval_secs)
+    }
+}
+
+/// Scheduled outbox/event_store integrity scan (db::IntegrityChecker)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IntegrityConfig {
+    /// Scan this often (the window of each scan), 0 disables the scan
+    pub check_interval_secs: u64,
+}
+
+impl IntegrityConfig {
+    /// None when the scheduled scan is disabled
+    pub fn check_interval(&self) -> Option<Duration> {
+        (self.check_interval_secs > 0).then(|| Duration::from_secs(self.check_interval_secs))
+    }
+}
+
+/// Traffic of `load-test` (loadgen::LoadProfile)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoadSettings {
+    /// Target commands per second outside bursts
+    pub rate_per_sec: f64,
+    pub duration_secs: u64,
+    /// Maximum commands in flight
+    pub concurrency: usize,
+    /// Fraction of commands targeting customers (the rest target orders)
+    pub customer_ratio: f64,
+    /// Number of hot aggregates per type, 0 disables hot-spotting
+    pub hot_aggregates: usize,
+    /// Fraction of commands sent to a hot aggregate
+    pub hot_fraction: f64,
+    /// `<every secs>:<length secs>:<multiplier>`, e.g. "30:5:4"
+    pub burst: Option<String>,
+    pub seed: u64,
+}
+
+impl Default for LoadSettings {
+    fn default() -> Self {
+        let profile = LoadProfile::default();
+        Self {
+            rate_per_sec: profile.rate_per_sec,
+            duration_secs: profile.duration.as_secs(),
+            concurrency: profile.concurrency,
+            customer_ratio: profile.customer_ratio,
+            hot_aggregates: profile.hot_aggregates,
+            hot_fraction: profile.hot_fraction,
+            burst: None,
+            seed: profile.seed,
+        }
+    }
+}
+
+impl LoadSettings {
+    pub fn profile(&self) -> Result<LoadProfile> {
+        let profile = LoadProfile {
+            rate_per_sec: self.rate_per_sec,
+            duration: Duration::from_secs(self.duration_secs),
+            concurrency: self.concurrency,
+            customer_ratio: self.customer_ratio,
+            hot_aggregates: self.hot_aggregates,
+            hot_fraction: self.hot_fraction,
+            burst: self.burst.as_deref().map(parse_burst).transpose()?,
+            seed: self.seed,
+        };
+        profile.validate()?;
+        Ok(profile)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DlqConfig {
+    /// Failed replays after which an entry is quarantined
+    pub max_replays: i32,
+    /// Entries first failing longer ago are reported as aged
+    pub max_age_secs: u64,
+    pub age_check_interval_secs: u64,
+}
+
+impl Default for DlqConfig {
+    fn default() -> Self {
+        Self { max_replays: 3, max_age_secs: 24 * 60 * 60, age_check_interval_secs: 300 }
+    }
+}
+
+/// Span export to an OpenTelemetry collector (metrics::otel)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TelemetryConfig {
+    /// OTLP/gRPC endpoint of the collector (Jaeger, Tempo); None disables export
+    pub otlp_endpoint: Option<String>,
+    /// `service.name` of the exported spans
+    pub service_name: String,
+    /// Share of new traces that are exported, 0.0 ..= 1.0
+    pub sample_ratio: f64,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self { otlp_endpoint: None, service_name: "scylladb_cdc".to_string(), sample_ratio: 1.0 }
+    }
+}
+
+/// Encryption at rest of event payloads (event_sourcing::EventCrypto)
+#[derive(Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EncryptionConfig {
+    /// `<key_id>:<base64 key>` entries, the first one active; empty disables encryption
+    pub keys: Vec<String>,
+}
+
+impl EncryptionConfig {
+    /// Cipher over the configured keys; None when encryption is off
+    pub fn crypto(&self) -> Result<Option<AesGcmCrypto>> {
+        if self.keys.is_empty() {
+            return Ok(None);
+        }
+        AesGcmCrypto::from_key_list(&self.keys).map(Some)
+    }
+
+    /// Ids of the configured keys, active first
+    pub fn key_ids(&self) -> Vec<&str> {
+        self.keys.iter().map(|entry| entry.split(':').next().unwrap_or_default().trim()).collect()
+    }
+}
+
+// Manual Debug so the keys never end up in logs
+impl std::fmt::Debug for EncryptionConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncryptionConfig").field("key_ids", &self.key_ids()).finish()
+    }
+}
+
+/// Blue/green write fencing (event_sourcing::WriteFence)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FencingConfig {
+    /// Generation of this deployment; None disables fencing
+    pub generation: Option<i64>,
+    pub name: String,
+    pub refresh_interval_ms: u64,
+    /// Recorded as the fence holder (with the process id); default the host name
+    pub holder: Option<String>,
+}
+
+impl Default for FencingConfig {
+    fn default() -> Self {
+        Self { generation: None, name: "orders-service".to_string(), refresh_interval_ms: 2000, holder: None }
+    }
+}
+
+impl FencingConfig {
+    pub fn refresh_interval(&self) -> Duration {
+        Duration::from_millis(self.refresh_interval_ms)
+    }
+
+    /// Holder recorded by this process
+    pub fn holder(&self) -> String {
+        format!("{}-{}", self.holder.as_deref().unwrap_or("local"), std::process::id())
+    }
+}
+
+/// Regional tax pricing for orders; amounts in minor units (cents)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PricingConfig {
+    pub currency: String,
+    /// Tax rate in basis points (725 = 7.25%)
+    pub tax_rate_bps: i64,
+    #[serde(default)]
+    pub shipping: i64,
+    #[serde(default)]
+    pub free_shipping_over: Option<i64>,
+    #[serde(default)]
+    pub discount_bps: i64,
+    pub unit_prices: HashMap<Uuid, i64>,
+}
+
+impl PricingConfig {
+    pub fn calculator(&self) -> RegionalTaxCalculator {
+        self.unit_prices.iter().fold(
+            RegionalTaxCalculator::new(&self.currency, self.tax_rate_bps)
+                .with_shipping(self.shipping, self.free_shipping_over)
+                .with_discount_bps(self.discount_bps),
+            |calculator, (product_id, price)| calculator.with_unit_price(*product_id, *price),
+        )
+    }
+}
+
+impl AppConfig {
+    /// Defaults, then APP_CONFIG_FILE, then environment overrides
+    pub fn load() -> Result<Self> {
+        Self::from_lookup(
+            |name| std::env::var(name).ok(),
+            |path| std::fs::read_to_string(path).with_context(|| format!("Failed to read config file {}", path)),
+        )
+    }
+
+    fn from_lookup(
+        lookup: impl Fn(&str) -> Option<String>,
+        read_file: impl Fn(&str) -> Result<String>,
+    ) -> Result<Self> {
+        let mut config = match lookup("APP_CONFIG_FILE") {
+            Some(path) => Self::from_toml(&read_file(&path)?).with_context(|| format!("Invalid config file {}", path))?,
+            None => Self::default(),
+        };
+
+        if let Some(v) = lookup("APP_ENV") {
+            config.environment = v;
+        }
+        if let Some(v) = lookup("SERVICE_REGION") {
+            config.region = Some(v.trim().to_string()).filter(|region| !region.is_empty());
+        }
+        if let Some(v) = lookup("SCYLLA_NODES") {
+            config.scylla.nodes = v.split(',').map(|n| n.trim().to_string()).filter(|n| !n.is_empty()).collect();
+        }
+        if let Some(v) = lookup("SCYLLA_KEYSPACE") {
+            config.scylla.keyspace = v;
+        }
+        if let Some(v) = lookup("SCYLLA_USERNAME") {
+            config.scylla.username = Some(v);
+        }
+        if let Some(v) = lookup("SCYLLA_PASSWORD") {
+            config.scylla.password = Some(v);
+        }
+        if let Some(v) = lookup("SCYLLA_REPLICATION_FACTOR") {
+            config.scylla.replication_factor = parse("SCYLLA_REPLICATION_FACTOR", &v)?;
+        }
+        if let Some(v) = lookup("REDPANDA_BROKERS") {
+            config.redpanda.brokers = v;
+        }
+        if let Some(v) = lookup("REDPANDA_BATCH_MAX_RECORDS") {
+            config.redpanda.batch_max_records = parse("REDPANDA_BATCH_MAX_RECORDS", &v)?;
+        }
+        if let Some(v) = lookup("REDPANDA_BATCH_LINGER_MS") {
+            config.redpanda.batch_linger_ms = parse("REDPANDA_BATCH_LINGER_MS", &v)?;
+        }
+        if let Some(v) = lookup("REDPANDA_RATE_LIMIT_PER_SEC") {
+            config.redpanda.rate_limit_per_sec = parse("REDPANDA_RATE_LIMIT_PER_SEC", &v)?;
+        }
+        if let Some(v) = lookup("REDPANDA_RATE_LIMIT_BURST") {
+            config.redpanda.rate_limit_burst = parse("REDPANDA_RATE_LIMIT_BURST", &v)?;
+        }
+        if let Some(v) = lookup("REDPANDA_TOPIC_RATE_LIMITS") {
+            config.redpanda.topic_rate_limits = v
+                .split(',')
+                .map(str::trim)
+                .filter(|limit| !limit.is_empty())
+                .map(|limit| {
+                    let (topic, rate) = limit
+                        .split_once('=')
+                        .with_context(|| format!("Invalid REDPANDA_TOPIC_RATE_LIMITS entry '{}' (topic=rate)", limit))?;
+                    Ok((topic.trim().to_string(), parse("REDPANDA_TOPIC_RATE_LIMITS", rate)?))
+                })
+                .collect::<Result<_>>()?;
+        }
+        if let Some(v) = lookup("REDPANDA_DUAL_WRITE_POLICY") {
+            config.redpanda.dual_write_policy = v.parse().context("Invalid REDPANDA_DUAL_WRITE_POLICY")?;
+        }
+        if let Some(v) = lookup("REDPANDA_PROTECTED_TOPICS") {
+            config.redpanda.protected_topics =
+                Some(v.split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect());
+        }
+        if let Some(v) = lookup("REDPANDA_TRANSACTIONAL_ID") {
+            config.redpanda.transactional_id = Some(v.trim().to_string()).filter(|id| !id.is_empty());
+        }
+        if let Some(v) = lookup("CDC_OUTBOX_TABLE") {
+            config.cdc.outbox_table = v;
+        }
+        if let Some(v) = lookup("CDC_APPROVAL_REQUIRED") {
+            config.cdc.approval_required = v.split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect();
+        }
+        if let Some(v) = lookup("CDC_DEDUP_TTL_SECS") {
+            config.cdc.dedup_ttl_secs = parse("CDC_DEDUP_TTL_SECS", &v)?;
+        }
+        if let Some(v) = lookup("CDC_PAYLOAD_FORMAT") {
+            config.cdc.payload_format = v.parse().context("Invalid CDC_PAYLOAD_FORMAT")?;
+        }
+        if let Some(v) = lookup("CDC_KEY_STRATEGY") {
+            config.cdc.key_strategy = v.trim().parse().context("Invalid CDC_KEY_STRATEGY")?;
+        }
+        if let Some(v) = lookup("CDC_PUBLISH_WORKERS") {
+            config.cdc.publish_workers = parse("CDC_PUBLISH_WORKERS", &v)?;
+        }
+        if let Some(v) = lookup("CDC_PUBLISH_QUEUE_DEPTH") {
+            config.cdc.publish_queue_depth = parse("CDC_PUBLISH_QUEUE_DEPTH", &v)?;
+        }
+        if let Some(v) = lookup("CDC_STREAM_AUDIT") {
+            config.cdc.stream_audit = parse("CDC_STREAM_AUDIT", &v)?;
+        }
+        if let Some(v) = lookup("CDC_MAX_LAG_SECS") {
+            config.cdc.max_lag_secs = parse("CDC_MAX_LAG_SECS", &v)?;
+        }
+        if let Some(v) = lookup("CDC_MODE") {
+            config.cdc.mode = v.parse().context("Invalid CDC_MODE")?;
+        }
+        if let Some(v) = lookup("CDC_POLL_INTERVAL_MS") {
+            config.cdc.poll_interval_ms = parse("CDC_POLL_INTERVAL_MS", &v)?;
+        }
+        if let Some(v) = lookup("ROUTING_RULES_FILE") {
+            config.cdc.routing_rules_file = Some(v.trim().to_string()).filter(|path| !path.is_empty());
+        }
+        if let Some(v) = lookup("SNAPSHOT_EVERY") {
+            config.event_store.snapshot_every = parse("SNAPSHOT_EVERY", &v)?;
+        }
+        if let Some(v) = lookup("EVENT_STORE_SHARDS") {
+            config.event_store.shards = parse("EVENT_STORE_SHARDS", &v)?;
+        }
+        if let Some(v) = lookup("EVENT_STORE_LOAD_PAGE_SIZE") {
+            config.event_store.load_page_size = parse("EVENT_STORE_LOAD_PAGE_SIZE", &v)?;
+        }
+        if let Some(v) = lookup("SNAPSHOT_KEEP_LAST") {
+            config.event_store.snapshot_keep_last = parse("SNAPSHOT_KEEP_LAST", &v)?;
+        }
+        if let Some(v) = lookup("SNAPSHOT_PRUNE_INTERVAL_SECS") {
+            config.event_store.snapshot_prune_interval_secs = parse("SNAPSHOT_PRUNE_INTERVAL_SECS", &v)?;
+        }
+        if let Some(v) = lookup("COMMAND_CONFLICT_RETRIES") {
+            config.event_store.conflict_retries = parse("COMMAND_CONFLICT_RETRIES", &v)?;
+        }
+        if let Some(v) = lookup("STATE_SNAPSHOTS_ENABLED") {
+            config.state_snapshots.enabled = parse("STATE_SNAPSHOTS_ENABLED", &v)?;
+        }
+        if let Some(v) = lookup("STATE_SNAPSHOT_EVERY") {
+            config.state_snapshots.every = parse("STATE_SNAPSHOT_EVERY", &v)?;
+        }
+        if let Some(v) = lookup("RETRY_MAX_ATTEMPTS") {
+            config.retry.max_attempts = parse("RETRY_MAX_ATTEMPTS", &v)?;
+        }
+        if let Some(v) = lookup("RETRY_INITIAL_DELAY_MS") {
+            config.retry.initial_delay_ms = parse("RETRY_INITIAL_DELAY_MS", &v)?;
+        }
+        if let Some(v) = lookup("RETRY_MAX_DELAY_MS") {
+            config.retry.max_delay_ms = parse("RETRY_MAX_DELAY_MS", &v)?;
+        }
+        if let Some(v) = lookup("RETRY_JITTER") {
+            config.retry.jitter = v.parse().context("Invalid RETRY_JITTER")?;
+        }
+        if let Some(v) = lookup("RETRY_BUDGET_PER_SEC") {
+            config.retry.budget_per_sec = parse("RETRY_BUDGET_PER_SEC", &v)?;
+        }
+        if let Some(v) = lookup("RETRY_BUDGET_BURST") {
+            config.retry.budget_burst = parse("RETRY_BUDGET_BURST", &v)?;
+        }
+        if let Some(v) = lookup("CIRCUIT_BREAKER_FAILURE_THRESHOLD") {
+            config.circuit_breaker.failure_threshold = parse("CIRCUIT_BREAKER_FAILURE_THRESHOLD", &v)?;
+        }
+        if let Some(v) = lookup("CIRCUIT_BREAKER_TIMEOUT_SECS") {
+            config.circuit_breaker.timeout_secs = parse("CIRCUIT_BREAKER_TIMEOUT_SECS", &v)?;
+        }
+        if let Some(v) = lookup("CIRCUIT_BREAKER_SUCCESS_THRESHOLD") {
+            config.circuit_breaker.success_threshold = parse("CIRCUIT_BREAKER_SUCCESS_THRESHOLD", &v)?;
+        }
+        if let Some(v) = lookup("CIRCUIT_BREAKER_FAILURE_RATE") {
+            config.circuit_breaker.failure_rate = parse("CIRCUIT_BREAKER_FAILURE_RATE", &v)?;
+        }
+        if let Some(v) = lookup("CIRCUIT_BREAKER_WINDOW_SIZE") {
+            config.circuit_breaker.window_size = parse("CIRCUIT_BREAKER_WINDOW_SIZE", &v)?;
+        }
+        if let Some(v) = lookup("CIRCUIT_BREAKER_HALF_OPEN_MAX_CALLS") {
+            config.circuit_breaker.half_open_max_calls = parse("CIRCUIT_BREAKER_HALF_OPEN_MAX_CALLS", &v)?;
+        }
+        if let Some(v) = lookup("METRICS_PORT") {
+            config.metrics.port = parse("METRICS_PORT", &v)?;
+        }
+        if let Some(v) = lookup("API_PORT") {
+            config.api.port = parse("API_PORT", &v)?;
+        }
+        if let Some(v) = lookup("ADMIN_PORT") {
+            config.api.admin_port = parse("ADMIN_PORT", &v)?;
+        }
+        if let Some(v) = lookup("ADMIN_TOKEN") {
+            config.api.admin_token = Some(v).filter(|token| !token.is_empty());
+        }
+        if let Some(v) = lookup("SHUTDOWN_TIMEOUT_SECS") {
+            config.shutdown.timeout_secs = parse("SHUTDOWN_TIMEOUT_SECS", &v)?;
+        }
+        if let Some(v) = lookup("SUPERVISION_MAX_RESTARTS") {
+            config.supervision.max_restarts = parse("SUPERVISION_MAX_RESTARTS", &v)?;
+        }
+        if let Some(v) = lookup("SUPERVISION_INITIAL_BACKOFF_MS") {
+            config.supervision.initial_backoff_ms = parse("SUPERVISION_INITIAL_BACKOFF_MS", &v)?;
+        }
+        if let Some(v) = lookup("SUPERVISION_MAX_BACKOFF_MS") {
+            config.supervision.max_backoff_ms = parse("SUPERVISION_MAX_BACKOFF_MS", &v)?;
+        }
+        if let Some(v) = lookup("OUTBOX_RETENTION_SECS") {
+            config.outbox.retention_secs = parse("OUTBOX_RETENTION_SECS", &v)?;
+        }
+        if let Some(v) = lookup("OUTBOX_CLEANUP_INTERVAL_SECS") {
+            config.outbox.cleanup_interval_secs = parse("OUTBOX_CLEANUP_INTERVAL_SECS", &v)?;
+        }
+        if let Some(v) = lookup("OUTBOX_SCHEDULE_INTERVAL_SECS") {
+            config.outbox.schedule_interval_secs = parse("OUTBOX_SCHEDULE_INTERVAL_SECS", &v)?;
+        }
+        if let Some(v) = lookup("READ_MODEL_TOMBSTONE_RETENTION_SECS") {
+            config.read_models.tombstone_retention_secs = parse("READ_MODEL_TOMBSTONE_RETENTION_SECS", &v)?;
+        }
+        if let Some(v) = lookup("READ_MODEL_PURGE_INTERVAL_SECS") {
+            config.read_models.purge_interval_secs = parse("READ_MODEL_PURGE_INTERVAL_SECS", &v)?;
+        }
+        if let Some(v) = lookup("INTEGRITY_CHECK_INTERVAL_SECS") {
+            config.integrity.check_interval_secs = parse("INTEGRITY_CHECK_INTERVAL_SECS", &v)?;
+        }
+        if let Some(v) = lookup("LOAD_RATE") {
+            config.load.rate_per_sec = parse("LOAD_RATE", &v)?;
+        }
+        if let Some(v) = lookup("LOAD_DURATION_SECS") {
+            config.load.duration_secs = parse("LOAD_DURATION_SECS", &v)?;
+        }
+        if let Some(v) = lookup("LOAD_CONCURRENCY") {
+            config.load.concurrency = parse("LOAD_CONCURRENCY", &v)?;
+        }
+        if let Some(v) = lookup("LOAD_CUSTOMER_RATIO") {
+            config.load.customer_ratio = parse("LOAD_CUSTOMER_RATIO", &v)?;
+        }
+        if let Some(v) = lookup("LOAD_HOT_AGGREGATES") {
+            config.load.hot_aggregates = parse("LOAD_HOT_AGGREGATES", &v)?;
+        }
+        if let Some(v) = lookup("LOAD_HOT_FRACTION") {
+            config.load.hot_fraction = parse("LOAD_HOT_FRACTION", &v)?;
+        }
+        if let Some(v) = lookup("LOAD_BURST") {
+            config.load.burst = Some(v.trim().to_string()).filter(|burst| !burst.is_empty());
+        }
+        if let Some(v) = lookup("LOAD_SEED") {
+            config.load.seed = parse("LOAD_SEED", &v)?;
+        }
+        if let Some(v) = lookup("DLQ_MAX_REPLAYS") {
+            config.dlq.max_replays = parse("DLQ_MAX_REPLAYS", &v)?;
+        }
+        if let Some(v) = lookup("DLQ_MAX_AGE_SECS") {
+            config.dlq.max_age_secs = parse("DLQ_MAX_AGE_SECS", &v)?;
+        }
+        for (name, level) in [
+            ("SCYLLA_CONSISTENCY_APPEND", &mut config.consistency.append),
+            ("SCYLLA_CONSISTENCY_READ", &mut config.consistency.read),
+            ("SCYLLA_CONSISTENCY_LWT", &mut config.consistency.lwt),
+            ("SCYLLA_CONSISTENCY_DLQ", &mut config.consistency.dlq),
+            ("SCYLLA_CONSISTENCY_OFFSETS", &mut config.consistency.offsets),
+        ] {
+            if let Some(v) = lookup(name) {
+                *level = v.parse().with_context(|| format!("Invalid {}", name))?;
+            }
+        }
+        if let Some(v) = lookup("SCYLLA_SERIAL_CONSISTENCY") {
+            config.consistency.serial = v.parse().context("Invalid SCYLLA_SERIAL_CONSISTENCY")?;
+        }
+        if let Some(v) = lookup("FENCE_GENERATION") {
+            config.fencing.generation = Some(parse("FENCE_GENERATION", &v)?);
+        }
+        if let Some(v) = lookup("FENCE_NAME") {
+            config.fencing.name = v;
+        }
+        if let Some(v) = lookup("FENCE_REFRESH_INTERVAL_MS") {
+            config.fencing.refresh_interval_ms = parse("FENCE_REFRESH_INTERVAL_MS", &v)?;
+        }
+        if let Some(v) = lookup("FENCE_HOLDER").or_else(|| lookup("HOSTNAME")) {
+            config.fencing.holder = Some(v);
+        }
+        if let Some(v) = lookup("OTEL_EXPORTER_OTLP_ENDPOINT") {
+            config.telemetry.otlp_endpoint = Some(v.trim().to_string()).filter(|endpoint| !endpoint.is_empty());
+        }
+        if let Some(v) = lookup("OTEL_SERVICE_NAME") {
+            config.telemetry.service_name = v;
+        }
+        if let Some(v) = lookup("OTEL_TRACES_SAMPLER_ARG") {
+            config.telemetry.sample_ratio = parse("OTEL_TRACES_SAMPLER_ARG", &v)?;
+        }
+        if let Some(v) = lookup("EVENT_ENCRYPTION_KEYS") {
+            config.encryption.keys = v.split(',').map(|k| k.trim().to_string()).filter(|k| !k.is_empty()).collect();
+        }
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn from_toml(contents: &str) -> Result<Self> {
+        Ok(toml::from_str(contents)?)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.scylla.nodes.is_empty() {
+            anyhow::bail!("At least one Scylla node is required (SCYLLA_NODES)");
+        }
+        for (name, identifier) in [("keyspace", &self.scylla.keyspace), ("CDC outbox table", &self.cdc.outbox_table)] {
+            if identifier.is_empty() || !identifier.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                anyhow::bail!("Invalid {} '{}'", name, identifier);
+            }
+        }
+        if self.scylla.password.is_some() && self.scylla.username.is_none() {
+            anyhow::bail!("SCYLLA_PASSWORD is set without SCYLLA_USERNAME");
+        }
+        if self.scylla.replication_factor == 0 {
+            anyhow::bail!("SCYLLA_REPLICATION_FACTOR must be at least 1");
+        }
+        if self.redpanda.brokers.trim().is_empty() {
+            anyhow::bail!("At least one Redpanda broker is required (REDPANDA_BROKERS)");
+        }
+        if self.redpanda.batch_max_records == 0 {
+            anyhow::bail!("REDPANDA_BATCH_MAX_RECORDS must be at least 1");
+        }
+        if self.redpanda.transactional_id.is_some() && self.redpanda.batch_max_records == 1 {
+            anyhow::bail!(
+                "REDPANDA_TRANSACTIONAL_ID needs REDPANDA_BATCH_MAX_RECORDS > 1 - every record would be its own transaction"
+            );
+        }
+        let valid_rate = |rate: f64| rate.is_finite() && rate >= 0.0;
+        if !valid_rate(self.redpanda.rate_limit_per_sec) || self.redpanda.rate_limit_burst == 0 {
+            anyhow::bail!("REDPANDA_RATE_LIMIT_PER_SEC must be >= 0 and REDPANDA_RATE_LIMIT_BURST >= 1");
+        }
+        if let Some((topic, _)) = self.redpanda.topic_rate_limits.iter().find(|(_, rate)| !valid_rate(**rate)) {
+            anyhow::bail!("Rate limit of topic {} must be >= 0", topic);
+        }
+        self.validate_cdc_tables()?;
+        self.validate_tenants()?;
+        if self.cdc.dedup_ttl_secs > MAX_TTL_SECS {
+            anyhow::bail!("CDC_DEDUP_TTL_SECS must be <= {} (Scylla's TTL limit)", MAX_TTL_SECS);
+        }
+        if self.cdc.publish_workers == 0 || self.cdc.publish_queue_depth == 0 {
+            anyhow::bail!("CDC_PUBLISH_WORKERS and CDC_PUBLISH_QUEUE_DEPTH must be at least 1");
+        }
+        if self.cdc.max_lag_secs == 0 {
+            anyhow::bail!("CDC_MAX_LAG_SECS must be at least 1");
+        }
+        if self.cdc.mode == CdcMode::Polling {
+            if self.cdc.poll_interval_ms == 0 {
+                anyhow::bail!("CDC_POLL_INTERVAL_MS must be at least 1");
+            }
+            // Deletes and updates of a table only show up in its CDC log
+            if let Some(rows) = self.cdc.tables.iter().find(|extra| extra.topic.is_some()) {
+                anyhow::bail!("CDC table {} publishes row changes, which needs CDC_MODE=streaming", rows.table);
+            }
+        }
+        self.event_store.shard_layout()?;
+        if self.event_store.conflict_retries > MAX_CONFLICT_RETRIES {
+            anyhow::bail!("COMMAND_CONFLICT_RETRIES must be <= {}", MAX_CONFLICT_RETRIES);
+        }
+        if self.event_store.load_page_size < 1 {
+            anyhow::bail!("EVENT_STORE_LOAD_PAGE_SIZE must be at least 1");
+        }
+        if self.event_store.snapshot_retention().is_some() && self.event_store.snapshot_keep_last == 0 {
+            anyhow::bail!("SNAPSHOT_KEEP_LAST must be at least 1");
+        }
+        if self.state_snapshots.every < 0 {
+            anyhow::bail!("STATE_SNAPSHOT_EVERY must be >= 0");
+        }
+        if self.state_snapshots.partitions < 1 {
+            anyhow::bail!("state_snapshots.partitions must be >= 1");
+        }
+        self.consistency.validate()?;
+        if self.supervision.initial_backoff_ms > self.supervision.max_backoff_ms {
+            anyhow::bail!("SUPERVISION_INITIAL_BACKOFF_MS must be <= SUPERVISION_MAX_BACKOFF_MS");
+        }
+        if self.shutdown.timeout_secs == 0 {
+            anyhow::bail!("SHUTDOWN_TIMEOUT_SECS must be >= 1");
+        }
+        if self.outbox.retention().is_some() && (self.outbox.cleanup_interval_secs == 0 || self.outbox.cleanup_batch == 0) {
+            anyhow::bail!("OUTBOX_CLEANUP_INTERVAL_SECS and outbox.cleanup_batch must be >= 1");
+        }
+        if self.read_models.purge_interval_secs == 0 {
+            anyhow::bail!("READ_MODEL_PURGE_INTERVAL_SECS must be >= 1");
+        }
+        self.load.profile().context("Invalid load profile")?;
+        if self.retry.max_attempts == 0 {
+            anyhow::bail!("RETRY_MAX_ATTEMPTS must be >= 1");
+        }
+        if !self.retry.budget_per_sec.is_finite() || self.retry.budget_per_sec < 0.0 || self.retry.budget_burst == 0 {
+            anyhow::bail!("RETRY_BUDGET_PER_SEC must be >= 0 and RETRY_BUDGET_BURST >= 1");
+        }
+        let breaker = &self.circuit_breaker;
+        if !(0.0..=1.0).contains(&breaker.failure_rate) {
+            anyhow::bail!("CIRCUIT_BREAKER_FAILURE_RATE must be within 0.0..=1.0 (0 = off)");
+        }
+        if breaker.window_size == 0 || breaker.minimum_calls > breaker.window_size || breaker.half_open_max_calls == 0 {
+            anyhow::bail!(
+                "CIRCUIT_BREAKER_WINDOW_SIZE and CIRCUIT_BREAKER_HALF_OPEN_MAX_CALLS must be >= 1, with circuit_breaker.minimum_calls <= the window size"
+            );
+        }
+        if self.dlq.max_replays < 1 || self.dlq.age_check_interval_secs == 0 {
+            anyhow::bail!("DLQ_MAX_REPLAYS and dlq.age_check_interval_secs must be >= 1");
+        }
+        if self.fencing.generation.is_some_and(|generation| generation < 1) {
+            anyhow::bail!("FENCE_GENERATION must be >= 1");
+        }
+        if self.fencing.name.is_empty() || self.fencing.refresh_interval_ms == 0 {
+            anyhow::bail!("FENCE_NAME must not be empty and FENCE_REFRESH_INTERVAL_MS must be >= 1");
+        }
+        if !(0.0..=1.0).contains(&self.telemetry.sample_ratio) {
+            anyhow::bail!("OTEL_TRACES_SAMPLER_ARG must be within 0.0..=1.0");
+        }
+        if self.telemetry.otlp_endpoint.is_some() && !cfg!(feature = "otel") {
+            anyhow::bail!("OTEL_EXPORTER_OTLP_ENDPOINT is set, but span export needs a build with `--features otel`");
+        }
+        self.encryption.crypto().context("Invalid EVENT_ENCRYPTION_KEYS")?;
+        if let Some(pricing) = &self.pricing {
+            if pricing.currency.len() != 3 || !pricing.currency.chars().all(|c| c.is_ascii_uppercase()) {
+                anyhow::bail!("Invalid pricing currency '{}' (expected an ISO 4217 code)", pricing.currency);
+            }
+            if pricing.tax_rate_bps < 0 || !(0..=10_000).contains(&pricing.discount_bps) {
+                anyhow::bail!("Pricing tax and discount rates must be within 0..=10000 basis points");
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_cdc_tables(&self) -> Result<()> {
+        // Reader checkpoints are keyed by table name
+        let mut tables = vec![self.cdc.outbox_table.as_str()];
+        for extra in &self.cdc.tables {
+            let keyspace = extra.keyspace.as_deref().unwrap_or(&self.scylla.keyspace);
+            let identifiers = [keyspace, &extra.table].into_iter()
+                .chain(extra.key_column.as_deref())
+                .chain(extra.columns.iter().map(String::as_str));
+            for identifier in identifiers {
+                if identifier.is_empty() || !identifier.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                    anyhow::bail!("Invalid identifier '{}' in CDC table {}", identifier, extra.table);
+                }
+            }
+            if tables.contains(&extra.table.as_str()) {
+                anyhow::bail!("CDC table {} is configured twice", extra.table);
+            }
+            tables.push(&extra.table);
+
+            match extra.topic {
+                Some(ref topic) if topic.trim().is_empty() => anyhow::bail!("Empty topic for CDC table {}", extra.table),
+                Some(_) if extra.key_column.is_none() || extra.columns.is_empty() => {
+                    anyhow::bail!("CDC table {} needs key_column and columns to publish rows", extra.table)
+                }
+                None if extra.key_column.is_some() || !extra.columns.is_empty() => {
+                    anyhow::bail!("CDC table {} has key_column/columns but no topic", extra.table)
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_tenants(&self) -> Result<()> {
+        let mut ids = Vec::new();
+        let mut outboxes = Vec::new();
+        for tenant in self.tenant_contexts()? {
+            let id = tenant.tenant_id().unwrap_or_default().to_string();
+            if ids.contains(&id) {
+                anyhow::bail!("Tenant {} is configured twice", id);
+            }
+            let outbox = self.tenant_outbox(&tenant);
+            let streamed = self.cdc.tables.iter().any(|extra| {
+                extra.table == outbox.table && extra.keyspace.as_deref().unwrap_or(&self.scylla.keyspace) == outbox.keyspace
+            });
+            if outbox == self.cdc_source() || outboxes.contains(&outbox) || streamed {
+                anyhow::bail!("Tenant {} shares its tables ({}) with another tenant", id, outbox.label());
+            }
+            ids.push(id);
+            outboxes.push(outbox);
+        }
+        Ok(())
+    }
+
+    pub fn is_production(&self) -> bool {
+        matches!(self.environment.to_ascii_lowercase().as_str(), "production" | "prod")
+    }
+
+    /// CDC log the relay and projections read
+    pub fn cdc_source(&self) -> CdcSource {
+        CdcSource {
+            keyspace: self.scylla.keyspace.clone(),
+            table: self.cdc.outbox_table.clone(),
+        }
+    }
+
+    /// Tenants of [[tenants]], in configuration order
+    pub fn tenant_contexts(&self) -> Result<Vec<TenantContext>> {
+        self.tenants.iter().map(TenantSettings::context).collect()
+    }
+
+    /// Outbox table of `tenant`
+    fn tenant_outbox(&self, tenant: &TenantContext) -> CdcSource {
+        CdcSource {
+            keyspace: tenant.keyspace_name().unwrap_or(&self.scylla.keyspace).to_string(),
+            table: tenant.table_name(&self.cdc.outbox_table),
+        }
+    }
+
+    /// Every table the coordinator streams: the outbox first, the tenant
+    /// outboxes, then [[cdc.tables]]
+    pub fn cdc_tables(&self) -> Vec<CdcTable> {
+        // Validated on load
+        let tenants = self.tenant_contexts().unwrap_or_default().into_iter().map(|tenant| CdcTable {
+            source: self.tenant_outbox(&tenant),
+            mapping: CdcTopicMapping::Outbox,
+            tenant: Some(tenant),
+        });
+        let extra = self.cdc.tables.iter().map(|table| {
+            let source = CdcSource {
+                keyspace: table.keyspace.clone().unwrap_or_else(|| self.scylla.keyspace.clone()),
+                table: table.table.clone(),
+            };
+            let mapping = match (&table.topic, &table.key_column) {
+                (Some(topic), Some(key_column)) => CdcTopicMapping::Rows {
+                    topic: topic.clone(),
+                    key_column: key_column.clone(),
+                    columns: table.columns.clone(),
+                },
+                _ => CdcTopicMapping::Outbox,
+            };
+            CdcTable { source, mapping, tenant: None }
+        });
+        std::iter::once(CdcTable::outbox(self.cdc_source())).chain(tenants).chain(extra).collect()
+    }
+}
+
+fn parse<T: std::str::FromStr>(name: &str, value: &str) -> Result<T>
+where
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    value.trim().parse().with_context(|| format!("Invalid {}: '{}'", name, value))
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{ConsistencyLevel, SerialConsistencyLevel};
+
+    fn load(env: &[(&str, &str)], file: &str) -> Result<AppConfig> {
+        let env: HashMap<String, String> = env.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        let file = file.to_string();
+        AppConfig::from_lookup(|name| env.get(name).cloned(), |_| Ok(file.clone()))
+    }
+
+    #[test]
+    fn test_defaults_match_local_setup() {
+        let config = load(&[], "").unwrap();
+
+        assert_eq!(config.scylla.nodes, vec!["127.0.0.1:9042"]);
+        assert_eq!(config.scylla.replication_factor, 1);
+        assert_eq!(config.redpanda.brokers, "127.0.0.1:9092");
+        assert!(config.redpanda.batch_config().is_enabled());
+        assert!(config.redpanda.transactional_id.is_none());
+        assert!(!config.redpanda.rate_limit().is_enabled());
+        assert_eq!(config.metrics.port, 9090);
+        assert!(!config.is_production());
+        assert_eq!(config.cdc_source(), CdcSource::default());
+        assert_eq!(config.retry.retry_config().max_attempts, RetryConfig::aggressive().max_attempts);
+        assert!(config.retry.retry_budget().is_none());
+        assert!(config.pricing.is_none());
+        assert!(config.cdc.approval_required.is_empty());
+        assert!(!config.event_store.shard_layout().unwrap().is_sharded());
+        assert!(!config.state_snapshots.enabled);
+        assert_eq!(config.shutdown.timeout(), Duration::from_secs(30));
+        assert_eq!(config.cdc.dedup_ttl(), Some(Duration::from_secs(604_800)));
+        assert_eq!(config.cdc.payload_format, PayloadFormat::Event);
+        assert_eq!(config.cdc.key_strategy, KeyStrategy::AggregateId);
+        assert_eq!((config.cdc.publish_workers, config.cdc.publish_queue_depth), (1, 100));
+        assert_eq!(config.cdc.mode, CdcMode::Streaming);
+        assert_eq!(config.outbox.retention(), Some(Duration::from_secs(3600)));
+        assert_eq!(config.read_models.tombstone_retention(), None);
+        assert_eq!(config.dlq.max_replays, 3);
+        assert!(config.telemetry.otlp_endpoint.is_none());
+        assert!(config.encryption.crypto().unwrap().is_none());
+        assert!(config.fencing.generation.is_none());
+    }
+
+    #[test]
+    fn test_env_overrides_file() {
+        let file = r#"
+            [scylla]
+            nodes = ["scylla-1:9042", "scylla-2:9042"]
+            keyspace = "orders_prod"
+
+            [redpanda.topic_rate_limits]
+            OrderCreated = 200
+
+            [retry]
+            max_attempts = 8
+
+            [pricing]
+            currency = "EUR"
+            tax_rate_bps = 1900
+            [pricing.unit_prices]
+            "6f1c2a5e-0000-4000-8000-000000000001" = 1999
+        "#;
+        let config = load(
+            &[
+                ("APP_CONFIG_FILE", "app.toml"),
+                ("APP_ENV", "Production"),
+                ("SCYLLA_KEYSPACE", "orders_eu"),
+                ("SCYLLA_REPLICATION_FACTOR", "3"),
+                ("METRICS_PORT", "9191"),
+                ("ADMIN_TOKEN", "s3cret"),
+                ("CDC_APPROVAL_REQUIRED", "RefundIssued, OrderCancelled"),
+                ("EVENT_STORE_SHARDS", "8"),
+                ("EVENT_STORE_LOAD_PAGE_SIZE", "250"),
+                ("COMMAND_CONFLICT_RETRIES", "5"),
+                ("STATE_SNAPSHOTS_ENABLED", "true"),
+                ("SHUTDOWN_TIMEOUT_SECS", "10"),
+                ("SUPERVISION_MAX_RESTARTS", "0"),
+                ("CDC_DEDUP_TTL_SECS", "0"),
+                ("CDC_PAYLOAD_FORMAT", "envelope"),
+                ("CDC_KEY_STRATEGY", "correlation_id"),
+                ("CDC_PUBLISH_WORKERS", "4"),
+                ("CDC_STREAM_AUDIT", "false"),
+                ("CDC_MAX_LAG_SECS", "120"),
+                ("CDC_MODE", "polling"),
+                ("CDC_POLL_INTERVAL_MS", "250"),
+                ("RETRY_JITTER", "decorrelated"),
+                ("RETRY_BUDGET_PER_SEC", "25"),
+                ("CIRCUIT_BREAKER_FAILURE_RATE", "0.5"),
+                ("REDPANDA_BATCH_MAX_RECORDS", "100"),
+                ("REDPANDA_TRANSACTIONAL_ID", "scylladb-cdc-eu-1"),
+                ("REDPANDA_RATE_LIMIT_PER_SEC", "500"),
+                ("REDPANDA_DUAL_WRITE_POLICY", "deny"),
+                ("OUTBOX_RETENTION_SECS", "0"),
+                ("OUTBOX_SCHEDULE_INTERVAL_SECS", "0"),
+                ("READ_MODEL_TOMBSTONE_RETENTION_SECS", "604800"),
+                ("SNAPSHOT_KEEP_LAST", "5"),
+                ("EVENT_ENCRYPTION_KEYS", "k2:AgICAgICAgICAgICAgICAgICAgICAgICAgICAgICAgI=, k1:AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE="),
+            ],
+            file,
+        )
+        .unwrap();
+
+        assert_eq!(config.scylla.nodes.len(), 2);
+        assert_eq!(config.scylla.keyspace, "orders_eu");
+        assert_eq!(config.scylla.replication_factor, 3);
+        assert_eq!(config.retry.max_attempts, 8);
+        // Unset fields of a present section keep their defaults
+        assert_eq!(config.retry.multiplier, RetrySettings::default().multiplier);
+        assert_eq!(config.retry.retry_config().jitter, Jitter::Decorrelated);
+        assert!(config.retry.retry_budget().is_some());
+        assert_eq!(config.circuit_breaker.circuit_breaker_config().failure_rate_threshold, Some(0.5));
+        assert_eq!(config.metrics.port, 9191);
+        assert_eq!(config.api.admin_token.as_deref(), Some("s3cret"));
+        assert!(!format!("{:?}", config.api).contains("s3cret"));
+        assert_eq!(config.cdc.approval_required, vec!["RefundIssued", "OrderCancelled"]);
+        assert_eq!(config.event_store.shard_layout().unwrap().shards(), 8);
+        assert_eq!(config.event_store.conflict_retries, 5);
+        assert_eq!(config.event_store.load_page_size, 250);
+        assert!(config.state_snapshots.enabled);
+        assert_eq!(config.state_snapshots.every, 100);
+        assert_eq!(config.shutdown.timeout_secs, 10);
+        assert_eq!(config.supervision.restart_policy().max_restarts, 0);
+        assert_eq!(config.cdc.dedup_ttl(), None);
+        assert_eq!(config.cdc.payload_format, PayloadFormat::Envelope);
+        assert_eq!(config.cdc.key_strategy, KeyStrategy::CorrelationId);
+        assert_eq!(config.cdc.publish_workers, 4);
+        assert!(!config.cdc.stream_audit);
+        assert_eq!(config.cdc.max_lag(), Duration::from_secs(120));
+        assert_eq!((config.cdc.mode, config.cdc.poll_interval()), (CdcMode::Polling, Duration::from_millis(250)));
+        assert_eq!(config.redpanda.batch_config().max_records, 100);
+        assert_eq!(config.redpanda.transactional_id.as_deref(), Some("scylladb-cdc-eu-1"));
+        let rate_limit = config.redpanda.rate_limit();
+        assert_eq!((rate_limit.per_sec, rate_limit.burst), (500.0, 500));
+        assert_eq!(rate_limit.topics.get("OrderCreated"), Some(&200.0));
+        assert_eq!(config.redpanda.dual_write_policy, DualWritePolicy::Deny);
+        assert_eq!(config.outbox.retention(), None);
+        assert_eq!(config.read_models.tombstone_retention(), Some(Duration::from_secs(604_800)));
+        let snapshot_retention = config.event_store.snapshot_retention().unwrap();
+        assert_eq!((snapshot_retention.keep_last, snapshot_retention.interval), (5, Duration::from_secs(3600)));
+        assert_eq!(config.outbox.schedule_interval(), None);
+        assert_eq!(config.encryption.key_ids(), vec!["k2", "k1"]);
+        assert!(!format!("{:?}", config.encryption).contains("AgIC"));
+        assert!(config.is_production());
+        let pricing = config.pricing.as_ref().unwrap();
+        assert_eq!((pricing.currency.as_str(), pricing.shipping), ("EUR", 0));
+        assert_eq!(pricing.unit_prices.len(), 1);
+
+        assert!(load(&[("SCYLLA_KEYSPACE", "orders; DROP")], "").is_err());
+        assert!(load(&[("EVENT_STORE_SHARDS", "0")], "").is_err());
+        assert!(load(&[("EVENT_STORE_LOAD_PAGE_SIZE", "0")], "").is_err());
+        assert!(load(&[("SCYLLA_REPLICATION_FACTOR", "0")], "").is_err());
+        assert!(load(&[("OUTBOX_CLEANUP_INTERVAL_SECS", "0")], "").is_err());
+        assert!(load(&[("CDC_PUBLISH_WORKERS", "0")], "").is_err());
+        assert!(load(&[("CDC_MODE", "batch")], "").is_err());
+        assert!(load(&[("CDC_MODE", "polling"), ("CDC_POLL_INTERVAL_MS", "0")], "").is_err());
+        assert!(load(&[("DLQ_MAX_REPLAYS", "0")], "").is_err());
+        assert!(load(&[("REDPANDA_TRANSACTIONAL_ID", "cdc-1"), ("REDPANDA_BATCH_MAX_RECORDS", "1")], "").is_err());
+        assert!(load(&[("EVENT_ENCRYPTION_KEYS", "k1:c2hvcnQ=")], "").is_err());
+        assert!(load(&[("OTEL_TRACES_SAMPLER_ARG", "1.5")], "").is_err());
+        assert!(load(&[("CIRCUIT_BREAKER_FAILURE_RATE", "1.5")], "").is_err());
+        assert!(load(&[("SUPERVISION_INITIAL_BACKOFF_MS", "120000")], "").is_err());
+        assert!(load(&[("CIRCUIT_BREAKER_HALF_OPEN_MAX_CALLS", "0")], "").is_err());
+        assert!(load(&[("FENCE_GENERATION", "0")], "").is_err());
+        assert_eq!(load(&[("FENCE_GENERATION", "12")], "").unwrap().fencing.generation, Some(12));
+        assert_eq!(
+            load(&[("OTEL_EXPORTER_OTLP_ENDPOINT", "http://tempo:4317")], "").is_ok(),
+            cfg!(feature = "otel")
+        );
+        assert!(load(&[("APP_CONFIG_FILE", "app.toml")], "[scylla]\nport = 1").is_err());
+    }
+
+    #[test]
+    fn test_runtime_settings_from_env() {
+        let config = load(
+            &[
+                ("SERVICE_REGION", "eu-west"),
+                ("ROUTING_RULES_FILE", "routing.toml"),
+                ("INTEGRITY_CHECK_INTERVAL_SECS", "600"),
+                ("LOAD_RATE", "200"),
+                ("LOAD_BURST", "30:5:4"),
+                ("LOAD_SEED", "7"),
+            ],
+            "",
+        )
+        .unwrap();
+
+        assert_eq!(config.region.as_deref(), Some("eu-west"));
+        assert_eq!(config.cdc.routing_rules_file.as_deref(), Some("routing.toml"));
+        assert_eq!(config.integrity.check_interval(), Some(Duration::from_secs(600)));
+        let profile = config.load.profile().unwrap();
+        assert_eq!((profile.rate_per_sec, profile.seed), (200.0, 7));
+        assert_eq!(profile.burst.map(|burst| burst.multiplier), Some(4.0));
+        assert_eq!(profile.concurrency, LoadProfile::default().concurrency);
+
+        let defaults = load(&[], "").unwrap();
+        assert!(defaults.region.is_none() && defaults.cdc.routing_rules_file.is_none());
+        assert_eq!(defaults.integrity.check_interval(), None);
+
+        assert!(load(&[("INTEGRITY_CHECK_INTERVAL_SECS", "hourly")], "").is_err());
+        assert!(load(&[("LOAD_RATE", "NaN")], "").is_err());
+        assert!(load(&[("LOAD_BURST", "30:5:0")], "").is_err());
+        assert!(load(&[("LOAD_BURST", "30:5")], "").is_err());
+        assert!(load(&[("LOAD_HOT_FRACTION", "1.5")], "").is_err());
+    }
+
+    #[test]
+    fn test_dual_write_guard_protects_relay_topics() {
+        use crate::messaging::PublishOrigin;
+
+        // The relay publishes domain events to their event type
+        let config = load(&[("REDPANDA_DUAL_WRITE_POLICY", "deny")], "").unwrap();
+        let guard = config.redpanda.dual_write_guard();
+        assert!(guard.check("OrderCreated", PublishOrigin::Direct).is_err());
+        assert!(guard.check("CustomerRegistered", PublishOrigin::Direct).is_err());
+        assert!(guard.check("notifications", PublishOrigin::Direct).is_ok());
+        assert!(guard.check("OrderCreated", PublishOrigin::OutboxCdc).is_ok());
+
+        let file = "[redpanda]\ndual_write_policy = \"deny\"\nprotected_topics = [\"order-audit\"]";
+        let guard = load(&[("APP_CONFIG_FILE", "app.toml")], file).unwrap().redpanda.dual_write_guard();
+        assert!(guard.check("order-audit", PublishOrigin::Direct).is_err());
+        assert!(guard.check("OrderCreated", PublishOrigin::Direct).is_ok());
+        assert!(load(&[("REDPANDA_DUAL_WRITE_POLICY", "block")], "").is_err());
+    }
+
+    #[test]
+    fn test_cdc_tables() {
+        let file = r#"
+            [[cdc.tables]]
+            table = "order_summaries"
+            topic = "order-summaries"
+            key_column = "order_id"
+            columns = ["order_id", "status"]
+
+            [[cdc.tables]]
+            keyspace = "billing_ks"
+            table = "billing_outbox"
+        "#;
+        let config = load(&[("APP_CONFIG_FILE", "app.toml")], file).unwrap();
+
+        let tables = config.cdc_tables();
+        assert_eq!(tables.len(), 3);
+        assert_eq!(tables[0], CdcTable::outbox(CdcSource::default()));
+        assert_eq!(tables[1].source.label(), "orders_ks.order_summaries");
+        assert!(matches!(tables[1].mapping, CdcTopicMapping::Rows { ref key_column, .. } if key_column == "order_id"));
+        assert_eq!(tables[2], CdcTable::outbox(CdcSource { keyspace: "billing_ks".to_string(), table: "billing_outbox".to_string() }));
+
+        // Rows need a key, a topic needs rows, and tables are unique
+        assert!(load(&[("APP_CONFIG_FILE", "app.toml")], "[[cdc.tables]]\ntable = \"t\"\ntopic = \"t\"").is_err());
+        assert!(load(&[("APP_CONFIG_FILE", "app.toml")], "[[cdc.tables]]\ntable = \"t\"\nkey_column = \"id\"").is_err());
+        assert!(load(&[("APP_CONFIG_FILE", "app.toml")], "[[cdc.tables]]\ntable = \"outbox_messages\"").is_err());
+        // Row changes cannot be polled
+        assert!(load(&[("APP_CONFIG_FILE", "app.toml"), ("CDC_MODE", "polling")], file).is_err());
+    }
+
+    #[test]
+    fn test_consistency_per_operation() {
+        let file = r#"
+            [consistency]
+            append = "quorum"
+            read = "local_one"
+            serial = "serial"
+        "#;
+        let config = load(&[("APP_CONFIG_FILE", "app.toml"), ("SCYLLA_CONSISTENCY_READ", "LOCAL_QUORUM")], file).unwrap();
+
+        assert_eq!(config.consistency.append, ConsistencyLevel::Quorum);
+        assert_eq!(config.consistency.read, ConsistencyLevel::LocalQuorum);
+        assert_eq!(config.consistency.serial, SerialConsistencyLevel::Serial);
+        assert_eq!(config.consistency.offsets, ConsistencyLevel::LocalQuorum);
+
+        assert!(load(&[("SCYLLA_CONSISTENCY_READ", "any")], "").is_err());
+        assert!(load(&[("SCYLLA_SERIAL_CONSISTENCY", "quorum")], "").is_err());
+    }
+
+    #[test]
+    fn test_tenant_outboxes_follow_the_main_outbox() {
+        let file = r#"
+            [[tenants]]
+            id = "acme"
+            keyspace = "acme_ks"
+
+            [[tenants]]
+            id = "globex"
+            table_prefix = "globex"
+
+            [[cdc.tables]]
+            keyspace = "billing_ks"
+            table = "billing_outbox"
+        "#;
+        let config = load(&[("APP_CONFIG_FILE", "app.toml")], file).unwrap();
+
+        let tenants = config.tenant_contexts().unwrap();
+        assert_eq!(tenants.len(), 2);
+        assert_eq!(tenants[0].table("event_store"), "acme_ks.event_store");
+
+        let tables = config.cdc_tables();
+        let labels: Vec<String> = tables.iter().map(|table| table.source.label()).collect();
+        assert_eq!(labels, ["orders_ks.outbox_messages", "acme_ks.outbox_messages", "orders_ks.globex_outbox_messages", "billing_ks.billing_outbox"]);
+        assert_eq!(tables[1].tenant.as_ref().and_then(|tenant| tenant.tenant_id()), Some("acme"));
+        assert_eq!(tables[3].tenant, None);
+
+        // One scope per tenant, unique ids, no shared tables
+        let tenant = |body: &str| load(&[("APP_CONFIG_FILE", "app.toml")], &format!("[[tenants]]\n{}", body));
+        assert!(tenant("id = \"a\"").is_err());
+        assert!(tenant("id = \"a\"\nkeyspace = \"a_ks\"\ntable_prefix = \"a\"").is_err());
+        assert!(tenant("id = \"a\"\nkeyspace = \"orders_ks\"").is_err());
+        assert!(tenant("id = \"a\"\nkeyspace = \"a-ks\"").is_err());
+        assert!(tenant("id = \"a\"\nkeyspace = \"a_ks\"\n[[tenants]]\nid = \"a\"\ntable_prefix = \"a\"").is_err());
+    }
+}
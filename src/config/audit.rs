@@ -0,0 +1,227 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::event_sourcing::{ConcurrencyConflict, DomainEvent, EventEnvelope, EventStore};
+
+// ============================================================================
+// Configuration Audit Trail - Event-Sourced Config History
+// ============================================================================
+//
+// Every configuration change is appended as a ConfigChanged event to one
+// dedicated stream (aggregate CONFIG_STREAM_ID, type "Config") in our own
+// event store:
+//
+//   key        what changed, e.g. "cdc_throttle", "routing_rules"
+//   before     value before the change (None when first set)
+//   after      value after the change (None when removed)
+//   changed_by operator or "system"
+//   source     where it came from, e.g. "startup", "admin_api", "hot_reload"
+//
+// `record` derives `before` from the stream itself and skips no-op changes,
+// so sources can report their effective value unconditionally (e.g. at every
+// startup). Concurrent writers are serialized by the store's optimistic
+// concurrency check; `record` retries on conflict.
+//
+// Config events are not published to the outbox. Config changes are rare,
+// so history is read by replaying the whole stream.
+//
+// ============================================================================
+
+/// Stream all configuration changes are appended to
+pub const CONFIG_STREAM_ID: Uuid = Uuid::from_u128(0x636f_6e66_6967_4000_8000_0000_0000_0001);
+
+/// Attempts of `record` under concurrent writers
+const MAX_RECORD_ATTEMPTS: u32 = 3;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, DomainEvent)]
+pub struct ConfigChanged {
+    pub key: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub changed_by: String,
+    pub source: String,
+    pub reason: Option<String>,
+}
+
+/// A change to record; `before` is taken from the stream
+#[derive(Debug, Clone)]
+pub struct ConfigChange {
+    pub key: String,
+    pub after: Option<Value>,
+    pub changed_by: String,
+    pub source: String,
+    pub reason: Option<String>,
+}
+
+impl ConfigChange {
+    pub fn new(key: &str, after: Option<Value>, changed_by: &str, source: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            after,
+            changed_by: changed_by.to_string(),
+            source: source.to_string(),
+            reason: None,
+        }
+    }
+}
+
+/// One entry of the configuration history
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConfigHistoryEntry {
+    pub version: i64,
+    pub changed_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub change: ConfigChanged,
+}
+
+/// Current value per key after applying `events` in order
+pub fn current_values(events: &[EventEnvelope<ConfigChanged>]) -> BTreeMap<String, Value> {
+    let mut values = BTreeMap::new();
+    for event in events {
+        match event.event_data.after {
+            Some(ref after) => values.insert(event.event_data.key.clone(), after.clone()),
+            None => values.remove(&event.event_data.key),
+        };
+    }
+    values
+}
+
+/// Newest `limit` changes, optionally of one key
+pub fn history(events: &[EventEnvelope<ConfigChanged>], key: Option<&str>, limit: usize) -> Vec<ConfigHistoryEntry> {
+    events
+        .iter()
+        .rev()
+        .filter(|e| key.is_none_or(|key| e.event_data.key == key))
+        .take(limit)
+        .map(|e| ConfigHistoryEntry {
+            version: e.sequence_number,
+            changed_at: e.timestamp,
+            change: e.event_data.clone(),
+        })
+        .collect()
+}
+
+pub struct ConfigAuditLog {
+    store: EventStore<ConfigChanged>,
+}
+
+impl ConfigAuditLog {
+    pub fn new(store: EventStore<ConfigChanged>) -> Self {
+        Self { store }
+    }
+
+    /// Append `change` unless the key already has that value
+    ///
+    /// Returns the stream version of the appended event, None for no-ops.
+    pub async fn record(&self, change: ConfigChange) -> Result<Option<i64>> {
+        let mut attempt = 1;
+        loop {
+            let events = self.store.load_events(CONFIG_STREAM_ID).await?;
+            let before = current_values(&events).remove(&change.key);
+            if before == change.after {
+                return Ok(None);
+            }
+
+            let expected_version = events.last().map(|e| e.sequence_number).unwrap_or(0);
+            let event = ConfigChanged {
+                key: change.key.clone(),
+                before,
+                after: change.after.clone(),
+                changed_by: change.changed_by.clone(),
+                source: change.source.clone(),
+                reason: change.reason.clone(),
+            };
+            let envelope = EventEnvelope::new(
+                CONFIG_STREAM_ID,
+                expected_version + 1,
+                ConfigChanged::event_type().to_string(),
+                event,
+                Uuid::new_v4(),
+            );
+
+            match self.store.append_events(CONFIG_STREAM_ID, expected_version, vec![envelope], false).await {
+                Ok(version) => {
+                    tracing::info!(
+                        key = %change.key,
+                        changed_by = %change.changed_by,
+                        source = %change.source,
+                        version = version,
+                        "📝 Configuration change recorded"
+                    );
+                    return Ok(Some(version));
+                }
+                Err(e) if attempt < MAX_RECORD_ATTEMPTS && e.downcast_ref::<ConcurrencyConflict>().is_some() => {
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Newest `limit` changes, optionally of one key
+    pub async fn history(&self, key: Option<&str>, limit: usize) -> Result<Vec<ConfigHistoryEntry>> {
+        let events = self.store.load_events(CONFIG_STREAM_ID).await?;
+        Ok(history(&events, key, limit))
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn changed(sequence_number: i64, key: &str, before: Option<Value>, after: Option<Value>) -> EventEnvelope<ConfigChanged> {
+        EventEnvelope::new(
+            CONFIG_STREAM_ID,
+            sequence_number,
+            "ConfigChanged".to_string(),
+            ConfigChanged {
+                key: key.to_string(),
+                before,
+                after,
+                changed_by: "ops@example.com".to_string(),
+                source: "admin_api".to_string(),
+                reason: None,
+            },
+            Uuid::new_v4(),
+        )
+    }
+
+    fn stream() -> Vec<EventEnvelope<ConfigChanged>> {
+        vec![
+            changed(1, "cdc_throttle", None, Some(json!({"p95_ms": 250}))),
+            changed(2, "routing_rules", None, Some(json!("rules.json"))),
+            changed(3, "cdc_throttle", Some(json!({"p95_ms": 250})), Some(json!({"p95_ms": 400}))),
+            changed(4, "routing_rules", Some(json!("rules.json")), None),
+        ]
+    }
+
+    #[test]
+    fn test_current_values_fold_the_stream() {
+        let values = current_values(&stream());
+
+        assert_eq!(values.len(), 1);
+        assert_eq!(values["cdc_throttle"], json!({"p95_ms": 400}));
+    }
+
+    #[test]
+    fn test_history_is_newest_first_and_filterable() {
+        let events = stream();
+
+        let versions: Vec<i64> = history(&events, None, 3).iter().map(|e| e.version).collect();
+        assert_eq!(versions, vec![4, 3, 2]);
+
+        let throttle = history(&events, Some("cdc_throttle"), 10);
+        assert_eq!(throttle.len(), 2);
+        assert_eq!(throttle[0].change.before, Some(json!({"p95_ms": 250})));
+        assert_eq!(throttle[0].change.after, Some(json!({"p95_ms": 400})));
+    }
+}
@@ -0,0 +1,15 @@
+// ============================================================================
+// Config Module - Application Configuration
+// ============================================================================
+//
+// Structure:
+// - app   - AppConfig: connections and tuning from file/env
+// - audit - Event-sourced history of configuration changes
+//
+// ============================================================================
+
+mod app;
+mod audit;
+
+pub use app::{AppConfig, CdcMode, CdcSource, CdcTable, CdcTopicMapping, TelemetryConfig};
+pub use audit::{ConfigAuditLog, ConfigChange, ConfigChanged, ConfigHistoryEntry, CONFIG_STREAM_ID};
@@ -0,0 +1,204 @@
+use serde::Deserialize;
+
+// ============================================================================
+// Consistency Levels - Durability vs Latency per Operation
+// ============================================================================
+//
+// Which consistency each kind of statement runs at ([consistency] in the
+// app config). The StatementCache applies them to the statements and
+// batches it hands out:
+//
+//   append   event + outbox batch, snapshot writes
+//   read     event, version and snapshot loads
+//   lwt      commit phase of the aggregate_sequence LWTs
+//   serial   Paxos phase of those LWTs
+//   dlq      dead letter writes, replays and quarantine moves
+//   offsets  CDC checkpoint reads and writes
+//
+// All default to LOCAL_QUORUM and serial to LOCAL_SERIAL - the driver's
+// defaults, so nothing changes unless configured.
+//
+// Lowering `read` below the `append` level (LOCAL_ONE reads of QUORUM
+// writes) trades read-your-writes for latency: a command may then see a
+// stale version and retry on the concurrency conflict.
+//
+// ============================================================================
+
+/// Consistency level of a regular statement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsistencyLevel {
+    Any,
+    One,
+    Two,
+    Three,
+    Quorum,
+    All,
+    LocalQuorum,
+    EachQuorum,
+    LocalOne,
+}
+
+impl ConsistencyLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ConsistencyLevel::Any => "any",
+            ConsistencyLevel::One => "one",
+            ConsistencyLevel::Two => "two",
+            ConsistencyLevel::Three => "three",
+            ConsistencyLevel::Quorum => "quorum",
+            ConsistencyLevel::All => "all",
+            ConsistencyLevel::LocalQuorum => "local_quorum",
+            ConsistencyLevel::EachQuorum => "each_quorum",
+            ConsistencyLevel::LocalOne => "local_one",
+        }
+    }
+
+    /// Whether reads can run at this level (ANY and EACH_QUORUM are write-only)
+    pub fn is_readable(self) -> bool {
+        !matches!(self, ConsistencyLevel::Any | ConsistencyLevel::EachQuorum)
+    }
+}
+
+impl std::str::FromStr for ConsistencyLevel {
+    type Err = anyhow::Error;
+
+    /// `QUORUM`, `local_one`, `LOCAL-QUORUM`, ...
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let level = match s.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "any" => ConsistencyLevel::Any,
+            "one" => ConsistencyLevel::One,
+            "two" => ConsistencyLevel::Two,
+            "three" => ConsistencyLevel::Three,
+            "quorum" => ConsistencyLevel::Quorum,
+            "all" => ConsistencyLevel::All,
+            "local_quorum" => ConsistencyLevel::LocalQuorum,
+            "each_quorum" => ConsistencyLevel::EachQuorum,
+            "local_one" => ConsistencyLevel::LocalOne,
+            other => anyhow::bail!("Unknown consistency level '{}'", other),
+        };
+        Ok(level)
+    }
+}
+
+/// Consistency of the Paxos phase of lightweight transactions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SerialConsistencyLevel {
+    Serial,
+    LocalSerial,
+}
+
+impl SerialConsistencyLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SerialConsistencyLevel::Serial => "serial",
+            SerialConsistencyLevel::LocalSerial => "local_serial",
+        }
+    }
+}
+
+impl std::str::FromStr for SerialConsistencyLevel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "serial" => Ok(SerialConsistencyLevel::Serial),
+            "local_serial" => Ok(SerialConsistencyLevel::LocalSerial),
+            other => anyhow::bail!("Unknown serial consistency '{}' (serial or local_serial)", other),
+        }
+    }
+}
+
+/// Kind of statement, which picks its consistency level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Append,
+    Read,
+    Lwt,
+    Dlq,
+    Offsets,
+}
+
+/// Consistency per operation ([consistency])
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConsistencyConfig {
+    pub append: ConsistencyLevel,
+    pub read: ConsistencyLevel,
+    pub lwt: ConsistencyLevel,
+    pub serial: SerialConsistencyLevel,
+    pub dlq: ConsistencyLevel,
+    pub offsets: ConsistencyLevel,
+}
+
+impl Default for ConsistencyConfig {
+    fn default() -> Self {
+        Self {
+            append: ConsistencyLevel::LocalQuorum,
+            read: ConsistencyLevel::LocalQuorum,
+            lwt: ConsistencyLevel::LocalQuorum,
+            serial: SerialConsistencyLevel::LocalSerial,
+            dlq: ConsistencyLevel::LocalQuorum,
+            offsets: ConsistencyLevel::LocalQuorum,
+        }
+    }
+}
+
+impl ConsistencyConfig {
+    pub fn level(&self, operation: Operation) -> ConsistencyLevel {
+        match operation {
+            Operation::Append => self.append,
+            Operation::Read => self.read,
+            Operation::Lwt => self.lwt,
+            Operation::Dlq => self.dlq,
+            Operation::Offsets => self.offsets,
+        }
+    }
+
+    /// Reject levels an operation cannot run at
+    pub fn validate(&self) -> anyhow::Result<()> {
+        // DLQ and offsets are read back, LWTs read the current sequence
+        for (name, level) in [("read", self.read), ("lwt", self.lwt), ("dlq", self.dlq), ("offsets", self.offsets)] {
+            if !level.is_readable() {
+                anyhow::bail!("consistency.{} cannot be {} (reads are not possible at that level)", name, level.as_str());
+            }
+        }
+        if self.append == ConsistencyLevel::Any {
+            // The event batch is logged; ANY would acknowledge it from a hint alone
+            anyhow::bail!("consistency.append cannot be any");
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels_parse_in_any_spelling() {
+        assert_eq!("QUORUM".parse::<ConsistencyLevel>().unwrap(), ConsistencyLevel::Quorum);
+        assert_eq!(" local-one ".parse::<ConsistencyLevel>().unwrap(), ConsistencyLevel::LocalOne);
+        assert_eq!("LOCAL_SERIAL".parse::<SerialConsistencyLevel>().unwrap(), SerialConsistencyLevel::LocalSerial);
+        assert!("majority".parse::<ConsistencyLevel>().is_err());
+        assert!("quorum".parse::<SerialConsistencyLevel>().is_err());
+    }
+
+    #[test]
+    fn test_write_only_levels_are_rejected_for_reads() {
+        let config = ConsistencyConfig { append: ConsistencyLevel::Quorum, read: ConsistencyLevel::LocalOne, ..Default::default() };
+        config.validate().unwrap();
+        assert_eq!(config.level(Operation::Append), ConsistencyLevel::Quorum);
+        assert_eq!(config.level(Operation::Read), ConsistencyLevel::LocalOne);
+        assert_eq!(config.level(Operation::Lwt), ConsistencyLevel::LocalQuorum);
+
+        assert!(ConsistencyConfig { read: ConsistencyLevel::Any, ..Default::default() }.validate().is_err());
+        assert!(ConsistencyConfig { offsets: ConsistencyLevel::EachQuorum, ..Default::default() }.validate().is_err());
+        assert!(ConsistencyConfig { append: ConsistencyLevel::Any, ..Default::default() }.validate().is_err());
+    }
+}
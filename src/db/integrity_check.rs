@@ -0,0 +1,528 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use futures_util::TryStreamExt;
+use scylla::client::session::Session;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::event_sourcing::ShardLayout;
+use crate::metrics::MetricsHandle;
+
+// ============================================================================
+// Outbox ↔ Event Store Integrity Check
+// ============================================================================
+//
+// Every event appended through the EventStore has exactly one outbox row
+// with the same event_id (same batch). A bug that writes one without the
+// other either publishes an event that isn't in the history (orphaned
+// outbox row) or silently never publishes one (missing outbox row).
+//
+// The check scans both tables over a time window and cross-checks event_id:
+// 1. Scan outbox_messages by created_at and event_store by timestamp, both
+//    widened by `slack` so rows written around the window edges still match
+// 2. Orphan candidates are re-checked against the aggregate's partition in
+//    event_store before being reported
+// 3. Optionally repair:
+//    - orphaned outbox rows are deleted (cleanup)
+//    - missing outbox rows are re-emitted from event_store, which makes the
+//      CDC relay publish them (consumers dedupe by event_id)
+//
+// Outbox rows expire after OUTBOX_TTL, so the window never reaches further
+// back than that - nor than the outbox retention, once the OutboxJanitor
+// deletes published rows. Both scans are full-table ALLOW FILTERING reads - run it
+// off-peak or on a schedule with a generous interval. With a sharded event
+// store every shard table is scanned.
+//
+// ============================================================================
+
+/// default_time_to_live of outbox_messages
+const OUTBOX_TTL: Duration = Duration::from_secs(86_400);
+
+#[derive(Debug, Clone)]
+pub struct IntegrityCheckConfig {
+    /// How far back to check (clamped to the outbox TTL and retention)
+    pub window: Duration,
+    /// Tolerance between event timestamp and outbox created_at
+    pub slack: Duration,
+    /// Delete outbox rows whose event doesn't exist
+    pub cleanup_orphans: bool,
+    /// Re-insert outbox rows for events that have none
+    pub reemit_missing: bool,
+}
+
+impl Default for IntegrityCheckConfig {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_secs(3600),
+            slack: Duration::from_secs(60),
+            cleanup_orphans: false,
+            reemit_missing: false,
+        }
+    }
+}
+
+/// Where re-emitted events of an aggregate type are published
+#[derive(Debug, Clone)]
+pub struct ReemitRoute {
+    pub event_type_prefix: String,
+    pub aggregate_type: String,
+    pub topic: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OutboxRef {
+    pub id: Uuid,
+    pub aggregate_id: Uuid,
+    pub event_id: Option<Uuid>,
+    pub sequence_number: Option<i64>,
+    pub event_type: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EventRef {
+    pub aggregate_id: Uuid,
+    pub sequence_number: i64,
+    pub event_id: Uuid,
+    pub event_type: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Rows without a counterpart, before confirmation
+#[derive(Debug, Default, PartialEq)]
+pub struct IntegrityCandidates {
+    pub orphaned_outbox: Vec<OutboxRef>,
+    pub missing_outbox: Vec<EventRef>,
+    /// Outbox rows without event_id (legacy OrderActor writes), not checked
+    pub legacy_outbox_rows: usize,
+}
+
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct RepairSummary {
+    pub orphans_deleted: usize,
+    pub events_reemitted: usize,
+    /// Missing outbox rows no ReemitRoute matched
+    pub unroutable: usize,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrityReport {
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    /// Requested window reached past the outbox TTL (or retention) and was shortened
+    pub window_clamped: bool,
+    pub outbox_rows_scanned: usize,
+    pub events_scanned: usize,
+    pub legacy_outbox_rows: usize,
+    pub orphaned_outbox: Vec<OutboxRef>,
+    pub missing_outbox: Vec<EventRef>,
+    pub repair: Option<RepairSummary>,
+}
+
+impl IntegrityReport {
+    pub fn is_clean(&self) -> bool {
+        self.orphaned_outbox.is_empty() && self.missing_outbox.is_empty()
+    }
+}
+
+/// Match outbox rows and events by event_id within [start, end)
+///
+/// `outbox` and `events` may extend past the window (the slack); only rows
+/// inside the window are reported.
+pub fn cross_check(
+    outbox: &[OutboxRef],
+    events: &[EventRef],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> IntegrityCandidates {
+    let event_ids: HashSet<Uuid> = events.iter().map(|e| e.event_id).collect();
+    let outbox_event_ids: HashSet<Uuid> = outbox.iter().filter_map(|o| o.event_id).collect();
+    let in_window = |at: DateTime<Utc>| at >= start && at < end;
+
+    let mut candidates = IntegrityCandidates::default();
+
+    for row in outbox.iter().filter(|o| in_window(o.created_at)) {
+        match row.event_id {
+            None => candidates.legacy_outbox_rows += 1,
+            Some(event_id) if !event_ids.contains(&event_id) => candidates.orphaned_outbox.push(row.clone()),
+            Some(_) => {}
+        }
+    }
+
+    candidates.missing_outbox = events
+        .iter()
+        .filter(|e| in_window(e.timestamp) && !outbox_event_ids.contains(&e.event_id))
+        .cloned()
+        .collect();
+
+    candidates
+}
+
+pub struct IntegrityChecker {
+    session: Arc<Session>,
+    config: IntegrityCheckConfig,
+    routes: Vec<ReemitRoute>,
+    shards: ShardLayout,
+    /// Published outbox rows are deleted after this (OutboxJanitor)
+    outbox_retention: Option<Duration>,
+    metrics: MetricsHandle,
+}
+
+impl IntegrityChecker {
+    pub fn new(session: Arc<Session>, config: IntegrityCheckConfig) -> Self {
+        Self {
+            session,
+            config,
+            routes: Vec::new(),
+            shards: ShardLayout::default(),
+            outbox_retention: None,
+            metrics: MetricsHandle::noop(),
+        }
+    }
+
+    /// Re-emit missing events whose type starts with `event_type_prefix`
+    pub fn with_reemit_route(mut self, event_type_prefix: &str, aggregate_type: &str, topic: &str) -> Self {
+        self.routes.push(ReemitRoute {
+            event_type_prefix: event_type_prefix.to_string(),
+            aggregate_type: aggregate_type.to_string(),
+            topic: topic.to_string(),
+        });
+        self
+    }
+
+    /// Event tables of a sharded event store
+    pub fn with_shards(mut self, layout: ShardLayout) -> Self {
+        self.shards = layout;
+        self
+    }
+
+    /// Never look back further than the janitor keeps published rows
+    pub fn with_outbox_retention(mut self, retention: Duration) -> Self {
+        self.outbox_retention = Some(retention);
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: MetricsHandle) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Oldest window whose outbox rows all still exist
+    fn max_window(&self) -> Duration {
+        match self.outbox_retention {
+            // Rows within the slack before the window are matched too
+            Some(retention) => OUTBOX_TTL.min(retention.saturating_sub(self.config.slack)),
+            None => OUTBOX_TTL,
+        }
+    }
+
+    /// Scan the configured window ending now
+    pub async fn run(&self) -> Result<IntegrityReport> {
+        let end = Utc::now();
+        let max_window = self.max_window();
+        let window_clamped = self.config.window > max_window;
+        let window = chrono::Duration::from_std(self.config.window.min(max_window))?;
+        let slack = chrono::Duration::from_std(self.config.slack)?;
+        let start = end - window;
+
+        let outbox = self.scan_outbox(start - slack, end + slack).await?;
+        let events = self.scan_events(start - slack, end + slack).await?;
+        let mut candidates = cross_check(&outbox, &events, start, end);
+
+        // The event may be outside the scanned range - check its partition
+        let mut orphaned = Vec::new();
+        for row in candidates.orphaned_outbox.drain(..) {
+            if !self.event_exists(row.aggregate_id, row.event_id).await? {
+                orphaned.push(row);
+            }
+        }
+
+        let mut report = IntegrityReport {
+            window_start: start,
+            window_end: end,
+            window_clamped,
+            outbox_rows_scanned: outbox.len(),
+            events_scanned: events.len(),
+            legacy_outbox_rows: candidates.legacy_outbox_rows,
+            orphaned_outbox: orphaned,
+            missing_outbox: candidates.missing_outbox,
+            repair: None,
+        };
+
+        if self.config.cleanup_orphans || self.config.reemit_missing {
+            report.repair = Some(self.repair(&report).await);
+        }
+
+        self.metrics.record_integrity_findings(report.orphaned_outbox.len(), report.missing_outbox.len());
+
+        if report.is_clean() {
+            tracing::info!(
+                outbox_rows = report.outbox_rows_scanned,
+                events = report.events_scanned,
+                "🔗 Outbox/event_store integrity check clean"
+            );
+        } else {
+            tracing::warn!(
+                orphaned_outbox = report.orphaned_outbox.len(),
+                missing_outbox = report.missing_outbox.len(),
+                repaired = report.repair.is_some(),
+                "⚠️ Outbox/event_store integrity check found orphans"
+            );
+        }
+
+        Ok(report)
+    }
+
+    /// Run the check every `interval` until the task is aborted
+    pub fn spawn_background(self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.run().await {
+                    tracing::error!(error = %e, "Integrity check failed");
+                }
+            }
+        })
+    }
+
+    async fn repair(&self, report: &IntegrityReport) -> RepairSummary {
+        let mut summary = RepairSummary::default();
+
+        if self.config.cleanup_orphans {
+            for row in &report.orphaned_outbox {
+                match self.session
+                    .query_unpaged("DELETE FROM outbox_messages WHERE id = ?", (row.id,))
+                    .await
+                {
+                    Ok(_) => summary.orphans_deleted += 1,
+                    Err(e) => summary.errors.push(format!("delete outbox {}: {}", row.id, e)),
+                }
+            }
+        }
+
+        if self.config.reemit_missing {
+            for event in &report.missing_outbox {
+                let Some(route) = self.routes.iter().find(|r| event.event_type.starts_with(&r.event_type_prefix)) else {
+                    summary.unroutable += 1;
+                    continue;
+                };
+                match self.reemit(event, route).await {
+                    Ok(true) => summary.events_reemitted += 1,
+                    Ok(false) => {}
+                    Err(e) => summary.errors.push(format!("re-emit event {}: {}", event.event_id, e)),
+                }
+            }
+        }
+
+        tracing::info!(
+            orphans_deleted = summary.orphans_deleted,
+            events_reemitted = summary.events_reemitted,
+            unroutable = summary.unroutable,
+            errors = summary.errors.len(),
+            "🔧 Integrity repair finished"
+        );
+        summary
+    }
+
+    async fn scan_outbox(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<OutboxRef>> {
+        let mut rows = self.session
+            .query_iter(
+                "SELECT id, aggregate_id, event_id, sequence_number, event_type, created_at
+                 FROM outbox_messages WHERE created_at >= ? AND created_at < ? ALLOW FILTERING",
+                (from, to),
+            )
+            .await?
+            .rows_stream::<(Uuid, Uuid, Option<Uuid>, Option<i64>, Option<String>, Option<DateTime<Utc>>)>()?;
+
+        let mut outbox = Vec::new();
+        while let Some((id, aggregate_id, event_id, sequence_number, event_type, created_at)) = rows.try_next().await? {
+            let Some(created_at) = created_at else { continue };
+            outbox.push(OutboxRef {
+                id,
+                aggregate_id,
+                event_id,
+                sequence_number,
+                event_type: event_type.unwrap_or_default(),
+                created_at,
+            });
+        }
+        Ok(outbox)
+    }
+
+    async fn scan_events(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<EventRef>> {
+        let mut events = Vec::new();
+        for table in self.shards.tables() {
+            let mut rows = self.session
+                .query_iter(
+                    format!(
+                        "SELECT aggregate_id, sequence_number, event_id, event_type, timestamp
+                         FROM {} WHERE timestamp >= ? AND timestamp < ? ALLOW FILTERING",
+                        table
+                    ),
+                    (from, to),
+                )
+                .await?
+                .rows_stream::<(Uuid, i64, Uuid, String, DateTime<Utc>)>()?;
+
+            while let Some((aggregate_id, sequence_number, event_id, event_type, timestamp)) = rows.try_next().await? {
+                events.push(EventRef { aggregate_id, sequence_number, event_id, event_type, timestamp });
+            }
+        }
+        Ok(events)
+    }
+
+    async fn event_exists(&self, aggregate_id: Uuid, event_id: Option<Uuid>) -> Result<bool> {
+        let Some(event_id) = event_id else { return Ok(true) };
+        let result = self.session
+            .query_unpaged(
+                format!("SELECT event_id FROM {} WHERE aggregate_id = ?", self.shards.table_for(aggregate_id)),
+                (aggregate_id,),
+            )
+            .await?;
+
+        for row in result.into_rows_result()?.rows::<(Uuid,)>()? {
+            if row?.0 == event_id {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Insert an outbox row for a stored event; false if one appeared meanwhile
+    async fn reemit(&self, event: &EventRef, route: &ReemitRoute) -> Result<bool> {
+        let existing = self.session
+            .query_unpaged(
+                "SELECT id FROM outbox_messages WHERE event_id = ? LIMIT 1 ALLOW FILTERING",
+                (event.event_id,),
+            )
+            .await?
+            .into_rows_result()?
+            .maybe_first_row::<(Uuid,)>()?;
+        if existing.is_some() {
+            return Ok(false);
+        }
+
+        let Some((event_version, event_data, causation_id, correlation_id, origin_region)) = self.session
+            .query_unpaged(
+                format!(
+                    "SELECT event_version, event_data, causation_id, correlation_id, origin_region
+                     FROM {} WHERE aggregate_id = ? AND sequence_number = ?",
+                    self.shards.table_for(event.aggregate_id)
+                ),
+                (event.aggregate_id, event.sequence_number),
+            )
+            .await?
+            .into_rows_result()?
+            .maybe_first_row::<(i32, String, Option<Uuid>, Option<Uuid>, Option<String>)>()?
+        else {
+            return Ok(false);
+        };
+
+        self.session
+            .query_unpaged(
+                "INSERT INTO outbox_messages (
+                    id, aggregate_id, aggregate_type, event_id, event_type, event_version,
+                    sequence_number, payload, topic, partition_key, causation_id,
+                    correlation_id, origin_region, created_at, attempts
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 0)",
+                (
+                    Uuid::new_v4(),
+                    event.aggregate_id,
+                    route.aggregate_type.as_str(),
+                    event.event_id,
+                    event.event_type.as_str(),
+                    event_version,
+                    event.sequence_number,
+                    event_data,
+                    route.topic.as_str(),
+                    event.aggregate_id.to_string(),
+                    causation_id,
+                    correlation_id,
+                    origin_region,
+                    Utc::now(),
+                ),
+            )
+            .await?;
+
+        tracing::info!(
+            event_id = %event.event_id,
+            aggregate_id = %event.aggregate_id,
+            event_type = %event.event_type,
+            "♻️ Re-emitted event with missing outbox row"
+        );
+        Ok(true)
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    fn event(event_id: Uuid, secs: i64) -> EventRef {
+        EventRef {
+            aggregate_id: Uuid::new_v4(),
+            sequence_number: 1,
+            event_id,
+            event_type: "OrderCreated".to_string(),
+            timestamp: at(secs),
+        }
+    }
+
+    fn outbox(event_id: Option<Uuid>, secs: i64) -> OutboxRef {
+        OutboxRef {
+            id: Uuid::new_v4(),
+            aggregate_id: Uuid::new_v4(),
+            event_id,
+            sequence_number: Some(1),
+            event_type: "OrderCreated".to_string(),
+            created_at: at(secs),
+        }
+    }
+
+    #[test]
+    fn test_matching_rows_are_clean() {
+        let id = Uuid::new_v4();
+        let candidates = cross_check(&[outbox(Some(id), 10)], &[event(id, 10)], at(0), at(100));
+        assert_eq!(candidates, IntegrityCandidates::default());
+    }
+
+    #[test]
+    fn test_orphans_in_both_directions() {
+        let (orphan, missing) = (Uuid::new_v4(), Uuid::new_v4());
+        let candidates = cross_check(&[outbox(Some(orphan), 10)], &[event(missing, 20)], at(0), at(100));
+
+        assert_eq!(candidates.orphaned_outbox.len(), 1);
+        assert_eq!(candidates.orphaned_outbox[0].event_id, Some(orphan));
+        assert_eq!(candidates.missing_outbox.len(), 1);
+        assert_eq!(candidates.missing_outbox[0].event_id, missing);
+    }
+
+    #[test]
+    fn test_counterpart_in_slack_matches_and_legacy_rows_skipped() {
+        let id = Uuid::new_v4();
+        // Event just inside the window, outbox row written just after it
+        let candidates = cross_check(
+            &[outbox(Some(id), 101), outbox(None, 50)],
+            &[event(id, 99)],
+            at(0),
+            at(100),
+        );
+
+        assert!(candidates.missing_outbox.is_empty());
+        assert!(candidates.orphaned_outbox.is_empty());
+        assert_eq!(candidates.legacy_outbox_rows, 1);
+    }
+}
@@ -0,0 +1,286 @@
+use scylla::client::session::Session;
+use scylla::statement::Consistency;
+use std::collections::{BTreeMap, HashMap};
+use anyhow::{Result, anyhow};
+
+use crate::actors::HealthStatus;
+
+// ============================================================================
+// Keyspace Check - Replication/Consistency Expectations Verified at Startup
+// ============================================================================
+//
+// The schema ships with replication_factor 1 so it works on a single dev
+// node. Deployed as-is to production, every write has exactly one copy and
+// QUORUM silently degenerates to ONE.
+//
+// At startup we read the actual replication settings from
+// system_schema.keyspaces and compare them with what the application
+// expects:
+// 1. Replication factor per datacenter >= the expected minimum
+// 2. Every operation's consistency level can be satisfied by that RF
+//    (e.g. QUORUM needs RF/2+1 live replicas, ALL needs every replica)
+//
+// Divergence is logged and reported to health as Degraded - the system
+// still runs, but the problem is visible immediately.
+//
+// ============================================================================
+
+/// What the application expects from its keyspace
+#[derive(Debug, Clone)]
+pub struct KeyspaceExpectations {
+    pub keyspace: String,
+    pub min_replication_factor: u32,
+    /// Consistency level used per operation (e.g. "event_store.append")
+    pub consistency: BTreeMap<String, Consistency>,
+}
+
+impl KeyspaceExpectations {
+    /// Production defaults: RF >= 3, LOCAL_QUORUM reads and writes
+    pub fn new(keyspace: impl Into<String>) -> Self {
+        Self {
+            keyspace: keyspace.into(),
+            min_replication_factor: 3,
+            consistency: BTreeMap::new(),
+        }
+        .with_consistency("event_store.append", Consistency::LocalQuorum)
+        .with_consistency("event_store.load", Consistency::LocalQuorum)
+    }
+
+    pub fn with_min_replication_factor(mut self, rf: u32) -> Self {
+        self.min_replication_factor = rf;
+        self
+    }
+
+    pub fn with_consistency(mut self, operation: impl Into<String>, consistency: Consistency) -> Self {
+        self.consistency.insert(operation.into(), consistency);
+        self
+    }
+}
+
+/// Replication settings as stored in system_schema.keyspaces
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplicationSettings {
+    pub strategy: String,
+    /// Replication factor per datacenter ("replication_factor" for SimpleStrategy)
+    pub factors: BTreeMap<String, u32>,
+}
+
+impl ReplicationSettings {
+    /// Parse the `replication` map of a keyspace
+    pub fn from_map(replication: &HashMap<String, String>) -> Result<Self> {
+        let class = replication
+            .get("class")
+            .ok_or_else(|| anyhow!("Replication settings have no 'class'"))?;
+        let strategy = class.rsplit('.').next().unwrap_or(class).to_string();
+
+        let mut factors = BTreeMap::new();
+        for (key, value) in replication {
+            if key == "class" {
+                continue;
+            }
+            let rf: u32 = value
+                .parse()
+                .map_err(|_| anyhow!("Invalid replication factor '{}' for '{}'", value, key))?;
+            factors.insert(key.clone(), rf);
+        }
+
+        Ok(Self { strategy, factors })
+    }
+
+    /// Smallest replication factor of any datacenter
+    pub fn min_factor(&self) -> u32 {
+        self.factors.values().copied().min().unwrap_or(0)
+    }
+
+    /// Replication factor summed over all datacenters
+    pub fn total_factor(&self) -> u32 {
+        self.factors.values().sum()
+    }
+}
+
+/// Replicas that must acknowledge for `consistency` to succeed
+fn required_replicas(consistency: Consistency, settings: &ReplicationSettings) -> u32 {
+    let local_rf = settings.min_factor();
+    let total_rf = settings.total_factor();
+
+    match consistency {
+        Consistency::Any | Consistency::One | Consistency::LocalOne => 1,
+        Consistency::Two => 2,
+        Consistency::Three => 3,
+        Consistency::Quorum => total_rf / 2 + 1,
+        Consistency::LocalQuorum | Consistency::EachQuorum => local_rf / 2 + 1,
+        Consistency::All => total_rf,
+        _ => local_rf / 2 + 1,
+    }
+}
+
+/// Outcome of comparing expectations with the live keyspace
+#[derive(Debug, Clone)]
+pub struct KeyspaceReport {
+    pub keyspace: String,
+    pub actual: ReplicationSettings,
+    pub divergences: Vec<String>,
+}
+
+impl KeyspaceReport {
+    pub fn is_ok(&self) -> bool {
+        self.divergences.is_empty()
+    }
+
+    pub fn health_status(&self) -> HealthStatus {
+        if self.is_ok() {
+            HealthStatus::Healthy
+        } else {
+            HealthStatus::Degraded(self.divergences.join("; "))
+        }
+    }
+}
+
+/// Compare expectations against actual replication settings
+pub fn evaluate(expectations: &KeyspaceExpectations, actual: ReplicationSettings) -> KeyspaceReport {
+    let mut divergences = Vec::new();
+
+    for (dc, rf) in &actual.factors {
+        if *rf < expectations.min_replication_factor {
+            divergences.push(format!(
+                "replication factor {} in '{}' is below expected minimum {}",
+                rf, dc, expectations.min_replication_factor
+            ));
+        }
+    }
+
+    for (operation, consistency) in &expectations.consistency {
+        let required = required_replicas(*consistency, &actual);
+        let available = match consistency {
+            Consistency::Quorum | Consistency::All => actual.total_factor(),
+            _ => actual.min_factor(),
+        };
+
+        if required > available {
+            divergences.push(format!(
+                "{} uses {:?} which needs {} replicas but only {} exist",
+                operation, consistency, required, available
+            ));
+        } else if available == 1 && !matches!(consistency, Consistency::Any | Consistency::One | Consistency::LocalOne) {
+            divergences.push(format!(
+                "{} uses {:?} but with a single replica it is no stronger than ONE",
+                operation, consistency
+            ));
+        }
+    }
+
+    KeyspaceReport {
+        keyspace: expectations.keyspace.clone(),
+        actual,
+        divergences,
+    }
+}
+
+/// Read the keyspace's replication settings and evaluate them
+pub async fn check_keyspace(session: &Session, expectations: &KeyspaceExpectations) -> Result<KeyspaceReport> {
+    let result = session
+        .query_unpaged(
+            "SELECT replication FROM system_schema.keyspaces WHERE keyspace_name = ?",
+            (expectations.keyspace.as_str(),),
+        )
+        .await?;
+
+    let rows_result = result.into_rows_result()?;
+    let (replication,) = rows_result
+        .maybe_first_row::<(HashMap<String, String>,)>()?
+        .ok_or_else(|| anyhow!("Keyspace '{}' does not exist", expectations.keyspace))?;
+
+    let report = evaluate(expectations, ReplicationSettings::from_map(&replication)?);
+
+    if report.is_ok() {
+        tracing::info!(
+            keyspace = %report.keyspace,
+            strategy = %report.actual.strategy,
+            replication_factor = report.actual.min_factor(),
+            "✅ Keyspace replication matches expectations"
+        );
+    } else {
+        for divergence in &report.divergences {
+            tracing::warn!(
+                keyspace = %report.keyspace,
+                "⚠️  Keyspace misconfiguration: {}",
+                divergence
+            );
+        }
+    }
+
+    Ok(report)
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replication(pairs: &[(&str, &str)]) -> ReplicationSettings {
+        let map: HashMap<String, String> = pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        ReplicationSettings::from_map(&map).unwrap()
+    }
+
+    #[test]
+    fn test_parse_network_topology() {
+        let settings = replication(&[
+            ("class", "org.apache.cassandra.locator.NetworkTopologyStrategy"),
+            ("dc1", "3"),
+            ("dc2", "2"),
+        ]);
+
+        assert_eq!(settings.strategy, "NetworkTopologyStrategy");
+        assert_eq!(settings.min_factor(), 2);
+        assert_eq!(settings.total_factor(), 5);
+    }
+
+    #[test]
+    fn test_rf1_flagged_against_production_expectations() {
+        let settings = replication(&[
+            ("class", "NetworkTopologyStrategy"),
+            ("datacenter1", "1"),
+        ]);
+
+        let report = evaluate(&KeyspaceExpectations::new("orders_ks"), settings);
+
+        assert!(!report.is_ok());
+        assert!(report.divergences.iter().any(|d| d.contains("below expected minimum 3")));
+        assert!(report.health_status().is_degraded());
+    }
+
+    #[test]
+    fn test_rf3_local_quorum_is_healthy() {
+        let settings = replication(&[
+            ("class", "NetworkTopologyStrategy"),
+            ("datacenter1", "3"),
+        ]);
+
+        let report = evaluate(&KeyspaceExpectations::new("orders_ks"), settings);
+
+        assert!(report.is_ok(), "{:?}", report.divergences);
+        assert!(report.health_status().is_healthy());
+    }
+
+    #[test]
+    fn test_unsatisfiable_consistency_flagged() {
+        let settings = replication(&[
+            ("class", "SimpleStrategy"),
+            ("replication_factor", "2"),
+        ]);
+        let expectations = KeyspaceExpectations::new("orders_ks")
+            .with_min_replication_factor(2)
+            .with_consistency("audit.write", Consistency::Three);
+
+        let report = evaluate(&expectations, settings);
+
+        assert_eq!(report.divergences.len(), 1);
+        assert!(report.divergences[0].contains("audit.write"));
+    }
+}
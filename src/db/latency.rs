@@ -0,0 +1,153 @@
+use scylla::client::session::Session;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// ============================================================================
+// Scylla Latency Monitor - Client-Side Cluster Stress Signal
+// ============================================================================
+//
+// Collects latency and outcome of our own Scylla queries over a sliding
+// window, so background consumers (the CDC relay) can back off while the
+// cluster is struggling instead of making an incident worse.
+//
+// Sources:
+// - EventStore queries/appends (when the monitor is attached)
+// - A lightweight probe (`SELECT now() FROM system.local`) on an interval,
+//   so there is a signal even without command traffic
+//
+// The window holds at most MAX_SAMPLES samples no older than `window`.
+//
+// ============================================================================
+
+/// Upper bound on retained samples
+const MAX_SAMPLES: usize = 10_000;
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    at: Instant,
+    latency: Duration,
+    ok: bool,
+}
+
+/// Latency/error statistics of the current window
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LatencySnapshot {
+    pub samples: usize,
+    pub p95: Duration,
+    pub error_rate: f64,
+}
+
+pub struct LatencyMonitor {
+    window: Duration,
+    samples: Mutex<VecDeque<Sample>>,
+}
+
+impl LatencyMonitor {
+    pub fn new(window: Duration) -> Self {
+        Self { window, samples: Mutex::new(VecDeque::new()) }
+    }
+
+    /// Record one query
+    pub fn observe(&self, latency: Duration, ok: bool) {
+        self.observe_at(Instant::now(), latency, ok);
+    }
+
+    fn observe_at(&self, at: Instant, latency: Duration, ok: bool) {
+        let mut samples = self.samples.lock().unwrap();
+        samples.push_back(Sample { at, latency, ok });
+        if samples.len() > MAX_SAMPLES {
+            samples.pop_front();
+        }
+    }
+
+    /// Run `query` and record its latency and outcome
+    pub async fn time<T, E, F>(&self, query: F) -> Result<T, E>
+    where
+        F: Future<Output = Result<T, E>>,
+    {
+        let started = Instant::now();
+        let result = query.await;
+        self.observe(started.elapsed(), result.is_ok());
+        result
+    }
+
+    pub fn snapshot(&self) -> LatencySnapshot {
+        self.snapshot_at(Instant::now())
+    }
+
+    fn snapshot_at(&self, now: Instant) -> LatencySnapshot {
+        let mut samples = self.samples.lock().unwrap();
+        while samples.front().is_some_and(|s| now.saturating_duration_since(s.at) > self.window) {
+            samples.pop_front();
+        }
+        if samples.is_empty() {
+            return LatencySnapshot::default();
+        }
+
+        let mut latencies: Vec<Duration> = samples.iter().map(|s| s.latency).collect();
+        latencies.sort_unstable();
+        // Nearest-rank percentile
+        let rank = ((0.95 * latencies.len() as f64).ceil() as usize).clamp(1, latencies.len());
+        let errors = samples.iter().filter(|s| !s.ok).count();
+
+        LatencySnapshot {
+            samples: samples.len(),
+            p95: latencies[rank - 1],
+            error_rate: errors as f64 / samples.len() as f64,
+        }
+    }
+
+    /// Probe the cluster every `interval` with a trivial query
+    pub fn spawn_probe(self: Arc<Self>, session: Arc<Session>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                let probe = self.time(session.query_unpaged("SELECT now() FROM system.local", &[])).await;
+                if let Err(e) = probe {
+                    tracing::debug!(error = %e, "Scylla latency probe failed");
+                }
+            }
+        })
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_p95_and_error_rate() {
+        let monitor = LatencyMonitor::new(Duration::from_secs(60));
+        let now = Instant::now();
+        for ms in 1..=100 {
+            monitor.observe_at(now, Duration::from_millis(ms), ms % 10 != 0);
+        }
+
+        let snapshot = monitor.snapshot_at(now);
+        assert_eq!(snapshot.samples, 100);
+        assert_eq!(snapshot.p95, Duration::from_millis(95));
+        assert_eq!(snapshot.error_rate, 0.1);
+    }
+
+    #[test]
+    fn test_old_samples_leave_the_window() {
+        let monitor = LatencyMonitor::new(Duration::from_secs(10));
+        let start = Instant::now();
+        monitor.observe_at(start, Duration::from_secs(2), false);
+        monitor.observe_at(start + Duration::from_secs(8), Duration::from_millis(3), true);
+
+        let snapshot = monitor.snapshot_at(start + Duration::from_secs(15));
+        assert_eq!(snapshot.samples, 1);
+        assert_eq!(snapshot.p95, Duration::from_millis(3));
+        assert_eq!(snapshot.error_rate, 0.0);
+
+        assert_eq!(monitor.snapshot_at(start + Duration::from_secs(30)), LatencySnapshot::default());
+    }
+}
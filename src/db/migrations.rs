@@ -0,0 +1,615 @@
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use scylla::client::session::Session;
+use serde::Serialize;
+use std::collections::HashSet;
+
+use crate::config::CdcSource;
+use crate::event_sourcing::{TenantContext, TenantScope};
+
+// ============================================================================
+// Migrations - Schema Bootstrap Without `make schema`
+// ============================================================================
+//
+// `scylladb_cdc --migrate` creates what the service needs instead of
+// relying on schema.cql having been applied by hand:
+//
+//   1. the keyspace (NetworkTopologyStrategy with the configured
+//      replication factor, tablets disabled for CDC and secondary indexes)
+//   2. `schema_migrations`, the versions applied so far
+//   3. every migration not recorded there, statement by statement
+//   4. CDC on the [[cdc.tables]] streamed next to the outbox
+//   5. the columns added to event_store since, on the shard tables
+//      (event_store_0 .. event_store_{N-1}) of a sharded event store
+//   6. steps 1-3 and 5 for the keyspace of every keyspace tenant, then CDC
+//      (with the outbox's options from schema.cql) on every tenant outbox
+//
+// Tables of prefixed tenants are not created: their outbox must exist
+// (created from schema.cql with the prefix applied), or the run fails
+// before a relay would start on a missing table.
+//
+// Migration 1 is schema.cql itself (event_store, outbox_messages with CDC,
+// aggregate_sequence, dead_letter_queue, cdc_offsets, read models, ...),
+// minus its CREATE KEYSPACE / USE. schema.cql stays the complete current
+// schema for `make schema`; a change to it is also added below as the next
+// migration so that migrated keyspaces pick it up. Statements use IF NOT
+// EXISTS, so a migration interrupted halfway is simply re-run. CQL has no
+// `ADD IF NOT EXISTS`: an `ALTER TABLE t ADD column type` is skipped when
+// the column exists already (fresh keyspaces get it from migration 1).
+//
+// ============================================================================
+
+/// A versioned schema change
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub version: i32,
+    pub description: &'static str,
+    pub cql: &'static str,
+}
+
+/// All migrations, in version order
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Baseline schema (schema.cql)",
+        cql: include_str!("schema.cql"),
+    },
+    Migration {
+        version: 2,
+        description: "DLQ quarantine table",
+        cql: "
+            CREATE TABLE IF NOT EXISTS dead_letter_quarantine (
+                id              UUID PRIMARY KEY,
+                aggregate_id    UUID,
+                event_type      TEXT,
+                payload         TEXT,
+                error_message   TEXT,
+                failure_count   INT,
+                first_failed_at TIMESTAMP,
+                last_failed_at  TIMESTAMP,
+                failure_context TEXT,
+                quarantined_at  TIMESTAMP
+            ) WITH comment = 'Dead letters quarantined after repeated replay failures';
+        ",
+    },
+    Migration {
+        version: 3,
+        description: "Audit columns on event_store",
+        cql: "
+            ALTER TABLE event_store ADD user_id UUID;
+            ALTER TABLE event_store ADD metadata MAP<TEXT, TEXT>;
+        ",
+    },
+    Migration {
+        version: 4,
+        description: "Stream audit progress table",
+        cql: "
+            CREATE TABLE IF NOT EXISTS stream_audit_progress (
+                aggregate_id  UUID PRIMARY KEY,
+                last_sequence BIGINT,
+                updated_at    TIMESTAMP
+            ) WITH comment = 'Highest published sequence per aggregate for the stream audit';
+        ",
+    },
+    Migration {
+        version: 5,
+        description: "Personal data keys table",
+        cql: "
+            CREATE TABLE IF NOT EXISTS personal_data_keys (
+                subject_id UUID PRIMARY KEY,
+                data_key   BLOB,
+                created_at TIMESTAMP
+            ) WITH comment = 'Per-aggregate keys of encrypted personal data, deleted to forget it';
+        ",
+    },
+    Migration {
+        version: 6,
+        description: "Processed commands table",
+        cql: "
+            CREATE TABLE IF NOT EXISTS processed_commands (
+                aggregate_id UUID,
+                command_id   UUID,
+                version      BIGINT,
+                processed_at TIMESTAMP,
+                PRIMARY KEY (aggregate_id, command_id)
+            ) WITH default_time_to_live = 86400
+              AND comment = 'Version each command id produced per aggregate (duplicate command protection)';
+        ",
+    },
+    Migration {
+        version: 7,
+        description: "Event type names on outbox_messages",
+        cql: "
+            ALTER TABLE outbox_messages ADD aggregate_event_type TEXT;
+            ALTER TABLE outbox_messages ADD variant_type TEXT;
+        ",
+    },
+    Migration {
+        version: 8,
+        description: "Events by time table",
+        cql: "
+            CREATE TABLE IF NOT EXISTS events_by_time (
+                aggregate_type  TEXT,
+                bucket          TIMESTAMP,
+                timestamp       TIMESTAMP,
+                aggregate_id    UUID,
+                sequence_number BIGINT,
+                event_id        UUID,
+                event_type      TEXT,
+                event_version   INT,
+                event_data      TEXT,
+                causation_id    UUID,
+                correlation_id  UUID,
+                origin_region   TEXT,
+                user_id         UUID,
+                metadata        MAP<TEXT, TEXT>,
+                PRIMARY KEY ((aggregate_type, bucket), timestamp, aggregate_id, sequence_number)
+            ) WITH CLUSTERING ORDER BY (timestamp ASC, aggregate_id ASC, sequence_number ASC)
+              AND default_time_to_live = 2592000
+              AND comment = 'Events of all aggregates by minute (global feed), kept 30 days';
+        ",
+    },
+    Migration {
+        version: 9,
+        description: "Aggregate catalog and daily event counts",
+        cql: "
+            CREATE TABLE IF NOT EXISTS aggregate_catalog (
+                aggregate_id    UUID PRIMARY KEY,
+                aggregate_type  TEXT,
+                created_at      TIMESTAMP
+            ) WITH comment = 'Type and creation time of every aggregate';
+            CREATE TABLE IF NOT EXISTS daily_event_counts (
+                aggregate_type  TEXT,
+                day             DATE,
+                events          COUNTER,
+                PRIMARY KEY (aggregate_type, day)
+            ) WITH CLUSTERING ORDER BY (day DESC)
+              AND comment = 'Events appended per aggregate type and day';
+        ",
+    },
+    Migration {
+        version: 10,
+        description: "Scheduled outbox publications",
+        cql: "
+            ALTER TABLE outbox_messages ADD publish_after TIMESTAMP;
+            CREATE TABLE IF NOT EXISTS scheduled_publications (
+                due_bucket           TIMESTAMP,
+                id                   UUID,
+                outbox_table         TEXT,
+                aggregate_id         UUID,
+                aggregate_type       TEXT,
+                event_id             UUID,
+                event_version        INT,
+                correlation_id       UUID,
+                causation_id         UUID,
+                sequence_number      BIGINT,
+                event_type           TEXT,
+                aggregate_event_type TEXT,
+                payload              TEXT,
+                partition_key        TEXT,
+                origin_region        TEXT,
+                publish_after        TIMESTAMP,
+                scheduled_at         TIMESTAMP,
+                PRIMARY KEY ((due_bucket), publish_after, id)
+            ) WITH CLUSTERING ORDER BY (publish_after ASC, id ASC)
+              AND comment = 'Outbox events held until their publish_after, by the hour they are due';
+        ",
+    },
+    Migration {
+        version: 11,
+        description: "HLC timestamps on outbox rows",
+        cql: "
+            ALTER TABLE outbox_messages ADD hlc TEXT;
+        ",
+    },
+    Migration {
+        version: 12,
+        description: "Per-stream CDC relay positions",
+        cql: "
+            CREATE TABLE IF NOT EXISTS cdc_stream_offsets (
+                consumer_id TEXT,
+                table_name  TEXT,
+                stream_id   BLOB,
+                generation  TIMESTAMP,
+                time        TIMESTAMP,
+                updated_at  TIMESTAMP,
+                PRIMARY KEY ((consumer_id, table_name), stream_id)
+            ) WITH default_time_to_live = 604800
+              AND comment = 'Resume position of every CDC stream of a relay';
+        ",
+    },
+    Migration {
+        version: 13,
+        description: "Soft-delete index of read models",
+        cql: "
+            ALTER TABLE orders_by_customer ADD is_deleted BOOLEAN;
+            CREATE TABLE IF NOT EXISTS read_model_tombstones (
+                table_name  TEXT,
+                day         DATE,
+                deleted_at  TIMESTAMP,
+                id          UUID,
+                PRIMARY KEY ((table_name, day), deleted_at, id)
+            ) WITH comment = 'Soft-deleted read model rows by table and day, for purging';
+        ",
+    },
+];
+
+/// What a migration run did
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationReport {
+    pub keyspace: String,
+    /// Versions applied by this run
+    pub applied: Vec<i32>,
+    /// Versions found already applied
+    pub already_applied: Vec<i32>,
+    /// `keyspace.table` of the tables CDC was enabled on
+    pub cdc_enabled: Vec<String>,
+    /// Keyspaces of keyspace tenants, migrated the same way
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tenants: Vec<MigrationReport>,
+}
+
+/// CDC options of outbox tables, as schema.cql creates outbox_messages
+const OUTBOX_CDC: &str = "{'enabled': true, 'preimage': false, 'postimage': true, 'ttl': 86400}";
+
+pub struct Migrator<'a> {
+    session: &'a Session,
+    keyspace: String,
+    replication_factor: u32,
+    cdc_tables: Vec<CdcSource>,
+    event_tables: Vec<String>,
+    tenant_outboxes: Vec<(TenantContext, CdcSource)>,
+}
+
+impl<'a> Migrator<'a> {
+    /// Migrator of `keyspace`; a keyspace it creates gets replication factor 1
+    pub fn new(session: &'a Session, keyspace: &str) -> Self {
+        Self {
+            session,
+            keyspace: keyspace.to_string(),
+            replication_factor: 1,
+            cdc_tables: Vec::new(),
+            event_tables: Vec::new(),
+            tenant_outboxes: Vec::new(),
+        }
+    }
+
+    pub fn with_replication_factor(mut self, replication_factor: u32) -> Self {
+        self.replication_factor = replication_factor;
+        self
+    }
+
+    /// Tables to enable CDC on (default CDC options)
+    ///
+    /// Not for the outbox: migration 1 creates it with CDC, and re-enabling
+    /// would reset its post-image and TTL options.
+    pub fn with_cdc_tables(mut self, cdc_tables: Vec<CdcSource>) -> Self {
+        self.cdc_tables = cdc_tables;
+        self
+    }
+
+    /// Event tables besides event_store (shards) that get its added columns
+    pub fn with_event_tables(mut self, event_tables: Vec<String>) -> Self {
+        self.event_tables = event_tables;
+        self
+    }
+
+    /// Outboxes of [[tenants]]: keyspace tenants get their keyspace migrated,
+    /// every tenant outbox gets CDC
+    pub fn with_tenant_outboxes(mut self, tenant_outboxes: Vec<(TenantContext, CdcSource)>) -> Self {
+        self.tenant_outboxes = tenant_outboxes;
+        self
+    }
+
+    /// Bring the keyspace, and the keyspaces of tenants, up to the newest
+    /// migration; leaves the session using the keyspace
+    pub async fn run(&self) -> Result<MigrationReport> {
+        // Tenant keyspaces first, the session ends up using ours
+        let mut tenants = Vec::new();
+        for keyspace in self.tenant_keyspaces() {
+            tenants.push(self.migrate_keyspace(keyspace).await?);
+        }
+        let mut report = self.migrate_keyspace(&self.keyspace).await?;
+        report.tenants = tenants;
+
+        for source in &self.cdc_tables {
+            let result = self
+                .session
+                .query_unpaged(format!("ALTER TABLE {} WITH cdc = {{'enabled': true}}", source.label()), &[])
+                .await;
+            match result {
+                Ok(_) => report.cdc_enabled.push(source.label()),
+                // Tables of other services may not exist yet; their reader fails loudly later
+                Err(e) => tracing::warn!(table = %source.label(), error = %e, "Could not enable CDC"),
+            }
+        }
+
+        self.enable_tenant_outboxes(&mut report).await?;
+
+        tracing::info!(
+            keyspace = %report.keyspace,
+            applied = ?report.applied,
+            already_applied = report.already_applied.len(),
+            tenant_keyspaces = report.tenants.len(),
+            "✅ Schema up to date"
+        );
+        Ok(report)
+    }
+
+    /// Keyspaces of keyspace tenants, each once
+    fn tenant_keyspaces(&self) -> Vec<&str> {
+        let mut keyspaces: Vec<&str> = self
+            .tenant_outboxes
+            .iter()
+            .filter_map(|(tenant, _)| tenant.keyspace_name())
+            .filter(|keyspace| *keyspace != self.keyspace)
+            .collect();
+        keyspaces.sort_unstable();
+        keyspaces.dedup();
+        keyspaces
+    }
+
+    /// Apply the pending migrations to `keyspace`, creating it if needed
+    async fn migrate_keyspace(&self, keyspace: &str) -> Result<MigrationReport> {
+        self.session
+            .query_unpaged(
+                format!(
+                    "CREATE KEYSPACE IF NOT EXISTS {} WITH replication = \
+                     {{'class': 'NetworkTopologyStrategy', 'replication_factor': {}}} \
+                     AND tablets = {{'enabled': false}}",
+                    keyspace, self.replication_factor
+                ),
+                &[],
+            )
+            .await?;
+        self.session.use_keyspace(keyspace, false).await?;
+        self.session
+            .query_unpaged(
+                "CREATE TABLE IF NOT EXISTS schema_migrations (
+                    version     INT PRIMARY KEY,
+                    description TEXT,
+                    applied_at  TIMESTAMP
+                ) WITH comment = 'Schema migrations applied by --migrate'",
+                &[],
+            )
+            .await?;
+
+        let applied_versions = self.applied_versions().await?;
+        let mut report = MigrationReport {
+            keyspace: keyspace.to_string(),
+            applied: Vec::new(),
+            already_applied: Vec::new(),
+            cdc_enabled: Vec::new(),
+            tenants: Vec::new(),
+        };
+
+        for migration in MIGRATIONS {
+            if applied_versions.contains(&migration.version) {
+                report.already_applied.push(migration.version);
+                continue;
+            }
+            tracing::info!(keyspace = %keyspace, version = migration.version, description = migration.description, "Applying schema migration");
+            for statement in statements(migration.cql) {
+                if let Some((table, column, cql_type)) = added_column(&statement) {
+                    add_column_if_missing(self.session, keyspace, table, column, &cql_type).await?;
+                    continue;
+                }
+                self.session
+                    .query_unpaged(statement.clone(), &[])
+                    .await
+                    .with_context(|| format!("Migration {} failed at: {}", migration.version, statement))?;
+            }
+            self.session
+                .query_unpaged(
+                    "INSERT INTO schema_migrations (version, description, applied_at) VALUES (?, ?, ?)",
+                    (migration.version, migration.description, Utc::now()),
+                )
+                .await?;
+            report.applied.push(migration.version);
+        }
+
+        // Shard tables are created by `reshard` with the columns of the time
+        for statement in MIGRATIONS.iter().flat_map(|migration| statements(migration.cql)) {
+            if let Some(("event_store", column, cql_type)) = added_column(&statement) {
+                for table in self.event_tables.iter().filter(|table| *table != "event_store") {
+                    add_column_if_missing(self.session, keyspace, table, column, &cql_type).await?;
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Make sure every tenant outbox exists and streams CDC, so no relay
+    /// starts on a table that is missing or silent
+    ///
+    /// Keyspace tenants got theirs from the migrations; prefixed tables are
+    /// not created here, a missing one fails the run.
+    async fn enable_tenant_outboxes(&self, report: &mut MigrationReport) -> Result<()> {
+        for (tenant, outbox) in &self.tenant_outboxes {
+            let tenant_id = tenant.tenant_id().unwrap_or_default();
+            if !table_exists(self.session, &outbox.keyspace, &outbox.table).await? {
+                match tenant.scope() {
+                    TenantScope::TablePrefix(prefix) => bail!(
+                        "Outbox {} of tenant '{}' does not exist: migrate does not create prefixed tables - \
+                         create the tenant's tables (schema.cql with the '{}_' prefix) or give it a keyspace",
+                        outbox.label(), tenant_id, prefix
+                    ),
+                    _ => bail!(
+                        "Outbox {} of tenant '{}' does not exist after migrating {} - check cdc.outbox_table",
+                        outbox.label(), tenant_id, outbox.keyspace
+                    ),
+                }
+            }
+            self.session
+                .query_unpaged(format!("ALTER TABLE {} WITH cdc = {}", outbox.label(), OUTBOX_CDC), &[])
+                .await
+                .with_context(|| format!("Failed to enable CDC on outbox {} of tenant '{}'", outbox.label(), tenant_id))?;
+            report.cdc_enabled.push(outbox.label());
+        }
+        Ok(())
+    }
+
+    async fn applied_versions(&self) -> Result<HashSet<i32>> {
+        let rows = self
+            .session
+            .query_unpaged("SELECT version FROM schema_migrations", &[])
+            .await?
+            .into_rows_result()?;
+        Ok(rows.rows::<(i32,)>()?.map(|row| row.map(|(version,)| version)).collect::<std::result::Result<_, _>>()?)
+    }
+}
+
+async fn table_exists(session: &Session, keyspace: &str, table: &str) -> Result<bool> {
+    let rows = session
+        .query_unpaged(
+            "SELECT table_name FROM system_schema.tables WHERE keyspace_name = ? AND table_name = ?",
+            (keyspace, table),
+        )
+        .await?
+        .into_rows_result()?;
+    Ok(rows.rows_num() > 0)
+}
+
+/// Add `column` to `keyspace.table` unless it has it; true when added
+pub async fn add_column_if_missing(session: &Session, keyspace: &str, table: &str, column: &str, cql_type: &str) -> Result<bool> {
+    let existing = session
+        .query_unpaged(
+            "SELECT column_name FROM system_schema.columns WHERE keyspace_name = ? AND table_name = ? AND column_name = ?",
+            (keyspace, table, column),
+        )
+        .await?
+        .into_rows_result()?
+        .rows_num();
+    if existing > 0 {
+        return Ok(false);
+    }
+    session
+        .query_unpaged(format!("ALTER TABLE {}.{} ADD {} {}", keyspace, table, column, cql_type), &[])
+        .await
+        .with_context(|| format!("Failed to add {} to {}.{}", column, keyspace, table))?;
+    Ok(true)
+}
+
+/// Table, column and type of an `ALTER TABLE <table> ADD <column> <type>`
+fn added_column(statement: &str) -> Option<(&str, &str, String)> {
+    let mut words = statement.split_whitespace();
+    let alter = [words.next()?, words.next()?];
+    if !alter[0].eq_ignore_ascii_case("ALTER") || !alter[1].eq_ignore_ascii_case("TABLE") {
+        return None;
+    }
+    let table = words.next()?;
+    if !words.next()?.eq_ignore_ascii_case("ADD") {
+        return None;
+    }
+    let column = words.next()?;
+    // The type may contain spaces (MAP<TEXT, TEXT>)
+    let cql_type = words.collect::<Vec<_>>().join(" ");
+    (!cql_type.is_empty()).then_some((table, column, cql_type))
+}
+
+/// The statements of a CQL script, without comments, CREATE KEYSPACE and USE
+fn statements(cql: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    for line in cql.lines() {
+        let line = strip_comment(line);
+        // Statements end at a `;` outside of string literals
+        let mut in_string = false;
+        for c in line.chars() {
+            match c {
+                '\'' => {
+                    in_string = !in_string;
+                    current.push(c);
+                }
+                ';' if !in_string => statements.push(std::mem::take(&mut current)),
+                _ => current.push(c),
+            }
+        }
+        current.push('\n');
+    }
+    statements.push(current);
+
+    statements
+        .into_iter()
+        .map(|statement| statement.trim().to_string())
+        .filter(|statement| {
+            let upper = statement.to_ascii_uppercase();
+            !statement.is_empty() && !upper.starts_with("CREATE KEYSPACE") && !upper.starts_with("USE ")
+        })
+        .collect()
+}
+
+/// `line` up to a `--` comment outside of string literals
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let bytes = line.as_bytes();
+    for (i, byte) in bytes.iter().enumerate() {
+        match byte {
+            b'\'' => in_string = !in_string,
+            b'-' if !in_string && bytes.get(i + 1) == Some(&b'-') => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_statements_skip_comments_and_keyspace() {
+        let cql = "
+            -- Keyspace
+            CREATE KEYSPACE IF NOT EXISTS orders_ks WITH replication = {'class': 'SimpleStrategy'};
+            USE orders_ks;
+
+            CREATE TABLE IF NOT EXISTS t (
+                id UUID PRIMARY KEY,   -- the key
+                note TEXT
+            ) WITH comment = 'a; b -- c';
+            CREATE INDEX IF NOT EXISTS idx_note ON t (note);
+        ";
+        let statements = statements(cql);
+        assert_eq!(statements.len(), 2);
+        assert!(statements[0].starts_with("CREATE TABLE IF NOT EXISTS t ("));
+        assert!(statements[0].ends_with("WITH comment = 'a; b -- c'"));
+        assert!(!statements[0].contains("the key"));
+        assert_eq!(statements[1], "CREATE INDEX IF NOT EXISTS idx_note ON t (note)");
+    }
+
+    #[test]
+    fn test_baseline_creates_core_tables() {
+        assert!(MIGRATIONS.windows(2).all(|pair| pair[0].version < pair[1].version));
+
+        let baseline = statements(MIGRATIONS[0].cql);
+        for table in ["event_store", "outbox_messages", "aggregate_sequence", "dead_letter_queue", "cdc_offsets", "order_read_model"] {
+            let create = format!("CREATE TABLE IF NOT EXISTS {} (", table);
+            assert!(baseline.iter().any(|s| s.starts_with(&create)), "missing {}", table);
+        }
+        assert!(baseline.iter().any(|s| s.starts_with("CREATE TABLE IF NOT EXISTS outbox_messages") && s.contains("cdc =")));
+        // Tenant outboxes get CDC as the baseline creates it
+        let outbox = baseline.iter().find(|s| s.starts_with("CREATE TABLE IF NOT EXISTS outbox_messages")).unwrap();
+        assert!(outbox.contains(&format!("WITH cdc = {}", OUTBOX_CDC)), "{}", outbox);
+        assert!(baseline.iter().all(|s| !s.to_ascii_uppercase().starts_with("USE ")));
+    }
+
+    #[test]
+    fn test_added_columns_are_recognised() {
+        let audit = statements(MIGRATIONS[2].cql);
+        assert_eq!(added_column(&audit[0]), Some(("event_store", "user_id", "UUID".to_string())));
+        assert_eq!(added_column(&audit[1]), Some(("event_store", "metadata", "MAP<TEXT, TEXT>".to_string())));
+        assert_eq!(added_column("ALTER TABLE t WITH cdc = {'enabled': true}"), None);
+        assert_eq!(added_column("CREATE TABLE IF NOT EXISTS t (id UUID PRIMARY KEY)"), None);
+
+        // Fresh keyspaces get the columns from the baseline
+        let baseline = statements(MIGRATIONS[0].cql);
+        let event_store = baseline.iter().find(|s| s.starts_with("CREATE TABLE IF NOT EXISTS event_store (")).unwrap();
+        assert!(event_store.contains("user_id") && event_store.contains("metadata"));
+    }
+}
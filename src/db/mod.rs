@@ -0,0 +1,38 @@
+// ============================================================================
+// Database Module - ScyllaDB Schema and Runtime Checks
+// ============================================================================
+//
+// - schema.cql      - Keyspace and table definitions
+// - migrations      - Versioned bootstrap of keyspace and tables (`--migrate`)
+// - keyspace_check  - Startup verification of replication/consistency
+// - partition_advisor - Oversized partition / wide row diagnostics
+// - integrity_check - Outbox ↔ event_store orphan scan and repair
+// - latency         - Client-side query latency/error window
+// - statements      - Prepared statement cache for hot queries
+// - consistency     - Consistency level per kind of operation
+//
+// ============================================================================
+
+mod consistency;
+mod integrity_check;
+mod keyspace_check;
+mod latency;
+mod migrations;
+mod partition_advisor;
+mod statements;
+
+pub use consistency::{ConsistencyConfig, ConsistencyLevel, Operation, SerialConsistencyLevel};
+pub use integrity_check::{
+    cross_check, EventRef, IntegrityCandidates, IntegrityCheckConfig, IntegrityChecker, IntegrityReport,
+    OutboxRef, ReemitRoute, RepairSummary,
+};
+pub use keyspace_check::{
+    check_keyspace, evaluate, KeyspaceExpectations, KeyspaceReport, ReplicationSettings,
+};
+pub use latency::{LatencyMonitor, LatencySnapshot};
+pub use migrations::{add_column_if_missing, Migration, MigrationReport, Migrator, MIGRATIONS};
+pub use partition_advisor::{
+    advise, collect_observations, run_partition_advisor, AdvisedAction, AdvisorReport,
+    AdvisorThresholds, Finding, PartitionObservations, Severity,
+};
+pub use statements::StatementCache;
@@ -0,0 +1,426 @@
+use scylla::client::session::Session;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+use anyhow::Result;
+
+// ============================================================================
+// Partition Advisor - Oversized Partitions and Wide Rows
+// ============================================================================
+//
+// event_store keeps every event of an aggregate in one partition and is
+// never deleted from. Long-lived aggregates therefore grow without bound,
+// which hurts reads, repairs and compaction long before anything fails.
+//
+// The advisor collects (cheaply, from system tables where possible):
+// 1. system.size_estimates       - partition count / mean size per table
+// 2. system.large_partitions     - partitions Scylla flagged at compaction
+// 3. aggregate_sequence          - event count per aggregate (= rows in its
+//                                  event_store partition)
+// 4. aggregate_snapshots         - latest snapshot of each wide aggregate
+//
+// and turns them into findings with a recommended action:
+// - Snapshot  - many events replayed since the last snapshot
+// - Archive   - the partition itself is too large; move old events out
+// - Bucket    - the table's partitions are large on average; add a time
+//               or sequence bucket to the partition key
+// - Expire    - outbox partitions should be tiny; rows are not expiring
+//
+// The report is JSON (`serde_json::to_string_pretty`) for tooling.
+//
+// ============================================================================
+
+const ADVISED_TABLES: [&str; 2] = ["event_store", "outbox_messages"];
+
+/// Limits above which a partition or aggregate is flagged
+#[derive(Debug, Clone, Serialize)]
+pub struct AdvisorThresholds {
+    /// Partition size flagged as oversized (Scylla warns at 1000 MB)
+    pub max_partition_bytes: i64,
+    /// Rows in one partition flagged as a wide row (Scylla warns at 100k)
+    pub max_partition_rows: i64,
+    /// Events replayed after the latest snapshot before snapshotting is advised
+    pub max_events_since_snapshot: i64,
+    /// Mean partition size above which bucketing is advised
+    pub max_mean_partition_bytes: i64,
+}
+
+impl Default for AdvisorThresholds {
+    fn default() -> Self {
+        Self {
+            max_partition_bytes: 100 * 1024 * 1024,
+            max_partition_rows: 100_000,
+            max_events_since_snapshot: 1_000,
+            max_mean_partition_bytes: 10 * 1024 * 1024,
+        }
+    }
+}
+
+/// Aggregated size estimate of one table
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TableEstimate {
+    pub table: String,
+    pub partitions: i64,
+    pub mean_partition_bytes: i64,
+}
+
+/// A partition recorded in system.large_partitions
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LargePartition {
+    pub table: String,
+    pub partition_key: String,
+    pub size_bytes: i64,
+    pub rows: i64,
+}
+
+/// Event count of one aggregate and its latest snapshot
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AggregateSize {
+    pub aggregate_id: Uuid,
+    pub events: i64,
+    pub latest_snapshot: Option<i64>,
+}
+
+/// Everything the advisor looks at
+#[derive(Debug, Clone, Default)]
+pub struct PartitionObservations {
+    pub tables: Vec<TableEstimate>,
+    pub large_partitions: Vec<LargePartition>,
+    pub aggregates: Vec<AggregateSize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdvisedAction {
+    Snapshot,
+    Archive,
+    Bucket,
+    Expire,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,
+    Warning,
+    Critical,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Finding {
+    pub severity: Severity,
+    pub table: String,
+    /// Partition key / aggregate id, or None for table-wide findings
+    pub subject: Option<String>,
+    pub action: AdvisedAction,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AdvisorReport {
+    pub keyspace: String,
+    pub generated_at: chrono::DateTime<chrono::Utc>,
+    pub thresholds: AdvisorThresholds,
+    pub tables: Vec<TableEstimate>,
+    pub findings: Vec<Finding>,
+}
+
+impl AdvisorReport {
+    pub fn has_critical(&self) -> bool {
+        self.findings.iter().any(|f| f.severity == Severity::Critical)
+    }
+}
+
+/// Turn observations into findings (most severe first)
+pub fn advise(keyspace: &str, thresholds: &AdvisorThresholds, observations: PartitionObservations) -> AdvisorReport {
+    let mut findings = Vec::new();
+
+    for table in &observations.tables {
+        if table.mean_partition_bytes <= thresholds.max_mean_partition_bytes {
+            continue;
+        }
+        let (action, detail) = if table.table == "outbox_messages" {
+            (
+                AdvisedAction::Expire,
+                format!(
+                    "Mean partition {} bytes - outbox rows should be small and short-lived; check TTL and cleanup",
+                    table.mean_partition_bytes
+                ),
+            )
+        } else {
+            (
+                AdvisedAction::Bucket,
+                format!(
+                    "Mean partition {} bytes over {} partitions - add a bucket (e.g. sequence_number / 10000) to the partition key",
+                    table.mean_partition_bytes, table.partitions
+                ),
+            )
+        };
+        findings.push(Finding {
+            severity: Severity::Warning,
+            table: table.table.clone(),
+            subject: None,
+            action,
+            detail,
+        });
+    }
+
+    for partition in &observations.large_partitions {
+        let oversized = partition.size_bytes > thresholds.max_partition_bytes;
+        let wide = partition.rows > thresholds.max_partition_rows;
+        if !oversized && !wide {
+            continue;
+        }
+        findings.push(Finding {
+            severity: if oversized { Severity::Critical } else { Severity::Warning },
+            table: partition.table.clone(),
+            subject: Some(partition.partition_key.clone()),
+            action: if partition.table == "outbox_messages" { AdvisedAction::Expire } else { AdvisedAction::Archive },
+            detail: format!(
+                "Partition of {} bytes / {} rows - archive events covered by a snapshot to cold storage",
+                partition.size_bytes, partition.rows
+            ),
+        });
+    }
+
+    for aggregate in &observations.aggregates {
+        if aggregate.events > thresholds.max_partition_rows {
+            findings.push(Finding {
+                severity: Severity::Warning,
+                table: "event_store".to_string(),
+                subject: Some(aggregate.aggregate_id.to_string()),
+                action: AdvisedAction::Archive,
+                detail: format!("{} events in one partition (wide row)", aggregate.events),
+            });
+        }
+
+        let since_snapshot = aggregate.events - aggregate.latest_snapshot.unwrap_or(0);
+        if since_snapshot > thresholds.max_events_since_snapshot {
+            findings.push(Finding {
+                severity: Severity::Info,
+                table: "event_store".to_string(),
+                subject: Some(aggregate.aggregate_id.to_string()),
+                action: AdvisedAction::Snapshot,
+                detail: match aggregate.latest_snapshot {
+                    Some(at) => format!("{} events replayed since snapshot at {}", since_snapshot, at),
+                    None => format!("{} events and no snapshot", aggregate.events),
+                },
+            });
+        }
+    }
+
+    findings.sort_by(|a, b| b.severity.partial_cmp(&a.severity).unwrap_or(std::cmp::Ordering::Equal));
+
+    AdvisorReport {
+        keyspace: keyspace.to_string(),
+        generated_at: chrono::Utc::now(),
+        thresholds: thresholds.clone(),
+        tables: observations.tables,
+        findings,
+    }
+}
+
+/// Read partition statistics from Scylla
+///
+/// Missing system tables (e.g. large_partitions on Cassandra) are skipped
+/// with a warning rather than failing the whole report.
+pub async fn collect_observations(
+    session: &Session,
+    keyspace: &str,
+    thresholds: &AdvisorThresholds,
+) -> Result<PartitionObservations> {
+    let mut observations = PartitionObservations::default();
+
+    match table_estimates(session, keyspace).await {
+        Ok(tables) => observations.tables = tables,
+        Err(e) => tracing::warn!(error = %e, "system.size_estimates unavailable"),
+    }
+
+    for table in ADVISED_TABLES {
+        match large_partitions(session, keyspace, table).await {
+            Ok(mut partitions) => observations.large_partitions.append(&mut partitions),
+            Err(e) => tracing::warn!(table = table, error = %e, "system.large_partitions unavailable"),
+        }
+    }
+
+    let result = session
+        .query_unpaged(
+            format!("SELECT aggregate_id, current_sequence FROM {}.aggregate_sequence", keyspace),
+            &[],
+        )
+        .await?;
+
+    // Only wide aggregates need a snapshot lookup
+    let threshold = thresholds.max_events_since_snapshot.min(thresholds.max_partition_rows);
+    for row in result.into_rows_result()?.rows::<(Uuid, i64)>()? {
+        let (aggregate_id, events) = row?;
+        if events <= threshold {
+            continue;
+        }
+
+        let latest_snapshot = session
+            .query_unpaged(
+                format!(
+                    "SELECT sequence_number FROM {}.aggregate_snapshots WHERE aggregate_id = ? LIMIT 1",
+                    keyspace
+                ),
+                (aggregate_id,),
+            )
+            .await?
+            .into_rows_result()?
+            .maybe_first_row::<(i64,)>()?
+            .map(|(sequence,)| sequence);
+
+        observations.aggregates.push(AggregateSize { aggregate_id, events, latest_snapshot });
+    }
+
+    Ok(observations)
+}
+
+async fn table_estimates(session: &Session, keyspace: &str) -> Result<Vec<TableEstimate>> {
+    let result = session
+        .query_unpaged(
+            "SELECT table_name, partitions_count, mean_partition_size FROM system.size_estimates WHERE keyspace_name = ?",
+            (keyspace,),
+        )
+        .await?;
+
+    // One row per token range - sum partitions, weight the mean by them
+    let mut totals: BTreeMap<String, (i64, i128)> = BTreeMap::new();
+    for row in result.into_rows_result()?.rows::<(String, i64, i64)>()? {
+        let (table, partitions, mean_size) = row?;
+        let entry = totals.entry(table).or_default();
+        entry.0 += partitions;
+        entry.1 += partitions as i128 * mean_size as i128;
+    }
+
+    Ok(totals
+        .into_iter()
+        .filter(|(table, _)| ADVISED_TABLES.contains(&table.as_str()))
+        .map(|(table, (partitions, bytes))| TableEstimate {
+            table,
+            partitions,
+            mean_partition_bytes: if partitions > 0 { (bytes / partitions as i128) as i64 } else { 0 },
+        })
+        .collect())
+}
+
+async fn large_partitions(session: &Session, keyspace: &str, table: &str) -> Result<Vec<LargePartition>> {
+    let result = session
+        .query_unpaged(
+            "SELECT partition_key, partition_size, rows FROM system.large_partitions
+             WHERE keyspace_name = ? AND table_name = ?",
+            (keyspace, table),
+        )
+        .await?;
+
+    let mut partitions = Vec::new();
+    for row in result.into_rows_result()?.rows::<(String, i64, Option<i64>)>()? {
+        let (partition_key, size_bytes, rows) = row?;
+        partitions.push(LargePartition {
+            table: table.to_string(),
+            partition_key,
+            size_bytes,
+            rows: rows.unwrap_or(0),
+        });
+    }
+    Ok(partitions)
+}
+
+/// Collect, advise and log a summary
+pub async fn run_partition_advisor(
+    session: &Session,
+    keyspace: &str,
+    thresholds: AdvisorThresholds,
+) -> Result<AdvisorReport> {
+    let observations = collect_observations(session, keyspace, &thresholds).await?;
+    let report = advise(keyspace, &thresholds, observations);
+
+    tracing::info!(
+        keyspace = %keyspace,
+        findings = report.findings.len(),
+        critical = report.has_critical(),
+        "🔎 Partition advisor finished"
+    );
+
+    Ok(report)
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aggregate(events: i64, latest_snapshot: Option<i64>) -> AggregateSize {
+        AggregateSize { aggregate_id: Uuid::new_v4(), events, latest_snapshot }
+    }
+
+    #[test]
+    fn test_snapshot_advised_when_far_behind() {
+        let thresholds = AdvisorThresholds::default();
+        let observations = PartitionObservations {
+            aggregates: vec![aggregate(5_000, Some(4_500)), aggregate(5_000, Some(1_000)), aggregate(2_000, None)],
+            ..Default::default()
+        };
+
+        let report = advise("orders_ks", &thresholds, observations);
+        let snapshots: Vec<_> = report.findings.iter().filter(|f| f.action == AdvisedAction::Snapshot).collect();
+        assert_eq!(snapshots.len(), 2);
+    }
+
+    #[test]
+    fn test_large_partition_is_critical_and_sorted_first() {
+        let thresholds = AdvisorThresholds::default();
+        let observations = PartitionObservations {
+            tables: vec![TableEstimate {
+                table: "event_store".to_string(),
+                partitions: 10,
+                mean_partition_bytes: 50 * 1024 * 1024,
+            }],
+            large_partitions: vec![LargePartition {
+                table: "event_store".to_string(),
+                partition_key: "3f2504e0-4f89-11d3-9a0c-0305e82c3301".to_string(),
+                size_bytes: 500 * 1024 * 1024,
+                rows: 2_000,
+            }],
+            aggregates: vec![],
+        };
+
+        let report = advise("orders_ks", &thresholds, observations);
+        assert!(report.has_critical());
+        assert_eq!(report.findings[0].action, AdvisedAction::Archive);
+        assert_eq!(report.findings[1].action, AdvisedAction::Bucket);
+    }
+
+    #[test]
+    fn test_outbox_gets_expire_advice() {
+        let thresholds = AdvisorThresholds::default();
+        let observations = PartitionObservations {
+            tables: vec![TableEstimate {
+                table: "outbox_messages".to_string(),
+                partitions: 1_000,
+                mean_partition_bytes: 20 * 1024 * 1024,
+            }],
+            ..Default::default()
+        };
+
+        let report = advise("orders_ks", &thresholds, observations);
+        assert_eq!(report.findings[0].action, AdvisedAction::Expire);
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["findings"][0]["action"], "expire");
+    }
+
+    #[test]
+    fn test_healthy_keyspace_has_no_findings() {
+        let observations = PartitionObservations {
+            tables: vec![TableEstimate { table: "event_store".to_string(), partitions: 100, mean_partition_bytes: 4096 }],
+            aggregates: vec![aggregate(50, None)],
+            ..Default::default()
+        };
+        assert!(advise("orders_ks", &AdvisorThresholds::default(), observations).findings.is_empty());
+    }
+}
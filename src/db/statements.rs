@@ -0,0 +1,198 @@
+use anyhow::{Context, Result};
+use scylla::client::session::Session;
+use scylla::response::query_result::QueryResult;
+use scylla::serialize::row::SerializeRow;
+use scylla::statement::batch::{Batch, BatchType};
+use scylla::statement::prepared::PreparedStatement;
+use scylla::statement::{Consistency, SerialConsistency};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+
+use super::consistency::{ConsistencyConfig, ConsistencyLevel, Operation, SerialConsistencyLevel};
+
+// ============================================================================
+// Statement Cache - Prepared Statements on First Use
+// ============================================================================
+//
+// An unprepared query is parsed by the coordinator on every execution, and
+// the driver cannot compute its partition token, so it goes to an arbitrary
+// node instead of a replica. Batches are worse: the driver prepares each of
+// their unprepared statements again on every execution.
+//
+// The StatementCache prepares a CQL text the first time it is used and
+// hands out the prepared statement (a cheap clone) from then on. Hot paths
+// - event and outbox appends, aggregate_sequence LWTs, DLQ writes and CDC
+// offsets - run through it; their batches are built from prepared
+// statements. Prepared statements are token-aware, so appends and LWTs are
+// sent to a replica of their partition.
+//
+// Statements are cached by their exact text: queries with a table name
+// filled in (sharded event tables) get one entry per table. Two tasks
+// missing the cache at once both prepare; preparing twice is harmless.
+//
+// Statements and batches are handed out for an Operation and run at the
+// consistency configured for it (see consistency.rs); the serial
+// consistency applies to the LWTs among them.
+//
+// ============================================================================
+
+/// Prepared statements by CQL text, prepared on first use
+pub struct StatementCache {
+    session: Arc<Session>,
+    consistency: ConsistencyConfig,
+    prepared: PreparedMap<PreparedStatement>,
+}
+
+impl StatementCache {
+    pub fn new(session: Arc<Session>) -> Self {
+        Self { session, consistency: ConsistencyConfig::default(), prepared: PreparedMap::default() }
+    }
+
+    /// Run statements at the levels of `consistency`
+    pub fn with_consistency(mut self, consistency: ConsistencyConfig) -> Self {
+        self.consistency = consistency;
+        self
+    }
+
+    pub fn session(&self) -> &Arc<Session> {
+        &self.session
+    }
+
+    /// The prepared statement for `cql`, preparing it on first use
+    pub async fn prepared(&self, cql: &str) -> Result<PreparedStatement> {
+        self.prepared
+            .get_or_prepare(cql, || async {
+                let prepared = self
+                    .session
+                    .prepare(cql)
+                    .await
+                    .with_context(|| format!("Failed to prepare: {}", cql))?;
+                tracing::debug!(cql = %cql, "Prepared statement");
+                Ok(prepared)
+            })
+            .await
+    }
+
+    /// The prepared statement for `cql`, at the consistency of `operation`
+    pub async fn statement(&self, operation: Operation, cql: &str) -> Result<PreparedStatement> {
+        let mut statement = self.prepared(cql).await?;
+        statement.set_consistency(self.consistency.level(operation).into());
+        statement.set_serial_consistency(Some(self.consistency.serial.into()));
+        Ok(statement)
+    }
+
+    /// An empty batch at the consistency of `operation`
+    pub fn batch(&self, operation: Operation, batch_type: BatchType) -> Batch {
+        let mut batch = Batch::new(batch_type);
+        batch.set_consistency(self.consistency.level(operation).into());
+        batch.set_serial_consistency(Some(self.consistency.serial.into()));
+        batch
+    }
+
+    /// Execute `cql` as a prepared statement of `operation`, unpaged
+    pub async fn execute(&self, operation: Operation, cql: &str, values: impl SerializeRow) -> Result<QueryResult> {
+        let statement = self.statement(operation, cql).await?;
+        Ok(self.session.execute_unpaged(&statement, values).await?)
+    }
+
+    /// Statements prepared so far
+    pub fn len(&self) -> usize {
+        self.prepared.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl From<ConsistencyLevel> for Consistency {
+    fn from(level: ConsistencyLevel) -> Self {
+        match level {
+            ConsistencyLevel::Any => Consistency::Any,
+            ConsistencyLevel::One => Consistency::One,
+            ConsistencyLevel::Two => Consistency::Two,
+            ConsistencyLevel::Three => Consistency::Three,
+            ConsistencyLevel::Quorum => Consistency::Quorum,
+            ConsistencyLevel::All => Consistency::All,
+            ConsistencyLevel::LocalQuorum => Consistency::LocalQuorum,
+            ConsistencyLevel::EachQuorum => Consistency::EachQuorum,
+            ConsistencyLevel::LocalOne => Consistency::LocalOne,
+        }
+    }
+}
+
+impl From<SerialConsistencyLevel> for SerialConsistency {
+    fn from(level: SerialConsistencyLevel) -> Self {
+        match level {
+            SerialConsistencyLevel::Serial => SerialConsistency::Serial,
+            SerialConsistencyLevel::LocalSerial => SerialConsistency::LocalSerial,
+        }
+    }
+}
+
+/// Values by statement text, filled in by a preparing function
+struct PreparedMap<T> {
+    entries: RwLock<HashMap<String, T>>,
+}
+
+impl<T> Default for PreparedMap<T> {
+    fn default() -> Self {
+        Self { entries: RwLock::new(HashMap::new()) }
+    }
+}
+
+impl<T: Clone> PreparedMap<T> {
+    /// The cached value for `cql`, or the one `prepare` returns (cached if Ok)
+    async fn get_or_prepare<F, Fut>(&self, cql: &str, prepare: F) -> Result<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        if let Some(prepared) = self.entries.read().unwrap().get(cql) {
+            return Ok(prepared.clone());
+        }
+        let prepared = prepare().await?;
+        self.entries.write().unwrap().insert(cql.to_string(), prepared.clone());
+        Ok(prepared)
+    }
+
+    fn len(&self) -> usize {
+        self.entries.read().unwrap().len()
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_prepares_each_statement_once() {
+        let map = PreparedMap::default();
+        let prepares = AtomicUsize::new(0);
+        let prepare = |cql: &'static str| {
+            let prepares = &prepares;
+            move || async move {
+                prepares.fetch_add(1, Ordering::SeqCst);
+                Ok(cql.len())
+            }
+        };
+
+        for _ in 0..3 {
+            assert_eq!(map.get_or_prepare("SELECT a FROM t", prepare("SELECT a FROM t")).await.unwrap(), 15);
+        }
+        map.get_or_prepare("SELECT b FROM t", prepare("SELECT b FROM t")).await.unwrap();
+        assert_eq!(prepares.load(Ordering::SeqCst), 2);
+        assert_eq!(map.len(), 2);
+
+        // A failed prepare is not cached
+        let failed = map.get_or_prepare("SELEC", || async { Err(anyhow::anyhow!("syntax error")) }).await;
+        assert!(failed.is_err());
+        assert_eq!(map.len(), 2);
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::OwnedMutexGuard;
+use uuid::Uuid;
+
+// ============================================================================
+// Aggregate Locks - One Command per Aggregate at a Time, In-Process
+// ============================================================================
+//
+// Two commands on the same aggregate handled concurrently load the same
+// version, and one of them loses the append with a ConcurrencyConflict
+// (then reloads and retries). Within one process that is avoidable: the
+// CommandHandler takes the aggregate's lock before loading it, so commands
+// on one aggregate queue up and each sees the previous one's events.
+//
+// Commands on different aggregates never wait for each other. A lock lives
+// only while a command holds or waits for it; idle aggregates cost nothing.
+// Waiting is bounded by the command's deadline.
+//
+// Conflicts remain possible between instances (and with other writers of
+// the event store); the optimistic concurrency check and the conflict retry
+// still cover those.
+//
+// ============================================================================
+
+type Locks = Arc<Mutex<HashMap<Uuid, Arc<tokio::sync::Mutex<()>>>>>;
+
+/// Async mutex per aggregate id
+#[derive(Default)]
+pub struct AggregateLocks {
+    locks: Locks,
+}
+
+impl AggregateLocks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wait until no other command holds `aggregate_id`, then hold it
+    pub async fn lock(&self, aggregate_id: Uuid) -> AggregateLock {
+        // Created first (dropped last), so a wait cancelled by the deadline
+        // still removes a lock nobody else uses
+        let mut held = AggregateLock { aggregate_id, guard: None, locks: self.locks.clone() };
+        let lock = self.locks.lock().unwrap().entry(aggregate_id).or_default().clone();
+        held.guard = Some(lock.lock_owned().await);
+        held
+    }
+
+    /// Aggregates with a command holding or waiting for their lock
+    pub fn len(&self) -> usize {
+        self.locks.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Held lock of one aggregate, released on drop
+pub struct AggregateLock {
+    aggregate_id: Uuid,
+    guard: Option<OwnedMutexGuard<()>>,
+    locks: Locks,
+}
+
+impl Drop for AggregateLock {
+    fn drop(&mut self) {
+        let Ok(mut locks) = self.locks.lock() else {
+            return;
+        };
+        self.guard.take();
+        // Only the map still refers to it: nobody is waiting
+        if locks.get(&self.aggregate_id).is_some_and(|lock| Arc::strong_count(lock) == 1) {
+            locks.remove(&self.aggregate_id);
+        }
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_commands_on_one_aggregate_take_turns() {
+        let locks = Arc::new(AggregateLocks::new());
+        let (order, other) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let held = locks.lock(order).await;
+        // Another aggregate is not blocked
+        drop(locks.lock(other).await);
+
+        let waiter = tokio::spawn({
+            let locks = locks.clone();
+            async move { drop(locks.lock(order).await) }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+        assert_eq!(locks.len(), 1);
+
+        drop(held);
+        tokio::time::timeout(Duration::from_secs(1), waiter).await.unwrap().unwrap();
+        assert!(locks.is_empty());
+    }
+}
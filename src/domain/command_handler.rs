@@ -0,0 +1,252 @@
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+use anyhow::{Result, bail};
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::Instrument;
+
+use crate::event_sourcing::with_deadline;
+use crate::event_sourcing::{AggregateRoot, CommandContext, ConcurrencyConflict, Deadline, DomainEvent, EventEnvelope, EventStorage, EventStore};
+use crate::messaging::StateSnapshotPublisher;
+use super::aggregate_locks::AggregateLocks;
+
+// ============================================================================
+// Generic Command Handler - Shared by All Aggregates
+// ============================================================================
+//
+// Orchestrates: Command → Aggregate → Events → Event Store
+//
+// Aggregates plug in through CommandAggregate (the state a creating command
+// is validated against); stored event types come from
+// `DomainEvent::event_type_name`. Domain handlers wrap a
+// CommandHandler<A> and add their own steps through `handle_with`, e.g.
+// order pricing.
+//
+// Serialization: commands on the same aggregate take turns on its
+// AggregateLocks entry (waiting at most until the context's deadline), so
+// within one handler they never conflict with each other.
+//
+// Conflict retry: two commands on the same aggregate handled by different
+// instances (or written past this handler) load the same version and one
+// of them loses the append with ConcurrencyConflict. Instead of failing,
+// the handler reloads the aggregate, decides the command again against the
+// new state and re-appends, up to `conflict_retries` times with a short,
+// growing backoff. Deciding again matters: the command may no
+// longer be valid (e.g. the order was cancelled meanwhile) and then fails
+// with the domain error. Retries stop early when the context's deadline
+// would pass during the backoff; the conflict is returned then.
+//
+// Audit: every appended event carries the user, causation id and metadata
+// of the CommandContext the command was handled under (see
+// `CommandContext::stamp`), and the event store persists them.
+//
+// Duplicate commands: a context with a command_id is checked against the
+// storage's record of processed commands first (every attempt, so a retry
+// after losing a conflict to its own twin sees the twin's append). A known
+// id returns the version its first run produced; nothing is appended.
+//
+// With a StateSnapshotPublisher attached, appends crossing its interval
+// publish the full aggregate state to the state topic.
+//
+// Tracing: every command runs in a `command` span carrying aggregate_id and
+// correlation_id. The EventStore append, and later the CDC relay and the
+// Redpanda publish of the same events, open spans with the same ids, so one
+// correlation id can be followed from the API to the topic.
+//
+// Storage: `new` runs on the Scylla EventStore, including its aggregate
+// snapshots; `from_storage` runs on any EventStorage backend (e.g.
+// InMemoryEventStorage in tests) and always replays the full history.
+//
+// ============================================================================
+
+/// Retries after a conflict unless configured otherwise
+pub const DEFAULT_CONFLICT_RETRIES: u32 = 3;
+
+/// Backoff before the first retry; doubled for each further one
+const CONFLICT_BACKOFF: Duration = Duration::from_millis(10);
+
+/// What the generic handler needs to know about an aggregate
+pub trait CommandAggregate: AggregateRoot + Serialize + DeserializeOwned + 'static
+where
+    Self::Event: DomainEvent,
+{
+    /// State to validate `command` against when the aggregate does not exist
+    /// yet; None when the command cannot create the aggregate
+    fn initial_state(command: &Self::Command) -> Result<Option<Self>, Self::Error>;
+}
+
+pub struct CommandHandler<A: CommandAggregate>
+where
+    A::Event: DomainEvent,
+{
+    storage: Arc<dyn EventStorage<A::Event>>,
+    /// Set when `storage` is the Scylla store (aggregate snapshots)
+    event_store: Option<Arc<EventStore<A::Event>>>,
+    state_snapshots: Option<Arc<StateSnapshotPublisher<A>>>,
+    conflict_retries: u32,
+    locks: AggregateLocks,
+}
+
+impl<A> CommandHandler<A>
+where
+    A: CommandAggregate,
+    A::Event: DomainEvent,
+    A::Error: std::error::Error + Send + Sync + 'static,
+{
+    pub fn new(event_store: Arc<EventStore<A::Event>>) -> Self {
+        Self {
+            storage: event_store.clone(),
+            event_store: Some(event_store),
+            state_snapshots: None,
+            conflict_retries: DEFAULT_CONFLICT_RETRIES,
+            locks: AggregateLocks::new(),
+        }
+    }
+
+    /// Handler on another storage backend (no aggregate snapshots)
+    pub fn from_storage(storage: Arc<dyn EventStorage<A::Event>>) -> Self {
+        Self {
+            storage,
+            event_store: None,
+            state_snapshots: None,
+            conflict_retries: DEFAULT_CONFLICT_RETRIES,
+            locks: AggregateLocks::new(),
+        }
+    }
+
+    /// Publish full states periodically (event-carried state transfer)
+    pub fn with_state_snapshots(mut self, publisher: Arc<StateSnapshotPublisher<A>>) -> Self {
+        self.state_snapshots = Some(publisher);
+        self
+    }
+
+    /// Retries after a ConcurrencyConflict (0 = fail on the first conflict)
+    pub fn with_conflict_retries(mut self, retries: u32) -> Self {
+        self.conflict_retries = retries;
+        self
+    }
+
+    /// Handle a command under the caller's context
+    pub async fn handle(&self, aggregate_id: Uuid, command: &A::Command, ctx: &CommandContext) -> Result<i64> {
+        self.handle_with(aggregate_id, command, ctx, |_, events| Ok(events)).await
+    }
+
+    /// Like `handle`, passing the decided events through
+    /// `complete` (with the state they were decided on) before the append
+    ///
+    /// `complete` runs again for every conflict retry.
+    pub async fn handle_with<F>(&self, aggregate_id: Uuid, command: &A::Command, ctx: &CommandContext, mut complete: F) -> Result<i64>
+    where
+        F: FnMut(&A, Vec<A::Event>) -> Result<Vec<A::Event>>,
+    {
+        let span = tracing::info_span!("command", aggregate_id = %aggregate_id, correlation_id = %ctx.correlation_id);
+        async move {
+            // Commands queued behind this one see its events when they load
+            let _lock = with_deadline(ctx.deadline(), "command.aggregate_lock", async {
+                Ok(self.locks.lock(aggregate_id).await)
+            })
+            .await?;
+
+            let mut retries = 0;
+            loop {
+                match self.try_handle(aggregate_id, command, ctx, &mut complete).await {
+                    Err(e) if e.downcast_ref::<ConcurrencyConflict>().is_some() && retries < self.conflict_retries => {
+                        let backoff = CONFLICT_BACKOFF * 2u32.pow(retries);
+                        if ctx.deadline().and_then(|d| d.remaining()).is_some_and(|remaining| remaining <= backoff) {
+                            return Err(e);
+                        }
+                        retries += 1;
+                        tracing::debug!(
+                            aggregate_id = %aggregate_id,
+                            retry = retries,
+                            backoff_ms = backoff.as_millis() as u64,
+                            error = %e,
+                            "Concurrency conflict, retrying command"
+                        );
+                        tokio::time::sleep(backoff).await;
+                    }
+                    result => return result,
+                }
+            }
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// One load → decide → append round
+    async fn try_handle<F>(&self, aggregate_id: Uuid, command: &A::Command, ctx: &CommandContext, complete: &mut F) -> Result<i64>
+    where
+        F: FnMut(&A, Vec<A::Event>) -> Result<Vec<A::Event>>,
+    {
+        let deadline = ctx.deadline();
+
+        // A repeated command gets the result of its first run
+        if let Some(command_id) = ctx.command_id {
+            if let Some(version) = self.storage.command_version(deadline, aggregate_id, command_id).await? {
+                tracing::info!(
+                    aggregate_id = %aggregate_id,
+                    command_id = %command_id,
+                    version = version,
+                    "Duplicate command, returning the version of its first run"
+                );
+                return Ok(version);
+            }
+        }
+
+        // Load current aggregate state
+        let exists = self.storage.exists(deadline, aggregate_id).await?;
+        let (aggregate, expected_version) = if exists {
+            let agg = self.load(deadline, aggregate_id).await?;
+            let ver = agg.version();
+            tracing::debug!("Loaded aggregate {} with version: {}", aggregate_id, ver);
+            (agg, ver)
+        } else {
+            match A::initial_state(command).map_err(anyhow::Error::new)? {
+                Some(agg) => (agg, 0), // Expected version is 0 for new aggregates
+                None => bail!("Aggregate does not exist: {}", aggregate_id),
+            }
+        };
+
+        // Handle command to get events
+        let domain_events = aggregate.handle_command(command)
+            .map_err(anyhow::Error::new)?; // typed, so the API can map domain errors
+        let domain_events = complete(&aggregate, domain_events)?;
+
+        // Wrap in envelopes
+        let envelopes: Vec<_> = domain_events
+            .into_iter()
+            .zip(expected_version + 1..)
+            .map(|(domain_event, seq)| ctx.stamp(EventEnvelope::for_event(aggregate_id, seq, domain_event, ctx.correlation_id)))
+            .collect();
+
+        // Append to event store
+        let new_version = self.storage.append(
+            deadline,
+            aggregate_id,
+            expected_version,
+            envelopes.clone(),
+            true, // publish to outbox
+        ).await?;
+
+        // Snapshot if this append crossed the snapshot frequency
+        if let Some(ref event_store) = self.event_store {
+            event_store
+                .snapshot_after_append(exists.then_some(aggregate), expected_version, &envelopes)
+                .await;
+        }
+
+        if let Some(ref state_snapshots) = self.state_snapshots {
+            state_snapshots.after_append(aggregate_id, expected_version, new_version);
+        }
+
+        Ok(new_version)
+    }
+
+    /// Current state, hydrated from the newest snapshot on the Scylla store
+    async fn load(&self, deadline: Option<&Deadline>, aggregate_id: Uuid) -> Result<A> {
+        match self.event_store {
+            Some(ref event_store) => event_store.load_from_snapshot_within::<A>(deadline, aggregate_id).await,
+            None => A::load_from_events(self.storage.load(deadline, aggregate_id).await?),
+        }
+    }
+}
@@ -1,8 +1,9 @@
 use uuid::Uuid;
 use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
 use anyhow::Result;
 
-use crate::event_sourcing::{AggregateRoot, EventEnvelope};
+use crate::event_sourcing::{AggregateRoot, EventEnvelope, FORGOTTEN_PLACEHOLDER};
 use super::value_objects::{Email, PhoneNumber, Address, CustomerStatus, CustomerTier, PaymentMethod};
 use super::commands::CustomerCommand;
 use super::events::*;
@@ -12,7 +13,7 @@ use super::errors::CustomerError;
 // Customer Aggregate - Business Logic
 // ============================================================================
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CustomerAggregate {
     pub customer_id: Uuid,
     pub version: i64,
@@ -25,6 +26,9 @@ pub struct CustomerAggregate {
     pub addresses: HashMap<Uuid, Address>,
     pub default_address_id: Option<Uuid>,
     pub payment_methods: HashMap<Uuid, PaymentMethod>,
+    /// Personal data erased; no further commands are accepted
+    #[serde(default)]
+    pub forgotten: bool,
 }
 
 impl CustomerAggregate {
@@ -70,6 +74,7 @@ impl AggregateRoot for CustomerAggregate {
                     addresses: HashMap::new(),
                     default_address_id: None,
                     payment_methods: HashMap::new(),
+                    forgotten: false,
                 })
             }
             _ => Err(CustomerError::NotInitialized),
@@ -131,6 +136,19 @@ impl AggregateRoot for CustomerAggregate {
             CustomerEvent::Deactivated(_) => {
                 self.status = CustomerStatus::Deactivated;
             }
+            CustomerEvent::Forgotten(_) => {
+                // Past events read as placeholders once the key is gone; the
+                // state must not keep the data either (snapshots, read model)
+                self.email = Email::new(FORGOTTEN_PLACEHOLDER);
+                self.first_name = FORGOTTEN_PLACEHOLDER.to_string();
+                self.last_name = FORGOTTEN_PLACEHOLDER.to_string();
+                self.phone = None;
+                self.addresses.clear();
+                self.default_address_id = None;
+                self.payment_methods.clear();
+                self.status = CustomerStatus::Deactivated;
+                self.forgotten = true;
+            }
         }
 
         self.version += 1;
@@ -138,6 +156,13 @@ impl AggregateRoot for CustomerAggregate {
     }
 
     fn handle_command(&self, command: &Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        if self.forgotten {
+            return Err(match command {
+                CustomerCommand::ForgetCustomer => CustomerError::AlreadyForgotten,
+                _ => CustomerError::Forgotten,
+            });
+        }
+
         match command {
             CustomerCommand::RegisterCustomer { email, first_name, last_name, phone, .. } => {
                 // Validate
@@ -305,6 +330,8 @@ impl AggregateRoot for CustomerAggregate {
                     reason: reason.clone(),
                 })])
             }
+
+            CustomerCommand::ForgetCustomer => Ok(vec![CustomerEvent::Forgotten(CustomerForgotten {})]),
         }
     }
 
@@ -316,6 +343,10 @@ impl AggregateRoot for CustomerAggregate {
         self.version
     }
 
+    fn set_version(&mut self, version: i64) {
+        self.version = version;
+    }
+
     fn load_from_events(events: Vec<EventEnvelope<Self::Event>>) -> Result<Self> {
         if events.is_empty() {
             anyhow::bail!("No events to load");
@@ -839,4 +870,31 @@ mod tests {
         let events = aggregate.handle_command(&command).unwrap();
         assert_eq!(events.len(), 0);
     }
+
+    #[test]
+    fn test_forgotten_customer_is_scrubbed_and_closed() {
+        let mut aggregate = CustomerAggregate::apply_first_event(
+            &CustomerEvent::Registered(create_test_customer())
+        ).unwrap();
+        aggregate.apply_event(&CustomerEvent::AddressAdded(CustomerAddressAdded {
+            address_id: Uuid::new_v4(),
+            address: create_test_address(),
+            is_default: true,
+        })).unwrap();
+
+        let events = aggregate.handle_command(&CustomerCommand::ForgetCustomer).unwrap();
+        aggregate.apply_event(&events[0]).unwrap();
+
+        assert!(aggregate.forgotten);
+        assert_eq!(aggregate.email.as_str(), FORGOTTEN_PLACEHOLDER);
+        assert_eq!(aggregate.first_name, FORGOTTEN_PLACEHOLDER);
+        assert_eq!(aggregate.phone, None);
+        assert!(aggregate.addresses.is_empty());
+        assert_eq!(aggregate.status, CustomerStatus::Deactivated);
+
+        let result = aggregate.handle_command(&CustomerCommand::ReactivateCustomer { notes: None });
+        assert!(matches!(result, Err(CustomerError::Forgotten)));
+        let result = aggregate.handle_command(&CustomerCommand::ForgetCustomer);
+        assert!(matches!(result, Err(CustomerError::AlreadyForgotten)));
+    }
 }
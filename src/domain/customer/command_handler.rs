@@ -1,105 +1,94 @@
 use std::sync::Arc;
 use uuid::Uuid;
-use anyhow::{Result, bail};
+use anyhow::{bail, Result};
 
-use crate::event_sourcing::{AggregateRoot, EventEnvelope, EventStore};
+use crate::domain::{CommandAggregate, CommandHandler};
+use crate::event_sourcing::{AggregateRoot, CommandContext, EventStorage, EventStore};
+use crate::messaging::StateSnapshotPublisher;
 
 use super::aggregate::CustomerAggregate;
 use super::commands::CustomerCommand;
+use super::errors::CustomerError;
 use super::events::CustomerEvent;
 
 // ============================================================================
 // Customer Command Handler
 // ============================================================================
 //
-// Orchestrates: Command → Aggregate → Events → Event Store
+// Orchestrates: Command → Aggregate → Events → Event Store, through the
+// generic CommandHandler (conflict retries, snapshots, state snapshots).
 //
 // ============================================================================
 
+impl CommandAggregate for CustomerAggregate {
+    fn initial_state(command: &CustomerCommand) -> Result<Option<Self>, CustomerError> {
+        match command {
+            CustomerCommand::RegisterCustomer { .. } => {
+                // Create a dummy aggregate just for validation
+                let event = CustomerEvent::Registered(super::events::CustomerRegistered {
+                    email: super::value_objects::Email::new("temp@example.com"),
+                    first_name: String::new(),
+                    last_name: String::new(),
+                    phone: None,
+                });
+                CustomerAggregate::apply_first_event(&event).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
 pub struct CustomerCommandHandler {
-    event_store: Arc<EventStore<CustomerEvent>>,
+    inner: CommandHandler<CustomerAggregate>,
+    /// Deletes data keys on ForgetCustomer; None on other storage backends
+    event_store: Option<Arc<EventStore<CustomerEvent>>>,
 }
 
 impl CustomerCommandHandler {
     pub fn new(event_store: Arc<EventStore<CustomerEvent>>) -> Self {
-        Self { event_store }
+        Self { inner: CommandHandler::new(event_store.clone()), event_store: Some(event_store) }
+    }
+
+    /// Handler on another storage backend, e.g. InMemoryEventStorage in tests
+    pub fn from_storage(storage: Arc<dyn EventStorage<CustomerEvent>>) -> Self {
+        Self { inner: CommandHandler::from_storage(storage), event_store: None }
+    }
+
+    /// Publish full customer states periodically (event-carried state transfer)
+    pub fn with_state_snapshots(mut self, publisher: Arc<StateSnapshotPublisher<CustomerAggregate>>) -> Self {
+        self.inner = self.inner.with_state_snapshots(publisher);
+        self
+    }
+
+    /// Retries after a concurrency conflict (0 = fail on the first conflict)
+    pub fn with_conflict_retries(mut self, retries: u32) -> Self {
+        self.inner = self.inner.with_conflict_retries(retries);
+        self
     }
 
     /// Handle a command and persist resulting events
+    ///
+    /// The events carry the context's correlation, causation, user and
+    /// metadata; event store calls are bounded by its deadline, if any.
     pub async fn handle(
         &self,
         aggregate_id: Uuid,
         command: CustomerCommand,
-        correlation_id: Uuid,
+        ctx: &CommandContext,
     ) -> Result<i64> {
-        // Load current aggregate state
-        let (aggregate, expected_version) = if self.event_store.aggregate_exists(aggregate_id).await? {
-            let agg = self.event_store.load_aggregate::<CustomerAggregate>(aggregate_id).await?;
-            let ver = agg.version();
-            (agg, ver)
-        } else {
-            // For RegisterCustomer, we don't have existing aggregate
-            match &command {
-                CustomerCommand::RegisterCustomer { .. } => {
-                    // Create a dummy aggregate just for validation
-                    let event = CustomerEvent::Registered(super::events::CustomerRegistered {
-                        email: super::value_objects::Email::new("temp@example.com"),
-                        first_name: String::new(),
-                        last_name: String::new(),
-                        phone: None,
-                    });
-                    let agg = CustomerAggregate::apply_first_event(&event)?;
-                    (agg, 0) // Expected version is 0 for new aggregates
-                }
-                _ => bail!("Aggregate does not exist: {}", aggregate_id),
-            }
-        };
-
-        // Handle command to get events
-        let domain_events = aggregate.handle_command(&command)
-            .map_err(|e| anyhow::anyhow!("Command failed: {}", e))?;
-
-        // Wrap in envelopes
-        let mut envelopes = Vec::new();
-        let mut seq = expected_version;
-
-        for domain_event in domain_events {
-            seq += 1;
-            let event_type = match &domain_event {
-                CustomerEvent::Registered(_) => "CustomerRegistered",
-                CustomerEvent::ProfileUpdated(_) => "CustomerProfileUpdated",
-                CustomerEvent::EmailChanged(_) => "CustomerEmailChanged",
-                CustomerEvent::PhoneChanged(_) => "CustomerPhoneChanged",
-                CustomerEvent::AddressAdded(_) => "CustomerAddressAdded",
-                CustomerEvent::AddressUpdated(_) => "CustomerAddressUpdated",
-                CustomerEvent::AddressRemoved(_) => "CustomerAddressRemoved",
-                CustomerEvent::PaymentMethodAdded(_) => "CustomerPaymentMethodAdded",
-                CustomerEvent::PaymentMethodRemoved(_) => "CustomerPaymentMethodRemoved",
-                CustomerEvent::TierUpgraded(_) => "CustomerTierUpgraded",
-                CustomerEvent::Suspended(_) => "CustomerSuspended",
-                CustomerEvent::Reactivated(_) => "CustomerReactivated",
-                CustomerEvent::Deactivated(_) => "CustomerDeactivated",
-            };
-
-            let envelope = EventEnvelope::new(
-                aggregate_id,
-                seq,
-                event_type.to_string(),
-                domain_event,
-                correlation_id,
-            );
-
-            envelopes.push(envelope);
-        }
-
-        // Append to event store
-        let new_version = self.event_store.append_events(
-            aggregate_id,
-            expected_version,
-            envelopes,
-            true, // publish to outbox
-        ).await?;
+        self.inner.handle(aggregate_id, &command, ctx).await
+    }
 
-        Ok(new_version)
+    /// Forget a customer's personal data (right to erasure)
+    ///
+    /// Deletes the customer's data key, so the personal fields of its past
+    /// events can no longer be read, then records CustomerForgotten. The key
+    /// goes first: a failed append can simply be retried.
+    pub async fn forget(&self, customer_id: Uuid, ctx: &CommandContext) -> Result<i64> {
+        let Some(ref event_store) = self.event_store else {
+            bail!("Forgetting customers needs the Scylla event store");
+        };
+        event_store.forget_personal_data(customer_id).await?;
+        self.inner.handle(customer_id, &CustomerCommand::ForgetCustomer, ctx).await
     }
 }
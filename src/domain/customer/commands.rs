@@ -55,4 +55,6 @@ pub enum CustomerCommand {
     DeactivateCustomer {
         reason: String,
     },
+    /// Erase personal data; the data key is deleted by the command handler
+    ForgetCustomer,
 }
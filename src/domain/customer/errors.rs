@@ -24,7 +24,7 @@ pub enum CustomerError {
     #[error("Email cannot be empty")]
     EmptyEmail,
 
-    #[error("Invalid email format: {0}")]
+    #[error("Invalid email format: {}", crate::utils::Pii(.0))]
     InvalidEmail(String),
 
     #[error("First name cannot be empty")]
@@ -47,6 +47,12 @@ pub enum CustomerError {
 
     #[error("Aggregate not initialized")]
     NotInitialized,
+
+    #[error("Customer was forgotten")]
+    Forgotten,
+
+    #[error("Customer is already forgotten")]
+    AlreadyForgotten,
 }
 
 // ============================================================================
@@ -89,6 +95,9 @@ mod tests {
 
         let err = CustomerError::NotInitialized;
         assert_eq!(err.to_string(), "Aggregate not initialized");
+
+        let err = CustomerError::Forgotten;
+        assert_eq!(err.to_string(), "Customer was forgotten");
     }
 
     #[test]
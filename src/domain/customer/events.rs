@@ -1,40 +1,37 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use crate::event_sourcing::DomainEvent;
-use super::value_objects::{Email, PhoneNumber, Address, CustomerStatus, CustomerTier, PaymentMethod};
+use crate::event_sourcing::{domain_event_enum, DomainEvent};
+use super::value_objects::{Email, PhoneNumber, Address, CustomerTier, PaymentMethod};
 
 // ============================================================================
 // Customer Domain Events
 // ============================================================================
 
-/// Union type for all customer events
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type", content = "data")]
-pub enum CustomerEvent {
-    Registered(CustomerRegistered),
-    ProfileUpdated(CustomerProfileUpdated),
-    EmailChanged(CustomerEmailChanged),
-    PhoneChanged(CustomerPhoneChanged),
-    AddressAdded(CustomerAddressAdded),
-    AddressUpdated(CustomerAddressUpdated),
-    AddressRemoved(CustomerAddressRemoved),
-    PaymentMethodAdded(CustomerPaymentMethodAdded),
-    PaymentMethodRemoved(CustomerPaymentMethodRemoved),
-    TierUpgraded(CustomerTierUpgraded),
-    Suspended(CustomerSuspended),
-    Reactivated(CustomerReactivated),
-    Deactivated(CustomerDeactivated),
-}
-
-impl DomainEvent for CustomerEvent {
-    fn event_type() -> &'static str {
-        "CustomerEvent"
+domain_event_enum! {
+    /// Union type for all customer events
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(tag = "type", content = "data")]
+    pub enum CustomerEvent {
+        Registered(CustomerRegistered) personal_data [email, first_name, last_name, phone],
+        ProfileUpdated(CustomerProfileUpdated) personal_data [first_name, last_name, phone],
+        EmailChanged(CustomerEmailChanged) personal_data [old_email, new_email],
+        PhoneChanged(CustomerPhoneChanged) personal_data [old_phone, new_phone],
+        AddressAdded(CustomerAddressAdded) personal_data [address],
+        AddressUpdated(CustomerAddressUpdated) personal_data [address],
+        AddressRemoved(CustomerAddressRemoved),
+        PaymentMethodAdded(CustomerPaymentMethodAdded),
+        PaymentMethodRemoved(CustomerPaymentMethodRemoved),
+        TierUpgraded(CustomerTierUpgraded),
+        Suspended(CustomerSuspended),
+        Reactivated(CustomerReactivated),
+        Deactivated(CustomerDeactivated),
+        Forgotten(CustomerForgotten),
     }
 }
 
 // Individual event types
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, DomainEvent)]
 pub struct CustomerRegistered {
     pub email: Email,
     pub first_name: String,
@@ -42,74 +39,78 @@ pub struct CustomerRegistered {
     pub phone: Option<PhoneNumber>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, DomainEvent)]
 pub struct CustomerProfileUpdated {
     pub first_name: Option<String>,
     pub last_name: Option<String>,
     pub phone: Option<PhoneNumber>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, DomainEvent)]
 pub struct CustomerEmailChanged {
     pub old_email: Email,
     pub new_email: Email,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, DomainEvent)]
 pub struct CustomerPhoneChanged {
     pub old_phone: Option<PhoneNumber>,
     pub new_phone: PhoneNumber,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, DomainEvent)]
 pub struct CustomerAddressAdded {
     pub address_id: Uuid,
     pub address: Address,
     pub is_default: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, DomainEvent)]
 pub struct CustomerAddressUpdated {
     pub address_id: Uuid,
     pub address: Address,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, DomainEvent)]
 pub struct CustomerAddressRemoved {
     pub address_id: Uuid,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, DomainEvent)]
 pub struct CustomerPaymentMethodAdded {
     pub payment_method: PaymentMethod,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, DomainEvent)]
 pub struct CustomerPaymentMethodRemoved {
     pub payment_method_id: Uuid,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, DomainEvent)]
 pub struct CustomerTierUpgraded {
     pub old_tier: CustomerTier,
     pub new_tier: CustomerTier,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, DomainEvent)]
 pub struct CustomerSuspended {
     pub reason: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, DomainEvent)]
 pub struct CustomerReactivated {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, DomainEvent)]
 pub struct CustomerDeactivated {
     pub reason: String,
 }
 
+/// The customer's personal data was erased (its data key deleted)
+#[derive(Debug, Clone, Serialize, Deserialize, DomainEvent)]
+pub struct CustomerForgotten {}
+
 // ============================================================================
 // Unit Tests
 // ============================================================================
@@ -373,6 +374,7 @@ mod tests {
             CustomerEvent::Deactivated(CustomerDeactivated {
                 reason: "Test".to_string(),
             }),
+            CustomerEvent::Forgotten(CustomerForgotten {}),
         ];
 
         for event in events {
@@ -1,14 +1,23 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use uuid::Uuid;
 
+use crate::utils::Pii;
+
 // ============================================================================
 // Customer Value Objects
 // ============================================================================
 
-/// Customer email address
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Customer email address (PII - redacted in production Debug output)
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Email(pub String);
 
+impl fmt::Debug for Email {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Email").field(&Pii(&self.0)).finish()
+    }
+}
+
 impl Email {
     pub fn new(email: impl Into<String>) -> Self {
         Self(email.into())
@@ -19,10 +28,16 @@ impl Email {
     }
 }
 
-/// Customer phone number
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Customer phone number (PII - redacted in production Debug output)
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct PhoneNumber(pub String);
 
+impl fmt::Debug for PhoneNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PhoneNumber").field(&Pii(&self.0)).finish()
+    }
+}
+
 impl PhoneNumber {
     pub fn new(phone: impl Into<String>) -> Self {
         Self(phone.into())
@@ -33,8 +48,8 @@ impl PhoneNumber {
     }
 }
 
-/// Customer address
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// Customer address (street and postal code redacted in production Debug output)
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Address {
     pub street: String,
     pub city: String,
@@ -43,6 +58,18 @@ pub struct Address {
     pub country: String,
 }
 
+impl fmt::Debug for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Address")
+            .field("street", &Pii(&self.street))
+            .field("city", &self.city)
+            .field("state", &self.state)
+            .field("postal_code", &Pii(&self.postal_code))
+            .field("country", &self.country)
+            .finish()
+    }
+}
+
 /// Customer status in the system
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum CustomerStatus {
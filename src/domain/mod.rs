@@ -12,12 +12,39 @@
 // - Command handler
 //
 // This layer is completely separate from the event sourcing infrastructure.
+// The generic CommandHandler runs the command flow (load, decide, append,
+// retry on conflict) for every aggregate, one command per aggregate at a
+// time (AggregateLocks); per-aggregate handlers wrap it.
+//
+// `upcasters()` declares the current version of every event type and holds
+// the steps that bring older stored versions up to it. Raising a payload's
+// EVENT_VERSION needs a step registered here, or startup fails.
 //
 // ============================================================================
 
+mod aggregate_locks;
+mod command_handler;
+
 pub mod order;
 pub mod customer;
 
+pub use aggregate_locks::{AggregateLock, AggregateLocks};
+pub use command_handler::{CommandAggregate, CommandHandler, DEFAULT_CONFLICT_RETRIES};
+
+use crate::event_sourcing::UpcasterRegistry;
+
+/// Event types of all domain events - the topics the relay publishes them to
+pub fn event_types() -> impl Iterator<Item = &'static str> {
+    order::OrderEvent::EVENT_TYPES.iter().chain(customer::CustomerEvent::EVENT_TYPES).copied()
+}
+
+/// Upcasters of all domain events, at the versions the payloads declare
+pub fn upcasters() -> UpcasterRegistry {
+    UpcasterRegistry::new()
+        .with_event_versions(order::OrderEvent::EVENT_VERSIONS)
+        .with_event_versions(customer::CustomerEvent::EVENT_VERSIONS)
+}
+
 // Future aggregates can be added here:
 // pub mod product;
 // pub mod payment;
@@ -4,7 +4,7 @@ use chrono::{DateTime, Utc};
 use anyhow::{Result, bail};
 
 use crate::event_sourcing::{AggregateRoot, EventEnvelope};
-use super::value_objects::{OrderItem, OrderStatus};
+use super::value_objects::{OrderItem, OrderStatus, OrderTotals};
 use super::events::*;
 use super::commands::OrderCommand;
 use super::errors::OrderError;
@@ -32,6 +32,9 @@ pub struct OrderAggregate {
     pub tracking_number: Option<String>,
     pub carrier: Option<String>,
     pub cancelled_reason: Option<String>,
+    /// Latest OrderPriced totals (None until priced)
+    #[serde(default)]
+    pub totals: Option<OrderTotals>,
 }
 
 impl OrderAggregate {
@@ -77,6 +80,7 @@ impl AggregateRoot for OrderAggregate {
                     tracking_number: None,
                     carrier: None,
                     cancelled_reason: None,
+                    totals: None,
                 })
             }
             _ => Err(OrderError::NotInitialized),
@@ -114,6 +118,10 @@ impl AggregateRoot for OrderAggregate {
                 self.cancelled_reason = e.reason.clone();
                 Ok(())
             }
+            OrderEvent::Priced(e) => {
+                self.totals = Some(e.totals.clone());
+                Ok(())
+            }
         }
     }
 
@@ -209,6 +217,10 @@ impl AggregateRoot for OrderAggregate {
         self.version
     }
 
+    fn set_version(&mut self, version: i64) {
+        self.version = version;
+    }
+
     fn load_from_events(events: Vec<EventEnvelope<Self::Event>>) -> Result<Self> {
         if events.is_empty() {
             bail!("Cannot load aggregate from empty event list");
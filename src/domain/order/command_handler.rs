@@ -1,101 +1,193 @@
 use std::sync::Arc;
 use uuid::Uuid;
-use anyhow::{Result, bail};
+use anyhow::Result;
 
-use crate::event_sourcing::{AggregateRoot, EventEnvelope, EventStore};
+use crate::domain::{CommandAggregate, CommandHandler};
+use crate::event_sourcing::{AggregateRoot, CommandContext, EventStorage, EventStore};
+use crate::messaging::StateSnapshotPublisher;
 
 use super::aggregate::OrderAggregate;
 use super::commands::OrderCommand;
-use super::events::OrderEvent;
+use super::errors::OrderError;
+use super::events::{OrderEvent, OrderPriced};
+use super::pricing::{NoPricing, OrderPricing, PricingInput};
 
 // ============================================================================
 // Order Command Handler
 // ============================================================================
 //
-// Orchestrates: Command → Aggregate → Events → Event Store
+// Orchestrates: Command → Aggregate → Events → Event Store, through the
+// generic CommandHandler (conflict retries, snapshots, state snapshots).
+//
+// Commands that change the items are priced after the aggregate accepts
+// them; the totals are appended as OrderPriced in the same batch.
 //
 // ============================================================================
 
+impl CommandAggregate for OrderAggregate {
+    fn initial_state(command: &OrderCommand) -> Result<Option<Self>, OrderError> {
+        match command {
+            OrderCommand::CreateOrder { .. } => {
+                // Create a dummy aggregate just for validation
+                let event = OrderEvent::Created(super::events::OrderCreated {
+                    customer_id: Uuid::new_v4(),
+                    items: vec![],
+                });
+                OrderAggregate::apply_first_event(&event).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
 pub struct OrderCommandHandler {
-    event_store: Arc<EventStore<OrderEvent>>,
+    inner: CommandHandler<OrderAggregate>,
+    pricing: Arc<dyn OrderPricing>,
 }
 
 impl OrderCommandHandler {
     pub fn new(event_store: Arc<EventStore<OrderEvent>>) -> Self {
-        Self { event_store }
+        Self {
+            inner: CommandHandler::new(event_store),
+            pricing: Arc::new(NoPricing),
+        }
+    }
+
+    /// Handler on another storage backend, e.g. InMemoryEventStorage in tests
+    pub fn from_storage(storage: Arc<dyn EventStorage<OrderEvent>>) -> Self {
+        Self {
+            inner: CommandHandler::from_storage(storage),
+            pricing: Arc::new(NoPricing),
+        }
+    }
+
+    /// Price orders with `pricing` (taxes, shipping, discounts)
+    pub fn with_pricing(mut self, pricing: Arc<dyn OrderPricing>) -> Self {
+        self.pricing = pricing;
+        self
+    }
+
+    /// Publish full order states periodically (event-carried state transfer)
+    pub fn with_state_snapshots(mut self, publisher: Arc<StateSnapshotPublisher<OrderAggregate>>) -> Self {
+        self.inner = self.inner.with_state_snapshots(publisher);
+        self
+    }
+
+    /// Retries after a concurrency conflict (0 = fail on the first conflict)
+    pub fn with_conflict_retries(mut self, retries: u32) -> Self {
+        self.inner = self.inner.with_conflict_retries(retries);
+        self
     }
 
     /// Handle a command and persist resulting events
+    ///
+    /// The events carry the context's correlation, causation, user and
+    /// metadata; event store calls are bounded by its deadline, if any.
     pub async fn handle(
         &self,
         aggregate_id: Uuid,
         command: OrderCommand,
-        correlation_id: Uuid,
+        ctx: &CommandContext,
     ) -> Result<i64> {
-        // Load current aggregate state
-        let exists = self.event_store.aggregate_exists(aggregate_id).await?;
-        tracing::debug!("Aggregate {} exists: {}", aggregate_id, exists);
-
-        let (aggregate, expected_version) = if exists {
-            let agg = self.event_store.load_aggregate::<OrderAggregate>(aggregate_id).await?;
-            let ver = agg.version();
-            tracing::debug!("Loaded aggregate {} with version: {}", aggregate_id, ver);
-            (agg, ver)
-        } else {
-            // For CreateOrder, we don't have existing aggregate
-            match &command {
-                OrderCommand::CreateOrder { .. } => {
-                    // Create a dummy aggregate just for validation
-                    let event = OrderEvent::Created(super::events::OrderCreated {
-                        customer_id: Uuid::new_v4(),
-                        items: vec![],
-                    });
-                    let agg = OrderAggregate::apply_first_event(&event)?;
-                    tracing::debug!("Creating new aggregate {} with expected_version: 0", aggregate_id);
-                    (agg, 0) // Expected version is 0 for new aggregates
+        self.inner
+            .handle_with(aggregate_id, &command, ctx, |aggregate, mut events| {
+                if let Some(priced) = self.price(aggregate_id, aggregate, &events)? {
+                    events.push(OrderEvent::Priced(priced));
                 }
-                _ => bail!("Aggregate does not exist: {}", aggregate_id),
-            }
+                Ok(events)
+            })
+            .await
+    }
+
+    /// Totals for the items set by `events`, if they set any
+    fn price(
+        &self,
+        order_id: Uuid,
+        aggregate: &OrderAggregate,
+        events: &[OrderEvent],
+    ) -> Result<Option<OrderPriced>> {
+        let (customer_id, items) = match events.last() {
+            Some(OrderEvent::Created(e)) => (e.customer_id, &e.items),
+            Some(OrderEvent::ItemsUpdated(e)) => (aggregate.customer_id, &e.items),
+            _ => return Ok(None),
         };
 
-        // Handle command to get events
-        let domain_events = aggregate.handle_command(&command)
-            .map_err(|e| anyhow::anyhow!("Command failed: {}", e))?;
-
-        // Wrap in envelopes
-        let mut envelopes = Vec::new();
-        let mut seq = expected_version;
-
-        for domain_event in domain_events {
-            seq += 1;
-            let event_type = match &domain_event {
-                OrderEvent::Created(_) => "OrderCreated",
-                OrderEvent::ItemsUpdated(_) => "OrderItemsUpdated",
-                OrderEvent::Confirmed(_) => "OrderConfirmed",
-                OrderEvent::Shipped(_) => "OrderShipped",
-                OrderEvent::Delivered(_) => "OrderDelivered",
-                OrderEvent::Cancelled(_) => "OrderCancelled",
-            };
-
-            let envelope = EventEnvelope::new(
-                aggregate_id,
-                seq,
-                event_type.to_string(),
-                domain_event,
-                correlation_id,
-            );
-
-            envelopes.push(envelope);
-        }
+        let input = PricingInput { order_id, customer_id, items };
+        let totals = self.pricing.price(&input).map_err(anyhow::Error::new)?;
+
+        Ok(totals.map(|totals| OrderPriced {
+            totals,
+            priced_at: chrono::Utc::now(),
+        }))
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_create_order_starts_an_aggregate() {
+        let create = OrderCommand::CreateOrder {
+            order_id: Uuid::new_v4(),
+            customer_id: Uuid::new_v4(),
+            items: vec![],
+        };
+        assert!(OrderAggregate::initial_state(&create).unwrap().is_some());
+        assert!(OrderAggregate::initial_state(&OrderCommand::ConfirmOrder).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_commands_run_on_in_memory_storage() {
+        use crate::event_sourcing::InMemoryEventStorage;
+        use crate::domain::order::OrderItem;
+
+        let storage = Arc::new(InMemoryEventStorage::<OrderEvent>::new());
+        let handler = OrderCommandHandler::from_storage(storage.clone());
+        let order_id = Uuid::new_v4();
+
+        let user_id = Uuid::new_v4();
+        let ctx = CommandContext::new(Uuid::new_v4()).with_user(user_id).with_metadata("client_ip", "10.0.0.7");
+
+        assert!(handler.handle(order_id, OrderCommand::ConfirmOrder, &ctx).await.is_err());
+
+        let create = OrderCommand::CreateOrder {
+            order_id,
+            customer_id: Uuid::new_v4(),
+            items: vec![OrderItem { product_id: Uuid::new_v4(), quantity: 1 }],
+        };
+        assert_eq!(handler.handle(order_id, create, &ctx).await.unwrap(), 1);
+        assert_eq!(handler.handle(order_id, OrderCommand::ConfirmOrder, &ctx).await.unwrap(), 2);
+
+        let outbox = storage.outbox();
+        let types: Vec<_> = outbox.iter().map(|e| e.event_type.as_str()).collect();
+        assert_eq!(types, vec!["OrderCreated", "OrderConfirmed"]);
+        // Audit fields of the context land on every event
+        assert!(outbox.iter().all(|e| e.user_id == Some(user_id) && e.correlation_id == ctx.correlation_id));
+        assert_eq!(outbox[0].metadata.get("client_ip").map(String::as_str), Some("10.0.0.7"));
+    }
+
+    #[tokio::test]
+    async fn test_repeated_command_id_is_applied_once() {
+        use crate::event_sourcing::InMemoryEventStorage;
+        use crate::domain::order::OrderItem;
 
-        // Append to event store
-        let new_version = self.event_store.append_events(
-            aggregate_id,
-            expected_version,
-            envelopes,
-            true, // publish to outbox
-        ).await?;
+        let storage = Arc::new(InMemoryEventStorage::<OrderEvent>::new());
+        let handler = OrderCommandHandler::from_storage(storage.clone());
+        let order_id = Uuid::new_v4();
+        let items = vec![OrderItem { product_id: Uuid::new_v4(), quantity: 1 }];
+        let create = OrderCommand::CreateOrder { order_id, customer_id: Uuid::new_v4(), items };
+        handler.handle(order_id, create, &CommandContext::new(Uuid::new_v4())).await.unwrap();
 
-        Ok(new_version)
+        // The retry would fail as already confirmed if it ran again
+        let ctx = CommandContext::new(Uuid::new_v4()).with_command_id(Uuid::new_v4());
+        assert_eq!(handler.handle(order_id, OrderCommand::ConfirmOrder, &ctx).await.unwrap(), 2);
+        assert_eq!(handler.handle(order_id, OrderCommand::ConfirmOrder, &ctx).await.unwrap(), 2);
+        assert_eq!(storage.outbox().len(), 2);
+        assert_eq!(storage.outbox()[1].command_id(), ctx.command_id);
     }
 }
@@ -29,6 +29,9 @@ pub enum OrderError {
 
     #[error("Aggregate not initialized")]
     NotInitialized,
+
+    #[error("No price for product: {0}")]
+    UnpricedProduct(uuid::Uuid),
 }
 
 // ============================================================================
@@ -9,6 +9,7 @@
 // - Errors (OrderError enum)
 // - Aggregate (OrderAggregate with business logic)
 // - Command Handler (OrderCommandHandler)
+// - Pricing (OrderPricing extension point, RegionalTaxCalculator)
 //
 // This is completely separate from the generic event sourcing infrastructure.
 //
@@ -20,6 +21,7 @@ mod commands;
 mod errors;
 mod aggregate;
 mod command_handler;
+mod pricing;
 
 // Re-export for convenience
 pub use value_objects::*;
@@ -28,3 +30,4 @@ pub use commands::*;
 pub use errors::*;
 pub use aggregate::*;
 pub use command_handler::*;
+pub use pricing::*;
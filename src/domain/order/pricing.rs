@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::errors::OrderError;
+use super::value_objects::{OrderItem, OrderTotals};
+
+// ============================================================================
+// Order Pricing - Extension Point for Taxes, Shipping and Discounts
+// ============================================================================
+//
+// Totals are computed once, on the write side, and recorded as an
+// OrderPriced event next to the event that changed the items (OrderCreated,
+// OrderItemsUpdated). Consumers read the priced totals from the event
+// history instead of re-deriving them with their own rules.
+//
+// The command handler runs the configured `OrderPricing` after the aggregate
+// accepted the command. The default `NoPricing` emits nothing, so
+// deployments without pricing keep their event stream unchanged.
+//
+// Amounts are integers in the currency's minor unit (cents), never floats.
+//
+// ============================================================================
+
+/// What an order is priced from
+#[derive(Debug, Clone)]
+pub struct PricingInput<'a> {
+    pub order_id: Uuid,
+    pub customer_id: Uuid,
+    pub items: &'a [OrderItem],
+}
+
+/// Computes order totals
+///
+/// Returning `Ok(None)` leaves the order unpriced; an error rejects the
+/// command (e.g. a product without a price).
+pub trait OrderPricing: Send + Sync {
+    fn price(&self, input: &PricingInput<'_>) -> Result<Option<OrderTotals>, OrderError>;
+}
+
+/// Default: orders are not priced
+pub struct NoPricing;
+
+impl OrderPricing for NoPricing {
+    fn price(&self, _input: &PricingInput<'_>) -> Result<Option<OrderTotals>, OrderError> {
+        Ok(None)
+    }
+}
+
+// ============================================================================
+// Sample Implementation - Flat Regional Tax
+// ============================================================================
+
+/// Prices orders for one region: catalog unit prices, a flat tax rate,
+/// flat shipping (free above a threshold) and an optional percentage discount
+pub struct RegionalTaxCalculator {
+    currency: String,
+    /// Tax rate in basis points (725 = 7.25%)
+    tax_rate_bps: i64,
+    unit_prices: HashMap<Uuid, i64>,
+    shipping: i64,
+    free_shipping_over: Option<i64>,
+    discount_bps: i64,
+}
+
+impl RegionalTaxCalculator {
+    pub fn new(currency: &str, tax_rate_bps: i64) -> Self {
+        Self {
+            currency: currency.to_string(),
+            tax_rate_bps,
+            unit_prices: HashMap::new(),
+            shipping: 0,
+            free_shipping_over: None,
+            discount_bps: 0,
+        }
+    }
+
+    pub fn with_unit_price(mut self, product_id: Uuid, unit_price: i64) -> Self {
+        self.unit_prices.insert(product_id, unit_price);
+        self
+    }
+
+    pub fn with_shipping(mut self, shipping: i64, free_over: Option<i64>) -> Self {
+        self.shipping = shipping;
+        self.free_shipping_over = free_over;
+        self
+    }
+
+    pub fn with_discount_bps(mut self, discount_bps: i64) -> Self {
+        self.discount_bps = discount_bps;
+        self
+    }
+}
+
+/// `amount * bps / 10_000`, rounded half up
+fn apply_bps(amount: i64, bps: i64) -> i64 {
+    (amount * bps + 5_000) / 10_000
+}
+
+impl OrderPricing for RegionalTaxCalculator {
+    fn price(&self, input: &PricingInput<'_>) -> Result<Option<OrderTotals>, OrderError> {
+        let mut subtotal = 0;
+        for item in input.items {
+            let unit_price = self
+                .unit_prices
+                .get(&item.product_id)
+                .ok_or(OrderError::UnpricedProduct(item.product_id))?;
+            subtotal += unit_price * item.quantity as i64;
+        }
+
+        // Discount before tax; shipping is neither discounted nor taxed
+        let discount = apply_bps(subtotal, self.discount_bps);
+        let tax = apply_bps(subtotal - discount, self.tax_rate_bps);
+        let shipping = match self.free_shipping_over {
+            Some(threshold) if subtotal - discount >= threshold => 0,
+            _ => self.shipping,
+        };
+
+        Ok(Some(OrderTotals {
+            currency: self.currency.clone(),
+            subtotal,
+            tax,
+            shipping,
+            discount,
+            total: subtotal - discount + tax + shipping,
+        }))
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regional_tax_calculator_totals() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let pricing = RegionalTaxCalculator::new("USD", 725)
+            .with_unit_price(a, 1_999)
+            .with_unit_price(b, 500)
+            .with_shipping(799, Some(10_000))
+            .with_discount_bps(1_000);
+
+        let items = [
+            OrderItem { product_id: a, quantity: 2 },
+            OrderItem { product_id: b, quantity: 1 },
+        ];
+        let input = PricingInput { order_id: Uuid::new_v4(), customer_id: Uuid::new_v4(), items: &items };
+        let totals = pricing.price(&input).unwrap().unwrap();
+
+        assert_eq!(totals.currency, "USD");
+        assert_eq!(totals.subtotal, 4_498);
+        assert_eq!(totals.discount, 450);
+        // 7.25% of 40.48, rounded
+        assert_eq!(totals.tax, 293);
+        assert_eq!(totals.shipping, 799);
+        assert_eq!(totals.total, 4_498 - 450 + 293 + 799);
+
+        // Free shipping above the threshold
+        let bulk = [OrderItem { product_id: a, quantity: 10 }];
+        let input = PricingInput { items: &bulk, ..input };
+        assert_eq!(pricing.price(&input).unwrap().unwrap().shipping, 0);
+    }
+
+    #[test]
+    fn test_unpriced_product_rejects_and_default_is_no_op() {
+        let items = [OrderItem { product_id: Uuid::new_v4(), quantity: 1 }];
+        let input = PricingInput { order_id: Uuid::new_v4(), customer_id: Uuid::new_v4(), items: &items };
+
+        assert!(matches!(
+            RegionalTaxCalculator::new("EUR", 1_900).price(&input),
+            Err(OrderError::UnpricedProduct(_))
+        ));
+        assert!(NoPricing.price(&input).unwrap().is_none());
+    }
+}
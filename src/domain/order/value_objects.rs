@@ -11,6 +11,17 @@ pub struct OrderItem {
     pub quantity: i32,
 }
 
+/// Priced order totals, in minor units (cents) of `currency`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OrderTotals {
+    pub currency: String,
+    pub subtotal: i64,
+    pub tax: i64,
+    pub shipping: i64,
+    pub discount: i64,
+    pub total: i64,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum OrderStatus {
     Created,
@@ -50,10 +50,46 @@ pub trait AggregateRoot: Sized + Send + Sync {
     /// Get current version (sequence number)
     fn version(&self) -> i64;
 
+    /// Set the version after applying the event at `version`
+    fn set_version(&mut self, version: i64);
+
     /// Load aggregate from event history (reconstruct from events)
     /// This method must be implemented by each aggregate to properly set version from events
     fn load_from_events(events: Vec<EventEnvelope<Self::Event>>) -> Result<Self>
     where
         Self::Error: std::fmt::Display;
 
+    /// Apply events recorded after this state (e.g. after a snapshot)
+    fn apply_envelopes(mut self, events: Vec<EventEnvelope<Self::Event>>) -> Result<Self>
+    where
+        Self::Error: std::fmt::Display,
+    {
+        for envelope in events {
+            self.apply_event(&envelope.event_data)
+                .map_err(|e| anyhow::anyhow!("Failed to apply event: {}", e))?;
+            self.set_version(envelope.sequence_number);
+        }
+        Ok(self)
+    }
+
+    /// State after one more event; `None` is created from it
+    ///
+    /// Replays a stream of events (EventStore::load_events_stream) one at a
+    /// time instead of collecting the history first.
+    fn replay_envelope(state: Option<Self>, envelope: &EventEnvelope<Self::Event>) -> Result<Self>
+    where
+        Self::Error: std::fmt::Display,
+    {
+        let mut aggregate = match state {
+            Some(mut aggregate) => {
+                aggregate.apply_event(&envelope.event_data)
+                    .map_err(|e| anyhow::anyhow!("Failed to apply event: {}", e))?;
+                aggregate
+            }
+            None => Self::apply_first_event(&envelope.event_data)
+                .map_err(|e| anyhow::anyhow!("Failed to apply first event: {}", e))?,
+        };
+        aggregate.set_version(envelope.sequence_number);
+        Ok(aggregate)
+    }
 }
@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::Instant;
+use uuid::Uuid;
+
+use super::event::{EventEnvelope, COMMAND_ID_KEY};
+
+// ============================================================================
+// Command Context - Per-Request State Carried Through Command Handling
+// ============================================================================
+//
+// HTTP/gRPC callers give up after their own timeout, but without a deadline
+// the command keeps loading events and writing batches for nobody.
+//
+// A CommandContext carries the request's Deadline from the API edge down to
+// the EventStore and publisher calls. Each bounded call:
+// 1. Fails fast if the deadline has already passed (no work started)
+// 2. Is cancelled when the deadline passes while it is in flight
+// 3. Reports a typed DeadlineExceeded (inside anyhow::Error) so callers can
+//    distinguish "too late" from real failures via `downcast_ref`
+//
+// It also carries who and what issued the command: the user, the message
+// that caused it and free-form metadata (client ip, user agent, ...). The
+// command handlers stamp them on every event they append, so they are
+// stored with the events as their audit trail.
+//
+// A command_id, if the caller gave one, is stamped as well: the command
+// handler looks it up before running the command and answers a repeated id
+// with the version the first run produced.
+//
+// ============================================================================
+
+/// The caller's deadline passed before an operation could finish
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Deadline exceeded during {operation} (budget {budget:?})")]
+pub struct DeadlineExceeded {
+    pub operation: String,
+    pub budget: Duration,
+}
+
+/// Point in time after which work on a request is abandoned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deadline {
+    at: Instant,
+    budget: Duration,
+}
+
+impl Deadline {
+    /// Deadline `timeout` from now
+    pub fn after(timeout: Duration) -> Self {
+        Self {
+            at: Instant::now() + timeout,
+            budget: timeout,
+        }
+    }
+
+    pub fn instant(&self) -> Instant {
+        self.at
+    }
+
+    /// Time left, or `None` once the deadline has passed
+    pub fn remaining(&self) -> Option<Duration> {
+        self.at.checked_duration_since(Instant::now()).filter(|d| !d.is_zero())
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.remaining().is_none()
+    }
+
+    /// Fail if the deadline has already passed
+    pub fn check(&self, operation: &str) -> Result<(), DeadlineExceeded> {
+        if self.is_expired() {
+            return Err(self.exceeded(operation));
+        }
+        Ok(())
+    }
+
+    /// Run `future`, cancelling it if the deadline passes first
+    pub async fn run<T, F>(&self, operation: &str, future: F) -> anyhow::Result<T>
+    where
+        F: Future<Output = anyhow::Result<T>>,
+    {
+        self.check(operation)?;
+
+        match tokio::time::timeout_at(self.at, future).await {
+            Ok(result) => result,
+            Err(_) => {
+                tracing::warn!(
+                    operation = %operation,
+                    budget_ms = self.budget.as_millis() as u64,
+                    "⏱️  Deadline exceeded - abandoning work"
+                );
+                Err(self.exceeded(operation).into())
+            }
+        }
+    }
+
+    fn exceeded(&self, operation: &str) -> DeadlineExceeded {
+        DeadlineExceeded {
+            operation: operation.to_string(),
+            budget: self.budget,
+        }
+    }
+}
+
+/// Run `future` under an optional deadline
+pub(crate) async fn with_deadline<T, F>(deadline: Option<&Deadline>, operation: &str, future: F) -> anyhow::Result<T>
+where
+    F: Future<Output = anyhow::Result<T>>,
+{
+    match deadline {
+        Some(deadline) => deadline.run(operation, future).await,
+        None => future.await,
+    }
+}
+
+/// Request-scoped context passed to command handlers
+#[derive(Debug, Clone)]
+pub struct CommandContext {
+    pub correlation_id: Uuid,
+    pub deadline: Option<Deadline>,
+    /// User who issued the command
+    pub user_id: Option<Uuid>,
+    /// Command or event that caused this command
+    pub causation_id: Option<Uuid>,
+    /// Audit details stored with the events (e.g. client_ip, user_agent)
+    pub metadata: HashMap<String, String>,
+    /// Caller-chosen id of the command; a repeated id is not applied again
+    pub command_id: Option<Uuid>,
+}
+
+impl CommandContext {
+    pub fn new(correlation_id: Uuid) -> Self {
+        Self {
+            correlation_id,
+            deadline: None,
+            user_id: None,
+            causation_id: None,
+            metadata: HashMap::new(),
+            command_id: None,
+        }
+    }
+
+    pub fn with_user(mut self, user_id: Uuid) -> Self {
+        self.user_id = Some(user_id);
+        self
+    }
+
+    pub fn with_causation(mut self, causation_id: Uuid) -> Self {
+        self.causation_id = Some(causation_id);
+        self
+    }
+
+    pub fn with_metadata(mut self, key: &str, value: &str) -> Self {
+        self.metadata.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    pub fn with_command_id(mut self, command_id: Uuid) -> Self {
+        self.command_id = Some(command_id);
+        self
+    }
+
+    pub fn with_deadline(mut self, deadline: Deadline) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Convenience for `with_deadline(Deadline::after(timeout))`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.deadline = Some(Deadline::after(timeout));
+        self
+    }
+
+    pub fn deadline(&self) -> Option<&Deadline> {
+        self.deadline.as_ref()
+    }
+
+    /// `envelope` with this context's user, causation, command id and metadata
+    ///
+    /// Metadata already on the envelope (e.g. its origin region) wins.
+    pub fn stamp<E>(&self, mut envelope: EventEnvelope<E>) -> EventEnvelope<E> {
+        envelope.user_id = envelope.user_id.or(self.user_id);
+        envelope.causation_id = envelope.causation_id.or(self.causation_id);
+        if let Some(command_id) = self.command_id {
+            envelope.metadata.entry(COMMAND_ID_KEY.to_string()).or_insert_with(|| command_id.to_string());
+        }
+        for (key, value) in &self.metadata {
+            envelope.metadata.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+        envelope
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_completes_within_deadline() {
+        let deadline = Deadline::after(Duration::from_secs(5));
+
+        let value = deadline.run("fast", async { Ok(42) }).await.unwrap();
+
+        assert_eq!(value, 42);
+    }
+
+    #[tokio::test]
+    async fn test_run_cancels_slow_work() {
+        let deadline = Deadline::after(Duration::from_millis(10));
+
+        let err = deadline
+            .run("slow", async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok(())
+            })
+            .await
+            .unwrap_err();
+
+        let exceeded = err.downcast_ref::<DeadlineExceeded>().unwrap();
+        assert_eq!(exceeded.operation, "slow");
+    }
+
+    #[tokio::test]
+    async fn test_expired_deadline_does_not_start_work() {
+        let deadline = Deadline::after(Duration::ZERO);
+        let mut started = false;
+
+        let result = deadline.run("never", async {
+            started = true;
+            Ok(())
+        }).await;
+
+        assert!(result.is_err());
+        assert!(!started);
+        assert!(deadline.check("never").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_without_deadline_runs_unbounded() {
+        let value = with_deadline(None, "unbounded", async { Ok("done") }).await.unwrap();
+        assert_eq!(value, "done");
+    }
+
+    #[test]
+    fn test_context_with_timeout() {
+        let ctx = CommandContext::new(Uuid::new_v4()).with_timeout(Duration::from_secs(1));
+
+        let remaining = ctx.deadline().unwrap().remaining().unwrap();
+        assert!(remaining <= Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_stamp_adds_audit_fields() {
+        let (user_id, causation_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let command_id = Uuid::new_v4();
+        let ctx = CommandContext::new(Uuid::new_v4())
+            .with_user(user_id)
+            .with_causation(causation_id)
+            .with_command_id(command_id)
+            .with_metadata("client_ip", "10.0.0.7")
+            .with_metadata("origin_region", "us-east");
+
+        let envelope = EventEnvelope::new(Uuid::new_v4(), 1, "Noted".to_string(), "payload".to_string(), ctx.correlation_id)
+            .with_origin_region("eu-west");
+        let stamped = ctx.stamp(envelope);
+
+        assert_eq!(stamped.user_id, Some(user_id));
+        assert_eq!(stamped.causation_id, Some(causation_id));
+        assert_eq!(stamped.command_id(), Some(command_id));
+        assert_eq!(stamped.metadata.get("client_ip").map(String::as_str), Some("10.0.0.7"));
+        assert_eq!(stamped.origin_region(), Some("eu-west"));
+    }
+}
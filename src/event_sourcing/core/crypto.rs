@@ -0,0 +1,223 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::{anyhow, bail, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+// ============================================================================
+// Event Crypto - Encryption at Rest for Event Payloads
+// ============================================================================
+//
+// With an EventCrypto configured, the event store seals the serialized event
+// before it is written to the event table and the outbox:
+//
+//   enc:v1:<key_id>:<base64(nonce || ciphertext)>
+//
+// The key id travels with the payload (and in the envelope metadata under
+// `encryption_key_id`), so a payload is opened with the key it was sealed
+// with. Rotating keys is adding a new active key and keeping the old ones
+// for decryption:
+//
+//   EVENT_ENCRYPTION_KEYS=k2:<base64 key>,k1:<base64 key>
+//
+// The first key seals new events; every listed key opens. Payloads that are
+// not sealed (written before encryption was enabled) pass through as they
+// are, so it can be turned on for an existing store.
+//
+// The relay opens payloads before publishing - Redpanda gets plaintext. The
+// DLQ and the approval gate keep what the outbox stored.
+//
+// AesGcmCrypto holds its keys in memory; a KMS-backed implementation of
+// EventCrypto (unwrapping data keys on startup) plugs in the same way.
+//
+// ============================================================================
+
+/// Metadata key under which the id of the sealing key is stored
+pub const ENCRYPTION_KEY_METADATA_KEY: &str = "encryption_key_id";
+
+/// Prefix of sealed payloads, followed by `<key_id>:<base64>`
+const SEALED_PREFIX: &str = "enc:v1:";
+
+/// AES-GCM nonce length in bytes
+const NONCE_LEN: usize = 12;
+
+/// AES-256 key length in bytes
+const KEY_LEN: usize = 32;
+
+/// Encrypts payloads with an active key and decrypts with any known key
+pub trait EventCrypto: Send + Sync {
+    /// Key new payloads are sealed with
+    fn active_key_id(&self) -> &str;
+
+    /// Encrypt `plaintext` with the active key
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>>;
+
+    /// Decrypt `ciphertext` sealed with `key_id`
+    fn decrypt(&self, key_id: &str, ciphertext: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// Seal a serialized event with the active key
+pub fn seal_payload(crypto: &dyn EventCrypto, plaintext: &str) -> Result<String> {
+    let ciphertext = crypto.encrypt(plaintext.as_bytes())?;
+    Ok(format!("{}{}:{}", SEALED_PREFIX, crypto.active_key_id(), BASE64.encode(ciphertext)))
+}
+
+/// Id of the key a payload was sealed with; None for plaintext payloads
+pub fn sealed_key_id(payload: &str) -> Option<&str> {
+    let (key_id, _) = payload.strip_prefix(SEALED_PREFIX)?.split_once(':')?;
+    Some(key_id)
+}
+
+/// The serialized event of a stored payload, decrypted if it was sealed
+pub fn open_payload<'a>(crypto: Option<&dyn EventCrypto>, payload: &'a str) -> Result<Cow<'a, str>> {
+    let Some(sealed) = payload.strip_prefix(SEALED_PREFIX) else {
+        return Ok(Cow::Borrowed(payload));
+    };
+    let (key_id, encoded) = sealed
+        .split_once(':')
+        .ok_or_else(|| anyhow!("Malformed sealed payload"))?;
+    let crypto = crypto.ok_or_else(|| anyhow!("Payload sealed with key {} but no encryption keys are configured", key_id))?;
+    let plaintext = crypto.decrypt(key_id, &BASE64.decode(encoded)?)?;
+    Ok(Cow::Owned(String::from_utf8(plaintext)?))
+}
+
+/// AES-256-GCM with a random nonce per payload
+pub struct AesGcmCrypto {
+    active_key_id: String,
+    ciphers: HashMap<String, Aes256Gcm>,
+}
+
+impl AesGcmCrypto {
+    /// Seal with `key` (32 bytes) under `key_id`
+    pub fn new(key_id: &str, key: &[u8]) -> Result<Self> {
+        let crypto = Self { active_key_id: key_id.to_string(), ciphers: HashMap::new() };
+        crypto.with_key(key_id, key)
+    }
+
+    /// Also open payloads sealed with a retired key
+    pub fn with_key(mut self, key_id: &str, key: &[u8]) -> Result<Self> {
+        if key_id.is_empty() || key_id.contains(':') || key_id.contains(',') {
+            bail!("Invalid encryption key id {:?}: must be non-empty without ':' or ','", key_id);
+        }
+        if key.len() != KEY_LEN {
+            bail!("Encryption key {} must be {} bytes, got {}", key_id, KEY_LEN, key.len());
+        }
+        if self.ciphers.contains_key(key_id) {
+            bail!("Duplicate encryption key id {}", key_id);
+        }
+        let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| anyhow!("Invalid encryption key {}: {}", key_id, e))?;
+        self.ciphers.insert(key_id.to_string(), cipher);
+        Ok(self)
+    }
+
+    /// Keys as `<key_id>:<base64 key>` entries, the first one active
+    pub fn from_key_list<S: AsRef<str>>(entries: &[S]) -> Result<Self> {
+        let mut keys = entries.iter().map(|entry| {
+            let entry = entry.as_ref().trim();
+            let (key_id, encoded) = entry
+                .split_once(':')
+                .ok_or_else(|| anyhow!("Encryption key entry must be <key_id>:<base64 key>"))?;
+            let key = BASE64
+                .decode(encoded)
+                .map_err(|e| anyhow!("Encryption key {} is not valid base64: {}", key_id, e))?;
+            Ok::<_, anyhow::Error>((key_id.to_string(), key))
+        });
+        let (active_id, active_key) = keys.next().ok_or_else(|| anyhow!("No encryption keys given"))??;
+        let mut crypto = Self::new(&active_id, &active_key)?;
+        for key in keys {
+            let (key_id, key) = key?;
+            crypto = crypto.with_key(&key_id, &key)?;
+        }
+        Ok(crypto)
+    }
+
+    /// Ids of all keys, for logging (never the keys)
+    pub fn key_ids(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self.ciphers.keys().map(String::as_str).collect();
+        ids.sort();
+        ids
+    }
+}
+
+impl EventCrypto for AesGcmCrypto {
+    fn active_key_id(&self) -> &str {
+        &self.active_key_id
+    }
+
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let cipher = &self.ciphers[&self.active_key_id];
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| anyhow!("Failed to encrypt payload with key {}", self.active_key_id))?;
+        let mut sealed = nonce.to_vec();
+        sealed.extend(ciphertext);
+        Ok(sealed)
+    }
+
+    fn decrypt(&self, key_id: &str, ciphertext: &[u8]) -> Result<Vec<u8>> {
+        let cipher = self
+            .ciphers
+            .get(key_id)
+            .ok_or_else(|| anyhow!("Unknown encryption key {}", key_id))?;
+        if ciphertext.len() < NONCE_LEN {
+            bail!("Sealed payload too short");
+        }
+        let (nonce, ciphertext) = ciphertext.split_at(NONCE_LEN);
+        cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| anyhow!("Failed to decrypt payload with key {}", key_id))
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key_entry(key_id: &str, byte: u8) -> String {
+        format!("{}:{}", key_id, BASE64.encode([byte; KEY_LEN]))
+    }
+
+    #[test]
+    fn test_sealed_payloads_round_trip() {
+        let crypto = AesGcmCrypto::from_key_list(&[key_entry("k1", 1)]).unwrap();
+        let event = r#"{"type":"Created","data":{"customer_id":"c-1"}}"#;
+
+        let sealed = seal_payload(&crypto, event).unwrap();
+        assert!(!sealed.contains("customer_id"));
+        assert_eq!(sealed_key_id(&sealed), Some("k1"));
+        assert_eq!(open_payload(Some(&crypto), &sealed).unwrap(), event);
+
+        // Plaintext written before encryption was enabled passes through
+        assert_eq!(sealed_key_id(event), None);
+        assert!(matches!(open_payload(Some(&crypto), event).unwrap(), Cow::Borrowed(_)));
+        assert_eq!(open_payload(None, event).unwrap(), event);
+        assert!(open_payload(None, &sealed).is_err());
+    }
+
+    #[test]
+    fn test_rotated_keys_still_open_old_payloads() {
+        let old = AesGcmCrypto::from_key_list(&[key_entry("k1", 1)]).unwrap();
+        let sealed_before = seal_payload(&old, "{}").unwrap();
+
+        let rotated = AesGcmCrypto::from_key_list(&[key_entry("k2", 2), key_entry("k1", 1)]).unwrap();
+        assert_eq!(rotated.active_key_id(), "k2");
+        assert_eq!(rotated.key_ids(), vec!["k1", "k2"]);
+        assert_eq!(sealed_key_id(&seal_payload(&rotated, "{}").unwrap()), Some("k2"));
+        assert_eq!(open_payload(Some(&rotated), &sealed_before).unwrap(), "{}");
+
+        // Dropping the old key makes its payloads unreadable
+        let k2_only = AesGcmCrypto::from_key_list(&[key_entry("k2", 2)]).unwrap();
+        assert!(open_payload(Some(&k2_only), &sealed_before).is_err());
+
+        assert!(AesGcmCrypto::from_key_list(&[key_entry("k1", 1), key_entry("k1", 2)]).is_err());
+        assert!(AesGcmCrypto::from_key_list(&["k1:c2hvcnQ="]).is_err());
+        assert!(AesGcmCrypto::from_key_list::<&str>(&[]).is_err());
+    }
+}
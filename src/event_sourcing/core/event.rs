@@ -13,6 +13,15 @@ use anyhow::Result;
 //
 // ============================================================================
 
+/// Metadata key holding the region an event originated in
+pub const ORIGIN_REGION_KEY: &str = "origin_region";
+
+/// Metadata key holding the id of the command that produced an event
+pub const COMMAND_ID_KEY: &str = "command_id";
+
+/// Metadata key holding the time before which an event must not be published
+pub const PUBLISH_AFTER_KEY: &str = "publish_after";
+
 /// Generic Event Envelope - wraps any domain event with metadata
 ///
 /// Type Parameter:
@@ -82,6 +91,43 @@ impl<E> EventEnvelope<E> {
         self.metadata.insert(key, value);
         self
     }
+
+    pub fn with_origin_region(self, region: &str) -> Self {
+        self.with_metadata(ORIGIN_REGION_KEY.to_string(), region.to_string())
+    }
+
+    /// Region the event was first written in, if known
+    pub fn origin_region(&self) -> Option<&str> {
+        self.metadata.get(ORIGIN_REGION_KEY).map(|s| s.as_str())
+    }
+
+    /// Id of the command that produced the event, if the caller gave one
+    pub fn command_id(&self) -> Option<Uuid> {
+        self.metadata.get(COMMAND_ID_KEY).and_then(|id| Uuid::parse_str(id).ok())
+    }
+
+    /// Publish the event no earlier than `at` (the outbox row's `publish_after`)
+    pub fn with_publish_after(self, at: DateTime<Utc>) -> Self {
+        self.with_metadata(PUBLISH_AFTER_KEY.to_string(), at.to_rfc3339())
+    }
+
+    /// Time before which the event must not be published, if scheduled
+    pub fn publish_after(&self) -> Option<DateTime<Utc>> {
+        self.metadata
+            .get(PUBLISH_AFTER_KEY)
+            .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+            .map(|at| at.with_timezone(&Utc))
+    }
+}
+
+impl<E: DomainEvent> EventEnvelope<E> {
+    /// Envelope typed by its event: `event_type` and `event_version` are
+    /// the event's own type name and schema version
+    pub fn for_event(aggregate_id: Uuid, sequence_number: i64, event_data: E, correlation_id: Uuid) -> Self {
+        let event_type = event_data.event_type_name().to_string();
+        let event_version = event_data.schema_version();
+        Self { event_version, ..Self::new(aggregate_id, sequence_number, event_type, event_data, correlation_id) }
+    }
 }
 
 // ============================================================================
@@ -91,10 +137,120 @@ impl<E> EventEnvelope<E> {
 /// Generic Domain Event trait
 ///
 /// All domain events must implement this trait to be used with the event store.
+/// Event structs derive it: `#[derive(DomainEvent)]`, optionally with
+/// `#[event(type = "OrderShipped", version = 2)]` (defaults: the struct's
+/// name, version 1).
 pub trait DomainEvent: Serialize + for<'de> Deserialize<'de> + Clone + Send + Sync {
     fn event_type() -> &'static str where Self: Sized;
     fn event_version() -> i32 where Self: Sized { 1 }
+
+    /// Event type stored with this event (event_store.event_type, outbox)
+    ///
+    /// The type's own name by default; enums declared with
+    /// `domain_event_enum!` use the variant payload's, e.g. "OrderShipped".
+    fn event_type_name(&self) -> &'static str where Self: Sized {
+        Self::event_type()
+    }
+
+    /// Schema version stored with this event (event_store.event_version)
+    ///
+    /// The type's own version by default; `domain_event_enum!` enums use
+    /// the variant payload's, upcast to on load (see upcasting.rs).
+    fn schema_version(&self) -> i32 where Self: Sized {
+        Self::event_version()
+    }
+
+    /// Both stored type names of this event (outbox `aggregate_event_type`
+    /// and `variant_type`), from `event_type` and `event_type_name`
+    fn type_names(&self) -> EventTypeNames where Self: Sized {
+        EventTypeNames {
+            aggregate_event_type: Self::event_type(),
+            variant_type: self.event_type_name(),
+        }
+    }
+
+    /// Fields of this event holding personal data (see personal_data.rs)
+    ///
+    /// None by default; `domain_event_enum!` variants declare theirs with
+    /// `personal_data [field, ...]`.
+    fn personal_data_fields(&self) -> &'static [&'static str] where Self: Sized {
+        &[]
+    }
+}
+
+/// Type names an event is stored under
+///
+/// For an aggregate's event enum the two differ ("CustomerEvent" /
+/// "CustomerRegistered"); a standalone event struct has its name in both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventTypeNames {
+    /// Type the payload deserializes into, e.g. "CustomerEvent"
+    pub aggregate_event_type: &'static str,
+    /// Type of this particular event, e.g. "CustomerRegistered"
+    pub variant_type: &'static str,
+}
+
+// `#[derive(DomainEvent)]` for event structs (scylladb_cdc_macros)
+pub use scylladb_cdc_macros::DomainEvent;
+
+/// Declare the enum of an aggregate's events, one variant per event struct
+///
+/// Implements DomainEvent with `event_type_name` and `schema_version` taken
+/// from the payload's `EVENT_TYPE` and `EVENT_VERSION` (payloads
+/// `#[derive(DomainEvent)]`), so a variant is stored under the same name and
+/// version as its struct, and adds `EVENT_TYPES` listing every name and
+/// `EVENT_VERSIONS` every (name, version).
+///
+/// A variant followed by `personal_data [field, ...]` names the payload
+/// fields the event store encrypts with the aggregate's data key.
+macro_rules! domain_event_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($variant:ident($payload:ident) $(personal_data [$($field:ident),* $(,)?])?),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $($variant($payload),)*
+        }
+
+        impl $name {
+            /// Stored event type of every variant
+            #[allow(dead_code)]
+            pub const EVENT_TYPES: &'static [&'static str] = &[$($payload::EVENT_TYPE),*];
+
+            /// Stored event type and current schema version of every variant
+            #[allow(dead_code)]
+            pub const EVENT_VERSIONS: &'static [(&'static str, i32)] = &[$(($payload::EVENT_TYPE, $payload::EVENT_VERSION)),*];
+        }
+
+        impl $crate::event_sourcing::DomainEvent for $name {
+            fn event_type() -> &'static str {
+                stringify!($name)
+            }
+
+            fn event_type_name(&self) -> &'static str {
+                match self {
+                    $($name::$variant(_) => $payload::EVENT_TYPE,)*
+                }
+            }
+
+            fn schema_version(&self) -> i32 {
+                match self {
+                    $($name::$variant(_) => $payload::EVENT_VERSION,)*
+                }
+            }
+
+            fn personal_data_fields(&self) -> &'static [&'static str] {
+                match self {
+                    $($name::$variant(_) => &[$($(stringify!($field)),*)?],)*
+                }
+            }
+        }
+    };
 }
+pub(crate) use domain_event_enum;
 
 // ============================================================================
 // Event Serialization Helpers
@@ -113,6 +269,9 @@ pub fn deserialize_event<E: for<'de> Deserialize<'de>>(json: &str) -> Result<E>
 // ============================================================================
 
 /// Upcaster trait for evolving event schemas
+///
+/// Single JSON transformations; the store and the CDC relay chain theirs
+/// through an UpcasterRegistry (upcasting.rs).
 pub trait EventUpcaster {
     fn upcast(&self, from_version: i32, event_json: &str) -> Result<String>;
 }
@@ -125,13 +284,56 @@ pub trait EventUpcaster {
 mod tests {
     use super::*;
 
-    #[derive(Serialize, Deserialize, Clone, Debug)]
+    #[derive(Serialize, Deserialize, Clone, Debug, DomainEvent)]
     struct TestEvent {
         data: String,
     }
 
-    impl DomainEvent for TestEvent {
-        fn event_type() -> &'static str { "TestEvent" }
+    #[derive(Serialize, Deserialize, Clone, Debug, DomainEvent)]
+    #[event(type = "TestRenamed", version = 3)]
+    struct TestEventV3 {}
+
+    domain_event_enum! {
+        #[derive(Serialize, Deserialize, Clone, Debug)]
+        enum TestEvents {
+            Happened(TestEvent) personal_data [data],
+            Repeated(TestEvent),
+            Renamed(TestEventV3),
+        }
+    }
+
+    #[test]
+    fn test_event_type_names() {
+        let event = TestEvent { data: "test".to_string() };
+        assert_eq!(event.event_type_name(), "TestEvent");
+
+        let wrapped = TestEvents::Happened(event);
+        assert_eq!(wrapped.event_type_name(), "TestEvent");
+        assert_eq!(TestEvents::event_type(), "TestEvents");
+        assert_eq!(TestEvents::EVENT_TYPES, ["TestEvent", "TestEvent", "TestRenamed"]);
+        assert_eq!(wrapped.personal_data_fields(), ["data"]);
+        assert!(TestEvents::Repeated(TestEvent { data: "test".to_string() }).personal_data_fields().is_empty());
+    }
+
+    #[test]
+    fn test_derived_type_and_version() {
+        assert_eq!((TestEvent::event_type(), TestEvent::event_version()), ("TestEvent", 1));
+        assert_eq!((TestEventV3::event_type(), TestEventV3::event_version()), ("TestRenamed", 3));
+        assert_eq!(TestEvents::Renamed(TestEventV3 {}).event_type_name(), "TestRenamed");
+        assert_eq!(TestEvents::Renamed(TestEventV3 {}).schema_version(), 3);
+        assert_eq!(TestEvents::EVENT_VERSIONS, [("TestEvent", 1), ("TestEvent", 1), ("TestRenamed", 3)]);
+    }
+
+    #[test]
+    fn test_type_names_come_from_the_event() {
+        let wrapped = TestEvents::Renamed(TestEventV3 {});
+        let names = wrapped.type_names();
+        assert_eq!((names.aggregate_event_type, names.variant_type), ("TestEvents", "TestRenamed"));
+        assert_eq!(TestEventV3 {}.type_names(), EventTypeNames { aggregate_event_type: "TestRenamed", variant_type: "TestRenamed" });
+
+        let envelope = EventEnvelope::for_event(Uuid::new_v4(), 1, wrapped, Uuid::new_v4());
+        assert_eq!(envelope.event_type, names.variant_type);
+        assert_eq!(envelope.event_version, 3);
     }
 
     #[test]
@@ -155,6 +357,16 @@ mod tests {
         assert_eq!(envelope.sequence_number, 1);
         assert_eq!(envelope.event_type, "TestEvent");
         assert_eq!(envelope.correlation_id, correlation_id);
+        assert_eq!(envelope.publish_after(), None);
+    }
+
+    #[test]
+    fn test_publish_after_roundtrip() {
+        let at = DateTime::parse_from_rfc3339("2026-03-02T09:00:00Z").unwrap().with_timezone(&Utc);
+        let envelope = EventEnvelope::new(Uuid::new_v4(), 1, "TestEvent".to_string(), TestEvent { data: "test".to_string() }, Uuid::new_v4())
+            .with_publish_after(at);
+        assert_eq!(envelope.publish_after(), Some(at));
+        assert_eq!(envelope.with_metadata(PUBLISH_AFTER_KEY.to_string(), "tomorrow".to_string()).publish_after(), None);
     }
 
     #[test]
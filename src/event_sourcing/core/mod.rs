@@ -14,8 +14,22 @@
 
 // Private module declarations
 mod aggregate;
+mod as_of;
+mod context;
+mod crypto;
+mod diff;
 mod event;
+mod ordering;
+mod upcasting;
 
 // Re-export core types for public API
 pub use aggregate::AggregateRoot;
-pub use event::{DomainEvent, EventEnvelope, serialize_event, deserialize_event, EventUpcaster};
+pub use as_of::AsOf;
+pub use context::{CommandContext, Deadline, DeadlineExceeded};
+pub(crate) use context::with_deadline;
+pub use crypto::{EventCrypto, AesGcmCrypto, seal_payload, open_payload, sealed_key_id, ENCRYPTION_KEY_METADATA_KEY};
+pub use diff::{diff_json, diff_at_version, FieldChange, StateDiff};
+pub use event::{DomainEvent, EventEnvelope, EventTypeNames, serialize_event, deserialize_event, EventUpcaster, COMMAND_ID_KEY, ORIGIN_REGION_KEY, PUBLISH_AFTER_KEY};
+pub(crate) use event::domain_event_enum;
+pub use upcasting::{UpcasterRegistry, UpcastStep};
+pub use ordering::{HybridLogicalClock, HybridTimestamp, EventOrderKey, order_events, HLC_METADATA_KEY};
@@ -0,0 +1,336 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+use chrono::Utc;
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::event::EventEnvelope;
+
+// ============================================================================
+// Event Ordering - Skew-Tolerant Ordering for Projections
+// ============================================================================
+//
+// Event timestamps come from the wall clock of whichever node handled the
+// command. Clocks drift, so ordering by `timestamp` alone can place
+// sequence 5 of an aggregate before sequence 4.
+//
+// Rules:
+// 1. Within one aggregate, `sequence_number` is authoritative - it is
+//    assigned under optimistic concurrency and never skewed.
+// 2. Across aggregates, events are ordered by a Hybrid Logical Clock (HLC)
+//    timestamp: physical milliseconds plus a logical counter. An HLC never
+//    goes backwards, even if the wall clock does.
+// 3. Ties are broken by (aggregate_id, sequence_number) so the resulting
+//    order is deterministic on every node.
+//
+// `EventOrderKey` is the plain (hlc, aggregate_id, sequence_number) order.
+// Rule 1 needs context a single key lacks: `order_events` gives every event
+// the running maximum HLC of its aggregate before comparing keys, so a
+// skewed later event never sorts before its predecessor.
+//
+// `EventStore::append` stamps every event with the store's clock (events
+// replicated from another region keep their stamp and are observed), so the
+// `hlc` metadata entry is persisted with the event and on events_by_time.
+// Events without one (written before stamping) fall back to the envelope
+// timestamp as the physical component.
+//
+// Users: the events_by_time feed orders each page with `order_events`;
+// projections buffer outbox events for a short window and apply them in
+// `EventOrderKey` order (see projections/manager.rs).
+//
+// ============================================================================
+
+/// Metadata key under which an envelope's HLC timestamp is stored
+pub const HLC_METADATA_KEY: &str = "hlc";
+
+/// Hybrid logical clock timestamp (physical millis + logical counter)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct HybridTimestamp {
+    pub physical_ms: i64,
+    pub logical: u32,
+}
+
+impl HybridTimestamp {
+    pub fn new(physical_ms: i64, logical: u32) -> Self {
+        Self { physical_ms, logical }
+    }
+
+    /// Parse the "<physical_ms>.<logical>" encoding used in envelope metadata
+    pub fn parse(value: &str) -> Option<Self> {
+        let (physical, logical) = value.split_once('.')?;
+        Some(Self {
+            physical_ms: physical.parse().ok()?,
+            logical: logical.parse().ok()?,
+        })
+    }
+
+    /// HLC timestamp of an envelope (metadata if stamped, else wall clock)
+    pub fn from_envelope<E>(envelope: &EventEnvelope<E>) -> Self {
+        envelope
+            .metadata
+            .get(HLC_METADATA_KEY)
+            .and_then(|v| Self::parse(v))
+            .unwrap_or_else(|| Self::new(envelope.timestamp.timestamp_millis(), 0))
+    }
+}
+
+impl std::fmt::Display for HybridTimestamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.physical_ms, self.logical)
+    }
+}
+
+/// Hybrid Logical Clock
+///
+/// `now()` stamps local events; `observe()` merges a timestamp received from
+/// another node so that anything stamped afterwards sorts after it.
+pub struct HybridLogicalClock {
+    last: Mutex<HybridTimestamp>,
+}
+
+impl HybridLogicalClock {
+    pub fn new() -> Self {
+        Self {
+            last: Mutex::new(HybridTimestamp::new(0, 0)),
+        }
+    }
+
+    /// Timestamp for a new local event
+    pub fn now(&self) -> HybridTimestamp {
+        self.tick(Utc::now().timestamp_millis(), None)
+    }
+
+    /// Merge a remote timestamp and return a local timestamp after it
+    pub fn observe(&self, remote: HybridTimestamp) -> HybridTimestamp {
+        self.tick(Utc::now().timestamp_millis(), Some(remote))
+    }
+
+    fn tick(&self, wall_ms: i64, remote: Option<HybridTimestamp>) -> HybridTimestamp {
+        let mut last = self.last.lock().unwrap();
+
+        let remote_ms = remote.map(|r| r.physical_ms).unwrap_or(i64::MIN);
+        let physical_ms = wall_ms.max(last.physical_ms).max(remote_ms);
+
+        let logical = if physical_ms == last.physical_ms && physical_ms == remote_ms {
+            last.logical.max(remote.map(|r| r.logical).unwrap_or(0)) + 1
+        } else if physical_ms == last.physical_ms {
+            last.logical + 1
+        } else if physical_ms == remote_ms {
+            remote.map(|r| r.logical).unwrap_or(0) + 1
+        } else {
+            0
+        };
+
+        *last = HybridTimestamp::new(physical_ms, logical);
+        *last
+    }
+}
+
+impl Default for HybridLogicalClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E> EventEnvelope<E> {
+    /// Stamp the envelope with an HLC timestamp
+    pub fn with_hlc(self, hlc: HybridTimestamp) -> Self {
+        self.with_metadata(HLC_METADATA_KEY.to_string(), hlc.to_string())
+    }
+}
+
+/// Total order key for an event across aggregates
+///
+/// Ordered lexicographically by (hlc, aggregate_id, sequence_number) - the
+/// field order below. Use `order_events` to also keep each aggregate's own
+/// sequence order when HLCs are skewed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct EventOrderKey {
+    pub hlc: HybridTimestamp,
+    pub aggregate_id: Uuid,
+    pub sequence_number: i64,
+}
+
+impl EventOrderKey {
+    pub fn of<E>(envelope: &EventEnvelope<E>) -> Self {
+        Self {
+            hlc: HybridTimestamp::from_envelope(envelope),
+            aggregate_id: envelope.aggregate_id,
+            sequence_number: envelope.sequence_number,
+        }
+    }
+}
+
+/// Order events from many aggregates for a projection or global stream
+///
+/// Per-aggregate sequence order is always preserved, even when a later
+/// event carries an earlier (skewed) timestamp. Streams are then merged by
+/// HLC, using each aggregate's running maximum so that a skewed event never
+/// pulls its successors ahead of other aggregates' events.
+pub fn order_events<E>(events: Vec<EventEnvelope<E>>) -> Vec<EventEnvelope<E>> {
+    let total = events.len();
+
+    // Group by aggregate, each group in sequence order
+    let mut streams: BTreeMap<Uuid, Vec<EventEnvelope<E>>> = BTreeMap::new();
+    for event in events {
+        streams.entry(event.aggregate_id).or_default().push(event);
+    }
+
+    let mut queues: Vec<VecDeque<(EventOrderKey, EventEnvelope<E>)>> = streams
+        .into_values()
+        .map(|mut stream| {
+            stream.sort_by_key(|e| e.sequence_number);
+            let mut running = HybridTimestamp::new(i64::MIN, 0);
+            stream
+                .into_iter()
+                .map(|e| {
+                    let mut key = EventOrderKey::of(&e);
+                    running = running.max(key.hlc);
+                    key.hlc = running;
+                    (key, e)
+                })
+                .collect()
+        })
+        .collect();
+
+    // K-way merge on the head of each aggregate stream
+    let mut ordered = Vec::with_capacity(total);
+    loop {
+        let next = queues
+            .iter()
+            .enumerate()
+            .filter_map(|(i, q)| q.front().map(|(key, _)| (i, *key)))
+            .min_by_key(|(_, key)| *key);
+
+        match next {
+            Some((index, _)) => {
+                if let Some((_, event)) = queues[index].pop_front() {
+                    ordered.push(event);
+                }
+            }
+            None => break,
+        }
+    }
+
+    ordered
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn envelope(aggregate_id: Uuid, seq: i64, millis: i64) -> EventEnvelope<String> {
+        let mut e = EventEnvelope::new(
+            aggregate_id,
+            seq,
+            "TestEvent".to_string(),
+            format!("{}-{}", aggregate_id, seq),
+            Uuid::new_v4(),
+        );
+        e.timestamp = Utc.timestamp_millis_opt(millis).unwrap();
+        e
+    }
+
+    #[test]
+    fn test_hlc_is_monotonic() {
+        let clock = HybridLogicalClock::new();
+        let a = clock.now();
+        let b = clock.now();
+        let c = clock.now();
+
+        assert!(a < b);
+        assert!(b < c);
+    }
+
+    #[test]
+    fn test_hlc_observe_moves_past_remote() {
+        let clock = HybridLogicalClock::new();
+        let future = HybridTimestamp::new(
+            (Utc::now() + Duration::hours(1)).timestamp_millis(),
+            7,
+        );
+
+        let local = clock.observe(future);
+        assert!(local > future);
+
+        // Subsequent local events stay ahead of the remote timestamp
+        assert!(clock.now() > future);
+    }
+
+    #[test]
+    fn test_hlc_round_trip_through_metadata() {
+        let hlc = HybridTimestamp::new(1_700_000_000_000, 3);
+        let e = envelope(Uuid::new_v4(), 1, 0).with_hlc(hlc);
+
+        assert_eq!(HybridTimestamp::from_envelope(&e), hlc);
+        assert_eq!(HybridTimestamp::parse("12.4"), Some(HybridTimestamp::new(12, 4)));
+        assert_eq!(HybridTimestamp::parse("garbage"), None);
+    }
+
+    #[test]
+    fn test_same_aggregate_ordered_by_sequence_despite_skew() {
+        let id = Uuid::new_v4();
+        // Sequence 2 was written by a node whose clock is behind
+        let events = vec![envelope(id, 2, 1_000), envelope(id, 1, 5_000)];
+
+        let ordered = order_events(events);
+
+        assert_eq!(ordered[0].sequence_number, 1);
+        assert_eq!(ordered[1].sequence_number, 2);
+    }
+
+    #[test]
+    fn test_cross_aggregate_ordered_by_time() {
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let events = vec![
+            envelope(a, 1, 3_000),
+            envelope(b, 1, 1_000),
+            envelope(b, 2, 4_000),
+            envelope(a, 2, 2_000), // skewed backwards
+        ];
+
+        let ordered = order_events(events);
+        let keys: Vec<(Uuid, i64)> = ordered.iter().map(|e| (e.aggregate_id, e.sequence_number)).collect();
+
+        // a:2 inherits a:1's running HLC (3000) so it stays after a:1
+        assert_eq!(keys, vec![(b, 1), (a, 1), (a, 2), (b, 2)]);
+    }
+
+    #[test]
+    fn test_order_key_tie_break_is_deterministic() {
+        let a = Uuid::from_u128(1);
+        let b = Uuid::from_u128(2);
+
+        let ka = EventOrderKey::of(&envelope(a, 9, 1_000));
+        let kb = EventOrderKey::of(&envelope(b, 1, 1_000));
+
+        assert!(ka < kb);
+    }
+
+    #[test]
+    fn test_order_key_is_transitive_across_aggregates() {
+        let a = Uuid::from_u128(1);
+        let b = Uuid::from_u128(2);
+
+        // a:1 < b:1 < a:2 by HLC; a:1 < a:2 must agree with that chain
+        let a1 = EventOrderKey::of(&envelope(a, 1, 1_000));
+        let b1 = EventOrderKey::of(&envelope(b, 1, 2_000));
+        let a2 = EventOrderKey::of(&envelope(a, 2, 3_000));
+        assert!(a1 < b1 && b1 < a2 && a1 < a2);
+
+        // A skewed a:2 sorts by its HLC, not its sequence
+        let skewed = EventOrderKey::of(&envelope(a, 2, 500));
+        assert!(skewed < a1);
+        assert!(skewed < b1);
+
+        let mut keys = vec![a2, b1, skewed, a1];
+        keys.sort();
+        assert_eq!(keys, vec![skewed, a1, b1, a2]);
+    }
+}
@@ -0,0 +1,249 @@
+use anyhow::{bail, Result};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+// ============================================================================
+// Upcaster Registry - Stored Events Brought to Their Current Version
+// ============================================================================
+//
+// Events are stored with the schema version they were written in
+// (`event_version`, from `DomainEvent::schema_version`). When an event's
+// schema changes, its version goes up and an upcaster step is registered
+// that turns the stored data of the old version into the next one:
+//
+//   registry.register("OrderShipped", 1, |data| ...)   v1 -> v2
+//   registry.register("OrderShipped", 2, |data| ...)   v2 -> v3
+//
+// `upcast` chains the steps from the stored version to the latest, so old
+// events are only ever migrated on read. The latest version of a type is
+// the one declared (`with_event_versions(OrderEvent::EVENT_VERSIONS)`), or
+// one past its last step. The registry is consulted by:
+//
+//   EventStore     events loaded (load_events, streams, time feed) before
+//                  they are deserialized into the event type
+//   CDC relay      outbox payloads before publishing, with the upcast
+//                  version in the event version header
+//
+// `validate` fails on gaps: every version from 1 up to the latest needs a
+// step, and no step may start at or past the latest. The service validates
+// the domain's registry at startup, so a missing step stops it before the
+// first old event would fail. Events already at (or, mid-rollout, past) the
+// latest version pass unchanged.
+//
+// Steps see the event's fields: events are stored as the serde-tagged
+// `{"type": "OrderShipped", "data": {..}}` of their enum, and `upcast`
+// hands a step the `data` object and puts its result back under the tag.
+// Other payloads (untagged, or a tag without data) are handed over whole.
+//
+// Personal-data fields are still sealed when steps see them, in the event
+// store and the CDC relay alike (the store opens them after upcasting, the
+// relay never does): a step may move or drop such a field, not read it.
+//
+// ============================================================================
+
+/// One step: the data of an event at `from_version`, as `from_version + 1`
+pub type UpcastStep = Arc<dyn Fn(Value) -> Result<Value> + Send + Sync>;
+
+/// Upcaster steps by (event type, from version), and the latest versions
+#[derive(Clone, Default)]
+pub struct UpcasterRegistry {
+    steps: BTreeMap<(String, i32), UpcastStep>,
+    latest: BTreeMap<String, i32>,
+}
+
+impl std::fmt::Debug for UpcasterRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UpcasterRegistry")
+            .field("steps", &self.steps.keys().collect::<Vec<_>>())
+            .field("latest", &self.latest)
+            .finish()
+    }
+}
+
+impl UpcasterRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare the current version of `event_type`
+    pub fn with_latest(mut self, event_type: &str, version: i32) -> Self {
+        self.latest.insert(event_type.to_string(), version);
+        self
+    }
+
+    /// Declare the current versions of an event enum (`EVENT_VERSIONS`)
+    pub fn with_event_versions(self, versions: &[(&str, i32)]) -> Self {
+        versions
+            .iter()
+            .fold(self, |registry, (event_type, version)| registry.with_latest(event_type, *version))
+    }
+
+    /// Upcast `event_type` data from `from_version` to `from_version + 1`
+    pub fn register<F>(mut self, event_type: &str, from_version: i32, step: F) -> Self
+    where
+        F: Fn(Value) -> Result<Value> + Send + Sync + 'static,
+    {
+        self.steps.insert((event_type.to_string(), from_version), Arc::new(step));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Version events of `event_type` are upcast to; None when unknown
+    pub fn latest_version(&self, event_type: &str) -> Option<i32> {
+        let last_step = self
+            .steps
+            .keys()
+            .filter(|(step_type, _)| step_type == event_type)
+            .map(|(_, from_version)| from_version + 1)
+            .max();
+        self.latest.get(event_type).copied().or(last_step)
+    }
+
+    /// Whether an event stored at `version` needs upcasting
+    pub fn is_outdated(&self, event_type: &str, version: i32) -> bool {
+        self.latest_version(event_type).is_some_and(|latest| version < latest)
+    }
+
+    /// Fail on gaps in, or steps past the end of, any chain
+    pub fn validate(&self) -> Result<()> {
+        let event_types: BTreeSet<&str> = self
+            .latest
+            .keys()
+            .map(String::as_str)
+            .chain(self.steps.keys().map(|(event_type, _)| event_type.as_str()))
+            .collect();
+
+        let mut problems = Vec::new();
+        for event_type in event_types {
+            let Some(latest) = self.latest_version(event_type) else {
+                continue;
+            };
+            for version in 1..latest {
+                if !self.steps.contains_key(&(event_type.to_string(), version)) {
+                    problems.push(format!("{} has no upcaster from v{} (latest v{})", event_type, version, latest));
+                }
+            }
+            for (_, from_version) in self.steps.keys().filter(|(step_type, _)| step_type == event_type) {
+                if *from_version < 1 || *from_version >= latest {
+                    problems.push(format!("{} upcaster from v{} is outside v1..v{}", event_type, from_version, latest));
+                }
+            }
+        }
+        if !problems.is_empty() {
+            bail!("Incomplete upcaster chains: {}", problems.join("; "));
+        }
+        Ok(())
+    }
+
+    /// The stored event of `event_type` at `version`, at the latest version
+    ///
+    /// Steps get the fields of a tagged event, see the module docs.
+    pub fn upcast(&self, event_type: &str, version: i32, event: Value) -> Result<(Value, i32)> {
+        let Some(latest) = self.latest_version(event_type) else {
+            return Ok((event, version));
+        };
+        let (tag, mut data) = untag(event);
+        let mut current = version;
+        while current < latest {
+            let Some(step) = self.steps.get(&(event_type.to_string(), current)) else {
+                bail!("No upcaster for {} from v{} (latest v{})", event_type, current, latest);
+            };
+            data = step(data).map_err(|e| e.context(format!("Upcasting {} from v{}", event_type, current)))?;
+            current += 1;
+        }
+        Ok((retag(tag, data), current))
+    }
+}
+
+/// Split a tagged event `{"type": .., "data": {..}}` into its tag and data
+fn untag(event: Value) -> (Option<serde_json::Map<String, Value>>, Value) {
+    match event {
+        Value::Object(mut object)
+            if object.len() == 2 && object.contains_key("type") && object.get("data").is_some_and(Value::is_object) =>
+        {
+            let data = object.remove("data").unwrap_or_default();
+            (Some(object), data)
+        }
+        event => (None, event),
+    }
+}
+
+/// The upcast data back under its tag
+fn retag(tag: Option<serde_json::Map<String, Value>>, data: Value) -> Value {
+    match tag {
+        Some(mut object) => {
+            object.insert("data".to_string(), data);
+            Value::Object(object)
+        }
+        None => data,
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rename(from: &'static str, to: &'static str) -> impl Fn(Value) -> Result<Value> + Send + Sync {
+        move |mut data| {
+            let value = data.as_object_mut().and_then(|o| o.remove(from)).unwrap_or(Value::Null);
+            data[to] = value;
+            Ok(data)
+        }
+    }
+
+    #[test]
+    fn test_chains_to_latest_and_rejects_gaps() {
+        let registry = UpcasterRegistry::new()
+            .with_event_versions(&[("OrderShipped", 3), ("OrderCreated", 1)])
+            .register("OrderShipped", 1, rename("tracking", "tracking_number"))
+            .register("OrderShipped", 2, rename("carrier", "carrier_code"));
+        registry.validate().unwrap();
+
+        let (data, version) = registry
+            .upcast("OrderShipped", 1, json!({ "tracking": "T1", "carrier": "DHL" }))
+            .unwrap();
+        assert_eq!((data, version), (json!({ "tracking_number": "T1", "carrier_code": "DHL" }), 3));
+        assert_eq!(registry.upcast("OrderShipped", 2, json!({ "carrier": "UPS" })).unwrap().1, 3);
+        // Current, newer and unknown events pass unchanged
+        assert_eq!(registry.upcast("OrderShipped", 4, json!({})).unwrap(), (json!({}), 4));
+        assert_eq!(registry.upcast("OrderCreated", 1, json!({})).unwrap().1, 1);
+        assert!(!registry.is_outdated("OrderNoted", 1));
+        assert!(registry.is_outdated("OrderShipped", 2));
+
+        let gap = UpcasterRegistry::new()
+            .with_latest("OrderShipped", 3)
+            .register("OrderShipped", 2, rename("carrier", "carrier_code"))
+            .register("OrderCancelled", 1, rename("why", "reason"))
+            .register("OrderCancelled", 3, rename("by", "cancelled_by"));
+        let error = gap.validate().unwrap_err().to_string();
+        assert!(error.contains("OrderShipped has no upcaster from v1 (latest v3)"), "{}", error);
+        assert!(error.contains("OrderCancelled has no upcaster from v2 (latest v4)"), "{}", error);
+        assert!(gap.upcast("OrderShipped", 1, json!({})).is_err());
+
+        let past_latest = UpcasterRegistry::new().with_latest("OrderCreated", 1).register("OrderCreated", 1, Ok);
+        assert!(past_latest.validate().is_err());
+    }
+
+    #[test]
+    fn test_steps_see_fields_of_tagged_events() {
+        let registry = UpcasterRegistry::new()
+            .with_latest("OrderShipped", 2)
+            .register("OrderShipped", 1, rename("tracking", "tracking_number"));
+
+        // As stored by the event store: the enum's serde tag around the fields
+        let stored = json!({ "type": "Shipped", "data": { "tracking": "T1" } });
+        let (event, version) = registry.upcast("OrderShipped", 1, stored).unwrap();
+
+        assert_eq!(event, json!({ "type": "Shipped", "data": { "tracking_number": "T1" } }));
+        assert_eq!(version, 2);
+    }
+}
@@ -0,0 +1,174 @@
+use anyhow::Result;
+use chrono::{Days, NaiveDate, Utc};
+use futures_util::TryStreamExt;
+use scylla::client::session::Session;
+use scylla::value::Counter;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::tenant::TenantContext;
+
+// ============================================================================
+// Aggregate Catalog - Aggregates per Type and Per-Type Statistics
+// ============================================================================
+//
+// Capacity planning wants to know how many aggregates of each type exist,
+// how many events they get per day and which aggregates are the largest.
+// The EventStore keeps two tables for that:
+//
+//   aggregate_catalog    type and created_at of each aggregate, written in
+//                        the batch of its first event
+//   daily_event_counts   events appended per type and day (counters),
+//                        bumped after each successful append
+//
+// `AggregateCatalog::stats` scans aggregate_catalog (counts per type) and
+// aggregate_sequence (sizes), so it is meant for the occasional operator
+// request, not for hot paths. The daily counters are best effort: a failed
+// increment is logged, a retried one may count twice. Aggregates created
+// before the catalog existed have no row and show up without a type.
+//
+// ============================================================================
+
+/// Days of event counts unless asked otherwise
+pub const DEFAULT_STATS_DAYS: u32 = 7;
+
+/// Largest aggregates listed unless asked otherwise
+pub const DEFAULT_STATS_TOP: usize = 10;
+
+/// Events appended to aggregates of one type on one day
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DailyEvents {
+    pub aggregate_type: String,
+    pub day: NaiveDate,
+    pub events: i64,
+}
+
+/// Event count of one aggregate
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LargestAggregate {
+    pub aggregate_id: Uuid,
+    /// None for aggregates created before the catalog
+    pub aggregate_type: Option<String>,
+    pub events: i64,
+}
+
+/// Aggregate counts, event rates and the largest aggregates
+#[derive(Debug, Clone, Serialize)]
+pub struct CatalogStats {
+    pub aggregates_by_type: BTreeMap<String, i64>,
+    /// Newest day first
+    pub events_per_day: Vec<DailyEvents>,
+    /// Most events first
+    pub largest: Vec<LargestAggregate>,
+}
+
+/// Read side of aggregate_catalog and daily_event_counts
+pub struct AggregateCatalog {
+    session: Arc<Session>,
+    tenant: TenantContext,
+}
+
+impl AggregateCatalog {
+    pub fn new(session: Arc<Session>, tenant: TenantContext) -> Self {
+        Self { session, tenant }
+    }
+
+    /// Statistics over the last `days` days and the `top` largest aggregates
+    pub async fn stats(&self, days: u32, top: usize) -> Result<CatalogStats> {
+        let mut aggregates_by_type = BTreeMap::new();
+        let mut catalog = HashMap::new();
+        let mut rows = self
+            .session
+            .query_iter(format!("SELECT aggregate_id, aggregate_type FROM {}", self.tenant.table("aggregate_catalog")), &[])
+            .await?
+            .rows_stream::<(Uuid, String)>()?;
+        while let Some((aggregate_id, aggregate_type)) = rows.try_next().await? {
+            *aggregates_by_type.entry(aggregate_type.clone()).or_insert(0) += 1;
+            catalog.insert(aggregate_id, aggregate_type);
+        }
+
+        let first_day = Utc::now().date_naive() - Days::new(days.saturating_sub(1) as u64);
+        let mut rows = self
+            .session
+            .query_iter(format!("SELECT aggregate_type, day, events FROM {}", self.tenant.table("daily_event_counts")), &[])
+            .await?
+            .rows_stream::<(String, NaiveDate, Counter)>()?;
+        let mut counts = Vec::new();
+        while let Some((aggregate_type, day, Counter(events))) = rows.try_next().await? {
+            counts.push(DailyEvents { aggregate_type, day, events });
+        }
+
+        let mut rows = self
+            .session
+            .query_iter(format!("SELECT aggregate_id, current_sequence FROM {}", self.tenant.table("aggregate_sequence")), &[])
+            .await?
+            .rows_stream::<(Uuid, i64)>()?;
+        let mut sizes = Vec::new();
+        while let Some(size) = rows.try_next().await? {
+            sizes.push(size);
+            // Keep memory bounded on large tables
+            if sizes.len() >= top.max(1) * 64 {
+                sizes = largest(sizes, top);
+            }
+        }
+
+        Ok(CatalogStats {
+            aggregates_by_type,
+            events_per_day: recent_days(counts, first_day),
+            largest: largest(sizes, top)
+                .into_iter()
+                .map(|(aggregate_id, events)| LargestAggregate {
+                    aggregate_id,
+                    aggregate_type: catalog.get(&aggregate_id).cloned(),
+                    events,
+                })
+                .collect(),
+        })
+    }
+}
+
+/// The `top` (aggregate, events) with the most events, most first
+fn largest(sizes: impl IntoIterator<Item = (Uuid, i64)>, top: usize) -> Vec<(Uuid, i64)> {
+    let mut heap = BinaryHeap::with_capacity(top + 1);
+    for (aggregate_id, events) in sizes {
+        heap.push(Reverse((events, aggregate_id)));
+        if heap.len() > top {
+            heap.pop();
+        }
+    }
+    heap.into_sorted_vec().into_iter().map(|Reverse((events, aggregate_id))| (aggregate_id, events)).collect()
+}
+
+/// Counts from `first_day` on, newest day first
+fn recent_days(mut counts: Vec<DailyEvents>, first_day: NaiveDate) -> Vec<DailyEvents> {
+    counts.retain(|count| count.day >= first_day);
+    counts.sort_by(|a, b| b.day.cmp(&a.day).then_with(|| a.aggregate_type.cmp(&b.aggregate_type)));
+    counts
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_largest_and_recent_days() {
+        let ids: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+        let sizes = ids.iter().zip([3, 50, 7, 50, 1]).map(|(id, events)| (*id, events));
+        let top = largest(sizes, 3);
+        assert_eq!(top.iter().map(|(_, events)| *events).collect::<Vec<_>>(), vec![50, 50, 7]);
+        assert_eq!(top[2].0, ids[2]);
+        assert!(largest([(ids[0], 1)], 0).is_empty());
+
+        let day = |d| NaiveDate::from_ymd_opt(2026, 3, d).unwrap();
+        let count = |aggregate_type: &str, d, events| DailyEvents { aggregate_type: aggregate_type.to_string(), day: day(d), events };
+        let recent = recent_days(vec![count("Order", 1, 5), count("Order", 3, 2), count("Customer", 3, 4), count("Order", 2, 9)], day(2));
+        assert_eq!(recent, vec![count("Customer", 3, 4), count("Order", 3, 2), count("Order", 2, 9)]);
+    }
+}
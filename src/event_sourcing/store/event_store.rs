@@ -1,11 +1,30 @@
 use scylla::client::session::Session;
+use scylla::statement::prepared::PreparedStatement;
+use scylla::value::Counter;
 use std::sync::Arc;
 use uuid::Uuid;
 use anyhow::{Result, bail};
-use chrono::Utc;
+use chrono::{NaiveDate, Utc};
+use futures_util::{Stream, StreamExt, TryStreamExt};
+use std::collections::{hash_map::Entry, BTreeMap, HashMap};
+use std::future::Future;
 use std::marker::PhantomData;
+use tracing::Instrument;
 
-use crate::event_sourcing::core::{DomainEvent, EventEnvelope, AggregateRoot, serialize_event};
+use crate::event_sourcing::core::{DomainEvent, EventEnvelope, AggregateRoot, AsOf, Deadline, StateDiff, diff_at_version, serialize_event, ORIGIN_REGION_KEY};
+use crate::event_sourcing::core::{open_payload, seal_payload, EventCrypto, UpcasterRegistry, ENCRYPTION_KEY_METADATA_KEY};
+use crate::event_sourcing::core::with_deadline;
+use crate::event_sourcing::core::{order_events, HybridLogicalClock, HybridTimestamp, HLC_METADATA_KEY};
+use super::fencing::WriteFence;
+use super::lifecycle::{LifecycleEvent, LifecycleHooks};
+use super::personal_data::{open_personal_data, seal_personal_data, PersonalDataVault};
+use super::sharding::ShardLayout;
+use super::tenant::TenantContext;
+use super::time_feed::{feed_bucket, validate_feed_range, EventFeedFilter, FeedCursor};
+use super::snapshots::SnapshotPolicy;
+use crate::db::{LatencyMonitor, Operation, StatementCache};
+use crate::metrics::MetricsHandle;
+use crate::projections::{query_page, Page, Paging, StalenessTracker};
 
 // ============================================================================
 // Generic Event Store - Repository for Events
@@ -19,28 +38,265 @@ use crate::event_sourcing::core::{DomainEvent, EventEnvelope, AggregateRoot, ser
 // Responsibilities:
 // 1. Append events to event_store table (append-only)
 // 2. Load event history for aggregates
-// 3. Ensure optimistic concurrency control
+// 3. Ensure optimistic concurrency control (LWT on aggregate_sequence)
 // 4. Write to outbox for publishing
 //
+// Every operation has a `*_within` variant taking the caller's Deadline.
+// Queries are cancelled once it passes and return DeadlineExceeded; the
+// append batch is never started after the deadline.
+//
+// Concurrency: an append first claims its version range with a lightweight
+// transaction on aggregate_sequence (`IF current_sequence = <expected>`, or
+// `IF NOT EXISTS` for new aggregates). Only the winner writes its events and
+// outbox rows; a loser fails with a typed ConcurrencyConflict (inside
+// anyhow::Error, use `downcast_ref`) carrying the current version, so the
+// command can be retried on fresh state. The claim and the event batch live
+// in different partitions and cannot share one conditional batch: if the
+// event batch fails before it was applied (rejected as invalid, unprepared,
+// unavailable replicas, deadline passed before it was sent), the claim is
+// released again (also by LWT, so a newer claim is never undone; a new
+// aggregate's claim is released by deleting its row, so the next attempt
+// can create it with `IF NOT EXISTS` again). A batch
+// that timed out or failed ambiguously may still be applied by the
+// batchlog replay, so its claim is kept: releasing it would let another
+// writer take the same sequence numbers, overwritten once the replay lands.
+// The aggregate then conflicts until the batch lands or `repair_sequence`
+// (`scylladb_cdc repair-sequence`) rolls aggregate_sequence back to its last
+// stored event.
+//
+// With a region configured, appended events are stamped with their origin
+// region (unless already set) so the CDC relay of other regions skips them.
+//
+// Every appended event is stamped with a hybrid logical clock timestamp
+// (`hlc` metadata, also written to the outbox) that orders events across
+// aggregates (see ordering.rs). Events that already carry one, e.g.
+// replicated from another region, keep it and move the clock past it.
+// Stores built by the SystemBuilder share one clock.
+//
+// With a WriteFence attached, every append first checks the fence's cached
+// epoch (no round trip) and fails with FencedOut once a newer deployment
+// took over.
+//
+// With a StalenessTracker attached, the event time of every appended event
+// advances the head that read model staleness is measured against.
+//
+// With LifecycleHooks attached, appends that create or close an aggregate
+// trigger the hooks in the background (see lifecycle.rs).
+//
+// Appends are counted per aggregate type and outcome (appended, conflict,
+// failed) on the injected MetricsHandle, and traced in an
+// `event_store_append` span with the correlation and causation id of the
+// appended events.
+//
+// With a SnapshotPolicy attached, command handlers hydrate aggregates from
+// the newest snapshot plus the events after it, and appends crossing the
+// snapshot frequency save a new snapshot (see snapshots.rs). With crypto
+// attached, snapshots are sealed like event payloads.
+//
+// With a LatencyMonitor attached, the latency and outcome of every query and
+// append feed the CDC throttle's view of cluster stress.
+//
+// All queries run as prepared statements from a StatementCache (one per
+// store, or shared through `with_statement_cache`), so appends and LWTs are
+// routed to a replica of the aggregate and the append batch is not
+// prepared again on every call.
+//
+// Event histories are read in pages of `load_page_size` rows (default
+// 1000), never in one response: load_events_stream yields them one by one,
+// and aggregates are rebuilt from that stream event by event, so even huge
+// histories are never held in memory as a whole.
+//
+// With a ShardLayout of N > 1 shards, each aggregate's events live in one of
+// event_store_0 .. event_store_{N-1}, chosen by hashing the aggregate id
+// (see sharding.rs). Unsharded stores use the event_store table.
+//
+// Every table is resolved through the store's TenantContext (see
+// tenant.rs): a tenant with its own keyspace or table prefix gets its own
+// events, aggregate_sequence, snapshots and outbox. The default context is
+// the session keyspace with the plain table names.
+//
+// With a PersonalDataVault attached, the personal data fields of events are
+// sealed with the aggregate's own data key, and forget_personal_data deletes
+// that key and the aggregate's snapshots (see personal_data.rs).
+//
+// Appends of events stamped with a command id also write the command id and
+// the resulting version to processed_commands, in the same batch. Rows
+// expire after 24 hours (table TTL); within that window a repeated command
+// id is answered from there instead of being applied again.
+//
+// Every event is also written to events_by_time in the same batch, the feed
+// read by `load_events_in_range` (see time_feed.rs). The first append of an
+// aggregate records it in aggregate_catalog, and every append bumps the
+// per-day counters of daily_event_counts (see catalog.rs).
+//
+// With an UpcasterRegistry attached, loaded events stored at an older
+// schema version are upcast to the latest before they are deserialized,
+// and come back with the latest `event_version` (see upcasting.rs).
+//
 // ============================================================================
 
+/// Rows per page when reading an aggregate's events
+pub const DEFAULT_LOAD_PAGE_SIZE: i32 = 1000;
+
+/// Another writer appended to the aggregate since `expected_version` was read
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("Concurrency conflict on {aggregate_id}: expected version {expected_version}, but current is {current_version}")]
+pub struct ConcurrencyConflict {
+    pub aggregate_id: Uuid,
+    pub expected_version: i64,
+    pub current_version: i64,
+}
+
 pub struct EventStore<E: DomainEvent> {
     session: Arc<Session>,
+    statements: Arc<StatementCache>,
     aggregate_type_name: String,  // e.g., "Order", "Customer", "Product"
     topic_name: String,            // e.g., "order-events", "customer-events"
+    region: Option<String>,        // e.g., "eu-west" in active-active deployments
+    clock: Arc<HybridLogicalClock>,
+    fence: Option<Arc<WriteFence>>,
+    staleness: Option<Arc<StalenessTracker>>,
+    lifecycle_hooks: Option<Arc<LifecycleHooks>>,
+    latency: Option<Arc<LatencyMonitor>>,
+    snapshots: Option<SnapshotPolicy>,
+    shards: ShardLayout,
+    tenant: TenantContext,
+    load_page_size: i32,
+    /// Seals event payloads at rest; None stores them as plain JSON
+    crypto: Option<Arc<dyn EventCrypto>>,
+    /// Seals personal data fields per aggregate; None stores them as they are
+    personal_data: Option<Arc<PersonalDataVault>>,
+    /// Brings loaded events of older schema versions up to date
+    upcasters: Option<Arc<UpcasterRegistry>>,
+    metrics: MetricsHandle,
     _phantom: PhantomData<E>,
 }
 
 impl<E: DomainEvent> EventStore<E> {
-    pub fn new(session: Arc<Session>, aggregate_type_name: &str, topic_name: &str) -> Self {
+    pub fn new(session: Arc<Session>, tenant: TenantContext, aggregate_type_name: &str, topic_name: &str) -> Self {
         Self {
+            statements: Arc::new(StatementCache::new(session.clone())),
             session,
             aggregate_type_name: aggregate_type_name.to_string(),
             topic_name: topic_name.to_string(),
+            region: None,
+            clock: Arc::new(HybridLogicalClock::new()),
+            fence: None,
+            staleness: None,
+            lifecycle_hooks: None,
+            latency: None,
+            snapshots: None,
+            shards: ShardLayout::default(),
+            tenant,
+            load_page_size: DEFAULT_LOAD_PAGE_SIZE,
+            crypto: None,
+            personal_data: None,
+            upcasters: None,
+            metrics: MetricsHandle::noop(),
             _phantom: PhantomData,
         }
     }
 
+    /// Stamp appended events with the region they originate in
+    pub fn with_region(mut self, region: &str) -> Self {
+        self.region = Some(region.to_string());
+        self
+    }
+
+    /// Stamp appended events from a clock shared with other stores
+    pub fn with_clock(mut self, clock: Arc<HybridLogicalClock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Reject appends once a newer deployment has taken over the fence
+    pub fn with_fence(mut self, fence: Arc<WriteFence>) -> Self {
+        self.fence = Some(fence);
+        self
+    }
+
+    /// Report appended events as the head for read model staleness
+    pub fn with_staleness_tracker(mut self, tracker: Arc<StalenessTracker>) -> Self {
+        self.staleness = Some(tracker);
+        self
+    }
+
+    /// Run infrastructure hooks when an append creates or closes an aggregate
+    pub fn with_lifecycle_hooks(mut self, hooks: Arc<LifecycleHooks>) -> Self {
+        self.lifecycle_hooks = Some(hooks);
+        self
+    }
+
+    /// Snapshot aggregates periodically and hydrate from snapshots
+    pub fn with_snapshot_policy(mut self, policy: SnapshotPolicy) -> Self {
+        self.snapshots = Some(policy);
+        self
+    }
+
+    /// Record query latencies/outcomes as a cluster stress signal
+    pub fn with_latency_monitor(mut self, monitor: Arc<LatencyMonitor>) -> Self {
+        self.latency = Some(monitor);
+        self
+    }
+
+    /// Spread aggregates over the event tables of `layout`
+    pub fn with_shards(mut self, layout: ShardLayout) -> Self {
+        self.shards = layout;
+        self
+    }
+
+    /// Prepare statements in a cache shared with other stores of the session
+    pub fn with_statement_cache(mut self, statements: Arc<StatementCache>) -> Self {
+        self.statements = statements;
+        self
+    }
+
+    /// Rows fetched per page when loading events
+    pub fn with_load_page_size(mut self, page_size: i32) -> Self {
+        self.load_page_size = page_size.max(1);
+        self
+    }
+
+    /// Encrypt event payloads in the event table and the outbox
+    pub fn with_crypto(mut self, crypto: Arc<dyn EventCrypto>) -> Self {
+        self.crypto = Some(crypto);
+        self
+    }
+
+    /// Encrypt personal data fields with per-aggregate keys
+    pub fn with_personal_data(mut self, vault: Arc<PersonalDataVault>) -> Self {
+        self.personal_data = Some(vault);
+        self
+    }
+
+    /// Upcast loaded events to the latest schema version of their type
+    pub fn with_upcasters(mut self, upcasters: Arc<UpcasterRegistry>) -> Self {
+        self.upcasters = Some(upcasters);
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: MetricsHandle) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    pub fn tenant(&self) -> &TenantContext {
+        &self.tenant
+    }
+
+    /// The tenant's event table holding `aggregate_id`
+    fn event_table(&self, aggregate_id: Uuid) -> String {
+        self.tenant.table(&self.shards.table_for(aggregate_id))
+    }
+
+    /// Run a Scylla query, timed by the latency monitor if attached
+    async fn observed<T, Err>(&self, query: impl Future<Output = std::result::Result<T, Err>>) -> std::result::Result<T, Err> {
+        match self.latency {
+            Some(ref monitor) => monitor.time(query).await,
+            None => query.await,
+        }
+    }
+
     /// Append events to the event store
     /// Returns the new version number after appending
     pub async fn append_events(
@@ -49,24 +305,87 @@ impl<E: DomainEvent> EventStore<E> {
         expected_version: i64,
         events: Vec<EventEnvelope<E>>,
         publish_to_outbox: bool,
+    ) -> Result<i64> {
+        self.append_events_within(None, aggregate_id, expected_version, events, publish_to_outbox).await
+    }
+
+    /// Append events, abandoning the write if `deadline` passes
+    pub async fn append_events_within(
+        &self,
+        deadline: Option<&Deadline>,
+        aggregate_id: Uuid,
+        expected_version: i64,
+        events: Vec<EventEnvelope<E>>,
+        publish_to_outbox: bool,
     ) -> Result<i64> {
         if events.is_empty() {
             bail!("Cannot append empty event list");
         }
 
-        // Check optimistic concurrency
-        let current_version = self.get_current_version(aggregate_id).await?;
-        if current_version != expected_version {
-            bail!(
-                "Concurrency conflict: expected version {}, but current is {}",
-                expected_version,
-                current_version
-            );
+        let span = tracing::info_span!(
+            "event_store_append",
+            aggregate_type = %self.aggregate_type_name,
+            aggregate_id = %aggregate_id,
+            correlation_id = %events[0].correlation_id,
+            causation_id = events[0].causation_id.map(tracing::field::display),
+            expected_version = expected_version,
+            event_count = events.len(),
+        );
+        self.write_events(deadline, aggregate_id, expected_version, events, publish_to_outbox)
+            .instrument(span)
+            .await
+    }
+
+    async fn write_events(
+        &self,
+        deadline: Option<&Deadline>,
+        aggregate_id: Uuid,
+        expected_version: i64,
+        events: Vec<EventEnvelope<E>>,
+        publish_to_outbox: bool,
+    ) -> Result<i64> {
+        // Blue/green: make sure no newer deployment has taken over
+        if let Some(ref fence) = self.fence {
+            fence.check()?;
+        }
+
+        let command_id = events.first().and_then(|e| e.command_id());
+        let statements = match self.append_statements(aggregate_id, publish_to_outbox, command_id.is_some(), expected_version == 0).await {
+            Ok(statements) => statements,
+            Err(e) => {
+                self.metrics.record_event_store_append(&self.aggregate_type_name, "failed", events.len());
+                return Err(e);
+            }
+        };
+
+        // Data key of the aggregate, if any event carries personal data
+        let mut personal_key = None;
+        if let Some(ref vault) = self.personal_data {
+            if events.iter().any(|e| !e.event_data.personal_data_fields().is_empty()) {
+                match with_deadline(deadline, "event_store.personal_data_key", vault.key_for_write(aggregate_id)).await {
+                    Ok(key) => personal_key = Some(key),
+                    Err(e) => {
+                        self.metrics.record_event_store_append(&self.aggregate_type_name, "failed", events.len());
+                        return Err(e);
+                    }
+                }
+            }
+        }
+
+        // Optimistic concurrency: claim the version range (LWT)
+        let claimed_version = expected_version + events.len() as i64;
+        if let Err(e) = self.claim_versions(deadline, aggregate_id, expected_version, claimed_version).await {
+            if e.downcast_ref::<ConcurrencyConflict>().is_some() {
+                self.metrics.record_event_store_append(&self.aggregate_type_name, "conflict", events.len());
+            } else {
+                self.metrics.record_event_store_append(&self.aggregate_type_name, "failed", events.len());
+            }
+            return Err(e);
         }
 
         // Prepare batch for atomic write
-        let mut batch = scylla::statement::batch::Batch::default();
-        let mut values: Vec<Box<dyn scylla::serialize::row::SerializeRow>> = vec![];
+        let mut batch = self.statements.batch(Operation::Append, scylla::statement::batch::BatchType::Logged);
+        let mut values: Vec<Box<dyn scylla::serialize::row::SerializeRow + Send + Sync>> = vec![];
 
         let mut new_version = expected_version;
 
@@ -74,16 +393,55 @@ impl<E: DomainEvent> EventStore<E> {
         for event_envelope in &events {
             new_version += 1;
 
-            // Serialize event data once
-            let event_json = serialize_event(&event_envelope.event_data)?;
+            // Serialize (and seal) event data once
+            let personal_fields = event_envelope.event_data.personal_data_fields();
+            let event_json = match personal_key {
+                Some(ref key) if !personal_fields.is_empty() => {
+                    let mut event_value = serde_json::to_value(&event_envelope.event_data)?;
+                    seal_personal_data(&mut event_value, personal_fields, key.as_ref())?;
+                    serde_json::to_string(&event_value)?
+                }
+                _ => serialize_event(&event_envelope.event_data)?,
+            };
+            let mut metadata = event_envelope.metadata.clone();
+            let hlc = match metadata.get(HLC_METADATA_KEY).and_then(|v| HybridTimestamp::parse(v)) {
+                Some(stamped) => {
+                    self.clock.observe(stamped);
+                    stamped
+                }
+                None => {
+                    let now = self.clock.now();
+                    metadata.insert(HLC_METADATA_KEY.to_string(), now.to_string());
+                    now
+                }
+            };
+            let event_json = match self.crypto {
+                Some(ref crypto) => {
+                    metadata.insert(ENCRYPTION_KEY_METADATA_KEY.to_string(), crypto.active_key_id().to_string());
+                    seal_payload(crypto.as_ref(), &event_json)?
+                }
+                None => event_json,
+            };
 
-            // Insert into event_store
-            batch.append_statement(
-                "INSERT INTO event_store (
-                    aggregate_id, sequence_number, event_id, event_type, event_version,
-                    event_data, causation_id, correlation_id, timestamp
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
-            );
+            // Outbox type names come from the event; the envelope's name should match
+            let type_names = event_envelope.event_data.type_names();
+            if event_envelope.event_type != type_names.variant_type {
+                tracing::warn!(
+                    aggregate_id = %aggregate_id,
+                    event_type = %event_envelope.event_type,
+                    variant_type = type_names.variant_type,
+                    "Envelope event type differs from the event's own type name"
+                );
+            }
+
+            // Events replayed from another region keep their original origin
+            let origin_region = event_envelope
+                .origin_region()
+                .map(|r| r.to_string())
+                .or_else(|| self.region.clone());
+
+            // Insert into the aggregate's event table
+            batch.append_statement(statements.event.clone());
 
             // Event store values
             values.push(Box::new((
@@ -96,48 +454,103 @@ impl<E: DomainEvent> EventStore<E> {
                 event_envelope.causation_id,
                 event_envelope.correlation_id,
                 event_envelope.timestamp,
+                origin_region.clone(),
+                event_envelope.user_id,
+                audit_metadata(&metadata),
+            )));
+
+            // Same event in the time feed
+            batch.append_statement(statements.by_time.clone());
+            values.push(Box::new((
+                self.aggregate_type_name.clone(),
+                feed_bucket(event_envelope.timestamp),
+                event_envelope.timestamp,
+                aggregate_id,
+                new_version,
+                event_envelope.event_id,
+                event_envelope.event_type.clone(),
+                event_envelope.event_version,
+                event_json.clone(),
+                event_envelope.causation_id,
+                event_envelope.correlation_id,
+                origin_region.clone(),
+                event_envelope.user_id,
+                audit_metadata(&metadata),
             )));
 
             // If publishing to outbox, add outbox entry
-            if publish_to_outbox {
-                batch.append_statement(
-                    "INSERT INTO outbox_messages (
-                        id, aggregate_id, aggregate_type, event_id, event_type, event_version,
-                        payload, topic, partition_key, causation_id, correlation_id,
-                        created_at, attempts
-                    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 0)"
-                );
+            if let Some(ref insert_outbox) = statements.outbox {
+                batch.append_statement(insert_outbox.clone());
 
                 let partition_key = aggregate_id.to_string();
 
                 // Outbox values
-                values.push(Box::new((
-                    Uuid::new_v4(), // outbox message id
+                values.push(Box::new(OutboxRow {
+                    id: Uuid::new_v4(),
                     aggregate_id,
-                    self.aggregate_type_name.clone(),
-                    event_envelope.event_id,
-                    event_envelope.event_type.clone(),
-                    event_envelope.event_version,
-                    event_json,
-                    self.topic_name.clone(),
+                    aggregate_type: self.aggregate_type_name.clone(),
+                    event_id: event_envelope.event_id,
+                    event_type: event_envelope.event_type.clone(),
+                    event_version: event_envelope.event_version,
+                    sequence_number: new_version,
+                    payload: event_json,
+                    topic: self.topic_name.clone(),
                     partition_key,
-                    event_envelope.causation_id,
-                    event_envelope.correlation_id,
-                    Utc::now(),
-                )));
+                    causation_id: event_envelope.causation_id,
+                    correlation_id: event_envelope.correlation_id,
+                    origin_region,
+                    created_at: Utc::now(),
+                    aggregate_event_type: type_names.aggregate_event_type,
+                    variant_type: type_names.variant_type,
+                    publish_after: event_envelope.publish_after(),
+                    hlc: hlc.to_string(),
+                }));
             }
         }
 
-        // Insert/Update aggregate sequence (use INSERT for upsert behavior)
-        batch.append_statement(
-            "INSERT INTO aggregate_sequence (aggregate_id, current_sequence, updated_at) VALUES (?, ?, ?)"
-        );
+        // Remember the command that produced these versions
+        if let (Some(command_id), Some(insert_command)) = (command_id, statements.command) {
+            batch.append_statement(insert_command);
+            values.push(Box::new((aggregate_id, command_id, new_version, Utc::now())));
+        }
 
-        // Sequence update values
-        values.push(Box::new((aggregate_id, new_version, Utc::now())));
+        // A new aggregate enters the catalog with its first event
+        if let (Some(insert_catalog), Some(first)) = (statements.catalog, events.first()) {
+            batch.append_statement(insert_catalog);
+            values.push(Box::new((aggregate_id, self.aggregate_type_name.clone(), first.timestamp)));
+        }
+
+        // Never send the batch once the deadline passed; cancelled in flight
+        // it may still be applied
+        if let Some(Err(e)) = deadline.map(|deadline| deadline.check("event_store.append")) {
+            self.metrics.record_event_store_append(&self.aggregate_type_name, "failed", events.len());
+            // The batch is never sent; don't hold its row values across the release
+            drop(values);
+            self.release_versions(aggregate_id, expected_version, new_version).await;
+            return Err(e.into());
+        }
 
         // Execute batch
-        self.session.batch(&batch, values).await?;
+        let written = with_deadline(deadline, "event_store.append", async {
+            self.observed(self.session.batch(&batch, values)).await?;
+            Ok(())
+        }).await;
+        if let Err(e) = written {
+            self.metrics.record_event_store_append(&self.aggregate_type_name, "failed", events.len());
+            if batch_not_applied(&e) {
+                self.release_versions(aggregate_id, expected_version, new_version).await;
+            } else {
+                tracing::error!(
+                    aggregate_id = %aggregate_id,
+                    claimed = new_version,
+                    error = %e,
+                    "Append outcome unknown - keeping claimed versions until the batch lands or repair_sequence"
+                );
+            }
+            return Err(e);
+        }
+        self.metrics.record_event_store_append(&self.aggregate_type_name, "appended", events.len());
+        self.count_daily_events(&events).await;
 
         tracing::info!(
             aggregate_id = %aggregate_id,
@@ -147,66 +560,372 @@ impl<E: DomainEvent> EventStore<E> {
             "✅ Appended events to event store"
         );
 
+        if let Some(ref tracker) = self.staleness {
+            if let Some(newest) = events.iter().map(|e| e.timestamp).max() {
+                tracker.observe_event(newest);
+            }
+        }
+
+        if let Some(ref hooks) = self.lifecycle_hooks {
+            let transitions = hooks.transitions(expected_version, events.iter().map(|e| e.event_type.as_str()));
+            for (stage, event_type) in transitions {
+                hooks.dispatch(LifecycleEvent {
+                    aggregate_type: self.aggregate_type_name.clone(),
+                    aggregate_id,
+                    stage,
+                    event_type,
+                    version: new_version,
+                    correlation_id: events[0].correlation_id,
+                });
+            }
+        }
+
         Ok(new_version)
     }
 
-    /// Load all events for an aggregate
-    pub async fn load_events(&self, aggregate_id: Uuid) -> Result<Vec<EventEnvelope<E>>> {
-        let result = self.session
-            .query_unpaged(
-                "SELECT aggregate_id, sequence_number, event_id, event_type, event_version,
-                        event_data, causation_id, correlation_id, timestamp
-                 FROM event_store
-                 WHERE aggregate_id = ?
-                 ORDER BY sequence_number ASC",
+    /// Prepared inserts of the append batch
+    async fn append_statements(
+        &self,
+        aggregate_id: Uuid,
+        publish_to_outbox: bool,
+        record_command: bool,
+        first_append: bool,
+    ) -> Result<AppendStatements> {
+        let insert_event = self
+            .statements
+            .statement(Operation::Append, &format!(
+                "INSERT INTO {} (
+                    aggregate_id, sequence_number, event_id, event_type, event_version,
+                    event_data, causation_id, correlation_id, timestamp, origin_region,
+                    user_id, metadata
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                self.event_table(aggregate_id)
+            ))
+            .await?;
+        let insert_by_time = self
+            .statements
+            .statement(Operation::Append, &INSERT_BY_TIME.replace("{events_by_time}", &self.tenant.table("events_by_time")))
+            .await?;
+        let insert_outbox = if publish_to_outbox {
+            let cql = INSERT_OUTBOX.replace("{outbox}", &self.tenant.table("outbox_messages"));
+            Some(self.statements.statement(Operation::Append, &cql).await?)
+        } else {
+            None
+        };
+        let insert_command = if record_command {
+            let cql = format!(
+                "INSERT INTO {} (aggregate_id, command_id, version, processed_at) VALUES (?, ?, ?, ?)",
+                self.tenant.table("processed_commands")
+            );
+            Some(self.statements.statement(Operation::Append, &cql).await?)
+        } else {
+            None
+        };
+        let insert_catalog = if first_append {
+            let cql = format!(
+                "INSERT INTO {} (aggregate_id, aggregate_type, created_at) VALUES (?, ?, ?)",
+                self.tenant.table("aggregate_catalog")
+            );
+            Some(self.statements.statement(Operation::Append, &cql).await?)
+        } else {
+            None
+        };
+        Ok(AppendStatements {
+            event: insert_event,
+            by_time: insert_by_time,
+            outbox: insert_outbox,
+            command: insert_command,
+            catalog: insert_catalog,
+        })
+    }
+
+    /// Add appended events to daily_event_counts; failures are only logged
+    async fn count_daily_events(&self, events: &[EventEnvelope<E>]) {
+        let mut per_day: BTreeMap<NaiveDate, i64> = BTreeMap::new();
+        for envelope in events {
+            *per_day.entry(envelope.timestamp.date_naive()).or_insert(0) += 1;
+        }
+        let cql = format!(
+            "UPDATE {} SET events = events + ? WHERE aggregate_type = ? AND day = ?",
+            self.tenant.table("daily_event_counts")
+        );
+        for (day, count) in per_day {
+            let counted = self.statements.execute(Operation::Append, &cql, (Counter(count), &self.aggregate_type_name, day));
+            if let Err(e) = self.observed(counted).await {
+                tracing::warn!(aggregate_type = %self.aggregate_type_name, day = %day, error = %e, "Failed to count daily events");
+            }
+        }
+    }
+
+    /// Advance aggregate_sequence from `expected_version` to `new_version`
+    /// if nobody else did first
+    async fn claim_versions(
+        &self,
+        deadline: Option<&Deadline>,
+        aggregate_id: Uuid,
+        expected_version: i64,
+        new_version: i64,
+    ) -> Result<()> {
+        let result = with_deadline(deadline, "event_store.claim_versions", async {
+            if expected_version == 0 {
+                Ok(self.observed(self.statements
+                    .execute(
+                        Operation::Lwt,
+                        &format!(
+                            "INSERT INTO {} (aggregate_id, current_sequence, updated_at)
+                             VALUES (?, ?, ?) IF NOT EXISTS",
+                            self.tenant.table("aggregate_sequence")
+                        ),
+                        (aggregate_id, new_version, Utc::now()),
+                    ))
+                    .await?)
+            } else {
+                Ok(self.observed(self.statements
+                    .execute(
+                        Operation::Lwt,
+                        &format!(
+                            "UPDATE {} SET current_sequence = ?, updated_at = ?
+                             WHERE aggregate_id = ? IF current_sequence = ?",
+                            self.tenant.table("aggregate_sequence")
+                        ),
+                        (new_version, Utc::now(), aggregate_id, expected_version),
+                    ))
+                    .await?)
+            }
+        }).await?;
+
+        let (applied, current_version) = sequence_lwt_outcome(result)?;
+        if applied {
+            return Ok(());
+        }
+        Err(ConcurrencyConflict {
+            aggregate_id,
+            expected_version,
+            // A concurrent writer may have created the row without us seeing it
+            current_version: current_version.unwrap_or(expected_version),
+        }
+        .into())
+    }
+
+    /// Undo our claim after the event batch failed; never undoes a newer claim
+    async fn release_versions(&self, aggregate_id: Uuid, expected_version: i64, new_version: i64) {
+        match self.roll_back_sequence(aggregate_id, new_version, expected_version).await {
+            Ok((true, _)) => tracing::debug!(aggregate_id = %aggregate_id, version = expected_version, "Released claimed versions"),
+            Ok((false, current)) => tracing::warn!(
+                aggregate_id = %aggregate_id,
+                claimed = new_version,
+                current = ?current,
+                "Claimed versions were taken over before they could be released"
+            ),
+            Err(e) => tracing::error!(
+                aggregate_id = %aggregate_id,
+                claimed = new_version,
+                error = %e,
+                "Failed to release claimed versions - aggregate_sequence is ahead of its events"
+            ),
+        }
+    }
+
+    /// Roll aggregate_sequence back to the last stored event of an
+    /// aggregate whose append failed ambiguously
+    ///
+    /// Only run once the failed batch can no longer be replayed (the
+    /// batchlog replays within minutes). Returns the version rolled back to,
+    /// or None when the sequence matched the stored events.
+    pub async fn repair_sequence(&self, aggregate_id: Uuid) -> Result<Option<i64>> {
+        let claimed = self.get_current_version(aggregate_id).await?;
+        let result = self.statements
+            .execute(
+                Operation::Read,
+                &format!(
+                    "SELECT sequence_number FROM {} WHERE aggregate_id = ? ORDER BY sequence_number DESC LIMIT 1",
+                    self.event_table(aggregate_id)
+                ),
                 (aggregate_id,),
             )
             .await?;
+        let stored = result.into_rows_result()?.maybe_first_row::<(i64,)>()?.map_or(0, |(version,)| version);
+        if stored >= claimed {
+            return Ok(None);
+        }
 
-        let mut events = Vec::new();
+        let (applied, current) = self.roll_back_sequence(aggregate_id, claimed, stored).await?;
+        if !applied {
+            bail!("aggregate_sequence of {} moved to {:?} during repair", aggregate_id, current);
+        }
+        tracing::warn!(aggregate_id = %aggregate_id, claimed = claimed, version = stored, "Rolled back unwritten claimed versions");
+        Ok(Some(stored))
+    }
 
-        let rows_result = match result.into_rows_result() {
-            Ok(rows) => rows,
-            Err(_) => return Ok(events), // No rows
+    /// Set aggregate_sequence back from `claimed` to `version` (LWT, only
+    /// while nobody claimed further). Back to 0 the row is deleted: a new
+    /// aggregate's claim is `IF NOT EXISTS`.
+    async fn roll_back_sequence(&self, aggregate_id: Uuid, claimed: i64, version: i64) -> Result<(bool, Option<i64>)> {
+        let table = self.tenant.table("aggregate_sequence");
+        let result = if version == 0 {
+            self.statements
+                .execute(
+                    Operation::Lwt,
+                    &format!("DELETE FROM {} WHERE aggregate_id = ? IF current_sequence = ?", table),
+                    (aggregate_id, claimed),
+                )
+                .await?
+        } else {
+            self.statements
+                .execute(
+                    Operation::Lwt,
+                    &format!(
+                        "UPDATE {} SET current_sequence = ?, updated_at = ?
+                         WHERE aggregate_id = ? IF current_sequence = ?",
+                        table
+                    ),
+                    (version, Utc::now(), aggregate_id, claimed),
+                )
+                .await?
         };
+        sequence_lwt_outcome(result)
+    }
+
+    /// Load all events for an aggregate
+    pub async fn load_events(&self, aggregate_id: Uuid) -> Result<Vec<EventEnvelope<E>>> {
+        self.load_events_within(None, aggregate_id).await
+    }
+
+    /// Load all events for an aggregate, bounded by `deadline`
+    pub async fn load_events_within(&self, deadline: Option<&Deadline>, aggregate_id: Uuid) -> Result<Vec<EventEnvelope<E>>> {
+        let events: Vec<_> =
+            with_deadline(deadline, "event_store.load_events", self.load_events_stream(aggregate_id).try_collect()).await?;
+        tracing::debug!("Loaded {} events for aggregate {}", events.len(), aggregate_id);
+        Ok(events)
+    }
 
-        for row in rows_result.rows::<(Uuid, i64, Uuid, String, i32, String, Option<Uuid>, Uuid, chrono::DateTime<Utc>)>()? {
-            let (agg_id, sequence_number, event_id, event_type, event_version, event_data_json, causation_id, correlation_id, timestamp) = row?;
+    /// Load the events after `sequence_number`, bounded by `deadline`
+    pub async fn load_events_after_within(
+        &self,
+        deadline: Option<&Deadline>,
+        aggregate_id: Uuid,
+        sequence_number: i64,
+    ) -> Result<Vec<EventEnvelope<E>>> {
+        let events = self.events_after_stream(aggregate_id, sequence_number).try_collect();
+        with_deadline(deadline, "event_store.load_events_after", events).await
+    }
 
-            tracing::debug!("Loaded event for aggregate {}: seq={}, type={}", agg_id, sequence_number, event_type);
+    /// All events of an aggregate in order, fetched page by page
+    ///
+    /// Only the current page is held in memory; a failed page fetch ends
+    /// the stream with the error.
+    pub fn load_events_stream(&self, aggregate_id: Uuid) -> impl Stream<Item = Result<EventEnvelope<E>>> + Send + '_ {
+        // Sequence numbers start at 1
+        self.events_after_stream(aggregate_id, 0)
+    }
 
-            // Parse event data based on type
-            let event_data: E = serde_json::from_str(&event_data_json)?;
+    fn events_after_stream(&self, aggregate_id: Uuid, sequence_number: i64) -> impl Stream<Item = Result<EventEnvelope<E>>> + Send + '_ {
+        let cql = format!(
+            "SELECT {} FROM {} WHERE aggregate_id = ? AND sequence_number > ? ORDER BY sequence_number ASC",
+            EVENT_COLUMNS,
+            self.event_table(aggregate_id)
+        );
 
-            let envelope = EventEnvelope {
-                event_id,
-                aggregate_id: agg_id,
-                sequence_number,
-                event_type,
-                event_version,
-                event_data,
-                causation_id,
-                correlation_id,
-                user_id: None,
-                timestamp,
-                metadata: std::collections::HashMap::new(),
+        futures_util::stream::once(async move {
+            // One key lookup per load; Some(None) once the aggregate was forgotten
+            let personal_key = match self.personal_data {
+                Some(ref vault) => Some(vault.key(aggregate_id).await?),
+                None => None,
             };
+            let mut statement = self.statements.statement(Operation::Read, &cql).await?;
+            statement.set_page_size(self.load_page_size);
+            let pager = self.observed(self.session.execute_iter(statement, (aggregate_id, sequence_number))).await?;
+            let rows = pager.rows_stream::<EventRow>()?;
+            Ok::<_, anyhow::Error>(rows.map(move |row| {
+                let personal_key = personal_key.as_ref().map(|key| key.as_deref().map(|key| key as &dyn EventCrypto));
+                parse_event_row(row?, self.crypto.as_deref(), personal_key, self.upcasters.as_deref())
+            }))
+        })
+        .try_flatten()
+    }
+
+    /// Events of all aggregates of this type with a timestamp in `[from, to)`
+    ///
+    /// Read from events_by_time one minute bucket at a time. Each page is in
+    /// HLC order (`order_events`): per aggregate in sequence order, across
+    /// aggregates by their HLC stamps rather than the skewed wall clocks.
+    /// A `next_cursor` is only valid for the same range and filter.
+    pub async fn load_events_in_range(
+        &self,
+        from: chrono::DateTime<Utc>,
+        to: chrono::DateTime<Utc>,
+        filter: &EventFeedFilter,
+        paging: &Paging,
+    ) -> Result<Page<EventEnvelope<E>>> {
+        validate_feed_range(from, to)?;
+        let limit = paging.page_size() as usize;
+        let mut cursor = match paging.cursor {
+            Some(ref cursor) => FeedCursor::decode(cursor, from, to)?,
+            None => FeedCursor::start(from),
+        };
+        let cql = format!(
+            "SELECT {} FROM {} WHERE aggregate_type = ? AND bucket = ? AND timestamp >= ? AND timestamp < ?",
+            EVENT_COLUMNS,
+            self.tenant.table("events_by_time")
+        );
 
-            events.push(envelope);
+        let mut items = Vec::new();
+        // Data key per aggregate seen on this page (personal data stores only)
+        let mut personal_keys = HashMap::new();
+        while items.len() < limit && cursor.bucket < to {
+            let page = Paging { limit: Some((limit - items.len()) as i32), cursor: cursor.paging.take() };
+            let values = (&self.aggregate_type_name, cursor.bucket, from, to);
+            let (rows, next) = self.observed(query_page(&self.session, &cql, values, &page)).await?;
+            for row in rows.rows::<EventRow>()? {
+                let row = row?;
+                if !filter.matches(&row.3) {
+                    continue;
+                }
+                if let Some(ref vault) = self.personal_data {
+                    if let Entry::Vacant(entry) = personal_keys.entry(row.0) {
+                        entry.insert(vault.key(row.0).await?);
+                    }
+                }
+                let personal_key = personal_keys.get(&row.0).map(|key| key.as_deref().map(|key| key as &dyn EventCrypto));
+                items.push(parse_event_row(row, self.crypto.as_deref(), personal_key, self.upcasters.as_deref())?);
+            }
+            match next {
+                Some(paging_state) => cursor.paging = Some(paging_state),
+                None => cursor.next_bucket(),
+            }
         }
 
-        tracing::debug!("Loaded {} events for aggregate {}", events.len(), aggregate_id);
-        Ok(events)
+        let next_cursor = (cursor.bucket < to).then(|| cursor.encode());
+        Ok(Page { items: order_events(items), next_cursor })
+    }
+
+    /// Rebuild an aggregate from the stream of its events, onto `state`
+    async fn replay_stream<A>(&self, state: Option<A>, events: impl Stream<Item = Result<EventEnvelope<E>>>) -> Result<Option<A>>
+    where
+        A: AggregateRoot<Event = E>,
+        <A as AggregateRoot>::Error: std::fmt::Display,
+    {
+        events
+            .try_fold(state, |state, envelope| async move { A::replay_envelope(state, &envelope).map(Some) })
+            .await
     }
 
     /// Get current version of aggregate
     pub async fn get_current_version(&self, aggregate_id: Uuid) -> Result<i64> {
-        let result = self.session
-            .query_unpaged(
-                "SELECT current_sequence FROM aggregate_sequence WHERE aggregate_id = ?",
-                (aggregate_id,),
-            )
-            .await?;
+        self.get_current_version_within(None, aggregate_id).await
+    }
+
+    /// Get current version of aggregate, bounded by `deadline`
+    pub async fn get_current_version_within(&self, deadline: Option<&Deadline>, aggregate_id: Uuid) -> Result<i64> {
+        let result = with_deadline(deadline, "event_store.get_current_version", async {
+            self.observed(self.statements
+                .execute(
+                    Operation::Read,
+                    &format!("SELECT current_sequence FROM {} WHERE aggregate_id = ?", self.tenant.table("aggregate_sequence")),
+                    (aggregate_id,),
+                ))
+                .await
+        }).await?;
 
         let rows_result = match result.into_rows_result() {
             Ok(rows) => rows,
@@ -219,11 +938,253 @@ impl<E: DomainEvent> EventStore<E> {
         }
     }
 
+    /// Version the aggregate reached with the events of `command_id`, if
+    /// they were appended within the processed_commands TTL
+    pub async fn command_version_within(&self, deadline: Option<&Deadline>, aggregate_id: Uuid, command_id: Uuid) -> Result<Option<i64>> {
+        let result = with_deadline(deadline, "event_store.command_version", async {
+            self.observed(self.statements
+                .execute(
+                    Operation::Read,
+                    &format!(
+                        "SELECT version FROM {} WHERE aggregate_id = ? AND command_id = ?",
+                        self.tenant.table("processed_commands")
+                    ),
+                    (aggregate_id, command_id),
+                ))
+                .await
+        }).await?;
+
+        Ok(result.into_rows_result()?.maybe_first_row::<(i64,)>()?.map(|(version,)| version))
+    }
+
+    /// Current versions of many aggregates (0 for unknown ones)
+    pub async fn get_versions(&self, aggregate_ids: &[Uuid]) -> Result<HashMap<Uuid, i64>> {
+        self.get_versions_within(None, aggregate_ids).await
+    }
+
+    /// Current versions of many aggregates, bounded by `deadline`
+    ///
+    /// Looks ids up with `IN` queries of VERSION_LOOKUP_CHUNK ids, at most
+    /// VERSION_LOOKUP_CONCURRENCY of them in flight.
+    pub async fn get_versions_within(&self, deadline: Option<&Deadline>, aggregate_ids: &[Uuid]) -> Result<HashMap<Uuid, i64>> {
+        let found = with_deadline(deadline, "event_store.get_versions", async {
+            futures_util::stream::iter(aggregate_ids.chunks(VERSION_LOOKUP_CHUNK))
+                .map(|chunk| self.lookup_versions(chunk))
+                .buffer_unordered(VERSION_LOOKUP_CONCURRENCY)
+                .try_concat()
+                .await
+        }).await?;
+
+        Ok(collect_versions(aggregate_ids, found))
+    }
+
+    async fn lookup_versions(&self, aggregate_ids: &[Uuid]) -> Result<Vec<(Uuid, i64)>> {
+        let result = self.observed(self.statements
+            .execute(
+                Operation::Read,
+                &format!(
+                    "SELECT aggregate_id, current_sequence FROM {} WHERE aggregate_id IN ?",
+                    self.tenant.table("aggregate_sequence")
+                ),
+                (aggregate_ids.to_vec(),),
+            ))
+            .await?;
+
+        let rows_result = match result.into_rows_result() {
+            Ok(rows) => rows,
+            Err(_) => return Ok(Vec::new()),
+        };
+        Ok(rows_result.rows::<(Uuid, i64)>()?.collect::<std::result::Result<_, _>>()?)
+    }
+
     /// Load aggregate from events
     pub async fn load_aggregate<A>(&self, aggregate_id: Uuid) -> Result<A>
     where
         A: AggregateRoot<Event = E>,
         <A as AggregateRoot>::Error: std::fmt::Display,
+    {
+        self.load_aggregate_within(None, aggregate_id).await
+    }
+
+    /// Load aggregate from events, bounded by `deadline`
+    pub async fn load_aggregate_within<A>(&self, deadline: Option<&Deadline>, aggregate_id: Uuid) -> Result<A>
+    where
+        A: AggregateRoot<Event = E>,
+        <A as AggregateRoot>::Error: std::fmt::Display,
+    {
+        let replay = self.replay_stream::<A>(None, self.load_events_stream(aggregate_id));
+        match with_deadline(deadline, "event_store.load_events", replay).await? {
+            Some(aggregate) => Ok(aggregate),
+            None => bail!("Aggregate not found: {}", aggregate_id),
+        }
+    }
+
+    /// Load aggregate as it was at a past version or point in time
+    ///
+    /// Replays the events up to `as_of` (snapshots hold the newest state
+    /// only). Fails when the aggregate did not exist yet, or has not
+    /// reached the requested version.
+    pub async fn load_aggregate_at<A>(&self, aggregate_id: Uuid, as_of: AsOf) -> Result<A>
+    where
+        A: AggregateRoot<Event = E>,
+        <A as AggregateRoot>::Error: std::fmt::Display,
+    {
+        as_of.validate()?;
+        let events = self
+            .load_events_stream(aggregate_id)
+            .try_take_while(move |envelope| std::future::ready(Ok(as_of.includes(envelope))));
+        let state = self.replay_stream::<A>(None, events).await?;
+        as_of.reached(aggregate_id, state)
+    }
+
+    /// Load aggregate from its newest snapshot plus the events after it
+    ///
+    /// Replays all events without a snapshot policy, without a usable
+    /// snapshot, or when the snapshot cannot be read.
+    pub async fn load_from_snapshot_within<A>(&self, deadline: Option<&Deadline>, aggregate_id: Uuid) -> Result<A>
+    where
+        A: AggregateRoot<Event = E> + serde::de::DeserializeOwned,
+        <A as AggregateRoot>::Error: std::fmt::Display,
+    {
+        if let Some(policy) = self.snapshots {
+            match self.load_snapshot_within::<A>(deadline, aggregate_id, policy).await {
+                Ok(Some(snapshot)) => {
+                    let snapshot_version = snapshot.version();
+                    let events = self.events_after_stream(aggregate_id, snapshot_version);
+                    let replay = self.replay_stream(Some(snapshot), events);
+                    let aggregate = with_deadline(deadline, "event_store.load_events_after", replay)
+                        .await?
+                        .expect("replay onto a snapshot yields a state");
+                    tracing::debug!(
+                        aggregate_id = %aggregate_id,
+                        snapshot_version = snapshot_version,
+                        events_after = aggregate.version() - snapshot_version,
+                        "Hydrating aggregate from snapshot"
+                    );
+                    return Ok(aggregate);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::warn!(aggregate_id = %aggregate_id, error = %e, "Unusable snapshot - replaying all events");
+                }
+            }
+        }
+
+        self.load_aggregate_within(deadline, aggregate_id).await
+    }
+
+    /// Newest snapshot of the policy's schema version, if any
+    async fn load_snapshot_within<A>(&self, deadline: Option<&Deadline>, aggregate_id: Uuid, policy: SnapshotPolicy) -> Result<Option<A>>
+    where
+        A: serde::de::DeserializeOwned,
+    {
+        let result = with_deadline(deadline, "event_store.load_snapshot", async {
+            self.observed(self.statements
+                .execute(
+                    Operation::Read,
+                    &format!(
+                        "SELECT sequence_number, aggregate_version, snapshot_data
+                         FROM {} WHERE aggregate_id = ? LIMIT 1",
+                        self.tenant.table("aggregate_snapshots")
+                    ),
+                    (aggregate_id,),
+                ))
+                .await
+        }).await?;
+
+        let rows_result = match result.into_rows_result() {
+            Ok(rows) => rows,
+            Err(_) => return Ok(None),
+        };
+
+        match rows_result.maybe_first_row::<(i64, Option<i32>, String)>()? {
+            Some((_, Some(schema_version), snapshot_data)) if schema_version == policy.schema_version => {
+                Ok(Some(open_snapshot(self.crypto.as_deref(), &snapshot_data)?))
+            }
+            Some((sequence_number, schema_version, _)) => {
+                tracing::debug!(
+                    aggregate_id = %aggregate_id,
+                    sequence_number = sequence_number,
+                    schema_version = ?schema_version,
+                    "Ignoring snapshot of another schema version"
+                );
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Save a snapshot of `aggregate` at its current version
+    pub async fn save_snapshot<A>(&self, aggregate: &A) -> Result<()>
+    where
+        A: AggregateRoot<Event = E> + serde::Serialize,
+    {
+        let schema_version = self.snapshots.unwrap_or_default().schema_version;
+        let snapshot_data = seal_snapshot(self.crypto.as_deref(), aggregate)?;
+
+        self.observed(self.statements.execute(
+            Operation::Append,
+            &format!(
+                "INSERT INTO {} (
+                    aggregate_id, sequence_number, aggregate_type, aggregate_version,
+                    snapshot_data, created_at, event_count
+                ) VALUES (?, ?, ?, ?, ?, ?, ?)",
+                self.tenant.table("aggregate_snapshots")
+            ),
+            (
+                aggregate.aggregate_id(),
+                aggregate.version(),
+                self.aggregate_type_name.as_str(),
+                schema_version,
+                snapshot_data,
+                Utc::now(),
+                aggregate.version() as i32,
+            ),
+        ))
+        .await?;
+
+        tracing::debug!(
+            aggregate_id = %aggregate.aggregate_id(),
+            aggregate_type = %self.aggregate_type_name,
+            version = aggregate.version(),
+            "📸 Saved aggregate snapshot"
+        );
+        Ok(())
+    }
+
+    /// Snapshot the state after an append if it crossed the snapshot frequency
+    ///
+    /// `current` is the state the command was handled on (None for a new
+    /// aggregate). Failures are logged - a missing snapshot only costs replay.
+    pub async fn snapshot_after_append<A>(&self, current: Option<A>, expected_version: i64, appended: &[EventEnvelope<E>])
+    where
+        A: AggregateRoot<Event = E> + serde::Serialize,
+        <A as AggregateRoot>::Error: std::fmt::Display,
+    {
+        let Some(policy) = self.snapshots else { return };
+        let new_version = expected_version + appended.len() as i64;
+        if !policy.is_due(expected_version, new_version) {
+            return;
+        }
+
+        let state = match current {
+            Some(aggregate) => aggregate.apply_envelopes(appended.to_vec()),
+            None => A::load_from_events(appended.to_vec()),
+        };
+        let saved = match state {
+            Ok(aggregate) => self.save_snapshot(&aggregate).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = saved {
+            tracing::warn!(aggregate_type = %self.aggregate_type_name, version = new_version, error = %e, "Failed to save snapshot");
+        }
+    }
+
+    /// State diff introduced by the event at `version` (state v-1 vs v)
+    pub async fn diff_version<A>(&self, aggregate_id: Uuid, version: i64) -> Result<StateDiff>
+    where
+        A: AggregateRoot<Event = E> + serde::Serialize,
+        <A as AggregateRoot>::Error: std::fmt::Display,
     {
         let events = self.load_events(aggregate_id).await?;
 
@@ -231,16 +1192,241 @@ impl<E: DomainEvent> EventStore<E> {
             bail!("Aggregate not found: {}", aggregate_id);
         }
 
-        A::load_from_events(events)
+        diff_at_version::<A>(&events, version)
+    }
+
+    /// Forget the personal data of an aggregate (right to erasure)
+    ///
+    /// Deletes its data key, so the personal fields of its events can no
+    /// longer be opened, and its snapshots, which hold them in the clear.
+    /// The events themselves stay.
+    pub async fn forget_personal_data(&self, aggregate_id: Uuid) -> Result<()> {
+        let Some(ref vault) = self.personal_data else {
+            bail!("No personal data vault attached to the {} event store", self.aggregate_type_name);
+        };
+        vault.forget(aggregate_id).await?;
+        self.observed(self.statements.execute(
+            Operation::Append,
+            &format!("DELETE FROM {} WHERE aggregate_id = ?", self.tenant.table("aggregate_snapshots")),
+            (aggregate_id,),
+        ))
+        .await?;
+        Ok(())
     }
 
     /// Check if aggregate exists
     pub async fn aggregate_exists(&self, aggregate_id: Uuid) -> Result<bool> {
-        let version = self.get_current_version(aggregate_id).await?;
+        self.aggregate_exists_within(None, aggregate_id).await
+    }
+
+    /// Check if aggregate exists, bounded by `deadline`
+    pub async fn aggregate_exists_within(&self, deadline: Option<&Deadline>, aggregate_id: Uuid) -> Result<bool> {
+        let version = self.get_current_version_within(deadline, aggregate_id).await?;
         Ok(version > 0)
     }
 }
 
+/// Ids per `IN` query of `get_versions`
+const VERSION_LOOKUP_CHUNK: usize = 100;
+
+/// `IN` queries of one `get_versions` call in flight at once
+const VERSION_LOOKUP_CONCURRENCY: usize = 8;
+
+/// Version per requested id, 0 for ids without a row
+/// Serialized aggregate state, sealed like event payloads when crypto is set
+fn seal_snapshot<A: serde::Serialize>(crypto: Option<&dyn EventCrypto>, aggregate: &A) -> Result<String> {
+    let snapshot_json = serde_json::to_string(aggregate)?;
+    match crypto {
+        Some(crypto) => seal_payload(crypto, &snapshot_json),
+        None => Ok(snapshot_json),
+    }
+}
+
+/// Aggregate state of a stored snapshot (plaintext ones from before
+/// encryption was enabled still open)
+fn open_snapshot<A: serde::de::DeserializeOwned>(crypto: Option<&dyn EventCrypto>, snapshot_data: &str) -> Result<A> {
+    Ok(serde_json::from_str(&open_payload(crypto, snapshot_data)?)?)
+}
+
+fn collect_versions(aggregate_ids: &[Uuid], found: Vec<(Uuid, i64)>) -> HashMap<Uuid, i64> {
+    let mut versions: HashMap<Uuid, i64> = aggregate_ids.iter().map(|id| (*id, 0)).collect();
+    versions.extend(found);
+    versions
+}
+
+/// Read `[applied]` and, when not applied, the current_sequence of an LWT
+///
+/// The returned columns of a failed LWT differ between Scylla and Cassandra,
+/// so current_sequence is looked up by name.
+fn sequence_lwt_outcome(result: scylla::response::query_result::QueryResult) -> Result<(bool, Option<i64>)> {
+    let rows = result.into_rows_result()?;
+    let sequence_idx = rows.column_specs().iter().position(|c| c.name() == "current_sequence");
+
+    let Some(row) = rows.maybe_first_row::<scylla::value::Row>()? else {
+        bail!("LWT returned no [applied] row");
+    };
+    let applied = row.columns.first().cloned().flatten().and_then(|v| v.as_boolean()).unwrap_or(false);
+    let current = sequence_idx.and_then(|i| row.columns.get(i).cloned().flatten()).and_then(|v| v.as_bigint());
+    Ok((applied, current))
+}
+
+/// Whether a failed append batch was certainly not applied
+///
+/// Errors raised before the batch reached a replica, or that Scylla
+/// answers without writing anything, qualify. Timeouts, lost connections
+/// and write failures do not: the batchlog may still replay the batch.
+fn batch_not_applied(error: &anyhow::Error) -> bool {
+    use scylla::errors::{DbError, ExecutionError, RequestAttemptError};
+
+    match error.downcast_ref::<ExecutionError>() {
+        Some(
+            ExecutionError::BadQuery(_)
+            | ExecutionError::PrepareError(_)
+            | ExecutionError::EmptyPlan
+            | ExecutionError::ConnectionPoolError(_)
+            | ExecutionError::LastAttemptError(RequestAttemptError::SerializationError(_)),
+        ) => true,
+        Some(ExecutionError::LastAttemptError(RequestAttemptError::DbError(db_error, _))) => matches!(
+            db_error,
+            DbError::SyntaxError
+                | DbError::Invalid
+                | DbError::Unauthorized
+                | DbError::AuthenticationError
+                | DbError::ConfigError
+                | DbError::Unavailable { .. }
+                | DbError::Overloaded
+                | DbError::IsBootstrapping
+                | DbError::Unprepared { .. }
+                | DbError::RateLimitReached { .. }
+        ),
+        _ => false,
+    }
+}
+
+/// Prepared inserts of one append batch
+struct AppendStatements {
+    /// Into the aggregate's event table
+    event: PreparedStatement,
+    /// Into events_by_time
+    by_time: PreparedStatement,
+    /// Into the outbox, when publishing
+    outbox: Option<PreparedStatement>,
+    /// Into processed_commands, for commands with an id
+    command: Option<PreparedStatement>,
+    /// Into aggregate_catalog, on the aggregate's first append
+    catalog: Option<PreparedStatement>,
+}
+
+/// Time feed insert; `{events_by_time}` is replaced by the tenant's table
+const INSERT_BY_TIME: &str = "INSERT INTO {events_by_time} (
+        aggregate_type, bucket, timestamp, aggregate_id, sequence_number, event_id,
+        event_type, event_version, event_data, causation_id, correlation_id,
+        origin_region, user_id, metadata
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
+
+/// Outbox insert; `{outbox}` is replaced by the tenant's outbox table
+const INSERT_OUTBOX: &str = "INSERT INTO {outbox} (
+        id, aggregate_id, aggregate_type, event_id, event_type, event_version,
+        sequence_number, payload, topic, partition_key, causation_id,
+        correlation_id, origin_region, created_at, aggregate_event_type, variant_type,
+        publish_after, hlc, attempts
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 0)";
+
+/// Values of INSERT_OUTBOX, bound by column name (more than a tuple holds)
+#[derive(scylla::SerializeRow)]
+struct OutboxRow {
+    id: Uuid,
+    aggregate_id: Uuid,
+    aggregate_type: String,
+    event_id: Uuid,
+    event_type: String,
+    event_version: i32,
+    sequence_number: i64,
+    payload: String,
+    topic: String,
+    partition_key: String,
+    causation_id: Option<Uuid>,
+    correlation_id: Uuid,
+    origin_region: Option<String>,
+    created_at: chrono::DateTime<Utc>,
+    aggregate_event_type: &'static str,
+    variant_type: &'static str,
+    publish_after: Option<chrono::DateTime<Utc>>,
+    hlc: String,
+}
+
+const EVENT_COLUMNS: &str = "aggregate_id, sequence_number, event_id, event_type, event_version,
+     event_data, causation_id, correlation_id, timestamp, origin_region, user_id, metadata";
+
+/// A row of EVENT_COLUMNS
+type EventRow = (
+    Uuid, i64, Uuid, String, i32, String, Option<Uuid>, Uuid, chrono::DateTime<Utc>, Option<String>,
+    Option<Uuid>, Option<HashMap<String, String>>,
+);
+
+/// Envelope metadata stored in the `metadata` column (origin region has its own)
+pub(super) fn audit_metadata(metadata: &HashMap<String, String>) -> Option<HashMap<String, String>> {
+    let audit: HashMap<String, String> = metadata
+        .iter()
+        .filter(|(key, _)| key.as_str() != ORIGIN_REGION_KEY)
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    (!audit.is_empty()).then_some(audit)
+}
+
+/// Envelope of a stored event row
+///
+/// `personal_key` is None for stores without a PersonalDataVault, and
+/// Some(None) for aggregates whose data key was deleted. Events older than
+/// the latest version of their type in `upcasters` are upcast first, with
+/// their personal data still sealed - as the CDC relay upcasts them.
+fn parse_event_row<E: DomainEvent>(
+    row: EventRow,
+    crypto: Option<&dyn EventCrypto>,
+    personal_key: Option<Option<&dyn EventCrypto>>,
+    upcasters: Option<&UpcasterRegistry>,
+) -> Result<EventEnvelope<E>> {
+    let (agg_id, sequence_number, event_id, event_type, event_version, event_data_json, causation_id, correlation_id, timestamp, origin_region, user_id, metadata) = row;
+
+    tracing::debug!("Loaded event for aggregate {}: seq={}, type={}", agg_id, sequence_number, event_type);
+
+    // Parse event data based on type
+    let event_json = open_payload(crypto, &event_data_json)?;
+    let upcasters = upcasters.filter(|upcasters| upcasters.is_outdated(&event_type, event_version));
+    let (event_data, event_version): (E, i32) = if personal_key.is_some() || upcasters.is_some() {
+        let event_value = serde_json::from_str(&event_json)?;
+        let (mut event_value, event_version) = match upcasters {
+            Some(upcasters) => upcasters.upcast(&event_type, event_version, event_value)?,
+            None => (event_value, event_version),
+        };
+        if let Some(key) = personal_key {
+            open_personal_data(&mut event_value, key)?;
+        }
+        (serde_json::from_value(event_value)?, event_version)
+    } else {
+        (serde_json::from_str(&event_json)?, event_version)
+    };
+
+    let mut metadata = metadata.unwrap_or_default();
+    if let Some(region) = origin_region {
+        metadata.insert(ORIGIN_REGION_KEY.to_string(), region);
+    }
+
+    Ok(EventEnvelope {
+        event_id,
+        aggregate_id: agg_id,
+        sequence_number,
+        event_type,
+        event_version,
+        event_data,
+        causation_id,
+        correlation_id,
+        user_id,
+        timestamp,
+        metadata,
+    })
+}
+
 // ============================================================================
 // Unit Tests
 // ============================================================================
@@ -250,6 +1436,7 @@ mod tests {
     use super::*;
     use crate::domain::order::{OrderEvent, OrderCreated};
     use crate::domain::order::OrderItem;
+    use crate::event_sourcing::core::AesGcmCrypto;
 
     #[test]
     fn test_event_store_creation() {
@@ -268,6 +1455,40 @@ mod tests {
         assert_eq!(topic, "order-events");
     }
 
+    #[test]
+    fn test_concurrency_conflict_is_typed() {
+        let aggregate_id = Uuid::new_v4();
+        let error = anyhow::Error::new(ConcurrencyConflict { aggregate_id, expected_version: 3, current_version: 4 });
+
+        let conflict = error.downcast_ref::<ConcurrencyConflict>().unwrap();
+        assert_eq!((conflict.expected_version, conflict.current_version), (3, 4));
+        assert!(error.to_string().starts_with("Concurrency conflict"));
+    }
+
+    #[test]
+    fn test_claim_released_only_when_batch_not_applied() {
+        use scylla::errors::{DbError, ExecutionError, RequestAttemptError, WriteType};
+        use scylla::statement::Consistency;
+        use crate::event_sourcing::core::DeadlineExceeded;
+
+        let db = |error: DbError| anyhow::Error::new(ExecutionError::LastAttemptError(RequestAttemptError::DbError(error, String::new())));
+        assert!(batch_not_applied(&db(DbError::Invalid)));
+        assert!(batch_not_applied(&db(DbError::Unavailable { consistency: Consistency::Quorum, required: 2, alive: 1 })));
+        assert!(batch_not_applied(&anyhow::Error::new(ExecutionError::EmptyPlan)));
+
+        // The batchlog may still replay these
+        assert!(!batch_not_applied(&db(DbError::WriteTimeout {
+            consistency: Consistency::Quorum,
+            received: 1,
+            required: 2,
+            write_type: WriteType::BatchLog,
+        })));
+        assert!(!batch_not_applied(&db(DbError::ServerError)));
+        assert!(!batch_not_applied(&anyhow::Error::new(ExecutionError::RequestTimeout(std::time::Duration::from_secs(2)))));
+        let cancelled = DeadlineExceeded { operation: "event_store.append".to_string(), budget: std::time::Duration::from_millis(50) };
+        assert!(!batch_not_applied(&cancelled.into()));
+    }
+
     #[test]
     fn test_event_envelope_construction_for_store() {
         let aggregate_id = Uuid::new_v4();
@@ -386,6 +1607,62 @@ mod tests {
         assert_eq!(customer_topic, "customer-events");
     }
 
+    #[test]
+    fn test_collect_versions_defaults_unknown_ids_to_zero() {
+        let (known, unknown) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let versions = collect_versions(&[known, unknown, known], vec![(known, 7)]);
+
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[&known], 7);
+        assert_eq!(versions[&unknown], 0);
+    }
+
+    #[test]
+    fn test_audit_fields_round_trip_through_event_rows() {
+        let user_id = Uuid::new_v4();
+        let metadata: HashMap<String, String> = [
+            ("client_ip".to_string(), "10.0.0.7".to_string()),
+            (ORIGIN_REGION_KEY.to_string(), "eu-west".to_string()),
+        ]
+        .into();
+
+        // The origin region has its own column
+        let stored = audit_metadata(&metadata);
+        assert_eq!(stored.as_ref().map(HashMap::len), Some(1));
+        assert_eq!(audit_metadata(&HashMap::new()), None);
+
+        let event = OrderEvent::Created(OrderCreated { customer_id: Uuid::new_v4(), items: vec![] });
+        let row: EventRow = (
+            Uuid::new_v4(), 1, Uuid::new_v4(), "OrderCreated".to_string(), 1, serialize_event(&event).unwrap(),
+            None, Uuid::new_v4(), Utc::now(), Some("eu-west".to_string()), Some(user_id), stored,
+        );
+        let envelope = parse_event_row::<OrderEvent>(row.clone(), None, None, None).unwrap();
+        assert_eq!(envelope.user_id, Some(user_id));
+        assert_eq!(envelope.metadata, metadata);
+
+        // A v1 row of a type now at v2 comes back upcast
+        let upcasters = UpcasterRegistry::new().with_latest("OrderCreated", 2).register("OrderCreated", 1, Ok);
+        let envelope = parse_event_row::<OrderEvent>(row, None, None, Some(&upcasters)).unwrap();
+        assert_eq!(envelope.event_version, 2);
+    }
+
+    #[test]
+    fn test_snapshots_sealed_with_crypto() {
+        let crypto = AesGcmCrypto::new("k1", &[7; 32]).unwrap();
+        let state = serde_json::json!({"customer_id": "c-1", "total": 42});
+
+        let sealed = seal_snapshot(Some(&crypto), &state).unwrap();
+        assert!(!sealed.contains("customer_id"));
+        assert_eq!(open_snapshot::<serde_json::Value>(Some(&crypto), &sealed).unwrap(), state);
+        // Without the keys a sealed snapshot is unusable (full replay instead)
+        assert!(open_snapshot::<serde_json::Value>(None, &sealed).is_err());
+
+        // Snapshots saved before encryption was enabled still load
+        let plain = seal_snapshot(None, &state).unwrap();
+        assert_eq!(open_snapshot::<serde_json::Value>(Some(&crypto), &plain).unwrap(), state);
+    }
+
     // Note: The following tests require integration testing with a real ScyllaDB instance:
     // - append_events with successful append
     // - append_events with concurrency conflict detection
@@ -394,10 +1671,11 @@ mod tests {
     // - load_events with empty aggregate
     // - load_aggregate reconstructing from events
     // - get_current_version tracking
+    // - get_versions chunking of IN queries
     // - aggregate_exists checking
     // - Multiple aggregates isolation
     //
-    // These are covered by the integration test in tests/integration_test.sh
+    // These are covered by the integration suite in tests/integration
 }
 
 // ============================================================================
@@ -425,8 +1703,8 @@ mod tests {
 //    - Events loaded in sequence_number order
 //    - Sequence number gaps detection
 //
-// Integration tests should be run using:
-// - testcontainers with ScyllaDB Docker image
+// Integration tests run against containers (`make test-integration`):
+// - tests/integration - testcontainers with ScyllaDB and Redpanda
 // - Or the existing tests/integration_test.sh
 //
 // ============================================================================
@@ -0,0 +1,305 @@
+use scylla::client::session::Session;
+use scylla::value::Row;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use anyhow::{Result, bail};
+use chrono::Utc;
+
+// ============================================================================
+// Write Fencing - Reject Appends from Superseded Deployments
+// ============================================================================
+//
+// During a blue/green cutover the old and the new deployment can both be
+// accepting commands for a short time. Optimistic concurrency prevents two
+// appends at the same version, but not the old deployment appending stale
+// decisions after the new one took over.
+//
+// Opt-in ([fencing] / FENCE_GENERATION): every deployment is configured
+// with a generation, increased for each rollout. On startup an instance
+// acquires the fence (one row of write_fencing) for its generation:
+// - no row yet, or an older generation: an LWT makes ours the epoch,
+//   superseding the older deployment
+// - the same generation: another replica of this deployment got there
+//   first; the instance joins it
+// - a newer generation: this deployment is already superseded and the
+//   instance refuses to start (FencedOut)
+//
+// Appends check the cached epoch, not the table: a background refresher
+// re-reads the fence every `refresh_interval`, and once it sees a newer
+// generation every append of the instance fails with a typed FencedOut
+// (inside anyhow::Error, use `downcast_ref`). Appends started within one
+// refresh interval of the takeover can still land - the price of not
+// running an LWT on one hot partition per append. A fenced-out instance
+// stays fenced; it must be redeployed with a newer generation.
+//
+// ============================================================================
+
+/// This instance's fence epoch has been superseded by a newer deployment
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Fenced out of '{fence}': epoch {epoch} superseded by {current_epoch:?} (holder {current_holder:?})")]
+pub struct FencedOut {
+    pub fence: String,
+    pub epoch: i64,
+    pub current_epoch: Option<i64>,
+    pub current_holder: Option<String>,
+}
+
+/// Number of times acquisition retries when another instance bumps concurrently
+const ACQUIRE_ATTEMPTS: u32 = 5;
+
+/// What acquiring the fence for a generation has to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Acquisition {
+    /// No deployment held the fence yet
+    Create,
+    /// Supersede the older generation `from`
+    TakeOver { from: i64 },
+    /// A replica of the same generation holds it already
+    Join,
+    /// A newer generation took over
+    Superseded { current: i64 },
+}
+
+fn acquisition(current: Option<i64>, generation: i64) -> Acquisition {
+    match current {
+        None => Acquisition::Create,
+        Some(epoch) if epoch < generation => Acquisition::TakeOver { from: epoch },
+        Some(epoch) if epoch == generation => Acquisition::Join,
+        Some(epoch) => Acquisition::Superseded { current: epoch },
+    }
+}
+
+/// The epoch an instance holds and what it last saw of the fence
+struct FenceState {
+    name: String,
+    epoch: i64,
+    fenced_out: AtomicBool,
+    /// Epoch and holder that superseded us
+    superseded_by: Mutex<Option<(i64, Option<String>)>>,
+}
+
+impl FenceState {
+    fn new(name: &str, epoch: i64) -> Self {
+        Self {
+            name: name.to_string(),
+            epoch,
+            fenced_out: AtomicBool::new(false),
+            superseded_by: Mutex::new(None),
+        }
+    }
+
+    /// Take note of the fence's current epoch and holder
+    fn observe(&self, current_epoch: i64, current_holder: Option<String>) {
+        if current_epoch <= self.epoch || self.fenced_out.load(Ordering::SeqCst) {
+            return;
+        }
+        tracing::error!(
+            fence = %self.name,
+            epoch = self.epoch,
+            current_epoch = current_epoch,
+            current_holder = ?current_holder,
+            "⛔ Fenced out - a newer deployment took over, rejecting writes"
+        );
+        *self.superseded_by.lock().unwrap() = Some((current_epoch, current_holder));
+        self.fenced_out.store(true, Ordering::SeqCst);
+    }
+
+    fn check(&self) -> std::result::Result<(), FencedOut> {
+        if !self.fenced_out.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        let (current_epoch, current_holder) = self.superseded_by.lock().unwrap().clone().unzip();
+        Err(FencedOut {
+            fence: self.name.clone(),
+            epoch: self.epoch,
+            current_epoch,
+            current_holder: current_holder.flatten(),
+        })
+    }
+}
+
+pub struct WriteFence {
+    session: Arc<Session>,
+    holder: String,
+    state: FenceState,
+}
+
+impl WriteFence {
+    /// Hold `name` for deployment `generation`: take it over from older
+    /// generations, join the replicas of the same one
+    pub async fn acquire(session: Arc<Session>, name: &str, generation: i64, holder: &str) -> Result<Self> {
+        for _ in 0..ACQUIRE_ATTEMPTS {
+            let current = read_fence(&session, name).await?;
+            let result = match acquisition(current.as_ref().map(|(epoch, _)| *epoch), generation) {
+                Acquisition::Join => {
+                    tracing::info!(fence = %name, holder = %holder, epoch = generation, "🔒 Joined write fence of this deployment");
+                    return Ok(Self::held(session, name, generation, holder));
+                }
+                Acquisition::Superseded { current: epoch } => {
+                    return Err(FencedOut {
+                        fence: name.to_string(),
+                        epoch: generation,
+                        current_epoch: Some(epoch),
+                        current_holder: current.and_then(|(_, holder)| holder),
+                    }
+                    .into());
+                }
+                Acquisition::Create => {
+                    session
+                        .query_unpaged(
+                            "INSERT INTO write_fencing (fence_name, epoch, holder, acquired_at)
+                             VALUES (?, ?, ?, ?) IF NOT EXISTS",
+                            (name, generation, holder, Utc::now()),
+                        )
+                        .await?
+                }
+                Acquisition::TakeOver { from } => {
+                    session
+                        .query_unpaged(
+                            "UPDATE write_fencing SET epoch = ?, holder = ?, acquired_at = ?
+                             WHERE fence_name = ? IF epoch = ?",
+                            (generation, holder, Utc::now(), name, from),
+                        )
+                        .await?
+                }
+            };
+
+            let outcome = lwt_outcome(result)?;
+            if outcome.applied {
+                tracing::info!(fence = %name, holder = %holder, epoch = generation, "🔒 Acquired write fence");
+                return Ok(Self::held(session, name, generation, holder));
+            }
+
+            tracing::debug!(
+                fence = %name,
+                current_epoch = ?outcome.epoch,
+                current_holder = ?outcome.holder,
+                "Fence epoch changed concurrently, retrying acquisition"
+            );
+        }
+
+        bail!("Could not acquire write fence '{}' after {} attempts", name, ACQUIRE_ATTEMPTS)
+    }
+
+    fn held(session: Arc<Session>, name: &str, epoch: i64, holder: &str) -> Self {
+        Self {
+            session,
+            holder: holder.to_string(),
+            state: FenceState::new(name, epoch),
+        }
+    }
+
+    pub fn epoch(&self) -> i64 {
+        self.state.epoch
+    }
+
+    pub fn holder(&self) -> &str {
+        &self.holder
+    }
+
+    pub fn is_fenced_out(&self) -> bool {
+        self.state.fenced_out.load(Ordering::SeqCst)
+    }
+
+    /// Whether this deployment still holds the fence, as of the last
+    /// refresh; called before appends
+    pub fn check(&self) -> Result<()> {
+        Ok(self.state.check()?)
+    }
+
+    /// Re-read the fence; a newer generation fences this instance out
+    pub async fn refresh(&self) -> Result<()> {
+        if let Some((epoch, holder)) = read_fence(&self.session, &self.state.name).await? {
+            self.state.observe(epoch, holder);
+        }
+        Ok(())
+    }
+
+    /// Refresh every `interval` until fenced out
+    pub fn spawn_refresher(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            while !self.is_fenced_out() {
+                ticker.tick().await;
+                if let Err(e) = self.refresh().await {
+                    tracing::warn!(fence = %self.state.name, error = %e, "Failed to refresh write fence");
+                }
+            }
+        })
+    }
+}
+
+/// Current epoch and holder of fence `name`, None before anyone acquired it
+async fn read_fence(session: &Session, name: &str) -> Result<Option<(i64, Option<String>)>> {
+    Ok(session
+        .query_unpaged("SELECT epoch, holder FROM write_fencing WHERE fence_name = ?", (name,))
+        .await?
+        .into_rows_result()?
+        .maybe_first_row::<(i64, Option<String>)>()?)
+}
+
+struct LwtOutcome {
+    applied: bool,
+    epoch: Option<i64>,
+    holder: Option<String>,
+}
+
+/// Read `[applied]` and, when not applied, the current epoch/holder
+///
+/// The returned columns of a failed LWT differ between Scylla and Cassandra,
+/// so they are looked up by name.
+fn lwt_outcome(result: scylla::response::query_result::QueryResult) -> Result<LwtOutcome> {
+    let rows = result.into_rows_result()?;
+    let column = |name: &str| rows.column_specs().iter().position(|c| c.name() == name);
+    let (epoch_idx, holder_idx) = (column("epoch"), column("holder"));
+
+    let Some(row) = rows.maybe_first_row::<Row>()? else {
+        bail!("LWT returned no [applied] row");
+    };
+    let value = |idx: Option<usize>| idx.and_then(|i| row.columns.get(i).cloned().flatten());
+
+    Ok(LwtOutcome {
+        applied: row.columns.first().cloned().flatten().and_then(|v| v.as_boolean()).unwrap_or(false),
+        epoch: value(epoch_idx).and_then(|v| v.as_bigint()),
+        holder: value(holder_idx).and_then(|v| v.as_text().cloned()),
+    })
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_newer_generation_takes_over() {
+        assert_eq!(acquisition(None, 1), Acquisition::Create);
+        assert_eq!(acquisition(Some(3), 4), Acquisition::TakeOver { from: 3 });
+        // Replicas of one deployment share the fence instead of fencing each other out
+        assert_eq!(acquisition(Some(4), 4), Acquisition::Join);
+        // A deployment started after its successor never writes
+        assert_eq!(acquisition(Some(5), 4), Acquisition::Superseded { current: 5 });
+    }
+
+    #[test]
+    fn test_stale_writer_fenced_out_after_takeover() {
+        let blue = FenceState::new("orders-service", 4);
+        blue.observe(4, Some("blue-2".to_string()));
+        assert!(blue.check().is_ok());
+
+        // Green (generation 5) took over
+        blue.observe(5, Some("green-1".to_string()));
+        let err = blue.check().unwrap_err();
+        assert_eq!(err.epoch, 4);
+        assert_eq!(err.current_epoch, Some(5));
+        assert_eq!(err.current_holder.as_deref(), Some("green-1"));
+
+        // Stays fenced, whatever it reads later
+        blue.observe(4, None);
+        blue.observe(6, Some("green-2".to_string()));
+        assert_eq!(blue.check().unwrap_err().current_epoch, Some(5));
+    }
+}
@@ -0,0 +1,245 @@
+use async_trait::async_trait;
+use anyhow::Result;
+use std::collections::HashSet;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::metrics::MetricsHandle;
+use crate::utils::{retry_with_backoff, RetryConfig, RetryResult};
+
+// ============================================================================
+// Aggregate Lifecycle Hooks - Infrastructure Side Effects
+// ============================================================================
+//
+// Some infrastructure has to follow an aggregate's lifecycle: provision a
+// per-order resource when the order is created, drop caches when it is
+// closed. Hooks registered on the EventStore run after a successful append:
+// - on_created: the append contained the aggregate's first event
+// - on_closed:  the append contained one of the configured closing event
+//   types (e.g. OrderDelivered, OrderCancelled, CustomerDeactivated)
+//
+// Hooks run on spawned tasks with retry, so a slow or failing hook never
+// delays or fails the command. They are at-least-once within the process
+// and must be idempotent; they are lost if the process dies first - side
+// effects that must survive a crash belong in a consumer of the outbox.
+//
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifecycleStage {
+    Created,
+    Closed,
+}
+
+impl LifecycleStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LifecycleStage::Created => "created",
+            LifecycleStage::Closed => "closed",
+        }
+    }
+}
+
+/// What a hook learns about the lifecycle transition
+#[derive(Debug, Clone, PartialEq)]
+pub struct LifecycleEvent {
+    pub aggregate_type: String,
+    pub aggregate_id: Uuid,
+    pub stage: LifecycleStage,
+    /// Event that caused the transition
+    pub event_type: String,
+    /// Aggregate version after the append
+    pub version: i64,
+    pub correlation_id: Uuid,
+}
+
+/// Infrastructure reaction to aggregate creation/closure
+#[async_trait]
+pub trait LifecycleHook: Send + Sync {
+    /// Name used in logs and metrics
+    fn name(&self) -> &str;
+
+    async fn on_created(&self, event: &LifecycleEvent) -> Result<()> {
+        let _ = event;
+        Ok(())
+    }
+
+    async fn on_closed(&self, event: &LifecycleEvent) -> Result<()> {
+        let _ = event;
+        Ok(())
+    }
+}
+
+/// Hooks of one event store and the event types that close its aggregates
+pub struct LifecycleHooks {
+    hooks: Vec<Arc<dyn LifecycleHook>>,
+    closing_event_types: HashSet<String>,
+    retry_config: RetryConfig,
+    metrics: MetricsHandle,
+}
+
+impl LifecycleHooks {
+    pub fn new<I, S>(closing_event_types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            hooks: Vec::new(),
+            closing_event_types: closing_event_types.into_iter().map(Into::into).collect(),
+            retry_config: RetryConfig::default(),
+            metrics: MetricsHandle::noop(),
+        }
+    }
+
+    pub fn register(mut self, hook: Arc<dyn LifecycleHook>) -> Self {
+        self.hooks.push(hook);
+        self
+    }
+
+    pub fn with_retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.retry_config = retry_config;
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: MetricsHandle) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// Lifecycle transitions caused by appending `event_types` on top of
+    /// `expected_version`
+    pub fn transitions<'a>(&self, expected_version: i64, event_types: impl IntoIterator<Item = &'a str>) -> Vec<(LifecycleStage, String)> {
+        let mut transitions = Vec::new();
+
+        for (index, event_type) in event_types.into_iter().enumerate() {
+            if expected_version == 0 && index == 0 {
+                transitions.push((LifecycleStage::Created, event_type.to_string()));
+            }
+            if self.closing_event_types.contains(event_type) {
+                transitions.push((LifecycleStage::Closed, event_type.to_string()));
+            }
+        }
+
+        transitions
+    }
+
+    /// Run every hook for `event` in the background
+    pub fn dispatch(&self, event: LifecycleEvent) {
+        for hook in &self.hooks {
+            let hook = hook.clone();
+            let event = event.clone();
+            let retry_config = self.retry_config.clone();
+            let metrics = self.metrics.clone();
+
+            tokio::spawn(async move {
+                let result = retry_with_backoff(retry_config, |_attempt| {
+                    let hook = hook.clone();
+                    let event = event.clone();
+                    async move {
+                        match event.stage {
+                            LifecycleStage::Created => hook.on_created(&event).await,
+                            LifecycleStage::Closed => hook.on_closed(&event).await,
+                        }
+                    }
+                })
+                .await;
+
+                let outcome = match result {
+                    RetryResult::Success(()) => "success",
+                    RetryResult::Failed(e) | RetryResult::PermanentFailure(e) => {
+                        tracing::error!(
+                            hook = %hook.name(),
+                            stage = event.stage.as_str(),
+                            aggregate_type = %event.aggregate_type,
+                            aggregate_id = %event.aggregate_id,
+                            error = %e,
+                            "Lifecycle hook failed after retries"
+                        );
+                        "failure"
+                    }
+                };
+
+                metrics.record_lifecycle_hook(hook.name(), event.stage.as_str(), outcome);
+            });
+        }
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+
+    fn hooks() -> LifecycleHooks {
+        LifecycleHooks::new(["OrderDelivered", "OrderCancelled"])
+    }
+
+    #[test]
+    fn test_transitions() {
+        let hooks = hooks();
+
+        assert_eq!(hooks.transitions(0, ["OrderCreated"]), vec![(LifecycleStage::Created, "OrderCreated".to_string())]);
+        assert!(hooks.transitions(2, ["OrderShipped"]).is_empty());
+        assert_eq!(hooks.transitions(3, ["OrderDelivered"]), vec![(LifecycleStage::Closed, "OrderDelivered".to_string())]);
+        // Created and cancelled in one append
+        assert_eq!(hooks.transitions(0, ["OrderCreated", "OrderCancelled"]).len(), 2);
+    }
+
+    struct FlakyHook {
+        failures_left: AtomicU32,
+        done: mpsc::UnboundedSender<LifecycleEvent>,
+    }
+
+    #[async_trait]
+    impl LifecycleHook for FlakyHook {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        async fn on_created(&self, event: &LifecycleEvent) -> Result<()> {
+            if self.failures_left.fetch_sub(1, Ordering::SeqCst) > 0 {
+                anyhow::bail!("resource provider unavailable");
+            }
+            let _ = self.done.send(event.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_retries_failing_hook() {
+        let (done, mut received) = mpsc::unbounded_channel();
+        let hooks = hooks()
+            .with_retry_config(RetryConfig {
+                max_attempts: 3,
+                initial_delay: Duration::from_millis(1),
+                max_delay: Duration::from_millis(5),
+                multiplier: 2.0,
+                ..RetryConfig::default()
+            })
+            .register(Arc::new(FlakyHook { failures_left: AtomicU32::new(2), done }));
+
+        let event = LifecycleEvent {
+            aggregate_type: "Order".to_string(),
+            aggregate_id: Uuid::new_v4(),
+            stage: LifecycleStage::Created,
+            event_type: "OrderCreated".to_string(),
+            version: 1,
+            correlation_id: Uuid::new_v4(),
+        };
+        hooks.dispatch(event.clone());
+
+        let delivered = tokio::time::timeout(Duration::from_secs(1), received.recv()).await.unwrap();
+        assert_eq!(delivered, Some(event));
+    }
+}
@@ -7,6 +7,30 @@
 //
 // ============================================================================
 
+mod catalog;
 mod event_store;
+mod snapshot_pruner;
+mod snapshots;
+mod fencing;
+mod lifecycle;
+mod personal_data;
+mod sharding;
+mod storage;
+mod tenant;
+mod time_feed;
+#[cfg(feature = "postgres")]
+mod postgres;
 
-pub use event_store::EventStore;
+pub use catalog::{AggregateCatalog, CatalogStats, DailyEvents, LargestAggregate, DEFAULT_STATS_DAYS, DEFAULT_STATS_TOP};
+pub use event_store::{EventStore, ConcurrencyConflict, DEFAULT_LOAD_PAGE_SIZE};
+pub use fencing::{WriteFence, FencedOut};
+pub use lifecycle::{LifecycleHook, LifecycleHooks, LifecycleEvent, LifecycleStage};
+pub use personal_data::{PersonalDataVault, seal_personal_data, open_personal_data, FORGOTTEN_PLACEHOLDER};
+pub use snapshot_pruner::{SnapshotPruner, SnapshotRetentionPolicy, SnapshotInfo, PrunePlan};
+pub use sharding::{ShardLayout, ShardRebalancer, RebalanceReport};
+pub use snapshots::SnapshotPolicy;
+pub use storage::{EventStorage, InMemoryEventStorage};
+pub use tenant::{TenantContext, TenantScope};
+pub use time_feed::{EventFeedFilter, feed_bucket, FEED_BUCKET_SECS, MAX_FEED_RANGE_HOURS};
+#[cfg(feature = "postgres")]
+pub use postgres::{PostgresEventStorage, POSTGRES_SCHEMA};
@@ -0,0 +1,240 @@
+use aes_gcm::aead::{KeyInit, OsRng};
+use aes_gcm::Aes256Gcm;
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::db::{Operation, StatementCache};
+use crate::event_sourcing::core::{open_payload, seal_payload, sealed_key_id, AesGcmCrypto, EventCrypto};
+
+// ============================================================================
+// Personal Data Vault - Crypto-Shredding for the Append-Only Store
+// ============================================================================
+//
+// Events are never deleted, but the personal data in them must be on request
+// (right to erasure). With a PersonalDataVault attached to an EventStore,
+// every aggregate gets its own data key in personal_data_keys, and the
+// fields an event declares as personal data (`personal_data [...]` in
+// `domain_event_enum!`) are encrypted with it before the event is written:
+//
+//   {"type":"CustomerRegistered","data":{"email":"enc:v1:<aggregate_id>:...", ...}}
+//
+// Every string inside a declared field is sealed on its own, so the event
+// keeps its shape (and its consumer contracts). The event store opens them
+// again when it loads the aggregate.
+//
+// Forgetting an aggregate deletes its key: the sealed fields of its history
+// can no longer be opened and load as FORGOTTEN_PLACEHOLDER. Everything
+// else in the events - ids, amounts, statuses - stays readable.
+//
+// Personal fields stay sealed on their way out: the outbox, Redpanda, the
+// DLQ and in-process subscribers see ciphertext. Consumers needing the data
+// read it through the event store.
+//
+// Keys are cached for KEY_CACHE_TTL. An aggregate forgotten on another
+// instance stays readable there until its cached key expires.
+//
+// ============================================================================
+
+/// What a sealed personal field reads as once its key was deleted
+pub const FORGOTTEN_PLACEHOLDER: &str = "[forgotten]";
+
+/// How long a loaded data key is reused before it is read again
+const KEY_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Per-aggregate data keys in personal_data_keys
+pub struct PersonalDataVault {
+    statements: Arc<StatementCache>,
+    cache: Mutex<HashMap<Uuid, (Instant, Arc<AesGcmCrypto>)>>,
+}
+
+impl PersonalDataVault {
+    pub fn new(statements: Arc<StatementCache>) -> Self {
+        Self { statements, cache: Mutex::new(HashMap::new()) }
+    }
+
+    /// Data key of `subject_id`; None if it never had one or was forgotten
+    pub async fn key(&self, subject_id: Uuid) -> Result<Option<Arc<AesGcmCrypto>>> {
+        if let Some((loaded_at, key)) = self.cache.lock().unwrap().get(&subject_id) {
+            if loaded_at.elapsed() < KEY_CACHE_TTL {
+                return Ok(Some(key.clone()));
+            }
+        }
+
+        let row = self
+            .statements
+            .execute(
+                Operation::Read,
+                "SELECT data_key FROM personal_data_keys WHERE subject_id = ?",
+                (subject_id,),
+            )
+            .await?
+            .into_rows_result()?
+            .maybe_first_row::<(Vec<u8>,)>()?;
+        let Some((data_key,)) = row else {
+            self.cache.lock().unwrap().remove(&subject_id);
+            return Ok(None);
+        };
+
+        let key = Arc::new(AesGcmCrypto::new(&subject_id.to_string(), &data_key)?);
+        self.cache.lock().unwrap().insert(subject_id, (Instant::now(), key.clone()));
+        Ok(Some(key))
+    }
+
+    /// Data key of `subject_id`, created on its first personal data
+    pub async fn key_for_write(&self, subject_id: Uuid) -> Result<Arc<AesGcmCrypto>> {
+        if let Some(key) = self.key(subject_id).await? {
+            return Ok(key);
+        }
+
+        // Concurrent writers race for the key; the first one wins (LWT) and
+        // everyone uses what was stored
+        let data_key = Aes256Gcm::generate_key(OsRng);
+        self.statements
+            .execute(
+                Operation::Lwt,
+                "INSERT INTO personal_data_keys (subject_id, data_key, created_at) VALUES (?, ?, ?) IF NOT EXISTS",
+                (subject_id, data_key.to_vec(), chrono::Utc::now()),
+            )
+            .await?;
+        self.key(subject_id)
+            .await?
+            .ok_or_else(|| anyhow!("Data key of {} was deleted while it was created", subject_id))
+    }
+
+    /// Delete the data key of `subject_id`, making its personal data unreadable
+    pub async fn forget(&self, subject_id: Uuid) -> Result<()> {
+        self.statements
+            .execute(
+                Operation::Append,
+                "DELETE FROM personal_data_keys WHERE subject_id = ?",
+                (subject_id,),
+            )
+            .await?;
+        self.cache.lock().unwrap().remove(&subject_id);
+        tracing::info!(subject_id = %subject_id, "🔥 Deleted personal data key");
+        Ok(())
+    }
+}
+
+/// Object holding an event's fields: `data` of tagged enums, else the event
+fn event_fields(event: &mut Value) -> Option<&mut serde_json::Map<String, Value>> {
+    let object = event.as_object_mut()?;
+    if object.contains_key("type") && object.get("data").is_some_and(Value::is_object) {
+        return object.get_mut("data")?.as_object_mut();
+    }
+    Some(object)
+}
+
+/// Encrypt every string in the `fields` of a serialized event with `key`
+pub fn seal_personal_data(event: &mut Value, fields: &[&str], key: &dyn EventCrypto) -> Result<()> {
+    let Some(object) = event_fields(event) else {
+        return Ok(());
+    };
+    for field in fields {
+        if let Some(value) = object.get_mut(*field) {
+            seal_strings(value, key)?;
+        }
+    }
+    Ok(())
+}
+
+fn seal_strings(value: &mut Value, key: &dyn EventCrypto) -> Result<()> {
+    match value {
+        Value::String(s) => *s = seal_payload(key, s)?,
+        Value::Array(values) => {
+            for value in values {
+                seal_strings(value, key)?;
+            }
+        }
+        Value::Object(values) => {
+            for value in values.values_mut() {
+                seal_strings(value, key)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Decrypt every sealed string of a serialized event
+///
+/// With no key (the aggregate was forgotten) they become
+/// FORGOTTEN_PLACEHOLDER.
+pub fn open_personal_data(event: &mut Value, key: Option<&dyn EventCrypto>) -> Result<()> {
+    match event {
+        Value::String(s) if sealed_key_id(s).is_some() => {
+            *s = match key {
+                Some(key) => open_payload(Some(key), s)?.into_owned(),
+                None => FORGOTTEN_PLACEHOLDER.to_string(),
+            };
+        }
+        Value::Array(values) => {
+            for value in values {
+                open_personal_data(value, key)?;
+            }
+        }
+        Value::Object(values) => {
+            for value in values.values_mut() {
+                open_personal_data(value, key)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn registered() -> Value {
+        json!({
+            "type": "CustomerRegistered",
+            "data": {
+                "email": "jane@example.com",
+                "first_name": "Jane",
+                "phone": null,
+                "address": { "street": "1 Main St", "city": "Springfield" },
+                "tier": "Bronze"
+            }
+        })
+    }
+
+    #[test]
+    fn test_personal_fields_round_trip() {
+        let key = AesGcmCrypto::new(&Uuid::new_v4().to_string(), &[7; 32]).unwrap();
+        let mut event = registered();
+
+        seal_personal_data(&mut event, &["email", "first_name", "phone", "address"], &key).unwrap();
+        let sealed = event.to_string();
+        assert!(!sealed.contains("jane@example.com") && !sealed.contains("Springfield"));
+        assert_eq!(event["data"]["tier"], "Bronze");
+        assert!(event["data"]["phone"].is_null());
+        assert_eq!(sealed_key_id(event["data"]["address"]["city"].as_str().unwrap()), Some(key.active_key_id()));
+
+        open_personal_data(&mut event, Some(&key)).unwrap();
+        assert_eq!(event, registered());
+    }
+
+    #[test]
+    fn test_forgotten_fields_read_as_placeholder() {
+        let key = AesGcmCrypto::new(&Uuid::new_v4().to_string(), &[7; 32]).unwrap();
+        let mut event = registered();
+        seal_personal_data(&mut event, &["email", "address"], &key).unwrap();
+
+        open_personal_data(&mut event, None).unwrap();
+        assert_eq!(event["data"]["email"], FORGOTTEN_PLACEHOLDER);
+        assert_eq!(event["data"]["address"]["street"], FORGOTTEN_PLACEHOLDER);
+        assert_eq!(event["data"]["first_name"], "Jane");
+        assert_eq!(event["data"]["tier"], "Bronze");
+    }
+}
@@ -0,0 +1,284 @@
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgPool;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use uuid::Uuid;
+
+use crate::event_sourcing::core::{
+    serialize_event, with_deadline, Deadline, DomainEvent, EventEnvelope, COMMAND_ID_KEY, ORIGIN_REGION_KEY,
+};
+use super::event_store::{audit_metadata, ConcurrencyConflict};
+use super::storage::EventStorage;
+
+// ============================================================================
+// Postgres Event Storage (feature "postgres")
+// ============================================================================
+//
+// EventStorage on PostgreSQL, for services that embed the command side
+// without a Scylla cluster. Same tables as the Scylla schema, minus the
+// Scylla-only parts (aggregate_sequence, snapshots, CDC):
+//
+//   event_store      PRIMARY KEY (aggregate_id, sequence_number)
+//   outbox_messages  written in the same transaction as the events
+//
+// Concurrency: the append transaction reads the aggregate's highest
+// sequence number and fails with ConcurrencyConflict unless it equals
+// `expected_version`. Two appends racing past that check collide on the
+// primary key; the loser's unique violation is reported as a conflict too.
+//
+// The audit metadata of an event (everything but its origin region) is
+// stored as a JSON object in `metadata`. Repeated command ids are found
+// through the `command_id` in it, with no expiry.
+//
+// The outbox is not relayed by this crate (the relay reads Scylla CDC);
+// poll it or attach logical replication.
+//
+// ============================================================================
+
+/// DDL of the tables PostgresEventStorage uses
+pub const POSTGRES_SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS event_store (
+    aggregate_id    UUID        NOT NULL,
+    sequence_number BIGINT      NOT NULL,
+    event_id        UUID        NOT NULL,
+    event_type      TEXT        NOT NULL,
+    event_version   INT         NOT NULL,
+    event_data      TEXT        NOT NULL,
+    causation_id    UUID,
+    correlation_id  UUID        NOT NULL,
+    timestamp       TIMESTAMPTZ NOT NULL,
+    origin_region   TEXT,
+    user_id         UUID,
+    metadata        TEXT,
+    PRIMARY KEY (aggregate_id, sequence_number)
+);
+ALTER TABLE event_store ADD COLUMN IF NOT EXISTS user_id UUID;
+ALTER TABLE event_store ADD COLUMN IF NOT EXISTS metadata TEXT;
+CREATE TABLE IF NOT EXISTS outbox_messages (
+    id              UUID        PRIMARY KEY,
+    aggregate_id    UUID        NOT NULL,
+    aggregate_type  TEXT        NOT NULL,
+    event_id        UUID        NOT NULL,
+    event_type      TEXT        NOT NULL,
+    event_version   INT         NOT NULL,
+    sequence_number BIGINT      NOT NULL,
+    payload         TEXT        NOT NULL,
+    topic           TEXT        NOT NULL,
+    partition_key   TEXT        NOT NULL,
+    causation_id    UUID,
+    correlation_id  UUID        NOT NULL,
+    origin_region   TEXT,
+    created_at      TIMESTAMPTZ NOT NULL
+);
+ALTER TABLE outbox_messages ADD COLUMN IF NOT EXISTS aggregate_event_type TEXT;
+ALTER TABLE outbox_messages ADD COLUMN IF NOT EXISTS variant_type TEXT;
+ALTER TABLE outbox_messages ADD COLUMN IF NOT EXISTS publish_after TIMESTAMPTZ;
+";
+
+type EventRow = (
+    Uuid, i64, Uuid, String, i32, String, Option<Uuid>, Uuid, DateTime<Utc>, Option<String>,
+    Option<Uuid>, Option<String>,
+);
+
+pub struct PostgresEventStorage<E: DomainEvent> {
+    pool: PgPool,
+    aggregate_type_name: String,
+    topic_name: String,
+    _phantom: PhantomData<E>,
+}
+
+impl<E: DomainEvent> PostgresEventStorage<E> {
+    pub fn new(pool: PgPool, aggregate_type_name: &str, topic_name: &str) -> Self {
+        Self {
+            pool,
+            aggregate_type_name: aggregate_type_name.to_string(),
+            topic_name: topic_name.to_string(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Create the tables if they do not exist
+    pub async fn ensure_schema(&self) -> Result<()> {
+        sqlx::raw_sql(POSTGRES_SCHEMA).execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn conflict(&self, aggregate_id: Uuid, expected_version: i64) -> anyhow::Error {
+        let current_version = self.current_version(None, aggregate_id).await.unwrap_or(expected_version);
+        ConcurrencyConflict { aggregate_id, expected_version, current_version }.into()
+    }
+}
+
+#[async_trait]
+impl<E: DomainEvent> EventStorage<E> for PostgresEventStorage<E> {
+    async fn append(
+        &self,
+        deadline: Option<&Deadline>,
+        aggregate_id: Uuid,
+        expected_version: i64,
+        events: Vec<EventEnvelope<E>>,
+        publish_to_outbox: bool,
+    ) -> Result<i64> {
+        if events.is_empty() {
+            bail!("Cannot append empty event list");
+        }
+
+        let written = with_deadline(deadline, "event_storage.append", async {
+            let mut tx = self.pool.begin().await?;
+
+            let current_version: i64 = sqlx::query_scalar(
+                "SELECT COALESCE(MAX(sequence_number), 0) FROM event_store WHERE aggregate_id = $1",
+            )
+            .bind(aggregate_id)
+            .fetch_one(&mut *tx)
+            .await?;
+            if current_version != expected_version {
+                return Ok(None);
+            }
+
+            let mut new_version = expected_version;
+            for envelope in &events {
+                new_version += 1;
+                let event_json = serialize_event(&envelope.event_data)?;
+                let origin_region = envelope.origin_region();
+                let metadata = audit_metadata(&envelope.metadata).map(|m| serde_json::to_string(&m)).transpose()?;
+
+                let inserted = sqlx::query(
+                    "INSERT INTO event_store (
+                        aggregate_id, sequence_number, event_id, event_type, event_version,
+                        event_data, causation_id, correlation_id, timestamp, origin_region,
+                        user_id, metadata
+                    ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+                )
+                .bind(aggregate_id)
+                .bind(new_version)
+                .bind(envelope.event_id)
+                .bind(&envelope.event_type)
+                .bind(envelope.event_version)
+                .bind(&event_json)
+                .bind(envelope.causation_id)
+                .bind(envelope.correlation_id)
+                .bind(envelope.timestamp)
+                .bind(origin_region)
+                .bind(envelope.user_id)
+                .bind(metadata)
+                .execute(&mut *tx)
+                .await;
+                match inserted {
+                    Err(sqlx::Error::Database(ref e)) if e.is_unique_violation() => return Ok(None),
+                    other => other?,
+                };
+
+                if publish_to_outbox {
+                    let type_names = envelope.event_data.type_names();
+                    sqlx::query(
+                        "INSERT INTO outbox_messages (
+                            id, aggregate_id, aggregate_type, event_id, event_type, event_version,
+                            sequence_number, payload, topic, partition_key, causation_id,
+                            correlation_id, origin_region, created_at, aggregate_event_type, variant_type,
+                            publish_after
+                        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)",
+                    )
+                    .bind(Uuid::new_v4())
+                    .bind(aggregate_id)
+                    .bind(&self.aggregate_type_name)
+                    .bind(envelope.event_id)
+                    .bind(&envelope.event_type)
+                    .bind(envelope.event_version)
+                    .bind(new_version)
+                    .bind(&event_json)
+                    .bind(&self.topic_name)
+                    .bind(aggregate_id.to_string())
+                    .bind(envelope.causation_id)
+                    .bind(envelope.correlation_id)
+                    .bind(origin_region)
+                    .bind(Utc::now())
+                    .bind(type_names.aggregate_event_type)
+                    .bind(type_names.variant_type)
+                    .bind(envelope.publish_after())
+                    .execute(&mut *tx)
+                    .await?;
+                }
+            }
+
+            tx.commit().await?;
+            Ok(Some(new_version))
+        })
+        .await?;
+
+        match written {
+            Some(new_version) => Ok(new_version),
+            None => Err(self.conflict(aggregate_id, expected_version).await),
+        }
+    }
+
+    async fn load(&self, deadline: Option<&Deadline>, aggregate_id: Uuid) -> Result<Vec<EventEnvelope<E>>> {
+        let rows: Vec<EventRow> = with_deadline(deadline, "event_storage.load", async {
+            Ok(sqlx::query_as(
+                "SELECT aggregate_id, sequence_number, event_id, event_type, event_version,
+                        event_data, causation_id, correlation_id, timestamp, origin_region,
+                        user_id, metadata
+                 FROM event_store WHERE aggregate_id = $1 ORDER BY sequence_number ASC",
+            )
+            .bind(aggregate_id)
+            .fetch_all(&self.pool)
+            .await?)
+        })
+        .await?;
+
+        rows.into_iter().map(envelope_from_row).collect()
+    }
+
+    async fn current_version(&self, deadline: Option<&Deadline>, aggregate_id: Uuid) -> Result<i64> {
+        with_deadline(deadline, "event_storage.current_version", async {
+            Ok(sqlx::query_scalar("SELECT COALESCE(MAX(sequence_number), 0) FROM event_store WHERE aggregate_id = $1")
+                .bind(aggregate_id)
+                .fetch_one(&self.pool)
+                .await?)
+        })
+        .await
+    }
+
+    async fn command_version(&self, deadline: Option<&Deadline>, aggregate_id: Uuid, command_id: Uuid) -> Result<Option<i64>> {
+        with_deadline(deadline, "event_storage.command_version", async {
+            Ok(sqlx::query_scalar(
+                "SELECT MAX(sequence_number) FROM event_store
+                 WHERE aggregate_id = $1 AND metadata::jsonb ->> $2 = $3",
+            )
+            .bind(aggregate_id)
+            .bind(COMMAND_ID_KEY)
+            .bind(command_id.to_string())
+            .fetch_one(&self.pool)
+            .await?)
+        })
+        .await
+    }
+}
+
+fn envelope_from_row<E: DomainEvent>(row: EventRow) -> Result<EventEnvelope<E>> {
+    let (aggregate_id, sequence_number, event_id, event_type, event_version, event_data_json, causation_id, correlation_id, timestamp, origin_region, user_id, metadata) = row;
+
+    let mut metadata: HashMap<String, String> = match metadata {
+        Some(json) => serde_json::from_str(&json)?,
+        None => HashMap::new(),
+    };
+    if let Some(region) = origin_region {
+        metadata.insert(ORIGIN_REGION_KEY.to_string(), region);
+    }
+
+    Ok(EventEnvelope {
+        event_id,
+        aggregate_id,
+        sequence_number,
+        event_type,
+        event_version,
+        event_data: serde_json::from_str(&event_data_json)?,
+        causation_id,
+        correlation_id,
+        user_id,
+        timestamp,
+        metadata,
+    })
+}
@@ -0,0 +1,307 @@
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use futures_util::TryStreamExt;
+use scylla::client::session::Session;
+use scylla::statement::batch::{Batch, BatchType};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+// ============================================================================
+// Event Store Sharding - Aggregates Spread over N Event Tables
+// ============================================================================
+//
+// One event_store table holds every event of every aggregate. Partitions
+// stay small (one per aggregate), but at billions of rows a single table
+// makes compaction, repair and secondary indexes the bottleneck. With
+// EVENT_STORE_SHARDS = N > 1 the events live in event_store_0 ..
+// event_store_{N-1} instead; each aggregate belongs to exactly one shard.
+//
+// The shard is a jump consistent hash of the aggregate id: stable across
+// processes and releases, and growing N to N+1 moves only ~1/(N+1) of the
+// aggregates. EventStore resolves the table per aggregate, so callers do
+// not notice. aggregate_sequence, outbox and snapshots stay unsharded.
+//
+// N = 1 (the default) keeps the plain event_store table.
+//
+// Changing the shard count is an offline operation (writers stopped):
+//
+//   scylladb_cdc reshard --from 1 --to 8 [--dry-run]
+//
+// creates the target tables, copies every aggregate whose shard changes,
+// verifies the copy and only then deletes the source partition. Restart
+// with the new EVENT_STORE_SHARDS afterwards. An interrupted run can be
+// repeated; already moved aggregates are skipped.
+//
+// ============================================================================
+
+/// Upper bound for the shard count
+pub const MAX_SHARDS: u32 = 1024;
+
+const UNSHARDED_TABLE: &str = "event_store";
+
+/// How aggregates map to event tables
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShardLayout {
+    shards: u32,
+}
+
+impl Default for ShardLayout {
+    fn default() -> Self {
+        Self { shards: 1 }
+    }
+}
+
+impl ShardLayout {
+    pub fn new(shards: u32) -> Result<Self> {
+        if !(1..=MAX_SHARDS).contains(&shards) {
+            bail!("Shard count must be within 1..={}, got {}", MAX_SHARDS, shards);
+        }
+        Ok(Self { shards })
+    }
+
+    pub fn shards(&self) -> u32 {
+        self.shards
+    }
+
+    pub fn is_sharded(&self) -> bool {
+        self.shards > 1
+    }
+
+    /// Shard of an aggregate (always 0 when unsharded)
+    pub fn shard_for(&self, aggregate_id: Uuid) -> u32 {
+        let (high, low) = aggregate_id.as_u64_pair();
+        jump_hash(high ^ low, self.shards)
+    }
+
+    /// Event table holding an aggregate's events
+    pub fn table_for(&self, aggregate_id: Uuid) -> String {
+        self.table(self.shard_for(aggregate_id))
+    }
+
+    /// Every event table of this layout
+    pub fn tables(&self) -> Vec<String> {
+        (0..self.shards).map(|shard| self.table(shard)).collect()
+    }
+
+    fn table(&self, shard: u32) -> String {
+        if self.is_sharded() {
+            format!("{}_{}", UNSHARDED_TABLE, shard)
+        } else {
+            UNSHARDED_TABLE.to_string()
+        }
+    }
+}
+
+/// Jump consistent hash (Lamping & Veach): bucket in 0..buckets
+fn jump_hash(mut key: u64, buckets: u32) -> u32 {
+    let (mut bucket, mut next) = (-1i64, 0i64);
+    while next < buckets as i64 {
+        bucket = next;
+        key = key.wrapping_mul(2_862_933_555_777_941_757).wrapping_add(1);
+        next = ((bucket + 1) as f64 * ((1u64 << 31) as f64 / ((key >> 33) + 1) as f64)) as i64;
+    }
+    bucket as u32
+}
+
+// ============================================================================
+// Offline Rebalancing
+// ============================================================================
+
+/// Outcome of a reshard run
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct RebalanceReport {
+    pub from_shards: u32,
+    pub to_shards: u32,
+    pub dry_run: bool,
+    pub aggregates_scanned: u64,
+    pub aggregates_moved: u64,
+    pub events_moved: u64,
+    /// Aggregates whose copy did not verify; their source was kept
+    pub failed: Vec<Uuid>,
+}
+
+type StoredEventRow = (
+    Uuid, i64, Uuid, String, i32, String, Option<Uuid>, Uuid, DateTime<Utc>, Option<String>,
+    Option<Uuid>, Option<HashMap<String, String>>,
+);
+
+const STORED_EVENT_COLUMNS: &str = "aggregate_id, sequence_number, event_id, event_type, event_version, \
+    event_data, causation_id, correlation_id, timestamp, origin_region, user_id, metadata";
+
+/// Events copied per batch when moving an aggregate
+const MOVE_BATCH_SIZE: usize = 100;
+
+/// Moves aggregates between shard layouts; writers must be stopped
+pub struct ShardRebalancer {
+    session: Arc<Session>,
+    from: ShardLayout,
+    to: ShardLayout,
+}
+
+impl ShardRebalancer {
+    pub fn new(session: Arc<Session>, from: ShardLayout, to: ShardLayout) -> Self {
+        Self { session, from, to }
+    }
+
+    /// Create the target tables (same definition and indexes as event_store)
+    pub async fn ensure_tables(&self) -> Result<()> {
+        for table in self.to.tables() {
+            self.session
+                .query_unpaged(
+                    format!(
+                        "CREATE TABLE IF NOT EXISTS {table} (
+                            aggregate_id UUID, sequence_number BIGINT, event_id UUID, event_type TEXT,
+                            event_version INT, event_data TEXT, causation_id UUID, correlation_id UUID,
+                            timestamp TIMESTAMP, origin_region TEXT, user_id UUID, metadata MAP<TEXT, TEXT>,
+                            PRIMARY KEY (aggregate_id, sequence_number)
+                        ) WITH CLUSTERING ORDER BY (sequence_number ASC)"
+                    ),
+                    &[],
+                )
+                .await?;
+            for column in ["event_type", "timestamp", "correlation_id"] {
+                self.session
+                    .query_unpaged(format!("CREATE INDEX IF NOT EXISTS ON {} ({})", table, column), &[])
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Move every aggregate whose table differs between the layouts
+    pub async fn run(&self, dry_run: bool) -> Result<RebalanceReport> {
+        let mut report = RebalanceReport {
+            from_shards: self.from.shards(),
+            to_shards: self.to.shards(),
+            dry_run,
+            ..Default::default()
+        };
+        if !dry_run {
+            self.ensure_tables().await?;
+        }
+
+        // aggregate_sequence has one row per aggregate, whatever the layout
+        let mut ids = self
+            .session
+            .query_iter("SELECT aggregate_id FROM aggregate_sequence", &[])
+            .await?
+            .rows_stream::<(Uuid,)>()?;
+
+        while let Some((aggregate_id,)) = ids.try_next().await? {
+            report.aggregates_scanned += 1;
+            let (source, target) = (self.from.table_for(aggregate_id), self.to.table_for(aggregate_id));
+            if source == target {
+                continue;
+            }
+
+            let events = self.load(&source, aggregate_id).await?;
+            if events.is_empty() {
+                // Moved by an earlier, interrupted run
+                continue;
+            }
+            report.aggregates_moved += 1;
+            report.events_moved += events.len() as u64;
+            if dry_run {
+                continue;
+            }
+
+            if let Err(e) = self.move_aggregate(aggregate_id, &source, &target, &events).await {
+                tracing::error!(aggregate_id = %aggregate_id, source = %source, target = %target, error = %e, "Failed to move aggregate");
+                report.failed.push(aggregate_id);
+            }
+        }
+
+        tracing::info!(
+            from = report.from_shards,
+            to = report.to_shards,
+            scanned = report.aggregates_scanned,
+            moved = report.aggregates_moved,
+            failed = report.failed.len(),
+            dry_run = dry_run,
+            "Event store reshard finished"
+        );
+        Ok(report)
+    }
+
+    async fn load(&self, table: &str, aggregate_id: Uuid) -> Result<Vec<StoredEventRow>> {
+        let result = self
+            .session
+            .query_unpaged(
+                format!("SELECT {} FROM {} WHERE aggregate_id = ?", STORED_EVENT_COLUMNS, table),
+                (aggregate_id,),
+            )
+            .await?
+            .into_rows_result()?;
+        Ok(result.rows::<StoredEventRow>()?.collect::<std::result::Result<_, _>>()?)
+    }
+
+    /// Copy, verify, then delete the source partition
+    async fn move_aggregate(&self, aggregate_id: Uuid, source: &str, target: &str, events: &[StoredEventRow]) -> Result<()> {
+        let insert = format!("INSERT INTO {} ({}) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)", target, STORED_EVENT_COLUMNS);
+        // Single-partition unlogged batches, small enough for the batch size limit
+        for chunk in events.chunks(MOVE_BATCH_SIZE) {
+            let mut batch = Batch::new(BatchType::Unlogged);
+            for _ in chunk {
+                batch.append_statement(insert.as_str());
+            }
+            self.session.batch(&batch, chunk.to_vec()).await?;
+        }
+
+        let copied = self.load(target, aggregate_id).await?;
+        if copied.len() != events.len() {
+            bail!("Copied {} of {} events to {}", copied.len(), events.len(), target);
+        }
+
+        self.session
+            .query_unpaged(format!("DELETE FROM {} WHERE aggregate_id = ?", source), (aggregate_id,))
+            .await?;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tables_and_stable_assignment() {
+        let unsharded = ShardLayout::default();
+        let id = Uuid::from_u128(0x0123_4567_89ab_4def_8123_4567_89ab_cdef);
+        assert_eq!(unsharded.table_for(id), "event_store");
+        assert_eq!(unsharded.tables(), vec!["event_store"]);
+
+        let layout = ShardLayout::new(4).unwrap();
+        assert_eq!(layout.tables(), vec!["event_store_0", "event_store_1", "event_store_2", "event_store_3"]);
+        // The same id always lands on the same shard
+        assert_eq!(layout.table_for(id), layout.table_for(id));
+        assert!(layout.shard_for(id) < 4);
+
+        assert!(ShardLayout::new(0).is_err());
+        assert!(ShardLayout::new(MAX_SHARDS + 1).is_err());
+    }
+
+    #[test]
+    fn test_expansion_moves_few_aggregates_and_balances() {
+        let ids: Vec<Uuid> = (0..10_000).map(|_| Uuid::new_v4()).collect();
+        let (eight, nine) = (ShardLayout::new(8).unwrap(), ShardLayout::new(9).unwrap());
+
+        let moved = ids.iter().filter(|id| eight.shard_for(**id) != nine.shard_for(**id)).count();
+        // Ideal is 1/9 (~1111); a modulo hash would move ~8/9
+        assert!((800..1500).contains(&moved), "moved {}", moved);
+
+        let mut per_shard = [0usize; 8];
+        for id in &ids {
+            per_shard[eight.shard_for(*id) as usize] += 1;
+        }
+        assert!(per_shard.iter().all(|n| (1_000..1_500).contains(n)), "{:?}", per_shard);
+
+        // Aggregates that move only ever go to the new shard
+        assert!(ids.iter().all(|id| eight.shard_for(*id) == nine.shard_for(*id) || nine.shard_for(*id) == 8));
+    }
+}
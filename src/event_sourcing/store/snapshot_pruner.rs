@@ -0,0 +1,359 @@
+use futures_util::TryStreamExt;
+use scylla::statement::batch::BatchType;
+use std::cmp::Reverse;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+use anyhow::Result;
+
+use crate::db::{Operation, StatementCache};
+use crate::metrics::MetricsHandle;
+use super::TenantContext;
+
+// ============================================================================
+// Snapshot Pruner - Retention and Integrity for aggregate_snapshots
+// ============================================================================
+//
+// Snapshots are an optimization; only the newest few are ever useful.
+// Without pruning, every snapshot ever taken stays in aggregate_snapshots.
+//
+// Responsibilities:
+// 1. Keep only the last N snapshots per aggregate (retention)
+// 2. Reject snapshots that claim a version beyond the aggregate's current
+//    version (integrity) - such a snapshot can never have been produced by
+//    replaying committed events and must not be used for hydration
+// 3. Run periodically in the background
+// 4. Report snapshots pruned and storage reclaimed via metrics
+//
+// A pass pages through the aggregates that have snapshots; the per-aggregate
+// reads and deletes are prepared statements (StatementCache). A failed read
+// fails the aggregate (or the pass) instead of passing for "no snapshots".
+//
+// A pruner works on the tables of one tenant (see tenant.rs); main runs one
+// for the shared tables and one per [[tenants]] entry, with the retention
+// of the [event_store] config (SNAPSHOT_KEEP_LAST, SNAPSHOT_PRUNE_INTERVAL_SECS).
+//
+// ============================================================================
+
+/// Retention policy for aggregate snapshots
+#[derive(Debug, Clone)]
+pub struct SnapshotRetentionPolicy {
+    /// Number of most recent snapshots to keep per aggregate
+    pub keep_last: usize,
+    /// How often the background pruner runs
+    pub interval: Duration,
+}
+
+impl Default for SnapshotRetentionPolicy {
+    fn default() -> Self {
+        Self {
+            keep_last: 3,
+            interval: Duration::from_secs(3600),
+        }
+    }
+}
+
+/// A stored snapshot, as seen by the pruner
+#[derive(Debug, Clone)]
+pub struct SnapshotInfo {
+    pub sequence_number: i64,
+    pub size_bytes: usize,
+}
+
+/// Which snapshots of one aggregate should be removed
+#[derive(Debug, Default, PartialEq)]
+pub struct PrunePlan {
+    /// Snapshots newer than the aggregate's current version
+    pub invalid: Vec<i64>,
+    /// Valid snapshots beyond the retention limit
+    pub expired: Vec<i64>,
+    /// Total payload size of all snapshots in the plan
+    pub reclaimed_bytes: usize,
+}
+
+impl PrunePlan {
+    pub fn is_empty(&self) -> bool {
+        self.invalid.is_empty() && self.expired.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.invalid.len() + self.expired.len()
+    }
+}
+
+impl SnapshotRetentionPolicy {
+    /// Decide which snapshots to remove for a single aggregate
+    pub fn plan(&self, snapshots: &[SnapshotInfo], current_version: i64) -> PrunePlan {
+        let mut plan = PrunePlan::default();
+
+        let mut valid: Vec<&SnapshotInfo> = Vec::new();
+        for snapshot in snapshots {
+            if snapshot.sequence_number > current_version {
+                plan.invalid.push(snapshot.sequence_number);
+                plan.reclaimed_bytes += snapshot.size_bytes;
+            } else {
+                valid.push(snapshot);
+            }
+        }
+
+        // Newest first, keep the first `keep_last`
+        valid.sort_by_key(|snapshot| Reverse(snapshot.sequence_number));
+        for snapshot in valid.into_iter().skip(self.keep_last) {
+            plan.expired.push(snapshot.sequence_number);
+            plan.reclaimed_bytes += snapshot.size_bytes;
+        }
+
+        plan
+    }
+}
+
+pub struct SnapshotPruner {
+    statements: Arc<StatementCache>,
+    policy: SnapshotRetentionPolicy,
+    tenant: TenantContext,
+    metrics: MetricsHandle,
+}
+
+impl SnapshotPruner {
+    pub fn new(statements: Arc<StatementCache>, policy: SnapshotRetentionPolicy) -> Self {
+        Self {
+            statements,
+            policy,
+            tenant: TenantContext::default(),
+            metrics: MetricsHandle::noop(),
+        }
+    }
+
+    /// Prune the snapshots of `tenant` instead of the shared tables
+    pub fn with_tenant(mut self, tenant: TenantContext) -> Self {
+        self.tenant = tenant;
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: MetricsHandle) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Apply retention and integrity checks to one aggregate's snapshots
+    pub async fn prune_aggregate(&self, aggregate_id: Uuid) -> Result<PrunePlan> {
+        let current_version = self.current_version(aggregate_id).await?;
+        let snapshots = self.list_snapshots(aggregate_id).await?;
+
+        let plan = self.policy.plan(&snapshots, current_version);
+        if plan.is_empty() {
+            return Ok(plan);
+        }
+
+        for sequence_number in &plan.invalid {
+            tracing::error!(
+                aggregate_id = %aggregate_id,
+                snapshot_version = sequence_number,
+                current_version = current_version,
+                "Snapshot version exceeds aggregate version - removing invalid snapshot"
+            );
+        }
+
+        // One partition, so an unlogged batch is still atomic
+        let delete = self
+            .statements
+            .statement(
+                Operation::Append,
+                &format!(
+                    "DELETE FROM {} WHERE aggregate_id = ? AND sequence_number = ?",
+                    self.tenant.table("aggregate_snapshots")
+                ),
+            )
+            .await?;
+        let mut batch = self.statements.batch(Operation::Append, BatchType::Unlogged);
+        let mut values: Vec<(Uuid, i64)> = Vec::new();
+        for sequence_number in plan.invalid.iter().chain(plan.expired.iter()) {
+            batch.append_statement(delete.clone());
+            values.push((aggregate_id, *sequence_number));
+        }
+        self.statements.session().batch(&batch, values).await?;
+
+        self.metrics.record_snapshots_pruned(
+            plan.expired.len() as u64,
+            plan.invalid.len() as u64,
+            plan.reclaimed_bytes as u64,
+        );
+
+        tracing::info!(
+            tenant = self.tenant.tenant_id().unwrap_or_default(),
+            aggregate_id = %aggregate_id,
+            expired = plan.expired.len(),
+            invalid = plan.invalid.len(),
+            reclaimed_bytes = plan.reclaimed_bytes,
+            "🧹 Pruned aggregate snapshots"
+        );
+
+        Ok(plan)
+    }
+
+    /// Prune snapshots for every aggregate that has any
+    pub async fn prune_all(&self) -> Result<usize> {
+        // Paged: one page of partition keys in memory at a time
+        let mut rows = self
+            .statements
+            .session()
+            .query_iter(format!("SELECT DISTINCT aggregate_id FROM {}", self.tenant.table("aggregate_snapshots")), &[])
+            .await?
+            .rows_stream::<(Uuid,)>()?;
+
+        let mut pruned = 0;
+        while let Some((aggregate_id,)) = rows.try_next().await? {
+            match self.prune_aggregate(aggregate_id).await {
+                Ok(plan) => pruned += plan.len(),
+                Err(e) => {
+                    tracing::warn!(
+                        tenant = self.tenant.tenant_id().unwrap_or_default(),
+                        aggregate_id = %aggregate_id,
+                        error = %e,
+                        "Failed to prune snapshots for aggregate"
+                    );
+                }
+            }
+        }
+
+        Ok(pruned)
+    }
+
+    /// Run `prune_all` on the policy interval until the task is aborted
+    pub fn spawn_background(self) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.policy.interval);
+            loop {
+                interval.tick().await;
+
+                match self.prune_all().await {
+                    Ok(pruned) => {
+                        tracing::debug!(tenant = self.tenant.tenant_id().unwrap_or_default(), pruned = pruned, "Snapshot pruning pass complete");
+                    }
+                    Err(e) => {
+                        tracing::error!(tenant = self.tenant.tenant_id().unwrap_or_default(), error = %e, "Snapshot pruning pass failed");
+                    }
+                }
+            }
+        })
+    }
+
+    async fn list_snapshots(&self, aggregate_id: Uuid) -> Result<Vec<SnapshotInfo>> {
+        // An aggregate that was never pruned may have many; paged
+        let statement = self
+            .statements
+            .statement(
+                Operation::Read,
+                &format!(
+                    "SELECT sequence_number, snapshot_data FROM {} WHERE aggregate_id = ?",
+                    self.tenant.table("aggregate_snapshots")
+                ),
+            )
+            .await?;
+        let mut rows = self
+            .statements
+            .session()
+            .execute_iter(statement, (aggregate_id,))
+            .await?
+            .rows_stream::<(i64, Option<String>)>()?;
+
+        let mut snapshots = Vec::new();
+        while let Some((sequence_number, snapshot_data)) = rows.try_next().await? {
+            snapshots.push(SnapshotInfo {
+                sequence_number,
+                size_bytes: snapshot_data.map(|d| d.len()).unwrap_or(0),
+            });
+        }
+
+        Ok(snapshots)
+    }
+
+    /// The aggregate's sequence; 0 when it has none
+    async fn current_version(&self, aggregate_id: Uuid) -> Result<i64> {
+        let row = self
+            .statements
+            .execute(
+                Operation::Read,
+                &format!("SELECT current_sequence FROM {} WHERE aggregate_id = ?", self.tenant.table("aggregate_sequence")),
+                (aggregate_id,),
+            )
+            .await?
+            .into_rows_result()?
+            .maybe_first_row::<(i64,)>()?;
+
+        Ok(row.map_or(0, |(version,)| version))
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(sequence_number: i64, size_bytes: usize) -> SnapshotInfo {
+        SnapshotInfo { sequence_number, size_bytes }
+    }
+
+    fn policy(keep_last: usize) -> SnapshotRetentionPolicy {
+        SnapshotRetentionPolicy {
+            keep_last,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_plan_keeps_last_n() {
+        let snapshots = vec![
+            snapshot(100, 10),
+            snapshot(200, 10),
+            snapshot(300, 10),
+            snapshot(400, 10),
+        ];
+
+        let plan = policy(2).plan(&snapshots, 450);
+
+        assert!(plan.invalid.is_empty());
+        assert_eq!(plan.expired, vec![200, 100]);
+        assert_eq!(plan.reclaimed_bytes, 20);
+    }
+
+    #[test]
+    fn test_plan_nothing_to_prune() {
+        let snapshots = vec![snapshot(100, 10), snapshot(200, 10)];
+
+        let plan = policy(3).plan(&snapshots, 250);
+
+        assert!(plan.is_empty());
+        assert_eq!(plan.reclaimed_bytes, 0);
+    }
+
+    #[test]
+    fn test_plan_flags_snapshot_beyond_current_version() {
+        let snapshots = vec![snapshot(100, 10), snapshot(200, 25)];
+
+        let plan = policy(3).plan(&snapshots, 150);
+
+        assert_eq!(plan.invalid, vec![200]);
+        assert!(plan.expired.is_empty());
+        assert_eq!(plan.reclaimed_bytes, 25);
+    }
+
+    #[test]
+    fn test_invalid_snapshots_do_not_count_towards_retention() {
+        let snapshots = vec![
+            snapshot(100, 1),
+            snapshot(200, 1),
+            snapshot(300, 1),
+        ];
+
+        // 300 is invalid, so 200 and 100 are the two newest valid snapshots
+        let plan = policy(2).plan(&snapshots, 250);
+
+        assert_eq!(plan.invalid, vec![300]);
+        assert!(plan.expired.is_empty());
+        assert_eq!(plan.len(), 1);
+    }
+}
@@ -0,0 +1,74 @@
+// ============================================================================
+// Snapshot Policy - When Aggregates Are Snapshotted
+// ============================================================================
+//
+// Loading an aggregate with thousands of events replays all of them. With a
+// SnapshotPolicy attached, the EventStore:
+// - saves the aggregate state to aggregate_snapshots whenever an append
+//   crosses a multiple of `every` events (`snapshot_after_append`)
+// - hydrates from the newest snapshot plus the events after it
+//   (`load_from_snapshot`)
+//
+// `schema_version` is stored with every snapshot (aggregate_version). Bump
+// it when the aggregate's serialized shape changes: snapshots of another
+// version are ignored and the aggregate is replayed from its events.
+// Unreadable snapshots also fall back to a full replay.
+//
+// Retention of old snapshots is the SnapshotPruner's job.
+//
+// The frequency is configured through AppConfig (event_store.snapshot_every
+// / SNAPSHOT_EVERY, default 100, 0 disables).
+//
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SnapshotPolicy {
+    /// Snapshot every `every` events
+    pub every: i64,
+    /// Version of the aggregate's serialized state
+    pub schema_version: i32,
+}
+
+impl Default for SnapshotPolicy {
+    fn default() -> Self {
+        Self { every: 100, schema_version: 1 }
+    }
+}
+
+impl SnapshotPolicy {
+    pub fn every(every: i64) -> Self {
+        Self { every, ..Self::default() }
+    }
+
+    /// Whether an append from `previous_version` to `new_version` crossed
+    /// a snapshot boundary
+    pub fn is_due(&self, previous_version: i64, new_version: i64) -> bool {
+        self.every > 0 && new_version / self.every > previous_version / self.every
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_due_when_crossing_a_boundary() {
+        let policy = SnapshotPolicy::every(10);
+
+        assert!(!policy.is_due(0, 9));
+        assert!(policy.is_due(9, 10));
+        // Multi-event append jumping over the boundary
+        assert!(policy.is_due(8, 12));
+        assert!(!policy.is_due(10, 19));
+        assert!(policy.is_due(19, 31));
+    }
+
+    #[test]
+    fn test_disabled_policy_never_snapshots() {
+        assert!(!SnapshotPolicy::every(0).is_due(0, 1_000));
+    }
+}
@@ -0,0 +1,209 @@
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+use crate::event_sourcing::core::{Deadline, DomainEvent, EventEnvelope};
+use super::event_store::{ConcurrencyConflict, EventStore};
+
+// ============================================================================
+// Event Storage - Pluggable Persistence Backends
+// ============================================================================
+//
+// The operations a CommandHandler needs from its event store, independent
+// of where the events live:
+//
+//   - EventStore (ScyllaDB)      - production; also snapshots, fencing,
+//                                  sharding and the CDC outbox
+//   - InMemoryEventStorage       - unit tests of domain and command handlers
+//   - PostgresEventStorage       - feature "postgres" (see postgres.rs)
+//
+// Every backend enforces optimistic concurrency the same way: an append
+// whose `expected_version` is not the aggregate's current version fails
+// with a typed ConcurrencyConflict (inside anyhow::Error), so the command
+// handler's conflict retry works on all of them.
+//
+// Each also remembers which command produced which version: events stamped
+// with a command id (CommandContext::with_command_id) are found again by
+// `command_version`, so the command handler can skip a repeated command.
+//
+// ============================================================================
+
+/// Append-only storage of one aggregate type's events
+#[async_trait]
+pub trait EventStorage<E: DomainEvent>: Send + Sync {
+    /// Append events after `expected_version`; returns the new version
+    async fn append(
+        &self,
+        deadline: Option<&Deadline>,
+        aggregate_id: Uuid,
+        expected_version: i64,
+        events: Vec<EventEnvelope<E>>,
+        publish_to_outbox: bool,
+    ) -> Result<i64>;
+
+    /// All events of the aggregate in sequence order (empty when unknown)
+    async fn load(&self, deadline: Option<&Deadline>, aggregate_id: Uuid) -> Result<Vec<EventEnvelope<E>>>;
+
+    /// Current version of the aggregate (0 when unknown)
+    async fn current_version(&self, deadline: Option<&Deadline>, aggregate_id: Uuid) -> Result<i64>;
+
+    /// Version the aggregate reached with the events of `command_id`, if any
+    async fn command_version(&self, deadline: Option<&Deadline>, aggregate_id: Uuid, command_id: Uuid) -> Result<Option<i64>>;
+
+    async fn exists(&self, deadline: Option<&Deadline>, aggregate_id: Uuid) -> Result<bool> {
+        Ok(self.current_version(deadline, aggregate_id).await? > 0)
+    }
+}
+
+#[async_trait]
+impl<E: DomainEvent> EventStorage<E> for EventStore<E> {
+    async fn append(
+        &self,
+        deadline: Option<&Deadline>,
+        aggregate_id: Uuid,
+        expected_version: i64,
+        events: Vec<EventEnvelope<E>>,
+        publish_to_outbox: bool,
+    ) -> Result<i64> {
+        self.append_events_within(deadline, aggregate_id, expected_version, events, publish_to_outbox).await
+    }
+
+    async fn load(&self, deadline: Option<&Deadline>, aggregate_id: Uuid) -> Result<Vec<EventEnvelope<E>>> {
+        self.load_events_within(deadline, aggregate_id).await
+    }
+
+    async fn current_version(&self, deadline: Option<&Deadline>, aggregate_id: Uuid) -> Result<i64> {
+        self.get_current_version_within(deadline, aggregate_id).await
+    }
+
+    async fn command_version(&self, deadline: Option<&Deadline>, aggregate_id: Uuid, command_id: Uuid) -> Result<Option<i64>> {
+        self.command_version_within(deadline, aggregate_id, command_id).await
+    }
+}
+
+/// Events kept in process memory, for tests
+///
+/// Events appended with `publish_to_outbox` are also kept in `outbox()`.
+pub struct InMemoryEventStorage<E> {
+    events: Mutex<HashMap<Uuid, Vec<EventEnvelope<E>>>>,
+    outbox: Mutex<Vec<EventEnvelope<E>>>,
+}
+
+impl<E: DomainEvent> InMemoryEventStorage<E> {
+    pub fn new() -> Self {
+        Self {
+            events: Mutex::new(HashMap::new()),
+            outbox: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Events appended for publishing, in append order
+    pub fn outbox(&self) -> Vec<EventEnvelope<E>> {
+        self.outbox.lock().unwrap().clone()
+    }
+}
+
+impl<E: DomainEvent> Default for InMemoryEventStorage<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl<E: DomainEvent> EventStorage<E> for InMemoryEventStorage<E> {
+    async fn append(
+        &self,
+        _deadline: Option<&Deadline>,
+        aggregate_id: Uuid,
+        expected_version: i64,
+        events: Vec<EventEnvelope<E>>,
+        publish_to_outbox: bool,
+    ) -> Result<i64> {
+        if events.is_empty() {
+            bail!("Cannot append empty event list");
+        }
+
+        let mut stored = self.events.lock().unwrap();
+        let history = stored.entry(aggregate_id).or_default();
+        let current_version = history.len() as i64;
+        if current_version != expected_version {
+            return Err(ConcurrencyConflict { aggregate_id, expected_version, current_version }.into());
+        }
+
+        let mut new_version = expected_version;
+        for mut envelope in events {
+            new_version += 1;
+            envelope.sequence_number = new_version;
+            if publish_to_outbox {
+                self.outbox.lock().unwrap().push(envelope.clone());
+            }
+            history.push(envelope);
+        }
+        Ok(new_version)
+    }
+
+    async fn load(&self, _deadline: Option<&Deadline>, aggregate_id: Uuid) -> Result<Vec<EventEnvelope<E>>> {
+        Ok(self.events.lock().unwrap().get(&aggregate_id).cloned().unwrap_or_default())
+    }
+
+    async fn current_version(&self, _deadline: Option<&Deadline>, aggregate_id: Uuid) -> Result<i64> {
+        Ok(self.events.lock().unwrap().get(&aggregate_id).map_or(0, |history| history.len() as i64))
+    }
+
+    async fn command_version(&self, _deadline: Option<&Deadline>, aggregate_id: Uuid, command_id: Uuid) -> Result<Option<i64>> {
+        let stored = self.events.lock().unwrap();
+        let history = stored.get(&aggregate_id).map(Vec::as_slice).unwrap_or_default();
+        Ok(history
+            .iter()
+            .filter(|envelope| envelope.command_id() == Some(command_id))
+            .map(|envelope| envelope.sequence_number)
+            .max())
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct Noted {
+        text: String,
+    }
+
+    impl DomainEvent for Noted {
+        fn event_type() -> &'static str {
+            "Noted"
+        }
+    }
+
+    fn noted(aggregate_id: Uuid, text: &str) -> EventEnvelope<Noted> {
+        EventEnvelope::new(aggregate_id, 0, "Noted".to_string(), Noted { text: text.to_string() }, Uuid::new_v4())
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_append_enforces_expected_version() {
+        let storage = InMemoryEventStorage::new();
+        let id = Uuid::new_v4();
+
+        assert!(!storage.exists(None, id).await.unwrap());
+        assert_eq!(storage.append(None, id, 0, vec![noted(id, "a"), noted(id, "b")], true).await.unwrap(), 2);
+        assert_eq!(storage.append(None, id, 2, vec![noted(id, "c")], false).await.unwrap(), 3);
+
+        let stale = storage.append(None, id, 2, vec![noted(id, "d")], true).await.unwrap_err();
+        let conflict = stale.downcast_ref::<ConcurrencyConflict>().unwrap();
+        assert_eq!(conflict.current_version, 3);
+
+        let events = storage.load(None, id).await.unwrap();
+        assert_eq!(events.iter().map(|e| e.sequence_number).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(events[2].event_data.text, "c");
+        assert_eq!(storage.current_version(None, id).await.unwrap(), 3);
+        assert_eq!(storage.outbox().len(), 2);
+    }
+}
@@ -0,0 +1,130 @@
+use anyhow::{bail, Result};
+
+// ============================================================================
+// Tenants - Isolated Event Stores in One Deployment
+// ============================================================================
+//
+// A TenantContext says where a tenant's event sourcing tables live:
+//
+//   shared      the session's keyspace, plain table names (single tenant)
+//   keyspace    its own keyspace with the standard tables: `acme_ks.event_store`
+//   prefix      the session's keyspace, prefixed tables: `acme_event_store`
+//
+// EventStore resolves every table it touches (event tables, outbox,
+// aggregate_sequence, snapshots) through its tenant, so the events, versions
+// and outbox rows of two tenants never share a partition or table. The
+// coordinator runs one CDC reader per tenant outbox ([[tenants]] in the app
+// config), with its own checkpoint.
+//
+// A tenant's tables must exist: `migrate` creates the keyspace of every
+// keyspace tenant with its tables, and enables CDC on every tenant outbox;
+// prefixed tables are created from schema.cql with the prefix applied, and
+// `migrate` fails while a prefixed tenant's outbox is missing.
+//
+// ============================================================================
+
+/// Where a tenant's tables live
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum TenantScope {
+    /// The session's keyspace and table names
+    #[default]
+    Shared,
+    /// A keyspace of its own, standard table names
+    Keyspace(String),
+    /// The session's keyspace, tables named `<prefix>_<table>`
+    TablePrefix(String),
+}
+
+/// The tenant an EventStore (and its CDC reader) works for
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TenantContext {
+    /// None for the single, shared tenant
+    tenant_id: Option<String>,
+    scope: TenantScope,
+}
+
+impl TenantContext {
+    /// Tenant with its own keyspace
+    pub fn keyspace(tenant_id: &str, keyspace: &str) -> Result<Self> {
+        Self::new(tenant_id, TenantScope::Keyspace(keyspace.to_string()))
+    }
+
+    /// Tenant with prefixed tables in the session's keyspace
+    pub fn table_prefix(tenant_id: &str, prefix: &str) -> Result<Self> {
+        Self::new(tenant_id, TenantScope::TablePrefix(prefix.to_string()))
+    }
+
+    fn new(tenant_id: &str, scope: TenantScope) -> Result<Self> {
+        let name = match scope {
+            TenantScope::Keyspace(ref name) | TenantScope::TablePrefix(ref name) => name.as_str(),
+            TenantScope::Shared => "",
+        };
+        // Both end up in CQL text
+        for identifier in [tenant_id, name] {
+            if identifier.is_empty() || !identifier.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                bail!("Invalid tenant identifier '{}'", identifier);
+            }
+        }
+        Ok(Self { tenant_id: Some(tenant_id.to_string()), scope })
+    }
+
+    pub fn tenant_id(&self) -> Option<&str> {
+        self.tenant_id.as_deref()
+    }
+
+    pub fn scope(&self) -> &TenantScope {
+        &self.scope
+    }
+
+    /// The tenant's own keyspace, if it has one
+    pub fn keyspace_name(&self) -> Option<&str> {
+        match self.scope {
+            TenantScope::Keyspace(ref keyspace) => Some(keyspace),
+            _ => None,
+        }
+    }
+
+    /// Table name within the tenant's keyspace: `event_store` -> `acme_event_store`
+    pub fn table_name(&self, table: &str) -> String {
+        match self.scope {
+            TenantScope::TablePrefix(ref prefix) => format!("{}_{}", prefix, table),
+            _ => table.to_string(),
+        }
+    }
+
+    /// Table as written in CQL: `event_store` -> `acme_ks.event_store`
+    pub fn table(&self, table: &str) -> String {
+        match self.scope {
+            TenantScope::Keyspace(ref keyspace) => format!("{}.{}", keyspace, table),
+            _ => self.table_name(table),
+        }
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tables_per_scope() {
+        let shared = TenantContext::default();
+        assert_eq!(shared.table("event_store_3"), "event_store_3");
+        assert_eq!(shared.tenant_id(), None);
+
+        let acme = TenantContext::keyspace("acme", "acme_ks").unwrap();
+        assert_eq!(acme.table("outbox_messages"), "acme_ks.outbox_messages");
+        assert_eq!(acme.table_name("outbox_messages"), "outbox_messages");
+        assert_eq!(acme.keyspace_name(), Some("acme_ks"));
+
+        let globex = TenantContext::table_prefix("globex", "globex").unwrap();
+        assert_eq!(globex.table("aggregate_sequence"), "globex_aggregate_sequence");
+        assert_eq!(globex.keyspace_name(), None);
+
+        assert!(TenantContext::keyspace("acme", "acme; DROP").is_err());
+        assert!(TenantContext::table_prefix("", "x").is_err());
+    }
+}
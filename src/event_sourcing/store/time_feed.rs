@@ -0,0 +1,147 @@
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Duration, Utc};
+
+// ============================================================================
+// Time Feed - Events Across Aggregates by Time Window
+// ============================================================================
+//
+// event_store is partitioned by aggregate, so "everything that happened
+// between 10:00 and 10:05" cannot be read from it. Every append also writes
+// its events to events_by_time (same logged batch), partitioned by
+// aggregate type and minute of the event timestamp:
+//
+//   ((aggregate_type, bucket), timestamp, aggregate_id, sequence_number)
+//
+// A range is read bucket by bucket, oldest first. The page cursor names the
+// bucket being read and the driver's paging state within it:
+//
+//   "<bucket millis>"               start of that bucket
+//   "<bucket millis>:<paging hex>"  inside it
+//
+// Rows come back in timestamp order, which follows the skewed wall clocks
+// of the writers; each page is reordered by HLC stamp with `order_events`
+// (see ordering.rs) before it is returned. The order holds within a page.
+//
+// Event type filters are applied to the rows read, so a page is only short
+// when the range is exhausted. Ranges are limited to MAX_FEED_RANGE; the
+// table keeps events for 30 days (its TTL), event_store keeps them forever.
+//
+// ============================================================================
+
+/// Width of one events_by_time partition
+pub const FEED_BUCKET_SECS: i64 = 60;
+
+/// Longest range one feed query may cover
+pub const MAX_FEED_RANGE_HOURS: i64 = 24;
+
+/// Which events of a time range to return
+#[derive(Debug, Clone, Default)]
+pub struct EventFeedFilter {
+    /// Event types to return; empty returns all
+    pub event_types: Vec<String>,
+}
+
+impl EventFeedFilter {
+    pub fn matches(&self, event_type: &str) -> bool {
+        self.event_types.is_empty() || self.event_types.iter().any(|t| t == event_type)
+    }
+}
+
+/// Bucket (partition) of an event written at `timestamp`
+pub fn feed_bucket(timestamp: DateTime<Utc>) -> DateTime<Utc> {
+    let secs = timestamp.timestamp();
+    DateTime::from_timestamp(secs - secs.rem_euclid(FEED_BUCKET_SECS), 0).expect("bucket of a valid timestamp")
+}
+
+/// Reject empty, inverted and overly long ranges
+pub(crate) fn validate_feed_range(from: DateTime<Utc>, to: DateTime<Utc>) -> Result<()> {
+    if from >= to {
+        bail!("Invalid range: from ({}) must be before to ({})", from.to_rfc3339(), to.to_rfc3339());
+    }
+    if to - from > Duration::hours(MAX_FEED_RANGE_HOURS) {
+        bail!("Invalid range: at most {} hours per query", MAX_FEED_RANGE_HOURS);
+    }
+    Ok(())
+}
+
+/// Position in a feed: the bucket being read and the paging state within it
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FeedCursor {
+    pub bucket: DateTime<Utc>,
+    pub paging: Option<String>,
+}
+
+impl FeedCursor {
+    pub fn start(from: DateTime<Utc>) -> Self {
+        Self { bucket: feed_bucket(from), paging: None }
+    }
+
+    /// Cursor of a page of the range `[from, to)`
+    pub fn decode(cursor: &str, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Self> {
+        let invalid = || anyhow!("Invalid cursor");
+        let (millis, paging) = match cursor.split_once(':') {
+            Some((millis, paging)) if !paging.is_empty() => (millis, Some(paging.to_string())),
+            Some(_) => return Err(invalid()),
+            None => (cursor, None),
+        };
+        let bucket = millis.parse().ok().and_then(DateTime::from_timestamp_millis).ok_or_else(invalid)?;
+        if bucket != feed_bucket(bucket) || bucket < feed_bucket(from) || bucket >= to {
+            return Err(invalid());
+        }
+        Ok(Self { bucket, paging })
+    }
+
+    pub fn encode(&self) -> String {
+        match self.paging {
+            Some(ref paging) => format!("{}:{}", self.bucket.timestamp_millis(), paging),
+            None => self.bucket.timestamp_millis().to_string(),
+        }
+    }
+
+    /// Move to the start of the next bucket
+    pub fn next_bucket(&mut self) {
+        self.bucket += Duration::seconds(FEED_BUCKET_SECS);
+        self.paging = None;
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_buckets_and_cursors() {
+        assert_eq!(feed_bucket(at("2026-03-01T10:04:59.999Z")), at("2026-03-01T10:04:00Z"));
+        assert_eq!(feed_bucket(at("2026-03-01T10:05:00Z")), at("2026-03-01T10:05:00Z"));
+
+        let (from, to) = (at("2026-03-01T10:00:30Z"), at("2026-03-01T10:05:00Z"));
+        assert!(validate_feed_range(from, to).is_ok());
+        assert!(validate_feed_range(to, from).is_err());
+        assert!(validate_feed_range(from, from + Duration::hours(25)).is_err());
+
+        let mut cursor = FeedCursor::start(from);
+        assert_eq!(cursor.bucket, at("2026-03-01T10:00:00Z"));
+        cursor.paging = Some("001fa0".to_string());
+        assert_eq!(FeedCursor::decode(&cursor.encode(), from, to).unwrap(), cursor);
+        cursor.next_bucket();
+        assert_eq!(FeedCursor::decode(&cursor.encode(), from, to).unwrap().bucket, at("2026-03-01T10:01:00Z"));
+
+        // Outside the range, not on a bucket boundary, or malformed
+        let invalid = [at("2026-03-01T10:05:00Z").timestamp_millis().to_string(), "1772359230000".to_string(), "x".to_string(), "1772359200000:".to_string()];
+        for cursor in invalid {
+            assert!(FeedCursor::decode(&cursor, from, to).is_err(), "{}", cursor);
+        }
+
+        let filter = EventFeedFilter { event_types: vec!["OrderCreated".to_string()] };
+        assert!(filter.matches("OrderCreated") && !filter.matches("OrderShipped"));
+        assert!(EventFeedFilter::default().matches("OrderShipped"));
+    }
+}
@@ -0,0 +1,53 @@
+// ============================================================================
+// scylladb_cdc - Event Sourcing and CDC Relay on ScyllaDB
+// ============================================================================
+//
+// Library target of the service: the binary (src/main.rs) is one consumer,
+// other services embed the same machinery.
+//
+// Entry points:
+// - EventStore / AggregateRoot / EventEnvelope - generic event sourcing
+//   (atomic event_store + outbox writes, snapshots, optimistic concurrency)
+// - CommandHandler / CommandAggregate - load, decide, append, retry
+// - CdcProcessor - outbox CDC relay, configured through its `with_*` methods;
+//   CoordinatorActor supervises it together with the DLQ and health checks
+// - SystemBuilder - wires all of the above from an AppConfig
+// - RedpandaClient / RedpandaConsumer / EventPublisher - messaging clients
+// - test_support (feature "test-support") - in-memory store, publisher and
+//   DLQ doubles, AggregateTest given/when/then
+//
+// The example aggregates (Order, Customer) live in `domain`. Everything
+// else in the modules is public for embedding but follows the service's
+// needs first; the re-exports below are the stable surface.
+//
+// ============================================================================
+
+// Lets the derive macros' `::scylladb_cdc::...` paths resolve in this crate too
+extern crate self as scylladb_cdc;
+
+pub mod actors;
+pub mod api;
+pub mod config;
+pub mod db;
+pub mod domain;
+pub mod event_sourcing;
+pub mod loadgen;
+pub mod messaging;
+pub mod metrics;
+pub mod projections;
+pub mod system;
+pub mod utils;
+// Typed HTTP client for the command API
+#[cfg(feature = "client")]
+pub mod client;
+// In-memory doubles and the aggregate test DSL
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
+
+pub use actors::{CdcProcessor, CoordinatorActor};
+pub use config::AppConfig;
+pub use domain::{CommandAggregate, CommandHandler};
+pub use event_sourcing::{AggregateRoot, DomainEvent, EventEnvelope, EventStore};
+pub use messaging::{EventPublisher, RedpandaClient, RedpandaConsumer};
+pub use metrics::{Metrics, MetricsHandle};
+pub use system::SystemBuilder;
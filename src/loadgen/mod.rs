@@ -0,0 +1,21 @@
+// ============================================================================
+// Load Generator - Soak and Regression Testing of the Pipeline
+// ============================================================================
+//
+// `scylladb_cdc load-test` replaces the demo with synthetic Order/Customer
+// traffic while the CDC relay runs as usual, then prints a JSON report of
+// achieved throughput and latency.
+//
+// - profile  - Target rate, bursts, command mix, hot aggregates ([load] config, LOAD_* env)
+// - workload - Deterministic command stream with realistic distributions
+// - runner   - Open-loop scheduling, latency recording, report
+//
+// ============================================================================
+
+mod profile;
+mod workload;
+mod runner;
+
+pub use profile::{parse_burst, BurstPattern, LoadProfile};
+pub use workload::{LoadCommand, Workload};
+pub use runner::{run_load, CommandStats, LatencySummary, LoadReport};
@@ -0,0 +1,168 @@
+use anyhow::{Context, Result};
+use std::time::Duration;
+
+// ============================================================================
+// Load Profile - Rate, Mix and Shape of Generated Traffic
+// ============================================================================
+//
+// Built from the [load] section of the AppConfig (LOAD_* environment
+// variables), which validates it on load.
+//
+// ============================================================================
+
+/// Periodic bursts on top of the base rate
+#[derive(Debug, Clone, PartialEq)]
+pub struct BurstPattern {
+    /// Time between the starts of two bursts
+    pub every: Duration,
+    /// How long a burst lasts
+    pub length: Duration,
+    /// Rate multiplier while bursting
+    pub multiplier: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadProfile {
+    /// Target commands per second outside bursts
+    pub rate_per_sec: f64,
+    pub duration: Duration,
+    /// Maximum commands in flight
+    pub concurrency: usize,
+    /// Fraction of commands targeting customers (the rest target orders)
+    pub customer_ratio: f64,
+    /// Number of hot aggregates per type, 0 disables hot-spotting
+    pub hot_aggregates: usize,
+    /// Fraction of commands sent to a hot aggregate
+    pub hot_fraction: f64,
+    pub burst: Option<BurstPattern>,
+    /// Seed of the command generator, for reproducible runs
+    pub seed: u64,
+}
+
+impl Default for LoadProfile {
+    fn default() -> Self {
+        Self {
+            rate_per_sec: 50.0,
+            duration: Duration::from_secs(60),
+            concurrency: 32,
+            customer_ratio: 0.3,
+            hot_aggregates: 5,
+            hot_fraction: 0.2,
+            burst: None,
+            seed: 42,
+        }
+    }
+}
+
+impl LoadProfile {
+    /// Reject rates the runner cannot pace and ratios outside 0..=1
+    pub fn validate(&self) -> Result<()> {
+        // The runner paces commands 1 / rate apart
+        if !self.rate_per_sec.is_finite() || self.rate_per_sec <= 0.0 {
+            anyhow::bail!("LOAD_RATE must be a finite number > 0");
+        }
+        if let Some(ref burst) = self.burst {
+            let burst_rate = self.rate_per_sec * burst.multiplier;
+            if !burst_rate.is_finite() || burst_rate <= 0.0 {
+                anyhow::bail!("LOAD_BURST multiplier must be a finite number > 0");
+            }
+        }
+        if self.concurrency == 0 {
+            anyhow::bail!("LOAD_CONCURRENCY must be >= 1");
+        }
+        if !(0.0..=1.0).contains(&self.customer_ratio) || !(0.0..=1.0).contains(&self.hot_fraction) {
+            anyhow::bail!("LOAD_CUSTOMER_RATIO and LOAD_HOT_FRACTION must be within 0..=1");
+        }
+        Ok(())
+    }
+
+    /// Target rate `elapsed` into the run
+    pub fn rate_at(&self, elapsed: Duration) -> f64 {
+        match self.burst {
+            Some(ref burst) if !burst.every.is_zero() => {
+                let into_cycle = elapsed.as_secs_f64() % burst.every.as_secs_f64();
+                if into_cycle < burst.length.as_secs_f64() {
+                    self.rate_per_sec * burst.multiplier
+                } else {
+                    self.rate_per_sec
+                }
+            }
+            _ => self.rate_per_sec,
+        }
+    }
+}
+
+fn parse<T: std::str::FromStr>(name: &str, value: &str) -> Result<T>
+where
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    value.trim().parse().with_context(|| format!("Invalid {}: '{}'", name, value))
+}
+
+/// LOAD_BURST: `<every secs>:<length secs>:<multiplier>`, e.g. `30:5:4`
+pub fn parse_burst(value: &str) -> Result<BurstPattern> {
+    let parts: Vec<&str> = value.split(':').collect();
+    let [every, length, multiplier] = parts.as_slice() else {
+        anyhow::bail!("Invalid LOAD_BURST '{}', expected <every secs>:<length secs>:<multiplier>", value);
+    };
+
+    Ok(BurstPattern {
+        every: Duration::from_secs(parse("LOAD_BURST", every)?),
+        length: Duration::from_secs(parse("LOAD_BURST", length)?),
+        multiplier: parse("LOAD_BURST", multiplier)?,
+    })
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with(rate_per_sec: f64, burst: Option<&str>) -> Result<()> {
+        let burst = burst.map(parse_burst).transpose()?;
+        LoadProfile { rate_per_sec, burst, ..LoadProfile::default() }.validate()
+    }
+
+    #[test]
+    fn test_parse_burst() {
+        assert_eq!(parse_burst("30:5:4").unwrap(), BurstPattern {
+            every: Duration::from_secs(30),
+            length: Duration::from_secs(5),
+            multiplier: 4.0,
+        });
+        assert!(parse_burst("30:5").is_err());
+        assert!(parse_burst("30:5:often").is_err());
+    }
+
+    #[test]
+    fn test_invalid_values_rejected() {
+        assert!(with(200.0, Some("30:5:4")).is_ok());
+        assert!(with(0.0, None).is_err());
+        assert!(with(f64::NAN, None).is_err());
+        assert!(with(f64::INFINITY, None).is_err());
+        assert!(with(10.0, Some("30:5:0")).is_err());
+        assert!(with(10.0, Some("30:5:-2")).is_err());
+        assert!(with(10.0, Some("30:5:NaN")).is_err());
+        assert!(LoadProfile { hot_fraction: 1.5, ..LoadProfile::default() }.validate().is_err());
+    }
+
+    #[test]
+    fn test_burst_rate() {
+        let profile = LoadProfile {
+            rate_per_sec: 10.0,
+            burst: Some(BurstPattern {
+                every: Duration::from_secs(30),
+                length: Duration::from_secs(5),
+                multiplier: 4.0,
+            }),
+            ..LoadProfile::default()
+        };
+
+        assert_eq!(profile.rate_at(Duration::from_secs(2)), 40.0);
+        assert_eq!(profile.rate_at(Duration::from_secs(10)), 10.0);
+        assert_eq!(profile.rate_at(Duration::from_secs(31)), 40.0);
+    }
+}
@@ -0,0 +1,266 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use uuid::Uuid;
+
+use crate::domain::customer::CustomerCommandHandler;
+use crate::domain::order::OrderCommandHandler;
+use crate::event_sourcing::CommandContext;
+
+use super::{LoadCommand, LoadProfile, Workload};
+
+// ============================================================================
+// Load Runner - Drive the Command Side at a Target Rate
+// ============================================================================
+//
+// Sends commands from the Workload on a fixed schedule (open loop) with at
+// most `concurrency` in flight. When the system can't keep up the permits
+// run out and the achieved rate drops below the target - that gap is the
+// headline number of a soak run. If the schedule falls more than a second
+// behind it is reset instead of firing a catch-up storm.
+//
+// Latency is measured per command (load + append through the event store);
+// the CDC relay runs alongside and can be watched on /metrics.
+//
+// ============================================================================
+
+/// Interval of progress log lines
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(10);
+/// Distinct error messages kept in the report
+const MAX_ERROR_KINDS: usize = 20;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LatencySummary {
+    pub count: usize,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencySummary {
+    fn from_samples(samples: &mut [Duration]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        samples.sort_unstable();
+
+        let ms = |d: Duration| d.as_micros() as f64 / 1000.0;
+        // Nearest-rank percentile
+        let percentile = |p: f64| {
+            let rank = ((p / 100.0) * samples.len() as f64).ceil() as usize;
+            ms(samples[rank.clamp(1, samples.len()) - 1])
+        };
+
+        Self {
+            count: samples.len(),
+            mean_ms: samples.iter().map(|d| ms(*d)).sum::<f64>() / samples.len() as f64,
+            p50_ms: percentile(50.0),
+            p90_ms: percentile(90.0),
+            p99_ms: percentile(99.0),
+            max_ms: ms(samples[samples.len() - 1]),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandStats {
+    pub sent: u64,
+    pub failed: u64,
+    pub latency: LatencySummary,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadReport {
+    pub correlation_id: Uuid,
+    pub target_rate_per_sec: f64,
+    pub elapsed_secs: f64,
+    pub sent: u64,
+    pub succeeded: u64,
+    pub failed: u64,
+    pub achieved_rate_per_sec: f64,
+    /// Times the schedule fell behind by more than a second and was reset
+    pub schedule_resets: u64,
+    pub latency: LatencySummary,
+    pub by_command: BTreeMap<String, CommandStats>,
+    /// Error message → count (first MAX_ERROR_KINDS kinds)
+    pub errors: BTreeMap<String, u64>,
+}
+
+#[derive(Default)]
+struct Recorder {
+    samples: Vec<Duration>,
+    by_command: BTreeMap<&'static str, (u64, u64, Vec<Duration>)>,
+    failed: u64,
+    errors: BTreeMap<String, u64>,
+}
+
+impl Recorder {
+    fn record(&mut self, command: &'static str, latency: Duration, error: Option<String>) {
+        self.samples.push(latency);
+        let entry = self.by_command.entry(command).or_default();
+        entry.0 += 1;
+        entry.2.push(latency);
+
+        if let Some(error) = error {
+            self.failed += 1;
+            entry.1 += 1;
+            if self.errors.len() < MAX_ERROR_KINDS || self.errors.contains_key(&error) {
+                *self.errors.entry(error).or_default() += 1;
+            }
+        }
+    }
+
+    fn report(mut self, correlation_id: Uuid, profile: &LoadProfile, elapsed: Duration, schedule_resets: u64) -> LoadReport {
+        let sent = self.samples.len() as u64;
+        LoadReport {
+            correlation_id,
+            target_rate_per_sec: profile.rate_per_sec,
+            elapsed_secs: elapsed.as_secs_f64(),
+            sent,
+            succeeded: sent - self.failed,
+            failed: self.failed,
+            achieved_rate_per_sec: sent as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+            schedule_resets,
+            latency: LatencySummary::from_samples(&mut self.samples),
+            by_command: self
+                .by_command
+                .into_iter()
+                .map(|(name, (sent, failed, mut samples))| {
+                    (name.to_string(), CommandStats { sent, failed, latency: LatencySummary::from_samples(&mut samples) })
+                })
+                .collect(),
+            errors: self.errors,
+        }
+    }
+}
+
+/// Run `profile` against the command handlers and report what was achieved
+pub async fn run_load(
+    profile: LoadProfile,
+    orders: Arc<OrderCommandHandler>,
+    customers: Arc<CustomerCommandHandler>,
+) -> LoadReport {
+    // One correlation id per run, so generated events can be told apart
+    let correlation_id = Uuid::new_v4();
+    let workload = Arc::new(Mutex::new(Workload::new(&profile)));
+    let recorder = Arc::new(Mutex::new(Recorder::default()));
+    let permits = Arc::new(Semaphore::new(profile.concurrency));
+
+    tracing::info!(
+        rate = profile.rate_per_sec,
+        duration_secs = profile.duration.as_secs(),
+        concurrency = profile.concurrency,
+        correlation_id = %correlation_id,
+        "🏋️ Starting load generation"
+    );
+
+    let started = Instant::now();
+    let mut next_at = started;
+    let mut next_progress = started + PROGRESS_INTERVAL;
+    let mut schedule_resets = 0;
+
+    while started.elapsed() < profile.duration {
+        tokio::time::sleep_until(next_at.into()).await;
+
+        let Ok(permit) = permits.clone().acquire_owned().await else {
+            break;
+        };
+        let command = workload.lock().unwrap().next_command();
+        let (name, aggregate_id) = (command.name(), command.aggregate_id());
+
+        let (orders, customers) = (orders.clone(), customers.clone());
+        let ctx = CommandContext::new(correlation_id).with_metadata("client", "loadgen");
+        let (task_workload, task_recorder) = (workload.clone(), recorder.clone());
+        tokio::spawn(async move {
+            let sent_at = Instant::now();
+            let result = match command {
+                LoadCommand::Order { aggregate_id, command } => orders.handle(aggregate_id, command, &ctx).await,
+                LoadCommand::Customer { aggregate_id, command } => customers.handle(aggregate_id, command, &ctx).await,
+            };
+            let latency = sent_at.elapsed();
+
+            let ok = result.is_ok();
+            task_recorder.lock().unwrap().record(name, latency, result.err().map(|e| e.to_string()));
+            task_workload.lock().unwrap().complete(name, aggregate_id, ok);
+            drop(permit);
+        });
+
+        let now = Instant::now();
+        next_at += Duration::from_secs_f64(1.0 / profile.rate_at(now - started));
+        if now.saturating_duration_since(next_at) > Duration::from_secs(1) {
+            next_at = now;
+            schedule_resets += 1;
+        }
+
+        if now >= next_progress {
+            let recorder = recorder.lock().unwrap();
+            tracing::info!(
+                elapsed_secs = (now - started).as_secs(),
+                sent = recorder.samples.len(),
+                failed = recorder.failed,
+                in_flight = profile.concurrency - permits.available_permits(),
+                "Load generation progress"
+            );
+            next_progress += PROGRESS_INTERVAL;
+        }
+    }
+
+    // Wait for in-flight commands
+    let _ = permits.acquire_many(profile.concurrency as u32).await;
+    let elapsed = started.elapsed();
+
+    let recorder = std::mem::take(&mut *recorder.lock().unwrap());
+    let report = recorder.report(correlation_id, &profile, elapsed, schedule_resets);
+
+    tracing::info!(
+        sent = report.sent,
+        failed = report.failed,
+        achieved_rate = report.achieved_rate_per_sec,
+        p99_ms = report.latency.p99_ms,
+        "🏁 Load generation finished"
+    );
+    report
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_percentiles() {
+        let mut samples: Vec<Duration> = (1..=100).rev().map(Duration::from_millis).collect();
+        let summary = LatencySummary::from_samples(&mut samples);
+
+        assert_eq!(summary.count, 100);
+        assert_eq!(summary.p50_ms, 50.0);
+        assert_eq!(summary.p90_ms, 90.0);
+        assert_eq!(summary.p99_ms, 99.0);
+        assert_eq!(summary.max_ms, 100.0);
+        assert_eq!(summary.mean_ms, 50.5);
+        assert_eq!(LatencySummary::from_samples(&mut []), LatencySummary::default());
+    }
+
+    #[test]
+    fn test_report_counts_per_command_and_errors() {
+        let mut recorder = Recorder::default();
+        recorder.record("CreateOrder", Duration::from_millis(10), None);
+        recorder.record("CreateOrder", Duration::from_millis(20), Some("conflict".to_string()));
+        recorder.record("ConfirmOrder", Duration::from_millis(5), Some("conflict".to_string()));
+
+        let report = recorder.report(Uuid::nil(), &LoadProfile::default(), Duration::from_secs(1), 0);
+
+        assert_eq!((report.sent, report.succeeded, report.failed), (3, 1, 2));
+        assert_eq!(report.achieved_rate_per_sec, 3.0);
+        assert_eq!(report.errors.get("conflict"), Some(&2));
+        assert_eq!(report.by_command["CreateOrder"].sent, 2);
+        assert_eq!(report.by_command["CreateOrder"].failed, 1);
+    }
+}
@@ -0,0 +1,385 @@
+use std::collections::{HashSet, VecDeque};
+use uuid::Uuid;
+
+use crate::domain::customer::{CustomerCommand, Email, PhoneNumber};
+use crate::domain::order::{OrderCommand, OrderItem};
+
+use super::LoadProfile;
+
+// ============================================================================
+// Workload - Synthetic Command Stream
+// ============================================================================
+//
+// Generates a stream of Order/Customer commands that resembles production:
+// - Orders go through their lifecycle (create → confirm → ship → deliver,
+//   some cancelled). An order is only advanced once its previous command
+//   completed, so lifecycle commands never race each other.
+// - Hot aggregates: a fixed set of orders/customers receives `hot_fraction`
+//   of the traffic (UpdateItems / UpdateProfile), concurrently, to exercise
+//   version conflicts and large partitions.
+// - Customers register and then update their profile/phone.
+//
+// The generator is deterministic for a given seed. The runner reports each
+// command's outcome back through `complete`.
+//
+// ============================================================================
+
+/// Orders kept open at once; beyond this existing orders are advanced
+const MAX_OPEN_ORDERS: usize = 1_000;
+/// Registered customers remembered for follow-up updates
+const MAX_KNOWN_CUSTOMERS: usize = 10_000;
+/// Share of non-hot order commands that create a new order
+const CREATE_ORDER_SHARE: f64 = 0.4;
+/// Share of non-hot customer commands that register a new customer
+const REGISTER_CUSTOMER_SHARE: f64 = 0.3;
+/// Share of confirmable orders that get cancelled instead
+const CANCEL_SHARE: f64 = 0.05;
+
+/// A generated command and the aggregate it targets
+#[derive(Debug, Clone)]
+pub enum LoadCommand {
+    Order { aggregate_id: Uuid, command: OrderCommand },
+    Customer { aggregate_id: Uuid, command: CustomerCommand },
+}
+
+impl LoadCommand {
+    pub fn aggregate_id(&self) -> Uuid {
+        match self {
+            LoadCommand::Order { aggregate_id, .. } | LoadCommand::Customer { aggregate_id, .. } => *aggregate_id,
+        }
+    }
+
+    /// Command name, used as report/metric label
+    pub fn name(&self) -> &'static str {
+        match self {
+            LoadCommand::Order { command, .. } => match command {
+                OrderCommand::CreateOrder { .. } => "CreateOrder",
+                OrderCommand::UpdateItems { .. } => "UpdateItems",
+                OrderCommand::ConfirmOrder => "ConfirmOrder",
+                OrderCommand::ShipOrder { .. } => "ShipOrder",
+                OrderCommand::DeliverOrder { .. } => "DeliverOrder",
+                OrderCommand::CancelOrder { .. } => "CancelOrder",
+            },
+            LoadCommand::Customer { command, .. } => match command {
+                CustomerCommand::RegisterCustomer { .. } => "RegisterCustomer",
+                CustomerCommand::UpdateProfile { .. } => "UpdateProfile",
+                CustomerCommand::ChangePhone { .. } => "ChangePhone",
+                _ => "OtherCustomerCommand",
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OrderStage {
+    Created,
+    Confirmed,
+    Shipped,
+}
+
+/// SplitMix64 - small, fast and good enough for traffic shaping
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1)
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n.max(1) as u64) as usize
+    }
+
+    fn uuid(&mut self) -> Uuid {
+        uuid::Builder::from_random_bytes(((self.next_u64() as u128) << 64 | self.next_u64() as u128).to_be_bytes())
+            .into_uuid()
+    }
+}
+
+pub struct Workload {
+    rng: Rng,
+    customer_ratio: f64,
+    hot_fraction: f64,
+    warmup: VecDeque<LoadCommand>,
+    /// Hot aggregates whose creation hasn't completed yet
+    pending_hot: HashSet<Uuid>,
+    hot_orders: Vec<Uuid>,
+    hot_customers: Vec<Uuid>,
+    /// Open orders with no command in flight
+    idle_orders: Vec<(Uuid, OrderStage)>,
+    in_flight_orders: usize,
+    customers: Vec<Uuid>,
+    sequence: u64,
+}
+
+impl Workload {
+    pub fn new(profile: &LoadProfile) -> Self {
+        let mut workload = Self {
+            rng: Rng(profile.seed),
+            customer_ratio: profile.customer_ratio,
+            hot_fraction: profile.hot_fraction,
+            warmup: VecDeque::new(),
+            pending_hot: HashSet::new(),
+            hot_orders: Vec::new(),
+            hot_customers: Vec::new(),
+            idle_orders: Vec::new(),
+            in_flight_orders: 0,
+            customers: Vec::new(),
+            sequence: 0,
+        };
+
+        // Hot aggregates are created first and only targeted once they exist
+        for _ in 0..profile.hot_aggregates {
+            let order = workload.create_order();
+            let customer = workload.register_customer();
+            workload.pending_hot.insert(order.aggregate_id());
+            workload.pending_hot.insert(customer.aggregate_id());
+            workload.warmup.push_back(order);
+            workload.warmup.push_back(customer);
+        }
+
+        workload
+    }
+
+    /// Next command to send
+    pub fn next_command(&mut self) -> LoadCommand {
+        if let Some(command) = self.warmup.pop_front() {
+            if matches!(command, LoadCommand::Order { .. }) {
+                self.in_flight_orders += 1;
+            }
+            return command;
+        }
+
+        if self.rng.next_f64() < self.customer_ratio {
+            self.next_customer_command()
+        } else {
+            self.next_order_command()
+        }
+    }
+
+    /// Report the outcome of a command returned by `next_command`
+    pub fn complete(&mut self, command: &'static str, aggregate_id: Uuid, ok: bool) {
+        let hot = self.pending_hot.remove(&aggregate_id);
+
+        match command {
+            "CreateOrder" | "ConfirmOrder" | "ShipOrder" | "DeliverOrder" | "CancelOrder" => {
+                self.in_flight_orders = self.in_flight_orders.saturating_sub(1);
+            }
+            _ => {}
+        }
+
+        if !ok {
+            // Failed lifecycle steps drop the order; it won't be advanced again
+            return;
+        }
+
+        match command {
+            "CreateOrder" if hot => self.hot_orders.push(aggregate_id),
+            "CreateOrder" => self.idle_orders.push((aggregate_id, OrderStage::Created)),
+            "ConfirmOrder" => self.idle_orders.push((aggregate_id, OrderStage::Confirmed)),
+            "ShipOrder" => self.idle_orders.push((aggregate_id, OrderStage::Shipped)),
+            "RegisterCustomer" if hot => self.hot_customers.push(aggregate_id),
+            "RegisterCustomer" => {
+                if self.customers.len() >= MAX_KNOWN_CUSTOMERS {
+                    let evicted = self.rng.below(self.customers.len());
+                    self.customers.swap_remove(evicted);
+                }
+                self.customers.push(aggregate_id);
+            }
+            _ => {}
+        }
+    }
+
+    fn next_order_command(&mut self) -> LoadCommand {
+        if !self.hot_orders.is_empty() && self.rng.next_f64() < self.hot_fraction {
+            let aggregate_id = self.hot_orders[self.rng.below(self.hot_orders.len())];
+            let items = self.items();
+            return LoadCommand::Order {
+                aggregate_id,
+                command: OrderCommand::UpdateItems { items, reason: Some("load test".to_string()) },
+            };
+        }
+
+        let open = self.idle_orders.len() + self.in_flight_orders;
+        let create = self.idle_orders.is_empty()
+            || (open < MAX_OPEN_ORDERS && self.rng.next_f64() < CREATE_ORDER_SHARE);
+        self.in_flight_orders += 1;
+
+        if create {
+            return self.create_order();
+        }
+
+        let index = self.rng.below(self.idle_orders.len());
+        let (aggregate_id, stage) = self.idle_orders.swap_remove(index);
+        let command = match stage {
+            OrderStage::Created if self.rng.next_f64() < CANCEL_SHARE => OrderCommand::CancelOrder {
+                reason: Some("load test".to_string()),
+                cancelled_by: None,
+            },
+            OrderStage::Created => OrderCommand::ConfirmOrder,
+            OrderStage::Confirmed => OrderCommand::ShipOrder {
+                tracking_number: format!("LOAD-{}", self.next_sequence()),
+                carrier: "Load Test Express".to_string(),
+            },
+            OrderStage::Shipped => OrderCommand::DeliverOrder { signature: None },
+        };
+
+        LoadCommand::Order { aggregate_id, command }
+    }
+
+    fn next_customer_command(&mut self) -> LoadCommand {
+        if !self.hot_customers.is_empty() && self.rng.next_f64() < self.hot_fraction {
+            let aggregate_id = self.hot_customers[self.rng.below(self.hot_customers.len())];
+            return self.update_profile(aggregate_id);
+        }
+
+        if self.customers.is_empty() || self.rng.next_f64() < REGISTER_CUSTOMER_SHARE {
+            return self.register_customer();
+        }
+
+        let aggregate_id = self.customers[self.rng.below(self.customers.len())];
+        if self.rng.next_f64() < 0.5 {
+            self.update_profile(aggregate_id)
+        } else {
+            let sequence = self.next_sequence();
+            LoadCommand::Customer {
+                aggregate_id,
+                command: CustomerCommand::ChangePhone { new_phone: PhoneNumber::new(format!("+1-555-{:07}", sequence % 10_000_000)) },
+            }
+        }
+    }
+
+    fn create_order(&mut self) -> LoadCommand {
+        let aggregate_id = self.rng.uuid();
+        let customer_id = self.rng.uuid();
+        let items = self.items();
+        LoadCommand::Order {
+            aggregate_id,
+            command: OrderCommand::CreateOrder { order_id: aggregate_id, customer_id, items },
+        }
+    }
+
+    fn register_customer(&mut self) -> LoadCommand {
+        let aggregate_id = self.rng.uuid();
+        let sequence = self.next_sequence();
+        LoadCommand::Customer {
+            aggregate_id,
+            command: CustomerCommand::RegisterCustomer {
+                customer_id: aggregate_id,
+                email: Email::new(format!("load-{}@example.com", sequence)),
+                first_name: "Load".to_string(),
+                last_name: format!("Tester{}", sequence),
+                phone: None,
+            },
+        }
+    }
+
+    fn update_profile(&mut self, aggregate_id: Uuid) -> LoadCommand {
+        let sequence = self.next_sequence();
+        LoadCommand::Customer {
+            aggregate_id,
+            command: CustomerCommand::UpdateProfile {
+                first_name: None,
+                last_name: Some(format!("Tester{}", sequence)),
+                phone: None,
+            },
+        }
+    }
+
+    /// 1-4 items with quantity 1-5
+    fn items(&mut self) -> Vec<OrderItem> {
+        (0..1 + self.rng.below(4))
+            .map(|_| OrderItem {
+                product_id: self.rng.uuid(),
+                quantity: 1 + self.rng.below(5) as i32,
+            })
+            .collect()
+    }
+
+    fn next_sequence(&mut self) -> u64 {
+        self.sequence += 1;
+        self.sequence
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(customer_ratio: f64, hot_aggregates: usize, hot_fraction: f64) -> LoadProfile {
+        LoadProfile { customer_ratio, hot_aggregates, hot_fraction, ..LoadProfile::default() }
+    }
+
+    #[test]
+    fn test_hot_aggregates_created_first_then_targeted() {
+        let mut workload = Workload::new(&profile(0.0, 2, 1.0));
+
+        let warmup: Vec<LoadCommand> = (0..4).map(|_| workload.next_command()).collect();
+        let names: Vec<&str> = warmup.iter().map(|c| c.name()).collect();
+        assert_eq!(names, ["CreateOrder", "RegisterCustomer", "CreateOrder", "RegisterCustomer"]);
+
+        let hot_orders: Vec<Uuid> = vec![warmup[0].aggregate_id(), warmup[2].aggregate_id()];
+        for command in &warmup {
+            workload.complete(command.name(), command.aggregate_id(), true);
+        }
+
+        for _ in 0..20 {
+            let command = workload.next_command();
+            assert_eq!(command.name(), "UpdateItems");
+            assert!(hot_orders.contains(&command.aggregate_id()));
+        }
+    }
+
+    #[test]
+    fn test_order_advances_only_after_completion() {
+        let mut workload = Workload::new(&profile(0.0, 0, 0.0));
+
+        let create = workload.next_command();
+        assert_eq!(create.name(), "CreateOrder");
+        let order_id = create.aggregate_id();
+
+        // Nothing idle yet - keeps creating instead of confirming a pending order
+        assert_eq!(workload.next_command().name(), "CreateOrder");
+
+        workload.complete("CreateOrder", order_id, true);
+        let mut seen = Vec::new();
+        for _ in 0..200 {
+            let command = workload.next_command();
+            if command.aggregate_id() == order_id {
+                seen.push(command.name());
+                workload.complete(command.name(), order_id, true);
+            }
+        }
+
+        assert!(
+            seen == ["ConfirmOrder", "ShipOrder", "DeliverOrder"] || seen == ["CancelOrder"],
+            "unexpected lifecycle {:?}",
+            seen
+        );
+    }
+
+    #[test]
+    fn test_same_seed_same_stream() {
+        let mut a = Workload::new(&LoadProfile::default());
+        let mut b = Workload::new(&LoadProfile::default());
+
+        for _ in 0..50 {
+            let (x, y) = (a.next_command(), b.next_command());
+            assert_eq!((x.name(), x.aggregate_id()), (y.name(), y.aggregate_id()));
+            a.complete(x.name(), x.aggregate_id(), true);
+            b.complete(y.name(), y.aggregate_id(), true);
+        }
+    }
+}
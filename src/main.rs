@@ -1,33 +1,189 @@
+use clap::{Parser, Subcommand, ValueEnum};
+use futures_util::TryStreamExt;
 use kameo::Actor;
 use scylla::client::session::Session;
 use scylla::client::session_builder::SessionBuilder;
 use std::sync::Arc;
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
-mod actors;
-mod messaging;
-mod utils;
-mod metrics;
-mod db;
-mod event_sourcing;
-mod domain;
-
-use actors::CoordinatorActor;
-use messaging::RedpandaClient;
+// The service is a thin consumer of the library (src/lib.rs)
+use scylladb_cdc::{api, config, db, loadgen, metrics, messaging, projections, utils};
+use scylladb_cdc::actors::{
+    list_dlq_messages, CdcThrottleConfig, CoordinatorActor, DlqActor, DlqFilter, HealthSnapshot, ReplayDlqMessage, ReplayOutcome,
+    StartupPhase, StartupPolicy, StartupSequencer,
+};
+use scylladb_cdc::system::{ShutdownController, SystemBuilder};
+use scylladb_cdc::messaging::{EventSubscriptions, Partitioner, RegionConfig};
 
 // Use new domain-layered structure
-use event_sourcing::EventStore;
-use domain::order::{OrderCommandHandler, OrderCommand, OrderItem, OrderEvent};
-use domain::customer::{
-    CustomerCommandHandler, CustomerCommand, CustomerEvent,
+use scylladb_cdc::event_sourcing::{AsOf, CommandContext, DomainEvent, EventStore, ShardLayout, ShardRebalancer, SnapshotPruner, TenantContext, WriteFence};
+use scylladb_cdc::domain::order::{OrderAggregate, OrderCommandHandler, OrderCommand, OrderItem, OrderEvent};
+use scylladb_cdc::domain::customer::{
+    CustomerAggregate, CustomerCommandHandler, CustomerCommand,
     Email, PhoneNumber, Address, CustomerTier,
 };
+use scylladb_cdc::domain::{self, CommandAggregate};
+
+// ============================================================================
+// Command Line
+// ============================================================================
+//
+//   scylladb_cdc [--migrate] [demo]       services + the scripted demo (default)
+//   scylladb_cdc [--migrate] serve        services until SIGINT/SIGTERM
+//   scylladb_cdc migrate                  create keyspace, apply migrations
+//   scylladb_cdc health [--ready]         ask a running instance (/health)
+//   scylladb_cdc dlq list [filters]       dead letters, one page as JSON
+//   scylladb_cdc dlq retry <ID>           republish a dead letter
+//   scylladb_cdc replay --aggregate <ID>  rebuild an aggregate from its events
+//   scylladb_cdc load-test | verify-contracts | advise-partitions |
+//                check-integrity | reshard | repair-sequence
+//
+// One-shot commands print JSON on stdout and exit non-zero on failure.
+//
+// ============================================================================
+
+#[derive(Debug, Parser)]
+#[command(name = "scylladb_cdc", version, about = "Event sourcing on ScyllaDB with a CDC relay to Redpanda")]
+struct Cli {
+    /// Create the keyspace and apply pending schema migrations first
+    #[arg(long, global = true)]
+    migrate: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Run the coordinator, CDC relay, projections and APIs until SIGINT/SIGTERM
+    Serve,
+    /// Like serve, after running the scripted order/customer lifecycle
+    Demo,
+    /// Create the keyspace and apply pending schema migrations, then exit
+    Migrate,
+    /// Health of a running instance (exit code 1 unless healthy)
+    Health {
+        /// Ask /health/ready instead of /health
+        #[arg(long)]
+        ready: bool,
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+        /// Metrics port of the instance (default: METRICS_PORT)
+        #[arg(long)]
+        port: Option<u16>,
+    },
+    /// Inspect and retry dead-lettered events
+    Dlq {
+        #[command(subcommand)]
+        command: DlqCommand,
+    },
+    /// Rebuild an aggregate from its events and print its state
+    Replay {
+        #[arg(long)]
+        aggregate: uuid::Uuid,
+        #[arg(long = "type", value_enum, default_value_t = AggregateKind::Order)]
+        aggregate_type: AggregateKind,
+        /// Stop after this version instead of the newest event
+        #[arg(long, conflicts_with = "at")]
+        to_version: Option<i64>,
+        /// State as of this time (RFC 3339): events written up to then
+        #[arg(long)]
+        at: Option<chrono::DateTime<chrono::Utc>>,
+    },
+    /// Send synthetic traffic shaped by LOAD_* env vars, then print a report
+    LoadTest,
+    /// Check published events against the consumer contracts in DIR
+    VerifyContracts {
+        #[arg(default_value = "contracts")]
+        dir: String,
+    },
+    /// Report partitions that grow too large
+    AdvisePartitions,
+    /// Cross-check outbox_messages against event_store
+    CheckIntegrity {
+        /// Delete orphaned outbox rows and re-emit missing ones
+        #[arg(long)]
+        repair: bool,
+        /// Window to scan, in seconds
+        window_secs: Option<u64>,
+    },
+    /// Move aggregates between event store shard layouts (writers stopped)
+    Reshard {
+        #[arg(long)]
+        to: u32,
+        /// Current layout (default: EVENT_STORE_SHARDS)
+        #[arg(long)]
+        from: Option<u32>,
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Roll aggregate_sequence back to the last stored event after an
+    /// ambiguously failed append (once the batchlog can no longer replay it)
+    RepairSequence {
+        #[arg(long)]
+        aggregate: uuid::Uuid,
+        #[arg(long = "type", value_enum, default_value_t = AggregateKind::Order)]
+        aggregate_type: AggregateKind,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum DlqCommand {
+    /// List dead letters matching the filters; prints one page
+    List {
+        #[arg(long)]
+        event_type: Option<String>,
+        #[arg(long)]
+        aggregate: Option<uuid::Uuid>,
+        /// Last failed at or after (RFC 3339)
+        #[arg(long)]
+        failed_from: Option<chrono::DateTime<chrono::Utc>>,
+        /// Last failed before (RFC 3339)
+        #[arg(long)]
+        failed_until: Option<chrono::DateTime<chrono::Utc>>,
+        #[arg(long)]
+        limit: Option<i32>,
+        /// `next_cursor` of the previous page
+        #[arg(long)]
+        cursor: Option<String>,
+    },
+    /// Republish a dead letter; repeated failures quarantine it
+    Retry { id: uuid::Uuid },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum AggregateKind {
+    Order,
+    Customer,
+}
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    let command = cli.command.unwrap_or(Command::Demo);
+
+    // Connections and tuning: defaults < APP_CONFIG_FILE < environment
+    let app_config = Arc::new(config::AppConfig::load()?);
+
+    // A raised event version without its upcaster would fail on the first
+    // old event read; refuse to start instead
+    domain::upcasters().validate()?;
+
+    // Production logs must not carry customer PII (emails, phones, addresses)
+    utils::set_redaction(app_config.is_production());
+
+    // Span export ([telemetry]); flushed when dropped at the end of main
+    #[cfg(feature = "otel")]
+    let otlp = metrics::OtlpExporter::from_config(&app_config.telemetry)?;
+    #[cfg(feature = "otel")]
+    let otlp_layer = otlp.as_ref().map(|exporter| exporter.layer());
+    #[cfg(not(feature = "otel"))]
+    let otlp_layer: Option<tracing_subscriber::layer::Identity> = None;
+
     // Initialize structured logging
     tracing_subscriber::registry()
-        .with(fmt::layer().with_target(true).with_thread_ids(true))
+        .with(fmt::layer().with_target(true).with_thread_ids(true).with_writer(utils::RedactingWriter::stdout))
+        .with(otlp_layer)
         .with(
             EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| EnvFilter::new("info,scylladb_cdc=debug"))
@@ -37,55 +193,572 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("🚀 Starting ScyllaDB Event Sourcing with CDC");
     tracing::info!("📊 Event Sourcing + CQRS + Direct CDC Projections");
 
+    tracing::info!(environment = %app_config.environment, pii_redaction = app_config.is_production(), "Loaded configuration");
+    if let Some(ref endpoint) = app_config.telemetry.otlp_endpoint {
+        tracing::info!(endpoint = %endpoint, sample_ratio = app_config.telemetry.sample_ratio, "Exporting spans over OTLP");
+    }
+    tracing::debug!(config = ?app_config, "Loaded application configuration");
+
+    // CI: `scylladb_cdc verify-contracts [DIR]` checks the published events
+    // against the downstream consumer contracts (default ./contracts), prints
+    // a JSON report and fails on breaking changes. Needs no cluster.
+    if let Command::VerifyContracts { ref dir } = command {
+        let report = messaging::ContractSet::load_dir(dir)?.verify(&messaging::published_samples()?);
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        if !report.is_compatible() {
+            anyhow::bail!("{} consumer contract violation(s)", report.violations.len());
+        }
+        return Ok(());
+    }
+
+    // Operators: `scylladb_cdc health [--ready]` asks a running instance
+    if let Command::Health { ready, ref host, port } = command {
+        let path = if ready { "/health/ready" } else { "/health" };
+        let (status, body) = http_get(host, port.unwrap_or(app_config.metrics.port), path).await?;
+        println!("{}", body);
+        if status != 200 {
+            anyhow::bail!("{} answered {}", path, status);
+        }
+        return Ok(());
+    }
+
     // === 1. Create ScyllaDB Session ===
-    tracing::info!("Connecting to ScyllaDB...");
-    let session: Session = SessionBuilder::new()
-        .known_node("127.0.0.1:9042")
-        .build()
-        .await?;
+    tracing::info!(nodes = ?app_config.scylla.nodes, "Connecting to ScyllaDB...");
+    let mut session_builder = SessionBuilder::new().known_nodes(&app_config.scylla.nodes);
+    if let Some(ref username) = app_config.scylla.username {
+        session_builder = session_builder.user(username, app_config.scylla.password.as_deref().unwrap_or_default());
+    }
+    let session: Session = session_builder.build().await?;
 
-    // Use existing keyspace (created by schema.cql via `make reset` or `make schema`)
-    session.use_keyspace("orders_ks", false).await?;
+    // `migrate` / `--migrate` create the keyspace and apply pending schema
+    // migrations; otherwise it was created by schema.cql via `make reset` or
+    // `make schema`
+    if cli.migrate || matches!(command, Command::Migrate) {
+        // [[cdc.tables]]; outboxes (shared and tenant) get CDC with its options from the schema
+        let (tenant_outboxes, cdc_tables): (Vec<_>, Vec<_>) =
+            app_config.cdc_tables().into_iter().skip(1).partition(|table| table.tenant.is_some());
+        let report = db::Migrator::new(&session, &app_config.scylla.keyspace)
+            .with_replication_factor(app_config.scylla.replication_factor)
+            .with_cdc_tables(cdc_tables.into_iter().map(|table| table.source).collect())
+            .with_tenant_outboxes(
+                tenant_outboxes
+                    .into_iter()
+                    .filter_map(|table| Some((table.tenant?, table.source)))
+                    .collect(),
+            )
+            .with_event_tables(app_config.event_store.shard_layout()?.tables())
+            .run()
+            .await?;
+        if matches!(command, Command::Migrate) {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            return Ok(());
+        }
+    }
+    session.use_keyspace(&app_config.scylla.keyspace, false).await?;
 
     let session = Arc::new(session);
+    let system = SystemBuilder::new(session.clone()).with_config(app_config.clone());
+
+    // Diagnostics: `scylladb_cdc advise-partitions` prints a JSON report and exits
+    if let Command::AdvisePartitions = command {
+        let report = db::run_partition_advisor(&session, &app_config.scylla.keyspace, db::AdvisorThresholds::default()).await?;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    // Diagnostics: `scylladb_cdc check-integrity [--repair] [WINDOW_SECS]`
+    // cross-checks outbox_messages against event_store and exits
+    if let Command::CheckIntegrity { repair, window_secs } = command {
+        let mut config = db::IntegrityCheckConfig { cleanup_orphans: repair, reemit_missing: repair, ..Default::default() };
+        if let Some(secs) = window_secs {
+            config.window = std::time::Duration::from_secs(secs);
+        }
+        let report = integrity_checker(&system, config).run().await?;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    // Maintenance: `scylladb_cdc reshard --to N [--from M] [--dry-run]` moves
+    // aggregates between event store shard layouts (writers stopped) and exits.
+    // --from defaults to the configured EVENT_STORE_SHARDS.
+    if let Command::Reshard { to, from, dry_run } = command {
+        let from = match from {
+            Some(n) => ShardLayout::new(n)?,
+            None => system.shard_layout(),
+        };
+        let rebalancer = ShardRebalancer::new(session.clone(), from, ShardLayout::new(to)?);
+        let report = rebalancer.run(dry_run).await?;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    // Operators: `scylladb_cdc dlq list|retry`, `scylladb_cdc replay` and
+    // `scylladb_cdc repair-sequence`
+    match command {
+        Command::Dlq { command: DlqCommand::List { event_type, aggregate, failed_from, failed_until, limit, cursor } } => {
+            let filter = DlqFilter { event_type, aggregate_id: aggregate, failed_from, failed_until };
+            let page = list_dlq_messages(&session, &filter, &projections::Paging { limit, cursor }).await?;
+            println!("{}", serde_json::to_string_pretty(&page)?);
+            return Ok(());
+        }
+        Command::Dlq { command: DlqCommand::Retry { id } } => {
+            // Own transactional id: the running service keeps its producer
+            let redpanda = Arc::new(system.cli_redpanda_client(Partitioner::Murmur2Random));
+            let dlq = DlqActor::spawn(
+                DlqActor::new(session.clone())
+                    .with_statement_cache(system.statements())
+                    .with_publisher(redpanda)
+                    .with_crypto(system.event_crypto())
+                    .with_quarantine_policy(system.dlq_quarantine_policy()),
+            );
+            let outcome = dlq
+                .ask(ReplayDlqMessage { id })
+                .await
+                .map_err(|e| anyhow::anyhow!("DLQ retry of {} failed: {}", id, e))?;
+            println!("{}", serde_json::to_string_pretty(&outcome)?);
+            if outcome != ReplayOutcome::Published {
+                anyhow::bail!("DLQ entry {} was not published", id);
+            }
+            return Ok(());
+        }
+        Command::RepairSequence { aggregate, aggregate_type } => {
+            let rolled_back_to = match aggregate_type {
+                AggregateKind::Order => system.event_store::<OrderEvent>("Order", "order-events").repair_sequence(aggregate).await?,
+                AggregateKind::Customer => system.customer_event_store().repair_sequence(aggregate).await?,
+            };
+            println!("{}", serde_json::to_string_pretty(&serde_json::json!({
+                "aggregate_id": aggregate,
+                "rolled_back_to": rolled_back_to,
+            }))?);
+            return Ok(());
+        }
+        Command::Replay { aggregate, aggregate_type, to_version, at } => {
+            let as_of = to_version.map(AsOf::Version).or(at.map(AsOf::Timestamp));
+            let replayed = match aggregate_type {
+                AggregateKind::Order => {
+                    let store = system.event_store::<OrderEvent>("Order", "order-events");
+                    replay_aggregate::<OrderAggregate>(&store, aggregate, as_of).await?
+                }
+                AggregateKind::Customer => {
+                    let store = system.customer_event_store();
+                    replay_aggregate::<CustomerAggregate>(&store, aggregate, as_of).await?
+                }
+            };
+            println!("{}", serde_json::to_string_pretty(&replayed)?);
+            return Ok(());
+        }
+        _ => {}
+    }
 
     // === 2. Initialize Prometheus metrics ===
     tracing::info!("Initializing metrics");
     let metrics = Arc::new(metrics::Metrics::new()?);
     tracing::info!("📊 Metrics registry created");
 
-    // Start metrics HTTP server in background
-    let metrics_registry = Arc::new(metrics.registry().clone());
+    // Latency/outcome of our own Scylla queries (event stores + a 1s probe);
+    // the CDC relay backs off while they spike
+    let latency = Arc::new(db::LatencyMonitor::new(std::time::Duration::from_secs(30)));
+    let _latency_probe = latency.clone().spawn_probe(session.clone(), std::time::Duration::from_secs(1));
+
+    // Everything built through `system` from here on records into `metrics`
+    let system = system
+        .with_metrics(metrics.clone().into())
+        .with_latency_monitor(latency);
+
+    // === 3. Create Redpanda client ===
+    // Direct publishes to event topics bypass the outbox - the guard of
+    // [redpanda] dual_write_policy handles them. murmur2 keeps keys on the
+    // same partitions as Java producers.
+    let redpanda = Arc::new(system.redpanda_client(Partitioner::Murmur2Random));
+
+    // Read model staleness; projections register themselves with their SLA
+    let staleness = Arc::new(system.staleness_tracker());
+    let _staleness_refresh = staleness.clone().spawn_background(std::time::Duration::from_secs(5));
+
+    // Cold start runs projection rebuild → CDC consumption → API one after
+    // another (STARTUP_ORDER / STARTUP_STAGGER_MS / STARTUP_PHASE_TIMEOUT_SECS)
+    let startup_policy = StartupPolicy::from_env()?;
+    let startup = Arc::new(StartupSequencer::new(startup_policy.clone()));
+
+    // Component health transitions survive restarts (health_events, 7 day TTL)
+    let health_history = Arc::new(system.health_history());
+    // Current component health, kept by the health monitor and served by /health
+    let health_snapshot = Arc::new(HealthSnapshot::new());
+
+    // Start metrics HTTP server in background (/metrics, /health, /health/live, /health/ready, /health/history, /info, /status/projections, /status/startup)
+    let service_info = Arc::new(metrics::ServiceInfo::collect(&session, &redpanda).await);
+    let metrics_server_handle = metrics.clone();
+    let staleness_handle = staleness.clone();
+    let startup_handle = startup.clone();
+    let health_history_handle = health_history.clone();
+    let health_handle = health_snapshot.clone();
+    let metrics_port = app_config.metrics.port;
     std::thread::spawn(move || {
         let rt = tokio::runtime::Runtime::new().unwrap();
         rt.block_on(async {
-            if let Err(e) = metrics::start_metrics_server(metrics_registry, 9090).await {
+            if let Err(e) = metrics::start_metrics_server(metrics_server_handle, service_info, staleness_handle, startup_handle, health_history_handle, health_handle, metrics_port).await {
                 tracing::error!("Metrics server error: {}", e);
             }
         });
     });
 
-    // === 3. Create Redpanda client ===
-    let redpanda = Arc::new(RedpandaClient::new("127.0.0.1:9092"));
+    // Active-active: SERVICE_REGION tags events with their origin and makes
+    // the CDC relay skip rows written by other regions
+    let region = app_config.region.clone().map(RegionConfig::new);
 
     // === 4. Start Coordinator Actor (manages CDC processor, DLQ, health check) ===
     tracing::info!("Starting coordinator actor with supervision");
-    let _coordinator = CoordinatorActor::spawn(CoordinatorActor::new(session.clone(), redpanda.clone()));
+    // [[tenants]]: one more outbox reader each, next to the shared outbox
+    for tenant in app_config.tenant_contexts()? {
+        tracing::info!(tenant = tenant.tenant_id().unwrap_or_default(), scope = ?tenant.scope(), "Relaying tenant outbox");
+    }
+    // Flags RF/consistency divergence (e.g. the dev schema's RF=1) in health
+    let mut coordinator = system
+        .coordinator(redpanda.clone())
+        .with_keyspace_expectations(db::KeyspaceExpectations::new(&app_config.scylla.keyspace))
+        .with_startup(startup.clone())
+        .with_health_history(health_history)
+        .with_health_snapshot(health_snapshot);
+    // CDC_APPROVAL_REQUIRED event types wait for approval via the admin API
+    let approval_gate = Arc::new(system.approval_gate());
+    coordinator = coordinator.with_approval_gate(approval_gate.clone());
+    // In-process watchers of single aggregates, fed by the outbox readers
+    let event_subscriptions = Arc::new(EventSubscriptions::default());
+    coordinator = coordinator.with_event_subscriptions(event_subscriptions.clone());
+    // CDC_THROTTLE_P95_MS / CDC_THROTTLE_ERROR_RATE / CDC_THROTTLE_MAX_DELAY_MS
+    let throttle_config = CdcThrottleConfig::from_env()?;
+    if let Some(throttle) = system.cdc_throttle(throttle_config.clone()) {
+        coordinator = coordinator.with_cdc_throttle(Arc::new(throttle));
+    }
+    if let Some(ref region) = region {
+        coordinator = coordinator.with_region(region.clone());
+    }
+    // Optional config-driven fan-out (e.g. large orders also to fraud-review)
+    if let Some(ref path) = app_config.cdc.routing_rules_file {
+        let rules = system.routing_rules_from_file(path)?;
+        tracing::info!(path = %path, rules = rules.len(), "Loaded routing rules");
+        coordinator = coordinator.with_routing_rules(rules);
+    }
+    let coordinator = CoordinatorActor::spawn(coordinator);
+
+    // SIGINT/SIGTERM drain the relay and flush offsets instead of killing
+    // the process (SHUTDOWN_TIMEOUT_SECS bounds the drain)
+    let shutdown = ShutdownController::install(coordinator.clone(), app_config.shutdown.timeout())?;
 
     // === 5. Initialize Event Sourcing Components ===
     tracing::info!("🎯 Initializing Event Sourcing");
 
+    // Keep aggregate_snapshots bounded (last N per aggregate, integrity-checked),
+    // for the shared tables and those of every [[tenants]] entry
+    let _snapshot_pruners: Vec<_> = std::iter::once(TenantContext::default())
+        .chain(app_config.tenant_contexts()?)
+        .filter_map(|tenant| system.snapshot_pruner(&tenant))
+        .map(SnapshotPruner::spawn_background)
+        .collect();
+
+    // Scheduled outbox/event_store integrity scan (report only)
+    let _integrity_check = match app_config.integrity.check_interval() {
+        Some(interval) => {
+            let config = db::IntegrityCheckConfig { window: interval, ..Default::default() };
+            Some(integrity_checker(&system, config).spawn_background(interval))
+        }
+        None => None,
+    };
+
     // Create Order event store (generic EventStore<OrderEvent>)
-    let event_store = Arc::new(EventStore::<OrderEvent>::new(
-        session.clone(),
+    let mut order_store = system.event_store::<OrderEvent>(
         "Order",         // aggregate type name
         "order-events"   // topic name
-    ));
+    );
+    let mut customer_store = system.customer_event_store();
+    if let Some(ref region) = region {
+        order_store = order_store.with_region(region.region());
+        customer_store = customer_store.with_region(region.region());
+    }
+
+    // Blue/green ([fencing] / FENCE_GENERATION): a deployment with a newer
+    // generation supersedes this one, whose appends fail with FencedOut
+    if let Some(generation) = app_config.fencing.generation {
+        let fencing = &app_config.fencing;
+        let fence = Arc::new(WriteFence::acquire(session.clone(), &fencing.name, generation, &fencing.holder()).await?);
+        fence.clone().spawn_refresher(fencing.refresh_interval());
+        order_store = order_store.with_fence(fence.clone());
+        customer_store = customer_store.with_fence(fence);
+    }
+    order_store = order_store.with_staleness_tracker(staleness.clone());
+    customer_store = customer_store.with_staleness_tracker(staleness.clone());
+    let event_store = Arc::new(order_store);
+    let customer_event_store = Arc::new(customer_store);
+
+    // Event-carried state transfer: full states on compacted order-state /
+    // customer-state topics ([state_snapshots] / STATE_SNAPSHOTS_ENABLED)
+    let (order_states, customer_states) = if app_config.state_snapshots.enabled {
+        for topic in ["order-state", "customer-state"] {
+            if let Err(e) = redpanda.ensure_compacted_topic(topic, app_config.state_snapshots.partitions).await {
+                tracing::warn!(topic = topic, error = %e, "Could not ensure compacted state topic");
+            }
+        }
+        let order_states = Arc::new(system.state_snapshot_publisher::<OrderAggregate>(
+            event_store.clone(),
+            redpanda.clone(),
+            "Order",
+            "order-state",
+        ));
+        let customer_states = Arc::new(system.state_snapshot_publisher::<CustomerAggregate>(
+            customer_event_store.clone(),
+            redpanda.clone(),
+            "Customer",
+            "customer-state",
+        ));
+        tracing::info!(
+            order_topic = order_states.topic(),
+            customer_topic = customer_states.topic(),
+            every = app_config.state_snapshots.every,
+            "📸 State snapshots enabled"
+        );
+        (Some(order_states), Some(customer_states))
+    } else {
+        (None, None)
+    };
+
+    // Create Order command handler; [pricing] in the config turns on OrderPriced totals
+    let mut order_handler = OrderCommandHandler::new(event_store.clone())
+        .with_conflict_retries(app_config.event_store.conflict_retries);
+    if let Some(pricing) = &app_config.pricing {
+        order_handler = order_handler.with_pricing(Arc::new(pricing.calculator()));
+    }
+    if let Some(publisher) = &order_states {
+        order_handler = order_handler.with_state_snapshots(publisher.clone());
+    }
+    let command_handler = Arc::new(order_handler);
+
+    // Create Customer command handler (command API, load test and demo)
+    let mut customer_handler = CustomerCommandHandler::new(customer_event_store.clone())
+        .with_conflict_retries(app_config.event_store.conflict_retries);
+    if let Some(publisher) = &customer_states {
+        customer_handler = customer_handler.with_state_snapshots(publisher.clone());
+    }
+    let customer_command_handler = Arc::new(customer_handler);
+
+    // Read models from CDC; restoring their checkpoints is the projection_rebuild phase
+    let projection_manager = projections::ProjectionManager::spawn(
+        system
+            .projection_manager()
+            .with_projection(system.order_read_model(event_store.clone()))
+            .with_projection(system.customer_read_model(customer_event_store.clone()))
+            .with_staleness_tracker(staleness.clone())
+            .with_startup(startup.clone()),
+    );
+    let mut shutdown = shutdown.with_projection_manager(projection_manager);
+
+    // Accept commands (command API, demo, load test) only once CDC is consuming
+    startup.wait_turn(StartupPhase::ApiAvailability).await;
+
+    // Start admin API in background (aggregate version diffs for support)
+    // Env-derived configuration goes into the audit trail (no-op when unchanged)
+    let config_audit = Arc::new(system.config_audit_log());
+    record_startup_config(&config_audit, &app_config, &startup_policy, &throttle_config).await;
+
+    let admin_state = Arc::new(api::AdminState {
+        orders: event_store.clone(),
+        customers: customer_event_store.clone(),
+        session: session.clone(),
+        config_audit,
+        approvals: approval_gate,
+        order_states,
+        customer_states,
+        coordinator,
+        admin_token: app_config.api.admin_token.clone(),
+    });
+    let admin_metrics = system.metrics();
+    let admin_port = app_config.api.admin_port;
+    std::thread::spawn(move || {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            if let Err(e) = api::start_admin_server(admin_state, admin_metrics, admin_port).await {
+                tracing::error!("Admin API error: {}", e);
+            }
+        });
+    });
+
+    // Command API (POST /orders, /customers, ...)
+    let command_state = Arc::new(api::CommandApiState {
+        orders: command_handler.clone(),
+        customers: customer_command_handler.clone(),
+        order_events: event_store.clone(),
+        order_queries: Arc::new(system.order_query_service()),
+        customer_queries: Arc::new(system.customer_query_service()),
+        idempotency: Arc::new(api::IdempotencyStore::new(session.clone())),
+    });
+    let command_metrics = system.metrics();
+    let command_port = app_config.api.port;
+    std::thread::spawn(move || {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            if let Err(e) = api::start_command_server(command_state, command_metrics, command_port).await {
+                tracing::error!("Command API error: {}", e);
+            }
+        });
+    });
+    startup.complete(StartupPhase::ApiAvailability);
+
+    // Soak testing: `scylladb_cdc load-test` sends synthetic traffic shaped
+    // by LOAD_* env vars instead of running the demo, then prints a report
+    if let Command::LoadTest = command {
+        let profile = app_config.load.profile()?;
+        let report = loadgen::run_load(profile, command_handler.clone(), customer_command_handler.clone()).await;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    // === 6. Event Sourcing Demo (`demo`, not `serve`) ===
+    // A signal during the demo goes straight to the graceful shutdown
+    let signal = match command {
+        Command::Demo => tokio::select! {
+            result = run_demo(&command_handler, &customer_command_handler, &event_store, &event_subscriptions, &app_config) => {
+                result?;
+                None
+            }
+            signal = shutdown.wait_for_signal() => Some(signal),
+        },
+        _ => None,
+    };
+
+    // === 7. Serve until SIGINT/SIGTERM, then drain and exit ===
+    let signal = match signal {
+        Some(signal) => signal,
+        None => {
+            tracing::info!("Running - SIGINT/SIGTERM shuts down gracefully");
+            shutdown.wait_for_signal().await
+        }
+    };
+    shutdown.shutdown(signal).await
+}
+
+/// `status` and body of `GET http://host:port/path`
+async fn http_get(host: &str, port: u16, path: &str) -> anyhow::Result<(u16, String)> {
+    use anyhow::Context;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
-    // Create Order command handler
-    let command_handler = Arc::new(OrderCommandHandler::new(event_store.clone()));
+    let mut stream = tokio::net::TcpStream::connect((host, port))
+        .await
+        .with_context(|| format!("No instance listening on {}:{}", host, port))?;
+    let request = format!("GET {} HTTP/1.0\r\nHost: {}\r\nConnection: close\r\n\r\n", path, host);
+    stream.write_all(request.as_bytes()).await?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await?;
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap_or((response.as_str(), ""));
+    let status = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .with_context(|| format!("Malformed HTTP response from {}:{}", host, port))?;
+    Ok((status, body.to_string()))
+}
+
+/// An aggregate rebuilt from its events, up to `to_version` if given
+async fn replay_aggregate<A>(
+    store: &EventStore<A::Event>,
+    aggregate_id: uuid::Uuid,
+    as_of: Option<AsOf>,
+) -> anyhow::Result<serde_json::Value>
+where
+    A: CommandAggregate,
+    A::Event: DomainEvent,
+    A::Error: std::fmt::Display,
+{
+    if let Some(as_of) = as_of {
+        as_of.validate()?;
+    }
+
+    // Folded event by event - the history is never held in memory as a whole
+    let mut events = std::pin::pin!(store.load_events_stream(aggregate_id));
+    let mut state: Option<A> = None;
+    let mut event_count = 0;
+    let mut last_event_type = None;
+    while let Some(envelope) = events.try_next().await? {
+        if as_of.is_some_and(|as_of| !as_of.includes(&envelope)) {
+            break;
+        }
+        state = Some(A::replay_envelope(state, &envelope)?);
+        event_count += 1;
+        last_event_type = Some(envelope.event_type);
+    }
+    let aggregate = match as_of {
+        Some(as_of) => as_of.reached(aggregate_id, state)?,
+        None => state.ok_or_else(|| anyhow::anyhow!("Aggregate {} has no events", aggregate_id))?,
+    };
+
+    Ok(serde_json::json!({
+        "aggregate_id": aggregate_id,
+        "version": aggregate.version(),
+        "event_count": event_count,
+        "last_event_type": last_event_type,
+        "as_of": as_of,
+        "state": aggregate,
+    }))
+}
+
+/// Order and customer lifecycles through the command handlers
+async fn run_demo(
+    command_handler: &OrderCommandHandler,
+    customer_command_handler: &CustomerCommandHandler,
+    event_store: &EventStore<OrderEvent>,
+    event_subscriptions: &EventSubscriptions,
+    app_config: &config::AppConfig,
+) -> anyhow::Result<()> {
+    // Each walkthrough drives several command handler futures; boxed so
+    // run_demo's own future stays small
+    Box::pin(run_order_demo(command_handler, event_store, event_subscriptions)).await?;
+    Box::pin(run_customer_demo(customer_command_handler)).await?;
 
-    // === 6. Event Sourcing Demo ===
+    tracing::info!("");
+    tracing::info!("════════════════════════════════════════════════════════════");
+    tracing::info!(" Event Sourcing Demo Complete!");
+    tracing::info!("════════════════════════════════════════════════════════════");
+    tracing::info!("");
+    tracing::info!("Aggregates Demonstrated:");
+    tracing::info!("  📦 Order Aggregate:");
+    tracing::info!("     - Created, Confirmed, Shipped, Delivered");
+    tracing::info!("  👤 Customer Aggregate:");
+    tracing::info!("     - Registered, Address Added, Tier Upgraded");
+    tracing::info!("");
+    tracing::info!("Event Sourcing Features:");
+    tracing::info!("  ✅ Multiple domain aggregates (Order, Customer)");
+    tracing::info!("  ✅ Generic event store infrastructure");
+    tracing::info!("  ✅ Command handlers with business logic");
+    tracing::info!("  ✅ Optimistic concurrency control (versioning)");
+    tracing::info!("  ✅ Atomic write to event_store + outbox_messages");
+    tracing::info!("  ✅ CDC streaming to Redpanda");
+    tracing::info!("  ✅ Event metadata (causation, correlation)");
+    tracing::info!("");
+    tracing::info!("Architecture:");
+    tracing::info!("  Command → Aggregate → Events → [event_store + outbox]");
+    tracing::info!("                                         ↓");
+    tracing::info!("                                    CDC Stream");
+    tracing::info!("                                         ↓");
+    tracing::info!("                          ┌──────────────┴──────────────┐");
+    tracing::info!("                          ↓                             ↓");
+    tracing::info!("                    Projections                    Redpanda");
+    tracing::info!("                   (Read Models)                (External Systems)");
+    tracing::info!("");
+    tracing::info!(" Metrics available at: http://localhost:{}/metrics", app_config.metrics.port);
+    tracing::info!(" Commands at:          http://localhost:{}/orders", app_config.api.port);
+    tracing::info!(" Version diffs at:     http://localhost:{}/admin/orders/{{id}}/versions/{{v}}/diff", app_config.api.admin_port);
+    tracing::info!(" Config history at:    http://localhost:{}/admin/config/history", app_config.api.admin_port);
+    tracing::info!(" Dead letters at:      http://localhost:{}/admin/dlq", app_config.api.admin_port);
+    tracing::info!("");
+
+    Ok(())
+}
+
+/// Order lifecycle: created, confirmed, shipped, delivered
+async fn run_order_demo(
+    command_handler: &OrderCommandHandler,
+    event_store: &EventStore<OrderEvent>,
+    event_subscriptions: &EventSubscriptions,
+) -> anyhow::Result<()> {
     tracing::info!("");
     tracing::info!("════════════════════════════════════════════════════════════");
     tracing::info!("📝 Event Sourcing Demo - Full Order Lifecycle");
@@ -94,7 +767,8 @@ async fn main() -> anyhow::Result<()> {
 
     let order_id = uuid::Uuid::new_v4();
     let customer_id = uuid::Uuid::new_v4();
-    let correlation_id = uuid::Uuid::new_v4();
+    let ctx = CommandContext::new(uuid::Uuid::new_v4()).with_metadata("client", "demo");
+    let mut order_events = event_subscriptions.subscribe(order_id);
 
     // Create Order
     tracing::info!("1️⃣  Creating order via Event Sourcing CommandHandler...");
@@ -114,7 +788,7 @@ async fn main() -> anyhow::Result<()> {
                 },
             ],
         },
-        correlation_id,
+        &ctx,
     ).await?;
 
     tracing::info!("   ✅ Order created: {} (version: {})", order_id, version);
@@ -123,15 +797,21 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("   🌊 CDC will stream to projections and Redpanda");
     tracing::info!("");
 
-    // Wait for CDC to process
-    tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+    // Wait for CDC to read it back
+    let relayed = order_events
+        .wait_for(std::time::Duration::from_secs(10), |event| event.sequence_number >= version)
+        .await;
+    match relayed {
+        Ok(event) => tracing::info!("   👀 CDC delivered {} to the order's subscribers", event.event_type),
+        Err(e) => tracing::warn!("   ⚠️  {}", e),
+    }
 
     // Confirm Order
     tracing::info!("2️⃣  Confirming order...");
     let version = command_handler.handle(
         order_id,
         OrderCommand::ConfirmOrder,
-        correlation_id,
+        &ctx,
     ).await?;
 
     tracing::info!("   ✅ Order confirmed (version: {})", version);
@@ -147,7 +827,7 @@ async fn main() -> anyhow::Result<()> {
             tracking_number: "TRACK-123-XYZ".to_string(),
             carrier: "DHL Express".to_string(),
         },
-        correlation_id,
+        &ctx,
     ).await?;
 
     tracing::info!("   ✅ Order shipped (version: {})", version);
@@ -163,7 +843,7 @@ async fn main() -> anyhow::Result<()> {
         OrderCommand::DeliverOrder {
             signature: Some("John Doe".to_string()),
         },
-        correlation_id,
+        &ctx,
     ).await?;
 
     tracing::info!("   ✅ Order delivered (version: {})", version);
@@ -179,24 +859,19 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("⏳ Waiting for CDC processor to publish events...");
     tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
 
-    // === 7. Customer Event Sourcing Demo ===
+    Ok(())
+}
+
+/// Customer lifecycle: registered, address added, tier upgraded
+async fn run_customer_demo(customer_command_handler: &CustomerCommandHandler) -> anyhow::Result<()> {
     tracing::info!("");
     tracing::info!("════════════════════════════════════════════════════════════");
     tracing::info!("👤 Customer Event Sourcing Demo");
     tracing::info!("════════════════════════════════════════════════════════════");
     tracing::info!("");
 
-    // Create Customer event store
-    let customer_event_store = Arc::new(EventStore::<CustomerEvent>::new(
-        session.clone(),
-        "Customer",
-        "customer-events"
-    ));
-
-    let customer_command_handler = Arc::new(CustomerCommandHandler::new(customer_event_store.clone()));
-
     let customer_id = uuid::Uuid::new_v4();
-    let customer_correlation_id = uuid::Uuid::new_v4();
+    let customer_ctx = CommandContext::new(uuid::Uuid::new_v4()).with_metadata("client", "demo");
 
     // Register Customer
     tracing::info!("1️⃣  Registering customer...");
@@ -209,7 +884,7 @@ async fn main() -> anyhow::Result<()> {
             last_name: "Doe".to_string(),
             phone: Some(PhoneNumber::new("+1-555-0123")),
         },
-        customer_correlation_id,
+        &customer_ctx,
     ).await?;
 
     tracing::info!("   ✅ Customer registered: {} (version: {})", customer_id, version);
@@ -233,7 +908,7 @@ async fn main() -> anyhow::Result<()> {
             },
             set_as_default: true,
         },
-        customer_correlation_id,
+        &customer_ctx,
     ).await?;
 
     tracing::info!("   ✅ Address added (version: {})", version);
@@ -248,7 +923,7 @@ async fn main() -> anyhow::Result<()> {
         CustomerCommand::UpgradeTier {
             new_tier: CustomerTier::Gold,
         },
-        customer_correlation_id,
+        &customer_ctx,
     ).await?;
 
     tracing::info!("   ✅ Customer upgraded to Gold tier (version: {})", version);
@@ -256,38 +931,127 @@ async fn main() -> anyhow::Result<()> {
 
     tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
 
-    tracing::info!("");
-    tracing::info!("════════════════════════════════════════════════════════════");
-    tracing::info!(" Event Sourcing Demo Complete!");
-    tracing::info!("════════════════════════════════════════════════════════════");
-    tracing::info!("");
-    tracing::info!("Aggregates Demonstrated:");
-    tracing::info!("  📦 Order Aggregate:");
-    tracing::info!("     - Created, Confirmed, Shipped, Delivered");
-    tracing::info!("  👤 Customer Aggregate:");
-    tracing::info!("     - Registered, Address Added, Tier Upgraded");
-    tracing::info!("");
-    tracing::info!("Event Sourcing Features:");
-    tracing::info!("  ✅ Multiple domain aggregates (Order, Customer)");
-    tracing::info!("  ✅ Generic event store infrastructure");
-    tracing::info!("  ✅ Command handlers with business logic");
-    tracing::info!("  ✅ Optimistic concurrency control (versioning)");
-    tracing::info!("  ✅ Atomic write to event_store + outbox_messages");
-    tracing::info!("  ✅ CDC streaming to Redpanda");
-    tracing::info!("  ✅ Event metadata (causation, correlation)");
-    tracing::info!("");
-    tracing::info!("Architecture:");
-    tracing::info!("  Command → Aggregate → Events → [event_store + outbox]");
-    tracing::info!("                                         ↓");
-    tracing::info!("                                    CDC Stream");
-    tracing::info!("                                         ↓");
-    tracing::info!("                          ┌──────────────┴──────────────┐");
-    tracing::info!("                          ↓                             ↓");
-    tracing::info!("                    Projections                    Redpanda");
-    tracing::info!("                   (Read Models)                (External Systems)");
-    tracing::info!("");
-    tracing::info!(" Metrics available at: http://localhost:9090/metrics");
-    tracing::info!("");
-
     Ok(())
 }
+
+/// Integrity checker that re-emits missing events to their event topics
+fn integrity_checker(system: &SystemBuilder, config: db::IntegrityCheckConfig) -> db::IntegrityChecker {
+    system
+        .integrity_checker(config)
+        .with_reemit_route("Order", "Order", "order-events")
+        .with_reemit_route("Customer", "Customer", "customer-events")
+}
+
+/// Record the configuration this instance started with
+async fn record_startup_config(
+    audit: &config::ConfigAuditLog,
+    app: &config::AppConfig,
+    startup: &StartupPolicy,
+    throttle: &CdcThrottleConfig,
+) {
+    let settings = [
+        // Credentials are deliberately left out
+        (
+            "connections",
+            Some(serde_json::json!({
+                "scylla_nodes": app.scylla.nodes,
+                "keyspace": app.scylla.keyspace,
+                "redpanda_brokers": app.redpanda.brokers,
+                "redpanda_batch_max_records": app.redpanda.batch_max_records,
+                "redpanda_batch_linger_ms": app.redpanda.batch_linger_ms,
+                "redpanda_transactional_id": app.redpanda.transactional_id,
+                "cdc_outbox_table": app.cdc.outbox_table,
+                "cdc_tables": app.cdc_tables().iter().map(|table| table.source.label()).collect::<Vec<_>>(),
+                "cdc_approval_required": app.cdc.approval_required,
+                "metrics_port": app.metrics.port,
+                "otlp_endpoint": app.telemetry.otlp_endpoint,
+            })),
+        ),
+        (
+            "tuning",
+            Some(serde_json::json!({
+                "snapshot_every": app.event_store.snapshot_every,
+                "event_store_shards": app.event_store.shards,
+                "command_conflict_retries": app.event_store.conflict_retries,
+                "event_store_load_page_size": app.event_store.load_page_size,
+                "state_snapshots_enabled": app.state_snapshots.enabled,
+                "state_snapshot_every": app.state_snapshots.every,
+                "retry_max_attempts": app.retry.max_attempts,
+                "retry_initial_delay_ms": app.retry.initial_delay_ms,
+                "retry_max_delay_ms": app.retry.max_delay_ms,
+                "retry_jitter": app.retry.jitter.as_str(),
+                "retry_budget_per_sec": app.retry.budget_per_sec,
+                "retry_budget_burst": app.retry.budget_burst,
+                "circuit_breaker_failure_threshold": app.circuit_breaker.failure_threshold,
+                "circuit_breaker_timeout_secs": app.circuit_breaker.timeout_secs,
+                "circuit_breaker_failure_rate": app.circuit_breaker.failure_rate,
+                "circuit_breaker_window_size": app.circuit_breaker.window_size,
+                "circuit_breaker_half_open_max_calls": app.circuit_breaker.half_open_max_calls,
+                "supervision_max_restarts": app.supervision.max_restarts,
+                "supervision_initial_backoff_ms": app.supervision.initial_backoff_ms,
+                "supervision_max_backoff_ms": app.supervision.max_backoff_ms,
+                "cdc_dedup_ttl_secs": app.cdc.dedup_ttl_secs,
+                "cdc_payload_format": app.cdc.payload_format.as_str(),
+                "cdc_key_strategy": app.cdc.key_strategy.as_str(),
+                "cdc_publish_workers": app.cdc.publish_workers,
+                "cdc_publish_queue_depth": app.cdc.publish_queue_depth,
+                "cdc_stream_audit": app.cdc.stream_audit,
+                "cdc_mode": app.cdc.mode.as_str(),
+                "cdc_poll_interval_ms": app.cdc.poll_interval_ms,
+                "outbox_retention_secs": app.outbox.retention_secs,
+                "outbox_cleanup_interval_secs": app.outbox.cleanup_interval_secs,
+                "dlq_max_replays": app.dlq.max_replays,
+                "dlq_max_age_secs": app.dlq.max_age_secs,
+                "otel_sample_ratio": app.telemetry.sample_ratio,
+                // Key ids only, never the keys
+                "encryption_key_ids": app.encryption.key_ids(),
+            })),
+        ),
+        (
+            "tenants",
+            Some(serde_json::json!(app
+                .tenants
+                .iter()
+                .map(|tenant| serde_json::json!({
+                    "id": tenant.id,
+                    "keyspace": tenant.keyspace,
+                    "table_prefix": tenant.table_prefix,
+                }))
+                .collect::<Vec<_>>())),
+        ),
+        (
+            "consistency",
+            Some(serde_json::json!({
+                "append": app.consistency.append.as_str(),
+                "read": app.consistency.read.as_str(),
+                "lwt": app.consistency.lwt.as_str(),
+                "serial": app.consistency.serial.as_str(),
+                "dlq": app.consistency.dlq.as_str(),
+                "offsets": app.consistency.offsets.as_str(),
+            })),
+        ),
+        (
+            "startup_policy",
+            Some(serde_json::json!({
+                "order": startup.order.iter().map(|phase| phase.as_str()).collect::<Vec<_>>(),
+                "stagger_ms": startup.stagger.as_millis() as u64,
+                "phase_timeout_secs": startup.phase_timeout.as_secs(),
+            })),
+        ),
+        (
+            "cdc_throttle",
+            Some(serde_json::json!({
+                "p95_ms": throttle.latency_threshold.as_millis() as u64,
+                "error_rate": throttle.error_rate_threshold,
+                "max_delay_ms": throttle.max_delay.as_millis() as u64,
+            })),
+        ),
+        ("routing_rules_file", app.cdc.routing_rules_file.clone().map(serde_json::Value::String)),
+    ];
+
+    for (key, value) in settings {
+        if let Err(e) = audit.record(config::ConfigChange::new(key, value, "system", "startup")).await {
+            tracing::warn!(key = key, error = %e, "Failed to record configuration in audit trail");
+        }
+    }
+}
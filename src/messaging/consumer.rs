@@ -0,0 +1,356 @@
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rdkafka::{
+    config::ClientConfig,
+    consumer::{CommitMode, Consumer, StreamConsumer},
+    message::{BorrowedMessage, Headers, Message},
+};
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::event_sourcing::{DomainEvent, EventEnvelope};
+use crate::utils::{retry_with_backoff, RetryConfig, RetryResult};
+use super::headers::{EnvelopeHeaders, PayloadFormat};
+
+// ============================================================================
+// Redpanda Consumer - Consume the Events We Publish
+// ============================================================================
+//
+// Counterpart of RedpandaClient for components inside this crate that react
+// to published events (projections, sagas, integration tests):
+//
+//   RedpandaConsumer::new(brokers, "order-saga")
+//       .with_commit_strategy(CommitStrategy::Batch(100))
+//       .run::<OrderEvent, _>(&["OrderCreated", "OrderCancelled"], handler)
+//       .await?;
+//
+// Messages are decoded into EventEnvelope<E>: the value is the event JSON,
+// the envelope fields come from the headers the CDC relay sets (see
+// headers.rs), falling back to the topic (event type) and the message key
+// (aggregate id) for messages without them. Values published in the
+// envelope format are the EventEnvelope itself.
+//
+// Delivery is at-least-once. Automatic offset storing is off; an offset is
+// stored only after its handler succeeded, and the commit strategy decides
+// when stored offsets are committed. Handlers must therefore be idempotent
+// (projections already are, via version-stamped rows).
+//
+// A failing handler is retried with backoff. If it still fails the consumer
+// commits what it handled so far and stops with the error - the message is
+// redelivered after a restart or rebalance instead of being skipped.
+// Messages that cannot be decoded never will be; they are logged and skipped.
+//
+// ============================================================================
+
+/// When handled offsets are committed to the consumer group
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CommitStrategy {
+    /// Synchronous commit after every message (smallest redelivery window)
+    EveryMessage,
+    /// Asynchronous commit after every `n` messages
+    Batch(usize),
+    /// Background commit of handled offsets by the client
+    Interval(Duration),
+}
+
+impl Default for CommitStrategy {
+    fn default() -> Self {
+        CommitStrategy::Interval(Duration::from_secs(5))
+    }
+}
+
+/// Callback invoked for every consumed event
+#[async_trait]
+pub trait EventHandler<E>: Send + Sync {
+    async fn handle(&self, envelope: EventEnvelope<E>) -> Result<()>;
+}
+
+/// Closures returning a future are handlers
+#[async_trait]
+impl<E, F, Fut> EventHandler<E> for F
+where
+    E: Send + 'static,
+    F: Fn(EventEnvelope<E>) -> Fut + Send + Sync,
+    Fut: Future<Output = Result<()>> + Send,
+{
+    async fn handle(&self, envelope: EventEnvelope<E>) -> Result<()> {
+        self(envelope).await
+    }
+}
+
+/// Decode a consumed message into an envelope
+///
+/// `event_type` falls back to the topic, `aggregate_id` to the key. Ids that
+/// are neither in the headers nor derivable are nil.
+pub fn decode_envelope<E: DomainEvent>(
+    topic: &str,
+    key: Option<&str>,
+    payload: &[u8],
+    headers: &EnvelopeHeaders,
+    timestamp: Option<DateTime<Utc>>,
+) -> Result<EventEnvelope<E>> {
+    if headers.payload_format == Some(PayloadFormat::Envelope) {
+        return serde_json::from_slice(payload)
+            .with_context(|| format!("Failed to deserialize event envelope from {}", topic));
+    }
+
+    let aggregate_id = match headers.aggregate_id {
+        Some(id) => id,
+        None => match key.and_then(|k| Uuid::parse_str(k).ok()) {
+            Some(id) => id,
+            None => bail!("Message on {} has no aggregate id (header or uuid key)", topic),
+        },
+    };
+
+    let event_data: E = serde_json::from_slice(payload)
+        .with_context(|| format!("Failed to deserialize event payload from {}", topic))?;
+
+    let envelope = EventEnvelope {
+        event_id: headers.event_id.unwrap_or_else(Uuid::nil),
+        aggregate_id,
+        sequence_number: headers.sequence_number.unwrap_or(0),
+        event_type: headers.event_type.clone().unwrap_or_else(|| topic.to_string()),
+        event_version: headers.event_version.unwrap_or(1),
+        event_data,
+        causation_id: headers.causation_id,
+        correlation_id: headers.correlation_id.unwrap_or_else(Uuid::nil),
+        user_id: None,
+        timestamp: timestamp.unwrap_or_else(Utc::now),
+        metadata: HashMap::new(),
+    };
+
+    Ok(match headers.origin_region {
+        Some(ref region) => envelope.with_origin_region(region),
+        None => envelope,
+    })
+}
+
+pub struct RedpandaConsumer {
+    brokers: String,
+    group_id: String,
+    commit_strategy: CommitStrategy,
+    from_beginning: bool,
+    retry_config: RetryConfig,
+}
+
+impl RedpandaConsumer {
+    pub fn new(brokers: &str, group_id: &str) -> Self {
+        Self {
+            brokers: brokers.to_string(),
+            group_id: group_id.to_string(),
+            commit_strategy: CommitStrategy::default(),
+            from_beginning: false,
+            retry_config: RetryConfig::conservative(),
+        }
+    }
+
+    pub fn with_commit_strategy(mut self, commit_strategy: CommitStrategy) -> Self {
+        self.commit_strategy = commit_strategy;
+        self
+    }
+
+    /// Start new consumer groups at the oldest retained message (default: latest)
+    pub fn from_beginning(mut self) -> Self {
+        self.from_beginning = true;
+        self
+    }
+
+    /// Retry of failing handlers before the consumer stops
+    pub fn with_retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.retry_config = retry_config;
+        self
+    }
+
+    fn client_config(&self) -> ClientConfig {
+        let mut config = ClientConfig::new();
+        config
+            .set("bootstrap.servers", &self.brokers)
+            .set("group.id", &self.group_id)
+            .set("enable.auto.offset.store", "false")
+            // Skip records of aborted publish transactions (RedpandaClient::with_transactions)
+            .set("isolation.level", "read_committed")
+            .set("auto.offset.reset", if self.from_beginning { "earliest" } else { "latest" });
+
+        match self.commit_strategy {
+            CommitStrategy::Interval(interval) => {
+                config
+                    .set("enable.auto.commit", "true")
+                    .set("auto.commit.interval.ms", interval.as_millis().to_string());
+            }
+            CommitStrategy::EveryMessage | CommitStrategy::Batch(_) => {
+                config.set("enable.auto.commit", "false");
+            }
+        }
+        config
+    }
+
+    /// Consume `topics` until a handler fails permanently or the client errors
+    pub async fn run<E, H>(&self, topics: &[&str], handler: H) -> Result<()>
+    where
+        E: DomainEvent + 'static,
+        H: EventHandler<E>,
+    {
+        let consumer: StreamConsumer = self
+            .client_config()
+            .create()
+            .context("Failed to create Redpanda consumer")?;
+        consumer.subscribe(topics)?;
+
+        tracing::info!(
+            group_id = %self.group_id,
+            topics = ?topics,
+            commit_strategy = ?self.commit_strategy,
+            "📥 Redpanda consumer subscribed"
+        );
+
+        let mut uncommitted = 0usize;
+        loop {
+            let message = consumer.recv().await?;
+
+            if let Err(e) = self.dispatch(&message, &handler).await {
+                // Keep progress made so far; the failed message is redelivered
+                if uncommitted > 0 {
+                    consumer.commit_consumer_state(CommitMode::Sync)?;
+                }
+                return Err(e);
+            }
+            consumer.store_offset_from_message(&message)?;
+
+            match self.commit_strategy {
+                CommitStrategy::EveryMessage => consumer.commit_consumer_state(CommitMode::Sync)?,
+                CommitStrategy::Batch(n) => {
+                    uncommitted += 1;
+                    if uncommitted >= n.max(1) {
+                        consumer.commit_consumer_state(CommitMode::Async)?;
+                        uncommitted = 0;
+                    }
+                }
+                CommitStrategy::Interval(_) => {}
+            }
+        }
+    }
+
+    /// Consume in a background task
+    pub fn spawn<E, H>(self, topics: Vec<String>, handler: H) -> tokio::task::JoinHandle<Result<()>>
+    where
+        E: DomainEvent + 'static,
+        H: EventHandler<E> + 'static,
+    {
+        tokio::spawn(async move {
+            let topics: Vec<&str> = topics.iter().map(String::as_str).collect();
+            let result = self.run(&topics, handler).await;
+            if let Err(ref e) = result {
+                tracing::error!(group_id = %self.group_id, error = %e, "Redpanda consumer stopped");
+            }
+            result
+        })
+    }
+
+    /// Decode and handle one message; Ok for skipped (undecodable) messages
+    async fn dispatch<E, H>(&self, message: &BorrowedMessage<'_>, handler: &H) -> Result<()>
+    where
+        E: DomainEvent + 'static,
+        H: EventHandler<E>,
+    {
+        let headers = message
+            .headers()
+            .map(|headers| {
+                EnvelopeHeaders::from_pairs(headers.iter().filter_map(|header| {
+                    header.value.and_then(|v| std::str::from_utf8(v).ok()).map(|v| (header.key, v))
+                }))
+            })
+            .unwrap_or_default();
+        let key = message.key().and_then(|k| std::str::from_utf8(k).ok());
+        let timestamp = message.timestamp().to_millis().and_then(DateTime::from_timestamp_millis);
+
+        let envelope = match decode_envelope::<E>(message.topic(), key, message.payload().unwrap_or_default(), &headers, timestamp) {
+            Ok(envelope) => envelope,
+            Err(e) => {
+                tracing::error!(
+                    topic = %message.topic(),
+                    partition = message.partition(),
+                    offset = message.offset(),
+                    error = %e,
+                    "Skipping undecodable message"
+                );
+                return Ok(());
+            }
+        };
+
+        let event_id = envelope.event_id;
+        let result = retry_with_backoff(self.retry_config.clone(), |_attempt| {
+            handler.handle(envelope.clone())
+        })
+        .await;
+
+        match result {
+            RetryResult::Success(()) => Ok(()),
+            RetryResult::Failed(e) | RetryResult::PermanentFailure(e) => Err(e.context(format!(
+                "Handler failed for event {} at {}/{}:{}",
+                event_id,
+                message.topic(),
+                message.partition(),
+                message.offset()
+            ))),
+        }
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::order::OrderEvent;
+
+    const SHIPPED: &str = r#"{"type":"Shipped","data":{"tracking_number":"TRACK-1","carrier":"UPS","shipped_at":"2024-01-01T00:00:00Z"}}"#;
+
+    #[test]
+    fn test_decode_envelope_from_headers_and_fallbacks() {
+        let aggregate_id = Uuid::new_v4();
+        let headers = EnvelopeHeaders {
+            aggregate_id: Some(aggregate_id),
+            sequence_number: Some(3),
+            event_type: Some("OrderShipped".to_string()),
+            origin_region: Some("eu-west".to_string()),
+            ..Default::default()
+        };
+
+        let envelope = decode_envelope::<OrderEvent>("OrderShipped.eu-west", None, SHIPPED.as_bytes(), &headers, None).unwrap();
+        assert_eq!(envelope.aggregate_id, aggregate_id);
+        assert_eq!(envelope.sequence_number, 3);
+        assert_eq!(envelope.event_type, "OrderShipped");
+        assert_eq!(envelope.origin_region(), Some("eu-west"));
+        assert!(matches!(envelope.event_data, OrderEvent::Shipped(_)));
+
+        // Without headers: event type from the topic, aggregate from the key
+        let key = aggregate_id.to_string();
+        let legacy = decode_envelope::<OrderEvent>("OrderShipped", Some(&key), SHIPPED.as_bytes(), &EnvelopeHeaders::default(), None).unwrap();
+        assert_eq!((legacy.aggregate_id, legacy.event_type.as_str()), (aggregate_id, "OrderShipped"));
+
+        assert!(decode_envelope::<OrderEvent>("OrderShipped", Some("not-a-uuid"), SHIPPED.as_bytes(), &EnvelopeHeaders::default(), None).is_err());
+        assert!(decode_envelope::<OrderEvent>("OrderShipped", Some(&key), b"not json", &EnvelopeHeaders::default(), None).is_err());
+    }
+
+    #[test]
+    fn test_commit_strategy_client_config() {
+        let consumer = RedpandaConsumer::new("localhost:9092", "saga");
+        let config = consumer.client_config();
+        assert_eq!(config.get("enable.auto.offset.store"), Some("false"));
+        assert_eq!(config.get("enable.auto.commit"), Some("true"));
+        assert_eq!(config.get("auto.commit.interval.ms"), Some("5000"));
+        assert_eq!(config.get("auto.offset.reset"), Some("latest"));
+
+        let config = RedpandaConsumer::new("localhost:9092", "saga")
+            .with_commit_strategy(CommitStrategy::Batch(50))
+            .from_beginning()
+            .client_config();
+        assert_eq!(config.get("enable.auto.commit"), Some("false"));
+        assert_eq!(config.get("auto.offset.reset"), Some("earliest"));
+    }
+}
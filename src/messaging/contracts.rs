@@ -0,0 +1,444 @@
+use anyhow::{bail, Context, Result};
+use chrono::{TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::domain::customer::{
+    Address, CustomerAddressAdded, CustomerAddressRemoved, CustomerAddressUpdated, CustomerDeactivated,
+    CustomerEmailChanged, CustomerEvent, CustomerForgotten, CustomerPaymentMethodAdded, CustomerPaymentMethodRemoved,
+    CustomerPhoneChanged, CustomerProfileUpdated, CustomerReactivated, CustomerRegistered, CustomerSuspended,
+    CustomerTier, CustomerTierUpgraded, Email, PaymentMethod, PaymentMethodType, PhoneNumber,
+};
+use crate::domain::order::{
+    OrderCancelled, OrderConfirmed, OrderCreated, OrderDelivered, OrderEvent, OrderItem, OrderItemsUpdated,
+    OrderPriced, OrderShipped, OrderTotals,
+};
+use crate::event_sourcing::{serialize_event, DomainEvent};
+
+// ============================================================================
+// Consumer Contracts - Downstream Expectations on Published Events
+// ============================================================================
+//
+// Downstream teams depend on fields of our events without us knowing which.
+// A consumer contract makes that explicit: the consumer lists, per topic and
+// event type, the payload fields it reads and their JSON types. Contracts
+// are fixtures in `contracts/` (one JSON file per consumer):
+//
+//   {
+//     "consumer": "shipping-service",
+//     "expectations": [{
+//       "topic": "order-events",
+//       "event_type": "OrderShipped",
+//       "fields": {
+//         "data.tracking_number": "string",
+//         "data.items[].product_id": "string",
+//         "data.signature": "string?"
+//       }
+//     }]
+//   }
+//
+// Fields are dot paths into the published payload; `name[]` applies the rest
+// of the path to every array element. Types: string, integer, number,
+// boolean, object, array, any - a trailing `?` also accepts null.
+//
+// The verifier checks every expectation against sample payloads of every
+// event we publish (`published_samples`, serialized exactly like the outbox
+// payload, optional fields both set and unset). Removing, renaming or
+// retyping a field a consumer relies on, or no longer publishing an event
+// type, is a violation. It runs as a unit test (so `cargo test` fails on a
+// breaking change) and as `scylladb_cdc verify-contracts [DIR]`.
+//
+// ============================================================================
+
+/// JSON type a consumer expects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    Object,
+    Array,
+    Any,
+}
+
+impl FieldType {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "string" => Some(FieldType::String),
+            "integer" => Some(FieldType::Integer),
+            "number" => Some(FieldType::Number),
+            "boolean" => Some(FieldType::Boolean),
+            "object" => Some(FieldType::Object),
+            "array" => Some(FieldType::Array),
+            "any" => Some(FieldType::Any),
+            _ => None,
+        }
+    }
+
+    fn matches(&self, value: &Value) -> bool {
+        match self {
+            FieldType::String => value.is_string(),
+            FieldType::Integer => value.is_i64() || value.is_u64(),
+            FieldType::Number => value.is_number(),
+            FieldType::Boolean => value.is_boolean(),
+            FieldType::Object => value.is_object(),
+            FieldType::Array => value.is_array(),
+            FieldType::Any => !value.is_null(),
+        }
+    }
+}
+
+/// Expected type of one field, e.g. "string?"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldSpec {
+    pub field_type: FieldType,
+    pub nullable: bool,
+}
+
+impl FieldSpec {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (name, nullable) = match spec.strip_suffix('?') {
+            Some(name) => (name, true),
+            None => (spec, false),
+        };
+        let Some(field_type) = FieldType::parse(name) else {
+            bail!("Unknown field type '{}'", spec);
+        };
+        Ok(Self { field_type, nullable })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Expectation {
+    pub topic: String,
+    pub event_type: String,
+    /// Dot path -> type spec
+    pub fields: BTreeMap<String, String>,
+}
+
+/// Everything one downstream consumer relies on
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConsumerContract {
+    pub consumer: String,
+    pub expectations: Vec<Expectation>,
+}
+
+impl ConsumerContract {
+    pub fn from_json(json: &str) -> Result<Self> {
+        let contract: Self = serde_json::from_str(json)?;
+        for expectation in &contract.expectations {
+            for spec in expectation.fields.values() {
+                FieldSpec::parse(spec)?;
+            }
+        }
+        Ok(contract)
+    }
+}
+
+/// All registered contracts
+#[derive(Debug, Clone, Default)]
+pub struct ContractSet {
+    pub contracts: Vec<ConsumerContract>,
+}
+
+impl ContractSet {
+    /// Every `*.json` file of `dir`, in file name order
+    pub fn load_dir(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref();
+        let mut paths: Vec<_> = std::fs::read_dir(dir)
+            .with_context(|| format!("Failed to read contract directory {}", dir.display()))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort();
+
+        let mut contracts = Vec::new();
+        for path in paths {
+            let json = std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+            contracts.push(ConsumerContract::from_json(&json).with_context(|| format!("Invalid contract {}", path.display()))?);
+        }
+        Ok(Self { contracts })
+    }
+
+    /// Check every expectation against `samples`
+    pub fn verify(&self, samples: &[PublishedSample]) -> ContractReport {
+        let mut report = ContractReport {
+            consumers: self.contracts.len(),
+            ..Default::default()
+        };
+
+        for contract in &self.contracts {
+            for expectation in &contract.expectations {
+                report.expectations_checked += 1;
+                let violation = |field: Option<&str>, problem: String| ContractViolation {
+                    consumer: contract.consumer.clone(),
+                    topic: expectation.topic.clone(),
+                    event_type: expectation.event_type.clone(),
+                    field: field.map(str::to_string),
+                    problem,
+                };
+
+                let matching: Vec<_> = samples
+                    .iter()
+                    .filter(|s| s.topic == expectation.topic && s.event_type == expectation.event_type)
+                    .collect();
+                if matching.is_empty() {
+                    report.violations.push(violation(None, "event type is not published on this topic".to_string()));
+                    continue;
+                }
+
+                for (field, spec) in &expectation.fields {
+                    // Specs were validated when the contract was loaded
+                    let Ok(spec) = FieldSpec::parse(spec) else { continue };
+                    if let Some(problem) = matching.iter().find_map(|sample| check_field(&sample.payload, field, spec)) {
+                        report.violations.push(violation(Some(field), problem));
+                    }
+                }
+            }
+        }
+        report
+    }
+}
+
+/// Outcome of verifying all contracts
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ContractReport {
+    pub consumers: usize,
+    pub expectations_checked: usize,
+    pub violations: Vec<ContractViolation>,
+}
+
+impl ContractReport {
+    pub fn is_compatible(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ContractViolation {
+    pub consumer: String,
+    pub topic: String,
+    pub event_type: String,
+    pub field: Option<String>,
+    pub problem: String,
+}
+
+/// Why `payload` does not satisfy `spec` at `path`, if it doesn't
+fn check_field(payload: &Value, path: &str, spec: FieldSpec) -> Option<String> {
+    let mut values = vec![payload];
+    for segment in path.split('.') {
+        let (name, each) = match segment.strip_suffix("[]") {
+            Some(name) => (name, true),
+            None => (segment, false),
+        };
+        let mut next = Vec::new();
+        for value in values {
+            let Some(field) = value.get(name) else {
+                return Some(format!("missing '{}'", name));
+            };
+            if !each {
+                next.push(field);
+                continue;
+            }
+            match field {
+                Value::Array(elements) => next.extend(elements),
+                Value::Null if spec.nullable => {}
+                other => return Some(format!("'{}' is {}, expected an array", name, json_type(other))),
+            }
+        }
+        values = next;
+    }
+
+    values.into_iter().find_map(|value| match value {
+        Value::Null if spec.nullable => None,
+        value if spec.field_type.matches(value) => None,
+        value => Some(format!("is {}, expected {:?}", json_type(value), spec.field_type).to_lowercase()),
+    })
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+// ============================================================================
+// Published Event Samples
+// ============================================================================
+
+/// One event as it appears on its topic
+#[derive(Debug, Clone)]
+pub struct PublishedSample {
+    pub topic: String,
+    pub event_type: String,
+    pub payload: Value,
+}
+
+impl PublishedSample {
+    fn new<E: Serialize>(topic: &str, event_type: &str, event: &E) -> Result<Self> {
+        Ok(Self {
+            topic: topic.to_string(),
+            event_type: event_type.to_string(),
+            payload: serde_json::from_str(&serialize_event(event)?)?,
+        })
+    }
+}
+
+/// A representative payload of every event we publish; events with optional
+/// fields appear with them set and unset
+pub fn published_samples() -> Result<Vec<PublishedSample>> {
+    let id = Uuid::from_u128(0x6f1c2a5e_0000_4000_8000_000000000001);
+    let at = Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap();
+    let items = vec![OrderItem { product_id: id, quantity: 2 }];
+    let address = Address {
+        street: "1 Main St".to_string(),
+        city: "Springfield".to_string(),
+        state: "IL".to_string(),
+        postal_code: "62701".to_string(),
+        country: "US".to_string(),
+    };
+    let email = Email::new("jane@example.com");
+    let phone = PhoneNumber::new("+15555550100");
+
+    let orders = [
+        OrderEvent::Created(OrderCreated { customer_id: id, items: items.clone() }),
+        OrderEvent::ItemsUpdated(OrderItemsUpdated { items: items.clone(), reason: Some("customer request".to_string()) }),
+        OrderEvent::ItemsUpdated(OrderItemsUpdated { items, reason: None }),
+        OrderEvent::Confirmed(OrderConfirmed { confirmed_at: at }),
+        OrderEvent::Shipped(OrderShipped { tracking_number: "1Z999".to_string(), carrier: "UPS".to_string(), shipped_at: at }),
+        OrderEvent::Delivered(OrderDelivered { delivered_at: at, signature: Some("J. Doe".to_string()) }),
+        OrderEvent::Delivered(OrderDelivered { delivered_at: at, signature: None }),
+        OrderEvent::Cancelled(OrderCancelled { reason: Some("out of stock".to_string()), cancelled_by: Some(id) }),
+        OrderEvent::Cancelled(OrderCancelled { reason: None, cancelled_by: None }),
+        OrderEvent::Priced(OrderPriced {
+            totals: OrderTotals { currency: "USD".to_string(), subtotal: 3998, tax: 290, shipping: 500, discount: 0, total: 4788 },
+            priced_at: at,
+        }),
+    ];
+
+    let customers = [
+        CustomerEvent::Registered(CustomerRegistered {
+            email: email.clone(),
+            first_name: "Jane".to_string(),
+            last_name: "Doe".to_string(),
+            phone: Some(phone.clone()),
+        }),
+        CustomerEvent::Registered(CustomerRegistered {
+            email: email.clone(),
+            first_name: "Jane".to_string(),
+            last_name: "Doe".to_string(),
+            phone: None,
+        }),
+        CustomerEvent::ProfileUpdated(CustomerProfileUpdated {
+            first_name: Some("Janet".to_string()),
+            last_name: Some("Roe".to_string()),
+            phone: Some(phone.clone()),
+        }),
+        CustomerEvent::ProfileUpdated(CustomerProfileUpdated { first_name: None, last_name: None, phone: None }),
+        CustomerEvent::EmailChanged(CustomerEmailChanged { old_email: email.clone(), new_email: Email::new("jane.doe@example.com") }),
+        CustomerEvent::PhoneChanged(CustomerPhoneChanged { old_phone: Some(phone.clone()), new_phone: phone.clone() }),
+        CustomerEvent::PhoneChanged(CustomerPhoneChanged { old_phone: None, new_phone: phone }),
+        CustomerEvent::AddressAdded(CustomerAddressAdded { address_id: id, address: address.clone(), is_default: true }),
+        CustomerEvent::AddressUpdated(CustomerAddressUpdated { address_id: id, address }),
+        CustomerEvent::AddressRemoved(CustomerAddressRemoved { address_id: id }),
+        CustomerEvent::PaymentMethodAdded(CustomerPaymentMethodAdded {
+            payment_method: PaymentMethod {
+                id,
+                method_type: PaymentMethodType::CreditCard,
+                last_four: "4242".to_string(),
+                is_default: true,
+            },
+        }),
+        CustomerEvent::PaymentMethodRemoved(CustomerPaymentMethodRemoved { payment_method_id: id }),
+        CustomerEvent::TierUpgraded(CustomerTierUpgraded { old_tier: CustomerTier::Bronze, new_tier: CustomerTier::Silver }),
+        CustomerEvent::Suspended(CustomerSuspended { reason: "chargeback".to_string() }),
+        CustomerEvent::Reactivated(CustomerReactivated { notes: Some("resolved".to_string()) }),
+        CustomerEvent::Reactivated(CustomerReactivated { notes: None }),
+        CustomerEvent::Deactivated(CustomerDeactivated { reason: "closed account".to_string() }),
+        CustomerEvent::Forgotten(CustomerForgotten {}),
+    ];
+
+    let mut samples = Vec::new();
+    for event in &orders {
+        samples.push(PublishedSample::new("order-events", event.event_type_name(), event)?);
+    }
+    for event in &customers {
+        samples.push(PublishedSample::new("customer-events", event.event_type_name(), event)?);
+    }
+    Ok(samples)
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registered_contracts_hold() {
+        let contracts = ContractSet::load_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/contracts")).unwrap();
+        let samples = published_samples().unwrap();
+        let report = contracts.verify(&samples);
+
+        // A new event needs a sample, or consumers of it are never checked
+        for event_type in OrderEvent::EVENT_TYPES.iter().chain(CustomerEvent::EVENT_TYPES) {
+            assert!(samples.iter().any(|s| s.event_type == *event_type), "No published sample of {}", event_type);
+        }
+
+        assert!(report.consumers > 0);
+        assert!(report.is_compatible(), "Breaking change for downstream consumers: {:#?}", report.violations);
+    }
+
+    #[test]
+    fn test_breaking_changes_are_reported() {
+        let contract = ConsumerContract::from_json(
+            r#"{
+                "consumer": "shipping-service",
+                "expectations": [
+                    { "topic": "order-events", "event_type": "OrderShipped",
+                      "fields": { "data.carrier": "string", "data.weight_kg": "number" } },
+                    { "topic": "order-events", "event_type": "OrderDelivered",
+                      "fields": { "data.signature": "string" } },
+                    { "topic": "order-events", "event_type": "OrderCreated",
+                      "fields": { "data.items[].quantity": "string", "data.items[].product_id": "string" } },
+                    { "topic": "order-events", "event_type": "OrderReturned", "fields": {} }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let report = ContractSet { contracts: vec![contract] }.verify(&published_samples().unwrap());
+
+        let problems: Vec<_> = report
+            .violations
+            .iter()
+            .map(|v| (v.event_type.as_str(), v.field.as_deref(), v.problem.as_str()))
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                ("OrderShipped", Some("data.weight_kg"), "missing 'weight_kg'"),
+                // Null in the sample without signature; "string?" would accept it
+                ("OrderDelivered", Some("data.signature"), "is null, expected string"),
+                ("OrderCreated", Some("data.items[].quantity"), "is integer, expected string"),
+                ("OrderReturned", None, "event type is not published on this topic"),
+            ]
+        );
+
+        assert!(ConsumerContract::from_json(r#"{ "consumer": "x", "expectations": [
+            { "topic": "t", "event_type": "e", "fields": { "a": "uuid" } }] }"#).is_err());
+    }
+}
@@ -0,0 +1,255 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use anyhow::{Result, bail};
+
+// ============================================================================
+// Dual-Write Guard - Enforces the Outbox Pattern at Publish Time
+// ============================================================================
+//
+// Domain events must reach Redpanda through the outbox:
+//
+//   Command → [event_store + outbox] → CDC → RedpandaClient
+//
+// Publishing an event directly (bypassing the outbox) is a dual write: the
+// broker may see an event the event store never committed, or vice versa.
+//
+// The guard classifies each publish by its origin. Publishers pass it on
+// from their callers (`EventPublisher::publish_with_origin`, callable with
+// an origin from inside the crate only): outbox rows -
+// relayed by CDC, backfilled after a gap or replayed from the DLQ - are
+// OutboxCdc; copies of relayed events sent to routing-rule topics are
+// RoutedCopy (allowed, but they do not make their topic event-typed);
+// everything else (state transfer, table relays, direct
+// producers) is "direct". Direct publishes to event-typed topics are warned
+// about or denied, depending on the configured policy.
+//
+// A topic is considered event-typed when it was explicitly protected, or
+// once the CDC pipeline has published to it at least once. The service
+// takes policy and protected topics from [redpanda] dual_write_policy and
+// protected_topics; by default the topics the relay publishes the domain
+// events to (their event types) are protected from the start.
+//
+// ============================================================================
+
+/// What to do when a direct publish targets an event-typed topic
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DualWritePolicy {
+    /// Do not track direct publishes
+    Allow,
+    /// Log a warning and count the violation, but publish anyway
+    #[default]
+    Warn,
+    /// Reject the publish with an error
+    Deny,
+}
+
+impl std::str::FromStr for DualWritePolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "allow" => Ok(DualWritePolicy::Allow),
+            "warn" => Ok(DualWritePolicy::Warn),
+            "deny" => Ok(DualWritePolicy::Deny),
+            other => bail!("Unknown dual-write policy '{}' (allow, warn or deny)", other),
+        }
+    }
+}
+
+/// Where a publish originated
+///
+/// Only publishes of outbox rows are `OutboxCdc`; this is the marker
+/// distinguishing outbox traffic from direct producers. Re-exported to the
+/// crate only: code outside it cannot name the type, so it cannot claim
+/// the outbox origin and every publish of its own is direct.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublishOrigin {
+    OutboxCdc,
+    /// Copy of a relayed outbox event for a routing rule's topic
+    RoutedCopy,
+    Direct,
+}
+
+impl PublishOrigin {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PublishOrigin::OutboxCdc => "outbox_cdc",
+            PublishOrigin::RoutedCopy => "routed_copy",
+            PublishOrigin::Direct => "direct",
+        }
+    }
+}
+
+pub struct DualWriteGuard {
+    policy: DualWritePolicy,
+    protected_topics: Mutex<HashSet<String>>,
+    violations: AtomicU64,
+}
+
+impl DualWriteGuard {
+    pub fn new(policy: DualWritePolicy) -> Self {
+        Self {
+            policy,
+            protected_topics: Mutex::new(HashSet::new()),
+            violations: AtomicU64::new(0),
+        }
+    }
+
+    /// Mark topics as event-typed up front (before CDC has published to them)
+    pub fn protect_topics<I, S>(self, topics: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        {
+            let mut protected = self.protected_topics.lock().unwrap();
+            protected.extend(topics.into_iter().map(Into::into));
+        }
+        self
+    }
+
+    pub fn policy(&self) -> DualWritePolicy {
+        self.policy
+    }
+
+    /// Number of direct publishes to event-typed topics observed so far
+    pub fn violations(&self) -> u64 {
+        self.violations.load(Ordering::Relaxed)
+    }
+
+    pub fn is_protected(&self, topic: &str) -> bool {
+        self.protected_topics.lock().unwrap().contains(topic)
+    }
+
+    /// Check a publish before it is sent
+    ///
+    /// Outbox publishes always pass and teach the guard that the topic
+    /// carries domain events. Routed copies pass without teaching it - a
+    /// routing topic may carry direct traffic too. Direct publishes to
+    /// event-typed topics are handled according to the policy.
+    pub(crate) fn check(&self, topic: &str, origin: PublishOrigin) -> Result<()> {
+        match origin {
+            PublishOrigin::OutboxCdc => {
+                let mut protected = self.protected_topics.lock().unwrap();
+                if !protected.contains(topic) {
+                    protected.insert(topic.to_string());
+                }
+                Ok(())
+            }
+            PublishOrigin::RoutedCopy => Ok(()),
+            PublishOrigin::Direct => {
+                if self.policy == DualWritePolicy::Allow || !self.is_protected(topic) {
+                    return Ok(());
+                }
+
+                self.violations.fetch_add(1, Ordering::Relaxed);
+
+                match self.policy {
+                    DualWritePolicy::Deny => {
+                        tracing::error!(
+                            topic = %topic,
+                            "🚫 Direct publish to event topic denied - events must go through the outbox"
+                        );
+                        bail!(
+                            "Direct publish to event topic '{}' denied: use the outbox (EventStore::append_events) instead",
+                            topic
+                        );
+                    }
+                    _ => {
+                        tracing::warn!(
+                            topic = %topic,
+                            "⚠️  Direct publish to event topic bypasses the outbox (dual write)"
+                        );
+                        Ok(())
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Default for DualWriteGuard {
+    fn default() -> Self {
+        Self::new(DualWritePolicy::Warn)
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outbox_publish_always_allowed() {
+        let guard = DualWriteGuard::new(DualWritePolicy::Deny)
+            .protect_topics(["order-events"]);
+
+        assert!(guard.check("order-events", PublishOrigin::OutboxCdc).is_ok());
+        assert_eq!(guard.violations(), 0);
+    }
+
+    #[test]
+    fn test_direct_publish_to_unprotected_topic_allowed() {
+        let guard = DualWriteGuard::new(DualWritePolicy::Deny);
+
+        assert!(guard.check("notifications", PublishOrigin::Direct).is_ok());
+        assert_eq!(guard.violations(), 0);
+    }
+
+    #[test]
+    fn test_direct_publish_denied_for_protected_topic() {
+        let guard = DualWriteGuard::new(DualWritePolicy::Deny)
+            .protect_topics(["order-events"]);
+
+        assert!(guard.check("order-events", PublishOrigin::Direct).is_err());
+        assert_eq!(guard.violations(), 1);
+    }
+
+    #[test]
+    fn test_direct_publish_warned_but_allowed() {
+        let guard = DualWriteGuard::new(DualWritePolicy::Warn)
+            .protect_topics(["order-events"]);
+
+        assert!(guard.check("order-events", PublishOrigin::Direct).is_ok());
+        assert_eq!(guard.violations(), 1);
+    }
+
+    #[test]
+    fn test_outbox_topics_are_learned() {
+        let guard = DualWriteGuard::new(DualWritePolicy::Deny);
+
+        assert!(guard.check("OrderCreated", PublishOrigin::Direct).is_ok());
+        guard.check("OrderCreated", PublishOrigin::OutboxCdc).unwrap();
+
+        assert!(guard.is_protected("OrderCreated"));
+        assert!(guard.check("OrderCreated", PublishOrigin::Direct).is_err());
+    }
+
+    #[test]
+    fn test_routed_copies_are_not_learned() {
+        let guard = DualWriteGuard::new(DualWritePolicy::Deny).protect_topics(["order-events"]);
+
+        // Routing topics keep accepting their own direct producers
+        guard.check("fraud-review", PublishOrigin::RoutedCopy).unwrap();
+        assert!(!guard.is_protected("fraud-review"));
+        assert!(guard.check("fraud-review", PublishOrigin::Direct).is_ok());
+
+        // Copies may still go to a protected topic
+        assert!(guard.check("order-events", PublishOrigin::RoutedCopy).is_ok());
+        assert_eq!(guard.violations(), 0);
+    }
+
+    #[test]
+    fn test_allow_policy_never_counts() {
+        let guard = DualWriteGuard::new(DualWritePolicy::Allow)
+            .protect_topics(["order-events"]);
+
+        assert!(guard.check("order-events", PublishOrigin::Direct).is_ok());
+        assert_eq!(guard.violations(), 0);
+    }
+}
@@ -0,0 +1,184 @@
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::region::REGION_HEADER;
+
+// ============================================================================
+// Envelope Headers - Event Metadata on Published Messages
+// ============================================================================
+//
+// The CDC relay publishes the serialized domain event as the message value,
+// the same JSON stored in event_store.event_data. Everything else a consumer
+// needs to rebuild the EventEnvelope (id, aggregate, sequence, version,
+// correlation) travels as Kafka headers, so the payload format stays
+// unchanged for existing consumers.
+//
+// With PayloadFormat::Envelope ([cdc] payload_format = "envelope") the value
+// is the whole EventEnvelope as JSON instead - for consumers that cannot
+// read headers. The headers are set either way, plus `payload-format:
+// envelope` so RedpandaConsumer knows how to decode the value.
+//
+// All headers are optional on read: messages relayed before they existed
+// (or published by other producers) carry only the region header, if any.
+//
+// ============================================================================
+
+pub const EVENT_ID_HEADER: &str = "event-id";
+pub const AGGREGATE_ID_HEADER: &str = "aggregate-id";
+pub const SEQUENCE_NUMBER_HEADER: &str = "sequence-number";
+pub const EVENT_TYPE_HEADER: &str = "event-type";
+pub const AGGREGATE_EVENT_TYPE_HEADER: &str = "aggregate-event-type";
+pub const CORRELATION_ID_HEADER: &str = "correlation-id";
+pub const CAUSATION_ID_HEADER: &str = "causation-id";
+pub const EVENT_VERSION_HEADER: &str = "event-version";
+pub const PAYLOAD_FORMAT_HEADER: &str = "payload-format";
+
+/// What the value of a relayed message holds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PayloadFormat {
+    /// The domain event JSON (event_store.event_data)
+    #[default]
+    Event,
+    /// The full EventEnvelope JSON, event under `event_data`
+    Envelope,
+}
+
+impl PayloadFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PayloadFormat::Event => "event",
+            PayloadFormat::Envelope => "envelope",
+        }
+    }
+}
+
+impl std::str::FromStr for PayloadFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "event" => Ok(PayloadFormat::Event),
+            "envelope" => Ok(PayloadFormat::Envelope),
+            other => anyhow::bail!("Unknown payload format '{}' (event or envelope)", other),
+        }
+    }
+}
+
+/// Envelope metadata carried in message headers
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnvelopeHeaders {
+    pub event_id: Option<Uuid>,
+    pub aggregate_id: Option<Uuid>,
+    pub sequence_number: Option<i64>,
+    pub event_type: Option<String>,
+    /// Type the payload decodes into, e.g. "OrderEvent" (outbox aggregate_event_type)
+    pub aggregate_event_type: Option<String>,
+    pub correlation_id: Option<Uuid>,
+    pub causation_id: Option<Uuid>,
+    pub event_version: Option<i32>,
+    pub origin_region: Option<String>,
+    /// None for the default (event) format
+    pub payload_format: Option<PayloadFormat>,
+}
+
+impl EnvelopeHeaders {
+    /// Header name/value pairs for the fields that are set
+    pub fn to_pairs(&self) -> Vec<(&'static str, String)> {
+        let uuids = [
+            (EVENT_ID_HEADER, self.event_id),
+            (AGGREGATE_ID_HEADER, self.aggregate_id),
+            (CORRELATION_ID_HEADER, self.correlation_id),
+            (CAUSATION_ID_HEADER, self.causation_id),
+        ];
+
+        let mut pairs: Vec<(&'static str, String)> = uuids
+            .into_iter()
+            .filter_map(|(name, value)| value.map(|v| (name, v.to_string())))
+            .collect();
+        if let Some(sequence) = self.sequence_number {
+            pairs.push((SEQUENCE_NUMBER_HEADER, sequence.to_string()));
+        }
+        if let Some(version) = self.event_version {
+            pairs.push((EVENT_VERSION_HEADER, version.to_string()));
+        }
+        if let Some(ref event_type) = self.event_type {
+            pairs.push((EVENT_TYPE_HEADER, event_type.clone()));
+        }
+        if let Some(ref aggregate_event_type) = self.aggregate_event_type {
+            pairs.push((AGGREGATE_EVENT_TYPE_HEADER, aggregate_event_type.clone()));
+        }
+        if let Some(ref region) = self.origin_region {
+            pairs.push((REGION_HEADER, region.clone()));
+        }
+        if let Some(format) = self.payload_format {
+            pairs.push((PAYLOAD_FORMAT_HEADER, format.as_str().to_string()));
+        }
+        pairs
+    }
+
+    /// Read known headers; unknown names and unparsable values are ignored
+    pub fn from_pairs<'a>(pairs: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let mut headers = Self::default();
+        for (name, value) in pairs {
+            match name {
+                EVENT_ID_HEADER => headers.event_id = value.parse().ok(),
+                AGGREGATE_ID_HEADER => headers.aggregate_id = value.parse().ok(),
+                SEQUENCE_NUMBER_HEADER => headers.sequence_number = value.parse().ok(),
+                EVENT_TYPE_HEADER => headers.event_type = Some(value.to_string()),
+                AGGREGATE_EVENT_TYPE_HEADER => headers.aggregate_event_type = Some(value.to_string()),
+                CORRELATION_ID_HEADER => headers.correlation_id = value.parse().ok(),
+                CAUSATION_ID_HEADER => headers.causation_id = value.parse().ok(),
+                EVENT_VERSION_HEADER => headers.event_version = value.parse().ok(),
+                PAYLOAD_FORMAT_HEADER => headers.payload_format = value.parse().ok(),
+                REGION_HEADER => headers.origin_region = Some(value.to_string()),
+                _ => {}
+            }
+        }
+        headers
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_headers_round_trip() {
+        let headers = EnvelopeHeaders {
+            event_id: Some(Uuid::new_v4()),
+            aggregate_id: Some(Uuid::new_v4()),
+            sequence_number: Some(7),
+            event_type: Some("OrderShipped".to_string()),
+            aggregate_event_type: Some("OrderEvent".to_string()),
+            correlation_id: Some(Uuid::new_v4()),
+            causation_id: None,
+            event_version: Some(2),
+            origin_region: Some("eu-west".to_string()),
+            payload_format: Some(PayloadFormat::Envelope),
+        };
+
+        let pairs = headers.to_pairs();
+        assert_eq!(pairs.len(), 9);
+        assert!(pairs.contains(&(EVENT_VERSION_HEADER, "2".to_string())));
+        assert!(pairs.contains(&(REGION_HEADER, "eu-west".to_string())));
+
+        let parsed = EnvelopeHeaders::from_pairs(pairs.iter().map(|(k, v)| (*k, v.as_str())));
+        assert_eq!(parsed, headers);
+    }
+
+    #[test]
+    fn test_unknown_and_malformed_headers_are_ignored() {
+        let parsed = EnvelopeHeaders::from_pairs([
+            ("traceparent", "00-abc-01"),
+            (SEQUENCE_NUMBER_HEADER, "seven"),
+            (AGGREGATE_ID_HEADER, "not-a-uuid"),
+            (PAYLOAD_FORMAT_HEADER, "avro"),
+        ]);
+        assert_eq!(parsed, EnvelopeHeaders::default());
+    }
+}
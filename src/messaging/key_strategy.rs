@@ -0,0 +1,170 @@
+use std::str::FromStr;
+use anyhow::{Result, bail};
+use serde::Deserialize;
+use uuid::Uuid;
+
+// ============================================================================
+// Key Strategy - Which Outbox Field Becomes the Kafka Message Key
+// ============================================================================
+//
+// Kafka only orders messages within a partition, and the partition is
+// chosen from the key. Keying by event_id spreads one aggregate's events
+// over all partitions, so consumers can see OrderShipped before
+// OrderCreated. Keying by aggregate_id keeps every event of an aggregate on
+// one partition, in the order they were appended.
+//
+// Other strategies trade that guarantee for another grouping:
+//
+//   correlation_id  all events of one workflow (across aggregates) in order
+//   event_type      one partition per event type, ordered per type
+//   partition_key   a key chosen by the writer (the outbox column), "custom"
+//   event_id        maximum spread, no ordering at all
+//
+// Keys that are missing on an event fall back to aggregate_id. Set with
+// `[cdc] key_strategy` / CDC_KEY_STRATEGY; gap backfills use the same key.
+//
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyStrategy {
+    /// Per-aggregate ordering (default)
+    #[default]
+    AggregateId,
+    /// The outbox row's partition_key column, falling back to aggregate_id
+    #[serde(alias = "custom")]
+    PartitionKey,
+    /// Per-workflow ordering across aggregates, falling back to aggregate_id
+    CorrelationId,
+    /// Per-type ordering; hot types make hot partitions
+    EventType,
+    /// Unique per event - maximum spread, no ordering guarantee
+    EventId,
+}
+
+/// What a message key can be taken from
+#[derive(Debug, Clone, Copy)]
+pub struct KeyFields<'a> {
+    pub event_id: Uuid,
+    pub aggregate_id: Uuid,
+    pub correlation_id: Option<Uuid>,
+    pub event_type: &'a str,
+    pub partition_key: Option<&'a str>,
+}
+
+impl KeyStrategy {
+    /// Message key for an outbox event
+    pub fn key_for(&self, fields: &KeyFields<'_>) -> String {
+        let aggregate_key = || fields.aggregate_id.to_string();
+        match self {
+            KeyStrategy::AggregateId => aggregate_key(),
+            KeyStrategy::PartitionKey => fields
+                .partition_key
+                .filter(|k| !k.is_empty())
+                .map(|k| k.to_string())
+                .unwrap_or_else(aggregate_key),
+            KeyStrategy::CorrelationId => fields
+                .correlation_id
+                .filter(|id| !id.is_nil())
+                .map(|id| id.to_string())
+                .unwrap_or_else(aggregate_key),
+            KeyStrategy::EventType if !fields.event_type.is_empty() => fields.event_type.to_string(),
+            KeyStrategy::EventType => aggregate_key(),
+            KeyStrategy::EventId => fields.event_id.to_string(),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeyStrategy::AggregateId => "aggregate_id",
+            KeyStrategy::PartitionKey => "partition_key",
+            KeyStrategy::CorrelationId => "correlation_id",
+            KeyStrategy::EventType => "event_type",
+            KeyStrategy::EventId => "event_id",
+        }
+    }
+}
+
+impl FromStr for KeyStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        Ok(match value {
+            "aggregate_id" => KeyStrategy::AggregateId,
+            "partition_key" | "custom" => KeyStrategy::PartitionKey,
+            "correlation_id" => KeyStrategy::CorrelationId,
+            "event_type" => KeyStrategy::EventType,
+            "event_id" => KeyStrategy::EventId,
+            other => bail!("Unknown key strategy '{}'", other),
+        })
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messaging::Partitioner;
+
+    fn fields(aggregate_id: Uuid) -> KeyFields<'static> {
+        KeyFields {
+            event_id: Uuid::new_v4(),
+            aggregate_id,
+            correlation_id: None,
+            event_type: "OrderCreated",
+            partition_key: None,
+        }
+    }
+
+    #[test]
+    fn test_aggregate_events_share_key_and_partition() {
+        let aggregate_id = Uuid::new_v4();
+        let strategy = KeyStrategy::default();
+
+        let keys: Vec<String> = (0..5)
+            .map(|_| strategy.key_for(&fields(aggregate_id)))
+            .collect();
+        assert!(keys.iter().all(|k| *k == aggregate_id.to_string()));
+
+        let partitions: Vec<i32> = keys
+            .iter()
+            .map(|k| Partitioner::Murmur2.partition_for(k.as_bytes(), 12))
+            .collect();
+        assert!(partitions.windows(2).all(|w| w[0] == w[1]));
+    }
+
+    #[test]
+    fn test_missing_keys_fall_back_to_aggregate_id() {
+        let aggregate_id = Uuid::new_v4();
+        let base = fields(aggregate_id);
+        let correlation_id = Uuid::new_v4();
+
+        let custom = KeyFields { partition_key: Some("customer-7"), ..base };
+        assert_eq!(KeyStrategy::PartitionKey.key_for(&custom), "customer-7");
+        assert_eq!(KeyStrategy::PartitionKey.key_for(&base), aggregate_id.to_string());
+        assert_eq!(KeyStrategy::PartitionKey.key_for(&KeyFields { partition_key: Some(""), ..base }), aggregate_id.to_string());
+
+        let correlated = KeyFields { correlation_id: Some(correlation_id), ..base };
+        assert_eq!(KeyStrategy::CorrelationId.key_for(&correlated), correlation_id.to_string());
+        assert_eq!(KeyStrategy::CorrelationId.key_for(&base), aggregate_id.to_string());
+        let nil = KeyFields { correlation_id: Some(Uuid::nil()), ..base };
+        assert_eq!(KeyStrategy::CorrelationId.key_for(&nil), aggregate_id.to_string());
+
+        assert_eq!(KeyStrategy::EventType.key_for(&base), "OrderCreated");
+        assert_eq!(KeyStrategy::EventId.key_for(&base), base.event_id.to_string());
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("partition_key".parse::<KeyStrategy>().unwrap(), KeyStrategy::PartitionKey);
+        assert_eq!("custom".parse::<KeyStrategy>().unwrap(), KeyStrategy::PartitionKey);
+        assert_eq!("correlation_id".parse::<KeyStrategy>().unwrap(), KeyStrategy::CorrelationId);
+        assert!("random".parse::<KeyStrategy>().is_err());
+        for strategy in [KeyStrategy::AggregateId, KeyStrategy::EventType, KeyStrategy::EventId] {
+            assert_eq!(strategy.as_str().parse::<KeyStrategy>().unwrap(), strategy);
+        }
+    }
+}
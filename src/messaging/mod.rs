@@ -1,5 +1,36 @@
 // Private module declaration
 mod redpanda;
+mod consumer;
+mod headers;
+mod dual_write;
+mod publisher;
+mod partitioner;
+mod key_strategy;
+mod region;
+mod routing;
+mod contracts;
+mod state_transfer;
+mod publish_batch;
+mod rate_limit;
+mod subscriptions;
+
+// Publisher doubles; public through crate::test_support (feature "test-support")
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
 
 // Re-export for public API
-pub use redpanda::RedpandaClient;
\ No newline at end of file
+pub use redpanda::{RedpandaClient, BrokerInfo};
+pub use consumer::{RedpandaConsumer, CommitStrategy, EventHandler, decode_envelope};
+pub use headers::{EnvelopeHeaders, PayloadFormat};
+pub use dual_write::{DualWriteGuard, DualWritePolicy};
+pub(crate) use dual_write::PublishOrigin;
+pub use publisher::{EventPublisher, PublisherDiagnostics};
+pub use partitioner::Partitioner;
+pub use key_strategy::{KeyFields, KeyStrategy};
+pub use region::{RegionConfig, REGION_HEADER};
+pub use routing::{RoutingRules, RoutingRule, RoutedEvent, RoutingDecision, Condition, ConditionOp};
+pub use contracts::{ContractSet, published_samples};
+pub use state_transfer::StateSnapshotPublisher;
+pub use publish_batch::BatchConfig;
+pub use rate_limit::PublishRateLimit;
+pub use subscriptions::{AggregateSubscription, EventSubscriptions, SubscriptionLagged};
@@ -0,0 +1,208 @@
+use std::str::FromStr;
+use anyhow::{Result, bail};
+
+// ============================================================================
+// Partitioner - Which Partition a Keyed Message Lands In
+// ============================================================================
+//
+// librdkafka's default partitioner (consistent_random, CRC32 of the key) is
+// NOT what the Java client uses (murmur2). If another producer in the
+// ecosystem writes the same keys with a different partitioner, the two
+// disagree on partitions and per-key ordering/co-partitioning is lost.
+//
+// `Partitioner` selects the librdkafka `partitioner` setting. It can also
+// compute the partition itself (`partition_for`), which the client uses in
+// explicit-partition mode so the choice never depends on client defaults.
+//
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Partitioner {
+    /// CRC32 of the key; null keys go to a random partition (librdkafka default)
+    #[default]
+    ConsistentRandom,
+    /// CRC32 of the key; null keys all go to the same partition
+    Consistent,
+    /// Java-client compatible murmur2; null keys go to a random partition
+    Murmur2Random,
+    /// Java-client compatible murmur2; null keys all go to the same partition
+    Murmur2,
+    /// FNV-1a of the key (Sarama/Go compatible); null keys random
+    Fnv1aRandom,
+    /// FNV-1a of the key; null keys all go to the same partition
+    Fnv1a,
+}
+
+impl Partitioner {
+    /// Value for the librdkafka `partitioner` configuration property
+    pub fn as_config_value(&self) -> &'static str {
+        match self {
+            Partitioner::ConsistentRandom => "consistent_random",
+            Partitioner::Consistent => "consistent",
+            Partitioner::Murmur2Random => "murmur2_random",
+            Partitioner::Murmur2 => "murmur2",
+            Partitioner::Fnv1aRandom => "fnv1a_random",
+            Partitioner::Fnv1a => "fnv1a",
+        }
+    }
+
+    /// Partition for a non-empty key, matching librdkafka for this partitioner
+    pub fn partition_for(&self, key: &[u8], partition_count: i32) -> i32 {
+        if partition_count <= 0 {
+            return 0;
+        }
+
+        match self {
+            Partitioner::ConsistentRandom | Partitioner::Consistent => {
+                (crc32(key) % partition_count as u32) as i32
+            }
+            Partitioner::Murmur2Random | Partitioner::Murmur2 => {
+                (murmur2(key) & 0x7fff_ffff) as i32 % partition_count
+            }
+            Partitioner::Fnv1aRandom | Partitioner::Fnv1a => {
+                (fnv1a(key) % partition_count as u32) as i32
+            }
+        }
+    }
+}
+
+impl FromStr for Partitioner {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        Ok(match value {
+            "consistent_random" => Partitioner::ConsistentRandom,
+            "consistent" => Partitioner::Consistent,
+            "murmur2_random" => Partitioner::Murmur2Random,
+            "murmur2" => Partitioner::Murmur2,
+            "fnv1a_random" => Partitioner::Fnv1aRandom,
+            "fnv1a" => Partitioner::Fnv1a,
+            other => bail!("Unknown partitioner '{}'", other),
+        })
+    }
+}
+
+/// Kafka's murmur2 (org.apache.kafka.common.utils.Utils#murmur2)
+pub fn murmur2(data: &[u8]) -> u32 {
+    const SEED: u32 = 0x9747_b28c;
+    const M: u32 = 0x5bd1_e995;
+    const R: u32 = 24;
+
+    let length = data.len();
+    let mut h: u32 = SEED ^ length as u32;
+
+    let chunks = data.chunks_exact(4);
+    let tail = chunks.remainder();
+    for chunk in chunks {
+        let mut k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        k = k.wrapping_mul(M);
+        k ^= k >> R;
+        k = k.wrapping_mul(M);
+        h = h.wrapping_mul(M);
+        h ^= k;
+    }
+
+    match tail.len() {
+        3 => {
+            h ^= (tail[2] as u32) << 16;
+            h ^= (tail[1] as u32) << 8;
+            h ^= tail[0] as u32;
+            h = h.wrapping_mul(M);
+        }
+        2 => {
+            h ^= (tail[1] as u32) << 8;
+            h ^= tail[0] as u32;
+            h = h.wrapping_mul(M);
+        }
+        1 => {
+            h ^= tail[0] as u32;
+            h = h.wrapping_mul(M);
+        }
+        _ => {}
+    }
+
+    h ^= h >> 13;
+    h = h.wrapping_mul(M);
+    h ^= h >> 15;
+    h
+}
+
+/// CRC-32 (IEEE), as used by librdkafka's consistent partitioners
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc: u32 = 0xffff_ffff;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// 32-bit FNV-1a
+pub fn fnv1a(data: &[u8]) -> u32 {
+    let mut hash: u32 = 0x811c_9dc5;
+    for byte in data {
+        hash ^= *byte as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    hash
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_murmur2_matches_java_client() {
+        // Vectors from Kafka's UtilsTest#testMurmur2
+        let cases: [(&str, i32); 6] = [
+            ("21", -973932308),
+            ("foobar", -790332482),
+            ("a-little-bit-long-string", -985981536),
+            ("a-little-bit-longer-string", -1486304829),
+            ("lkjh234lh9fiuh90y23oiuhsafujhadof229phr9h19h89h8", -58897971),
+            ("abc", 479470107),
+        ];
+
+        for (key, expected) in cases {
+            assert_eq!(murmur2(key.as_bytes()) as i32, expected, "key {}", key);
+        }
+    }
+
+    #[test]
+    fn test_crc32_known_value() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn test_partition_is_stable_and_in_range() {
+        let key = b"3f2504e0-4f89-11d3-9a0c-0305e82c3301";
+
+        for partitioner in [Partitioner::Consistent, Partitioner::Murmur2, Partitioner::Fnv1a] {
+            let first = partitioner.partition_for(key, 12);
+            assert!((0..12).contains(&first));
+            assert_eq!(partitioner.partition_for(key, 12), first);
+        }
+    }
+
+    #[test]
+    fn test_config_value_round_trip() {
+        for partitioner in [
+            Partitioner::ConsistentRandom,
+            Partitioner::Consistent,
+            Partitioner::Murmur2Random,
+            Partitioner::Murmur2,
+            Partitioner::Fnv1aRandom,
+            Partitioner::Fnv1a,
+        ] {
+            assert_eq!(partitioner.as_config_value().parse::<Partitioner>().unwrap(), partitioner);
+        }
+        assert!("round_robin".parse::<Partitioner>().is_err());
+    }
+}
@@ -0,0 +1,238 @@
+use anyhow::{anyhow, Result};
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::metrics::MetricsHandle;
+
+// ============================================================================
+// Publish Batching - Many Records per Flush
+// ============================================================================
+//
+// Sending one record and waiting for its delivery before the next caps the
+// relay at one broker round trip per event. The PublishBatcher collects
+// records from all publishing tasks and flushes them together:
+//
+//   - a batch is flushed once it holds `max_records`, or `linger_ms` after
+//     its first record arrived, whichever comes first
+//   - a flush hands the whole batch to the producer at once and waits for
+//     all deliveries; librdkafka packs them into few produce requests
+//   - every record gets its own delivery result: a failed record fails only
+//     its own publish (which the CDC relay retries and dead-letters), the
+//     rest of the batch is published as usual
+//
+// In transactional mode (RedpandaClient::with_transactions) a batch is one
+// producer transaction instead: committed as a whole, or aborted and failed
+// as a whole (transaction_results). A publish returns only after the
+// commit, so the CDC checkpoint - advanced once a CDC batch's publishes
+// returned - never covers uncommitted events. Consumers reading
+// read_committed see every event once, even when the relay retries.
+//
+// Batch sizes and flush latencies are recorded as
+// redpanda_publish_batch_size and redpanda_publish_batch_flush_seconds.
+//
+// ============================================================================
+
+/// When batches are flushed ([redpanda] batch_max_records / batch_linger_ms)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchConfig {
+    /// Flush once this many records are waiting; 1 disables batching
+    pub max_records: usize,
+    /// Flush this long after the first record of a batch at the latest
+    pub linger_ms: u64,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self { max_records: 500, linger_ms: 50 }
+    }
+}
+
+impl BatchConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.max_records > 1
+    }
+
+    pub fn linger(&self) -> Duration {
+        Duration::from_millis(self.linger_ms)
+    }
+}
+
+/// A record waiting for its batch
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BatchRecord {
+    pub topic: String,
+    pub key: String,
+    pub payload: String,
+    pub headers: Vec<(String, String)>,
+    pub partition: Option<i32>,
+}
+
+struct Pending {
+    record: BatchRecord,
+    delivered: oneshot::Sender<Result<()>>,
+}
+
+/// Collects records into batches for a flush function
+pub(crate) struct PublishBatcher {
+    queue: mpsc::Sender<Pending>,
+}
+
+impl PublishBatcher {
+    /// Start batching; `flush` returns one result per record, in order
+    ///
+    /// The batching task stops once the batcher is dropped.
+    pub fn spawn<F, Fut>(config: BatchConfig, metrics: MetricsHandle, flush: F) -> Self
+    where
+        F: Fn(Vec<BatchRecord>) -> Fut + Send + 'static,
+        Fut: Future<Output = Vec<Result<()>>> + Send,
+    {
+        let max_records = config.max_records.max(1);
+        // Room for a second batch while the first is being flushed
+        let (queue, mut receiver) = mpsc::channel::<Pending>(max_records * 2);
+
+        tokio::spawn(async move {
+            while let Some(first) = receiver.recv().await {
+                let mut batch = vec![first];
+                let linger = tokio::time::Instant::now() + config.linger();
+                while batch.len() < max_records {
+                    match tokio::time::timeout_at(linger, receiver.recv()).await {
+                        Ok(Some(pending)) => batch.push(pending),
+                        Ok(None) | Err(_) => break,
+                    }
+                }
+
+                let size = batch.len();
+                let (records, waiting): (Vec<_>, Vec<_>) =
+                    batch.into_iter().map(|pending| (pending.record, pending.delivered)).unzip();
+                let started = Instant::now();
+                let results = flush(records).await;
+                metrics.record_publish_batch(size, started.elapsed().as_secs_f64());
+
+                let mut results = results.into_iter();
+                for delivered in waiting {
+                    let result = results.next().unwrap_or_else(|| Err(anyhow!("No delivery result for record")));
+                    let _ = delivered.send(result);
+                }
+            }
+        });
+
+        Self { queue }
+    }
+
+    /// Queue `record` and wait until its batch was flushed
+    pub async fn publish(&self, record: BatchRecord) -> Result<()> {
+        let (delivered, delivery) = oneshot::channel();
+        self.queue
+            .send(Pending { record, delivered })
+            .await
+            .map_err(|_| anyhow!("Publish batcher stopped"))?;
+        delivery.await.map_err(|_| anyhow!("Publish batcher stopped"))?
+    }
+}
+
+/// Results of a transactional batch, one per record
+///
+/// Every record fails unless all were delivered and the commit succeeded;
+/// records that were delivered fail with the reason of the abort.
+pub(crate) fn transaction_results(delivered: Vec<Result<()>>, commit: Result<()>) -> Vec<Result<()>> {
+    let Err(abort) = commit else {
+        return delivered;
+    };
+    let reason = format!("{:#}", abort);
+    delivered
+        .into_iter()
+        .map(|result| result.and_then(|()| Err(anyhow!("Publish transaction aborted: {}", reason))))
+        .collect()
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    fn record(key: &str) -> BatchRecord {
+        BatchRecord {
+            topic: "order-events".to_string(),
+            key: key.to_string(),
+            payload: "{}".to_string(),
+            headers: Vec::new(),
+            partition: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_flushes_full_batches_then_after_linger() {
+        let batches = Arc::new(Mutex::new(Vec::new()));
+        let seen = batches.clone();
+        let batcher = Arc::new(PublishBatcher::spawn(
+            BatchConfig { max_records: 3, linger_ms: 20 },
+            MetricsHandle::noop(),
+            move |records: Vec<BatchRecord>| {
+                seen.lock().unwrap().push(records.len());
+                let results = records.iter().map(|_| Ok(())).collect();
+                async move { results }
+            },
+        ));
+
+        let publishes: Vec<_> = (0..7)
+            .map(|n| {
+                let batcher = batcher.clone();
+                tokio::spawn(async move { batcher.publish(record(&n.to_string())).await })
+            })
+            .collect();
+        for publish in publishes {
+            publish.await.unwrap().unwrap();
+        }
+
+        // Two full batches; the seventh record went out after the linger
+        assert_eq!(*batches.lock().unwrap(), vec![3, 3, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_record_fails_only_its_publish() {
+        let batcher = Arc::new(PublishBatcher::spawn(
+            BatchConfig { max_records: 3, linger_ms: 1000 },
+            MetricsHandle::noop(),
+            |records: Vec<BatchRecord>| {
+                let results = records
+                    .iter()
+                    .map(|record| if record.key == "bad" { Err(anyhow!("Message too large")) } else { Ok(()) })
+                    .collect();
+                async move { results }
+            },
+        ));
+
+        let publishes: Vec<_> = ["a", "bad", "b"]
+            .into_iter()
+            .map(|key| {
+                let batcher = batcher.clone();
+                tokio::spawn(async move { (key, batcher.publish(record(key)).await) })
+            })
+            .collect();
+        for publish in publishes {
+            let (key, result) = publish.await.unwrap();
+            assert_eq!(result.is_err(), key == "bad", "{}", key);
+        }
+    }
+
+    #[test]
+    fn test_transaction_fails_every_record_unless_committed() {
+        let committed = transaction_results(vec![Ok(()), Ok(())], Ok(()));
+        assert!(committed.iter().all(Result::is_ok));
+
+        let aborted = transaction_results(
+            vec![Ok(()), Err(anyhow!("Message too large"))],
+            Err(anyhow!("A record of the batch was not delivered")),
+        );
+        let errors: Vec<String> = aborted.into_iter().map(|result| result.unwrap_err().to_string()).collect();
+        assert_eq!(
+            errors,
+            vec!["Publish transaction aborted: A record of the batch was not delivered", "Message too large"]
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use async_trait::async_trait;
+use anyhow::Result;
+
+use crate::event_sourcing::Deadline;
+
+use super::{PublishOrigin, RedpandaClient};
+
+// ============================================================================
+// Event Publisher Abstraction
+// ============================================================================
+//
+// The CDC pipeline only needs one capability from the broker: relay an
+// outbox event to a topic. Depending on this trait instead of the concrete
+// RedpandaClient lets the consumer, retry and DLQ logic run against test
+// doubles (see test_support) without a live broker.
+//
+// `publish` and `publish_with_headers` are direct publishes. Callers
+// relaying outbox rows (the CDC relay, gap backfill, DLQ replay of outbox
+// events) say so with `publish_with_origin(.., PublishOrigin::OutboxCdc)`,
+// which the RedpandaClient hands to its dual-write guard.
+//
+// ============================================================================
+
+/// Publisher state captured when a publish finally fails
+///
+/// Stored with DLQ entries so operators can see whether the broker was
+/// unreachable for the topic (its breaker open) or rejected this particular
+/// message.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PublisherDiagnostics {
+    pub circuit_breaker_state: Option<String>,
+    pub bootstrap_servers: Option<String>,
+    pub partitioner: Option<String>,
+}
+
+/// Sink for events relayed from the outbox
+#[async_trait]
+pub trait EventPublisher: Send + Sync {
+    /// Publish an outbox event to `topic`, keyed by `key`
+    async fn publish(&self, topic: &str, key: &str, payload: &str) -> Result<()>;
+
+    /// Publish with message headers
+    ///
+    /// Publishers without header support drop them and publish as usual.
+    async fn publish_with_headers(&self, topic: &str, key: &str, payload: &str, headers: &[(&str, &str)]) -> Result<()> {
+        let _ = headers;
+        self.publish(topic, key, payload).await
+    }
+
+    /// Publish on behalf of `origin`
+    ///
+    /// Publishers without a dual-write guard ignore the origin. Only the
+    /// crate's relays can name a `PublishOrigin`; other callers publish
+    /// directly with `publish` or `publish_with_headers`.
+    #[doc(hidden)]
+    async fn publish_with_origin(
+        &self,
+        topic: &str,
+        key: &str,
+        payload: &str,
+        headers: &[(&str, &str)],
+        origin: PublishOrigin,
+    ) -> Result<()> {
+        let _ = origin;
+        self.publish_with_headers(topic, key, payload, headers).await
+    }
+
+    /// Publish, cancelling the send if `deadline` passes first
+    ///
+    /// Fails with DeadlineExceeded without publishing when the deadline has
+    /// already passed.
+    async fn publish_within(&self, deadline: &Deadline, topic: &str, key: &str, payload: &str) -> Result<()> {
+        deadline.run("publish", self.publish(topic, key, payload)).await
+    }
+
+    /// Publisher state for a failure report on `topic` (empty by default)
+    async fn diagnostics(&self, topic: &str) -> PublisherDiagnostics {
+        let _ = topic;
+        PublisherDiagnostics::default()
+    }
+}
+
+#[async_trait]
+impl EventPublisher for RedpandaClient {
+    async fn publish(&self, topic: &str, key: &str, payload: &str) -> Result<()> {
+        RedpandaClient::publish_with_origin(self, topic, key, payload, &[], PublishOrigin::Direct).await
+    }
+
+    async fn publish_with_headers(&self, topic: &str, key: &str, payload: &str, headers: &[(&str, &str)]) -> Result<()> {
+        RedpandaClient::publish_with_origin(self, topic, key, payload, headers, PublishOrigin::Direct).await
+    }
+
+    async fn publish_with_origin(
+        &self,
+        topic: &str,
+        key: &str,
+        payload: &str,
+        headers: &[(&str, &str)],
+        origin: PublishOrigin,
+    ) -> Result<()> {
+        RedpandaClient::publish_with_origin(self, topic, key, payload, headers, origin).await
+    }
+
+    async fn diagnostics(&self, topic: &str) -> PublisherDiagnostics {
+        // No metadata request here: the broker is likely unreachable already
+        PublisherDiagnostics {
+            circuit_breaker_state: Some(format!("{:?}", self.get_circuit_breaker_state(topic).await)),
+            bootstrap_servers: Some(self.bootstrap_servers().to_string()),
+            partitioner: Some(self.partitioner().as_config_value().to_string()),
+        }
+    }
+}
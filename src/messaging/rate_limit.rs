@@ -0,0 +1,191 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::metrics::MetricsHandle;
+
+// ============================================================================
+// Publish Rate Limit - Token Buckets in Front of the Producer
+// ============================================================================
+//
+// A bulk DLQ replay, or the CDC relay catching up after downtime, publishes
+// as fast as Redpanda accepts - which can be far more than the downstream
+// cluster (and its consumers) is sized for. With a PublishRateLimit every
+// RedpandaClient publish first takes a token from:
+//
+//   the global bucket      rate_limit_per_sec, all topics together
+//   its topic's bucket     topic_rate_limits, for the topics listed there
+//
+// Each bucket refills at its rate and holds at most `burst` tokens. A
+// publish without a token is not refused but delayed until its token is
+// due - callers (and the CDC reader behind them) simply slow down. Tokens
+// are reserved in arrival order, so waiting publishes cannot starve each
+// other.
+//
+//   redpanda_publish_rate_limited_total{scope}   publishes that had to wait
+//   redpanda_publish_rate{scope}                 publishes per second,
+//                                                measured over the last
+//                                                second with traffic
+//
+// `scope` is "global" or the topic name; only limited topics get a label.
+//
+// ============================================================================
+
+/// Rates publishes are held to ([redpanda] rate_limit_per_sec, ...)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PublishRateLimit {
+    /// Publishes per second across all topics; 0 leaves them unlimited
+    pub per_sec: f64,
+    /// Publishes a bucket allows at once
+    pub burst: u32,
+    /// Publishes per second of single topics
+    pub topics: BTreeMap<String, f64>,
+}
+
+impl PublishRateLimit {
+    pub fn is_enabled(&self) -> bool {
+        self.per_sec > 0.0 || self.topics.values().any(|rate| *rate > 0.0)
+    }
+}
+
+/// A token bucket that lends tokens ahead: a publish arriving at an empty
+/// bucket takes the next token due and waits for it
+#[derive(Debug)]
+struct TokenBucket {
+    per_sec: f64,
+    burst: f64,
+    /// Tokens left (negative when lent ahead) and when they were refilled
+    tokens: Mutex<(f64, Instant)>,
+    /// Publishes counted since the window started
+    window: Mutex<(u64, Instant)>,
+}
+
+impl TokenBucket {
+    fn new(per_sec: f64, burst: u32, now: Instant) -> Self {
+        let burst = f64::from(burst.max(1));
+        Self {
+            per_sec,
+            burst,
+            tokens: Mutex::new((burst, now)),
+            window: Mutex::new((0, now)),
+        }
+    }
+
+    /// Take a token; how long to wait until it is due
+    fn reserve(&self, now: Instant) -> Duration {
+        let mut tokens = self.tokens.lock().unwrap();
+        let (available, refilled_at) = *tokens;
+        let refill = now.saturating_duration_since(refilled_at).as_secs_f64() * self.per_sec;
+        let available = (available + refill).min(self.burst) - 1.0;
+        *tokens = (available, now);
+        if available >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-available / self.per_sec)
+        }
+    }
+
+    /// Count one publish; the rate of the window once a second has passed
+    fn count(&self, now: Instant) -> Option<f64> {
+        let mut window = self.window.lock().unwrap();
+        window.0 += 1;
+        let elapsed = now.saturating_duration_since(window.1);
+        if elapsed < Duration::from_secs(1) {
+            return None;
+        }
+        let rate = window.0 as f64 / elapsed.as_secs_f64();
+        *window = (0, now);
+        Some(rate)
+    }
+}
+
+/// The buckets of a PublishRateLimit
+#[derive(Debug)]
+pub(crate) struct PublishRateLimiter {
+    global: Option<TokenBucket>,
+    topics: BTreeMap<String, TokenBucket>,
+}
+
+impl PublishRateLimiter {
+    pub fn new(limit: &PublishRateLimit) -> Self {
+        let now = Instant::now();
+        Self {
+            global: (limit.per_sec > 0.0).then(|| TokenBucket::new(limit.per_sec, limit.burst, now)),
+            topics: limit
+                .topics
+                .iter()
+                .filter(|(_, rate)| **rate > 0.0)
+                .map(|(topic, rate)| (topic.clone(), TokenBucket::new(*rate, limit.burst, now)))
+                .collect(),
+        }
+    }
+
+    /// Wait until a publish to `topic` is within the limits
+    pub async fn acquire(&self, topic: &str, metrics: &MetricsHandle) {
+        let wait = self.reserve(topic, Instant::now(), metrics);
+        if !wait.is_zero() {
+            tracing::debug!(topic = %topic, wait_ms = wait.as_millis() as u64, "Publish rate limited");
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Take the tokens of one publish to `topic`; the longest wait of its buckets
+    fn reserve(&self, topic: &str, now: Instant, metrics: &MetricsHandle) -> Duration {
+        let buckets = self
+            .global
+            .as_ref()
+            .map(|bucket| ("global", bucket))
+            .into_iter()
+            .chain(self.topics.get_key_value(topic).map(|(topic, bucket)| (topic.as_str(), bucket)));
+
+        let mut wait = Duration::ZERO;
+        for (scope, bucket) in buckets {
+            let bucket_wait = bucket.reserve(now);
+            if !bucket_wait.is_zero() {
+                metrics.record_publish_rate_limited(scope);
+            }
+            if let Some(rate) = bucket.count(now) {
+                metrics.set_publish_rate(scope, rate);
+            }
+            wait = wait.max(bucket_wait);
+        }
+        wait
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_global_and_topic_buckets() {
+        let limit = PublishRateLimit {
+            per_sec: 10.0,
+            burst: 2,
+            topics: BTreeMap::from([("OrderCreated".to_string(), 1.0), ("OrderShipped".to_string(), 0.0)]),
+        };
+        assert!(limit.is_enabled());
+        assert!(!PublishRateLimit::default().is_enabled());
+
+        let limiter = PublishRateLimiter::new(&limit);
+        assert!(!limiter.topics.contains_key("OrderShipped"));
+        let now = Instant::now();
+        let metrics = MetricsHandle::noop();
+
+        // The burst passes, then tokens are lent at 10/s
+        assert_eq!(limiter.reserve("OrderShipped", now, &metrics), Duration::ZERO);
+        assert_eq!(limiter.reserve("OrderShipped", now, &metrics), Duration::ZERO);
+        assert_eq!(limiter.reserve("OrderShipped", now, &metrics), Duration::from_millis(100));
+        assert_eq!(limiter.reserve("OrderShipped", now, &metrics), Duration::from_millis(200));
+
+        // Refilled after a while; OrderCreated is held to 1/s on its own
+        let later = now + Duration::from_secs(1);
+        assert_eq!(limiter.reserve("OrderCreated", later, &metrics), Duration::ZERO);
+        assert_eq!(limiter.reserve("OrderCreated", later, &metrics), Duration::ZERO);
+        assert_eq!(limiter.reserve("OrderCreated", later, &metrics), Duration::from_secs(1));
+    }
+}
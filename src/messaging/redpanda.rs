@@ -1,46 +1,307 @@
 use rdkafka::{
-    producer::{FutureProducer, FutureRecord},
+    admin::{AdminClient, AdminOptions, NewTopic, TopicReplication},
+    client::DefaultClientContext,
+    error::{KafkaResult, RDKafkaErrorCode},
+    producer::{FutureProducer, FutureRecord, Producer},
     config::ClientConfig,
+    message::{Header, OwnedHeaders},
 };
 use anyhow::Result;
-use crate::utils::{CircuitBreaker, CircuitBreakerConfig, CircuitBreakerError};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tracing::Instrument;
+use crate::metrics::MetricsHandle;
+use crate::utils::{CircuitBreaker, CircuitBreakerConfig, CircuitBreakerError, CircuitState};
+use super::dual_write::{DualWriteGuard, PublishOrigin};
+use super::partitioner::Partitioner;
+use super::publish_batch::{transaction_results, BatchConfig, BatchRecord, PublishBatcher};
+use super::rate_limit::{PublishRateLimit, PublishRateLimiter};
+
+/// Bound of the blocking transaction calls (init, commit, abort)
+const TRANSACTION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Brokers the client is connected to, for /info
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BrokerInfo {
+    pub brokers: Vec<String>,
+    /// Broker that answered the metadata request
+    pub metadata_broker: String,
+    /// librdkafka version used by the producer
+    pub client_library: String,
+    /// Not exposed by the Kafka protocol; filled in when known
+    pub broker_version: Option<String>,
+}
 
 pub struct RedpandaClient {
     producer: FutureProducer,
-    circuit_breaker: CircuitBreaker,
+    bootstrap_servers: String,
+    /// Tuning of the per-topic breakers created from now on
+    circuit_breaker_config: CircuitBreakerConfig,
+    /// One breaker per topic, created with its first publish: an
+    /// unavailable partition leader only stops the topics it leads
+    circuit_breakers: Mutex<HashMap<String, CircuitBreaker>>,
+    dual_write_guard: DualWriteGuard,
+    partitioner: Partitioner,
+    explicit_partitioning: bool,
+    partition_counts: Mutex<HashMap<String, i32>>,
+    metrics: MetricsHandle,
+    /// None sends every record on its own
+    batching: Option<BatchConfig>,
+    /// Started with the first publish (needs the runtime)
+    batcher: OnceLock<PublishBatcher>,
+    /// Set in transactional mode: every batch is one producer transaction
+    transactional_id: Option<String>,
+    /// None publishes as fast as the brokers accept
+    rate_limiter: Option<PublishRateLimiter>,
 }
 
 impl RedpandaClient {
     pub fn new(brokers: &str) -> Self {
-        let producer: FutureProducer = ClientConfig::new()
-            .set("bootstrap.servers", brokers)
-            .set("message.timeout.ms", "5000")
+        Self::new_with_partitioner(brokers, Partitioner::default())
+    }
+
+    /// Create a client using `partitioner` for keyed messages
+    ///
+    /// Use `Partitioner::Murmur2Random` to co-partition with Java producers.
+    pub fn new_with_partitioner(brokers: &str, partitioner: Partitioner) -> Self {
+        let producer: FutureProducer = producer_config(brokers, partitioner)
             .create()
             .expect("Failed to create Redpanda producer");
 
-        // Configure circuit breaker for Redpanda
+        // Configure the circuit breakers for Redpanda
         let cb_config = CircuitBreakerConfig {
             failure_threshold: 5,           // Open after 5 failures
             timeout: std::time::Duration::from_secs(30),  // Wait 30s before retry
             success_threshold: 3,           // Need 3 successes to close
+            half_open_max_calls: 3,         // 3 trial publishes at once while half-open
+            ..Default::default()
         };
 
         Self {
             producer,
-            circuit_breaker: CircuitBreaker::new(cb_config),
+            bootstrap_servers: brokers.to_string(),
+            circuit_breaker_config: cb_config,
+            circuit_breakers: Mutex::new(HashMap::new()),
+            dual_write_guard: DualWriteGuard::default(),
+            partitioner,
+            explicit_partitioning: false,
+            partition_counts: Mutex::new(HashMap::new()),
+            metrics: MetricsHandle::noop(),
+            batching: None,
+            batcher: OnceLock::new(),
+            transactional_id: None,
+            rate_limiter: None,
         }
     }
 
+    /// Replace the default tuning of the per-topic circuit breakers
+    pub fn with_circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.circuit_breaker_config = config;
+        self.circuit_breakers.get_mut().unwrap().clear();
+        self
+    }
+
+    /// Compute the partition of outbox publishes ourselves instead of
+    /// leaving it to librdkafka, using the configured partitioner
+    pub fn with_explicit_partitioning(mut self) -> Self {
+        self.explicit_partitioning = true;
+        self
+    }
+
+    pub fn partitioner(&self) -> Partitioner {
+        self.partitioner
+    }
+
+    pub fn bootstrap_servers(&self) -> &str {
+        &self.bootstrap_servers
+    }
+
+    /// Count publishes per topic and origin, and circuit breaker transitions
+    pub fn with_metrics(mut self, metrics: MetricsHandle) -> Self {
+        self.circuit_breakers.get_mut().unwrap().clear();
+        self.metrics = metrics;
+        self
+    }
+
+    /// Flush publishes in batches (see publish_batch.rs) instead of one by one
+    pub fn with_batching(mut self, config: BatchConfig) -> Self {
+        self.batching = config.is_enabled().then_some(config);
+        self
+    }
+
+    /// Publish every batch in a producer transaction (exactly-once mode)
+    ///
+    /// `transactional_id` must be unique per running producer: a new
+    /// producer with the same id fences off the old one. Call before the
+    /// first publish. Without `with_batching`, records are batched with the
+    /// default BatchConfig.
+    pub fn with_transactions(mut self, transactional_id: &str) -> Self {
+        self.producer = producer_config(&self.bootstrap_servers, self.partitioner)
+            .set("transactional.id", transactional_id)
+            .create()
+            .expect("Failed to create transactional Redpanda producer");
+        self.transactional_id = Some(transactional_id.to_string());
+        self
+    }
+
+    pub fn is_transactional(&self) -> bool {
+        self.transactional_id.is_some()
+    }
+
+    /// Hold publishes to the global and per-topic rates (see rate_limit.rs)
+    pub fn with_rate_limit(mut self, limit: PublishRateLimit) -> Self {
+        self.rate_limiter = limit.is_enabled().then(|| PublishRateLimiter::new(&limit));
+        self
+    }
+
+    /// Replace the default dual-write guard (Warn, no pre-protected topics)
+    pub fn with_dual_write_guard(mut self, guard: DualWriteGuard) -> Self {
+        self.dual_write_guard = guard;
+        self
+    }
+
+    /// Publish directly to a topic
+    ///
+    /// Domain events should not be published this way - they belong in the
+    /// outbox. Direct publishes to event topics are checked by the
+    /// dual-write guard.
     pub async fn publish(&self, topic: &str, key: &str, payload: &str) -> Result<()> {
+        self.publish_with_origin(topic, key, payload, &[], PublishOrigin::Direct).await
+    }
+
+    /// Number of partitions of `topic`, cached after the first lookup
+    async fn partition_count(&self, topic: &str) -> Result<i32> {
+        if let Some(count) = self.partition_counts.lock().unwrap().get(topic) {
+            return Ok(*count);
+        }
+
+        // fetch_metadata blocks - keep it off the async workers
+        let producer = self.producer.clone();
+        let topic_name = topic.to_string();
+        let count = tokio::task::spawn_blocking(move || {
+            let metadata = producer
+                .client()
+                .fetch_metadata(Some(&topic_name), std::time::Duration::from_secs(5))?;
+            Ok::<i32, anyhow::Error>(
+                metadata
+                    .topics()
+                    .iter()
+                    .find(|t| t.name() == topic_name)
+                    .map(|t| t.partitions().len() as i32)
+                    .unwrap_or(0),
+            )
+        })
+        .await??;
+
+        if count > 0 {
+            self.partition_counts.lock().unwrap().insert(topic.to_string(), count);
+        }
+        Ok(count)
+    }
+
+    fn batcher(&self) -> Option<&PublishBatcher> {
+        // A transactional producer sends only inside transactions, so every
+        // record goes through a batch then - a real one, or every record
+        // would be its own serialized transaction
+        let config = match (self.batching, self.is_transactional()) {
+            (Some(config), _) => config,
+            (None, true) => BatchConfig::default(),
+            (None, false) => return None,
+        };
+        Some(self.batcher.get_or_init(|| {
+            let producer = self.producer.clone();
+            let transactional = self.is_transactional();
+            let initialized = Arc::new(tokio::sync::OnceCell::new());
+            PublishBatcher::spawn(config, self.metrics.clone(), move |records: Vec<BatchRecord>| {
+                let producer = producer.clone();
+                let initialized = initialized.clone();
+                async move {
+                    if transactional {
+                        send_transaction(&producer, &initialized, &records).await
+                    } else {
+                        send_batch(&producer, &records).await
+                    }
+                }
+            })
+        }))
+    }
+
+    /// Publish in a `redpanda_publish` span; relayed events inherit the
+    /// correlation id from the CDC relay's span around it
+    pub(crate) async fn publish_with_origin(
+        &self,
+        topic: &str,
+        key: &str,
+        payload: &str,
+        headers: &[(&str, &str)],
+        origin: PublishOrigin,
+    ) -> Result<()> {
+        let span = tracing::info_span!("redpanda_publish", topic = %topic, key = %key, origin = origin.as_str());
+        self.send_record(topic, key, payload, headers, origin).instrument(span).await
+    }
+
+    async fn send_record(
+        &self,
+        topic: &str,
+        key: &str,
+        payload: &str,
+        headers: &[(&str, &str)],
+        origin: PublishOrigin,
+    ) -> Result<()> {
+        self.dual_write_guard.check(topic, origin)?;
+        if let Some(ref rate_limiter) = self.rate_limiter {
+            rate_limiter.acquire(topic, &self.metrics).await;
+        }
+
+        let relayed = matches!(origin, PublishOrigin::OutboxCdc | PublishOrigin::RoutedCopy);
+        let partition = if self.explicit_partitioning && relayed && !key.is_empty() {
+            match self.partition_count(topic).await {
+                Ok(count) if count > 0 => Some(self.partitioner.partition_for(key.as_bytes(), count)),
+                Ok(_) => None,
+                Err(e) => {
+                    tracing::warn!(
+                        topic = %topic,
+                        error = %e,
+                        "Could not fetch partition count, falling back to client partitioner"
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
         let topic = topic.to_string();
         let key = key.to_string();
         let payload = payload.to_string();
+        let batch_headers: Vec<(String, String)> =
+            headers.iter().map(|&(name, value)| (name.to_string(), value.to_string())).collect();
+        let headers = headers.iter().fold(OwnedHeaders::new(), |acc, &(name, value)| {
+            acc.insert(Header { key: name, value: Some(value) })
+        });
+
+        // Use the topic's circuit breaker to protect against Redpanda failures
+        let result = self.circuit_breaker(&topic).call(async {
+            if let Some(batcher) = self.batcher() {
+                return batcher
+                    .publish(BatchRecord {
+                        topic: topic.clone(),
+                        key: key.clone(),
+                        payload: payload.clone(),
+                        headers: batch_headers.clone(),
+                        partition,
+                    })
+                    .await;
+            }
 
-        // Use circuit breaker to protect against Redpanda failures
-        let result = self.circuit_breaker.call(async {
-            let record = FutureRecord::to(&topic)
+            let mut record = FutureRecord::to(&topic)
                 .key(&key)
-                .payload(&payload);
+                .payload(&payload)
+                .headers(headers.clone());
+            if let Some(partition) = partition {
+                record = record.partition(partition);
+            }
 
             self.producer
                 .send(record, rdkafka::util::Timeout::After(std::time::Duration::from_secs(5)))
@@ -50,11 +311,13 @@ impl RedpandaClient {
             Ok::<(), anyhow::Error>(())
         }).await;
 
+        self.metrics.record_publish(&topic, origin.as_str(), result.is_ok());
         match result {
             Ok(_) => {
                 tracing::info!(
                     topic = %topic,
                     key = %key,
+                    partition = ?partition,
                     "Published to Redpanda"
                 );
                 Ok(())
@@ -62,9 +325,9 @@ impl RedpandaClient {
             Err(CircuitBreakerError::CircuitOpen) => {
                 tracing::error!(
                     topic = %topic,
-                    "Circuit breaker open - Redpanda unavailable"
+                    "Circuit breaker open - Redpanda unavailable for topic"
                 );
-                Err(anyhow::anyhow!("Circuit breaker open for Redpanda"))
+                Err(anyhow::anyhow!("Circuit breaker open for Redpanda topic {}", topic))
             }
             Err(CircuitBreakerError::OperationFailed(e)) => {
                 tracing::error!(
@@ -77,11 +340,177 @@ impl RedpandaClient {
         }
     }
 
-    pub async fn get_circuit_breaker_state(&self) -> crate::utils::CircuitState {
-        self.circuit_breaker.get_state().await
+    /// Broker list and client library version
+    pub async fn broker_info(&self) -> Result<BrokerInfo> {
+        let producer = self.producer.clone();
+        tokio::task::spawn_blocking(move || {
+            let metadata = producer
+                .client()
+                .fetch_metadata(None, std::time::Duration::from_secs(5))?;
+
+            Ok::<BrokerInfo, anyhow::Error>(BrokerInfo {
+                brokers: metadata
+                    .brokers()
+                    .iter()
+                    .map(|b| format!("{}:{} (id {})", b.host(), b.port(), b.id()))
+                    .collect(),
+                metadata_broker: metadata.orig_broker_name().to_string(),
+                client_library: format!("librdkafka {}", rdkafka::util::get_rdkafka_version().1),
+                broker_version: None,
+            })
+        })
+        .await?
+    }
+
+    /// Create `topic` with cleanup.policy=compact unless it already exists
+    ///
+    /// An existing topic is left as it is, whatever its cleanup policy.
+    /// The replication factor is the broker default.
+    pub async fn ensure_compacted_topic(&self, topic: &str, partitions: i32) -> Result<()> {
+        let admin: AdminClient<DefaultClientContext> = ClientConfig::new()
+            .set("bootstrap.servers", &self.bootstrap_servers)
+            .create()?;
+        let new_topic = NewTopic::new(topic, partitions, TopicReplication::Fixed(-1)).set("cleanup.policy", "compact");
+
+        let results = admin.create_topics([&new_topic], &AdminOptions::new()).await?;
+        for result in results {
+            match result {
+                Ok(name) => tracing::info!(topic = %name, partitions = partitions, "Created compacted topic"),
+                Err((_, RDKafkaErrorCode::TopicAlreadyExists)) => {}
+                Err((name, code)) => anyhow::bail!("Failed to create topic {}: {}", name, code),
+            }
+        }
+        Ok(())
+    }
+
+    /// The breaker of `topic`, created on first use
+    fn circuit_breaker(&self, topic: &str) -> CircuitBreaker {
+        self.circuit_breakers
+            .lock()
+            .unwrap()
+            .entry(topic.to_string())
+            .or_insert_with(|| {
+                CircuitBreaker::new(self.circuit_breaker_config.clone())
+                    .with_name(topic)
+                    .with_metrics(self.metrics.clone())
+            })
+            .clone()
+    }
+
+    /// State of the breaker of `topic` (Closed before its first publish)
+    pub async fn get_circuit_breaker_state(&self, topic: &str) -> CircuitState {
+        let breaker = self.circuit_breakers.lock().unwrap().get(topic).cloned();
+        match breaker {
+            Some(breaker) => breaker.get_state().await,
+            None => CircuitState::Closed,
+        }
+    }
+
+    /// State of every topic's breaker, by topic
+    pub async fn circuit_breaker_states(&self) -> Vec<(String, CircuitState)> {
+        let breakers: Vec<CircuitBreaker> = self.circuit_breakers.lock().unwrap().values().cloned().collect();
+        let mut states = Vec::with_capacity(breakers.len());
+        for breaker in breakers {
+            states.push((breaker.name().to_string(), breaker.get_state().await));
+        }
+        states.sort_by(|a, b| a.0.cmp(&b.0));
+        states
+    }
+
+    pub async fn reset_circuit_breaker(&self, topic: &str) {
+        let breaker = self.circuit_breakers.lock().unwrap().get(topic).cloned();
+        if let Some(breaker) = breaker {
+            breaker.reset().await;
+        }
+    }
+
+    pub fn dual_write_guard(&self) -> &DualWriteGuard {
+        &self.dual_write_guard
+    }
+}
+
+fn producer_config(brokers: &str, partitioner: Partitioner) -> ClientConfig {
+    let mut config = ClientConfig::new();
+    config
+        .set("bootstrap.servers", brokers)
+        .set("message.timeout.ms", "5000")
+        // Internal send retries must not duplicate or reorder messages
+        .set("enable.idempotence", "true")
+        .set("partitioner", partitioner.as_config_value());
+    config
+}
+
+/// Send `records` and wait for every delivery, one result per record
+async fn send_batch(producer: &FutureProducer, records: &[BatchRecord]) -> Vec<Result<()>> {
+    // Enqueue the whole batch first, then collect the deliveries
+    let deliveries: Vec<_> = records
+        .iter()
+        .map(|r| {
+            let headers = r.headers.iter().fold(OwnedHeaders::new(), |acc, (name, value)| {
+                acc.insert(Header { key: name, value: Some(value) })
+            });
+            let mut record = FutureRecord::to(&r.topic).key(&r.key).payload(&r.payload).headers(headers);
+            if let Some(partition) = r.partition {
+                record = record.partition(partition);
+            }
+            producer.send_result(record).map_err(|(e, _)| anyhow::anyhow!("Kafka send error: {}", e))
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(deliveries.len());
+    for delivery in deliveries {
+        results.push(match delivery {
+            Ok(delivery) => match delivery.await {
+                Ok(Ok(_)) => Ok(()),
+                Ok(Err((e, _))) => Err(anyhow::anyhow!("Kafka send error: {}", e)),
+                Err(_) => Err(anyhow::anyhow!("Kafka delivery cancelled")),
+            },
+            Err(e) => Err(e),
+        });
+    }
+    results
+}
+
+/// Send `records` in one producer transaction: all are committed or none
+async fn send_transaction(
+    producer: &FutureProducer,
+    initialized: &tokio::sync::OnceCell<()>,
+    records: &[BatchRecord],
+) -> Vec<Result<()>> {
+    let begun = async {
+        initialized
+            .get_or_try_init(|| run_blocking(producer, |p| p.init_transactions(TRANSACTION_TIMEOUT)))
+            .await?;
+        producer.begin_transaction()?;
+        Ok::<(), anyhow::Error>(())
+    }
+    .await;
+    if let Err(e) = begun {
+        tracing::error!(error = %e, "Could not begin publish transaction");
+        return records.iter().map(|_| Err(anyhow::anyhow!("Could not begin publish transaction: {:#}", e))).collect();
     }
 
-    pub async fn reset_circuit_breaker(&self) {
-        self.circuit_breaker.reset().await;
+    let delivered = send_batch(producer, records).await;
+    let commit = if delivered.iter().all(Result::is_ok) {
+        run_blocking(producer, |p| p.commit_transaction(TRANSACTION_TIMEOUT)).await
+    } else {
+        Err(anyhow::anyhow!("A record of the batch was not delivered"))
+    };
+    if let Err(ref e) = commit {
+        tracing::warn!(error = %e, records = records.len(), "Aborting publish transaction");
+        if let Err(abort_error) = run_blocking(producer, |p| p.abort_transaction(TRANSACTION_TIMEOUT)).await {
+            tracing::error!(error = %abort_error, "Failed to abort publish transaction");
+        }
     }
-}
\ No newline at end of file
+    transaction_results(delivered, commit)
+}
+
+/// Run a blocking producer call off the async workers
+async fn run_blocking<F>(producer: &FutureProducer, call: F) -> Result<()>
+where
+    F: FnOnce(&FutureProducer) -> KafkaResult<()> + Send + 'static,
+{
+    let producer = producer.clone();
+    tokio::task::spawn_blocking(move || call(&producer)).await??;
+    Ok(())
+}
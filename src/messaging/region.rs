@@ -0,0 +1,105 @@
+// ============================================================================
+// Region Awareness - Active-Active Deployments
+// ============================================================================
+//
+// With multi-DC replication every region's CDC reader sees the outbox rows
+// written in *all* regions. Without a region tag each region would publish
+// every event again, and any bridge replicating topics back into the store
+// would loop forever.
+//
+// Events are stamped with the region they originated in (envelope metadata,
+// event_store/outbox column, Kafka header). The CDC relay then:
+// - skips rows that originated elsewhere (origin filter), and
+// - optionally routes each event to a per-region topic (`order-events.eu-west`)
+//   so cross-region consumers can subscribe selectively.
+//
+// Rows without an origin (written before regions existed) are always relayed.
+//
+// ============================================================================
+
+/// Kafka header carrying the originating region
+pub const REGION_HEADER: &str = "origin-region";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegionConfig {
+    region: String,
+    per_region_topics: bool,
+    skip_foreign: bool,
+}
+
+impl RegionConfig {
+    /// Local region; foreign-origin events are skipped by default
+    pub fn new(region: impl Into<String>) -> Self {
+        Self {
+            region: region.into(),
+            per_region_topics: false,
+            skip_foreign: true,
+        }
+    }
+
+    /// Publish to `<topic>.<origin region>` instead of `<topic>`
+    pub fn with_per_region_topics(mut self) -> Self {
+        self.per_region_topics = true;
+        self
+    }
+
+    /// Relay events regardless of where they originated
+    ///
+    /// Only safe when no other region runs the pipeline on the same data.
+    pub fn relay_all_origins(mut self) -> Self {
+        self.skip_foreign = false;
+        self
+    }
+
+    pub fn region(&self) -> &str {
+        &self.region
+    }
+
+    /// Whether this region should relay an event from `origin`
+    pub fn should_relay(&self, origin: Option<&str>) -> bool {
+        match origin {
+            Some(origin) if self.skip_foreign => origin == self.region,
+            _ => true,
+        }
+    }
+
+    /// Topic to publish an event from `origin` to
+    pub fn topic_for(&self, topic: &str, origin: Option<&str>) -> String {
+        if self.per_region_topics {
+            format!("{}.{}", topic, origin.unwrap_or(&self.region))
+        } else {
+            topic.to_string()
+        }
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_foreign_origin_skipped() {
+        let region = RegionConfig::new("eu-west");
+
+        assert!(region.should_relay(Some("eu-west")));
+        assert!(!region.should_relay(Some("us-east")));
+        // Legacy rows without an origin are relayed
+        assert!(region.should_relay(None));
+
+        assert!(region.relay_all_origins().should_relay(Some("us-east")));
+    }
+
+    #[test]
+    fn test_per_region_topic_routing() {
+        let region = RegionConfig::new("eu-west");
+        assert_eq!(region.topic_for("order-events", Some("eu-west")), "order-events");
+
+        let routed = region.with_per_region_topics();
+        assert_eq!(routed.topic_for("order-events", Some("us-east")), "order-events.us-east");
+        assert_eq!(routed.topic_for("order-events", None), "order-events.eu-west");
+    }
+}
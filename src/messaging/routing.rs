@@ -0,0 +1,342 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::metrics::MetricsHandle;
+
+// ============================================================================
+// Routing Rules - Fan Out Events to Additional Topics
+// ============================================================================
+//
+// Config-driven rules evaluated by the CDC relay after an event has been
+// published to its primary topic. A rule whose conditions all hold copies
+// the event (same key, payload and headers) to extra topics, e.g. large
+// orders also go to `fraud-review`.
+//
+// Example (JSON):
+//
+//   {
+//     "dry_run": false,
+//     "rules": [{
+//       "name": "large-orders-to-fraud",
+//       "event_types": ["OrderCreated"],
+//       "when": [{ "field": "payload.data.total", "op": "gt", "value": 1000 }],
+//       "route_to": ["fraud-review"]
+//     }]
+//   }
+//
+// Fields:
+// - `payload.<path>` - dot path into the JSON payload (array indexes allowed)
+// - `header.<name>`  - Kafka header of the message
+// - `event_type`
+//
+// In dry-run mode (globally or per rule) matches are logged and counted but
+// nothing is published - use it to validate a rule against live traffic.
+//
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConditionOp {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Exists,
+    Missing,
+    /// String contains substring, or array contains value
+    Contains,
+    /// Field equals one of the values of an array
+    In,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Condition {
+    pub field: String,
+    pub op: ConditionOp,
+    #[serde(default)]
+    pub value: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RoutingRule {
+    pub name: String,
+    /// Only evaluate for these event types (all when empty)
+    #[serde(default)]
+    pub event_types: Vec<String>,
+    /// All conditions must hold
+    #[serde(default)]
+    pub when: Vec<Condition>,
+    pub route_to: Vec<String>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Event as seen by the rules
+pub struct RoutedEvent<'a> {
+    pub event_type: &'a str,
+    pub payload: &'a str,
+    pub headers: &'a [(&'a str, &'a str)],
+}
+
+/// A matched rule and where it routes the event
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoutingDecision {
+    pub rule: String,
+    pub topics: Vec<String>,
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct RoutingConfig {
+    #[serde(default)]
+    dry_run: bool,
+    #[serde(default)]
+    rules: Vec<RoutingRule>,
+}
+
+#[derive(Default)]
+pub struct RoutingRules {
+    rules: Vec<RoutingRule>,
+    dry_run: bool,
+    metrics: MetricsHandle,
+}
+
+impl RoutingRules {
+    pub fn new(rules: Vec<RoutingRule>) -> Self {
+        Self { rules, dry_run: false, metrics: MetricsHandle::noop() }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let config: RoutingConfig = serde_json::from_str(json).context("Invalid routing rules")?;
+        Ok(Self { rules: config.rules, dry_run: config.dry_run, metrics: MetricsHandle::noop() })
+    }
+
+    pub fn from_file(path: &str) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read routing rules from {}", path))?;
+        Self::from_json(&json)
+    }
+
+    /// Evaluate and log every rule but never publish
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: MetricsHandle) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Rules matching `event`, in configuration order
+    pub fn evaluate(&self, event: &RoutedEvent<'_>) -> Vec<RoutingDecision> {
+        // Non-JSON payloads simply have no payload fields
+        let payload: Value = serde_json::from_str(event.payload).unwrap_or(Value::Null);
+        let mut decisions = Vec::new();
+
+        for rule in &self.rules {
+            if !rule.event_types.is_empty() && !rule.event_types.iter().any(|t| t == event.event_type) {
+                continue;
+            }
+
+            let matched = rule.when.iter().all(|c| condition_holds(c, event, &payload));
+            self.metrics.record_routing_evaluation(&rule.name, matched);
+
+            if matched {
+                decisions.push(RoutingDecision {
+                    rule: rule.name.clone(),
+                    topics: rule.route_to.clone(),
+                    dry_run: self.dry_run || rule.dry_run,
+                });
+            }
+        }
+
+        decisions
+    }
+
+    /// Count what happened to a routed copy (published, dry_run, failed)
+    pub fn record_routed(&self, rule: &str, topic: &str, outcome: &str) {
+        self.metrics.record_routing_route(rule, topic, outcome);
+    }
+}
+
+fn resolve(event: &RoutedEvent<'_>, payload: &Value, field: &str) -> Option<Value> {
+    if field == "event_type" {
+        return Some(Value::String(event.event_type.to_string()));
+    }
+    if let Some(name) = field.strip_prefix("header.") {
+        return event
+            .headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| Value::String(v.to_string()));
+    }
+
+    let path = field.strip_prefix("payload.")?;
+    let mut current = payload;
+    for segment in path.split('.') {
+        current = match current {
+            Value::Object(map) => map.get(segment)?,
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(current.clone())
+}
+
+fn condition_holds(condition: &Condition, event: &RoutedEvent<'_>, payload: &Value) -> bool {
+    let actual = resolve(event, payload, &condition.field);
+
+    match condition.op {
+        ConditionOp::Exists => return actual.is_some_and(|v| !v.is_null()),
+        ConditionOp::Missing => return actual.is_none_or(|v| v.is_null()),
+        _ => {}
+    }
+
+    let (Some(actual), Some(expected)) = (actual, condition.value.as_ref()) else {
+        return false;
+    };
+
+    match condition.op {
+        ConditionOp::Eq => values_equal(&actual, expected),
+        ConditionOp::Ne => !values_equal(&actual, expected),
+        ConditionOp::Gt => compare(&actual, expected).is_some_and(|o| o.is_gt()),
+        ConditionOp::Gte => compare(&actual, expected).is_some_and(|o| o.is_ge()),
+        ConditionOp::Lt => compare(&actual, expected).is_some_and(|o| o.is_lt()),
+        ConditionOp::Lte => compare(&actual, expected).is_some_and(|o| o.is_le()),
+        ConditionOp::Contains => match (&actual, expected) {
+            (Value::String(s), Value::String(needle)) => s.contains(needle.as_str()),
+            (Value::Array(items), needle) => items.iter().any(|i| values_equal(i, needle)),
+            _ => false,
+        },
+        ConditionOp::In => match expected {
+            Value::Array(options) => options.iter().any(|o| values_equal(&actual, o)),
+            _ => false,
+        },
+        ConditionOp::Exists | ConditionOp::Missing => unreachable!(),
+    }
+}
+
+/// Numbers compare numerically, also when one side is a numeric string
+/// (headers are always strings)
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn values_equal(a: &Value, b: &Value) -> bool {
+    match (as_number(a), as_number(b)) {
+        (Some(x), Some(y)) if a.is_number() || b.is_number() => x == y,
+        _ => a == b,
+    }
+}
+
+fn compare(a: &Value, b: &Value) -> Option<std::cmp::Ordering> {
+    match (as_number(a), as_number(b)) {
+        (Some(x), Some(y)) => x.partial_cmp(&y),
+        _ => match (a, b) {
+            (Value::String(x), Value::String(y)) => Some(x.cmp(y)),
+            _ => None,
+        },
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: &str = r#"{
+        "rules": [
+            {
+                "name": "large-orders-to-fraud",
+                "event_types": ["OrderCreated"],
+                "when": [{ "field": "payload.data.total", "op": "gt", "value": 1000 }],
+                "route_to": ["fraud-review"]
+            },
+            {
+                "name": "eu-audit",
+                "when": [{ "field": "header.origin-region", "op": "in", "value": ["eu-west", "eu-central"] }],
+                "route_to": ["eu-audit"],
+                "dry_run": true
+            }
+        ]
+    }"#;
+
+    fn event<'a>(event_type: &'a str, payload: &'a str, headers: &'a [(&'a str, &'a str)]) -> RoutedEvent<'a> {
+        RoutedEvent { event_type, payload, headers }
+    }
+
+    #[test]
+    fn test_payload_predicate_routes_large_orders() {
+        let rules = RoutingRules::from_json(RULES).unwrap();
+
+        let large = rules.evaluate(&event("OrderCreated", r#"{"data":{"total":1500}}"#, &[]));
+        assert_eq!(large, vec![RoutingDecision {
+            rule: "large-orders-to-fraud".to_string(),
+            topics: vec!["fraud-review".to_string()],
+            dry_run: false,
+        }]);
+
+        assert!(rules.evaluate(&event("OrderCreated", r#"{"data":{"total":20}}"#, &[])).is_empty());
+        // Rule is scoped to OrderCreated
+        assert!(rules.evaluate(&event("OrderShipped", r#"{"data":{"total":1500}}"#, &[])).is_empty());
+    }
+
+    #[test]
+    fn test_header_predicate_and_rule_dry_run() {
+        let rules = RoutingRules::from_json(RULES).unwrap();
+
+        let decisions = rules.evaluate(&event("OrderShipped", "{}", &[("origin-region", "eu-west")]));
+        assert_eq!(decisions.len(), 1);
+        assert_eq!(decisions[0].rule, "eu-audit");
+        assert!(decisions[0].dry_run);
+    }
+
+    #[test]
+    fn test_global_dry_run() {
+        let rules = RoutingRules::from_json(RULES).unwrap().with_dry_run(true);
+        let decisions = rules.evaluate(&event("OrderCreated", r#"{"data":{"total":5000}}"#, &[]));
+        assert!(decisions.iter().all(|d| d.dry_run));
+    }
+
+    #[test]
+    fn test_exists_contains_and_array_paths() {
+        let rules = RoutingRules::new(vec![RoutingRule {
+            name: "gift".to_string(),
+            event_types: vec![],
+            when: vec![
+                Condition { field: "payload.items.0.sku".to_string(), op: ConditionOp::Contains, value: Some("GIFT".into()) },
+                Condition { field: "payload.note".to_string(), op: ConditionOp::Missing, value: None },
+            ],
+            route_to: vec!["gifts".to_string()],
+            dry_run: false,
+        }]);
+
+        assert_eq!(rules.evaluate(&event("OrderCreated", r#"{"items":[{"sku":"GIFT-1"}]}"#, &[])).len(), 1);
+        assert!(rules.evaluate(&event("OrderCreated", r#"{"items":[{"sku":"GIFT-1"}],"note":"x"}"#, &[])).is_empty());
+        assert!(rules.evaluate(&event("OrderCreated", "not json", &[])).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_config_rejected() {
+        assert!(RoutingRules::from_json(r#"{"rules":[{"name":"x","when":[{"field":"a","op":"between"}],"route_to":[]}]}"#).is_err());
+    }
+}
@@ -0,0 +1,164 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::event_sourcing::{AggregateRoot, DomainEvent, EventStore, SnapshotPolicy};
+use super::EventPublisher;
+
+// ============================================================================
+// Event-Carried State Transfer - Full Aggregate State on Compacted Topics
+// ============================================================================
+//
+// Event topics carry deltas. Consumers that only care about the current
+// state of an order (search indexes, caches, other teams' read models)
+// would otherwise have to replay and fold every event themselves. The
+// StateSnapshotPublisher publishes the whole aggregate state, rebuilt with
+// `load_aggregate`, to a separate compacted topic keyed by aggregate_id,
+// e.g. order-state next to order-events:
+//
+//   { "aggregate_type": "Order", "aggregate_id": "...", "version": 40,
+//     "published_at": "...", "state": { ...aggregate... } }
+//
+// Triggers:
+// - every N events: command handlers call `after_append`; an append that
+//   crosses a multiple of N publishes in the background
+// - on demand: `publish_now` (admin API)
+//
+// The topic is created with cleanup.policy=compact on startup if missing
+// (RedpandaClient::ensure_compacted_topic). Compaction keeps the newest
+// message per key, so a new consumer reads the topic from the beginning and
+// has every aggregate's latest published state. Two publishes of one
+// aggregate can race; consumers keep the message with the highest
+// `version`. A failed background publish is only logged - the next trigger
+// publishes the state again.
+//
+// Enabled through AppConfig ([state_snapshots] / STATE_SNAPSHOTS_ENABLED,
+// STATE_SNAPSHOT_EVERY).
+//
+// ============================================================================
+
+/// Message published to the state topic
+#[derive(Serialize)]
+pub struct StateSnapshotMessage<'a, A: Serialize> {
+    pub aggregate_type: &'a str,
+    pub aggregate_id: Uuid,
+    pub version: i64,
+    pub published_at: DateTime<Utc>,
+    pub state: &'a A,
+}
+
+/// Publishes full aggregate states of one aggregate type
+pub struct StateSnapshotPublisher<A: AggregateRoot>
+where
+    A::Event: DomainEvent,
+{
+    store: Arc<EventStore<A::Event>>,
+    publisher: Arc<dyn EventPublisher>,
+    aggregate_type: String,
+    topic: String,
+    /// 0 publishes on demand only
+    every: i64,
+}
+
+impl<A> StateSnapshotPublisher<A>
+where
+    A: AggregateRoot + Serialize + 'static,
+    A::Event: DomainEvent,
+    <A as AggregateRoot>::Error: std::fmt::Display,
+{
+    pub fn new(store: Arc<EventStore<A::Event>>, publisher: Arc<dyn EventPublisher>, aggregate_type: &str, topic: &str) -> Self {
+        Self {
+            store,
+            publisher,
+            aggregate_type: aggregate_type.to_string(),
+            topic: topic.to_string(),
+            every: 0,
+        }
+    }
+
+    /// Publish automatically whenever an append crosses a multiple of `every` events
+    pub fn with_every(mut self, every: i64) -> Self {
+        self.every = every;
+        self
+    }
+
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    /// Rebuild and publish the current state of an aggregate; returns its version
+    pub async fn publish_now(&self, aggregate_id: Uuid) -> Result<i64> {
+        let aggregate: A = self.store.load_aggregate(aggregate_id).await?;
+        let message = StateSnapshotMessage {
+            aggregate_type: &self.aggregate_type,
+            aggregate_id,
+            version: aggregate.version(),
+            published_at: Utc::now(),
+            state: &aggregate,
+        };
+        let payload = serde_json::to_string(&message)?;
+        self.publisher.publish(&self.topic, &aggregate_id.to_string(), &payload).await?;
+
+        tracing::debug!(
+            aggregate_type = %self.aggregate_type,
+            aggregate_id = %aggregate_id,
+            version = message.version,
+            topic = %self.topic,
+            "📸 Published aggregate state"
+        );
+        Ok(message.version)
+    }
+
+    /// Publish in the background if the append crossed the configured interval
+    pub fn after_append(self: &Arc<Self>, aggregate_id: Uuid, previous_version: i64, new_version: i64) {
+        if !SnapshotPolicy::every(self.every).is_due(previous_version, new_version) {
+            return;
+        }
+        let publisher = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = publisher.publish_now(aggregate_id).await {
+                tracing::warn!(
+                    aggregate_type = %publisher.aggregate_type,
+                    aggregate_id = %aggregate_id,
+                    version = new_version,
+                    error = %e,
+                    "Failed to publish aggregate state"
+                );
+            }
+        });
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Counter {
+        count: u32,
+    }
+
+    #[test]
+    fn test_message_shape() {
+        let aggregate_id = Uuid::new_v4();
+        let state = Counter { count: 3 };
+        let message = StateSnapshotMessage {
+            aggregate_type: "Counter",
+            aggregate_id,
+            version: 7,
+            published_at: Utc::now(),
+            state: &state,
+        };
+
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!(json["aggregate_id"], aggregate_id.to_string());
+        assert_eq!(json["version"], 7);
+        assert_eq!(json["state"]["count"], 3);
+    }
+}
@@ -0,0 +1,205 @@
+use anyhow::{anyhow, Result};
+use futures_util::Stream;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::RecvError};
+use uuid::Uuid;
+
+use crate::event_sourcing::EventEnvelope;
+
+// ============================================================================
+// Event Subscriptions - Watching an Aggregate In-Process
+// ============================================================================
+//
+// The CDC relay hands every event it reads from an outbox to the
+// EventSubscriptions hub before publishing it. In-process listeners
+// (websocket notifiers, tests waiting for a state change) watch a single
+// aggregate without consuming Redpanda:
+//
+//   let mut orders = subscriptions.subscribe(order_id);
+//   let shipped = orders.wait_for(timeout, |e| e.event_type == "OrderShipped").await?;
+//
+// Each watched aggregate has a broadcast channel of its own, created by the
+// first subscriber and dropped with the last; events of unwatched aggregates
+// are discarded right away. Delivery is what the CDC reader sees: events
+// arrive once they are committed, in sequence order per aggregate, and rows
+// re-read after a relay restart are delivered again.
+//
+// A subscriber that falls more than `capacity` events behind loses the
+// oldest ones and is told how many (SubscriptionLagged) - reload the
+// aggregate from the event store to catch up.
+//
+// ============================================================================
+
+/// Events buffered per watched aggregate for its slowest subscriber
+const DEFAULT_CAPACITY: usize = 256;
+
+type Channels = Arc<Mutex<HashMap<Uuid, broadcast::Sender<Arc<EventEnvelope<Value>>>>>>;
+
+/// Fans out relayed events to subscribers of their aggregate
+pub struct EventSubscriptions {
+    capacity: usize,
+    channels: Channels,
+}
+
+impl Default for EventSubscriptions {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl EventSubscriptions {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity: capacity.max(1), channels: Arc::new(Mutex::new(HashMap::new())) }
+    }
+
+    /// Watch the events of one aggregate from now on
+    pub fn subscribe(&self, aggregate_id: Uuid) -> AggregateSubscription {
+        let receiver = self
+            .channels
+            .lock()
+            .unwrap()
+            .entry(aggregate_id)
+            .or_insert_with(|| broadcast::channel(self.capacity).0)
+            .subscribe();
+        AggregateSubscription { aggregate_id, receiver, channels: self.channels.clone() }
+    }
+
+    /// Deliver `envelope` to the subscribers of its aggregate, if any
+    pub fn publish(&self, envelope: EventEnvelope<Value>) {
+        let aggregate_id = envelope.aggregate_id;
+        let mut channels = self.channels.lock().unwrap();
+        let Some(sender) = channels.get(&aggregate_id) else {
+            return;
+        };
+        if sender.send(Arc::new(envelope)).is_err() {
+            // No receiver left
+            channels.remove(&aggregate_id);
+        }
+    }
+
+    /// Aggregates with at least one subscriber
+    pub fn watched_aggregates(&self) -> usize {
+        self.channels.lock().unwrap().len()
+    }
+}
+
+/// The subscriber missed events while it was behind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubscriptionLagged {
+    pub missed: u64,
+}
+
+impl std::fmt::Display for SubscriptionLagged {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Subscriber lagged behind and missed {} events", self.missed)
+    }
+}
+
+impl std::error::Error for SubscriptionLagged {}
+
+/// Events of one aggregate, as the CDC relay reads them
+pub struct AggregateSubscription {
+    aggregate_id: Uuid,
+    receiver: broadcast::Receiver<Arc<EventEnvelope<Value>>>,
+    channels: Channels,
+}
+
+impl AggregateSubscription {
+    pub fn aggregate_id(&self) -> Uuid {
+        self.aggregate_id
+    }
+
+    /// The next event; after a lag, the oldest event still buffered follows
+    pub async fn next(&mut self) -> Result<Arc<EventEnvelope<Value>>, SubscriptionLagged> {
+        match self.receiver.recv().await {
+            Ok(envelope) => Ok(envelope),
+            Err(RecvError::Lagged(missed)) => Err(SubscriptionLagged { missed }),
+            // The channel outlives its subscribers (it is held by the hub)
+            Err(RecvError::Closed) => std::future::pending().await,
+        }
+    }
+
+    /// The next event matching `predicate`, failing after `timeout` or on lag
+    pub async fn wait_for<F>(&mut self, timeout: Duration, predicate: F) -> Result<Arc<EventEnvelope<Value>>>
+    where
+        F: Fn(&EventEnvelope<Value>) -> bool,
+    {
+        let aggregate_id = self.aggregate_id;
+        let matching = async {
+            loop {
+                let envelope = self.next().await?;
+                if predicate(&envelope) {
+                    return Ok::<_, SubscriptionLagged>(envelope);
+                }
+            }
+        };
+        tokio::time::timeout(timeout, matching)
+            .await
+            .map_err(|_| anyhow!("No matching event of aggregate {} within {:?}", aggregate_id, timeout))?
+            .map_err(Into::into)
+    }
+
+    /// The subscription as a stream (e.g. to forward to a websocket)
+    pub fn into_stream(self) -> impl Stream<Item = Result<Arc<EventEnvelope<Value>>, SubscriptionLagged>> {
+        futures_util::stream::unfold(self, |mut subscription| async move {
+            let next = subscription.next().await;
+            Some((next, subscription))
+        })
+    }
+}
+
+impl Drop for AggregateSubscription {
+    fn drop(&mut self) {
+        let Ok(mut channels) = self.channels.lock() else {
+            return;
+        };
+        // The receiver being dropped still counts
+        if channels.get(&self.aggregate_id).is_some_and(|sender| sender.receiver_count() <= 1) {
+            channels.remove(&self.aggregate_id);
+        }
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn envelope(aggregate_id: Uuid, sequence_number: i64, event_type: &str) -> EventEnvelope<Value> {
+        EventEnvelope::new(aggregate_id, sequence_number, event_type.to_string(), serde_json::json!({}), Uuid::new_v4())
+    }
+
+    #[tokio::test]
+    async fn test_subscribers_see_only_their_aggregate() {
+        let subscriptions = EventSubscriptions::new(2);
+        let (order, other) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut watcher = subscriptions.subscribe(order);
+        assert_eq!(subscriptions.watched_aggregates(), 1);
+
+        subscriptions.publish(envelope(other, 1, "OrderCreated"));
+        subscriptions.publish(envelope(order, 1, "OrderCreated"));
+        subscriptions.publish(envelope(order, 2, "OrderShipped"));
+        let shipped = watcher
+            .wait_for(Duration::from_secs(1), |e| e.event_type == "OrderShipped")
+            .await
+            .unwrap();
+        assert_eq!((shipped.aggregate_id, shipped.sequence_number), (order, 2));
+
+        // Three events into a buffer of two: the first is lost
+        for sequence_number in 3..6 {
+            subscriptions.publish(envelope(order, sequence_number, "OrderUpdated"));
+        }
+        assert_eq!(watcher.next().await.unwrap_err(), SubscriptionLagged { missed: 1 });
+        assert_eq!(watcher.next().await.unwrap().sequence_number, 4);
+        assert!(watcher.wait_for(Duration::from_millis(20), |e| e.sequence_number == 9).await.is_err());
+
+        drop(watcher);
+        assert_eq!(subscriptions.watched_aggregates(), 0);
+    }
+}
@@ -0,0 +1,136 @@
+use async_trait::async_trait;
+use anyhow::{Result, bail};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use super::{EventPublisher, PublishOrigin};
+
+// ============================================================================
+// Publisher Test Doubles
+// ============================================================================
+//
+// - RecordingPublisher: accepts everything and remembers what was published
+// - FailingPublisher: fails a configurable number of times (or forever),
+//   then records like RecordingPublisher
+//
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PublishedMessage {
+    pub topic: String,
+    pub key: String,
+    pub payload: String,
+    pub headers: Vec<(String, String)>,
+    pub origin: PublishOrigin,
+}
+
+#[derive(Default)]
+pub struct RecordingPublisher {
+    published: Mutex<Vec<PublishedMessage>>,
+}
+
+impl RecordingPublisher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn published(&self) -> Vec<PublishedMessage> {
+        self.published.lock().unwrap().clone()
+    }
+
+    pub fn count(&self) -> usize {
+        self.published.lock().unwrap().len()
+    }
+}
+
+#[async_trait]
+impl EventPublisher for RecordingPublisher {
+    async fn publish(&self, topic: &str, key: &str, payload: &str) -> Result<()> {
+        self.publish_with_headers(topic, key, payload, &[]).await
+    }
+
+    async fn publish_with_headers(&self, topic: &str, key: &str, payload: &str, headers: &[(&str, &str)]) -> Result<()> {
+        self.publish_with_origin(topic, key, payload, headers, PublishOrigin::Direct).await
+    }
+
+    async fn publish_with_origin(
+        &self,
+        topic: &str,
+        key: &str,
+        payload: &str,
+        headers: &[(&str, &str)],
+        origin: PublishOrigin,
+    ) -> Result<()> {
+        self.published.lock().unwrap().push(PublishedMessage {
+            topic: topic.to_string(),
+            key: key.to_string(),
+            payload: payload.to_string(),
+            headers: headers.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            origin,
+        });
+        Ok(())
+    }
+}
+
+pub struct FailingPublisher {
+    failures_remaining: AtomicU32,
+    attempts: AtomicU32,
+    recorder: RecordingPublisher,
+}
+
+impl FailingPublisher {
+    /// Fail the first `failures` publishes, then succeed
+    pub fn failing_times(failures: u32) -> Self {
+        Self {
+            failures_remaining: AtomicU32::new(failures),
+            attempts: AtomicU32::new(0),
+            recorder: RecordingPublisher::new(),
+        }
+    }
+
+    /// Fail every publish
+    pub fn always() -> Self {
+        Self::failing_times(u32::MAX)
+    }
+
+    /// Total publish calls, including failed ones
+    pub fn attempts(&self) -> u32 {
+        self.attempts.load(Ordering::SeqCst)
+    }
+
+    pub fn published(&self) -> Vec<PublishedMessage> {
+        self.recorder.published()
+    }
+}
+
+#[async_trait]
+impl EventPublisher for FailingPublisher {
+    async fn publish(&self, topic: &str, key: &str, payload: &str) -> Result<()> {
+        self.publish_with_headers(topic, key, payload, &[]).await
+    }
+
+    async fn publish_with_headers(&self, topic: &str, key: &str, payload: &str, headers: &[(&str, &str)]) -> Result<()> {
+        self.publish_with_origin(topic, key, payload, headers, PublishOrigin::Direct).await
+    }
+
+    async fn publish_with_origin(
+        &self,
+        topic: &str,
+        key: &str,
+        payload: &str,
+        headers: &[(&str, &str)],
+        origin: PublishOrigin,
+    ) -> Result<()> {
+        self.attempts.fetch_add(1, Ordering::SeqCst);
+
+        let remaining = self.failures_remaining.load(Ordering::SeqCst);
+        if remaining > 0 {
+            if remaining != u32::MAX {
+                self.failures_remaining.fetch_sub(1, Ordering::SeqCst);
+            }
+            bail!("Simulated broker failure");
+        }
+
+        self.recorder.publish_with_origin(topic, key, payload, headers, origin).await
+    }
+}
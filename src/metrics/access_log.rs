@@ -0,0 +1,235 @@
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::HttpMessage;
+use futures_util::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use std::time::Instant;
+use tracing::Instrument;
+use uuid::Uuid;
+
+use super::MetricsHandle;
+
+// ============================================================================
+// Access Log Middleware - Structured Per-Request Audit
+// ============================================================================
+//
+// Wraps an actix App and, for every request, emits one structured tracing
+// event (target "access_log") with method, route, status, latency,
+// principal, correlation id and command type, and counts it in Prometheus
+// per route. Replaces actix's text Logger so HTTP traffic follows the same
+// observability model as CDC processing.
+//
+// - Correlation id: taken from `x-correlation-id` or generated, stored in
+//   request extensions (`CorrelationId`) and echoed on the response
+// - Principal: `x-principal` header until real authentication exists
+// - Command type: `x-command-type` header, or a `CommandType` a handler put
+//   into the request extensions
+// - Route: the matched pattern (`/admin/orders/{id}/...`), never the raw
+//   path, so metric cardinality stays bounded
+//
+// ============================================================================
+
+pub const CORRELATION_ID_HEADER: &str = "x-correlation-id";
+pub const PRINCIPAL_HEADER: &str = "x-principal";
+pub const COMMAND_TYPE_HEADER: &str = "x-command-type";
+
+/// Correlation id of the current request, available to handlers
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorrelationId(pub String);
+
+/// Command a handler executed, reported in the access log
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandType(pub String);
+
+/// Access log middleware factory; `server` names the listener in logs/metrics
+#[derive(Clone)]
+pub struct AccessLog {
+    server: &'static str,
+    metrics: MetricsHandle,
+}
+
+impl AccessLog {
+    pub fn new(server: &'static str) -> Self {
+        Self { server, metrics: MetricsHandle::noop() }
+    }
+
+    pub fn with_metrics(mut self, metrics: MetricsHandle) -> Self {
+        self.metrics = metrics;
+        self
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for AccessLog
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Transform = AccessLogMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(AccessLogMiddleware {
+            service,
+            server: self.server,
+            metrics: self.metrics.clone(),
+        }))
+    }
+}
+
+pub struct AccessLogMiddleware<S> {
+    service: S,
+    server: &'static str,
+    metrics: MetricsHandle,
+}
+
+impl<S, B> Service<ServiceRequest> for AccessLogMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let started = Instant::now();
+        let server = self.server;
+        let metrics = self.metrics.clone();
+
+        let correlation_id = header_value(&req, CORRELATION_ID_HEADER)
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        let principal = header_value(&req, PRINCIPAL_HEADER).unwrap_or_else(|| "anonymous".to_string());
+        let method = req.method().to_string();
+        let path = req.path().to_string();
+
+        req.extensions_mut().insert(CorrelationId(correlation_id.clone()));
+
+        let span = tracing::info_span!("http_request", correlation_id = %correlation_id, server = server);
+        let fut = self.service.call(req);
+
+        Box::pin(
+            async move {
+                let result = fut.await;
+
+                let (status, route, command_type) = match &result {
+                    Ok(res) => {
+                        let request = res.request();
+                        let command_type = request
+                            .extensions()
+                            .get::<CommandType>()
+                            .map(|c| c.0.clone())
+                            .or_else(|| {
+                                request
+                                    .headers()
+                                    .get(COMMAND_TYPE_HEADER)
+                                    .and_then(|v| v.to_str().ok())
+                                    .map(|v| v.to_string())
+                            });
+                        (res.status().as_u16(), request.match_pattern(), command_type)
+                    }
+                    Err(e) => (e.as_response_error().status_code().as_u16(), None, None),
+                };
+                let route = route.unwrap_or_else(|| "unmatched".to_string());
+                let latency = started.elapsed();
+
+                tracing::info!(
+                    target: "access_log",
+                    server = server,
+                    method = %method,
+                    route = %route,
+                    path = %path,
+                    status = status,
+                    latency_ms = latency.as_secs_f64() * 1000.0,
+                    principal = %principal,
+                    correlation_id = %correlation_id,
+                    command_type = command_type.as_deref().unwrap_or("-"),
+                    "HTTP request"
+                );
+
+                metrics.record_http_request(server, &route, &method, status, latency.as_secs_f64());
+
+                let mut res = result?;
+                if let Ok(value) = HeaderValue::from_str(&correlation_id) {
+                    res.headers_mut()
+                        .insert(HeaderName::from_static(CORRELATION_ID_HEADER), value);
+                }
+                Ok(res)
+            }
+            .instrument(span),
+        )
+    }
+}
+
+fn header_value(req: &ServiceRequest, name: &str) -> Option<String> {
+    req.headers()
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+        .map(|v| v.to_string())
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::Metrics;
+    use actix_web::{test, web, App, HttpRequest, HttpResponse};
+    use std::sync::Arc;
+
+    async fn echo_correlation(req: HttpRequest) -> HttpResponse {
+        let id = req.extensions().get::<CorrelationId>().map(|c| c.0.clone()).unwrap_or_default();
+        req.extensions_mut().insert(CommandType("CreateOrder".to_string()));
+        HttpResponse::Ok().body(id)
+    }
+
+    #[actix_web::test]
+    async fn test_correlation_id_propagated_and_route_counted() {
+        let metrics = Arc::new(Metrics::new().unwrap());
+        let app = test::init_service(
+            App::new()
+                .wrap(AccessLog::new("test").with_metrics(MetricsHandle::from(metrics.clone())))
+                .route("/orders/{id}", web::post().to(echo_correlation)),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/orders/42")
+            .insert_header((CORRELATION_ID_HEADER, "corr-1"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(res.headers().get(CORRELATION_ID_HEADER).unwrap(), "corr-1");
+        assert_eq!(test::read_body(res).await, "corr-1");
+
+        let gathered = metrics.registry().gather();
+        let requests = gathered.iter().find(|m| m.name() == "http_requests_total").unwrap();
+        let labels: Vec<&str> = requests.metric[0].label.iter().map(|l| l.value()).collect();
+        assert!(labels.contains(&"/orders/{id}"));
+        assert!(labels.contains(&"200"));
+    }
+
+    #[actix_web::test]
+    async fn test_correlation_id_generated_when_missing() {
+        let app = test::init_service(
+            App::new()
+                .wrap(AccessLog::new("test"))
+                .route("/orders/{id}", web::post().to(echo_correlation)),
+        )
+        .await;
+
+        let res = test::call_service(&app, test::TestRequest::post().uri("/orders/1").to_request()).await;
+        let header = res.headers().get(CORRELATION_ID_HEADER).unwrap().to_str().unwrap().to_string();
+        assert!(Uuid::parse_str(&header).is_ok());
+    }
+}
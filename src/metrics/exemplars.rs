@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use chrono::Utc;
+use prometheus::proto::{MetricFamily, MetricType};
+
+// ============================================================================
+// Exemplars - Link Histogram Observations to Traces
+// ============================================================================
+//
+// An exemplar attaches a trace ID to a single observation in a histogram
+// bucket. Grafana shows exemplars as dots on latency panels; clicking one
+// jumps straight to the trace of that slow request.
+//
+// The prometheus crate does not support exemplars, so we keep the latest
+// exemplar per (histogram, label set, bucket) here and render them when the
+// scraper asks for OpenMetrics. The classic text format is unchanged.
+//
+// ============================================================================
+
+pub const OPENMETRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exemplar {
+    pub trace_id: String,
+    pub value: f64,
+    /// Unix timestamp in seconds
+    pub timestamp: f64,
+}
+
+/// (metric name, canonical label key) -> one slot per bucket, plus +Inf
+type BucketExemplars = HashMap<(String, String), Vec<Option<Exemplar>>>;
+
+/// Latest exemplar per histogram bucket
+#[derive(Default)]
+pub struct ExemplarStore {
+    exemplars: Mutex<BucketExemplars>,
+}
+
+impl ExemplarStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record an exemplar for an observation of `value` on `metric`
+    ///
+    /// `buckets` must be the histogram's bucket upper bounds.
+    pub fn record(&self, metric: &str, labels: &[(&str, &str)], buckets: &[f64], value: f64, trace_id: &str) {
+        let index = buckets.iter().position(|le| value <= *le).unwrap_or(buckets.len());
+
+        let mut exemplars = self.exemplars.lock().unwrap();
+        let slots = exemplars
+            .entry((metric.to_string(), label_key(labels.iter().copied())))
+            .or_insert_with(|| vec![None; buckets.len() + 1]);
+
+        if index < slots.len() {
+            slots[index] = Some(Exemplar {
+                trace_id: trace_id.to_string(),
+                value,
+                timestamp: Utc::now().timestamp_millis() as f64 / 1000.0,
+            });
+        }
+    }
+
+    pub fn get(&self, metric: &str, labels: &[(&str, &str)], bucket_index: usize) -> Option<Exemplar> {
+        let exemplars = self.exemplars.lock().unwrap();
+        exemplars
+            .get(&(metric.to_string(), label_key(labels.iter().copied())))
+            .and_then(|slots| slots.get(bucket_index).cloned().flatten())
+    }
+}
+
+/// Canonical, order-independent key for a label set
+fn label_key<'a>(labels: impl Iterator<Item = (&'a str, &'a str)>) -> String {
+    let mut pairs: Vec<(&str, &str)> = labels.collect();
+    pairs.sort();
+    pairs
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn format_value(value: f64) -> String {
+    if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else if value.is_nan() {
+        "NaN".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn format_labels(labels: &[(String, String)], extra: Option<(&str, &str)>) -> String {
+    let mut parts: Vec<String> = labels
+        .iter()
+        .map(|(k, v)| format!("{}=\"{}\"", k, escape_label_value(v)))
+        .collect();
+    if let Some((k, v)) = extra {
+        parts.push(format!("{}=\"{}\"", k, escape_label_value(v)));
+    }
+
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", parts.join(","))
+    }
+}
+
+/// Encode metric families in the OpenMetrics text format, with exemplars
+pub fn encode_openmetrics(families: &[MetricFamily], exemplars: &ExemplarStore) -> String {
+    let mut out = String::new();
+
+    for family in families {
+        let name = family.name();
+        let (type_name, family_name) = match family.get_field_type() {
+            MetricType::COUNTER => ("counter", name.strip_suffix("_total").unwrap_or(name)),
+            MetricType::GAUGE => ("gauge", name),
+            MetricType::HISTOGRAM => ("histogram", name),
+            MetricType::SUMMARY => ("summary", name),
+            MetricType::UNTYPED => ("unknown", name),
+        };
+
+        let _ = writeln!(out, "# HELP {} {}", family_name, family.help().replace('\\', "\\\\").replace('\n', "\\n"));
+        let _ = writeln!(out, "# TYPE {} {}", family_name, type_name);
+
+        for metric in family.get_metric() {
+            let labels: Vec<(String, String)> = metric
+                .get_label()
+                .iter()
+                .map(|l| (l.name().to_string(), l.value().to_string()))
+                .collect();
+            let label_refs: Vec<(&str, &str)> = labels.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+
+            match family.get_field_type() {
+                MetricType::COUNTER => {
+                    let _ = writeln!(
+                        out,
+                        "{}_total{} {}",
+                        family_name,
+                        format_labels(&labels, None),
+                        format_value(metric.get_counter().value())
+                    );
+                }
+                MetricType::GAUGE => {
+                    let _ = writeln!(
+                        out,
+                        "{}{} {}",
+                        family_name,
+                        format_labels(&labels, None),
+                        format_value(metric.get_gauge().value())
+                    );
+                }
+                MetricType::HISTOGRAM => {
+                    let histogram = metric.get_histogram();
+                    let buckets = histogram.get_bucket();
+
+                    let mut lines: Vec<(String, u64)> = buckets
+                        .iter()
+                        .map(|b| (format_value(b.upper_bound()), b.cumulative_count()))
+                        .collect();
+                    lines.push(("+Inf".to_string(), histogram.get_sample_count()));
+
+                    for (index, (le, count)) in lines.iter().enumerate() {
+                        let _ = write!(
+                            out,
+                            "{}_bucket{} {}",
+                            family_name,
+                            format_labels(&labels, Some(("le", le))),
+                            count
+                        );
+                        if let Some(exemplar) = exemplars.get(name, &label_refs, index) {
+                            let _ = write!(
+                                out,
+                                " # {{trace_id=\"{}\"}} {} {}",
+                                escape_label_value(&exemplar.trace_id),
+                                format_value(exemplar.value),
+                                exemplar.timestamp
+                            );
+                        }
+                        out.push('\n');
+                    }
+
+                    let _ = writeln!(out, "{}_count{} {}", family_name, format_labels(&labels, None), histogram.get_sample_count());
+                    let _ = writeln!(out, "{}_sum{} {}", family_name, format_labels(&labels, None), format_value(histogram.get_sample_sum()));
+                }
+                MetricType::SUMMARY | MetricType::UNTYPED => {
+                    // Not used by this application
+                }
+            }
+        }
+    }
+
+    out.push_str("# EOF\n");
+    out
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::{HistogramOpts, HistogramVec, IntCounter, Registry};
+
+    #[test]
+    fn test_exemplar_lands_in_matching_bucket() {
+        let store = ExemplarStore::new();
+        let buckets = [0.1, 0.5, 1.0];
+
+        store.record("latency", &[("topic", "orders")], &buckets, 0.3, "trace-1");
+
+        assert!(store.get("latency", &[("topic", "orders")], 0).is_none());
+        assert_eq!(store.get("latency", &[("topic", "orders")], 1).unwrap().trace_id, "trace-1");
+    }
+
+    #[test]
+    fn test_exemplar_above_all_buckets_goes_to_inf() {
+        let store = ExemplarStore::new();
+        let buckets = [0.1, 0.5];
+
+        store.record("latency", &[], &buckets, 7.0, "slow");
+
+        assert_eq!(store.get("latency", &[], 2).unwrap().trace_id, "slow");
+    }
+
+    #[test]
+    fn test_label_order_does_not_matter() {
+        let store = ExemplarStore::new();
+        store.record("latency", &[("a", "1"), ("b", "2")], &[1.0], 0.5, "t");
+
+        assert!(store.get("latency", &[("b", "2"), ("a", "1")], 0).is_some());
+    }
+
+    #[test]
+    fn test_encode_openmetrics_with_exemplar() {
+        let registry = Registry::new();
+        let histogram = HistogramVec::new(
+            HistogramOpts::new("latency_seconds", "Latency").buckets(vec![0.1, 1.0]),
+            &["topic"],
+        ).unwrap();
+        registry.register(Box::new(histogram.clone())).unwrap();
+        let counter = IntCounter::new("requests_total", "Requests").unwrap();
+        registry.register(Box::new(counter.clone())).unwrap();
+
+        histogram.with_label_values(&["orders"]).observe(0.05);
+        counter.inc();
+
+        let store = ExemplarStore::new();
+        store.record("latency_seconds", &[("topic", "orders")], &[0.1, 1.0], 0.05, "abc123");
+
+        let text = encode_openmetrics(&registry.gather(), &store);
+
+        assert!(text.contains("# TYPE latency_seconds histogram"));
+        assert!(text.contains(r#"latency_seconds_bucket{topic="orders",le="0.1"} 1 # {trace_id="abc123"} 0.05"#));
+        assert!(text.contains(r#"latency_seconds_bucket{topic="orders",le="+Inf"} 1"#));
+        assert!(text.contains("# TYPE requests counter"));
+        assert!(text.contains("requests_total 1"));
+        assert!(text.ends_with("# EOF\n"));
+    }
+}
@@ -0,0 +1,304 @@
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+
+use super::{EventLabels, Metrics};
+
+// ============================================================================
+// Metrics Handle - Injected Recording Facade
+// ============================================================================
+//
+// Components record metrics through a MetricsHandle instead of holding
+// Option<Arc<Metrics>> and reaching into individual collectors:
+// - cheap to clone (one Arc), so every actor/task can keep its own copy
+// - defaults to a no-op recorder, so components work unwired (tests, CLI
+//   diagnostics) without `if let Some(metrics)` at every call site
+// - backed by the MetricsRecorder trait, so tests can inject a fake and
+//   assert on what was recorded instead of scraping the Prometheus registry
+//
+// The SystemBuilder hands the same handle to every component it builds.
+// Exposition (/metrics) still goes through the Metrics registry itself.
+//
+// Every recorder method has a no-op default: fakes implement only what
+// they assert on, and new methods don't break existing fakes.
+//
+// ============================================================================
+
+/// What components can record
+#[allow(unused_variables)]
+pub trait MetricsRecorder: Send + Sync {
+    fn record_cdc_event(&self, labels: &EventLabels<'_>, duration_secs: f64, success: bool, trace_id: Option<&str>) {}
+    fn record_retry_attempt(&self, operation: &str, attempt: u32) {}
+    fn record_retry_outcome(&self, operation: &str, success: bool) {}
+    fn record_dlq_message(&self, event_type: &str) {}
+    fn record_dlq_batch_write(&self, success: bool) {}
+    fn record_dlq_overflow(&self) {}
+    fn set_dlq_buffered(&self, pending: i64) {}
+    fn record_dlq_drained(&self, count: i64) {}
+    fn record_dlq_replay(&self, outcome: &str) {}
+    fn set_dlq_aged(&self, count: i64) {}
+    fn record_sequence_gaps(&self, outcome: &str, count: u64) {}
+    fn record_stream_audit(&self, outcome: &str, count: u64) {}
+    fn record_cdc_row(&self, source: &str, outcome: &str) {}
+    fn set_cdc_lag(&self, source: &str, lag_secs: f64) {}
+    fn update_circuit_breaker_state(&self, breaker: &str, state: u8) {}
+    fn record_circuit_breaker_transition(&self, breaker: &str, from_state: &str, to_state: &str) {}
+    fn set_circuit_breaker_failure_rate(&self, breaker: &str, rate: f64) {}
+    fn set_circuit_breaker_half_open_trials(&self, breaker: &str, trials: i64) {}
+    fn set_actor_queue_depth(&self, actor: &str, priority: &str, depth: i64) {}
+    fn record_actor_restart(&self, actor: &str, outcome: &str) {}
+    fn record_snapshots_pruned(&self, expired: u64, invalid: u64, reclaimed_bytes: u64) {}
+    fn record_http_request(&self, server: &str, route: &str, method: &str, status: u16, duration_secs: f64) {}
+    fn record_routing_evaluation(&self, rule: &str, matched: bool) {}
+    fn record_routing_route(&self, rule: &str, topic: &str, outcome: &str) {}
+    fn record_projection_staleness(&self, projection: &str, staleness_secs: f64, lag_secs: f64, sla_breached: bool) {}
+    fn record_lifecycle_hook(&self, hook: &str, stage: &str, outcome: &str) {}
+    fn record_integrity_findings(&self, orphaned_outbox: usize, missing_outbox: usize) {}
+    fn record_outbox_cleanup(&self, reclaimed: u64, unpublished: usize, pending: usize) {}
+    fn record_outbox_schedule(&self, released: u64, pending: usize) {}
+    fn record_event_store_append(&self, aggregate_type: &str, outcome: &str, events: usize) {}
+    fn record_publish(&self, topic: &str, origin: &str, success: bool) {}
+    fn record_publish_batch(&self, records: usize, flush_secs: f64) {}
+    fn record_publish_rate_limited(&self, scope: &str) {}
+    fn set_publish_rate(&self, scope: &str, rate: f64) {}
+    fn record_cdc_throttle(&self, delay_secs: f64, p95_secs: f64, error_rate: f64) {}
+}
+
+impl MetricsRecorder for Metrics {
+    fn record_cdc_event(&self, labels: &EventLabels<'_>, duration_secs: f64, success: bool, trace_id: Option<&str>) {
+        Metrics::record_cdc_event(self, labels, duration_secs, success, trace_id)
+    }
+
+    fn record_retry_attempt(&self, operation: &str, attempt: u32) {
+        Metrics::record_retry_attempt(self, operation, attempt)
+    }
+
+    fn record_retry_outcome(&self, operation: &str, success: bool) {
+        Metrics::record_retry_outcome(self, operation, success)
+    }
+
+    fn record_dlq_message(&self, event_type: &str) {
+        Metrics::record_dlq_message(self, event_type)
+    }
+
+    fn record_dlq_batch_write(&self, success: bool) {
+        Metrics::record_dlq_batch_write(self, success)
+    }
+
+    fn record_dlq_overflow(&self) {
+        self.dlq_overflow.inc()
+    }
+
+    fn set_dlq_buffered(&self, pending: i64) {
+        self.dlq_buffered.set(pending)
+    }
+
+    fn record_dlq_drained(&self, count: i64) {
+        self.dlq_buffered.sub(count)
+    }
+
+    fn record_dlq_replay(&self, outcome: &str) {
+        self.dlq_replays.with_label_values(&[outcome]).inc()
+    }
+
+    fn set_dlq_aged(&self, count: i64) {
+        self.dlq_aged.set(count)
+    }
+
+    fn record_sequence_gaps(&self, outcome: &str, count: u64) {
+        self.cdc_sequence_gaps.with_label_values(&[outcome]).inc_by(count)
+    }
+
+    fn record_stream_audit(&self, outcome: &str, count: u64) {
+        self.stream_audit_events.with_label_values(&[outcome]).inc_by(count)
+    }
+
+    fn record_cdc_row(&self, source: &str, outcome: &str) {
+        self.cdc_rows.with_label_values(&[source, outcome]).inc()
+    }
+
+    fn set_cdc_lag(&self, source: &str, lag_secs: f64) {
+        self.cdc_lag_seconds.with_label_values(&[source]).set(lag_secs)
+    }
+
+    fn update_circuit_breaker_state(&self, breaker: &str, state: u8) {
+        Metrics::update_circuit_breaker_state(self, breaker, state)
+    }
+
+    fn record_circuit_breaker_transition(&self, breaker: &str, from_state: &str, to_state: &str) {
+        Metrics::record_circuit_breaker_transition(self, breaker, from_state, to_state)
+    }
+
+    fn set_circuit_breaker_failure_rate(&self, breaker: &str, rate: f64) {
+        Metrics::set_circuit_breaker_failure_rate(self, breaker, rate)
+    }
+
+    fn set_circuit_breaker_half_open_trials(&self, breaker: &str, trials: i64) {
+        Metrics::set_circuit_breaker_half_open_trials(self, breaker, trials)
+    }
+
+    fn set_actor_queue_depth(&self, actor: &str, priority: &str, depth: i64) {
+        Metrics::set_actor_queue_depth(self, actor, priority, depth)
+    }
+
+    fn record_actor_restart(&self, actor: &str, outcome: &str) {
+        Metrics::record_actor_restart(self, actor, outcome)
+    }
+
+    fn record_snapshots_pruned(&self, expired: u64, invalid: u64, reclaimed_bytes: u64) {
+        Metrics::record_snapshots_pruned(self, expired, invalid, reclaimed_bytes)
+    }
+
+    fn record_http_request(&self, server: &str, route: &str, method: &str, status: u16, duration_secs: f64) {
+        Metrics::record_http_request(self, server, route, method, status, duration_secs)
+    }
+
+    fn record_routing_evaluation(&self, rule: &str, matched: bool) {
+        Metrics::record_routing_evaluation(self, rule, matched)
+    }
+
+    fn record_routing_route(&self, rule: &str, topic: &str, outcome: &str) {
+        Metrics::record_routing_route(self, rule, topic, outcome)
+    }
+
+    fn record_projection_staleness(&self, projection: &str, staleness_secs: f64, lag_secs: f64, sla_breached: bool) {
+        Metrics::record_projection_staleness(self, projection, staleness_secs, lag_secs, sla_breached)
+    }
+
+    fn record_lifecycle_hook(&self, hook: &str, stage: &str, outcome: &str) {
+        Metrics::record_lifecycle_hook(self, hook, stage, outcome)
+    }
+
+    fn record_integrity_findings(&self, orphaned_outbox: usize, missing_outbox: usize) {
+        Metrics::record_integrity_findings(self, orphaned_outbox, missing_outbox)
+    }
+
+    fn record_outbox_cleanup(&self, reclaimed: u64, unpublished: usize, pending: usize) {
+        Metrics::record_outbox_cleanup(self, reclaimed, unpublished, pending)
+    }
+
+    fn record_outbox_schedule(&self, released: u64, pending: usize) {
+        Metrics::record_outbox_schedule(self, released, pending)
+    }
+
+    fn record_event_store_append(&self, aggregate_type: &str, outcome: &str, events: usize) {
+        Metrics::record_event_store_append(self, aggregate_type, outcome, events)
+    }
+
+    fn record_publish(&self, topic: &str, origin: &str, success: bool) {
+        Metrics::record_publish(self, topic, origin, success)
+    }
+
+    fn record_publish_batch(&self, records: usize, flush_secs: f64) {
+        Metrics::record_publish_batch(self, records, flush_secs)
+    }
+
+    fn record_publish_rate_limited(&self, scope: &str) {
+        Metrics::record_publish_rate_limited(self, scope)
+    }
+
+    fn set_publish_rate(&self, scope: &str, rate: f64) {
+        Metrics::set_publish_rate(self, scope, rate)
+    }
+
+    fn record_cdc_throttle(&self, delay_secs: f64, p95_secs: f64, error_rate: f64) {
+        Metrics::record_cdc_throttle(self, delay_secs, p95_secs, error_rate)
+    }
+}
+
+/// Records nothing - the default of unwired components
+pub struct NoopMetrics;
+
+impl MetricsRecorder for NoopMetrics {}
+
+/// Cloneable handle components record metrics through
+#[derive(Clone)]
+pub struct MetricsHandle {
+    recorder: Arc<dyn MetricsRecorder>,
+}
+
+impl MetricsHandle {
+    pub fn new(recorder: Arc<dyn MetricsRecorder>) -> Self {
+        Self { recorder }
+    }
+
+    pub fn noop() -> Self {
+        Self::new(Arc::new(NoopMetrics))
+    }
+}
+
+impl From<Arc<Metrics>> for MetricsHandle {
+    fn from(metrics: Arc<Metrics>) -> Self {
+        Self::new(metrics)
+    }
+}
+
+impl Default for MetricsHandle {
+    fn default() -> Self {
+        Self::noop()
+    }
+}
+
+impl Deref for MetricsHandle {
+    type Target = dyn MetricsRecorder;
+
+    fn deref(&self) -> &Self::Target {
+        self.recorder.as_ref()
+    }
+}
+
+impl fmt::Debug for MetricsHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MetricsHandle")
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Fake implementing only what the test asserts on
+    #[derive(Default)]
+    struct RecordedLifecycleHooks(Mutex<Vec<(String, String, String)>>);
+
+    impl MetricsRecorder for RecordedLifecycleHooks {
+        fn record_lifecycle_hook(&self, hook: &str, stage: &str, outcome: &str) {
+            self.0.lock().unwrap().push((hook.to_string(), stage.to_string(), outcome.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_handle_forwards_to_injected_recorder() {
+        let fake = Arc::new(RecordedLifecycleHooks::default());
+        let handle = MetricsHandle::new(fake.clone());
+
+        handle.clone().record_lifecycle_hook("provisioner", "created", "success");
+        // Not implemented by the fake - no-op
+        handle.record_dlq_overflow();
+
+        assert_eq!(
+            *fake.0.lock().unwrap(),
+            vec![("provisioner".to_string(), "created".to_string(), "success".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_prometheus_backed_handle() {
+        let metrics = Arc::new(Metrics::new().unwrap());
+        let handle = MetricsHandle::from(metrics.clone());
+
+        handle.record_sequence_gaps("detected", 2);
+        handle.set_dlq_buffered(5);
+        handle.record_dlq_drained(3);
+
+        assert_eq!(metrics.cdc_sequence_gaps.with_label_values(&["detected"]).get(), 2);
+        assert_eq!(metrics.dlq_buffered.get(), 2);
+        // Unwired components record into the void
+        MetricsHandle::default().record_dlq_overflow();
+    }
+}
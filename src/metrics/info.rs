@@ -0,0 +1,142 @@
+use chrono::{DateTime, Utc};
+use scylla::client::session::Session;
+use serde::Serialize;
+use anyhow::{Result, anyhow};
+
+use crate::messaging::{BrokerInfo, RedpandaClient};
+
+// ============================================================================
+// Service Info - Build and Dependency Metadata for /info
+// ============================================================================
+//
+// When something misbehaves on one instance of a fleet, the first question
+// is "what exactly is running there?". /info answers it:
+// - crate version, git SHA and build timestamp (set by build.rs)
+// - Scylla cluster name and release version (from system.local)
+// - Kafka/Redpanda brokers and client library version
+//
+// Dependency details are collected once at startup; a dependency that
+// cannot be reached is reported as null rather than failing startup.
+//
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub git_sha: &'static str,
+    pub build_timestamp: Option<DateTime<Utc>>,
+}
+
+impl BuildInfo {
+    /// Metadata of the running binary
+    pub fn current() -> Self {
+        let build_timestamp = option_env!("BUILD_TIMESTAMP")
+            .and_then(|ts| ts.parse::<i64>().ok())
+            .and_then(|secs| DateTime::from_timestamp(secs, 0));
+
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            git_sha: option_env!("BUILD_GIT_SHA").unwrap_or("unknown"),
+            build_timestamp,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScyllaInfo {
+    pub cluster_name: String,
+    pub release_version: String,
+}
+
+impl ScyllaInfo {
+    pub async fn fetch(session: &Session) -> Result<Self> {
+        let result = session
+            .query_unpaged("SELECT cluster_name, release_version FROM system.local", &[])
+            .await?;
+
+        let (cluster_name, release_version) = result
+            .into_rows_result()?
+            .maybe_first_row::<(String, String)>()?
+            .ok_or_else(|| anyhow!("system.local returned no rows"))?;
+
+        Ok(Self { cluster_name, release_version })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServiceInfo {
+    pub service: &'static str,
+    pub build: BuildInfo,
+    pub scylla: Option<ScyllaInfo>,
+    pub kafka: Option<BrokerInfo>,
+    pub started_at: DateTime<Utc>,
+}
+
+impl ServiceInfo {
+    /// Build info only, without dependency details
+    pub fn new() -> Self {
+        Self {
+            service: "scylladb-cdc-outbox",
+            build: BuildInfo::current(),
+            scylla: None,
+            kafka: None,
+            started_at: Utc::now(),
+        }
+    }
+
+    /// Collect build info plus Scylla and broker details
+    pub async fn collect(session: &Session, redpanda: &RedpandaClient) -> Self {
+        let mut info = Self::new();
+
+        match ScyllaInfo::fetch(session).await {
+            Ok(scylla) => info.scylla = Some(scylla),
+            Err(e) => tracing::warn!(error = %e, "Could not read Scylla cluster info"),
+        }
+
+        match redpanda.broker_info().await {
+            Ok(kafka) => info.kafka = Some(kafka),
+            Err(e) => tracing::warn!(error = %e, "Could not read broker info"),
+        }
+
+        tracing::info!(
+            version = info.build.version,
+            git_sha = info.build.git_sha,
+            scylla_cluster = ?info.scylla.as_ref().map(|s| &s.cluster_name),
+            scylla_version = ?info.scylla.as_ref().map(|s| &s.release_version),
+            "ℹ️  Service info collected"
+        );
+
+        info
+    }
+}
+
+impl Default for ServiceInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_info_has_crate_version() {
+        let build = BuildInfo::current();
+        assert_eq!(build.version, env!("CARGO_PKG_VERSION"));
+        assert!(!build.git_sha.is_empty());
+    }
+
+    #[test]
+    fn test_service_info_serializes_missing_dependencies_as_null() {
+        let json = serde_json::to_value(ServiceInfo::new()).unwrap();
+
+        assert!(json["scylla"].is_null());
+        assert!(json["kafka"].is_null());
+        assert_eq!(json["build"]["version"], env!("CARGO_PKG_VERSION"));
+    }
+}
@@ -1,13 +1,25 @@
 // Private module declaration
 mod server;
+mod exemplars;
+mod info;
+mod access_log;
+mod handle;
+#[cfg(feature = "otel")]
+mod otel;
 
 use prometheus::{
-    HistogramOpts, HistogramVec, IntCounter, IntCounterVec,
-    IntGauge, Opts, Registry,
+    Gauge, GaugeVec, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec,
+    IntGauge, IntGaugeVec, Opts, Registry,
 };
 
 // Re-export for public API
 pub use server::start_metrics_server;
+pub use exemplars::{ExemplarStore, Exemplar, encode_openmetrics, OPENMETRICS_CONTENT_TYPE};
+pub use info::{BuildInfo, ScyllaInfo, ServiceInfo};
+pub use access_log::{AccessLog, CorrelationId, CommandType};
+pub use handle::{MetricsHandle, MetricsRecorder, NoopMetrics};
+#[cfg(feature = "otel")]
+pub use otel::OtlpExporter;
 
 // ============================================================================
 // Metrics Module - Prometheus metrics for observability
@@ -19,19 +31,60 @@ pub use server::start_metrics_server;
 // - Dead Letter Queue statistics
 // - Circuit breaker state transitions
 // - Actor health status
+// - Build info (version, git SHA) as a constant gauge
+// - HTTP requests per server/route (recorded by the AccessLog middleware)
+// - Read model staleness per projection (against its SLA)
+// - Aggregate lifecycle hook runs
+// - Event store appends and publishes per topic, publish batch sizes and flush latency
+// - Publish rate limiting (delayed publishes and measured rate per scope)
+// - Outbox rows reclaimed by the janitor, and rows never confirmed published
 //
-// All metrics are registered with Prometheus and can be scraped via /metrics
+// With the `otel` feature, OtlpExporter (otel.rs) exports tracing spans to
+// an OpenTelemetry collector.
+//
+// All metrics are registered with Prometheus and can be scraped via /metrics.
+// Components record through a MetricsHandle (see handle.rs), not through
+// the collectors directly.
+//
+// Histograms are labelled by event_type, aggregate_type and topic - all
+// bounded sets. Observations may carry a trace ID, exposed as OpenMetrics
+// exemplars when the scraper requests that format.
 // ============================================================================
 
+/// Bucket bounds for cdc_processing_duration_seconds
+const CDC_DURATION_BUCKETS: &[f64] = &[0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0];
+
+/// Bucket bounds for http_request_duration_seconds
+const HTTP_DURATION_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5];
+
+/// Bucket bounds for redpanda_publish_batch_size
+const PUBLISH_BATCH_SIZE_BUCKETS: &[f64] = &[1.0, 5.0, 10.0, 50.0, 100.0, 250.0, 500.0, 1000.0];
+
+/// Labels attached to per-event metrics
+#[derive(Debug, Clone, Copy)]
+pub struct EventLabels<'a> {
+    pub event_type: &'a str,
+    pub aggregate_type: &'a str,
+    pub topic: &'a str,
+}
+
 /// Central metrics registry for the entire application
 #[allow(dead_code)]
 pub struct Metrics {
     registry: Registry,
+    exemplars: ExemplarStore,
+
+    // Build Metrics
+    pub build_info: IntGaugeVec,
 
     // CDC Processing Metrics
     pub cdc_events_processed: IntCounterVec,
     pub cdc_events_failed: IntCounterVec,
     pub cdc_processing_duration: HistogramVec,
+    pub cdc_sequence_gaps: IntCounterVec,
+    pub cdc_rows: IntCounterVec,
+    pub cdc_lag_seconds: GaugeVec,
+    pub stream_audit_events: IntCounterVec,
 
     // Retry Metrics
     pub retry_attempts_total: IntCounterVec,
@@ -41,15 +94,70 @@ pub struct Metrics {
     // DLQ Metrics
     pub dlq_messages_total: IntCounter,
     pub dlq_messages_by_event_type: IntCounterVec,
+    pub dlq_buffered: IntGauge,
+    pub dlq_batch_writes: IntCounterVec,
+    pub dlq_overflow: IntCounter,
+    pub dlq_replays: IntCounterVec,
+    pub dlq_aged: IntGauge,
 
     // Circuit Breaker Metrics
-    pub circuit_breaker_state: IntGauge,
+    pub circuit_breaker_state: IntGaugeVec,
     pub circuit_breaker_transitions: IntCounterVec,
+    pub circuit_breaker_failure_rate: GaugeVec,
+    pub circuit_breaker_half_open_trials: IntGaugeVec,
 
     // Actor Metrics
     pub actor_health_status: IntGauge,
     pub messages_sent: IntCounterVec,
     pub messages_received: IntCounterVec,
+    pub actor_queue_depth: IntGaugeVec,
+    pub actor_restarts: IntCounterVec,
+
+    // Snapshot Metrics
+    pub snapshots_pruned: IntCounterVec,
+    pub snapshot_bytes_reclaimed: IntCounter,
+
+    // HTTP Metrics
+    pub http_requests: IntCounterVec,
+    pub http_request_duration: HistogramVec,
+
+    // Routing Rule Metrics
+    pub routing_rule_evaluations: IntCounterVec,
+    pub routing_rule_routed: IntCounterVec,
+
+    // Projection Metrics
+    pub projection_staleness_seconds: GaugeVec,
+    pub projection_lag_seconds: GaugeVec,
+    pub projection_sla_breached: IntGaugeVec,
+
+    // Lifecycle Hook Metrics
+    pub lifecycle_hook_runs: IntCounterVec,
+
+    // Integrity Check Metrics
+    pub integrity_orphans: IntGaugeVec,
+
+    // Outbox Janitor Metrics
+    pub outbox_rows_reclaimed: IntCounter,
+    pub outbox_unpublished_rows: IntGauge,
+    pub outbox_pending_rows: IntGauge,
+
+    // Outbox Scheduler Metrics
+    pub outbox_scheduled_pending: IntGauge,
+    pub outbox_scheduled_released: IntCounter,
+
+    // Event Store / Publisher Metrics
+    pub event_store_appends: IntCounterVec,
+    pub event_store_events_appended: IntCounterVec,
+    pub publishes: IntCounterVec,
+    pub publish_batch_size: Histogram,
+    pub publish_batch_flush_seconds: Histogram,
+    pub publish_rate_limited: IntCounterVec,
+    pub publish_rate: GaugeVec,
+
+    // CDC Throttle Metrics
+    pub cdc_throttle_delay_seconds: Gauge,
+    pub scylla_latency_p95_seconds: Gauge,
+    pub scylla_error_rate: Gauge,
 }
 
 impl Metrics {
@@ -57,6 +165,15 @@ impl Metrics {
     pub fn new() -> anyhow::Result<Self> {
         let registry = Registry::new();
 
+        // Build Metrics - constant 1, labelled with what is running
+        let build_info = IntGaugeVec::new(
+            Opts::new("build_info", "Build metadata of the running binary"),
+            &["version", "git_sha"],
+        )?;
+        registry.register(Box::new(build_info.clone()))?;
+        let build = BuildInfo::current();
+        build_info.with_label_values(&[build.version, build.git_sha]).set(1);
+
         // CDC Processing Metrics
         let cdc_events_processed = IntCounterVec::new(
             Opts::new("cdc_events_processed_total", "Total CDC events processed"),
@@ -72,11 +189,38 @@ impl Metrics {
 
         let cdc_processing_duration = HistogramVec::new(
             HistogramOpts::new("cdc_processing_duration_seconds", "CDC event processing duration")
-                .buckets(vec![0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0]),
-            &["event_type"],
+                .buckets(CDC_DURATION_BUCKETS.to_vec()),
+            &["event_type", "aggregate_type", "topic"],
         )?;
         registry.register(Box::new(cdc_processing_duration.clone()))?;
 
+        let cdc_sequence_gaps = IntCounterVec::new(
+            Opts::new("cdc_sequence_gaps_total", "Missing aggregate sequences at publish time by outcome"),
+            &["outcome"],
+        )?;
+        registry.register(Box::new(cdc_sequence_gaps.clone()))?;
+
+        let stream_audit_events = IntCounterVec::new(
+            Opts::new(
+                "stream_audit_events_total",
+                "Published events by stream audit outcome; `missing` counts skipped sequences",
+            ),
+            &["outcome"],
+        )?;
+        registry.register(Box::new(stream_audit_events.clone()))?;
+
+        let cdc_rows = IntCounterVec::new(
+            Opts::new("cdc_rows_total", "CDC rows consumed per observed table by outcome"),
+            &["source", "outcome"],
+        )?;
+        registry.register(Box::new(cdc_rows.clone()))?;
+
+        let cdc_lag_seconds = GaugeVec::new(
+            Opts::new("cdc_lag_seconds", "Seconds between writing and relaying the last outbox row per observed table"),
+            &["source"],
+        )?;
+        registry.register(Box::new(cdc_lag_seconds.clone()))?;
+
         // Retry Metrics
         let retry_attempts_total = IntCounterVec::new(
             Opts::new("retry_attempts_total", "Total retry attempts"),
@@ -109,19 +253,64 @@ impl Metrics {
         )?;
         registry.register(Box::new(dlq_messages_by_event_type.clone()))?;
 
+        let dlq_buffered = IntGauge::new(
+            "dlq_buffered_messages",
+            "DLQ messages buffered or being written",
+        )?;
+        registry.register(Box::new(dlq_buffered.clone()))?;
+
+        let dlq_batch_writes = IntCounterVec::new(
+            Opts::new("dlq_batch_writes_total", "DLQ batch writes by outcome"),
+            &["outcome"],
+        )?;
+        registry.register(Box::new(dlq_batch_writes.clone()))?;
+
+        let dlq_overflow = IntCounter::new(
+            "dlq_overflow_total",
+            "DLQ messages rejected because the write buffer was full",
+        )?;
+        registry.register(Box::new(dlq_overflow.clone()))?;
+
+        let dlq_replays = IntCounterVec::new(
+            Opts::new("dlq_replays_total", "DLQ replays by outcome (published, failed, quarantined)"),
+            &["outcome"],
+        )?;
+        registry.register(Box::new(dlq_replays.clone()))?;
+
+        let dlq_aged = IntGauge::new(
+            "dlq_aged_messages",
+            "DLQ messages failing for longer than the aging threshold",
+        )?;
+        registry.register(Box::new(dlq_aged.clone()))?;
+
         // Circuit Breaker Metrics
-        let circuit_breaker_state = IntGauge::new(
-            "circuit_breaker_state",
-            "Circuit breaker state (0=Closed, 1=Open, 2=HalfOpen)",
+        let circuit_breaker_state = IntGaugeVec::new(
+            Opts::new("circuit_breaker_state", "Circuit breaker state (0=Closed, 1=Open, 2=HalfOpen)"),
+            &["breaker"],
         )?;
         registry.register(Box::new(circuit_breaker_state.clone()))?;
 
         let circuit_breaker_transitions = IntCounterVec::new(
             Opts::new("circuit_breaker_transitions_total", "Circuit breaker state transitions"),
-            &["from_state", "to_state"],
+            &["breaker", "from_state", "to_state"],
         )?;
         registry.register(Box::new(circuit_breaker_transitions.clone()))?;
 
+        let circuit_breaker_failure_rate = GaugeVec::new(
+            Opts::new(
+                "circuit_breaker_failure_rate",
+                "Failed share of the calls in the circuit breaker's rolling window",
+            ),
+            &["breaker"],
+        )?;
+        registry.register(Box::new(circuit_breaker_failure_rate.clone()))?;
+
+        let circuit_breaker_half_open_trials = IntGaugeVec::new(
+            Opts::new("circuit_breaker_half_open_trials", "Trial calls running while the circuit breaker is half-open"),
+            &["breaker"],
+        )?;
+        registry.register(Box::new(circuit_breaker_half_open_trials.clone()))?;
+
         // Actor Metrics
         let actor_health_status = IntGauge::new(
             "actor_health_status",
@@ -141,21 +330,241 @@ impl Metrics {
         )?;
         registry.register(Box::new(messages_received.clone()))?;
 
+        let actor_queue_depth = IntGaugeVec::new(
+            Opts::new("actor_queue_depth", "Messages waiting in an actor's priority mailbox"),
+            &["actor", "priority"],
+        )?;
+        registry.register(Box::new(actor_queue_depth.clone()))?;
+
+        let actor_restarts = IntCounterVec::new(
+            Opts::new("actor_restarts_total", "Failed child actors restarted (or given up on) by their supervisor"),
+            &["actor", "outcome"],
+        )?;
+        registry.register(Box::new(actor_restarts.clone()))?;
+
+        // Snapshot Metrics
+        let snapshots_pruned = IntCounterVec::new(
+            Opts::new("snapshots_pruned_total", "Snapshots removed by the pruner"),
+            &["reason"],
+        )?;
+        registry.register(Box::new(snapshots_pruned.clone()))?;
+
+        let snapshot_bytes_reclaimed = IntCounter::new(
+            "snapshot_bytes_reclaimed_total",
+            "Snapshot payload bytes reclaimed by pruning",
+        )?;
+        registry.register(Box::new(snapshot_bytes_reclaimed.clone()))?;
+
+        // HTTP Metrics
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests by server, route, method and status"),
+            &["server", "route", "method", "status"],
+        )?;
+        registry.register(Box::new(http_requests.clone()))?;
+
+        let http_request_duration = HistogramVec::new(
+            HistogramOpts::new("http_request_duration_seconds", "HTTP request latency")
+                .buckets(HTTP_DURATION_BUCKETS.to_vec()),
+            &["server", "route", "method"],
+        )?;
+        registry.register(Box::new(http_request_duration.clone()))?;
+
+        // Routing Rule Metrics
+        let routing_rule_evaluations = IntCounterVec::new(
+            Opts::new("routing_rule_evaluations_total", "Routing rule evaluations by outcome"),
+            &["rule", "outcome"],
+        )?;
+        registry.register(Box::new(routing_rule_evaluations.clone()))?;
+
+        let routing_rule_routed = IntCounterVec::new(
+            Opts::new("routing_rule_routed_total", "Event copies routed to additional topics by outcome"),
+            &["rule", "topic", "outcome"],
+        )?;
+        registry.register(Box::new(routing_rule_routed.clone()))?;
+
+        // Projection Metrics
+        let projection_staleness_seconds = GaugeVec::new(
+            Opts::new("projection_staleness_seconds", "Seconds a read model has been behind the event store (0 when caught up)"),
+            &["projection"],
+        )?;
+        registry.register(Box::new(projection_staleness_seconds.clone()))?;
+
+        let projection_lag_seconds = GaugeVec::new(
+            Opts::new("projection_lag_seconds", "Newest stored event time minus last applied event time"),
+            &["projection"],
+        )?;
+        registry.register(Box::new(projection_lag_seconds.clone()))?;
+
+        let projection_sla_breached = IntGaugeVec::new(
+            Opts::new("projection_sla_breached", "1 when staleness exceeds the projection's SLA"),
+            &["projection"],
+        )?;
+        registry.register(Box::new(projection_sla_breached.clone()))?;
+
+        // Lifecycle Hook Metrics
+        let lifecycle_hook_runs = IntCounterVec::new(
+            Opts::new("lifecycle_hook_runs_total", "Aggregate lifecycle hook runs by outcome (after retries)"),
+            &["hook", "stage", "outcome"],
+        )?;
+        registry.register(Box::new(lifecycle_hook_runs.clone()))?;
+
+        // Integrity Check Metrics
+        let integrity_orphans = IntGaugeVec::new(
+            Opts::new("integrity_orphans", "Outbox/event_store rows without counterpart in the last integrity check"),
+            &["kind"],
+        )?;
+        registry.register(Box::new(integrity_orphans.clone()))?;
+
+        // Outbox Janitor Metrics
+        let outbox_rows_reclaimed = IntCounter::new(
+            "outbox_rows_reclaimed_total",
+            "Published outbox rows deleted by the janitor after retention",
+        )?;
+        registry.register(Box::new(outbox_rows_reclaimed.clone()))?;
+
+        let outbox_unpublished_rows = IntGauge::new(
+            "outbox_unpublished_rows",
+            "Outbox rows past retention never confirmed published (last janitor pass)",
+        )?;
+        registry.register(Box::new(outbox_unpublished_rows.clone()))?;
+
+        let outbox_pending_rows = IntGauge::new(
+            "outbox_pending_rows",
+            "Outbox rows not yet confirmed published, of any age (last janitor pass)",
+        )?;
+        registry.register(Box::new(outbox_pending_rows.clone()))?;
+
+        // Outbox Scheduler Metrics
+        let outbox_scheduled_pending = IntGauge::new(
+            "outbox_scheduled_pending",
+            "Due scheduled outbox events that failed to release (last scheduler pass)",
+        )?;
+        registry.register(Box::new(outbox_scheduled_pending.clone()))?;
+
+        let outbox_scheduled_released = IntCounter::new(
+            "outbox_scheduled_released_total",
+            "Scheduled outbox events handed back to the relay once due",
+        )?;
+        registry.register(Box::new(outbox_scheduled_released.clone()))?;
+
+        // Event Store / Publisher Metrics
+        let event_store_appends = IntCounterVec::new(
+            Opts::new("event_store_appends_total", "Event store appends by outcome (appended, conflict, failed)"),
+            &["aggregate_type", "outcome"],
+        )?;
+        registry.register(Box::new(event_store_appends.clone()))?;
+
+        let event_store_events_appended = IntCounterVec::new(
+            Opts::new("event_store_events_appended_total", "Events written to the event store"),
+            &["aggregate_type"],
+        )?;
+        registry.register(Box::new(event_store_events_appended.clone()))?;
+
+        let publishes = IntCounterVec::new(
+            Opts::new("redpanda_publishes_total", "Messages published to Redpanda by origin (outbox_cdc, direct)"),
+            &["topic", "origin", "outcome"],
+        )?;
+        registry.register(Box::new(publishes.clone()))?;
+
+        let publish_batch_size = Histogram::with_opts(
+            HistogramOpts::new("redpanda_publish_batch_size", "Records per flushed publish batch")
+                .buckets(PUBLISH_BATCH_SIZE_BUCKETS.to_vec()),
+        )?;
+        registry.register(Box::new(publish_batch_size.clone()))?;
+
+        let publish_batch_flush_seconds = Histogram::with_opts(
+            HistogramOpts::new("redpanda_publish_batch_flush_seconds", "Time until every record of a batch was delivered")
+                .buckets(CDC_DURATION_BUCKETS.to_vec()),
+        )?;
+        registry.register(Box::new(publish_batch_flush_seconds.clone()))?;
+
+        let publish_rate_limited = IntCounterVec::new(
+            Opts::new("redpanda_publish_rate_limited_total", "Publishes delayed by a rate limit (global or topic)"),
+            &["scope"],
+        )?;
+        registry.register(Box::new(publish_rate_limited.clone()))?;
+
+        let publish_rate = GaugeVec::new(
+            Opts::new("redpanda_publish_rate", "Publishes per second under a rate limit (global or topic)"),
+            &["scope"],
+        )?;
+        registry.register(Box::new(publish_rate.clone()))?;
+
+        // CDC Throttle Metrics
+        let cdc_throttle_delay_seconds = Gauge::new(
+            "cdc_throttle_delay_seconds",
+            "Delay applied before each CDC row (0 = not throttled)",
+        )?;
+        registry.register(Box::new(cdc_throttle_delay_seconds.clone()))?;
+
+        let scylla_latency_p95_seconds = Gauge::new(
+            "scylla_latency_p95_seconds",
+            "p95 latency of our own Scylla queries over the monitor window",
+        )?;
+        registry.register(Box::new(scylla_latency_p95_seconds.clone()))?;
+
+        let scylla_error_rate = Gauge::new(
+            "scylla_error_rate",
+            "Share of failed Scylla queries over the monitor window",
+        )?;
+        registry.register(Box::new(scylla_error_rate.clone()))?;
+
         Ok(Self {
             registry,
+            exemplars: ExemplarStore::new(),
+            build_info,
             cdc_events_processed,
             cdc_events_failed,
             cdc_processing_duration,
+            cdc_sequence_gaps,
+            cdc_rows,
+            cdc_lag_seconds,
+            stream_audit_events,
             retry_attempts_total,
             retry_success,
             retry_failure,
             dlq_messages_total,
             dlq_messages_by_event_type,
+            dlq_buffered,
+            dlq_batch_writes,
+            dlq_overflow,
+            dlq_replays,
+            dlq_aged,
             circuit_breaker_state,
             circuit_breaker_transitions,
+            circuit_breaker_failure_rate,
+            circuit_breaker_half_open_trials,
             actor_health_status,
             messages_sent,
             messages_received,
+            actor_queue_depth,
+            actor_restarts,
+            snapshots_pruned,
+            snapshot_bytes_reclaimed,
+            http_requests,
+            http_request_duration,
+            routing_rule_evaluations,
+            routing_rule_routed,
+            projection_staleness_seconds,
+            projection_lag_seconds,
+            projection_sla_breached,
+            lifecycle_hook_runs,
+            integrity_orphans,
+            outbox_rows_reclaimed,
+            outbox_unpublished_rows,
+            outbox_pending_rows,
+            outbox_scheduled_pending,
+            outbox_scheduled_released,
+            event_store_appends,
+            event_store_events_appended,
+            publishes,
+            publish_batch_size,
+            publish_batch_flush_seconds,
+            publish_rate_limited,
+            publish_rate,
+            cdc_throttle_delay_seconds,
+            scylla_latency_p95_seconds,
+            scylla_error_rate,
         })
     }
 
@@ -164,14 +573,38 @@ impl Metrics {
         &self.registry
     }
 
+    /// Exemplars recorded alongside histogram observations
+    pub fn exemplars(&self) -> &ExemplarStore {
+        &self.exemplars
+    }
+
     /// Helper to record CDC event processing
-    pub fn record_cdc_event(&self, event_type: &str, duration_secs: f64, success: bool) {
+    ///
+    /// When a trace ID is given it is kept as the exemplar for the bucket
+    /// the observation falls into.
+    pub fn record_cdc_event(&self, labels: &EventLabels<'_>, duration_secs: f64, success: bool, trace_id: Option<&str>) {
         if success {
-            self.cdc_events_processed.with_label_values(&[event_type]).inc();
+            self.cdc_events_processed.with_label_values(&[labels.event_type]).inc();
         } else {
-            self.cdc_events_failed.with_label_values(&[event_type, "processing_error"]).inc();
+            self.cdc_events_failed.with_label_values(&[labels.event_type, "processing_error"]).inc();
+        }
+
+        let label_values = [labels.event_type, labels.aggregate_type, labels.topic];
+        self.cdc_processing_duration.with_label_values(&label_values).observe(duration_secs);
+
+        if let Some(trace_id) = trace_id {
+            self.exemplars.record(
+                "cdc_processing_duration_seconds",
+                &[
+                    ("event_type", labels.event_type),
+                    ("aggregate_type", labels.aggregate_type),
+                    ("topic", labels.topic),
+                ],
+                CDC_DURATION_BUCKETS,
+                duration_secs,
+                trace_id,
+            );
         }
-        self.cdc_processing_duration.with_label_values(&[event_type]).observe(duration_secs);
     }
 
     /// Helper to record retry attempt
@@ -194,14 +627,136 @@ impl Metrics {
         self.dlq_messages_by_event_type.with_label_values(&[event_type]).inc();
     }
 
+    /// Helper to record a DLQ batch write
+    pub fn record_dlq_batch_write(&self, success: bool) {
+        let outcome = if success { "success" } else { "failure" };
+        self.dlq_batch_writes.with_label_values(&[outcome]).inc();
+    }
+
     /// Helper to update circuit breaker state
-    pub fn update_circuit_breaker_state(&self, state: u8) {
-        self.circuit_breaker_state.set(state as i64);
+    pub fn update_circuit_breaker_state(&self, breaker: &str, state: u8) {
+        self.circuit_breaker_state.with_label_values(&[breaker]).set(state as i64);
     }
 
     /// Helper to record circuit breaker transition
-    pub fn record_circuit_breaker_transition(&self, from_state: &str, to_state: &str) {
-        self.circuit_breaker_transitions.with_label_values(&[from_state, to_state]).inc();
+    pub fn record_circuit_breaker_transition(&self, breaker: &str, from_state: &str, to_state: &str) {
+        self.circuit_breaker_transitions.with_label_values(&[breaker, from_state, to_state]).inc();
+    }
+
+    /// Helper to update the circuit breaker's rolling-window failure rate
+    pub fn set_circuit_breaker_failure_rate(&self, breaker: &str, rate: f64) {
+        self.circuit_breaker_failure_rate.with_label_values(&[breaker]).set(rate);
+    }
+
+    /// Helper to update the trial calls running in HalfOpen
+    pub fn set_circuit_breaker_half_open_trials(&self, breaker: &str, trials: i64) {
+        self.circuit_breaker_half_open_trials.with_label_values(&[breaker]).set(trials);
+    }
+
+    /// Helper to update the queue depth of a prioritized actor mailbox
+    pub fn set_actor_queue_depth(&self, actor: &str, priority: &str, depth: i64) {
+        self.actor_queue_depth.with_label_values(&[actor, priority]).set(depth);
+    }
+
+    /// Helper to record a supervisor restarting (or giving up on) a child actor
+    pub fn record_actor_restart(&self, actor: &str, outcome: &str) {
+        self.actor_restarts.with_label_values(&[actor, outcome]).inc();
+    }
+
+    /// Helper to record a snapshot pruning pass for one aggregate
+    pub fn record_snapshots_pruned(&self, expired: u64, invalid: u64, reclaimed_bytes: u64) {
+        self.snapshots_pruned.with_label_values(&["retention"]).inc_by(expired);
+        self.snapshots_pruned.with_label_values(&["invalid_version"]).inc_by(invalid);
+        self.snapshot_bytes_reclaimed.inc_by(reclaimed_bytes);
+    }
+
+    /// Helper to record a served HTTP request; `route` must be the matched pattern
+    pub fn record_http_request(&self, server: &str, route: &str, method: &str, status: u16, duration_secs: f64) {
+        self.http_requests
+            .with_label_values(&[server, route, method, &status.to_string()])
+            .inc();
+        self.http_request_duration
+            .with_label_values(&[server, route, method])
+            .observe(duration_secs);
+    }
+
+    /// Helper to record one routing rule evaluation
+    pub fn record_routing_evaluation(&self, rule: &str, matched: bool) {
+        let outcome = if matched { "matched" } else { "not_matched" };
+        self.routing_rule_evaluations.with_label_values(&[rule, outcome]).inc();
+    }
+
+    /// Helper to record a routed copy (published, dry_run or failed)
+    pub fn record_routing_route(&self, rule: &str, topic: &str, outcome: &str) {
+        self.routing_rule_routed.with_label_values(&[rule, topic, outcome]).inc();
+    }
+
+    /// Helper to publish one projection's staleness
+    pub fn record_projection_staleness(&self, projection: &str, staleness_secs: f64, lag_secs: f64, sla_breached: bool) {
+        self.projection_staleness_seconds.with_label_values(&[projection]).set(staleness_secs);
+        self.projection_lag_seconds.with_label_values(&[projection]).set(lag_secs);
+        self.projection_sla_breached.with_label_values(&[projection]).set(sla_breached as i64);
+    }
+
+    /// Helper to record a finished lifecycle hook run
+    pub fn record_lifecycle_hook(&self, hook: &str, stage: &str, outcome: &str) {
+        self.lifecycle_hook_runs.with_label_values(&[hook, stage, outcome]).inc();
+    }
+
+    /// Helper to publish the findings of the last integrity check
+    pub fn record_integrity_findings(&self, orphaned_outbox: usize, missing_outbox: usize) {
+        self.integrity_orphans.with_label_values(&["orphaned_outbox"]).set(orphaned_outbox as i64);
+        self.integrity_orphans.with_label_values(&["missing_outbox"]).set(missing_outbox as i64);
+    }
+
+    /// Helper to record an outbox janitor pass
+    pub fn record_outbox_cleanup(&self, reclaimed: u64, unpublished: usize, pending: usize) {
+        self.outbox_rows_reclaimed.inc_by(reclaimed);
+        self.outbox_unpublished_rows.set(unpublished as i64);
+        self.outbox_pending_rows.set(pending as i64);
+    }
+
+    /// Helper to record an outbox scheduler pass
+    pub fn record_outbox_schedule(&self, released: u64, pending: usize) {
+        self.outbox_scheduled_released.inc_by(released);
+        self.outbox_scheduled_pending.set(pending as i64);
+    }
+
+    /// Helper to record an event store append of `events` events
+    pub fn record_event_store_append(&self, aggregate_type: &str, outcome: &str, events: usize) {
+        self.event_store_appends.with_label_values(&[aggregate_type, outcome]).inc();
+        if outcome == "appended" {
+            self.event_store_events_appended.with_label_values(&[aggregate_type]).inc_by(events as u64);
+        }
+    }
+
+    /// Helper to record a publish to Redpanda
+    pub fn record_publish(&self, topic: &str, origin: &str, success: bool) {
+        let outcome = if success { "success" } else { "failure" };
+        self.publishes.with_label_values(&[topic, origin, outcome]).inc();
+    }
+
+    /// Helper to record a flushed publish batch
+    pub fn record_publish_batch(&self, records: usize, flush_secs: f64) {
+        self.publish_batch_size.observe(records as f64);
+        self.publish_batch_flush_seconds.observe(flush_secs);
+    }
+
+    /// Helper to count a publish delayed by the rate limit of `scope`
+    pub fn record_publish_rate_limited(&self, scope: &str) {
+        self.publish_rate_limited.with_label_values(&[scope]).inc();
+    }
+
+    /// Helper to record the measured publish rate of `scope`
+    pub fn set_publish_rate(&self, scope: &str, rate: f64) {
+        self.publish_rate.with_label_values(&[scope]).set(rate);
+    }
+
+    /// Helper to record the CDC throttle decision and the signal behind it
+    pub fn record_cdc_throttle(&self, delay_secs: f64, p95_secs: f64, error_rate: f64) {
+        self.cdc_throttle_delay_seconds.set(delay_secs);
+        self.scylla_latency_p95_seconds.set(p95_secs);
+        self.scylla_error_rate.set(error_rate);
     }
 }
 
@@ -218,19 +773,40 @@ mod tests {
     #[test]
     fn test_metrics_creation() {
         let metrics = Metrics::new().unwrap();
-        assert!(metrics.registry.gather().len() > 0);
+        assert!(!metrics.registry.gather().is_empty());
+    }
+
+    fn order_labels() -> EventLabels<'static> {
+        EventLabels {
+            event_type: "OrderCreated",
+            aggregate_type: "Order",
+            topic: "order-events",
+        }
     }
 
     #[test]
     fn test_record_cdc_event() {
         let metrics = Metrics::new().unwrap();
-        metrics.record_cdc_event("OrderCreated", 0.05, true);
+        metrics.record_cdc_event(&order_labels(), 0.05, true, None);
 
         let gathered = metrics.registry.gather();
         let processed = gathered.iter().find(|m| m.name() == "cdc_events_processed_total").unwrap();
         assert_eq!(processed.metric[0].counter.value, Some(1.0));
     }
 
+    #[test]
+    fn test_cdc_duration_labels_and_exemplar() {
+        let metrics = Metrics::new().unwrap();
+        metrics.record_cdc_event(&order_labels(), 0.03, true, Some("trace-42"));
+
+        let gathered = metrics.registry.gather();
+        let duration = gathered.iter().find(|m| m.name() == "cdc_processing_duration_seconds").unwrap();
+        assert_eq!(duration.metric[0].label.len(), 3);
+
+        let text = encode_openmetrics(&gathered, metrics.exemplars());
+        assert!(text.contains(r#"trace_id="trace-42""#));
+    }
+
     #[test]
     fn test_record_retry() {
         let metrics = Metrics::new().unwrap();
@@ -257,12 +833,36 @@ mod tests {
     #[test]
     fn test_circuit_breaker_metrics() {
         let metrics = Metrics::new().unwrap();
-        metrics.update_circuit_breaker_state(0); // Closed
-        metrics.record_circuit_breaker_transition("Closed", "Open");
-        metrics.update_circuit_breaker_state(1); // Open
+        metrics.update_circuit_breaker_state("orders", 0); // Closed
+        metrics.record_circuit_breaker_transition("orders", "Closed", "Open");
+        metrics.update_circuit_breaker_state("orders", 1); // Open
 
         let gathered = metrics.registry.gather();
         let state = gathered.iter().find(|m| m.name() == "circuit_breaker_state").unwrap();
         assert_eq!(state.metric[0].gauge.value, Some(1.0));
     }
+
+    #[test]
+    fn test_record_snapshots_pruned() {
+        let metrics = Metrics::new().unwrap();
+        metrics.record_snapshots_pruned(3, 1, 4096);
+
+        let gathered = metrics.registry.gather();
+        let reclaimed = gathered.iter().find(|m| m.name() == "snapshot_bytes_reclaimed_total").unwrap();
+        assert_eq!(reclaimed.metric[0].counter.value, Some(4096.0));
+
+        let pruned = gathered.iter().find(|m| m.name() == "snapshots_pruned_total").unwrap();
+        assert_eq!(pruned.metric.len(), 2); // retention + invalid_version
+    }
+
+    #[test]
+    fn test_actor_queue_depth_per_priority() {
+        let metrics = Metrics::new().unwrap();
+        metrics.set_actor_queue_depth("dlq_actor", "normal", 12);
+        metrics.set_actor_queue_depth("dlq_actor", "critical", 0);
+
+        let gathered = metrics.registry.gather();
+        let depth = gathered.iter().find(|m| m.name() == "actor_queue_depth").unwrap();
+        assert_eq!(depth.metric.len(), 2);
+    }
 }
@@ -0,0 +1,79 @@
+use anyhow::{Context, Result};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::trace::{Sampler, SdkTracerProvider, Tracer};
+use opentelemetry_sdk::Resource;
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::config::TelemetryConfig;
+
+// ============================================================================
+// OTLP Span Export - Distributed Tracing in Jaeger/Tempo
+// ============================================================================
+//
+// Built with `--features otel` and an `otlp_endpoint` configured
+// ([telemetry] / OTEL_EXPORTER_OTLP_ENDPOINT), every tracing span the
+// service opens is also exported over OTLP/gRPC:
+//
+//   command             aggregate_id, correlation_id
+//   event_store_append  aggregate_type, correlation_id, causation_id, ...
+//   cdc_relay           correlation_id, causation_id, aggregate_id, event_id
+//   redpanda_publish    topic, key, origin
+//   http_request        correlation_id, server
+//
+// Span fields become span attributes, so a correlation_id search in
+// Jaeger/Tempo finds both the command's trace and the trace of its CDC
+// relay (the outbox does not carry trace context across). Spans are
+// exported in batches from a background thread; the exporter flushes them
+// when it is dropped at shutdown. New traces are sampled at `sample_ratio`,
+// child spans follow their parent's decision.
+//
+// ============================================================================
+
+/// Exports tracing spans to an OTLP collector while it is alive
+pub struct OtlpExporter {
+    provider: SdkTracerProvider,
+}
+
+impl OtlpExporter {
+    /// Exporter for `config`; None when no endpoint is configured
+    ///
+    /// Needs a running Tokio runtime (the gRPC channel is created on it).
+    pub fn from_config(config: &TelemetryConfig) -> Result<Option<Self>> {
+        let Some(ref endpoint) = config.otlp_endpoint else {
+            return Ok(None);
+        };
+
+        let exporter = SpanExporter::builder()
+            .with_tonic()
+            .with_endpoint(endpoint.clone())
+            .build()
+            .with_context(|| format!("Failed to create OTLP exporter for {}", endpoint))?;
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(config.sample_ratio))))
+            .with_resource(Resource::builder().with_service_name(config.service_name.clone()).build())
+            .build();
+
+        Ok(Some(Self { provider }))
+    }
+
+    /// tracing-subscriber layer handing spans to the exporter
+    pub fn layer<S>(&self) -> OpenTelemetryLayer<S, Tracer>
+    where
+        S: Subscriber + for<'span> LookupSpan<'span>,
+    {
+        tracing_opentelemetry::layer().with_tracer(self.provider.tracer(env!("CARGO_PKG_NAME")))
+    }
+}
+
+impl Drop for OtlpExporter {
+    fn drop(&mut self) {
+        // Flushes the spans still waiting for their batch
+        if let Err(e) = self.provider.shutdown() {
+            tracing::warn!(error = %e, "Failed to flush OTLP spans");
+        }
+    }
+}
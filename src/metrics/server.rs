@@ -1,27 +1,69 @@
-use actix_web::{web, App, HttpResponse, HttpServer, Responder};
-use prometheus::{Encoder, Registry, TextEncoder};
+use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer, Responder};
+use prometheus::{Encoder, TextEncoder};
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
+use super::{AccessLog, Metrics, MetricsHandle, ServiceInfo, encode_openmetrics, OPENMETRICS_CONTENT_TYPE};
+use crate::actors::{transition_counts, HealthHistory, HealthSnapshot, StartupSequencer, SystemHealth};
+use crate::projections::StalenessTracker;
+
+/// A health snapshot older than this means the health monitor stopped reporting
+const HEALTH_SNAPSHOT_MAX_AGE: chrono::Duration = chrono::Duration::seconds(60);
+
 /// Start the metrics HTTP server
 /// This should be called in a separate thread/runtime to avoid conflicts
-pub async fn start_metrics_server(registry: Arc<Registry>, port: u16) -> std::io::Result<()> {
+pub async fn start_metrics_server(
+    metrics: Arc<Metrics>,
+    info: Arc<ServiceInfo>,
+    staleness: Arc<StalenessTracker>,
+    startup: Arc<StartupSequencer>,
+    health_history: Arc<HealthHistory>,
+    health: Arc<HealthSnapshot>,
+    port: u16,
+) -> std::io::Result<()> {
     tracing::info!("📊 Starting metrics server on http://0.0.0.0:{}/metrics", port);
 
     HttpServer::new(move || {
         App::new()
-            .app_data(web::Data::new(registry.clone()))
+            .wrap(AccessLog::new("metrics").with_metrics(MetricsHandle::from(metrics.clone())))
+            .app_data(web::Data::new(metrics.clone()))
+            .app_data(web::Data::new(info.clone()))
+            .app_data(web::Data::new(staleness.clone()))
+            .app_data(web::Data::new(startup.clone()))
+            .app_data(web::Data::new(health_history.clone()))
+            .app_data(web::Data::new(health.clone()))
             .route("/metrics", web::get().to(metrics_handler))
             .route("/health", web::get().to(health_handler))
+            .route("/health/live", web::get().to(liveness_handler))
+            .route("/health/ready", web::get().to(readiness_handler))
+            .route("/health/history", web::get().to(health_history_handler))
+            .route("/info", web::get().to(info_handler))
+            .route("/status/projections", web::get().to(projections_handler))
+            .route("/status/startup", web::get().to(startup_handler))
     })
     .bind(("0.0.0.0", port))?
     .run()
     .await
 }
 
-async fn metrics_handler(registry: web::Data<Arc<Registry>>) -> impl Responder {
-    let encoder = TextEncoder::new();
-    let metric_families = registry.gather();
+async fn metrics_handler(req: HttpRequest, metrics: web::Data<Arc<Metrics>>) -> impl Responder {
+    let metric_families = metrics.registry().gather();
+
+    // Exemplars are only representable in OpenMetrics
+    let wants_openmetrics = req
+        .headers()
+        .get(actix_web::http::header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.contains("application/openmetrics-text"))
+        .unwrap_or(false);
 
+    if wants_openmetrics {
+        return HttpResponse::Ok()
+            .content_type(OPENMETRICS_CONTENT_TYPE)
+            .body(encode_openmetrics(&metric_families, metrics.exemplars()));
+    }
+
+    let encoder = TextEncoder::new();
     let mut buffer = Vec::new();
     encoder.encode(&metric_families, &mut buffer).unwrap();
 
@@ -30,9 +72,135 @@ async fn metrics_handler(registry: web::Data<Arc<Registry>>) -> impl Responder {
         .body(buffer)
 }
 
-async fn health_handler() -> impl Responder {
-    HttpResponse::Ok().json(serde_json::json!({
-        "status": "healthy",
-        "service": "scylladb-cdc-outbox"
-    }))
+/// System health from the HealthMonitorActor; 503 when unhealthy, before
+/// the first report and when the monitor stopped reporting
+async fn health_handler(info: web::Data<Arc<ServiceInfo>>, health: web::Data<Arc<HealthSnapshot>>) -> impl Responder {
+    let now = chrono::Utc::now();
+    let (status, mut body) = match health.latest() {
+        Some(system) if now - system.check_time > HEALTH_SNAPSHOT_MAX_AGE => (
+            "unhealthy",
+            health_body(&system, Some(format!("Health monitor silent since {}", system.check_time))),
+        ),
+        Some(system) => (system.overall_status.label(), health_body(&system, None)),
+        None => (
+            "unhealthy",
+            serde_json::json!({ "message": "Health monitor not started yet", "components": {} }),
+        ),
+    };
+
+    body["status"] = status.into();
+    body["service"] = "scylladb-cdc-outbox".into();
+    body["version"] = info.build.version.into();
+    body["git_sha"] = info.build.git_sha.into();
+    if status == "unhealthy" {
+        HttpResponse::ServiceUnavailable().json(body)
+    } else {
+        HttpResponse::Ok().json(body)
+    }
+}
+
+/// Liveness probe: 503 only when the health monitor stopped reporting
+async fn liveness_handler(health: web::Data<Arc<HealthSnapshot>>) -> impl Responder {
+    match health.latest() {
+        Some(system) if chrono::Utc::now() - system.check_time > HEALTH_SNAPSHOT_MAX_AGE => {
+            HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "status": "dead",
+                "message": format!("Health monitor silent since {}", system.check_time),
+            }))
+        }
+        _ => HttpResponse::Ok().json(serde_json::json!({ "status": "alive" })),
+    }
+}
+
+/// Readiness probe: 200 once startup completed and every component gating
+/// readiness (scylla, cdc_reader, ...) reports healthy
+async fn readiness_handler(
+    health: web::Data<Arc<HealthSnapshot>>,
+    startup: web::Data<Arc<StartupSequencer>>,
+) -> impl Responder {
+    let mut not_ready = match health.latest() {
+        Some(system) if chrono::Utc::now() - system.check_time > HEALTH_SNAPSHOT_MAX_AGE => {
+            vec!["health_monitor".to_string()]
+        }
+        Some(system) => system.not_ready,
+        None => vec!["health_monitor".to_string()],
+    };
+    if !startup.status().ready {
+        not_ready.push("startup".to_string());
+    }
+
+    if not_ready.is_empty() {
+        HttpResponse::Ok().json(serde_json::json!({ "status": "ready" }))
+    } else {
+        HttpResponse::ServiceUnavailable().json(serde_json::json!({ "status": "not_ready", "waiting_for": not_ready }))
+    }
+}
+
+fn health_body(system: &SystemHealth, message: Option<String>) -> serde_json::Value {
+    let components: BTreeMap<&str, serde_json::Value> = system
+        .components
+        .iter()
+        .map(|(name, component)| {
+            (
+                name.as_str(),
+                serde_json::json!({
+                    "status": component.status.label(),
+                    "message": component.status.message(),
+                    "details": component.details,
+                    "last_check": component.last_check,
+                }),
+            )
+        })
+        .collect();
+    serde_json::json!({
+        "message": message.as_deref().or(system.overall_status.message()),
+        "ready": system.is_ready(),
+        "checked_at": system.check_time,
+        "components": components,
+    })
+}
+
+#[derive(serde::Deserialize)]
+struct HistoryQuery {
+    hours: Option<u64>,
+}
+
+/// Health transitions of the last `hours` (default 24, capped at the retention)
+async fn health_history_handler(
+    history: web::Data<Arc<HealthHistory>>,
+    query: web::Query<HistoryQuery>,
+) -> impl Responder {
+    let max_hours = (history.retention().as_secs() / 3600).max(1);
+    let hours = query.hours.unwrap_or(24).clamp(1, max_hours);
+    let since = chrono::Utc::now() - chrono::Duration::hours(hours as i64);
+
+    match history.since(since).await {
+        Ok(transitions) => HttpResponse::Ok().json(serde_json::json!({
+            "hours": hours,
+            "since": since,
+            "transition_counts": transition_counts(&transitions),
+            "transitions": transitions,
+        })),
+        Err(e) => HttpResponse::ServiceUnavailable().json(serde_json::json!({
+            "error": format!("Failed to read health history: {}", e)
+        })),
+    }
+}
+
+async fn info_handler(info: web::Data<Arc<ServiceInfo>>) -> impl Responder {
+    HttpResponse::Ok().json(info.get_ref().as_ref())
+}
+
+async fn projections_handler(staleness: web::Data<Arc<StalenessTracker>>) -> impl Responder {
+    HttpResponse::Ok().json(serde_json::json!({ "projections": staleness.report() }))
+}
+
+/// Startup phase progress; 503 until every phase completed
+async fn startup_handler(startup: web::Data<Arc<StartupSequencer>>) -> impl Responder {
+    let status = startup.status();
+    if status.ready {
+        HttpResponse::Ok().json(status)
+    } else {
+        HttpResponse::ServiceUnavailable().json(status)
+    }
 }
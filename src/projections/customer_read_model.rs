@@ -0,0 +1,377 @@
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use scylla::client::session::Session;
+use scylla::value::{CqlTimestamp, CqlValue};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::domain::customer::{Address, CustomerAggregate, CustomerEvent, CustomerStatus, CustomerTier};
+use crate::event_sourcing::{AggregateRoot, EventEnvelope, EventStore};
+use super::paging::{query_page, Page, Paging};
+use super::projection::{Projection, ProjectionCheckpoint, ProjectionEvent};
+use super::versioned::VersionedTable;
+
+// ============================================================================
+// Customer Read Model - Current Customer State Without Replay
+// ============================================================================
+//
+// The CustomerReadModel projection keeps `customer_read_model` current
+// from the CDC event stream: one row per customer with profile, status,
+// tier and addresses. Like the order read model it reloads the customer's
+// events for every Customer event and writes the complete state with a
+// version guard, so late or replayed events are skipped as stale.
+//
+// CustomerQueryService looks customers up by id or email and lists them
+// filtered by tier and/or status. Email, tier and status are secondary
+// indexes; filtering on both tier and status scans the tier's rows
+// (ALLOW FILTERING). Emails match exactly as registered.
+//
+// ============================================================================
+
+/// Staleness SLA reported for the customer read model
+const CUSTOMER_READ_MODEL_SLA: Duration = Duration::from_secs(30);
+
+/// Columns read back into a CustomerView
+const VIEW_COLUMNS: &str =
+    "customer_id, email, first_name, last_name, phone, status, tier, addresses, created_at, updated_at, version";
+
+type ViewRow = (
+    Uuid,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<DateTime<Utc>>,
+    Option<DateTime<Utc>>,
+    Option<i64>,
+);
+
+/// Current state of a customer as stored in customer_read_model
+#[derive(Debug, Clone, Serialize)]
+pub struct CustomerView {
+    pub customer_id: Uuid,
+    pub email: String,
+    pub first_name: String,
+    pub last_name: String,
+    pub phone: Option<String>,
+    pub status: String,
+    pub tier: String,
+    pub addresses: Vec<CustomerAddress>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Sequence number of the newest event reflected
+    pub version: i64,
+}
+
+impl CustomerView {
+    /// State after `events` (the customer's full history); None without events
+    pub fn from_events(customer_id: Uuid, events: Vec<EventEnvelope<CustomerEvent>>) -> Result<Option<Self>> {
+        let (Some(first), Some(last)) = (events.first(), events.last()) else {
+            return Ok(None);
+        };
+        let (created_at, updated_at, version) = (first.timestamp, last.timestamp, last.sequence_number);
+        let customer = CustomerAggregate::load_from_events(events)?;
+        let addresses = customer_addresses(&customer);
+
+        Ok(Some(Self {
+            customer_id,
+            email: customer.email.0,
+            first_name: customer.first_name,
+            last_name: customer.last_name,
+            phone: customer.phone.map(|phone| phone.0),
+            status: format!("{:?}", customer.status),
+            tier: format!("{:?}", customer.tier),
+            addresses,
+            created_at,
+            updated_at,
+            version,
+        }))
+    }
+
+    fn from_row(row: ViewRow) -> Result<Option<Self>> {
+        let (customer_id, email, first_name, last_name, phone, status, tier, addresses, created_at, updated_at, version) = row;
+        let (Some(email), Some(created_at)) = (email, created_at) else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            customer_id,
+            email,
+            first_name: first_name.unwrap_or_default(),
+            last_name: last_name.unwrap_or_default(),
+            phone: phone.filter(|phone| !phone.is_empty()),
+            status: status.unwrap_or_default(),
+            tier: tier.unwrap_or_default(),
+            addresses: match addresses.as_deref() {
+                Some(addresses) => serde_json::from_str(addresses)?,
+                None => Vec::new(),
+            },
+            created_at,
+            updated_at: updated_at.unwrap_or(created_at),
+            version: version.unwrap_or_default(),
+        }))
+    }
+}
+
+/// An address of a customer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomerAddress {
+    pub address_id: Uuid,
+    pub address: Address,
+    pub is_default: bool,
+}
+
+/// Addresses of a customer, the default one first
+fn customer_addresses(customer: &CustomerAggregate) -> Vec<CustomerAddress> {
+    let mut addresses: Vec<CustomerAddress> = customer
+        .addresses
+        .iter()
+        .map(|(address_id, address)| CustomerAddress {
+            address_id: *address_id,
+            address: address.clone(),
+            is_default: customer.default_address_id == Some(*address_id),
+        })
+        .collect();
+    addresses.sort_by_key(|address| (!address.is_default, address.address_id));
+    addresses
+}
+
+/// Which customers to list; None matches any
+#[derive(Debug, Clone, Default)]
+pub struct CustomerFilter {
+    pub tier: Option<CustomerTier>,
+    pub status: Option<CustomerStatus>,
+}
+
+impl CustomerFilter {
+    /// Listing query and its bound values
+    fn query(&self) -> (String, Vec<String>) {
+        let select = format!("SELECT {} FROM customer_read_model", VIEW_COLUMNS);
+        match (&self.tier, &self.status) {
+            (None, None) => (select, Vec::new()),
+            (Some(tier), None) => (format!("{} WHERE tier = ?", select), vec![format!("{:?}", tier)]),
+            (None, Some(status)) => (format!("{} WHERE status = ?", select), vec![format!("{:?}", status)]),
+            (Some(tier), Some(status)) => (
+                format!("{} WHERE tier = ? AND status = ? ALLOW FILTERING", select),
+                vec![format!("{:?}", tier), format!("{:?}", status)],
+            ),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Projection
+// ----------------------------------------------------------------------------
+
+pub struct CustomerReadModel {
+    session: Arc<Session>,
+    event_store: Arc<EventStore<CustomerEvent>>,
+    customers: VersionedTable,
+    checkpoint: ProjectionCheckpoint,
+}
+
+impl CustomerReadModel {
+    pub fn new(session: Arc<Session>, event_store: Arc<EventStore<CustomerEvent>>) -> Self {
+        Self {
+            customers: VersionedTable::new(session.clone(), "customer_read_model"),
+            session,
+            event_store,
+            checkpoint: ProjectionCheckpoint::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl Projection for CustomerReadModel {
+    fn name(&self) -> &str {
+        "customer_read_model"
+    }
+
+    fn handles(&self, event: &ProjectionEvent) -> bool {
+        event.aggregate_type.as_deref() == Some("Customer")
+    }
+
+    fn staleness_sla(&self) -> Option<Duration> {
+        Some(CUSTOMER_READ_MODEL_SLA)
+    }
+
+    async fn handle(&mut self, event: &ProjectionEvent) -> Result<()> {
+        let events = self.event_store.load_events(event.aggregate_id).await?;
+        let Some(view) = CustomerView::from_events(event.aggregate_id, events)? else {
+            bail!("No events stored for customer {}", event.aggregate_id);
+        };
+
+        self.customers
+            .upsert(
+                &[("customer_id", CqlValue::Uuid(view.customer_id))],
+                view.version,
+                &[
+                    ("email", CqlValue::Text(view.email)),
+                    ("first_name", CqlValue::Text(view.first_name)),
+                    ("last_name", CqlValue::Text(view.last_name)),
+                    // "" for none: versioned writes cannot bind NULL
+                    ("phone", CqlValue::Text(view.phone.unwrap_or_default())),
+                    ("status", CqlValue::Text(view.status)),
+                    ("tier", CqlValue::Text(view.tier)),
+                    ("addresses", CqlValue::Text(serde_json::to_string(&view.addresses)?)),
+                    ("created_at", CqlValue::Timestamp(CqlTimestamp(view.created_at.timestamp_millis()))),
+                    ("updated_at", CqlValue::Timestamp(CqlTimestamp(view.updated_at.timestamp_millis()))),
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
+    fn checkpoint(&self) -> &ProjectionCheckpoint {
+        &self.checkpoint
+    }
+
+    fn checkpoint_mut(&mut self) -> &mut ProjectionCheckpoint {
+        &mut self.checkpoint
+    }
+
+    async fn reset(&mut self) -> Result<()> {
+        self.session.query_unpaged("TRUNCATE customer_read_model", &[]).await?;
+        Ok(())
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Queries
+// ----------------------------------------------------------------------------
+
+/// Reads of the customer read model
+pub struct CustomerQueryService {
+    session: Arc<Session>,
+}
+
+impl CustomerQueryService {
+    pub fn new(session: Arc<Session>) -> Self {
+        Self { session }
+    }
+
+    /// Current state of a customer; None until its first event is projected
+    pub async fn get_customer(&self, customer_id: Uuid) -> Result<Option<CustomerView>> {
+        let row = self
+            .session
+            .query_unpaged(
+                format!("SELECT {} FROM customer_read_model WHERE customer_id = ?", VIEW_COLUMNS),
+                (customer_id,),
+            )
+            .await?
+            .into_rows_result()?
+            .maybe_first_row::<ViewRow>()?;
+
+        match row {
+            Some(row) => CustomerView::from_row(row),
+            None => Ok(None),
+        }
+    }
+
+    /// The customer registered under `email` (exact match)
+    pub async fn find_by_email(&self, email: &str) -> Result<Option<CustomerView>> {
+        let rows = self
+            .session
+            .query_unpaged(
+                format!("SELECT {} FROM customer_read_model WHERE email = ?", VIEW_COLUMNS),
+                (email,),
+            )
+            .await?
+            .into_rows_result()?;
+
+        for row in rows.rows::<ViewRow>()? {
+            if let Some(view) = CustomerView::from_row(row?)? {
+                return Ok(Some(view));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Customers matching `filter`, in token order
+    pub async fn list_customers(&self, filter: &CustomerFilter, paging: &Paging) -> Result<Page<CustomerView>> {
+        let (query, values) = filter.query();
+        let (rows, next_cursor) = query_page(&self.session, &query, values, paging).await?;
+
+        let mut items = Vec::new();
+        for row in rows.rows::<ViewRow>()? {
+            if let Some(view) = CustomerView::from_row(row?)? {
+                items.push(view);
+            }
+        }
+        Ok(Page { items, next_cursor })
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::customer::{CustomerAddressAdded, CustomerRegistered, CustomerTierUpgraded, Email};
+
+    fn envelope(customer_id: Uuid, sequence_number: i64, event_type: &str, event: CustomerEvent) -> EventEnvelope<CustomerEvent> {
+        EventEnvelope::new(customer_id, sequence_number, event_type.to_string(), event, Uuid::new_v4())
+    }
+
+    #[test]
+    fn test_view_reflects_profile_tier_and_addresses() {
+        let customer_id = Uuid::new_v4();
+        let address_id = Uuid::new_v4();
+        let address = Address {
+            street: "1 Main St".to_string(),
+            city: "Springfield".to_string(),
+            state: "IL".to_string(),
+            postal_code: "62701".to_string(),
+            country: "US".to_string(),
+        };
+        let events = vec![
+            envelope(customer_id, 1, "CustomerRegistered", CustomerEvent::Registered(CustomerRegistered {
+                email: Email::new("ada@example.com"),
+                first_name: "Ada".to_string(),
+                last_name: "Lovelace".to_string(),
+                phone: None,
+            })),
+            envelope(customer_id, 2, "CustomerTierUpgraded", CustomerEvent::TierUpgraded(CustomerTierUpgraded {
+                old_tier: CustomerTier::Bronze,
+                new_tier: CustomerTier::Gold,
+            })),
+            envelope(customer_id, 3, "CustomerAddressAdded", CustomerEvent::AddressAdded(CustomerAddressAdded {
+                address_id,
+                address: address.clone(),
+                is_default: true,
+            })),
+        ];
+
+        let view = CustomerView::from_events(customer_id, events).unwrap().unwrap();
+        assert_eq!(view.customer_id, customer_id);
+        assert_eq!(view.email, "ada@example.com");
+        assert_eq!((view.status.as_str(), view.tier.as_str()), ("Active", "Gold"));
+        assert_eq!(view.addresses, vec![CustomerAddress { address_id, address, is_default: true }]);
+        assert_eq!(view.version, 3);
+
+        assert!(CustomerView::from_events(customer_id, Vec::new()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_filter_queries() {
+        let (query, values) = CustomerFilter::default().query();
+        assert!(!query.contains("WHERE"));
+        assert!(values.is_empty());
+
+        let (query, values) = CustomerFilter { tier: Some(CustomerTier::Gold), status: None }.query();
+        assert!(query.ends_with("WHERE tier = ?"));
+        assert_eq!(values, vec!["Gold"]);
+
+        let filter = CustomerFilter { tier: Some(CustomerTier::Silver), status: Some(CustomerStatus::Suspended) };
+        let (query, values) = filter.query();
+        assert!(query.ends_with("WHERE tier = ? AND status = ? ALLOW FILTERING"));
+        assert_eq!(values, vec!["Silver", "Suspended"]);
+    }
+}
@@ -0,0 +1,762 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use kameo::actor::ActorRef;
+use kameo::error::Infallible;
+use kameo::message::{Context, Message};
+use kameo::Actor;
+use scylla::client::session::Session;
+use scylla_cdc::consumer::{CDCRow, Consumer, ConsumerFactory};
+use scylla_cdc::log_reader::CDCLogReaderBuilder;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use super::projection::{Projection, ProjectionCheckpoint, ProjectionEvent};
+use super::StalenessTracker;
+use crate::actors::{OutboxRow, StartupPhase, StartupSequencer};
+use crate::config::CdcSource;
+use crate::event_sourcing::{EventOrderKey, HybridTimestamp};
+
+// ============================================================================
+// Projection Manager Actor - Feeds Projections from CDC
+// ============================================================================
+//
+// Subscribes to the outbox CDC stream with its own log reader (independent
+// of the Redpanda relay) and dispatches every inserted event to the
+// registered projections, one event at a time:
+//
+//   outbox_messages CDC → ProjectionConsumer → ApplyEvent → ReorderBuffer
+//                                                          → Projection::handle
+//
+// Ordering: CDC streams of different vnodes are read concurrently, so
+// events of different aggregates arrive out of order. Each event is held
+// in a ReorderBuffer until its HLC stamp is `reorder_window` old (default
+// 250ms) or it was held that long, and events are applied in EventOrderKey
+// order (see ordering.rs); an aggregate's events keep their sequence order
+// even when their stamps are skewed. A zero window applies every event as
+// it arrives. Held events are applied before the manager stops.
+//
+// Checkpoints:
+// - Each projection's checkpoint lives in projection_offsets (partition 0),
+//   flushed every `checkpoint_interval` and on stop
+// - On start they are restored, and the log reader starts REPLAY_SLACK
+//   before the oldest one, so nothing written while we were down is missed
+//   (replayed events are re-applied - projections are idempotent)
+// - Without any checkpoint the reader starts at "now"
+//
+// Every `purge_interval` (default 1h) each projection hard-deletes its
+// soft-deleted rows past retention (Projection::purge_expired).
+//
+// A failing event is counted on the projection's checkpoint (errors_count,
+// last_error) and skipped, so one bad event cannot stall every read model.
+//
+// With a StalenessTracker, projections are registered with their SLA and
+// report the event time of every applied event. With a StartupSequencer,
+// restoring checkpoints and starting the reader is the projection_rebuild
+// phase.
+//
+// ============================================================================
+
+/// Single consumer - all checkpoints live in one partition_id
+const CHECKPOINT_PARTITION: i32 = 0;
+
+/// How far before the oldest checkpoint a restart resumes reading
+const REPLAY_SLACK: chrono::Duration = chrono::Duration::minutes(2);
+
+/// How long events are held to be applied in HLC order
+const DEFAULT_REORDER_WINDOW: Duration = Duration::from_millis(250);
+
+/// How often projections purge expired soft-deleted rows
+const DEFAULT_PURGE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Apply one event to every registered projection
+#[derive(Debug)]
+pub struct ApplyEvent(pub ProjectionEvent);
+
+/// Persist checkpoints changed since the last flush
+#[derive(Debug)]
+pub struct FlushCheckpoints;
+
+/// Apply the buffered events that are due
+#[derive(Debug)]
+struct ReleaseDue;
+
+/// Hard-delete expired soft-deleted rows of every projection
+#[derive(Debug)]
+struct PurgeTombstones;
+
+/// Reset a projection's read model and checkpoint
+#[derive(Debug)]
+pub struct ResetProjection {
+    pub name: String,
+}
+
+/// Checkpoint of every registered projection
+#[derive(Debug)]
+pub struct GetProjectionStatus;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectionStatus {
+    pub name: String,
+    pub checkpoint: ProjectionCheckpoint,
+}
+
+struct RegisteredProjection {
+    projection: Box<dyn Projection>,
+    /// Checkpoint changed since the last flush
+    dirty: bool,
+}
+
+pub struct ProjectionManager {
+    session: Arc<Session>,
+    projections: Vec<RegisteredProjection>,
+    staleness: Option<Arc<StalenessTracker>>,
+    startup: Option<Arc<StartupSequencer>>,
+    source: CdcSource,
+    checkpoint_interval: Duration,
+    purge_interval: Duration,
+    reorder: ReorderBuffer,
+}
+
+impl ProjectionManager {
+    pub fn new(session: Arc<Session>) -> Self {
+        Self {
+            session,
+            projections: Vec::new(),
+            staleness: None,
+            startup: None,
+            source: CdcSource::default(),
+            checkpoint_interval: Duration::from_secs(5),
+            purge_interval: DEFAULT_PURGE_INTERVAL,
+            reorder: ReorderBuffer::new(DEFAULT_REORDER_WINDOW),
+        }
+    }
+
+    /// Register a projection; names must be unique
+    pub fn with_projection(mut self, projection: impl Projection) -> Self {
+        assert!(
+            self.projections.iter().all(|p| p.projection.name() != projection.name()),
+            "projection '{}' registered twice",
+            projection.name()
+        );
+        self.projections.push(RegisteredProjection { projection: Box::new(projection), dirty: false });
+        self
+    }
+
+    /// Report applied events for read model staleness
+    pub fn with_staleness_tracker(mut self, tracker: Arc<StalenessTracker>) -> Self {
+        self.staleness = Some(tracker);
+        self
+    }
+
+    /// Start consuming as the projection_rebuild startup phase
+    pub fn with_startup(mut self, startup: Arc<StartupSequencer>) -> Self {
+        self.startup = Some(startup);
+        self
+    }
+
+    /// Keyspace and outbox table whose CDC log feeds the projections
+    pub fn with_cdc_source(mut self, source: CdcSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    pub fn with_checkpoint_interval(mut self, interval: Duration) -> Self {
+        self.checkpoint_interval = interval;
+        self
+    }
+
+    /// How often expired soft-deleted rows are purged
+    pub fn with_purge_interval(mut self, interval: Duration) -> Self {
+        self.purge_interval = interval;
+        self
+    }
+
+    /// How long events are held to be applied in HLC order (zero: as they arrive)
+    pub fn with_reorder_window(mut self, window: Duration) -> Self {
+        self.reorder = ReorderBuffer::new(window);
+        self
+    }
+
+    /// Load persisted checkpoints into the projections
+    async fn restore_checkpoints(&mut self) -> anyhow::Result<()> {
+        for registered in &mut self.projections {
+            let name = registered.projection.name().to_string();
+            if let Some(checkpoint) = load_checkpoint(&self.session, &name).await? {
+                tracing::info!(
+                    projection = %name,
+                    events_processed = checkpoint.events_processed,
+                    last_processed_at = ?checkpoint.last_processed_at,
+                    "Restored projection checkpoint"
+                );
+                *registered.projection.checkpoint_mut() = checkpoint;
+            }
+
+            if let Some(ref tracker) = self.staleness {
+                tracker.register(&name, registered.projection.staleness_sla());
+                if let Some(at) = registered.projection.checkpoint().last_processed_at {
+                    tracker.record_applied(&name, at);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Where the log reader starts: before the oldest checkpoint, or "now"
+    /// if any projection starts from scratch
+    fn resume_from(&self) -> Option<DateTime<Utc>> {
+        let positions: Option<Vec<DateTime<Utc>>> = self
+            .projections
+            .iter()
+            .map(|p| p.projection.checkpoint().last_processed_at)
+            .collect();
+        positions?.into_iter().min().map(|oldest| oldest - REPLAY_SLACK)
+    }
+
+    /// Apply one event to every registered projection
+    async fn apply(&mut self, event: &ProjectionEvent) {
+        for registered in &mut self.projections {
+            let projection = &mut registered.projection;
+            if projection.handles(event) {
+                if let Err(e) = projection.handle(event).await {
+                    tracing::warn!(
+                        projection = %projection.name(),
+                        event_id = %event.event_id,
+                        event_type = %event.event_type,
+                        error = %e,
+                        "Projection failed to apply event - skipped"
+                    );
+                    projection.checkpoint_mut().record_error(event, &e);
+                }
+            }
+            projection.checkpoint_mut().advance(event);
+            registered.dirty = true;
+
+            if let Some(ref tracker) = self.staleness {
+                tracker.record_applied(registered.projection.name(), event.timestamp);
+            }
+        }
+    }
+
+    async fn apply_all(&mut self, events: Vec<ProjectionEvent>) {
+        for event in events {
+            self.apply(&event).await;
+        }
+    }
+
+    async fn flush(&mut self) {
+        for registered in self.projections.iter_mut().filter(|p| p.dirty) {
+            let name = registered.projection.name();
+            match save_checkpoint(&self.session, name, registered.projection.checkpoint()).await {
+                Ok(()) => registered.dirty = false,
+                Err(e) => tracing::warn!(projection = %name, error = %e, "Failed to persist projection checkpoint"),
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Reorder Buffer
+// ============================================================================
+
+/// Events held back and released in EventOrderKey order
+struct ReorderBuffer {
+    window: Duration,
+    pending: BTreeMap<EventOrderKey, (ProjectionEvent, DateTime<Utc>)>,
+    /// Highest key HLC and number of pending events per aggregate
+    heads: HashMap<Uuid, (HybridTimestamp, usize)>,
+}
+
+impl ReorderBuffer {
+    fn new(window: Duration) -> Self {
+        Self { window, pending: BTreeMap::new(), heads: HashMap::new() }
+    }
+
+    /// Hold an event that arrived at `now`
+    ///
+    /// Its key takes the highest HLC of the aggregate's pending events, as
+    /// in `order_events`, so a skewed stamp cannot overtake its predecessor.
+    fn push(&mut self, event: ProjectionEvent, now: DateTime<Utc>) {
+        let mut key = event.order_key();
+        let head = self.heads.entry(event.aggregate_id).or_insert((key.hlc, 0));
+        head.0 = head.0.max(key.hlc);
+        key.hlc = head.0;
+        // A redelivered event replaces its pending copy
+        if self.pending.insert(key, (event, now)).is_none() {
+            head.1 += 1;
+        }
+    }
+
+    /// Events due at `now`, in order: stamped or held at least `window` ago
+    fn release(&mut self, now: DateTime<Utc>) -> Vec<ProjectionEvent> {
+        let window = chrono::Duration::from_std(self.window).unwrap_or(chrono::Duration::MAX);
+        let cutoff = now.checked_sub_signed(window).unwrap_or(DateTime::<Utc>::MIN_UTC);
+        self.release_while(|key, arrived| key.hlc.physical_ms <= cutoff.timestamp_millis() || arrived <= cutoff)
+    }
+
+    /// Every held event, in order
+    fn drain(&mut self) -> Vec<ProjectionEvent> {
+        self.release_while(|_, _| true)
+    }
+
+    fn release_while(&mut self, due: impl Fn(&EventOrderKey, DateTime<Utc>) -> bool) -> Vec<ProjectionEvent> {
+        let mut released = Vec::new();
+        while let Some(entry) = self.pending.first_entry() {
+            if !due(entry.key(), entry.get().1) {
+                break;
+            }
+            let (event, _) = entry.remove();
+            if let Some(head) = self.heads.get_mut(&event.aggregate_id) {
+                head.1 -= 1;
+                if head.1 == 0 {
+                    self.heads.remove(&event.aggregate_id);
+                }
+            }
+            released.push(event);
+        }
+        released
+    }
+}
+
+// ============================================================================
+// Checkpoint Persistence
+// ============================================================================
+
+async fn load_checkpoint(session: &Session, projection: &str) -> anyhow::Result<Option<ProjectionCheckpoint>> {
+    let result = session
+        .query_unpaged(
+            "SELECT last_event_id, last_sequence, last_processed_at, events_processed, errors_count, last_error
+             FROM projection_offsets WHERE projection_name = ? AND partition_id = ?",
+            (projection, CHECKPOINT_PARTITION),
+        )
+        .await?;
+
+    let rows_result = match result.into_rows_result() {
+        Ok(rows) => rows,
+        Err(_) => return Ok(None),
+    };
+
+    let row = rows_result.maybe_first_row::<(
+        Option<Uuid>,
+        Option<i64>,
+        Option<DateTime<Utc>>,
+        Option<i64>,
+        Option<i32>,
+        Option<String>,
+    )>()?;
+
+    Ok(row.map(|(last_event_id, last_sequence, last_processed_at, events_processed, errors_count, last_error)| {
+        ProjectionCheckpoint {
+            last_event_id,
+            last_sequence,
+            last_processed_at,
+            events_processed: events_processed.unwrap_or(0),
+            errors_count: errors_count.unwrap_or(0),
+            last_error,
+        }
+    }))
+}
+
+async fn save_checkpoint(session: &Session, projection: &str, checkpoint: &ProjectionCheckpoint) -> anyhow::Result<()> {
+    session
+        .query_unpaged(
+            "UPDATE projection_offsets
+             SET last_event_id = ?, last_sequence = ?, last_processed_at = ?,
+                 events_processed = ?, errors_count = ?, last_error = ?
+             WHERE projection_name = ? AND partition_id = ?",
+            (
+                checkpoint.last_event_id,
+                checkpoint.last_sequence,
+                checkpoint.last_processed_at,
+                checkpoint.events_processed,
+                checkpoint.errors_count,
+                checkpoint.last_error.as_deref(),
+                projection,
+                CHECKPOINT_PARTITION,
+            ),
+        )
+        .await?;
+    Ok(())
+}
+
+async fn delete_checkpoint(session: &Session, projection: &str) -> anyhow::Result<()> {
+    session
+        .query_unpaged(
+            "DELETE FROM projection_offsets WHERE projection_name = ? AND partition_id = ?",
+            (projection, CHECKPOINT_PARTITION),
+        )
+        .await?;
+    Ok(())
+}
+
+// ============================================================================
+// CDC Consumer
+// ============================================================================
+
+/// Read an outbox insert as a projection event (None for other changes)
+pub(crate) fn projection_event(row: &impl OutboxRow) -> anyhow::Result<Option<ProjectionEvent>> {
+    if !row.is_insert() {
+        return Ok(None);
+    }
+
+    let id = row.uuid("id").ok_or_else(|| anyhow::anyhow!("Missing or invalid id"))?;
+    let aggregate_id = row
+        .uuid("aggregate_id")
+        .ok_or_else(|| anyhow::anyhow!("Missing or invalid aggregate_id"))?;
+    let event_type = row
+        .text("event_type")
+        .ok_or_else(|| anyhow::anyhow!("Missing or invalid event_type"))?;
+    let payload = row.text("payload").ok_or_else(|| anyhow::anyhow!("Missing or invalid payload"))?;
+
+    Ok(Some(ProjectionEvent {
+        // Legacy rows carry no event_id - the outbox id identifies them
+        event_id: row.uuid("event_id").unwrap_or(id),
+        aggregate_id,
+        aggregate_type: row.text("aggregate_type"),
+        event_type,
+        sequence_number: row.bigint("sequence_number"),
+        payload,
+        timestamp: row.timestamp("created_at").unwrap_or_else(Utc::now),
+        hlc: row.text("hlc").and_then(|v| HybridTimestamp::parse(&v)),
+    }))
+}
+
+struct ProjectionConsumer {
+    manager: ActorRef<ProjectionManager>,
+}
+
+#[async_trait]
+impl Consumer for ProjectionConsumer {
+    async fn consume_cdc(&mut self, data: CDCRow<'_>) -> anyhow::Result<()> {
+        if let Some(event) = projection_event(&data)? {
+            // ask, not tell: a busy manager slows down this stream
+            self.manager
+                .ask(ApplyEvent(event))
+                .await
+                .map_err(|e| anyhow::anyhow!("Projection manager unavailable: {}", e))?;
+        }
+        Ok(())
+    }
+}
+
+struct ProjectionConsumerFactory {
+    manager: ActorRef<ProjectionManager>,
+}
+
+#[async_trait]
+impl ConsumerFactory for ProjectionConsumerFactory {
+    async fn new_consumer(&self) -> Box<dyn Consumer> {
+        Box::new(ProjectionConsumer { manager: self.manager.clone() })
+    }
+}
+
+async fn start_log_reader(
+    session: Arc<Session>,
+    source: &CdcSource,
+    manager: ActorRef<ProjectionManager>,
+    resume_from: Option<DateTime<Utc>>,
+) -> anyhow::Result<()> {
+    let mut builder = CDCLogReaderBuilder::new()
+        .session(session)
+        .keyspace(&source.keyspace)
+        .table_name(&source.table)
+        .consumer_factory(Arc::new(ProjectionConsumerFactory { manager }));
+    if let Some(resume_from) = resume_from {
+        builder = builder.start_timestamp(chrono::Duration::milliseconds(resume_from.timestamp_millis()));
+    }
+    let (_reader, handle) = builder
+        .build()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create projection CDC log reader: {}", e))?;
+
+    tokio::spawn(async move {
+        if let Err(e) = handle.await {
+            tracing::error!(error = %e, "Projection CDC reader failed");
+        }
+    });
+    Ok(())
+}
+
+// ============================================================================
+// Actor
+// ============================================================================
+
+impl Actor for ProjectionManager {
+    type Args = Self;
+    type Error = Infallible;
+
+    async fn on_start(mut state: Self::Args, actor_ref: ActorRef<Self>) -> Result<Self, Self::Error> {
+        let names: Vec<&str> = state.projections.iter().map(|p| p.projection.name()).collect();
+        tracing::info!(projections = ?names, "ProjectionManager started");
+
+        if let Some(ref startup) = state.startup {
+            startup.wait_turn(StartupPhase::ProjectionRebuild).await;
+        }
+
+        if !state.projections.is_empty() {
+            if let Err(e) = state.restore_checkpoints().await {
+                tracing::error!(error = %e, "Failed to restore projection checkpoints - starting from now");
+            }
+            let resume_from = state.resume_from();
+            match start_log_reader(state.session.clone(), &state.source, actor_ref.clone(), resume_from).await {
+                Ok(()) => tracing::info!(resume_from = ?resume_from, "📚 Projections consuming CDC"),
+                Err(e) => tracing::error!(error = %e, "Failed to start projection CDC reader"),
+            }
+
+            if !state.reorder.window.is_zero() {
+                let release_ref = actor_ref.clone();
+                let mut interval = tokio::time::interval(state.reorder.window);
+                tokio::spawn(async move {
+                    loop {
+                        interval.tick().await;
+                        if release_ref.tell(ReleaseDue).send().await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+
+            let purge_ref = actor_ref.clone();
+            let mut purge_interval = tokio::time::interval(state.purge_interval);
+            tokio::spawn(async move {
+                loop {
+                    purge_interval.tick().await;
+                    if purge_ref.tell(PurgeTombstones).send().await.is_err() {
+                        break;
+                    }
+                }
+            });
+
+            let interval = state.checkpoint_interval;
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(interval);
+                loop {
+                    interval.tick().await;
+                    if actor_ref.tell(FlushCheckpoints).send().await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        if let Some(ref startup) = state.startup {
+            startup.complete(StartupPhase::ProjectionRebuild);
+        }
+        Ok(state)
+    }
+
+    async fn on_stop(
+        &mut self,
+        _actor_ref: kameo::actor::WeakActorRef<Self>,
+        _reason: kameo::error::ActorStopReason,
+    ) -> Result<(), Self::Error> {
+        let held = self.reorder.drain();
+        self.apply_all(held).await;
+        self.flush().await;
+        tracing::info!("🛑 ProjectionManager stopped");
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Message Handlers
+// ============================================================================
+
+impl Message<ApplyEvent> for ProjectionManager {
+    type Reply = ();
+
+    async fn handle(&mut self, ApplyEvent(event): ApplyEvent, _ctx: &mut Context<Self, Self::Reply>) -> Self::Reply {
+        if self.reorder.window.is_zero() {
+            return self.apply(&event).await;
+        }
+        let now = Utc::now();
+        self.reorder.push(event, now);
+        let due = self.reorder.release(now);
+        self.apply_all(due).await;
+    }
+}
+
+impl Message<ReleaseDue> for ProjectionManager {
+    type Reply = ();
+
+    async fn handle(&mut self, _msg: ReleaseDue, _ctx: &mut Context<Self, Self::Reply>) -> Self::Reply {
+        let due = self.reorder.release(Utc::now());
+        self.apply_all(due).await;
+    }
+}
+
+impl Message<FlushCheckpoints> for ProjectionManager {
+    type Reply = ();
+
+    async fn handle(&mut self, _msg: FlushCheckpoints, _ctx: &mut Context<Self, Self::Reply>) -> Self::Reply {
+        self.flush().await;
+    }
+}
+
+impl Message<PurgeTombstones> for ProjectionManager {
+    type Reply = ();
+
+    async fn handle(&mut self, _msg: PurgeTombstones, _ctx: &mut Context<Self, Self::Reply>) -> Self::Reply {
+        for registered in &mut self.projections {
+            let projection = &mut registered.projection;
+            if let Err(e) = projection.purge_expired().await {
+                tracing::warn!(projection = %projection.name(), error = %e, "Failed to purge expired soft-deleted rows");
+            }
+        }
+    }
+}
+
+impl Message<ResetProjection> for ProjectionManager {
+    type Reply = Result<(), String>;
+
+    async fn handle(&mut self, msg: ResetProjection, _ctx: &mut Context<Self, Self::Reply>) -> Self::Reply {
+        let registered = self
+            .projections
+            .iter_mut()
+            .find(|p| p.projection.name() == msg.name)
+            .ok_or_else(|| format!("Unknown projection '{}'", msg.name))?;
+
+        registered
+            .projection
+            .reset()
+            .await
+            .map_err(|e| format!("Failed to reset projection '{}': {}", msg.name, e))?;
+        *registered.projection.checkpoint_mut() = ProjectionCheckpoint::default();
+        registered.dirty = false;
+        delete_checkpoint(&self.session, &msg.name)
+            .await
+            .map_err(|e| format!("Failed to delete checkpoint of '{}': {}", msg.name, e))?;
+
+        tracing::info!(projection = %msg.name, "Projection reset");
+        Ok(())
+    }
+}
+
+impl Message<GetProjectionStatus> for ProjectionManager {
+    type Reply = Vec<ProjectionStatus>;
+
+    async fn handle(&mut self, _msg: GetProjectionStatus, _ctx: &mut Context<Self, Self::Reply>) -> Self::Reply {
+        self.projections
+            .iter()
+            .map(|p| ProjectionStatus {
+                name: p.projection.name().to_string(),
+                checkpoint: p.projection.checkpoint().clone(),
+            })
+            .collect()
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actors::test_support::SyntheticOutboxRow;
+    use scylla_cdc::consumer::OperationType;
+
+    #[test]
+    fn test_projection_event_from_outbox_insert() {
+        let created_at = Utc::now();
+        let row = SyntheticOutboxRow::outbox_event("OrderCreated", r#"{"total": 42}"#)
+            .timestamp("created_at", created_at);
+
+        let event = projection_event(&row).unwrap().unwrap();
+        assert_eq!(event.event_id, row.get_uuid("event_id"));
+        assert_eq!(event.aggregate_id, row.get_uuid("aggregate_id"));
+        assert_eq!(event.aggregate_type.as_deref(), Some("Order"));
+        assert_eq!(event.event_type, "OrderCreated");
+        assert_eq!(event.sequence_number, Some(1));
+        assert_eq!(event.timestamp, created_at);
+    }
+
+    #[test]
+    fn test_legacy_rows_and_non_inserts() {
+        let legacy = SyntheticOutboxRow::outbox_event("OrderCreated", "{}")
+            .without("event_id")
+            .without("aggregate_type");
+        let event = projection_event(&legacy).unwrap().unwrap();
+        assert_eq!(event.event_id, legacy.get_uuid("id"));
+        assert_eq!(event.aggregate_type, None);
+
+        let delete = SyntheticOutboxRow::with_operation(OperationType::RowDelete);
+        assert!(projection_event(&delete).unwrap().is_none());
+
+        let broken = SyntheticOutboxRow::outbox_event("OrderCreated", "{}").without("payload");
+        assert!(projection_event(&broken).is_err());
+    }
+
+    fn stamped(aggregate_id: Uuid, sequence_number: i64, hlc_ms: i64) -> ProjectionEvent {
+        ProjectionEvent {
+            event_id: Uuid::new_v4(),
+            aggregate_id,
+            aggregate_type: Some("Order".to_string()),
+            event_type: "OrderCreated".to_string(),
+            sequence_number: Some(sequence_number),
+            payload: "{}".to_string(),
+            timestamp: Utc::now(),
+            hlc: Some(HybridTimestamp::new(hlc_ms, 0)),
+        }
+    }
+
+    fn positions(events: &[ProjectionEvent]) -> Vec<(Uuid, Option<i64>)> {
+        events.iter().map(|e| (e.aggregate_id, e.sequence_number)).collect()
+    }
+
+    #[test]
+    fn test_projection_event_reads_hlc() {
+        let row = SyntheticOutboxRow::outbox_event("OrderCreated", "{}").text("hlc", "1700000000000.3");
+        let event = projection_event(&row).unwrap().unwrap();
+        assert_eq!(event.hlc, Some(HybridTimestamp::new(1_700_000_000_000, 3)));
+
+        let unstamped = SyntheticOutboxRow::outbox_event("OrderCreated", "{}");
+        assert_eq!(projection_event(&unstamped).unwrap().unwrap().hlc, None);
+    }
+
+    #[test]
+    fn test_reorder_buffer_releases_in_hlc_order_once_due() {
+        let now = Utc::now();
+        let ms = now.timestamp_millis();
+        let (a, b) = (Uuid::from_u128(1), Uuid::from_u128(2));
+        let mut buffer = ReorderBuffer::new(Duration::from_millis(250));
+
+        // b's event arrives first although a's was stamped earlier
+        buffer.push(stamped(b, 1, ms - 100), now);
+        buffer.push(stamped(a, 1, ms - 200), now);
+        assert!(buffer.release(now).is_empty());
+
+        let released = buffer.release(now + chrono::Duration::milliseconds(150));
+        assert_eq!(positions(&released), vec![(a, Some(1)), (b, Some(1))]);
+        assert!(buffer.pending.is_empty() && buffer.heads.is_empty());
+    }
+
+    #[test]
+    fn test_reorder_buffer_keeps_sequence_order_despite_skew() {
+        let now = Utc::now();
+        let ms = now.timestamp_millis();
+        let (a, b) = (Uuid::from_u128(1), Uuid::from_u128(2));
+        let mut buffer = ReorderBuffer::new(Duration::from_millis(250));
+
+        buffer.push(stamped(a, 1, ms - 100), now);
+        // a:2 was stamped by a node whose clock is behind
+        buffer.push(stamped(a, 2, ms - 5_000), now);
+        buffer.push(stamped(b, 1, ms - 50), now);
+
+        let released = buffer.drain();
+        assert_eq!(positions(&released), vec![(a, Some(1)), (a, Some(2)), (b, Some(1))]);
+    }
+
+    #[test]
+    fn test_reorder_buffer_releases_future_stamps_after_window() {
+        let now = Utc::now();
+        let mut buffer = ReorderBuffer::new(Duration::from_millis(250));
+
+        // Stamped by a node whose clock is an hour ahead
+        buffer.push(stamped(Uuid::new_v4(), 1, (now + chrono::Duration::hours(1)).timestamp_millis()), now);
+        assert!(buffer.release(now).is_empty());
+        assert_eq!(buffer.release(now + chrono::Duration::milliseconds(250)).len(), 1);
+    }
+}
@@ -0,0 +1,39 @@
+// ============================================================================
+// Projections Module - Read Models Built from Events
+// ============================================================================
+//
+// Read models are denormalized, query-optimized views derived from the
+// event stream. They can be rebuilt at any time by replaying events.
+//
+// Structure:
+// - projection  - Projection trait, events and checkpoints
+// - customer_read_model - Customer read model projection and CustomerQueryService
+// - manager     - ProjectionManager actor feeding projections from CDC
+// - order_read_model - Order read model projection and OrderQueryService
+// - paging      - Cursor pagination of read model listings
+// - soft_delete - Tombstone-driven soft deletes for read model rows
+// - staleness   - Per-projection staleness against a consistency SLA
+// - versioned   - Version-guarded read model writes (idempotent, order-free)
+//
+// ============================================================================
+
+// Private module declarations
+mod customer_read_model;
+mod manager;
+mod order_read_model;
+mod paging;
+mod projection;
+mod soft_delete;
+mod staleness;
+mod versioned;
+
+// Re-export for public API
+pub use customer_read_model::{CustomerAddress, CustomerFilter, CustomerQueryService, CustomerReadModel, CustomerView};
+pub use manager::{ApplyEvent, FlushCheckpoints, GetProjectionStatus, ProjectionManager, ProjectionStatus, ResetProjection};
+pub use order_read_model::{OrderQueryService, OrderReadModel, OrderSummary, OrderView};
+pub use paging::{Page, Paging};
+pub(crate) use paging::query_page;
+pub use projection::{Projection, ProjectionCheckpoint, ProjectionEvent};
+pub use soft_delete::{SoftDeletePolicy, SoftDeleteStore, Visibility};
+pub use staleness::{ProjectionStaleness, StalenessTracker};
+pub use versioned::{supersedes, VersionedTable, VersionedWrite};
@@ -0,0 +1,372 @@
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use scylla::client::session::Session;
+use scylla::value::{CqlTimestamp, CqlValue};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::domain::order::{OrderAggregate, OrderEvent, OrderItem};
+use crate::event_sourcing::{AggregateRoot, EventEnvelope, EventStore};
+use super::paging::{query_page, Page, Paging};
+use super::projection::{Projection, ProjectionCheckpoint, ProjectionEvent};
+use super::soft_delete::{SoftDeletePolicy, SoftDeleteStore, Visibility};
+use super::versioned::VersionedTable;
+
+// ============================================================================
+// Order Read Model - Current Order State Without Replay
+// ============================================================================
+//
+// The OrderReadModel projection keeps two tables current from the CDC
+// event stream:
+//
+//   order_read_model    one row per order (customer, items, status, times)
+//   orders_by_customer  the orders of a customer, newest first
+//
+// For every Order event it loads the order's events from the event store
+// and writes the state they add up to, stamped with the newest sequence
+// number (versioned writes, see versioned.rs). Rows therefore always
+// reflect a complete state: a late or replayed event is skipped as stale
+// instead of overwriting newer state, and events arriving out of order
+// cannot leave a row with half of its columns. Times are event times, so
+// re-projecting an order yields the same orders_by_customer key.
+//
+// With a SoftDeletePolicy (the SystemBuilder tombstones OrderCancelled),
+// the first tombstone in the history marks both rows is_deleted, in the
+// same versioned write. Past the policy's retention `purge_expired`
+// deletes them (see soft_delete.rs).
+//
+// OrderQueryService reads both tables and hides deleted orders unless
+// asked for Visibility::IncludeDeleted. Reads are eventually consistent:
+// a command's effects show up once its events went through CDC (see the
+// projection's staleness). Customer listings page with an opaque cursor;
+// a page may hold fewer than `limit` orders when some were deleted.
+//
+// ============================================================================
+
+/// Staleness SLA reported for the order read model
+const ORDER_READ_MODEL_SLA: Duration = Duration::from_secs(30);
+
+/// Current state of an order as stored in order_read_model
+#[derive(Debug, Clone, Serialize)]
+pub struct OrderView {
+    pub order_id: Uuid,
+    pub customer_id: Uuid,
+    pub items: Vec<OrderItem>,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Sequence number of the newest event reflected
+    pub version: i64,
+    /// When the order was soft-deleted; None while active
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl OrderView {
+    /// State after `events` (the order's full history); None without events
+    pub fn from_events(order_id: Uuid, events: Vec<EventEnvelope<OrderEvent>>) -> Result<Option<Self>> {
+        let (Some(first), Some(last)) = (events.first(), events.last()) else {
+            return Ok(None);
+        };
+        let (created_at, updated_at, version) = (first.timestamp, last.timestamp, last.sequence_number);
+        let order = OrderAggregate::load_from_events(events)?;
+
+        Ok(Some(Self {
+            order_id,
+            customer_id: order.customer_id,
+            items: order.items,
+            status: format!("{:?}", order.status),
+            created_at,
+            updated_at,
+            version,
+            deleted_at: None,
+        }))
+    }
+}
+
+/// An order in a customer's listing
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OrderSummary {
+    pub order_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub status: Option<String>,
+    pub version: Option<i64>,
+}
+
+// ----------------------------------------------------------------------------
+// Projection
+// ----------------------------------------------------------------------------
+
+pub struct OrderReadModel {
+    session: Arc<Session>,
+    event_store: Arc<EventStore<OrderEvent>>,
+    orders: VersionedTable,
+    by_customer: VersionedTable,
+    soft_delete: Option<SoftDeleteStore>,
+    checkpoint: ProjectionCheckpoint,
+}
+
+impl OrderReadModel {
+    pub fn new(session: Arc<Session>, event_store: Arc<EventStore<OrderEvent>>) -> Self {
+        Self {
+            orders: VersionedTable::new(session.clone(), "order_read_model"),
+            by_customer: VersionedTable::new(session.clone(), "orders_by_customer"),
+            session,
+            event_store,
+            soft_delete: None,
+            checkpoint: ProjectionCheckpoint::default(),
+        }
+    }
+
+    /// Soft-delete orders on the policy's tombstone events
+    pub fn with_soft_delete(mut self, policy: SoftDeletePolicy) -> Self {
+        self.soft_delete = Some(SoftDeleteStore::new(self.session.clone(), "order_read_model", "order_id", policy));
+        self
+    }
+
+    /// Customer and creation time of a stored order (its orders_by_customer key)
+    async fn customer_key(&self, order_id: Uuid) -> Result<Option<(Uuid, DateTime<Utc>)>> {
+        let row = self
+            .session
+            .query_unpaged("SELECT customer_id, created_at FROM order_read_model WHERE order_id = ?", (order_id,))
+            .await?
+            .into_rows_result()?
+            .maybe_first_row::<(Option<Uuid>, Option<DateTime<Utc>>)>()?;
+        Ok(match row {
+            Some((Some(customer_id), Some(created_at))) => Some((customer_id, created_at)),
+            _ => None,
+        })
+    }
+}
+
+#[async_trait]
+impl Projection for OrderReadModel {
+    fn name(&self) -> &str {
+        "order_read_model"
+    }
+
+    fn handles(&self, event: &ProjectionEvent) -> bool {
+        event.aggregate_type.as_deref() == Some("Order")
+    }
+
+    fn staleness_sla(&self) -> Option<Duration> {
+        Some(ORDER_READ_MODEL_SLA)
+    }
+
+    async fn handle(&mut self, event: &ProjectionEvent) -> Result<()> {
+        let events = self.event_store.load_events(event.aggregate_id).await?;
+        let deleted_at = self.soft_delete.as_ref().and_then(|soft_delete| {
+            soft_delete.policy().deleted_at(events.iter().map(|e| (e.event_type.as_str(), e.timestamp)))
+        });
+        let Some(view) = OrderView::from_events(event.aggregate_id, events)? else {
+            bail!("No events stored for order {}", event.aggregate_id);
+        };
+
+        let created_at = CqlValue::Timestamp(CqlTimestamp(view.created_at.timestamp_millis()));
+        let is_deleted = ("is_deleted", CqlValue::Boolean(deleted_at.is_some()));
+        let mut columns = vec![
+            ("customer_id", CqlValue::Uuid(view.customer_id)),
+            ("items", CqlValue::Text(serde_json::to_string(&view.items)?)),
+            ("status", CqlValue::Text(view.status.clone())),
+            ("created_at", created_at.clone()),
+            ("updated_at", CqlValue::Timestamp(CqlTimestamp(view.updated_at.timestamp_millis()))),
+            is_deleted.clone(),
+        ];
+        if let Some(at) = deleted_at {
+            columns.push(("deleted_at", CqlValue::Timestamp(CqlTimestamp(at.timestamp_millis()))));
+        }
+        self.orders.upsert(&[("order_id", CqlValue::Uuid(view.order_id))], view.version, &columns).await?;
+        self.by_customer
+            .upsert(
+                &[
+                    ("customer_id", CqlValue::Uuid(view.customer_id)),
+                    ("created_at", created_at),
+                    ("order_id", CqlValue::Uuid(view.order_id)),
+                ],
+                view.version,
+                &[("status", CqlValue::Text(view.status)), is_deleted],
+            )
+            .await?;
+
+        if let (Some(soft_delete), Some(at)) = (&self.soft_delete, deleted_at) {
+            soft_delete.record(view.order_id, at).await?;
+        }
+        Ok(())
+    }
+
+    fn checkpoint(&self) -> &ProjectionCheckpoint {
+        &self.checkpoint
+    }
+
+    fn checkpoint_mut(&mut self) -> &mut ProjectionCheckpoint {
+        &mut self.checkpoint
+    }
+
+    async fn reset(&mut self) -> Result<()> {
+        for table in ["order_read_model", "orders_by_customer"] {
+            self.session.query_unpaged(format!("TRUNCATE {}", table), &[]).await?;
+        }
+        // read_model_tombstones stays: the rebuild records the same entries again
+        Ok(())
+    }
+
+    async fn purge_expired(&mut self) -> Result<usize> {
+        let Some(ref soft_delete) = self.soft_delete else {
+            return Ok(0);
+        };
+
+        let expired = soft_delete.expired(Utc::now()).await?;
+        for (order_id, deleted_at) in &expired {
+            if let Some((customer_id, created_at)) = self.customer_key(*order_id).await? {
+                self.session
+                    .query_unpaged(
+                        "DELETE FROM orders_by_customer WHERE customer_id = ? AND created_at = ? AND order_id = ?",
+                        (customer_id, created_at, order_id),
+                    )
+                    .await?;
+            }
+            soft_delete.purge(*order_id, *deleted_at).await?;
+        }
+
+        if !expired.is_empty() {
+            tracing::info!(purged = expired.len(), "🪦 Hard-deleted expired cancelled orders");
+        }
+        Ok(expired.len())
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Queries
+// ----------------------------------------------------------------------------
+
+/// Reads of the order read model
+pub struct OrderQueryService {
+    session: Arc<Session>,
+}
+
+impl OrderQueryService {
+    pub fn new(session: Arc<Session>) -> Self {
+        Self { session }
+    }
+
+    /// Current state of an order; None until its first event is projected
+    /// and once it was deleted
+    pub async fn get_order(&self, order_id: Uuid) -> Result<Option<OrderView>> {
+        self.get_order_with_visibility(order_id, Visibility::ActiveOnly).await
+    }
+
+    pub async fn get_order_with_visibility(&self, order_id: Uuid, visibility: Visibility) -> Result<Option<OrderView>> {
+        let row = self
+            .session
+            .query_unpaged(
+                "SELECT customer_id, items, status, created_at, updated_at, version, is_deleted, deleted_at
+                 FROM order_read_model WHERE order_id = ?",
+                (order_id,),
+            )
+            .await?
+            .into_rows_result()?
+            .maybe_first_row::<(
+                Option<Uuid>,
+                Option<String>,
+                Option<String>,
+                Option<DateTime<Utc>>,
+                Option<DateTime<Utc>>,
+                Option<i64>,
+                Option<bool>,
+                Option<DateTime<Utc>>,
+            )>()?;
+
+        let Some((Some(customer_id), items, status, Some(created_at), updated_at, version, is_deleted, deleted_at)) = row
+        else {
+            return Ok(None);
+        };
+        if !visibility.is_visible(is_deleted) {
+            return Ok(None);
+        }
+        Ok(Some(OrderView {
+            order_id,
+            customer_id,
+            items: match items {
+                Some(items) => serde_json::from_str(&items)?,
+                None => Vec::new(),
+            },
+            status: status.unwrap_or_default(),
+            created_at,
+            updated_at: updated_at.unwrap_or(created_at),
+            version: version.unwrap_or_default(),
+            deleted_at,
+        }))
+    }
+
+    /// Active orders of a customer, newest first
+    pub async fn list_orders_by_customer(&self, customer_id: Uuid, paging: &Paging) -> Result<Page<OrderSummary>> {
+        self.list_orders_by_customer_with_visibility(customer_id, paging, Visibility::ActiveOnly).await
+    }
+
+    pub async fn list_orders_by_customer_with_visibility(
+        &self,
+        customer_id: Uuid,
+        paging: &Paging,
+        visibility: Visibility,
+    ) -> Result<Page<OrderSummary>> {
+        let (rows, next_cursor) = query_page(
+            &self.session,
+            "SELECT order_id, created_at, status, version, is_deleted FROM orders_by_customer WHERE customer_id = ?",
+            (customer_id,),
+            paging,
+        )
+        .await?;
+        let mut items = Vec::new();
+        for row in rows.rows::<(Uuid, DateTime<Utc>, Option<String>, Option<i64>, Option<bool>)>()? {
+            let (order_id, created_at, status, version, is_deleted) = row?;
+            if visibility.is_visible(is_deleted) {
+                items.push(OrderSummary { order_id, created_at, status, version });
+            }
+        }
+        Ok(Page { items, next_cursor })
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::order::{OrderConfirmed, OrderCreated};
+
+    #[test]
+    fn test_view_reflects_full_history_with_event_times() {
+        let order_id = Uuid::new_v4();
+        let customer_id = Uuid::new_v4();
+        let items = vec![OrderItem { product_id: Uuid::new_v4(), quantity: 2 }];
+        let created = EventEnvelope::new(
+            order_id,
+            1,
+            "OrderCreated".to_string(),
+            OrderEvent::Created(OrderCreated { customer_id, items: items.clone() }),
+            Uuid::new_v4(),
+        );
+        let mut confirmed = EventEnvelope::new(
+            order_id,
+            2,
+            "OrderConfirmed".to_string(),
+            OrderEvent::Confirmed(OrderConfirmed { confirmed_at: Utc::now() }),
+            Uuid::new_v4(),
+        );
+        confirmed.timestamp = created.timestamp + chrono::Duration::seconds(5);
+
+        let view = OrderView::from_events(order_id, vec![created.clone(), confirmed.clone()]).unwrap().unwrap();
+        assert_eq!(view.customer_id, customer_id);
+        assert_eq!(view.items.len(), 1);
+        assert_eq!(view.items[0].product_id, items[0].product_id);
+        assert_eq!(view.status, "Confirmed");
+        assert_eq!(view.version, 2);
+        assert_eq!((view.created_at, view.updated_at), (created.timestamp, confirmed.timestamp));
+
+        assert!(OrderView::from_events(order_id, Vec::new()).unwrap().is_none());
+    }
+}
@@ -0,0 +1,111 @@
+use anyhow::{bail, Result};
+use scylla::client::session::Session;
+use scylla::response::query_result::QueryRowsResult;
+use scylla::response::{PagingState, PagingStateResponse};
+use scylla::serialize::row::SerializeRow;
+use scylla::statement::unprepared::Statement;
+use serde::Serialize;
+
+// ============================================================================
+// Paging - Cursor Pagination of Read Model Listings
+// ============================================================================
+//
+// Listings of the query services return one page at a time. The cursor is
+// the driver's paging state, hex-encoded: opaque to callers and only valid
+// for the listing (query and filters) that produced it.
+//
+// ============================================================================
+
+/// Rows per page unless asked otherwise
+const DEFAULT_PAGE_SIZE: i32 = 50;
+
+/// Upper bound of rows per page
+const MAX_PAGE_SIZE: i32 = 500;
+
+/// Which page of a listing to return
+#[derive(Debug, Clone, Default)]
+pub struct Paging {
+    /// Rows per page (default 50, at most 500)
+    pub limit: Option<i32>,
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+}
+
+impl Paging {
+    pub(crate) fn page_size(&self) -> i32 {
+        self.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE)
+    }
+}
+
+/// One page of a listing
+#[derive(Debug, Clone, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Cursor of the next page; None on the last one
+    pub next_cursor: Option<String>,
+}
+
+/// Run one page of `query`; returns its rows and the next page's cursor
+pub(crate) async fn query_page(
+    session: &Session,
+    query: &str,
+    values: impl SerializeRow,
+    paging: &Paging,
+) -> Result<(QueryRowsResult, Option<String>)> {
+    let mut statement = Statement::new(query);
+    statement.set_page_size(paging.page_size());
+    let paging_state = match paging.cursor {
+        Some(ref cursor) => PagingState::new_from_raw_bytes(decode_cursor(cursor)?),
+        None => PagingState::start(),
+    };
+
+    let (result, paging_response) = session.query_single_page(statement, values, paging_state).await?;
+    let next_cursor = match paging_response {
+        PagingStateResponse::HasMorePages { state } => state.as_bytes_slice().map(|bytes| encode_cursor(bytes)),
+        PagingStateResponse::NoMorePages => None,
+    };
+    Ok((result.into_rows_result()?, next_cursor))
+}
+
+/// Cursor of a Scylla paging state (hex)
+fn encode_cursor(paging_state: &[u8]) -> String {
+    paging_state.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn decode_cursor(cursor: &str) -> Result<Vec<u8>> {
+    if cursor.is_empty() || !cursor.len().is_multiple_of(2) || !cursor.is_ascii() {
+        bail!("Invalid cursor");
+    }
+    (0..cursor.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&cursor[i..i + 2], 16).map_err(|_| anyhow::anyhow!("Invalid cursor")))
+        .collect()
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let paging_state = vec![0x00, 0x1f, 0xa0, 0xff];
+        let cursor = encode_cursor(&paging_state);
+        assert_eq!(cursor, "001fa0ff");
+        assert_eq!(decode_cursor(&cursor).unwrap(), paging_state);
+
+        assert!(decode_cursor("").is_err());
+        assert!(decode_cursor("abc").is_err());
+        assert!(decode_cursor("zz").is_err());
+    }
+
+    #[test]
+    fn test_page_size_clamped() {
+        assert_eq!(Paging::default().page_size(), DEFAULT_PAGE_SIZE);
+        assert_eq!(Paging { limit: Some(0), cursor: None }.page_size(), 1);
+        assert_eq!(Paging { limit: Some(10_000), cursor: None }.page_size(), MAX_PAGE_SIZE);
+    }
+}
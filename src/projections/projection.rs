@@ -0,0 +1,183 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::event_sourcing::{EventOrderKey, HybridTimestamp};
+
+// ============================================================================
+// Projection - Read Model Built from the CDC Event Stream
+// ============================================================================
+//
+// A projection applies events (outbox inserts streamed through CDC) to its
+// read model. The ProjectionManager feeds every registered projection and
+// persists each one's checkpoint in projection_offsets, so a restarted
+// instance resumes where it left off.
+//
+// Delivery is at-least-once: after a restart the manager replays a little
+// before the checkpoint, and CDC streams of different vnodes interleave.
+// `handle` must therefore be idempotent (e.g. upserts keyed by aggregate,
+// guarded by the event's sequence number).
+//
+// Projections with soft deletes tombstone rows in `handle` and hard-delete
+// them past retention in `purge_expired`, which the manager calls every
+// `purge_interval`.
+//
+// ============================================================================
+
+/// An event as seen by projections
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProjectionEvent {
+    pub event_id: Uuid,
+    pub aggregate_id: Uuid,
+    /// None for legacy outbox rows
+    pub aggregate_type: Option<String>,
+    pub event_type: String,
+    /// Aggregate sequence; None for legacy outbox rows
+    pub sequence_number: Option<i64>,
+    /// Serialized event data (JSON)
+    pub payload: String,
+    /// When the event was written
+    pub timestamp: DateTime<Utc>,
+    /// HLC stamp of the append; None for rows written before stamping
+    pub hlc: Option<HybridTimestamp>,
+}
+
+impl ProjectionEvent {
+    /// Deserialize the event data
+    pub fn data<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(serde_json::from_str(&self.payload)?)
+    }
+
+    /// Position in the cross-aggregate order (wall clock without an HLC)
+    pub fn order_key(&self) -> EventOrderKey {
+        EventOrderKey {
+            hlc: self.hlc.unwrap_or_else(|| HybridTimestamp::new(self.timestamp.timestamp_millis(), 0)),
+            aggregate_id: self.aggregate_id,
+            sequence_number: self.sequence_number.unwrap_or(0),
+        }
+    }
+}
+
+/// How far a projection got in the event stream
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ProjectionCheckpoint {
+    pub last_event_id: Option<Uuid>,
+    pub last_sequence: Option<i64>,
+    /// Event time of the newest event applied - where a restart resumes
+    pub last_processed_at: Option<DateTime<Utc>>,
+    pub events_processed: i64,
+    pub errors_count: i32,
+    pub last_error: Option<String>,
+}
+
+impl ProjectionCheckpoint {
+    /// `event` was applied (or skipped as not relevant)
+    pub fn advance(&mut self, event: &ProjectionEvent) {
+        self.last_event_id = Some(event.event_id);
+        self.last_sequence = event.sequence_number;
+        self.events_processed += 1;
+        // Streams interleave - never move the resume point backwards
+        if self.last_processed_at.is_none_or(|at| event.timestamp > at) {
+            self.last_processed_at = Some(event.timestamp);
+        }
+    }
+
+    /// `event` failed to apply and was skipped
+    pub fn record_error(&mut self, event: &ProjectionEvent, error: &anyhow::Error) {
+        self.errors_count += 1;
+        self.last_error = Some(format!("{} ({}): {}", event.event_type, event.event_id, error));
+    }
+}
+
+/// A read model maintained from the event stream
+#[async_trait]
+pub trait Projection: Send + 'static {
+    /// Unique name; keys the persisted checkpoint
+    fn name(&self) -> &str;
+
+    /// Whether `event` is relevant (irrelevant events only advance the checkpoint)
+    #[allow(unused_variables)]
+    fn handles(&self, event: &ProjectionEvent) -> bool {
+        true
+    }
+
+    /// Staleness SLA reported for this projection
+    fn staleness_sla(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Apply one event to the read model; must be idempotent
+    async fn handle(&mut self, event: &ProjectionEvent) -> Result<()>;
+
+    /// Current position in the event stream
+    fn checkpoint(&self) -> &ProjectionCheckpoint;
+
+    /// Position the manager advances and restores after a restart
+    fn checkpoint_mut(&mut self) -> &mut ProjectionCheckpoint;
+
+    /// Drop the read model's state (the manager clears the checkpoint)
+    async fn reset(&mut self) -> Result<()>;
+
+    /// Hard-delete rows soft-deleted longer than their retention (see
+    /// soft_delete.rs); returns the rows removed
+    async fn purge_expired(&mut self) -> Result<usize> {
+        Ok(0)
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(sequence_number: i64, timestamp: DateTime<Utc>) -> ProjectionEvent {
+        ProjectionEvent {
+            event_id: Uuid::new_v4(),
+            aggregate_id: Uuid::new_v4(),
+            aggregate_type: Some("Order".to_string()),
+            event_type: "OrderCreated".to_string(),
+            sequence_number: Some(sequence_number),
+            payload: r#"{"total": 42}"#.to_string(),
+            timestamp,
+            hlc: None,
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_never_moves_resume_point_backwards() {
+        let now = Utc::now();
+        let mut checkpoint = ProjectionCheckpoint::default();
+
+        let newer = event(2, now);
+        checkpoint.advance(&newer);
+        // Interleaved stream delivers an older event afterwards
+        let older = event(1, now - chrono::Duration::seconds(5));
+        checkpoint.advance(&older);
+
+        assert_eq!(checkpoint.events_processed, 2);
+        assert_eq!(checkpoint.last_event_id, Some(older.event_id));
+        assert_eq!(checkpoint.last_processed_at, Some(now));
+
+        checkpoint.record_error(&older, &anyhow::anyhow!("boom"));
+        assert_eq!(checkpoint.errors_count, 1);
+        assert!(checkpoint.last_error.as_deref().unwrap().ends_with("boom"));
+    }
+
+    #[test]
+    fn test_event_data() {
+        #[derive(serde::Deserialize)]
+        struct Created {
+            total: i64,
+        }
+
+        let created: Created = event(1, Utc::now()).data().unwrap();
+        assert_eq!(created.total, 42);
+    }
+}
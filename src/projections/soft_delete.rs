@@ -0,0 +1,257 @@
+use scylla::client::session::Session;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::{DateTime, NaiveDate, Utc};
+use uuid::Uuid;
+use anyhow::Result;
+
+// ============================================================================
+// Soft Deletes for Read Models
+// ============================================================================
+//
+// When an aggregate reaches a terminal state (order cancelled, customer
+// deactivated) its read model row should disappear from queries, but the
+// history must stay available for audits and support.
+//
+// Each projection configures which event types are tombstones. The first
+// tombstone in an aggregate's history sets `is_deleted = true` and
+// `deleted_at` on the row instead of deleting it - written by the
+// projection together with the rest of the row (versioned, so a late
+// update cannot resurrect it). Queries hide tombstoned rows unless asked
+// for Visibility::IncludeDeleted.
+//
+// With a hard-delete retention, SoftDeleteStore also records every
+// tombstone in read_model_tombstones, partitioned by table and the day of
+// deleted_at. `expired` reads the day buckets up to now - retention (no
+// table scans) and `purge` deletes a row with its index entry; projections
+// do this in Projection::purge_expired, which the ProjectionManager calls
+// periodically, and also drop the rows they derived from it. `expired`
+// looks back PURGE_LOOKBACK_DAYS days from the cutoff; rows past that
+// (purging was off for longer) stay soft-deleted.
+//
+// Read model tables opt in by having these columns:
+//   is_deleted BOOLEAN, deleted_at TIMESTAMP
+//
+// ============================================================================
+
+/// Day buckets before the cutoff read by one purge pass
+const PURGE_LOOKBACK_DAYS: usize = 30;
+
+/// Whether queries should include soft-deleted rows
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Visibility {
+    /// Hide tombstoned rows (default for API queries)
+    #[default]
+    ActiveOnly,
+    /// Return every row, including tombstoned ones
+    IncludeDeleted,
+}
+
+impl Visibility {
+    /// Whether a row with the given `is_deleted` column value should be returned
+    pub fn is_visible(&self, is_deleted: Option<bool>) -> bool {
+        match self {
+            Visibility::ActiveOnly => !is_deleted.unwrap_or(false),
+            Visibility::IncludeDeleted => true,
+        }
+    }
+}
+
+/// Per-projection soft-delete configuration
+#[derive(Debug, Clone)]
+pub struct SoftDeletePolicy {
+    tombstone_event_types: HashSet<String>,
+    /// Hard-delete tombstoned rows after this long (None = keep forever)
+    pub hard_delete_after: Option<Duration>,
+}
+
+impl SoftDeletePolicy {
+    pub fn new<I, S>(tombstone_event_types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            tombstone_event_types: tombstone_event_types.into_iter().map(Into::into).collect(),
+            hard_delete_after: None,
+        }
+    }
+
+    pub fn with_hard_delete_after(mut self, retention: Duration) -> Self {
+        self.hard_delete_after = Some(retention);
+        self
+    }
+
+    /// Whether this event type tombstones the read model row
+    pub fn is_tombstone(&self, event_type: &str) -> bool {
+        self.tombstone_event_types.contains(event_type)
+    }
+
+    /// Time of the first tombstone in a history of (event type, event time)
+    pub fn deleted_at<'a>(&self, history: impl IntoIterator<Item = (&'a str, DateTime<Utc>)>) -> Option<DateTime<Utc>> {
+        history.into_iter().find(|(event_type, _)| self.is_tombstone(event_type)).map(|(_, at)| at)
+    }
+
+    /// Rows tombstoned at or before this are past retention; None without one
+    pub fn expiry_cutoff(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let retention = chrono::Duration::from_std(self.hard_delete_after?).ok()?;
+        now.checked_sub_signed(retention)
+    }
+
+    /// Whether a row tombstoned at `deleted_at` is past retention
+    pub fn is_expired(&self, deleted_at: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        self.expiry_cutoff(now).is_some_and(|cutoff| deleted_at <= cutoff)
+    }
+}
+
+/// Day buckets a purge pass reads for `cutoff`, newest first
+fn purge_buckets(cutoff: DateTime<Utc>) -> Vec<NaiveDate> {
+    std::iter::successors(Some(cutoff.date_naive()), NaiveDate::pred_opt)
+        .take(PURGE_LOOKBACK_DAYS + 1)
+        .collect()
+}
+
+/// Tombstone index and hard deletes of one read model table
+pub struct SoftDeleteStore {
+    session: Arc<Session>,
+    table: String,
+    key_column: String,
+    policy: SoftDeletePolicy,
+}
+
+impl SoftDeleteStore {
+    pub fn new(session: Arc<Session>, table: &str, key_column: &str, policy: SoftDeletePolicy) -> Self {
+        Self {
+            session,
+            table: table.to_string(),
+            key_column: key_column.to_string(),
+            policy,
+        }
+    }
+
+    pub fn policy(&self) -> &SoftDeletePolicy {
+        &self.policy
+    }
+
+    /// Index a row tombstoned at `deleted_at` for purging
+    ///
+    /// Idempotent; does nothing when the policy keeps rows forever.
+    pub async fn record(&self, id: Uuid, deleted_at: DateTime<Utc>) -> Result<()> {
+        if self.policy.hard_delete_after.is_none() {
+            return Ok(());
+        }
+        self.session
+            .query_unpaged(
+                "INSERT INTO read_model_tombstones (table_name, day, deleted_at, id) VALUES (?, ?, ?, ?)",
+                (&self.table, deleted_at.date_naive(), deleted_at, id),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Indexed rows past retention, as (id, deleted_at)
+    pub async fn expired(&self, now: DateTime<Utc>) -> Result<Vec<(Uuid, DateTime<Utc>)>> {
+        let Some(cutoff) = self.policy.expiry_cutoff(now) else {
+            return Ok(Vec::new());
+        };
+
+        let mut expired = Vec::new();
+        for day in purge_buckets(cutoff) {
+            let rows = self
+                .session
+                .query_unpaged(
+                    "SELECT id, deleted_at FROM read_model_tombstones
+                     WHERE table_name = ? AND day = ? AND deleted_at <= ?",
+                    (&self.table, day, cutoff),
+                )
+                .await?
+                .into_rows_result()?;
+            for row in rows.rows::<(Uuid, DateTime<Utc>)>()? {
+                expired.push(row?);
+            }
+        }
+        Ok(expired)
+    }
+
+    /// Hard-delete a row and its index entry
+    pub async fn purge(&self, id: Uuid, deleted_at: DateTime<Utc>) -> Result<()> {
+        self.session
+            .query_unpaged(format!("DELETE FROM {} WHERE {} = ?", self.table, self.key_column), (id,))
+            .await?;
+        self.session
+            .query_unpaged(
+                "DELETE FROM read_model_tombstones WHERE table_name = ? AND day = ? AND deleted_at = ? AND id = ?",
+                (&self.table, deleted_at.date_naive(), deleted_at, id),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tombstone_detection() {
+        let policy = SoftDeletePolicy::new(["OrderCancelled"]);
+
+        assert!(policy.is_tombstone("OrderCancelled"));
+        assert!(!policy.is_tombstone("OrderShipped"));
+    }
+
+    #[test]
+    fn test_no_hard_delete_by_default() {
+        let policy = SoftDeletePolicy::new(["CustomerDeactivated"]);
+        let long_ago = Utc::now() - chrono::Duration::days(3650);
+
+        assert!(!policy.is_expired(long_ago, Utc::now()));
+    }
+
+    #[test]
+    fn test_hard_delete_after_retention() {
+        let policy = SoftDeletePolicy::new(["OrderCancelled"])
+            .with_hard_delete_after(Duration::from_secs(30 * 24 * 3600));
+        let now = Utc::now();
+
+        assert!(policy.is_expired(now - chrono::Duration::days(31), now));
+        assert!(!policy.is_expired(now - chrono::Duration::days(29), now));
+    }
+
+    #[test]
+    fn test_deleted_at_is_first_tombstone() {
+        let policy = SoftDeletePolicy::new(["OrderCancelled"]);
+        let now = Utc::now();
+        let history = [
+            ("OrderCreated", now - chrono::Duration::hours(3)),
+            ("OrderCancelled", now - chrono::Duration::hours(2)),
+            ("OrderCancelled", now - chrono::Duration::hours(1)),
+        ];
+
+        assert_eq!(policy.deleted_at(history), Some(now - chrono::Duration::hours(2)));
+        assert_eq!(policy.deleted_at(history[..1].iter().copied()), None);
+    }
+
+    #[test]
+    fn test_purge_buckets_end_at_cutoff_day() {
+        let cutoff = "2026-03-02T10:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let buckets = purge_buckets(cutoff);
+
+        assert_eq!(buckets.len(), PURGE_LOOKBACK_DAYS + 1);
+        assert_eq!(buckets[0], NaiveDate::from_ymd_opt(2026, 3, 2).unwrap());
+        assert_eq!(buckets[2], NaiveDate::from_ymd_opt(2026, 2, 28).unwrap());
+    }
+
+    #[test]
+    fn test_visibility_filter() {
+        assert!(Visibility::ActiveOnly.is_visible(None));
+        assert!(Visibility::ActiveOnly.is_visible(Some(false)));
+        assert!(!Visibility::ActiveOnly.is_visible(Some(true)));
+        assert!(Visibility::IncludeDeleted.is_visible(Some(true)));
+    }
+}
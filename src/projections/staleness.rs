@@ -0,0 +1,238 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::metrics::MetricsHandle;
+
+// ============================================================================
+// Read Model Staleness - Consistency SLA Tracking
+// ============================================================================
+//
+// Read models are eventually consistent. How stale each one is gets measured
+// from two timestamps:
+// - head:         event time of the newest event appended to the store
+//                 (reported by EventStore after every append)
+// - last applied: event time of the newest event a projection has applied
+//                 (reported by the projection)
+//
+// lag       = head - last applied (how much event time is missing)
+// staleness = now - last applied while behind, 0 once caught up (how long
+//             readers may have been seeing outdated data)
+//
+// A projection that never applied anything counts from its registration.
+// Both are exported per projection, plus `projection_sla_breached` when
+// staleness exceeds the projection's SLA, and served as JSON on
+// /status/projections.
+//
+// Positions are kept in memory: each instance reports what its own store
+// and projections did since startup.
+//
+// ============================================================================
+
+/// Staleness of one projection at a point in time
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProjectionStaleness {
+    pub projection: String,
+    pub last_applied_event_at: Option<DateTime<Utc>>,
+    pub newest_event_at: Option<DateTime<Utc>>,
+    pub lag_secs: f64,
+    pub staleness_secs: f64,
+    pub sla_secs: Option<f64>,
+    pub sla_breached: bool,
+}
+
+struct ProjectionState {
+    registered_at: DateTime<Utc>,
+    last_applied_at: Option<DateTime<Utc>>,
+    sla: Option<Duration>,
+}
+
+#[derive(Default)]
+struct TrackerState {
+    head: Option<DateTime<Utc>>,
+    projections: BTreeMap<String, ProjectionState>,
+}
+
+#[derive(Default)]
+pub struct StalenessTracker {
+    state: Mutex<TrackerState>,
+    metrics: MetricsHandle,
+}
+
+impl StalenessTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_metrics(mut self, metrics: MetricsHandle) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Track `projection`, optionally alerting when staleness exceeds `sla`
+    pub fn register(&self, projection: &str, sla: Option<Duration>) {
+        let mut state = self.state.lock().unwrap();
+        let entry = state
+            .projections
+            .entry(projection.to_string())
+            .or_insert_with(|| ProjectionState { registered_at: Utc::now(), last_applied_at: None, sla: None });
+        entry.sla = sla;
+    }
+
+    /// An event with event time `at` was appended to the store
+    pub fn observe_event(&self, at: DateTime<Utc>) {
+        let mut state = self.state.lock().unwrap();
+        if state.head.is_none_or(|head| at > head) {
+            state.head = Some(at);
+        }
+    }
+
+    /// `projection` applied an event with event time `at`
+    pub fn record_applied(&self, projection: &str, at: DateTime<Utc>) {
+        let mut state = self.state.lock().unwrap();
+        let entry = state
+            .projections
+            .entry(projection.to_string())
+            .or_insert_with(|| ProjectionState { registered_at: at, last_applied_at: None, sla: None });
+        if entry.last_applied_at.is_none_or(|applied| at > applied) {
+            entry.last_applied_at = Some(at);
+        }
+    }
+
+    /// Staleness of every tracked projection, by name
+    pub fn report(&self) -> Vec<ProjectionStaleness> {
+        self.report_at(Utc::now())
+    }
+
+    fn report_at(&self, now: DateTime<Utc>) -> Vec<ProjectionStaleness> {
+        let state = self.state.lock().unwrap();
+        state
+            .projections
+            .iter()
+            .map(|(name, projection)| staleness(name, projection, state.head, now))
+            .collect()
+    }
+
+    /// Recompute and export the gauges
+    pub fn refresh(&self) -> Vec<ProjectionStaleness> {
+        let report = self.report();
+
+        for projection in &report {
+            self.metrics.record_projection_staleness(
+                &projection.projection,
+                projection.staleness_secs,
+                projection.lag_secs,
+                projection.sla_breached,
+            );
+            if projection.sla_breached {
+                tracing::warn!(
+                    projection = %projection.projection,
+                    staleness_secs = projection.staleness_secs,
+                    sla_secs = ?projection.sla_secs,
+                    "⏱️ Read model staleness exceeds its SLA"
+                );
+            }
+        }
+
+        report
+    }
+
+    /// Refresh the gauges every `interval` - staleness grows without events
+    pub fn spawn_background(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                self.refresh();
+            }
+        })
+    }
+}
+
+fn staleness(
+    name: &str,
+    projection: &ProjectionState,
+    head: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> ProjectionStaleness {
+    let secs = |d: chrono::Duration| (d.num_milliseconds() as f64 / 1000.0).max(0.0);
+    let applied = projection.last_applied_at.unwrap_or(projection.registered_at);
+
+    let (lag_secs, staleness_secs) = match head {
+        Some(head) if head > applied => (secs(head - applied), secs(now - applied)),
+        _ => (0.0, 0.0),
+    };
+    let sla_secs = projection.sla.map(|sla| sla.as_secs_f64());
+
+    ProjectionStaleness {
+        projection: name.to_string(),
+        last_applied_event_at: projection.last_applied_at,
+        newest_event_at: head,
+        lag_secs,
+        staleness_secs,
+        sla_secs,
+        sla_breached: sla_secs.is_some_and(|sla| staleness_secs > sla),
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::Metrics;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + secs, 0).unwrap()
+    }
+
+    #[test]
+    fn test_caught_up_projection_is_not_stale() {
+        let tracker = StalenessTracker::new();
+        tracker.observe_event(at(10));
+        tracker.record_applied("order_read_model", at(10));
+
+        // No new events for a long time: still fresh
+        let report = tracker.report_at(at(1000));
+        assert_eq!(report[0].staleness_secs, 0.0);
+        assert_eq!(report[0].lag_secs, 0.0);
+    }
+
+    #[test]
+    fn test_behind_projection_staleness_and_sla() {
+        let tracker = StalenessTracker::new();
+        tracker.register("order_read_model", Some(Duration::from_secs(30)));
+        tracker.record_applied("order_read_model", at(10));
+        tracker.observe_event(at(25));
+        // Older events don't move the head back
+        tracker.observe_event(at(20));
+
+        let report = tracker.report_at(at(35));
+        assert_eq!(report[0].newest_event_at, Some(at(25)));
+        assert_eq!(report[0].lag_secs, 15.0);
+        assert_eq!(report[0].staleness_secs, 25.0);
+        assert!(!report[0].sla_breached);
+
+        assert!(tracker.report_at(at(41)).first().unwrap().sla_breached);
+    }
+
+    #[test]
+    fn test_refresh_exports_gauges() {
+        let metrics = Arc::new(Metrics::new().unwrap());
+        let tracker = StalenessTracker::new().with_metrics(MetricsHandle::from(metrics.clone()));
+        tracker.register("orders_by_customer", Some(Duration::from_secs(1)));
+        tracker.observe_event(Utc::now() + chrono::Duration::seconds(5));
+
+        tracker.refresh();
+
+        let gathered = metrics.registry().gather();
+        let breached = gathered.iter().find(|m| m.name() == "projection_sla_breached").unwrap();
+        assert_eq!(breached.metric[0].gauge.value, Some(0.0));
+        let lag = gathered.iter().find(|m| m.name() == "projection_lag_seconds").unwrap();
+        assert!(lag.metric[0].gauge.value.unwrap() > 0.0);
+    }
+}
@@ -0,0 +1,258 @@
+use scylla::client::session::Session;
+use scylla::value::{CqlValue, Row};
+use std::sync::Arc;
+use anyhow::{Result, bail};
+
+// ============================================================================
+// Version-Stamped Read Model Rows
+// ============================================================================
+//
+// A rebuild replaying history and the live CDC tail can write the same read
+// model row concurrently, and either may be behind the other. Plain upserts
+// are last-writer-wins, so an old event landing late overwrites newer state.
+//
+// Every row therefore carries the version (sequence number) of the source
+// aggregate event it reflects, and writes are conditional:
+//
+//   UPDATE ... SET ..., version = ? WHERE <key> IF version < ?
+//
+// A write at or below the stored version is skipped as Stale, which makes
+// projection writes idempotent (re-applying an event is a no-op) and order
+// independent across workers. Rows that do not exist yet are created with
+// INSERT ... IF NOT EXISTS; rows written before stamping (version null) are
+// claimed with IF version = null.
+//
+// All writes are LWTs (Paxos), trading write latency for correctness.
+// Tombstones should go through `upsert` too (setting is_deleted/deleted_at)
+// so a late update cannot resurrect a deleted row.
+//
+// Read model tables opt in by having a `version BIGINT` column.
+//
+// ============================================================================
+
+/// Attempts when racing another writer creating the same row
+const WRITE_ATTEMPTS: u32 = 3;
+
+/// Result of a version-guarded write
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VersionedWrite {
+    Applied,
+    /// The row already reflects `current_version` >= the incoming version
+    Stale { current_version: i64 },
+}
+
+/// Whether an update at `incoming` supersedes a row stamped `current`
+pub fn supersedes(current: Option<i64>, incoming: i64) -> bool {
+    current.is_none_or(|current| incoming > current)
+}
+
+/// Writes rows of one read model table guarded by their version column
+pub struct VersionedTable {
+    session: Arc<Session>,
+    table: String,
+    version_column: String,
+}
+
+impl VersionedTable {
+    pub fn new(session: Arc<Session>, table: &str) -> Self {
+        Self {
+            session,
+            table: table.to_string(),
+            version_column: "version".to_string(),
+        }
+    }
+
+    pub fn with_version_column(mut self, column: &str) -> Self {
+        self.version_column = column.to_string();
+        self
+    }
+
+    /// Set `columns` on the row at `key` unless it is already at `version` or newer
+    pub async fn upsert(
+        &self,
+        key: &[(&str, CqlValue)],
+        version: i64,
+        columns: &[(&str, CqlValue)],
+    ) -> Result<VersionedWrite> {
+        for _ in 0..WRITE_ATTEMPTS {
+            match self.conditional_update(key, version, columns, Condition::Older).await? {
+                LwtOutcome { applied: true, .. } => return Ok(VersionedWrite::Applied),
+                LwtOutcome { version: Some(current_version), .. } => {
+                    tracing::debug!(
+                        table = %self.table,
+                        version = version,
+                        current_version = current_version,
+                        "Skipping stale read model write"
+                    );
+                    return Ok(VersionedWrite::Stale { current_version });
+                }
+                // Missing row or a row written before stamping
+                LwtOutcome { version: None, .. } => {
+                    if self.insert_if_not_exists(key, version, columns).await?
+                        || self.conditional_update(key, version, columns, Condition::Unstamped).await?.applied
+                    {
+                        return Ok(VersionedWrite::Applied);
+                    }
+                }
+            }
+            // Lost a race with another writer - re-evaluate against its row
+        }
+
+        bail!(
+            "Versioned write to {} at version {} kept conflicting after {} attempts",
+            self.table, version, WRITE_ATTEMPTS
+        )
+    }
+
+    async fn conditional_update(
+        &self,
+        key: &[(&str, CqlValue)],
+        version: i64,
+        columns: &[(&str, CqlValue)],
+        condition: Condition,
+    ) -> Result<LwtOutcome> {
+        let cql = update_cql(&self.table, &self.version_column, key, columns, condition);
+
+        let mut values: Vec<CqlValue> = columns.iter().map(|(_, v)| v.clone()).collect();
+        values.push(CqlValue::BigInt(version));
+        values.extend(key.iter().map(|(_, v)| v.clone()));
+        if condition == Condition::Older {
+            values.push(CqlValue::BigInt(version));
+        }
+
+        let result = self.session.query_unpaged(cql, values).await?;
+        lwt_outcome(result, &self.version_column)
+    }
+
+    async fn insert_if_not_exists(
+        &self,
+        key: &[(&str, CqlValue)],
+        version: i64,
+        columns: &[(&str, CqlValue)],
+    ) -> Result<bool> {
+        let cql = insert_cql(&self.table, &self.version_column, key, columns);
+
+        let mut values: Vec<CqlValue> = key.iter().map(|(_, v)| v.clone()).collect();
+        values.extend(columns.iter().map(|(_, v)| v.clone()));
+        values.push(CqlValue::BigInt(version));
+
+        let result = self.session.query_unpaged(cql, values).await?;
+        Ok(lwt_outcome(result, &self.version_column)?.applied)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Condition {
+    /// Stored version is lower than the incoming one
+    Older,
+    /// Row exists without a version stamp
+    Unstamped,
+}
+
+fn update_cql(
+    table: &str,
+    version_column: &str,
+    key: &[(&str, CqlValue)],
+    columns: &[(&str, CqlValue)],
+    condition: Condition,
+) -> String {
+    let assignments: Vec<String> = columns
+        .iter()
+        .map(|(c, _)| format!("{} = ?", c))
+        .chain(std::iter::once(format!("{} = ?", version_column)))
+        .collect();
+    let key: Vec<String> = key.iter().map(|(c, _)| format!("{} = ?", c)).collect();
+    let condition = match condition {
+        Condition::Older => format!("{} < ?", version_column),
+        Condition::Unstamped => format!("{} = null", version_column),
+    };
+
+    format!(
+        "UPDATE {} SET {} WHERE {} IF {}",
+        table,
+        assignments.join(", "),
+        key.join(" AND "),
+        condition
+    )
+}
+
+fn insert_cql(table: &str, version_column: &str, key: &[(&str, CqlValue)], columns: &[(&str, CqlValue)]) -> String {
+    let names: Vec<&str> = key
+        .iter()
+        .chain(columns)
+        .map(|(c, _)| *c)
+        .chain(std::iter::once(version_column))
+        .collect();
+    let markers = vec!["?"; names.len()];
+
+    format!(
+        "INSERT INTO {} ({}) VALUES ({}) IF NOT EXISTS",
+        table,
+        names.join(", "),
+        markers.join(", ")
+    )
+}
+
+struct LwtOutcome {
+    applied: bool,
+    version: Option<i64>,
+}
+
+/// Read `[applied]` and, when not applied, the stored version
+///
+/// Columns of a failed LWT are looked up by name (see fencing.rs). A missing
+/// row and a row without a stamp both yield `version: None`.
+fn lwt_outcome(result: scylla::response::query_result::QueryResult, version_column: &str) -> Result<LwtOutcome> {
+    let rows = result.into_rows_result()?;
+    let version_idx = rows.column_specs().iter().position(|c| c.name() == version_column);
+
+    let Some(row) = rows.maybe_first_row::<Row>()? else {
+        bail!("LWT returned no [applied] row");
+    };
+
+    Ok(LwtOutcome {
+        applied: row.columns.first().cloned().flatten().and_then(|v| v.as_boolean()).unwrap_or(false),
+        version: version_idx.and_then(|i| row.columns.get(i).cloned().flatten()).and_then(|v| v.as_bigint()),
+    })
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_only_newer_versions_supersede() {
+        assert!(supersedes(None, 1));
+        assert!(supersedes(Some(4), 5));
+        // Re-applying the same event is a no-op
+        assert!(!supersedes(Some(5), 5));
+        assert!(!supersedes(Some(7), 5));
+    }
+
+    #[test]
+    fn test_conditional_statements() {
+        let key = [("order_id", CqlValue::Uuid(Uuid::nil()))];
+        let columns = [
+            ("status", CqlValue::Text("SHIPPED".to_string())),
+            ("items", CqlValue::Text("[]".to_string())),
+        ];
+
+        assert_eq!(
+            update_cql("order_read_model", "version", &key, &columns, Condition::Older),
+            "UPDATE order_read_model SET status = ?, items = ?, version = ? WHERE order_id = ? IF version < ?"
+        );
+        assert_eq!(
+            update_cql("order_read_model", "version", &key, &columns, Condition::Unstamped),
+            "UPDATE order_read_model SET status = ?, items = ?, version = ? WHERE order_id = ? IF version = null"
+        );
+        assert_eq!(
+            insert_cql("order_read_model", "version", &key, &columns),
+            "INSERT INTO order_read_model (order_id, status, items, version) VALUES (?, ?, ?, ?) IF NOT EXISTS"
+        );
+    }
+}
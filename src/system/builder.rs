@@ -0,0 +1,387 @@
+use anyhow::Result;
+use scylla::client::session::Session;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::actors::{ApprovalGate, CdcThrottle, CdcThrottleConfig, CoordinatorActor, DlqQuarantinePolicy, HealthHistory, OutboxRetention, PublishPoolConfig};
+use crate::config::{AppConfig, ConfigAuditLog};
+use crate::db::{IntegrityCheckConfig, IntegrityChecker, LatencyMonitor, StatementCache};
+use crate::event_sourcing::{AggregateRoot, DomainEvent, EventCrypto, EventStore, HybridLogicalClock, LifecycleHooks, PersonalDataVault, ShardLayout, SnapshotPolicy, SnapshotPruner, TenantContext, UpcasterRegistry};
+use crate::messaging::{EventPublisher, Partitioner, RedpandaClient, RedpandaConsumer, RoutingRules, StateSnapshotPublisher};
+use crate::metrics::MetricsHandle;
+use crate::domain::customer::CustomerEvent;
+use crate::domain::order::OrderEvent;
+use crate::projections::{CustomerQueryService, CustomerReadModel, OrderQueryService, OrderReadModel, ProjectionManager, SoftDeletePolicy, StalenessTracker};
+
+// ============================================================================
+// System Builder - Dependency Injection for Components
+// ============================================================================
+//
+// Components take their dependencies through `with_*` builders. Instead of
+// every call site remembering to pass the session and metrics (and cloning
+// Arc<Metrics> into each), the SystemBuilder holds the shared ones and
+// constructs components with them already injected:
+//
+//   let system = SystemBuilder::new(session).with_metrics(metrics.into());
+//   let store = system.event_store::<OrderEvent>("Order", "order-events");
+//   let coordinator = system.coordinator(redpanda);
+//
+// Components built elsewhere (tests, CLI diagnostics) keep working with the
+// no-op MetricsHandle they default to. Component-specific configuration is
+// still added on the returned value.
+//
+// With an AppConfig attached, clients and actors get their connection
+// settings and tuning from it (brokers, CDC source, retry, circuit breaker,
+// snapshot frequency, event store shards) instead of the local defaults.
+//
+// With a LatencyMonitor attached, event stores report their query latencies
+// to it and `cdc_throttle` builds a throttle reading from it.
+//
+// Event stores, the DLQ and the CDC checkpoints share one StatementCache,
+// running at the [consistency] levels of the config, so a statement is
+// prepared once per session.
+//
+// With [encryption] keys configured, event stores seal the payloads they
+// write, and the CDC relays, gap backfill and DLQ replay open them.
+//
+// Customer event stores (`customer_event_store`) share one
+// PersonalDataVault: personal fields are sealed with per-customer keys.
+//
+// Event stores and the CDC relays share one UpcasterRegistry (the domain's
+// `upcasters()` unless replaced - a replacement with gaps is refused), so
+// events of older schema versions reach aggregates and Redpanda at the
+// current one.
+//
+// Event stores share one HybridLogicalClock, so events appended through
+// any of them are stamped in one causal order.
+//
+// ============================================================================
+
+#[derive(Clone)]
+pub struct SystemBuilder {
+    session: Arc<Session>,
+    statements: Arc<StatementCache>,
+    config: Arc<AppConfig>,
+    metrics: MetricsHandle,
+    latency: Option<Arc<LatencyMonitor>>,
+    crypto: Option<Arc<dyn EventCrypto>>,
+    personal_data: Arc<PersonalDataVault>,
+    upcasters: Arc<UpcasterRegistry>,
+    clock: Arc<HybridLogicalClock>,
+}
+
+impl SystemBuilder {
+    pub fn new(session: Arc<Session>) -> Self {
+        let statements = Arc::new(StatementCache::new(session.clone()));
+        Self {
+            personal_data: Arc::new(PersonalDataVault::new(statements.clone())),
+            statements,
+            session,
+            config: Arc::new(AppConfig::default()),
+            metrics: MetricsHandle::noop(),
+            latency: None,
+            crypto: None,
+            upcasters: Arc::new(crate::domain::upcasters()),
+            clock: Arc::new(HybridLogicalClock::new()),
+        }
+    }
+
+    pub fn with_config(mut self, config: Arc<AppConfig>) -> Self {
+        self.statements = Arc::new(StatementCache::new(self.session.clone()).with_consistency(config.consistency));
+        self.personal_data = Arc::new(PersonalDataVault::new(self.statements.clone()));
+        // Keys were validated when the config was loaded
+        self.crypto = config
+            .encryption
+            .crypto()
+            .ok()
+            .flatten()
+            .map(|crypto| Arc::new(crypto) as Arc<dyn EventCrypto>);
+        self.config = config;
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: MetricsHandle) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    pub fn with_latency_monitor(mut self, monitor: Arc<LatencyMonitor>) -> Self {
+        self.latency = Some(monitor);
+        self
+    }
+
+    /// Replace the domain's upcasters; a registry with gaps in a chain is
+    /// refused here rather than on the first old event read
+    pub fn with_upcasters(mut self, upcasters: Arc<UpcasterRegistry>) -> Result<Self> {
+        upcasters.validate()?;
+        self.upcasters = upcasters;
+        Ok(self)
+    }
+
+    pub fn session(&self) -> Arc<Session> {
+        self.session.clone()
+    }
+
+    pub fn metrics(&self) -> MetricsHandle {
+        self.metrics.clone()
+    }
+
+    /// Payload encryption of the [encryption] keys; None when it is off
+    pub fn event_crypto(&self) -> Option<Arc<dyn EventCrypto>> {
+        self.crypto.clone()
+    }
+
+    /// Prepared statements at the configured consistency levels
+    pub fn statements(&self) -> Arc<StatementCache> {
+        self.statements.clone()
+    }
+
+    // ------------------------------------------------------------------------
+    // Clients
+    // ------------------------------------------------------------------------
+
+    /// Client for the configured brokers, circuit breaker, batching, rate
+    /// limits, dual-write guard and transactional mode
+    ///
+    /// Only the service's own producer may use the configured
+    /// transactional id; one-off producers take `cli_redpanda_client`.
+    pub fn redpanda_client(&self, partitioner: Partitioner) -> RedpandaClient {
+        self.redpanda_client_with_id(partitioner, self.config.redpanda.transactional_id.clone())
+    }
+
+    /// Client for CLI commands run next to a live service: transactional
+    /// under `<transactional_id>-cli-<uuid>`, so it never fences off the
+    /// service's producer
+    pub fn cli_redpanda_client(&self, partitioner: Partitioner) -> RedpandaClient {
+        let transactional_id = self
+            .config
+            .redpanda
+            .transactional_id
+            .as_ref()
+            .map(|id| format!("{}-cli-{}", id, uuid::Uuid::new_v4()));
+        self.redpanda_client_with_id(partitioner, transactional_id)
+    }
+
+    fn redpanda_client_with_id(&self, partitioner: Partitioner, transactional_id: Option<String>) -> RedpandaClient {
+        let client = RedpandaClient::new_with_partitioner(&self.config.redpanda.brokers, partitioner)
+            .with_circuit_breaker(self.config.circuit_breaker.circuit_breaker_config())
+            .with_batching(self.config.redpanda.batch_config())
+            .with_rate_limit(self.config.redpanda.rate_limit())
+            .with_dual_write_guard(self.config.redpanda.dual_write_guard())
+            .with_metrics(self.metrics());
+        match transactional_id {
+            Some(ref transactional_id) => client.with_transactions(transactional_id),
+            None => client,
+        }
+    }
+
+    /// Consumer of our own topics in consumer group `group_id`
+    pub fn redpanda_consumer(&self, group_id: &str) -> RedpandaConsumer {
+        RedpandaConsumer::new(&self.config.redpanda.brokers, group_id)
+    }
+
+    // ------------------------------------------------------------------------
+    // Actors
+    // ------------------------------------------------------------------------
+
+    /// Coordinator whose DLQ, CDC processors and health mailbox record
+    /// metrics; one CDC processor per configured table
+    pub fn coordinator(&self, redpanda: Arc<RedpandaClient>) -> CoordinatorActor {
+        let coordinator = CoordinatorActor::new(self.session(), redpanda)
+            .with_cdc_tables(self.config.cdc_tables())
+            .with_cdc_mode(self.config.cdc.mode, self.config.cdc.poll_interval())
+            .with_cdc_retry(self.config.retry.retry_config())
+            .with_cdc_dedup_ttl(self.config.cdc.dedup_ttl())
+            .with_cdc_payload_format(self.config.cdc.payload_format)
+            .with_cdc_key_strategy(self.config.cdc.key_strategy)
+            .with_cdc_publish_pool(PublishPoolConfig {
+                workers: self.config.cdc.publish_workers,
+                queue_depth: self.config.cdc.publish_queue_depth,
+            })
+            .with_stream_audit(self.config.cdc.stream_audit)
+            .with_cdc_max_lag(self.config.cdc.max_lag())
+            .with_outbox_retention(self.outbox_retention())
+            .with_outbox_schedule_interval(self.config.outbox.schedule_interval())
+            .with_dlq_quarantine_policy(self.dlq_quarantine_policy())
+            .with_restart_policy(self.config.supervision.restart_policy())
+            .with_event_shards(self.shard_layout())
+            .with_statement_cache(self.statements())
+            .with_upcasters(self.upcasters.clone())
+            .with_metrics(self.metrics());
+        let coordinator = match self.crypto {
+            Some(ref crypto) => coordinator.with_event_crypto(crypto.clone()),
+            None => coordinator,
+        };
+        match self.config.retry.retry_budget() {
+            Some(budget) => coordinator.with_retry_budget(Arc::new(budget)),
+            None => coordinator,
+        }
+    }
+
+    /// Health transitions shared by the health monitor and /health/history
+    pub fn health_history(&self) -> HealthHistory {
+        HealthHistory::new(self.session())
+    }
+
+    /// Gate for the configured approval-required event types, shared by the
+    /// CDC relay and the admin API
+    pub fn approval_gate(&self) -> ApprovalGate {
+        ApprovalGate::new(self.session(), self.config.cdc.approval_required.clone())
+    }
+
+    /// CDC throttle driven by the latency monitor (None without one)
+    pub fn cdc_throttle(&self, config: CdcThrottleConfig) -> Option<CdcThrottle> {
+        let monitor = self.latency.clone()?;
+        Some(CdcThrottle::new(config, monitor).with_metrics(self.metrics()))
+    }
+
+    // ------------------------------------------------------------------------
+    // Stores and background jobs
+    // ------------------------------------------------------------------------
+
+    pub fn event_store<E: DomainEvent>(&self, aggregate_type: &str, topic: &str) -> EventStore<E> {
+        self.tenant_event_store(&TenantContext::default(), aggregate_type, topic)
+    }
+
+    /// Customer event store, personal data sealed with per-customer keys
+    pub fn customer_event_store(&self) -> EventStore<CustomerEvent> {
+        self.event_store::<CustomerEvent>("Customer", "customer-events")
+            .with_personal_data(self.personal_data.clone())
+    }
+
+    /// Event store on the tables of `tenant` (see [[tenants]])
+    pub fn tenant_event_store<E: DomainEvent>(&self, tenant: &TenantContext, aggregate_type: &str, topic: &str) -> EventStore<E> {
+        let mut store = EventStore::new(self.session(), tenant.clone(), aggregate_type, topic)
+            .with_shards(self.shard_layout())
+            .with_load_page_size(self.config.event_store.load_page_size)
+            .with_statement_cache(self.statements())
+            .with_upcasters(self.upcasters.clone())
+            .with_clock(self.clock.clone())
+            .with_metrics(self.metrics());
+        if self.config.event_store.snapshot_every > 0 {
+            store = store.with_snapshot_policy(SnapshotPolicy::every(self.config.event_store.snapshot_every));
+        }
+        if let Some(ref crypto) = self.crypto {
+            store = store.with_crypto(crypto.clone());
+        }
+        match self.latency {
+            Some(ref monitor) => store.with_latency_monitor(monitor.clone()),
+            None => store,
+        }
+    }
+
+    /// Full-state publisher for one aggregate type, interval from [state_snapshots]
+    pub fn state_snapshot_publisher<A>(
+        &self,
+        store: Arc<EventStore<A::Event>>,
+        publisher: Arc<dyn EventPublisher>,
+        aggregate_type: &str,
+        topic: &str,
+    ) -> StateSnapshotPublisher<A>
+    where
+        A: AggregateRoot + serde::Serialize + 'static,
+        A::Event: DomainEvent,
+        <A as AggregateRoot>::Error: std::fmt::Display,
+    {
+        StateSnapshotPublisher::new(store, publisher, aggregate_type, topic).with_every(self.config.state_snapshots.every)
+    }
+
+    /// Configuration history on its own event store stream
+    pub fn config_audit_log(&self) -> ConfigAuditLog {
+        ConfigAuditLog::new(self.event_store("Config", "config-events"))
+    }
+
+    pub fn lifecycle_hooks<I, S>(&self, closing_event_types: I) -> LifecycleHooks
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        LifecycleHooks::new(closing_event_types).with_metrics(self.metrics())
+    }
+
+    /// Snapshot pruner of `tenant`'s tables; None when SNAPSHOT_PRUNE_INTERVAL_SECS is 0
+    pub fn snapshot_pruner(&self, tenant: &TenantContext) -> Option<SnapshotPruner> {
+        let policy = self.config.event_store.snapshot_retention()?;
+        Some(
+            SnapshotPruner::new(self.statements.clone(), policy)
+                .with_tenant(tenant.clone())
+                .with_metrics(self.metrics()),
+        )
+    }
+
+    pub fn projection_manager(&self) -> ProjectionManager {
+        ProjectionManager::new(self.session())
+            .with_cdc_source(self.config.cdc_source())
+            .with_purge_interval(self.config.read_models.purge_interval())
+    }
+
+    /// Order read model projection, hydrating orders from `store`; cancelled
+    /// orders are soft-deleted
+    pub fn order_read_model(&self, store: Arc<EventStore<OrderEvent>>) -> OrderReadModel {
+        let mut policy = SoftDeletePolicy::new(["OrderCancelled"]);
+        if let Some(retention) = self.config.read_models.tombstone_retention() {
+            policy = policy.with_hard_delete_after(retention);
+        }
+        OrderReadModel::new(self.session(), store).with_soft_delete(policy)
+    }
+
+    pub fn order_query_service(&self) -> OrderQueryService {
+        OrderQueryService::new(self.session())
+    }
+
+    /// Customer read model projection, hydrating customers from `store`
+    pub fn customer_read_model(&self, store: Arc<EventStore<CustomerEvent>>) -> CustomerReadModel {
+        CustomerReadModel::new(self.session(), store)
+    }
+
+    pub fn customer_query_service(&self) -> CustomerQueryService {
+        CustomerQueryService::new(self.session())
+    }
+
+    pub fn staleness_tracker(&self) -> StalenessTracker {
+        StalenessTracker::new().with_metrics(self.metrics())
+    }
+
+    pub fn integrity_checker(&self, config: IntegrityCheckConfig) -> IntegrityChecker {
+        let checker = IntegrityChecker::new(self.session(), config)
+            .with_shards(self.shard_layout())
+            .with_metrics(self.metrics());
+        match self.config.outbox.retention() {
+            Some(retention) => checker.with_outbox_retention(retention),
+            None => checker,
+        }
+    }
+
+    /// Outbox janitor policy; None when OUTBOX_RETENTION_SECS is 0
+    pub fn outbox_retention(&self) -> Option<OutboxRetention> {
+        let outbox = &self.config.outbox;
+        outbox.retention().map(|retention| OutboxRetention {
+            retention,
+            interval: outbox.cleanup_interval(),
+            max_rows_per_pass: outbox.cleanup_batch,
+        })
+    }
+
+    /// DLQ quarantine and aging; relayed entries start at the relay's attempts
+    pub fn dlq_quarantine_policy(&self) -> DlqQuarantinePolicy {
+        let dlq = &self.config.dlq;
+        DlqQuarantinePolicy {
+            max_failures: self.config.retry.max_attempts as i32 + dlq.max_replays,
+            max_age: Duration::from_secs(dlq.max_age_secs),
+            age_check_interval: Duration::from_secs(dlq.age_check_interval_secs),
+        }
+    }
+
+    /// Event store shard layout (validated when the config was loaded)
+    pub fn shard_layout(&self) -> ShardLayout {
+        self.config.event_store.shard_layout().unwrap_or_default()
+    }
+
+    // ------------------------------------------------------------------------
+    // Messaging
+    // ------------------------------------------------------------------------
+
+    pub fn routing_rules_from_file(&self, path: &str) -> Result<RoutingRules> {
+        Ok(RoutingRules::from_file(path)?.with_metrics(self.metrics()))
+    }
+}
@@ -0,0 +1,16 @@
+// ============================================================================
+// System Module - Wiring of Shared Dependencies
+// ============================================================================
+//
+// - builder - SystemBuilder: constructs stores, actors and clients with the
+//             shared session and MetricsHandle injected
+// - shutdown - ShutdownController: SIGINT/SIGTERM handling and bounded,
+//              draining shutdown of the coordinator and projections
+//
+// ============================================================================
+
+mod builder;
+mod shutdown;
+
+pub use builder::SystemBuilder;
+pub use shutdown::ShutdownController;
@@ -0,0 +1,96 @@
+use anyhow::Result;
+use kameo::actor::ActorRef;
+use std::time::Duration;
+use tokio::signal::unix::{signal, Signal, SignalKind};
+
+use crate::actors::{CoordinatorActor, Shutdown};
+use crate::projections::ProjectionManager;
+
+// ============================================================================
+// Shutdown Controller - Signal Handling and Bounded Draining
+// ============================================================================
+//
+// Installed once the coordinator runs; from then on SIGINT/SIGTERM no longer
+// kill the process. `wait_for_signal` resolves on the first one and
+// `shutdown` then:
+//
+//   1. asks the CoordinatorActor to shut down - it stops the CDC reader,
+//      waits for in-flight publishes to complete or be dead-lettered,
+//      flushes the relay checkpoint and stops the DLQ gracefully
+//   2. stops the projection manager, which flushes projection_offsets
+//
+// The whole sequence is bounded by `timeout` (SHUTDOWN_TIMEOUT_SECS); a
+// second signal cuts it short. Whatever was not drained is relayed again
+// after the restart (at-least-once from the checkpoint).
+//
+// ============================================================================
+
+pub struct ShutdownController {
+    coordinator: ActorRef<CoordinatorActor>,
+    projection_manager: Option<ActorRef<ProjectionManager>>,
+    timeout: Duration,
+    interrupt: Signal,
+    terminate: Signal,
+}
+
+impl ShutdownController {
+    /// Trap SIGINT and SIGTERM from now on
+    pub fn install(coordinator: ActorRef<CoordinatorActor>, timeout: Duration) -> Result<Self> {
+        Ok(Self {
+            coordinator,
+            projection_manager: None,
+            timeout,
+            interrupt: signal(SignalKind::interrupt())?,
+            terminate: signal(SignalKind::terminate())?,
+        })
+    }
+
+    /// Stop the projection manager too (flushes projection checkpoints)
+    pub fn with_projection_manager(mut self, projection_manager: ActorRef<ProjectionManager>) -> Self {
+        self.projection_manager = Some(projection_manager);
+        self
+    }
+
+    /// Resolve on the next SIGINT or SIGTERM; returns its name
+    pub async fn wait_for_signal(&mut self) -> &'static str {
+        next_signal(&mut self.interrupt, &mut self.terminate).await
+    }
+
+    /// Drain and stop everything, within the timeout
+    pub async fn shutdown(self, signal: &str) -> Result<()> {
+        let Self { coordinator, projection_manager, timeout, mut interrupt, mut terminate } = self;
+        tracing::info!(signal = signal, timeout_secs = timeout.as_secs(), "🛑 Shutting down gracefully...");
+
+        let drain = async {
+            if let Err(e) = coordinator.ask(Shutdown).await {
+                tracing::error!(error = %e, "Coordinator shutdown failed");
+            }
+            if let Some(ref projection_manager) = projection_manager {
+                if projection_manager.stop_gracefully().await.is_ok() {
+                    projection_manager.wait_for_shutdown().await;
+                }
+            }
+        };
+
+        tokio::select! {
+            result = tokio::time::timeout(timeout, drain) => {
+                if result.is_err() {
+                    anyhow::bail!("Shutdown did not complete within {}s", timeout.as_secs());
+                }
+            }
+            second = next_signal(&mut interrupt, &mut terminate) => {
+                anyhow::bail!("Shutdown interrupted by a second {}", second);
+            }
+        }
+
+        tracing::info!("👋 Shutdown complete");
+        Ok(())
+    }
+}
+
+async fn next_signal(interrupt: &mut Signal, terminate: &mut Signal) -> &'static str {
+    tokio::select! {
+        _ = interrupt.recv() => "SIGINT",
+        _ = terminate.recv() => "SIGTERM",
+    }
+}
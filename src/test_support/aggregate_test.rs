@@ -0,0 +1,196 @@
+use serde_json::Value;
+use std::fmt::Display;
+use uuid::Uuid;
+
+use crate::domain::CommandAggregate;
+use crate::event_sourcing::{DomainEvent, EventEnvelope};
+
+// ============================================================================
+// Aggregate Test - Given Events, When Command, Then Events
+// ============================================================================
+//
+// Business rules tested without any store:
+//
+//   AggregateTest::<OrderAggregate>::new()
+//       .given_events([OrderEvent::Created(created)])
+//       .when_command(OrderCommand::ConfirmOrder)
+//       .then_expect_events([OrderEvent::Confirmed(confirmed)]);
+//
+// The given events are replayed (`load_from_events`) and the command is
+// decided on the result - with no given events, on the aggregate's
+// `CommandAggregate::initial_state`, as the CommandHandler does. Events
+// compare by their JSON, so event types need no PartialEq; timestamps set
+// by the aggregate itself are checked with `then_expect_events_matching`.
+//
+// The `then_*` methods panic with both sides printed on a mismatch and
+// return the state after the new events (or the error) for further asserts.
+//
+// ============================================================================
+
+/// Given/when/then test of one aggregate's command handling
+pub struct AggregateTest<A: CommandAggregate>
+where
+    A::Event: DomainEvent,
+{
+    aggregate_id: Uuid,
+    given: Vec<A::Event>,
+}
+
+/// Outcome of `when_command`, checked by the `then_*` methods
+pub struct AggregateTestOutcome<A: CommandAggregate>
+where
+    A::Event: DomainEvent,
+{
+    aggregate_id: Uuid,
+    given: Vec<A::Event>,
+    result: Result<Vec<A::Event>, A::Error>,
+}
+
+impl<A> Default for AggregateTest<A>
+where
+    A: CommandAggregate,
+    A::Event: DomainEvent,
+    A::Error: Display,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A> AggregateTest<A>
+where
+    A: CommandAggregate,
+    A::Event: DomainEvent,
+    A::Error: Display,
+{
+    pub fn new() -> Self {
+        Self::for_aggregate(Uuid::new_v4())
+    }
+
+    /// Test of the aggregate with `aggregate_id` (events carrying their id)
+    pub fn for_aggregate(aggregate_id: Uuid) -> Self {
+        Self { aggregate_id, given: Vec::new() }
+    }
+
+    /// History the command is decided on
+    pub fn given_events(mut self, events: impl IntoIterator<Item = A::Event>) -> Self {
+        self.given.extend(events);
+        self
+    }
+
+    /// Decide `command` on the given history
+    ///
+    /// Panics when the history does not replay, or when there is none and
+    /// the command cannot create the aggregate - the test's setup is wrong.
+    pub fn when_command(self, command: A::Command) -> AggregateTestOutcome<A> {
+        let state = if self.given.is_empty() {
+            match A::initial_state(&command) {
+                Ok(Some(state)) => state,
+                Ok(None) => panic!("No given events, and the command does not create the aggregate"),
+                Err(e) => return AggregateTestOutcome { aggregate_id: self.aggregate_id, given: self.given, result: Err(e) },
+            }
+        } else {
+            replay::<A>(self.aggregate_id, &self.given)
+        };
+        let result = state.handle_command(&command);
+        AggregateTestOutcome { aggregate_id: self.aggregate_id, given: self.given, result }
+    }
+}
+
+impl<A> AggregateTestOutcome<A>
+where
+    A: CommandAggregate,
+    A::Event: DomainEvent,
+    A::Error: Display,
+{
+    /// The command emitted exactly `expected`; returns the state after them
+    pub fn then_expect_events(self, expected: impl IntoIterator<Item = A::Event>) -> A {
+        let expected: Vec<Value> = expected.into_iter().map(|event| to_json(&event)).collect();
+        self.then_expect_events_matching(|events| {
+            let actual: Vec<Value> = events.iter().map(to_json).collect();
+            assert_eq!(actual, expected, "Emitted events differ from the expected ones");
+        })
+    }
+
+    /// The command succeeded and its events pass `check`; returns the state after them
+    pub fn then_expect_events_matching(self, check: impl FnOnce(&[A::Event])) -> A {
+        let events = match self.result {
+            Ok(events) => events,
+            Err(e) => panic!("Expected events, the command failed: {}", e),
+        };
+        check(&events);
+        let history: Vec<A::Event> = self.given.into_iter().chain(events).collect();
+        replay::<A>(self.aggregate_id, &history)
+    }
+
+    /// The command was rejected; returns its error
+    pub fn then_expect_error(self) -> A::Error {
+        match self.result {
+            Ok(events) => panic!(
+                "Expected an error, the command emitted {:?}",
+                events.iter().map(to_json).collect::<Vec<_>>()
+            ),
+            Err(e) => e,
+        }
+    }
+}
+
+/// State after `events`, replayed as the event store would load them
+fn replay<A>(aggregate_id: Uuid, events: &[A::Event]) -> A
+where
+    A: CommandAggregate,
+    A::Event: DomainEvent,
+    A::Error: Display,
+{
+    let envelopes = events
+        .iter()
+        .enumerate()
+        .map(|(i, event)| EventEnvelope::for_event(aggregate_id, i as i64 + 1, event.clone(), Uuid::new_v4()))
+        .collect();
+    A::load_from_events(envelopes).unwrap_or_else(|e| panic!("Given events do not replay: {}", e))
+}
+
+fn to_json<E: DomainEvent>(event: &E) -> Value {
+    serde_json::to_value(event).expect("events serialize")
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_sourcing::AggregateRoot;
+    use crate::domain::order::{OrderAggregate, OrderCancelled, OrderCommand, OrderCreated, OrderError, OrderEvent, OrderItem, OrderStatus};
+
+    fn created() -> OrderEvent {
+        OrderEvent::Created(OrderCreated {
+            customer_id: Uuid::new_v4(),
+            items: vec![OrderItem { product_id: Uuid::new_v4(), quantity: 1 }],
+        })
+    }
+
+    #[test]
+    fn test_given_when_then() {
+        let cancel = OrderCommand::CancelOrder { reason: Some("changed mind".to_string()), cancelled_by: None };
+        let order = AggregateTest::<OrderAggregate>::new()
+            .given_events([created()])
+            .when_command(cancel)
+            .then_expect_events([OrderEvent::Cancelled(OrderCancelled { reason: Some("changed mind".to_string()), cancelled_by: None })]);
+        assert_eq!(order.status, OrderStatus::Cancelled);
+        assert_eq!(order.version(), 2);
+
+        // Confirmation stamps its own time
+        AggregateTest::<OrderAggregate>::new()
+            .given_events([created()])
+            .when_command(OrderCommand::ConfirmOrder)
+            .then_expect_events_matching(|events| assert!(matches!(events, [OrderEvent::Confirmed(_)])));
+
+        let error = AggregateTest::<OrderAggregate>::new()
+            .given_events([created(), OrderEvent::Cancelled(OrderCancelled { reason: None, cancelled_by: None })])
+            .when_command(OrderCommand::ConfirmOrder)
+            .then_expect_error();
+        assert!(matches!(error, OrderError::InvalidStatusTransition(OrderStatus::Cancelled)));
+    }
+}
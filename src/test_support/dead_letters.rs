@@ -0,0 +1,32 @@
+use async_trait::async_trait;
+use std::sync::Mutex;
+
+use crate::actors::{AddToDlq, DeadLetterSink};
+
+/// DLQ that remembers what was dead-lettered instead of storing it
+#[derive(Default)]
+pub struct RecordingDlq {
+    dead_letters: Mutex<Vec<AddToDlq>>,
+}
+
+impl RecordingDlq {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn dead_letters(&self) -> Vec<AddToDlq> {
+        self.dead_letters.lock().unwrap().clone()
+    }
+
+    pub fn count(&self) -> usize {
+        self.dead_letters.lock().unwrap().len()
+    }
+}
+
+#[async_trait]
+impl DeadLetterSink for RecordingDlq {
+    async fn dead_letter(&self, message: AddToDlq) -> anyhow::Result<()> {
+        self.dead_letters.lock().unwrap().push(message);
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+// ============================================================================
+// Test Support - In-Memory Doubles and an Aggregate Test DSL
+// ============================================================================
+//
+// For unit tests of domain code and of components that publish or
+// dead-letter, with no ScyllaDB, Redpanda or actors running:
+//
+// - AggregateTest          - given events, when command, then events/error
+// - InMemoryEventStorage   - event store backend for command handlers
+//                            (`OrderCommandHandler::from_storage`)
+// - RecordingPublisher /
+//   FailingPublisher       - EventPublisher doubles
+// - RecordingDlq           - DeadLetterSink double for the CDC relay
+// - RecordingSchedule      - ScheduleSink double for the CDC relay
+//
+// Compiled for the crate's own tests; other crates enable the
+// "test-support" feature (as a dev-dependency). The containers suite in
+// tests/integration covers the real backends.
+//
+// ============================================================================
+
+mod aggregate_test;
+mod dead_letters;
+mod schedule;
+
+pub use aggregate_test::{AggregateTest, AggregateTestOutcome};
+pub use dead_letters::RecordingDlq;
+pub use schedule::RecordingSchedule;
+pub use crate::event_sourcing::InMemoryEventStorage;
+pub use crate::messaging::test_support::{FailingPublisher, PublishedMessage, RecordingPublisher};
@@ -0,0 +1,28 @@
+use async_trait::async_trait;
+use std::sync::Mutex;
+
+use crate::actors::{ScheduleSink, ScheduledPublication};
+
+/// Scheduler that remembers what was scheduled instead of storing it
+#[derive(Default)]
+pub struct RecordingSchedule {
+    scheduled: Mutex<Vec<ScheduledPublication>>,
+}
+
+impl RecordingSchedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn scheduled(&self) -> Vec<ScheduledPublication> {
+        self.scheduled.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl ScheduleSink for RecordingSchedule {
+    async fn schedule(&self, publication: &ScheduledPublication) -> anyhow::Result<()> {
+        self.scheduled.lock().unwrap().push(publication.clone());
+        Ok(())
+    }
+}
@@ -1,7 +1,11 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use std::time::{Duration, Instant};
 
+use crate::metrics::MetricsHandle;
+
 // ============================================================================
 // Circuit Breaker Pattern Implementation
 // ============================================================================
@@ -14,6 +18,20 @@ use std::time::{Duration, Instant};
 // - Open: Too many failures, requests blocked immediately
 // - HalfOpen: Testing if service recovered, limited requests allowed
 //
+// The circuit opens after `failure_threshold` consecutive failures or, with
+// a `failure_rate_threshold`, once that share of the last `window_size`
+// calls failed (judged from `minimum_calls` calls on). Intermittent
+// failures never add up to a streak but still show in the rate.
+//
+// HalfOpen lets at most `half_open_max_calls` trial calls run at once;
+// further calls are rejected as if the circuit were open, so a recovering
+// service is not flooded by everything that queued up meanwhile.
+//
+// Every state change is recorded (circuit_breaker_state gauge and
+// circuit_breaker_transitions_total{from_state,to_state}), as are the
+// window's failure rate and the trial calls in flight - all labelled with
+// the breaker's name (the topic, for the Redpanda producer's breakers).
+//
 // ============================================================================
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -23,10 +41,34 @@ pub enum CircuitState {
     HalfOpen,   // Testing recovery
 }
 
+impl CircuitState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CircuitState::Closed => "Closed",
+            CircuitState::Open => "Open",
+            CircuitState::HalfOpen => "HalfOpen",
+        }
+    }
+
+    /// Value of the circuit_breaker_state gauge
+    fn gauge_value(self) -> u8 {
+        match self {
+            CircuitState::Closed => 0,
+            CircuitState::Open => 1,
+            CircuitState::HalfOpen => 2,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct CircuitBreaker {
+    /// `breaker` label of its metrics
+    name: Arc<str>,
     state: Arc<Mutex<CircuitBreakerState>>,
+    /// Trial calls running while HalfOpen
+    half_open_trials: Arc<AtomicU32>,
     config: CircuitBreakerConfig,
+    metrics: MetricsHandle,
 }
 
 #[derive(Clone)]
@@ -37,6 +79,15 @@ pub struct CircuitBreakerConfig {
     pub timeout: Duration,
     /// Number of successes needed to close circuit from half-open
     pub success_threshold: u32,
+    /// Share of failed calls in the window that opens the circuit (0.5 =
+    /// half of them); None opens on consecutive failures only
+    pub failure_rate_threshold: Option<f64>,
+    /// Most recent calls the failure rate is computed over
+    pub window_size: usize,
+    /// Calls the window needs before its failure rate counts
+    pub minimum_calls: usize,
+    /// Trial calls allowed at once while half-open
+    pub half_open_max_calls: u32,
 }
 
 impl Default for CircuitBreakerConfig {
@@ -45,57 +96,166 @@ impl Default for CircuitBreakerConfig {
             failure_threshold: 5,
             timeout: Duration::from_secs(60),
             success_threshold: 2,
+            failure_rate_threshold: None,
+            window_size: 100,
+            minimum_calls: 20,
+            half_open_max_calls: 2,
         }
     }
 }
 
+/// Current state of a circuit breaker, for metrics and diagnostics
+#[derive(Debug, Clone, PartialEq)]
+pub struct CircuitBreakerSnapshot {
+    pub state: CircuitState,
+    pub consecutive_failures: u32,
+    /// Failed share of the calls in the window; None while it is empty
+    pub failure_rate: Option<f64>,
+    pub window_calls: usize,
+    pub half_open_trials: u32,
+}
+
 struct CircuitBreakerState {
     state: CircuitState,
     failure_count: u32,
     success_count: u32,
     last_failure_time: Option<Instant>,
+    /// Outcomes of the most recent calls while closed, true = failed
+    window: VecDeque<bool>,
+    window_failures: usize,
+}
+
+impl CircuitBreakerState {
+    fn failure_rate(&self) -> Option<f64> {
+        (!self.window.is_empty()).then(|| self.window_failures as f64 / self.window.len() as f64)
+    }
+}
+
+/// A trial call admitted while HalfOpen, released when it completes or is dropped
+struct TrialPermit {
+    name: Arc<str>,
+    trials: Arc<AtomicU32>,
+    metrics: MetricsHandle,
+}
+
+impl Drop for TrialPermit {
+    fn drop(&mut self) {
+        let running = self.trials.fetch_sub(1, Ordering::SeqCst) - 1;
+        self.metrics.set_circuit_breaker_half_open_trials(&self.name, running as i64);
+    }
 }
 
 impl CircuitBreaker {
     pub fn new(config: CircuitBreakerConfig) -> Self {
         Self {
+            name: Arc::from("default"),
             state: Arc::new(Mutex::new(CircuitBreakerState {
                 state: CircuitState::Closed,
                 failure_count: 0,
                 success_count: 0,
                 last_failure_time: None,
+                window: VecDeque::new(),
+                window_failures: 0,
             })),
+            half_open_trials: Arc::new(AtomicU32::new(0)),
             config,
+            metrics: MetricsHandle::noop(),
+        }
+    }
+
+    /// Name the breaker in its metrics (set before `with_metrics`)
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Arc::from(name);
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: MetricsHandle) -> Self {
+        metrics.update_circuit_breaker_state(&self.name, CircuitState::Closed.gauge_value());
+        self.metrics = metrics;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Move to `to` and record the transition
+    fn transition(&self, state: &mut CircuitBreakerState, to: CircuitState) {
+        let from = state.state;
+        state.state = to;
+        if from != to {
+            // A new state judges only its own calls
+            state.window.clear();
+            state.window_failures = 0;
+            self.metrics.set_circuit_breaker_failure_rate(&self.name, 0.0);
+            self.metrics.record_circuit_breaker_transition(&self.name, from.as_str(), to.as_str());
+            self.metrics.update_circuit_breaker_state(&self.name, to.gauge_value());
         }
     }
 
+    /// Add a call's outcome to the window (closed circuit only)
+    fn record_outcome(&self, state: &mut CircuitBreakerState, failed: bool) {
+        state.window.push_back(failed);
+        state.window_failures += usize::from(failed);
+        if state.window.len() > self.config.window_size.max(1) && state.window.pop_front() == Some(true) {
+            state.window_failures -= 1;
+        }
+        self.metrics.set_circuit_breaker_failure_rate(&self.name, state.failure_rate().unwrap_or(0.0));
+    }
+
+    /// Whether the window's failure rate calls for opening the circuit
+    fn failure_rate_exceeded(&self, state: &CircuitBreakerState) -> bool {
+        let (Some(threshold), Some(rate)) = (self.config.failure_rate_threshold, state.failure_rate()) else {
+            return false;
+        };
+        state.window.len() >= self.config.minimum_calls.max(1) && rate >= threshold
+    }
+
+    /// Admit a trial call while HalfOpen; None when enough are running
+    fn try_trial(&self) -> Option<TrialPermit> {
+        let running = self.half_open_trials.load(Ordering::SeqCst);
+        if running >= self.config.half_open_max_calls.max(1) {
+            return None;
+        }
+        self.half_open_trials.fetch_add(1, Ordering::SeqCst);
+        self.metrics.set_circuit_breaker_half_open_trials(&self.name, running as i64 + 1);
+        Some(TrialPermit {
+            name: self.name.clone(),
+            trials: self.half_open_trials.clone(),
+            metrics: self.metrics.clone(),
+        })
+    }
+
     /// Execute an operation with circuit breaker protection
     pub async fn call<F, T, E>(&self, operation: F) -> Result<T, CircuitBreakerError<E>>
     where
         F: std::future::Future<Output = Result<T, E>>,
     {
-        // Check if circuit allows the call
-        {
+        // Check if circuit allows the call; trial calls are admitted (and
+        // counted) under the state lock
+        let _trial = {
             let mut state = self.state.lock().await;
 
-            match state.state {
-                CircuitState::Open => {
-                    // Check if timeout has elapsed
-                    if let Some(last_failure) = state.last_failure_time {
-                        if last_failure.elapsed() >= self.config.timeout {
-                            tracing::info!("Circuit breaker transitioning to HalfOpen");
-                            state.state = CircuitState::HalfOpen;
-                            state.success_count = 0;
-                        } else {
-                            return Err(CircuitBreakerError::CircuitOpen);
-                        }
+            if state.state == CircuitState::Open {
+                // Check if timeout has elapsed
+                if let Some(last_failure) = state.last_failure_time {
+                    if last_failure.elapsed() >= self.config.timeout {
+                        tracing::info!("Circuit breaker transitioning to HalfOpen");
+                        self.transition(&mut state, CircuitState::HalfOpen);
+                        state.success_count = 0;
+                    } else {
+                        return Err(CircuitBreakerError::CircuitOpen);
                     }
                 }
-                CircuitState::HalfOpen | CircuitState::Closed => {
-                    // Allow the call
-                }
             }
-        }
+            match state.state {
+                CircuitState::HalfOpen => match self.try_trial() {
+                    Some(trial) => Some(trial),
+                    None => return Err(CircuitBreakerError::CircuitOpen),
+                },
+                CircuitState::Open | CircuitState::Closed => None,
+            }
+        };
 
         // Execute the operation
         match operation.await {
@@ -118,7 +278,7 @@ impl CircuitBreaker {
                 state.success_count += 1;
                 if state.success_count >= self.config.success_threshold {
                     tracing::info!("Circuit breaker closing after {} successes", state.success_count);
-                    state.state = CircuitState::Closed;
+                    self.transition(&mut state, CircuitState::Closed);
                     state.failure_count = 0;
                     state.success_count = 0;
                     state.last_failure_time = None;
@@ -127,6 +287,7 @@ impl CircuitBreaker {
             CircuitState::Closed => {
                 // Reset failure count on success
                 state.failure_count = 0;
+                self.record_outcome(&mut state, false);
             }
             CircuitState::Open => {
                 // Should not happen, but reset if it does
@@ -143,17 +304,25 @@ impl CircuitBreaker {
 
         match state.state {
             CircuitState::Closed => {
+                self.record_outcome(&mut state, true);
                 if state.failure_count >= self.config.failure_threshold {
                     tracing::warn!(
                         "Circuit breaker opening after {} failures",
                         state.failure_count
                     );
-                    state.state = CircuitState::Open;
+                    self.transition(&mut state, CircuitState::Open);
+                } else if self.failure_rate_exceeded(&state) {
+                    tracing::warn!(
+                        failure_rate = state.failure_rate().unwrap_or_default(),
+                        calls = state.window.len(),
+                        "Circuit breaker opening on failure rate"
+                    );
+                    self.transition(&mut state, CircuitState::Open);
                 }
             }
             CircuitState::HalfOpen => {
                 tracing::warn!("Failure during half-open, reopening circuit");
-                state.state = CircuitState::Open;
+                self.transition(&mut state, CircuitState::Open);
                 state.success_count = 0;
             }
             CircuitState::Open => {
@@ -172,14 +341,28 @@ impl CircuitBreaker {
         state.failure_count
     }
 
+    pub async fn snapshot(&self) -> CircuitBreakerSnapshot {
+        let state = self.state.lock().await;
+        CircuitBreakerSnapshot {
+            state: state.state,
+            consecutive_failures: state.failure_count,
+            failure_rate: state.failure_rate(),
+            window_calls: state.window.len(),
+            half_open_trials: self.half_open_trials.load(Ordering::SeqCst),
+        }
+    }
+
     /// Manually reset the circuit breaker
     pub async fn reset(&self) {
         let mut state = self.state.lock().await;
         tracing::info!("Circuit breaker manually reset");
-        state.state = CircuitState::Closed;
+        self.transition(&mut state, CircuitState::Closed);
         state.failure_count = 0;
         state.success_count = 0;
         state.last_failure_time = None;
+        state.window.clear();
+        state.window_failures = 0;
+        self.metrics.set_circuit_breaker_failure_rate(&self.name, 0.0);
     }
 }
 
@@ -210,6 +393,7 @@ mod tests {
             failure_threshold: 3,
             timeout: Duration::from_secs(1),
             success_threshold: 2,
+            ..Default::default()
         };
         let cb = CircuitBreaker::new(config);
 
@@ -232,6 +416,7 @@ mod tests {
             failure_threshold: 2,
             timeout: Duration::from_millis(100),
             success_threshold: 1,
+            ..Default::default()
         };
         let cb = CircuitBreaker::new(config);
 
@@ -252,4 +437,97 @@ mod tests {
         // After success threshold, should be closed
         assert_eq!(cb.get_state().await, CircuitState::Closed);
     }
+
+    /// Fake recording the transitions only
+    #[derive(Default)]
+    struct RecordedTransitions(std::sync::Mutex<Vec<(String, String)>>);
+
+    impl crate::metrics::MetricsRecorder for RecordedTransitions {
+        fn record_circuit_breaker_transition(&self, breaker: &str, from_state: &str, to_state: &str) {
+            assert_eq!(breaker, "orders");
+            self.0.lock().unwrap().push((from_state.to_string(), to_state.to_string()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transitions_recorded() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 1,
+            timeout: Duration::from_millis(20),
+            success_threshold: 1,
+            ..Default::default()
+        };
+        let recorded = Arc::new(RecordedTransitions::default());
+        let cb = CircuitBreaker::new(config)
+            .with_name("orders")
+            .with_metrics(MetricsHandle::new(recorded.clone()));
+
+        let _ = cb.call(async { Err::<(), _>("error") }).await;
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        let _ = cb.call(async { Ok::<_, &str>(()) }).await;
+
+        let transitions: Vec<(String, String)> = recorded.0.lock().unwrap().clone();
+        let expected = [("Closed", "Open"), ("Open", "HalfOpen"), ("HalfOpen", "Closed")];
+        assert_eq!(transitions, expected.map(|(from, to)| (from.to_string(), to.to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_opens_on_failure_rate() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 3,
+            failure_rate_threshold: Some(0.5),
+            window_size: 10,
+            minimum_calls: 4,
+            ..Default::default()
+        };
+        let cb = CircuitBreaker::new(config);
+
+        // Alternating outcomes never reach three consecutive failures
+        let _ = cb.call(async { Err::<(), _>("error") }).await;
+        let _ = cb.call(async { Ok::<_, &str>(()) }).await;
+        let _ = cb.call(async { Err::<(), _>("error") }).await;
+        let snapshot = cb.snapshot().await;
+        assert_eq!((snapshot.state, snapshot.window_calls), (CircuitState::Closed, 3));
+
+        // 2 of 4 failed, with enough calls to judge
+        let _ = cb.call(async { Ok::<_, &str>(()) }).await;
+        assert_eq!(cb.get_state().await, CircuitState::Closed);
+        let _ = cb.call(async { Err::<(), _>("error") }).await;
+        let snapshot = cb.snapshot().await;
+        assert_eq!(snapshot.state, CircuitState::Open);
+        assert_eq!(snapshot.window_calls, 0);
+    }
+
+    #[tokio::test]
+    async fn test_half_open_limits_trial_calls() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 1,
+            timeout: Duration::from_millis(20),
+            success_threshold: 2,
+            half_open_max_calls: 1,
+            ..Default::default()
+        };
+        let cb = CircuitBreaker::new(config);
+        let _ = cb.call(async { Err::<(), _>("error") }).await;
+        tokio::time::sleep(Duration::from_millis(30)).await;
+
+        let (release, released) = tokio::sync::oneshot::channel::<()>();
+        let trial = tokio::spawn({
+            let cb = cb.clone();
+            async move { cb.call(async { released.await.map_err(|_| "dropped") }).await }
+        });
+        while cb.snapshot().await.half_open_trials == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        // The one trial slot is taken
+        let rejected = cb.call(async { Ok::<_, &str>(()) }).await;
+        assert!(matches!(rejected, Err(CircuitBreakerError::CircuitOpen)));
+
+        release.send(()).unwrap();
+        assert!(trial.await.unwrap().is_ok());
+        assert_eq!(cb.snapshot().await.half_open_trials, 0);
+        assert!(cb.call(async { Ok::<_, &str>(()) }).await.is_ok());
+        assert_eq!(cb.get_state().await, CircuitState::Closed);
+    }
 }
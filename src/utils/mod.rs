@@ -1,7 +1,11 @@
 // Private module declarations
 mod circuit_breaker;
+mod pii;
 mod retry;
 
-// Re-export items used within the crate
-pub(crate) use circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitBreakerError, CircuitState};
-pub(crate) use retry::{retry_with_backoff, retry_on_transient, RetryConfig, RetryResult, IsTransient};
+// Re-export for public API
+pub use circuit_breaker::{
+    CircuitBreaker, CircuitBreakerConfig, CircuitBreakerError, CircuitBreakerSnapshot, CircuitState,
+};
+pub use pii::{redact_payload, set_redaction, Pii, RedactingWriter};
+pub use retry::{record_retry, retry_with_backoff, retry_with_backoff_recorded, retry_on_transient, RetryConfig, RetryResult, RetryAttempt, RetryBudget, Jitter, IsTransient};
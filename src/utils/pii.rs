@@ -0,0 +1,221 @@
+use serde_json::Value;
+use std::borrow::Cow;
+use std::fmt;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// ============================================================================
+// PII Redaction - Keep Customer Data out of Production Logs
+// ============================================================================
+//
+// Customer emails, phone numbers, names and addresses travel through
+// commands, events and outbox payloads, and from there into log lines,
+// debug output and error messages.
+//
+// Redaction is switched on once at startup for production (AppConfig
+// `environment`) and stays off in dev, where full detail helps debugging.
+// While on:
+// - PII value objects (Email, PhoneNumber, Address) print as "[REDACTED]"
+//   through `Debug`; wrap other sensitive values in `Pii(..)`
+// - `redact_payload` scrubs the PII_FIELDS registry from JSON payloads
+//   before they are logged (any nesting depth)
+// - `RedactingWriter` masks email addresses left in formatted log lines,
+//   as a last line of defense for values logged with `%`/`{}`
+//
+// The registry lists field names as they appear in serialized events and
+// commands; add new PII fields there.
+//
+// ============================================================================
+
+/// JSON field names holding personal data
+pub const PII_FIELDS: &[&str] = &[
+    "email",
+    "old_email",
+    "new_email",
+    "phone",
+    "old_phone",
+    "new_phone",
+    "first_name",
+    "last_name",
+    "street",
+    "postal_code",
+    "address",
+];
+
+pub const REDACTED: &str = "[REDACTED]";
+
+static REDACTION_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Switch redaction on (production) or off (dev); call once at startup
+pub fn set_redaction(enabled: bool) {
+    REDACTION_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn redaction_enabled() -> bool {
+    REDACTION_ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn is_pii_field(name: &str) -> bool {
+    PII_FIELDS.contains(&name)
+}
+
+/// Formats the wrapped value, or "[REDACTED]" while redaction is on
+pub struct Pii<T>(pub T);
+
+impl<T: fmt::Display> fmt::Display for Pii<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if redaction_enabled() {
+            f.write_str(REDACTED)
+        } else {
+            self.0.fmt(f)
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Pii<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if redaction_enabled() {
+            f.write_str(REDACTED)
+        } else {
+            self.0.fmt(f)
+        }
+    }
+}
+
+/// Replace the values of PII fields, at any depth
+pub fn redact_value(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                if is_pii_field(key) && !field.is_null() {
+                    *field = Value::String(REDACTED.to_string());
+                } else {
+                    redact_value(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_value),
+        _ => {}
+    }
+}
+
+/// A JSON payload safe to log
+///
+/// Unchanged while redaction is off. Payloads that are not JSON are replaced
+/// entirely, since their PII cannot be located.
+pub fn redact_payload(payload: &str) -> Cow<'_, str> {
+    if !redaction_enabled() {
+        return Cow::Borrowed(payload);
+    }
+    match serde_json::from_str::<Value>(payload) {
+        Ok(mut value) => {
+            redact_value(&mut value);
+            Cow::Owned(value.to_string())
+        }
+        Err(_) => Cow::Owned(format!("[REDACTED non-JSON payload, {} bytes]", payload.len())),
+    }
+}
+
+/// Mask email addresses in free text
+pub fn mask_emails(text: &str) -> Cow<'_, str> {
+    if !text.contains('@') {
+        return Cow::Borrowed(text);
+    }
+
+    let is_email_char = |c: char| c.is_ascii_alphanumeric() || "._%+-".contains(c);
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(at) = rest.find('@') {
+        let local_start = rest[..at].rfind(|c: char| !is_email_char(c)).map_or(0, |i| i + 1);
+        let domain_end = rest[at + 1..].find(|c: char| !is_email_char(c)).map_or(rest.len(), |i| at + 1 + i);
+        let domain = rest[at + 1..domain_end].trim_end_matches('.');
+
+        if local_start < at && domain.contains('.') {
+            out.push_str(&rest[..local_start]);
+            out.push_str(REDACTED);
+            rest = &rest[at + 1 + domain.len()..];
+        } else {
+            out.push_str(&rest[..=at]);
+            rest = &rest[at + 1..];
+        }
+    }
+    out.push_str(rest);
+    Cow::Owned(out)
+}
+
+/// Log writer masking email addresses while redaction is on
+///
+/// Use as the `fmt` layer's writer: `fmt::layer().with_writer(RedactingWriter::stdout)`.
+pub struct RedactingWriter<W: Write> {
+    inner: W,
+}
+
+impl RedactingWriter<std::io::Stdout> {
+    pub fn stdout() -> Self {
+        Self { inner: std::io::stdout() }
+    }
+}
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if !redaction_enabled() {
+            return self.inner.write(buf);
+        }
+        match std::str::from_utf8(buf) {
+            Ok(text) => {
+                self.inner.write_all(mask_emails(text).as_bytes())?;
+                Ok(buf.len())
+            }
+            Err(_) => self.inner.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+// ============================================================================
+// Unit Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redact_value_scrubs_registered_fields_at_any_depth() {
+        let mut value = json!({
+            "Registered": {
+                "email": "jane@example.com",
+                "first_name": "Jane",
+                "tier": "Gold",
+                "addresses": [{ "street": "1 Main St", "city": "Springfield", "postal_code": "12345" }],
+                "phone": null
+            }
+        });
+
+        redact_value(&mut value);
+
+        let registered = &value["Registered"];
+        assert_eq!(registered["email"], REDACTED);
+        assert_eq!(registered["first_name"], REDACTED);
+        assert_eq!(registered["tier"], "Gold");
+        assert_eq!(registered["addresses"][0]["street"], REDACTED);
+        assert_eq!(registered["addresses"][0]["city"], "Springfield");
+        assert!(registered["phone"].is_null());
+    }
+
+    #[test]
+    fn test_mask_emails_in_free_text() {
+        assert_eq!(
+            mask_emails("Invalid email format: jane.doe+x@mail.example.com."),
+            "Invalid email format: [REDACTED]."
+        );
+        assert_eq!(mask_emails("two: a@b.io, c@d.org"), "two: [REDACTED], [REDACTED]");
+        // Not an address
+        assert_eq!(mask_emails("user@localhost and @handle"), "user@localhost and @handle");
+        assert_eq!(mask_emails("no at sign"), "no at sign");
+    }
+}
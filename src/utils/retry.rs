@@ -1,5 +1,11 @@
-use std::time::Duration;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::time::sleep;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::metrics::MetricsHandle;
 
 // ============================================================================
 // Exponential Backoff Retry Strategy
@@ -8,8 +14,106 @@ use tokio::time::sleep;
 // Implements retry logic with exponential backoff for transient failures.
 // Useful for handling temporary network issues, service unavailability, etc.
 //
+// Jitter randomizes the delays so that many callers failing at once (every
+// CDC consumer when Redpanda goes down) do not retry in lockstep:
+//
+//   none          the exponential delay itself
+//   full          random between 0 and the exponential delay (default)
+//   equal         half the exponential delay plus a random half
+//   decorrelated  random between the initial delay and 3x the previous one
+//
+// A RetryBudget bounds how often retries happen across every operation
+// sharing it (a token bucket): once it is empty, failures are final instead
+// of retried, so retries cannot multiply the load on a struggling
+// dependency. First attempts are never limited.
+//
 // ============================================================================
 
+/// Randomization of backoff delays
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Jitter {
+    None,
+    #[default]
+    Full,
+    Equal,
+    Decorrelated,
+}
+
+impl Jitter {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Jitter::None => "none",
+            Jitter::Full => "full",
+            Jitter::Equal => "equal",
+            Jitter::Decorrelated => "decorrelated",
+        }
+    }
+}
+
+impl std::str::FromStr for Jitter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "none" => Ok(Jitter::None),
+            "full" => Ok(Jitter::Full),
+            "equal" => Ok(Jitter::Equal),
+            "decorrelated" => Ok(Jitter::Decorrelated),
+            other => anyhow::bail!("Unknown jitter '{}' (none, full, equal or decorrelated)", other),
+        }
+    }
+}
+
+/// Retries allowed across all operations sharing it: a token bucket
+/// refilled at `retries_per_sec`, holding at most `burst` tokens
+#[derive(Debug)]
+pub struct RetryBudget {
+    retries_per_sec: f64,
+    burst: f64,
+    /// Tokens left and when they were last refilled
+    tokens: Mutex<(f64, Instant)>,
+    /// Retries refused because the budget was empty
+    denied: AtomicU64,
+}
+
+impl RetryBudget {
+    /// A full budget
+    pub fn new(retries_per_sec: f64, burst: u32) -> Self {
+        let burst = f64::from(burst.max(1));
+        Self {
+            retries_per_sec: retries_per_sec.max(0.0),
+            burst,
+            tokens: Mutex::new((burst, Instant::now())),
+            denied: AtomicU64::new(0),
+        }
+    }
+
+    /// Take a token for one retry; false once the budget is spent
+    pub fn try_acquire(&self) -> bool {
+        self.try_acquire_at(Instant::now())
+    }
+
+    fn try_acquire_at(&self, now: Instant) -> bool {
+        let mut tokens = self.tokens.lock().unwrap();
+        let (available, refilled_at) = *tokens;
+        let refill = now.saturating_duration_since(refilled_at).as_secs_f64() * self.retries_per_sec;
+        let available = (available + refill).min(self.burst);
+        if available < 1.0 {
+            *tokens = (available, now);
+            self.denied.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+        *tokens = (available - 1.0, now);
+        true
+    }
+
+    /// Retries refused so far
+    pub fn denied(&self) -> u64 {
+        self.denied.load(Ordering::Relaxed)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct RetryConfig {
     /// Maximum number of retry attempts
@@ -20,6 +124,10 @@ pub struct RetryConfig {
     pub max_delay: Duration,
     /// Multiplier for exponential backoff
     pub multiplier: f64,
+    /// Randomization of the delays
+    pub jitter: Jitter,
+    /// Shared limit on retries; None retries without limit
+    pub budget: Option<Arc<RetryBudget>>,
 }
 
 impl Default for RetryConfig {
@@ -29,6 +137,8 @@ impl Default for RetryConfig {
             initial_delay: Duration::from_millis(100),
             max_delay: Duration::from_secs(10),
             multiplier: 2.0,
+            jitter: Jitter::default(),
+            budget: None,
         }
     }
 }
@@ -40,7 +150,7 @@ impl RetryConfig {
             max_attempts: 5,
             initial_delay: Duration::from_millis(50),
             max_delay: Duration::from_secs(30),
-            multiplier: 2.0,
+            ..Self::default()
         }
     }
 
@@ -50,9 +160,64 @@ impl RetryConfig {
             max_attempts: 2,
             initial_delay: Duration::from_millis(200),
             max_delay: Duration::from_secs(5),
-            multiplier: 2.0,
+            ..Self::default()
         }
     }
+
+    pub fn with_jitter(mut self, jitter: Jitter) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Draw retries from `budget`, shared with other operations
+    pub fn with_budget(mut self, budget: Arc<RetryBudget>) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Whether another attempt may be made (takes a budget token)
+    fn may_retry(&self) -> bool {
+        match self.budget {
+            Some(ref budget) => budget.try_acquire(),
+            None => true,
+        }
+    }
+}
+
+/// Delays between the attempts of one retried operation
+struct Backoff {
+    /// Exponential delay without jitter
+    exponential: Duration,
+    /// Delay actually used last time (decorrelated jitter)
+    previous: Duration,
+}
+
+impl Backoff {
+    fn new(config: &RetryConfig) -> Self {
+        Self { exponential: config.initial_delay, previous: config.initial_delay }
+    }
+
+    fn next_delay(&mut self, config: &RetryConfig) -> Duration {
+        let exponential = self.exponential.as_millis() as u64;
+        let millis = match config.jitter {
+            Jitter::None => exponential,
+            Jitter::Full => fastrand::u64(0..=exponential),
+            Jitter::Equal => exponential / 2 + fastrand::u64(0..=exponential - exponential / 2),
+            Jitter::Decorrelated => {
+                let initial = config.initial_delay.as_millis() as u64;
+                let upper = (self.previous.as_millis() as u64).saturating_mul(3).max(initial);
+                fastrand::u64(initial..=upper)
+            }
+        };
+        let delay = Duration::from_millis(millis).min(config.max_delay);
+
+        self.previous = delay;
+        self.exponential = Duration::from_millis(
+            ((self.exponential.as_millis() as f64) * config.multiplier) as u64
+        )
+        .min(config.max_delay);
+        delay
+    }
 }
 
 /// Result of a retry operation
@@ -66,18 +231,44 @@ pub enum RetryResult<T, E> {
     PermanentFailure(E),
 }
 
+/// One failed attempt of a retried operation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetryAttempt {
+    pub attempt: u32,
+    pub error: String,
+    pub failed_at: DateTime<Utc>,
+    /// Backoff before the next attempt; None for the last attempt
+    pub delay_ms: Option<u64>,
+}
+
 /// Execute an operation with exponential backoff retry
 pub async fn retry_with_backoff<F, Fut, T, E>(
     config: RetryConfig,
-    mut operation: F,
+    operation: F,
 ) -> RetryResult<T, E>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: std::future::Future<Output = Result<T, E>>,
+    E: std::fmt::Display,
+{
+    retry_with_backoff_recorded(config, operation).await.0
+}
+
+/// Like `retry_with_backoff`, also returning every failed attempt
+///
+/// Used where the failure history is worth keeping, e.g. DLQ entries.
+pub async fn retry_with_backoff_recorded<F, Fut, T, E>(
+    config: RetryConfig,
+    mut operation: F,
+) -> (RetryResult<T, E>, Vec<RetryAttempt>)
 where
     F: FnMut(u32) -> Fut,
     Fut: std::future::Future<Output = Result<T, E>>,
     E: std::fmt::Display,
 {
     let mut attempt = 0;
-    let mut delay = config.initial_delay;
+    let mut backoff = Backoff::new(&config);
+    let mut attempts = Vec::new();
 
     loop {
         attempt += 1;
@@ -96,19 +287,31 @@ where
                         "Operation succeeded after retry"
                     );
                 }
-                return RetryResult::Success(result);
+                return (RetryResult::Success(result), attempts);
             }
             Err(error) => {
                 // Check if we should retry
-                if attempt >= config.max_attempts {
-                    tracing::error!(
-                        attempt = attempt,
-                        error = %error,
-                        "Operation failed after all retries"
-                    );
-                    return RetryResult::Failed(error);
+                let out_of_attempts = attempt >= config.max_attempts;
+                if out_of_attempts || !config.may_retry() {
+                    if out_of_attempts {
+                        tracing::error!(
+                            attempt = attempt,
+                            error = %error,
+                            "Operation failed after all retries"
+                        );
+                    } else {
+                        tracing::error!(attempt = attempt, error = %error, "Retry budget exhausted, not retrying");
+                    }
+                    attempts.push(RetryAttempt {
+                        attempt,
+                        error: error.to_string(),
+                        failed_at: Utc::now(),
+                        delay_ms: None,
+                    });
+                    return (RetryResult::Failed(error), attempts);
                 }
 
+                let delay = backoff.next_delay(&config);
                 tracing::warn!(
                     attempt = attempt,
                     error = %error,
@@ -116,19 +319,33 @@ where
                     "Operation failed, retrying after delay"
                 );
 
+                attempts.push(RetryAttempt {
+                    attempt,
+                    error: error.to_string(),
+                    failed_at: Utc::now(),
+                    delay_ms: Some(delay.as_millis() as u64),
+                });
+
                 // Wait before next attempt
                 sleep(delay).await;
-
-                // Calculate next delay with exponential backoff
-                delay = Duration::from_millis(
-                    ((delay.as_millis() as f64) * config.multiplier) as u64
-                );
-                delay = delay.min(config.max_delay);
             }
         }
     }
 }
 
+/// Record a finished `retry_with_backoff_recorded` run under `operation`
+///
+/// Every retry counts by its attempt number; the outcome is recorded only
+/// when the first attempt failed (success = recovered by retrying).
+pub fn record_retry(metrics: &MetricsHandle, operation: &str, attempts: &[RetryAttempt], succeeded: bool) {
+    for failed in attempts.iter().filter(|a| a.delay_ms.is_some()) {
+        metrics.record_retry_attempt(operation, failed.attempt + 1);
+    }
+    if !attempts.is_empty() {
+        metrics.record_retry_outcome(operation, succeeded);
+    }
+}
+
 /// Check if an error is transient (should retry) or permanent (should not retry)
 pub trait IsTransient {
     fn is_transient(&self) -> bool;
@@ -145,7 +362,7 @@ where
     E: std::fmt::Display + IsTransient,
 {
     let mut attempt = 0;
-    let mut delay = config.initial_delay;
+    let mut backoff = Backoff::new(&config);
 
     loop {
         attempt += 1;
@@ -179,7 +396,12 @@ where
                     );
                     return RetryResult::Failed(error);
                 }
+                if !config.may_retry() {
+                    tracing::error!(attempt = attempt, error = %error, "Retry budget exhausted, not retrying");
+                    return RetryResult::Failed(error);
+                }
 
+                let delay = backoff.next_delay(&config);
                 tracing::warn!(
                     attempt = attempt,
                     error = %error,
@@ -188,11 +410,6 @@ where
                 );
 
                 sleep(delay).await;
-
-                delay = Duration::from_millis(
-                    ((delay.as_millis() as f64) * config.multiplier) as u64
-                );
-                delay = delay.min(config.max_delay);
             }
         }
     }
@@ -214,6 +431,7 @@ mod tests {
             initial_delay: Duration::from_millis(10),
             max_delay: Duration::from_millis(100),
             multiplier: 2.0,
+            ..RetryConfig::default()
         };
 
         let result = retry_with_backoff(config, |_attempt| {
@@ -240,6 +458,7 @@ mod tests {
             initial_delay: Duration::from_millis(10),
             max_delay: Duration::from_millis(100),
             multiplier: 2.0,
+            ..RetryConfig::default()
         };
 
         let result = retry_with_backoff(config, |_attempt| async {
@@ -249,4 +468,114 @@ mod tests {
 
         assert!(matches!(result, RetryResult::Failed(_)));
     }
+
+    #[tokio::test]
+    async fn test_recorded_attempts_carry_errors_and_delays() {
+        let config = RetryConfig {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(15),
+            multiplier: 2.0,
+            jitter: Jitter::None,
+            budget: None,
+        };
+
+        let (result, attempts) = retry_with_backoff_recorded(config, |attempt| async move {
+            Err::<(), _>(format!("failure {}", attempt))
+        })
+        .await;
+
+        assert!(matches!(result, RetryResult::Failed(_)));
+        assert_eq!(attempts.len(), 3);
+        assert_eq!(attempts[0].error, "failure 1");
+        assert_eq!(attempts[0].delay_ms, Some(10));
+        // Capped at max_delay
+        assert_eq!(attempts[1].delay_ms, Some(15));
+        assert_eq!(attempts[2].delay_ms, None);
+    }
+
+    /// Fake recording retries and outcomes as strings
+    #[derive(Default)]
+    struct RecordedRetries(std::sync::Mutex<Vec<String>>);
+
+    impl crate::metrics::MetricsRecorder for RecordedRetries {
+        fn record_retry_attempt(&self, operation: &str, attempt: u32) {
+            self.0.lock().unwrap().push(format!("{} attempt {}", operation, attempt));
+        }
+
+        fn record_retry_outcome(&self, operation: &str, success: bool) {
+            self.0.lock().unwrap().push(format!("{} success {}", operation, success));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_retry_counts_retries_and_outcome() {
+        let recorded = Arc::new(RecordedRetries::default());
+        let metrics = MetricsHandle::new(recorded.clone());
+        let config = RetryConfig { initial_delay: Duration::from_millis(1), ..RetryConfig::default() };
+
+        let (result, attempts) = retry_with_backoff_recorded(config.clone(), |attempt| async move {
+            if attempt < 3 { Err("transient") } else { Ok(()) }
+        })
+        .await;
+        record_retry(&metrics, "publish", &attempts, matches!(result, RetryResult::Success(_)));
+
+        // First-time success records nothing
+        let (_, attempts) = retry_with_backoff_recorded(config, |_| async { Ok::<_, &str>(()) }).await;
+        record_retry(&metrics, "publish", &attempts, true);
+
+        assert_eq!(
+            *recorded.0.lock().unwrap(),
+            vec!["publish attempt 2", "publish attempt 3", "publish success true"]
+        );
+    }
+
+    #[test]
+    fn test_jitter_stays_within_bounds() {
+        let config = RetryConfig {
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+            ..RetryConfig::default()
+        };
+        for jitter in [Jitter::Full, Jitter::Equal, Jitter::Decorrelated] {
+            let config = config.clone().with_jitter(jitter);
+            let mut backoff = Backoff::new(&config);
+            let mut exponential = 100;
+            for _ in 0..8 {
+                let delay = backoff.next_delay(&config).as_millis() as u64;
+                let (low, high) = match jitter {
+                    Jitter::Full => (0, exponential),
+                    Jitter::Equal => (exponential / 2, exponential),
+                    _ => (100, 1000),
+                };
+                assert!((low..=high).contains(&delay), "{:?} delay {} outside {}..={}", jitter, delay, low, high);
+                exponential = (exponential * 2).min(1000);
+            }
+        }
+        assert_eq!(" Decorrelated".parse::<Jitter>().unwrap(), Jitter::Decorrelated);
+        assert!("random".parse::<Jitter>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_budget_limits_retries_across_operations() {
+        let budget = Arc::new(RetryBudget::new(0.0, 2));
+        let config = RetryConfig { max_attempts: 5, initial_delay: Duration::from_millis(1), ..RetryConfig::default() }
+            .with_budget(budget.clone());
+
+        // Two retries left in the budget, then the failure is final
+        let (_, attempts) = retry_with_backoff_recorded(config.clone(), |_| async { Err::<(), _>("down") }).await;
+        assert_eq!(attempts.len(), 3);
+        let (result, attempts) = retry_with_backoff_recorded(config, |_| async { Err::<(), _>("down") }).await;
+        assert!(matches!(result, RetryResult::Failed("down")));
+        assert_eq!(attempts.len(), 1);
+        assert_eq!(budget.denied(), 2);
+
+        // Refilled over time, up to the burst
+        let budget = RetryBudget::new(10.0, 1);
+        let now = Instant::now();
+        assert!(budget.try_acquire_at(now));
+        assert!(!budget.try_acquire_at(now));
+        assert!(budget.try_acquire_at(now + Duration::from_millis(200)));
+        assert!(!budget.try_acquire_at(now + Duration::from_millis(250)));
+    }
 }
@@ -0,0 +1,96 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid::Uuid;
+
+use scylladb_cdc::actors::{list_dlq_messages, DlqFilter};
+use scylladb_cdc::domain::order::{OrderCancelled, OrderCommand, OrderCommandHandler, OrderEvent, OrderItem};
+use scylladb_cdc::event_sourcing::{CommandContext, EventEnvelope};
+use scylladb_cdc::projections::Paging;
+use scylladb_cdc::RedpandaConsumer;
+
+use crate::harness::{wait_for, Harness};
+
+/// Time an appended event gets to reach Redpanda or the DLQ
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(90);
+
+#[tokio::test]
+async fn test_outbox_events_reach_redpanda_through_cdc() -> anyhow::Result<()> {
+    let harness = Harness::start().await?;
+    let _relay = harness.start_relay().await?;
+
+    let received: Arc<Mutex<Vec<EventEnvelope<OrderEvent>>>> = Arc::default();
+    let _consumer = RedpandaConsumer::new(&harness.brokers, "integration-tests").from_beginning().spawn(
+        vec!["OrderCreated".to_string(), "OrderConfirmed".to_string()],
+        {
+            let received = received.clone();
+            move |envelope: EventEnvelope<OrderEvent>| {
+                received.lock().unwrap().push(envelope);
+                async { Ok(()) }
+            }
+        },
+    );
+
+    let store = Arc::new(harness.system().event_store::<OrderEvent>("Order", "order-events"));
+    let handler = OrderCommandHandler::new(store);
+    let order_id = Uuid::new_v4();
+    let ctx = CommandContext::new(Uuid::new_v4());
+    let create = OrderCommand::CreateOrder {
+        order_id,
+        customer_id: Uuid::new_v4(),
+        items: vec![OrderItem { product_id: Uuid::new_v4(), quantity: 1 }],
+    };
+    handler.handle(order_id, create, &ctx).await?;
+    handler.handle(order_id, OrderCommand::ConfirmOrder, &ctx).await?;
+
+    let delivered = wait_for("both events on Redpanda", DELIVERY_TIMEOUT, || {
+        let received = received.clone();
+        async move {
+            let received = received.lock().unwrap();
+            let events: Vec<_> = received.iter().filter(|e| e.aggregate_id == order_id).cloned().collect();
+            Ok((events.len() >= 2).then_some(events))
+        }
+    })
+    .await?;
+
+    let mut delivered: Vec<_> = delivered.iter().map(|e| (e.sequence_number, e.event_type.clone(), e.correlation_id)).collect();
+    delivered.sort();
+    assert_eq!(
+        delivered,
+        vec![
+            (1, "OrderCreated".to_string(), ctx.correlation_id),
+            (2, "OrderConfirmed".to_string(), ctx.correlation_id),
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unpublishable_event_lands_in_dlq() -> anyhow::Result<()> {
+    let harness = Harness::start().await?;
+    let _relay = harness.start_relay().await?;
+
+    // The event type names the topic; this one is not a valid topic name
+    let store = harness.system().event_store::<OrderEvent>("Order", "order-events");
+    let order_id = Uuid::new_v4();
+    let event = OrderEvent::Cancelled(OrderCancelled {
+        reason: Some("integration test".to_string()),
+        cancelled_by: None,
+    });
+    let envelope = EventEnvelope::new(order_id, 1, "Not A Topic!".to_string(), event, Uuid::new_v4());
+    store.append_events(order_id, 0, vec![envelope], true).await?;
+
+    let filter = DlqFilter { aggregate_id: Some(order_id), ..Default::default() };
+    let entry = wait_for("the DLQ entry", DELIVERY_TIMEOUT, || {
+        let (session, filter) = (harness.session.clone(), filter.clone());
+        async move { Ok(list_dlq_messages(&session, &filter, &Paging::default()).await?.items.into_iter().next()) }
+    })
+    .await?;
+
+    assert_eq!(entry.event_type, "Not A Topic!");
+    assert!(entry.payload.contains("integration test"));
+    let context = entry.failure_context.expect("failure context of a publish failure");
+    assert_eq!(context.topic, "Not A Topic!");
+    assert_eq!(context.key, order_id.to_string());
+    assert!(!context.attempts.is_empty());
+    Ok(())
+}
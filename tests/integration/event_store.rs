@@ -0,0 +1,81 @@
+use chrono::Utc;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use scylladb_cdc::domain::order::{OrderCommand, OrderCommandHandler, OrderConfirmed, OrderCreated, OrderEvent, OrderItem};
+use scylladb_cdc::event_sourcing::{CommandContext, ConcurrencyConflict, EventEnvelope};
+
+use crate::harness::Harness;
+
+fn created(order_id: Uuid) -> EventEnvelope<OrderEvent> {
+    let event = OrderEvent::Created(OrderCreated {
+        customer_id: Uuid::new_v4(),
+        items: vec![OrderItem { product_id: Uuid::new_v4(), quantity: 2 }],
+    });
+    EventEnvelope::new(order_id, 1, "OrderCreated".to_string(), event, Uuid::new_v4())
+}
+
+fn confirmed(order_id: Uuid, sequence_number: i64) -> EventEnvelope<OrderEvent> {
+    let event = OrderEvent::Confirmed(OrderConfirmed { confirmed_at: Utc::now() });
+    EventEnvelope::new(order_id, sequence_number, "OrderConfirmed".to_string(), event, Uuid::new_v4())
+}
+
+#[tokio::test]
+async fn test_append_load_and_concurrency_conflict() -> anyhow::Result<()> {
+    let harness = Harness::start().await?;
+    let store = Arc::new(harness.system().event_store::<OrderEvent>("Order", "order-events"));
+    let order_id = Uuid::new_v4();
+
+    assert!(!store.aggregate_exists(order_id).await?);
+    assert_eq!(store.append_events(order_id, 0, vec![created(order_id)], true).await?, 1);
+    assert_eq!(store.get_current_version(order_id).await?, 1);
+
+    // Two writers on version 1: the LWT lets exactly one of them through
+    let (first, second) = tokio::join!(
+        store.append_events(order_id, 1, vec![confirmed(order_id, 2)], true),
+        store.append_events(order_id, 1, vec![confirmed(order_id, 2)], true),
+    );
+    let (won, lost) = match (first, second) {
+        (Ok(version), Err(e)) | (Err(e), Ok(version)) => (version, e),
+        other => panic!("Expected exactly one append to win, got {:?}", other),
+    };
+    assert_eq!(won, 2);
+    let conflict = lost.downcast_ref::<ConcurrencyConflict>().expect("typed conflict");
+    assert_eq!((conflict.expected_version, conflict.current_version), (1, 2));
+
+    let events = store.load_events(order_id).await?;
+    let types: Vec<_> = events.iter().map(|e| e.event_type.as_str()).collect();
+    assert_eq!(types, vec!["OrderCreated", "OrderConfirmed"]);
+    assert_eq!(events.iter().map(|e| e.sequence_number).collect::<Vec<_>>(), vec![1, 2]);
+
+    // Other aggregates are untouched
+    assert_eq!(store.get_current_version(Uuid::new_v4()).await?, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_command_handlers_on_two_instances_retry_conflicts() -> anyhow::Result<()> {
+    let harness = Harness::start().await?;
+    let store = Arc::new(harness.system().event_store::<OrderEvent>("Order", "order-events"));
+    // Separate handlers: their in-process serialization does not apply
+    let (node_a, node_b) = (OrderCommandHandler::new(store.clone()), OrderCommandHandler::new(store.clone()));
+    let order_id = Uuid::new_v4();
+
+    let create = OrderCommand::CreateOrder {
+        order_id,
+        customer_id: Uuid::new_v4(),
+        items: vec![OrderItem { product_id: Uuid::new_v4(), quantity: 1 }],
+    };
+    node_a.handle(order_id, create, &CommandContext::new(Uuid::new_v4())).await?;
+
+    let items = |quantity| OrderCommand::UpdateItems { items: vec![OrderItem { product_id: Uuid::new_v4(), quantity }], reason: None };
+    let (a, b) = tokio::join!(
+        node_a.handle(order_id, items(2), &CommandContext::new(Uuid::new_v4())),
+        node_b.handle(order_id, items(3), &CommandContext::new(Uuid::new_v4())),
+    );
+    let mut versions = vec![a?, b?];
+    versions.sort();
+    assert_eq!(versions, vec![2, 3]);
+    assert_eq!(store.load_events(order_id).await?.len(), 3);
+    Ok(())
+}
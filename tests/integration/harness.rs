@@ -0,0 +1,158 @@
+use anyhow::{Context, Result};
+use scylla::client::session::Session;
+use scylla::client::session_builder::SessionBuilder;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use testcontainers::core::IntoContainerPort;
+use testcontainers::runners::AsyncRunner;
+use testcontainers::{ContainerAsync, GenericImage, ImageExt};
+
+use kameo::actor::ActorRef;
+use kameo::Actor;
+use scylladb_cdc::actors::DlqActor;
+use scylladb_cdc::config::CdcSource;
+use scylladb_cdc::db::Migrator;
+use scylladb_cdc::utils::RetryConfig;
+use scylladb_cdc::{CdcProcessor, RedpandaClient, SystemBuilder};
+
+// ============================================================================
+// Harness - ScyllaDB and Redpanda Containers for One Test
+// ============================================================================
+//
+// `Harness::start` runs the images of docker-compose.yml through
+// testcontainers (Redpanda advertised on a free host port, so clients
+// outside the container reach it), waits until both accept clients, and
+// applies the migrations - the outbox comes with CDC enabled, as with
+// `--migrate`.
+//
+// `start_relay` runs the outbox relay (CDC log reader + DLQ) against them.
+//
+// Every test gets its own containers; they are removed when the Harness is
+// dropped.
+//
+// ============================================================================
+
+pub const KEYSPACE: &str = "orders_ks";
+
+const SCYLLA_IMAGE: (&str, &str) = ("scylladb/scylla", "2025.3.1");
+const REDPANDA_IMAGE: (&str, &str) = ("redpandadata/redpanda", "v25.2.5");
+
+/// How long the containers get to accept clients
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// Time the CDC log reader gets to start before events are written
+const RELAY_STARTUP: Duration = Duration::from_secs(5);
+
+pub struct Harness {
+    pub session: Arc<Session>,
+    pub brokers: String,
+    _scylla: ContainerAsync<GenericImage>,
+    _redpanda: ContainerAsync<GenericImage>,
+}
+
+impl Harness {
+    pub async fn start() -> Result<Self> {
+        let _ = tracing_subscriber::fmt().with_env_filter("info").with_test_writer().try_init();
+
+        let scylla = GenericImage::new(SCYLLA_IMAGE.0, SCYLLA_IMAGE.1)
+            .with_exposed_port(9042.tcp())
+            .with_cmd(["--smp", "1", "--memory", "1G", "--overprovisioned", "1"])
+            .start()
+            .await
+            .context("Failed to start ScyllaDB (is Docker running?)")?;
+
+        // The advertised address must be reachable from the test process
+        let kafka_port = free_port()?;
+        let redpanda = GenericImage::new(REDPANDA_IMAGE.0, REDPANDA_IMAGE.1)
+            .with_mapped_port(kafka_port, 9092.tcp())
+            .with_cmd([
+                "redpanda".to_string(),
+                "start".to_string(),
+                "--mode=dev-container".to_string(),
+                "--smp=1".to_string(),
+                "--kafka-addr=0.0.0.0:9092".to_string(),
+                format!("--advertise-kafka-addr=127.0.0.1:{}", kafka_port),
+            ])
+            .start()
+            .await
+            .context("Failed to start Redpanda")?;
+        let brokers = format!("127.0.0.1:{}", kafka_port);
+
+        let scylla_node = format!("{}:{}", scylla.get_host().await?, scylla.get_host_port_ipv4(9042.tcp()).await?);
+        let session = eventually("ScyllaDB", || SessionBuilder::new().known_node(&scylla_node).build()).await?;
+        let report = Migrator::new(&session, KEYSPACE).run().await?;
+        tracing::info!(applied = ?report.applied, "Integration keyspace migrated");
+
+        let client = RedpandaClient::new(&brokers);
+        eventually("Redpanda", || client.broker_info()).await?;
+
+        Ok(Self { session: Arc::new(session), brokers, _scylla: scylla, _redpanda: redpanda })
+    }
+
+    /// Components wired as the service wires them, on the containers
+    pub fn system(&self) -> SystemBuilder {
+        SystemBuilder::new(self.session.clone())
+    }
+
+    pub fn redpanda(&self) -> Arc<RedpandaClient> {
+        Arc::new(RedpandaClient::new(&self.brokers))
+    }
+
+    pub fn outbox(&self) -> CdcSource {
+        CdcSource { keyspace: KEYSPACE.to_string(), table: "outbox_messages".to_string() }
+    }
+
+    /// Relay the outbox to Redpanda, dead-lettering after a few quick retries
+    ///
+    /// The reader starts at "now": write the events after this returns.
+    pub async fn start_relay(&self) -> Result<(ActorRef<CdcProcessor>, ActorRef<DlqActor>)> {
+        let dlq = DlqActor::spawn(DlqActor::new(self.session.clone()));
+        let processor = CdcProcessor::spawn(
+            CdcProcessor::new(self.session.clone(), self.redpanda(), Some(dlq.clone()))
+                .with_source(self.outbox())
+                .with_retry_config(RetryConfig::conservative()),
+        );
+        tokio::time::sleep(RELAY_STARTUP).await;
+        Ok((processor, dlq))
+    }
+}
+
+/// Retry `attempt` until it succeeds or STARTUP_TIMEOUT passes
+async fn eventually<T, E, F, Fut>(what: &str, mut attempt: F) -> Result<T>
+where
+    E: std::fmt::Display,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = std::result::Result<T, E>>,
+{
+    let started = Instant::now();
+    loop {
+        match attempt().await {
+            Ok(value) => return Ok(value),
+            Err(e) if started.elapsed() > STARTUP_TIMEOUT => anyhow::bail!("{} not ready after {:?}: {}", what, STARTUP_TIMEOUT, e),
+            Err(_) => tokio::time::sleep(Duration::from_secs(1)).await,
+        }
+    }
+}
+
+/// Wait until `check` returns Some, for at most `timeout`
+pub async fn wait_for<T, F, Fut>(what: &str, timeout: Duration, mut check: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Option<T>>>,
+{
+    let started = Instant::now();
+    loop {
+        if let Some(value) = check().await? {
+            return Ok(value);
+        }
+        if started.elapsed() > timeout {
+            anyhow::bail!("Timed out after {:?} waiting for {}", timeout, what);
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+}
+
+fn free_port() -> Result<u16> {
+    Ok(std::net::TcpListener::bind("127.0.0.1:0")?.local_addr()?.port())
+}
@@ -0,0 +1,20 @@
+// ============================================================================
+// Integration Tests - Event Store and CDC Relay on Real ScyllaDB and Redpanda
+// ============================================================================
+//
+// What the unit tests cannot cover: LWT version claims, the batched
+// event_store + outbox write, the CDC log reader and Kafka delivery. Each
+// test starts ScyllaDB (CDC enabled) and Redpanda containers through
+// testcontainers, so Docker must be running:
+//
+//   cargo test --features integration --test integration -- --test-threads=1
+//
+// (`make test-integration`). Without the feature the suite is not built and
+// `cargo test` stays container-free.
+//
+// ============================================================================
+
+mod harness;
+
+mod cdc_relay;
+mod event_store;